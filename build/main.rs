@@ -39,6 +39,8 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_APPLE_vertex_array_object",
             "GL_ARB_bindless_texture",
             "GL_ARB_buffer_storage",
+            "GL_ARB_cl_event",
+            "GL_ARB_clear_texture",
             "GL_ARB_compute_shader",
             "GL_ARB_copy_buffer",
             "GL_ARB_debug_output",
@@ -56,6 +58,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_invalidate_subdata",
             "GL_ARB_multi_draw_indirect",
             "GL_ARB_occlusion_query",
+            "GL_ARB_pipeline_statistics_query",
             "GL_ARB_pixel_buffer_object",
             "GL_ARB_robustness",
             "GL_ARB_shader_image_load_store",
@@ -77,8 +80,14 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_framebuffer_object",
             "GL_EXT_framebuffer_sRGB",
             "GL_EXT_gpu_shader4",
+            "GL_EXT_memory_object",
+            "GL_EXT_memory_object_fd",
+            "GL_EXT_memory_object_win32",
             "GL_EXT_packed_depth_stencil",
             "GL_EXT_provoking_vertex",
+            "GL_EXT_semaphore",
+            "GL_EXT_semaphore_fd",
+            "GL_EXT_semaphore_win32",
             "GL_EXT_texture_array",
             "GL_EXT_texture_buffer_object",
             "GL_EXT_texture_compression_s3tc",
@@ -87,6 +96,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_texture_sRGB",
             "GL_EXT_transform_feedback",
             "GL_GREMEDY_string_marker",
+            "GL_KHR_no_error",
             "GL_KHR_robustness",
             "GL_NVX_gpu_memory_info",
             "GL_NV_conditional_render",
@@ -100,7 +110,9 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
         Profile::Compatibility,
         Fallbacks::None,
         vec![
+            "GL_ANGLE_framebuffer_blit",
             "GL_ANGLE_framebuffer_multisample",
+            "GL_ANGLE_instanced_arrays",
             "GL_APPLE_framebuffer_multisample",
             "GL_APPLE_sync",
             "GL_ARM_rgba8",
@@ -111,13 +123,17 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_occlusion_query_boolean",
             "GL_EXT_primitive_bounding_box",
             "GL_EXT_robustness",
+            "GL_EXT_unpack_subimage",
             "GL_KHR_debug",
+            "GL_KHR_no_error",
             "GL_NV_copy_buffer",
             "GL_NV_framebuffer_multisample",
             "GL_NV_internalformat_sample_query",
+            "GL_NV_pack_subimage",
             "GL_NV_pixel_buffer_object",
             "GL_OES_depth_texture",
             "GL_OES_draw_elements_base_vertex",
+            "GL_OES_EGL_image",
             "GL_OES_packed_depth_stencil",
             "GL_OES_primitive_bounding_box",
             "GL_OES_rgb8_rgba8",
@@ -310,12 +310,14 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
 
             use texture::any::{{self, TextureAny, TextureAnyLayer, TextureAnyMipmap}};
             use texture::any::{{TextureAnyLayerMipmap, TextureAnyImage, Dimensions}};
+            use texture::any::SharedTexture;
             use texture::bindless::{{ResidentTexture, BindlessTexturesNotSupportedError}};
             use texture::get_format::{{InternalFormat, InternalFormatType, GetFormatError}};
             use texture::pixel_buffer::PixelBuffer;
             use texture::{{TextureCreationError, Texture1dDataSource, Texture2dDataSource}};
             use texture::{{Texture3dDataSource, Texture2dDataSink, MipmapsOption, CompressedMipmapsOption}};
             use texture::{{RawImage1d, RawImage2d, RawImage3d, CubeLayer}};
+            use texture::{{ChannelOrder, ReadError}};
             use texture::pixel::PixelValue;
 
             use image_format::{{ClientFormatAny, TextureFormatRequest}};
@@ -433,6 +435,34 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                                 }}
                             }}
 
+                            impl<'a, 't> AsUniformValue for &'a [&'t {myname}] {{
+                                #[inline]
+                                fn as_uniform_value(&self) -> UniformValue {{
+                                    UniformValue::Samplers(self)
+                                }}
+                            }}
+
+                            impl<'t> AsUniformValue for Vec<&'t {myname}> {{
+                                #[inline]
+                                fn as_uniform_value(&self) -> UniformValue {{
+                                    UniformValue::Samplers(self)
+                                }}
+                            }}
+
+                            impl<'a, 't> AsUniformValue for &'a [Sampler<'t, {myname}>] {{
+                                #[inline]
+                                fn as_uniform_value(&self) -> UniformValue {{
+                                    UniformValue::Samplers(self)
+                                }}
+                            }}
+
+                            impl<'t> AsUniformValue for Vec<Sampler<'t, {myname}>> {{
+                                #[inline]
+                                fn as_uniform_value(&self) -> UniformValue {{
+                                    UniformValue::Samplers(self)
+                                }}
+                            }}
+
                             impl {myname} {{
                                 /// Builds a `Sampler` marker object that allows you to indicate
                                 /// how the texture should be sampled from inside a shader.
@@ -798,6 +828,31 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 }}
         ", format = relevant_format, name = name)).unwrap();
 
+    // writing the `into_shared` and `from_shared` functions
+    (writeln!(dest, "
+                /// Detaches the texture from its context, so that it can be sent to another
+                /// thread whose context shares its objects with this one.
+                ///
+                /// See the documentation of `SharedTexture`.
+                #[inline]
+                pub fn into_shared(self) -> SharedTexture<{name}> {{
+                    any::into_shared(self.0)
+                }}
+
+                /// Attaches a texture that has been detached with `into_shared` to the context of
+                /// `facade`.
+                ///
+                /// # Panic
+                ///
+                /// Panics if the context doesn't share its objects with the context that detached
+                /// the texture.
+                #[inline]
+                pub fn from_shared<F: Facade + ?Sized>(facade: &F, shared: SharedTexture<{name}>)
+                                                       -> {name} {{
+                    {name}(any::from_shared(facade, shared))
+                }}
+        ", name = name)).unwrap();
+
     // dimensions getters
     write_dimensions_getters(dest, dimensions, "self.0", true);
 
@@ -848,12 +903,31 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 /// data, as it is the only format guaranteed to be supported across all OpenGL
                 /// versions.
                 ///
+                /// Textures that can't be attached to a framebuffer, like compressed textures,
+                /// are read with `glGetTexImage` on OpenGL, and are drawn on a temporary texture
+                /// with a shader on OpenGL ES.
+                ///
                 /// You should avoid doing this at all cost during performance-critical
                 /// operations (for example, while you're drawing).
                 /// Use `read_to_pixel_buffer` instead.
+                ///
+                /// # Panic
+                ///
+                /// Panics if the texture can't be read on OpenGL ES. See `try_read`.
                 #[inline]
                 pub fn read<T>(&self) -> T where T: Texture2dDataSink<(u8, u8, u8, u8)> {{
-                    unsafe {{ self.unchecked_read() }}
+                    self.try_read().expect("Failed to read the texture")
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Same as `read`, but returns an error instead of panicking if the backend
+                /// can't read the texture.
+                #[inline]
+                pub fn try_read<T>(&self) -> Result<T, ReadError>
+                                   where T: Texture2dDataSink<(u8, u8, u8, u8)>
+                {{
+                    ::texture::readback::read_texture_2d(self.0.get_context(), self, &self.0)
                 }}
             "#)).unwrap();
 
@@ -887,6 +961,29 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 }}
             "#)).unwrap();
 
+        (write!(dest, r#"
+                /// Reads the content of the texture to RAM in the client format of `P`, with the
+                /// components in the given order. For example you can read a floating-point
+                /// texture as `(f32, f32, f32, f32)`, or read a texture as BGRA data.
+                ///
+                /// With OpenGL the conversion is done by the driver. With OpenGL ES, formats
+                /// other than `U8U8U8U8` and the implementation-defined format are converted in
+                /// software from `U8U8U8U8` data, and only unsigned normalized and floating-point
+                /// formats are supported. Returns an error if the format isn't supported.
+                ///
+                /// You should avoid doing this at all cost during performance-critical
+                /// operations (for example, while you're drawing).
+                #[inline]
+                pub fn read_with_format<T, P>(&self, order: ChannelOrder) -> Result<T, ReadError>
+                                              where T: Texture2dDataSink<P>, P: PixelValue
+                {{
+                    let rect = Rect {{ left: 0, bottom: 0, width: self.get_width(),
+                                       height: self.get_height().unwrap_or(1) }};
+                    self.0.main_level().first_layer().into_image(None).unwrap()
+                          .raw_read_with_format(&rect, order)
+                }}
+            "#)).unwrap();
+
         (write!(dest, r#"
                 /// Unsafely reads the content of the texture into a buffer in video memory. It is
                 /// possible that the current OpenGL context does not support the given format, in
@@ -908,6 +1005,27 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             "#)).unwrap();
     }
 
+    // writing the `read_depth` function
+    if dimensions == TextureDimensions::Texture2d && ty == TextureType::Depth {
+        (write!(dest, r#"
+                /// Reads the depth values of the texture to RAM. The values are between `0.0`
+                /// and `1.0`.
+                ///
+                /// With OpenGL ES without the `GL_NV_read_depth` extension, the values are
+                /// drawn into a color texture with a shader and unpacked, which can reduce
+                /// their precision.
+                ///
+                /// You should avoid doing this at all cost during performance-critical
+                /// operations (for example, while you're drawing).
+                #[inline]
+                pub fn read_depth<T>(&self) -> Result<T, ReadError>
+                                     where T: Texture2dDataSink<f32>
+                {{
+                    ::texture::readback::read_depth_texture(self.0.get_context(), self)
+                }}
+            "#)).unwrap();
+    }
+
     // writing the `read_compressed_data` function
     if is_compressed && !dimensions.is_array() {
         (write!(dest, r#"
@@ -917,7 +1035,8 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 /// operations (for example, while you're drawing).
                 ///
                 /// Returns the compressed format of the texture and the compressed data, gives
-                /// `None` when the internal compression format is generic or unknown.
+                /// `None` when the internal compression format is generic or unknown, or when
+                /// using OpenGL ES.
                 #[inline]
                 pub fn read_compressed_data(&self) -> Option<({format}, Vec<u8>)> {{
                     self.main_level().read_compressed_data()
@@ -1221,7 +1340,8 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                     /// operations (for example, while you're drawing).
                     ///
                     /// Returns the compressed format of the texture and the compressed data, gives
-                    /// `None` when the internal compression format is generic or unknown.
+                    /// `None` when the internal compression format is generic or unknown, or when
+                    /// using OpenGL ES.
                     #[inline]
                     pub fn read_compressed_data(&self) -> Option<({format}, Vec<u8>)> {{
                         match self.0.download_compressed_data() {{
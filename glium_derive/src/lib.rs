@@ -0,0 +1,273 @@
+/*!
+Custom derive for the `glium::vertex::Vertex` trait.
+
+This crate is re-exported by glium when its `derive` feature is enabled, and you shouldn't need
+to depend on it directly.
+
+```
+# #[macro_use] extern crate glium;
+# fn main() {
+#[derive(Copy, Clone, Vertex)]
+#[glium(divisor = 2)]
+struct Particle {
+    position: [f32; 3],
+    #[glium(name = "in_color", normalize)]
+    color: [u8; 4],
+    #[glium(integer)]
+    material: u32,
+}
+# }
+```
+
+The struct must have named fields, and the type of each field must implement
+`glium::vertex::Attribute`.
+
+## Field attributes
+
+ - `#[glium(name = "...")]`: the name of the attribute in the shaders. The default is the name
+   of the field.
+ - `#[glium(normalize)]`: integer values are normalized to the `[0.0, 1.0]` range (or
+   `[-1.0, 1.0]` for signed types) when the shader reads them as floating-point values.
+ - `#[glium(integer)]`: checks at compile time that the field is made of integers, so that the
+   shader can read it as an `int`, `uvec2`, etc. The check looks at the type as written, which
+   means that type aliases are rejected.
+
+## Struct attributes
+
+ - `#[glium(divisor = N)]`: when a buffer of this type is used per instance, each element is
+   used for `N` consecutive instances.
+
+*/
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, GenericArgument, Lit, Meta, NestedMeta, PathArguments};
+use syn::Type;
+
+/// Implements `glium::vertex::Vertex` for a struct.
+#[proc_macro_derive(Vertex, attributes(glium))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match expand_vertex(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Options of a field, parsed from its `#[glium(...)]` attributes.
+#[derive(Default)]
+struct FieldOptions {
+    name: Option<String>,
+    normalize: bool,
+    integer: Option<proc_macro2::Span>,
+}
+
+fn expand_vertex(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let struct_name = &input.ident;
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(input.span(),
+                                           "#[derive(Vertex)] requires a struct with named \
+                                            fields"));
+            },
+        },
+        _ => {
+            return Err(syn::Error::new(input.span(),
+                                       "#[derive(Vertex)] can only be used on structs"));
+        },
+    };
+
+    let divisor = try!(parse_struct_options(input));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut bindings = Vec::with_capacity(fields.len());
+
+    for field in fields.iter() {
+        let options = try!(parse_field_options(field));
+        let field_name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+
+        if let Some(span) = options.integer {
+            if options.normalize {
+                return Err(syn::Error::new(span, "an attribute can't be both `integer` and \
+                                                  `normalize`"));
+            }
+
+            if !is_integer_type(ty) {
+                return Err(syn::Error::new(ty.span(), "`integer` requires a field made of \
+                                                       i8, u8, i16, u16, i32 or u32"));
+            }
+        }
+
+        let name = options.name.unwrap_or_else(|| field_name.to_string());
+        let normalize = options.normalize;
+
+        // the span makes the error point to the field if its type doesn't implement `Attribute`
+        let attribute_type = quote_spanned! { ty.span() =>
+            <#ty as ::glium::vertex::Attribute>::get_type()
+        };
+
+        bindings.push(quote! {
+            (
+                ::std::borrow::Cow::Borrowed(#name),
+                {
+                    let dummy = ::std::mem::MaybeUninit::<#struct_name #ty_generics>::uninit();
+                    let base = dummy.as_ptr();
+                    let field = unsafe { ::std::ptr::addr_of!((*base).#field_name) };
+                    (field as usize) - (base as usize)
+                },
+                #attribute_type,
+                #normalize
+            )
+        });
+    }
+
+    let instance_divisor = divisor.map(|divisor| {
+        quote! {
+            #[inline]
+            fn instance_divisor() -> u32 {
+                #divisor
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::glium::vertex::Vertex for #struct_name #ty_generics #where_clause {
+            #[inline]
+            fn build_bindings() -> ::glium::vertex::VertexFormat {
+                ::std::borrow::Cow::Owned(vec![#(#bindings),*])
+            }
+
+            #instance_divisor
+        }
+    })
+}
+
+/// Parses the `#[glium(divisor = N)]` attribute of the struct.
+fn parse_struct_options(input: &DeriveInput) -> syn::Result<Option<u32>> {
+    let mut divisor = None;
+
+    for meta in try!(glium_attributes(&input.attrs)) {
+        match meta {
+            Meta::NameValue(ref nv) if nv.path.is_ident("divisor") => {
+                let value = match nv.lit {
+                    Lit::Int(ref lit) => try!(lit.base10_parse::<u32>()),
+                    _ => return Err(syn::Error::new(nv.lit.span(), "expected an integer")),
+                };
+
+                if value == 0 {
+                    return Err(syn::Error::new(nv.lit.span(), "the divisor can't be 0"));
+                }
+
+                divisor = Some(value);
+            },
+            _ => return Err(syn::Error::new(meta.span(), "unknown attribute, expected \
+                                                          `divisor = N`")),
+        }
+    }
+
+    Ok(divisor)
+}
+
+/// Parses the `#[glium(...)]` attributes of a field.
+fn parse_field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for meta in try!(glium_attributes(&field.attrs)) {
+        match meta {
+            Meta::NameValue(ref nv) if nv.path.is_ident("name") => {
+                match nv.lit {
+                    Lit::Str(ref lit) => options.name = Some(lit.value()),
+                    _ => return Err(syn::Error::new(nv.lit.span(), "expected a string")),
+                }
+            },
+            Meta::Path(ref path) if path.is_ident("normalize") => {
+                options.normalize = true;
+            },
+            Meta::Path(ref path) if path.is_ident("integer") => {
+                options.integer = Some(path.span());
+            },
+            _ => return Err(syn::Error::new(meta.span(), "unknown attribute, expected \
+                                                          `name = \"...\"`, `normalize` or \
+                                                          `integer`")),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Returns the content of all the `#[glium(...)]` attributes.
+fn glium_attributes(attrs: &[syn::Attribute]) -> syn::Result<Vec<Meta>> {
+    let mut result = Vec::new();
+
+    for attr in attrs {
+        if !attr.path.is_ident("glium") {
+            continue;
+        }
+
+        match try!(attr.parse_meta()) {
+            Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(meta) => result.push(meta),
+                        NestedMeta::Lit(lit) => {
+                            return Err(syn::Error::new(lit.span(), "unexpected literal"));
+                        },
+                    }
+                }
+            },
+            meta => return Err(syn::Error::new(meta.span(), "expected `#[glium(...)]`")),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Returns true if the type is an integer type, or an array, tuple or generic type whose
+/// components are integers.
+fn is_integer_type(ty: &Type) -> bool {
+    match *ty {
+        Type::Array(ref array) => is_integer_type(&array.elem),
+        Type::Tuple(ref tuple) => {
+            !tuple.elems.is_empty() && tuple.elems.iter().all(is_integer_type)
+        },
+        Type::Paren(ref paren) => is_integer_type(&paren.elem),
+        Type::Group(ref group) => is_integer_type(&group.elem),
+        Type::Path(ref path) if path.qself.is_none() => {
+            let segment = match path.path.segments.last() {
+                Some(segment) => segment,
+                None => return false,
+            };
+
+            match segment.arguments {
+                PathArguments::None => {
+                    match &*segment.ident.to_string() {
+                        "i8" | "u8" | "i16" | "u16" | "i32" | "u32" => true,
+                        _ => false,
+                    }
+                },
+                // for example `cgmath::Vector2<i32>`
+                PathArguments::AngleBracketed(ref args) => {
+                    let mut types = args.args.iter().filter_map(|arg| match *arg {
+                        GenericArgument::Type(ref ty) => Some(ty),
+                        _ => None,
+                    }).peekable();
+
+                    types.peek().is_some() && types.all(is_integer_type)
+                },
+                PathArguments::Parenthesized(_) => false,
+            }
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate glium;
+
+use std::borrow::Cow;
+use glium::vertex::{AttributeType, Vertex};
+
+#[test]
+fn field_names_and_offsets() {
+    #[derive(Copy, Clone, Vertex)]
+    #[repr(C)]
+    struct MyVertex {
+        position: [f32; 3],
+        color: [u8; 4],
+    }
+
+    let bindings = MyVertex::build_bindings();
+    assert_eq!(bindings.len(), 2);
+    assert_eq!(bindings[0], (Cow::Borrowed("position"), 0, AttributeType::F32F32F32, false));
+    assert_eq!(bindings[1], (Cow::Borrowed("color"), 12, AttributeType::U8U8U8U8, false));
+    assert_eq!(MyVertex::instance_divisor(), 1);
+}
+
+#[test]
+fn rename_and_normalize() {
+    #[derive(Copy, Clone, Vertex)]
+    #[repr(C)]
+    struct MyVertex {
+        #[glium(name = "in_position")]
+        position: [f32; 2],
+        #[glium(normalize, name = "in_color")]
+        color: [u8; 4],
+    }
+
+    let bindings = MyVertex::build_bindings();
+    assert_eq!(bindings[0], (Cow::Borrowed("in_position"), 0, AttributeType::F32F32, false));
+    assert_eq!(bindings[1], (Cow::Borrowed("in_color"), 8, AttributeType::U8U8U8U8, true));
+}
+
+#[test]
+fn integer() {
+    #[derive(Copy, Clone, Vertex)]
+    #[repr(C)]
+    struct MyVertex {
+        #[glium(integer)]
+        id: u32,
+        #[glium(integer)]
+        indices: (i16, i16),
+    }
+
+    let bindings = MyVertex::build_bindings();
+    assert_eq!(bindings[0], (Cow::Borrowed("id"), 0, AttributeType::U32, false));
+    assert_eq!(bindings[1], (Cow::Borrowed("indices"), 4, AttributeType::I16I16, false));
+}
+
+#[test]
+fn divisor() {
+    #[derive(Copy, Clone, Vertex)]
+    #[glium(divisor = 3)]
+    struct Instance {
+        offset: [f32; 2],
+    }
+
+    assert_eq!(Instance::instance_divisor(), 3);
+}
+
+#[test]
+fn generic() {
+    #[derive(Copy, Clone, Vertex)]
+    #[repr(C)]
+    struct MyVertex<T: glium::vertex::Attribute + Copy> {
+        position: T,
+        weight: f32,
+    }
+
+    let bindings = <MyVertex<[f32; 2]> as Vertex>::build_bindings();
+    assert_eq!(bindings[0], (Cow::Borrowed("position"), 0, AttributeType::F32F32, false));
+    assert_eq!(bindings[1], (Cow::Borrowed("weight"), 8, AttributeType::F32, false));
+}
@@ -0,0 +1,62 @@
+//! The subset of EGL 1.4 and of its device extensions that the backend uses.
+#![allow(non_camel_case_types, non_snake_case)]
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+pub type EGLint = i32;
+pub type EGLBoolean = c_uint;
+pub type EGLenum = c_uint;
+pub type EGLDisplay = *mut c_void;
+pub type EGLConfig = *mut c_void;
+pub type EGLContext = *mut c_void;
+pub type EGLSurface = *mut c_void;
+pub type EGLDeviceEXT = *mut c_void;
+
+pub const EGL_ALPHA_SIZE: EGLint = 0x3021;
+pub const EGL_BLUE_SIZE: EGLint = 0x3022;
+pub const EGL_GREEN_SIZE: EGLint = 0x3023;
+pub const EGL_RED_SIZE: EGLint = 0x3024;
+pub const EGL_DEPTH_SIZE: EGLint = 0x3025;
+pub const EGL_STENCIL_SIZE: EGLint = 0x3026;
+pub const EGL_SURFACE_TYPE: EGLint = 0x3033;
+pub const EGL_NONE: EGLint = 0x3038;
+pub const EGL_RENDERABLE_TYPE: EGLint = 0x3040;
+pub const EGL_EXTENSIONS: EGLint = 0x3055;
+pub const EGL_HEIGHT: EGLint = 0x3056;
+pub const EGL_WIDTH: EGLint = 0x3057;
+pub const EGL_PBUFFER_BIT: EGLint = 0x0001;
+pub const EGL_OPENGL_BIT: EGLint = 0x0008;
+pub const EGL_OPENGL_API: EGLenum = 0x30A2;
+pub const EGL_PLATFORM_DEVICE_EXT: EGLenum = 0x313F;
+pub const EGL_DRM_DEVICE_FILE_EXT: EGLint = 0x3233;
+pub const EGL_CONTEXT_OPENGL_NO_ERROR_KHR: EGLint = 0x31B3;
+pub const EGL_TRUE: EGLint = 1;
+
+#[link(name = "EGL")]
+extern "system" {
+    pub fn eglGetProcAddress(procname: *const c_char) -> *const c_void;
+    pub fn eglGetError() -> EGLint;
+    pub fn eglQueryString(display: EGLDisplay, name: EGLint) -> *const c_char;
+    pub fn eglInitialize(display: EGLDisplay, major: *mut EGLint, minor: *mut EGLint)
+                         -> EGLBoolean;
+    pub fn eglBindAPI(api: EGLenum) -> EGLBoolean;
+    pub fn eglChooseConfig(display: EGLDisplay, attrib_list: *const EGLint,
+                           configs: *mut EGLConfig, config_size: EGLint,
+                           num_config: *mut EGLint) -> EGLBoolean;
+    pub fn eglCreatePbufferSurface(display: EGLDisplay, config: EGLConfig,
+                                   attrib_list: *const EGLint) -> EGLSurface;
+    pub fn eglDestroySurface(display: EGLDisplay, surface: EGLSurface) -> EGLBoolean;
+    pub fn eglCreateContext(display: EGLDisplay, config: EGLConfig, share_context: EGLContext,
+                            attrib_list: *const EGLint) -> EGLContext;
+    pub fn eglDestroyContext(display: EGLDisplay, context: EGLContext) -> EGLBoolean;
+    pub fn eglMakeCurrent(display: EGLDisplay, draw: EGLSurface, read: EGLSurface,
+                          context: EGLContext) -> EGLBoolean;
+    pub fn eglGetCurrentContext() -> EGLContext;
+}
+
+// `EGL_EXT_device_enumeration`, `EGL_EXT_device_query` and `EGL_EXT_platform_base`
+pub type QueryDevicesExt = extern "system" fn(EGLint, *mut EGLDeviceEXT, *mut EGLint)
+                                              -> EGLBoolean;
+pub type QueryDeviceStringExt = extern "system" fn(EGLDeviceEXT, EGLint) -> *const c_char;
+pub type GetPlatformDisplayExt = extern "system" fn(EGLenum, *mut c_void, *const EGLint)
+                                                   -> EGLDisplay;
@@ -0,0 +1,418 @@
+#![cfg(feature = "egl")]
+/*!
+
+Backend implementation for EGL devices, to create headless contexts on a chosen GPU.
+
+`devices` lists the GPUs that EGL can use, with the `EGL_EXT_device_enumeration` extension. A
+`Headless` context can then be created on any of them with `EGL_EXT_platform_device`, without
+a window system. This is what render farms and servers with several GPUs need.
+
+```no_run
+let devices = glium::backend::egl::devices().unwrap();
+for device in &devices {
+    println!("{:?}", device.drm_device_file());
+}
+
+let headless = glium::backend::egl::Headless::new(&devices[0], 1024, 768).unwrap();
+```
+
+# Features
+
+Only available if the 'egl' feature is enabled. The crate is then linked to `libEGL`.
+
+*/
+mod ffi;
+
+use {Frame, IncompatibleOpenGl, SwapBuffersError};
+use debug;
+use context;
+use backend::{self, Backend};
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::ptr;
+use std::rc::Rc;
+
+/// A GPU that EGL can create contexts on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Device {
+    handle: ffi::EGLDeviceEXT,
+}
+
+/// A headless context created on an EGL device.
+pub struct Headless {
+    // contains everything related to the current context and its state
+    context: Rc<context::Context>,
+    // the EGL context used by `context`
+    backend: Rc<EglBackend>,
+}
+
+/// An implementation of the `Backend` trait for an EGL context that draws on a pbuffer.
+pub struct EglBackend {
+    display: ffi::EGLDisplay,
+    context: ffi::EGLContext,
+    surface: ffi::EGLSurface,
+    dimensions: (u32, u32),
+}
+
+/// Error that can happen while enumerating the devices or creating a headless context.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CreationError {
+    /// The EGL implementation doesn't support an extension that is required.
+    ExtensionNotSupported(&'static str),
+    /// No configuration of the device can render with OpenGL on a pbuffer.
+    NoAvailableConfig,
+    /// An EGL function has failed with the given error code.
+    EglError(i32),
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+/// Returns the list of the devices that EGL can create contexts on.
+pub fn devices() -> Result<Vec<Device>, CreationError> {
+    unsafe {
+        if !has_client_extension("EGL_EXT_device_enumeration") &&
+           !has_client_extension("EGL_EXT_device_base")
+        {
+            return Err(CreationError::ExtensionNotSupported("EGL_EXT_device_enumeration"));
+        }
+
+        let query_devices = get_proc_address("eglQueryDevicesEXT");
+        if query_devices.is_null() {
+            return Err(CreationError::ExtensionNotSupported("EGL_EXT_device_enumeration"));
+        }
+        let query_devices: ffi::QueryDevicesExt = mem::transmute(query_devices);
+
+        let mut num_devices = 0;
+        if query_devices(0, ptr::null_mut(), &mut num_devices) == 0 {
+            return Err(last_error());
+        }
+
+        let mut handles = vec![ptr::null_mut(); num_devices as usize];
+        if query_devices(num_devices, handles.as_mut_ptr(), &mut num_devices) == 0 {
+            return Err(last_error());
+        }
+        handles.truncate(num_devices as usize);
+
+        Ok(handles.into_iter().map(|handle| Device { handle: handle }).collect())
+    }
+}
+
+impl Device {
+    /// Returns the path of the DRM device file of the GPU, for example `/dev/dri/card0`.
+    ///
+    /// Returns `None` if the device doesn't support `EGL_EXT_device_drm`.
+    pub fn drm_device_file(&self) -> Option<String> {
+        if !self.extensions().iter().any(|e| e == "EGL_EXT_device_drm") {
+            return None;
+        }
+
+        unsafe { self.query_string(ffi::EGL_DRM_DEVICE_FILE_EXT) }
+    }
+
+    /// Returns the list of the extensions of the device.
+    pub fn extensions(&self) -> Vec<String> {
+        match unsafe { self.query_string(ffi::EGL_EXTENSIONS) } {
+            Some(list) => {
+                list.split(' ').filter(|e| !e.is_empty()).map(|e| e.to_owned()).collect()
+            },
+            None => Vec::new(),
+        }
+    }
+
+    unsafe fn query_string(&self, name: ffi::EGLint) -> Option<String> {
+        let query_device_string = get_proc_address("eglQueryDeviceStringEXT");
+        if query_device_string.is_null() {
+            return None;
+        }
+        let query_device_string: ffi::QueryDeviceStringExt = mem::transmute(query_device_string);
+
+        let value = query_device_string(self.handle, name);
+        if value.is_null() {
+            return None;
+        }
+
+        Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+    }
+}
+
+impl Headless {
+    /// Create a new headless context on the given device, whose default framebuffer has the
+    /// given dimensions.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(device: &Device, width: u32, height: u32) -> Result<Self, CreationError> {
+        Self::with_debug(device, width, height, Default::default())
+    }
+
+    /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
+    pub fn with_debug(device: &Device, width: u32, height: u32,
+                      debug: debug::DebugCallbackBehavior) -> Result<Self, CreationError>
+    {
+        Self::build(device, width, height, debug, false)
+    }
+
+    /// The same as the `new` constructor, but requests a context created with
+    /// `GL_KHR_no_error`, which doesn't check the commands that it receives.
+    ///
+    /// Glium keeps validating the draw commands, which is the only thing that turns mistakes
+    /// into errors instead of undefined behavior with such a context. Returns
+    /// `ExtensionNotSupported` if the implementation doesn't support
+    /// `EGL_KHR_create_context_no_error`.
+    pub fn with_no_error(device: &Device, width: u32, height: u32)
+                         -> Result<Self, CreationError>
+    {
+        Self::build(device, width, height, Default::default(), true)
+    }
+
+    fn build(device: &Device, width: u32, height: u32, debug: debug::DebugCallbackBehavior,
+             no_error: bool) -> Result<Self, CreationError>
+    {
+        let backend = Rc::new(try!(unsafe { EglBackend::new(device, width, height, no_error) }));
+        let context = try!(unsafe { context::Context::new(backend.clone(), true, debug) });
+
+        Ok(Headless {
+            context: context,
+            backend: backend,
+        })
+    }
+
+    /// Start drawing on the default framebuffer.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. Its content can be
+    /// read back with `read_front_buffer`.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.backend.get_framebuffer_dimensions())
+    }
+}
+
+impl Deref for Headless {
+    type Target = context::Context;
+    #[inline]
+    fn deref(&self) -> &context::Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for Headless {
+    #[inline]
+    fn get_context(&self) -> &Rc<context::Context> {
+        &self.context
+    }
+}
+
+impl EglBackend {
+    /// Creates an OpenGL context and a pbuffer of the given dimensions on the device.
+    unsafe fn new(device: &Device, width: u32, height: u32, no_error: bool)
+                  -> Result<EglBackend, CreationError>
+    {
+        if !has_client_extension("EGL_EXT_platform_device") {
+            return Err(CreationError::ExtensionNotSupported("EGL_EXT_platform_device"));
+        }
+
+        let get_platform_display = get_proc_address("eglGetPlatformDisplayEXT");
+        if get_platform_display.is_null() {
+            return Err(CreationError::ExtensionNotSupported("EGL_EXT_platform_base"));
+        }
+        let get_platform_display: ffi::GetPlatformDisplayExt =
+            mem::transmute(get_platform_display);
+
+        // the display is the same for all the contexts of a device
+        let display = get_platform_display(ffi::EGL_PLATFORM_DEVICE_EXT, device.handle,
+                                           ptr::null());
+        if display.is_null() {
+            return Err(last_error());
+        }
+
+        let (mut major, mut minor) = (0, 0);
+        if ffi::eglInitialize(display, &mut major, &mut minor) == 0 {
+            return Err(last_error());
+        }
+
+        if no_error && !has_display_extension(display, "EGL_KHR_create_context_no_error") {
+            return Err(CreationError::ExtensionNotSupported("EGL_KHR_create_context_no_error"));
+        }
+
+        if ffi::eglBindAPI(ffi::EGL_OPENGL_API) == 0 {
+            return Err(last_error());
+        }
+
+        let config_attributes = [
+            ffi::EGL_SURFACE_TYPE, ffi::EGL_PBUFFER_BIT,
+            ffi::EGL_RENDERABLE_TYPE, ffi::EGL_OPENGL_BIT,
+            ffi::EGL_RED_SIZE, 8,
+            ffi::EGL_GREEN_SIZE, 8,
+            ffi::EGL_BLUE_SIZE, 8,
+            ffi::EGL_ALPHA_SIZE, 8,
+            ffi::EGL_DEPTH_SIZE, 24,
+            ffi::EGL_STENCIL_SIZE, 8,
+            ffi::EGL_NONE,
+        ];
+
+        let mut config = ptr::null_mut();
+        let mut num_configs = 0;
+        if ffi::eglChooseConfig(display, config_attributes.as_ptr(), &mut config, 1,
+                                &mut num_configs) == 0
+        {
+            return Err(last_error());
+        }
+        if num_configs == 0 {
+            return Err(CreationError::NoAvailableConfig);
+        }
+
+        let surface_attributes = [
+            ffi::EGL_WIDTH, width as ffi::EGLint,
+            ffi::EGL_HEIGHT, height as ffi::EGLint,
+            ffi::EGL_NONE,
+        ];
+
+        let surface = ffi::eglCreatePbufferSurface(display, config, surface_attributes.as_ptr());
+        if surface.is_null() {
+            return Err(last_error());
+        }
+
+        let context_attributes = if no_error {
+            [ffi::EGL_CONTEXT_OPENGL_NO_ERROR_KHR, ffi::EGL_TRUE, ffi::EGL_NONE]
+        } else {
+            [ffi::EGL_NONE, ffi::EGL_NONE, ffi::EGL_NONE]
+        };
+        let context = ffi::eglCreateContext(display, config, ptr::null_mut(),
+                                            context_attributes.as_ptr());
+        if context.is_null() {
+            let err = last_error();
+            ffi::eglDestroySurface(display, surface);
+            return Err(err);
+        }
+
+        Ok(EglBackend {
+            display: display,
+            context: context,
+            surface: surface,
+            dimensions: (width, height),
+        })
+    }
+}
+
+impl Drop for EglBackend {
+    fn drop(&mut self) {
+        unsafe {
+            if self.is_current() {
+                ffi::eglMakeCurrent(self.display, ptr::null_mut(), ptr::null_mut(),
+                                    ptr::null_mut());
+            }
+
+            // the display is shared with the other contexts of the device, so it isn't
+            // terminated
+            ffi::eglDestroyContext(self.display, self.context);
+            ffi::eglDestroySurface(self.display, self.surface);
+        }
+    }
+}
+
+unsafe impl Backend for EglBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // a pbuffer has no back buffer
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        get_proc_address(symbol)
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        unsafe { ffi::eglGetCurrentContext() == self.context }
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        let result = ffi::eglMakeCurrent(self.display, self.surface, self.surface, self.context);
+        assert!(result != 0, "eglMakeCurrent failed with error {:#x}", ffi::eglGetError());
+    }
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            CreationError::EglError(code) => write!(fmt, "{} ({:#x})", self.description(), code),
+            _ => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for CreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CreationError::ExtensionNotSupported(_) =>
+                "The EGL implementation doesn't support an extension that is required",
+            CreationError::NoAvailableConfig =>
+                "No configuration of the device can render with OpenGL on a pbuffer",
+            CreationError::EglError(_) => "An EGL function has failed",
+            CreationError::IncompatibleOpenGl(ref err) => err.description(),
+        }
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            CreationError::IncompatibleOpenGl(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for CreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> CreationError {
+        CreationError::IncompatibleOpenGl(err)
+    }
+}
+
+/// Returns true if the EGL implementation supports the given client extension.
+unsafe fn has_client_extension(name: &str) -> bool {
+    let extensions = ffi::eglQueryString(ptr::null_mut(), ffi::EGL_EXTENSIONS);
+
+    // implementations without client extensions return an error
+    if extensions.is_null() {
+        ffi::eglGetError();
+        return false;
+    }
+
+    CStr::from_ptr(extensions).to_bytes().split(|&c| c == b' ').any(|e| e == name.as_bytes())
+}
+
+/// Returns true if the EGL display supports the given extension.
+unsafe fn has_display_extension(display: ffi::EGLDisplay, name: &str) -> bool {
+    let extensions = ffi::eglQueryString(display, ffi::EGL_EXTENSIONS);
+    if extensions.is_null() {
+        ffi::eglGetError();
+        return false;
+    }
+
+    CStr::from_ptr(extensions).to_bytes().split(|&c| c == b' ').any(|e| e == name.as_bytes())
+}
+
+/// Loads a function of EGL or OpenGL.
+unsafe fn get_proc_address(symbol: &str) -> *const c_void {
+    let symbol = CString::new(symbol).unwrap();
+    ffi::eglGetProcAddress(symbol.as_ptr())
+}
+
+/// Builds an error from the last error of EGL in this thread.
+fn last_error() -> CreationError {
+    CreationError::EglError(unsafe { ffi::eglGetError() })
+}
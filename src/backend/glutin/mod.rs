@@ -11,8 +11,13 @@ Only available if the 'glutin' feature is enabled.
 pub extern crate glutin;
 
 pub mod headless;
+mod shared;
+mod window;
 
-use {Frame, IncompatibleOpenGl, SwapBuffersError};
+pub use self::shared::{SharedContext, WorkerContext};
+pub use self::window::DisplayWindow;
+
+use {Api, Frame, IncompatibleOpenGl, Profile, SwapBuffersError, Version};
 use debug;
 use context;
 use backend;
@@ -41,14 +46,116 @@ pub struct Display {
     // Used to check whether the framebuffer dimensions have changed between frames. If they have,
     // the glutin context must be resized accordingly.
     last_framebuffer_dimensions: Cell<(u32, u32)>,
+    // The window whose OpenGL context is used by `context`. Either `gl_window` or the window of
+    // a `DisplayWindow`.
+    current_gl_window: Rc<RefCell<Rc<RefCell<glutin::GlWindow>>>>,
+    // The hidden windows of the contexts built with `new_shared_context`, which must be
+    // destroyed by this thread.
+    shared_gl_windows: Rc<shared::SharedGlWindows>,
 }
 
 /// An implementation of the `Backend` trait for glutin.
 #[derive(Clone)]
 pub struct GlutinBackend(Rc<RefCell<glutin::GlWindow>>);
 
+/// An API, version and profile to request when creating a context with
+/// `Display::with_context_candidates`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ContextCandidate {
+    /// The API and version of the context, for example `Version(Api::Gl, 3, 3)`.
+    pub version: Version,
+
+    /// The profile of the context, or `None` to let the implementation choose. Ignored for
+    /// OpenGL ES and for versions of OpenGL before 3.2.
+    pub profile: Option<Profile>,
+}
+
+impl ContextCandidate {
+    /// Requests the version and profile of this candidate with the given builder.
+    fn apply<'a>(&self, context_builder: glutin::ContextBuilder<'a>)
+                 -> glutin::ContextBuilder<'a>
+    {
+        let api = match self.version.0 {
+            Api::Gl => glutin::Api::OpenGl,
+            Api::GlEs => glutin::Api::OpenGlEs,
+        };
+
+        let version = (self.version.1, self.version.2);
+        let context_builder = context_builder.with_gl(glutin::GlRequest::Specific(api, version));
+
+        match self.profile {
+            Some(Profile::Core) => context_builder.with_gl_profile(glutin::GlProfile::Core),
+            Some(Profile::Compatibility) => {
+                context_builder.with_gl_profile(glutin::GlProfile::Compatibility)
+            },
+            None => context_builder,
+        }
+    }
+}
+
+/// The GPU to prefer on a system that has several of them, for example a laptop with an
+/// integrated and a discrete GPU.
+///
+/// The drivers select the GPU when the OpenGL library is loaded, based on the environment of the
+/// process. As modifying the environment of a running program isn't thread-safe, the variables
+/// returned by `environment_variables` must be set before the application starts, for example
+/// by a launcher or a script.
+///
+/// On Linux and BSD, the GPU is selected with Mesa's `DRI_PRIME`. The proprietary NVIDIA driver
+/// is selected with `__NV_PRIME_RENDER_OFFLOAD=1` and `__GLX_VENDOR_LIBRARY_NAME=nvidia`, which
+/// aren't returned as they break OpenGL if this driver isn't installed.
+///
+/// On Windows, the drivers of NVIDIA and AMD choose the GPU based on the
+/// `NvOptimusEnablement` and `AmdPowerXpressRequestHighPerformance` symbols exported by the
+/// executable, so no variable is returned.
+///
+/// Use `get_opengl_renderer_string` to find out which GPU has been selected.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::process::Command;
+/// use glium::backend::glutin::GpuPreference;
+///
+/// // starts the renderer of the application on the discrete GPU
+/// let mut command = Command::new("renderer");
+/// for &(name, value) in GpuPreference::HighPerformance.environment_variables() {
+///     command.env(name, value);
+/// }
+/// command.spawn().unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GpuPreference {
+    /// Let the system choose the GPU.
+    Default,
+    /// Prefer the GPU that uses the least power, usually the integrated one.
+    LowPower,
+    /// Prefer the most powerful GPU, usually the discrete one.
+    HighPerformance,
+}
+
+impl GpuPreference {
+    /// Returns the environment variables that make the drivers select a GPU that matches the
+    /// preference. A launcher should keep the values that the user has already set.
+    pub fn environment_variables(&self) -> &'static [(&'static str, &'static str)] {
+        if !cfg!(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+                     target_os = "netbsd", target_os = "openbsd"))
+        {
+            return &[];
+        }
+
+        // `DRI_PRIME` is read by Mesa when it opens the screen
+        match *self {
+            GpuPreference::Default => &[],
+            GpuPreference::LowPower => &[("DRI_PRIME", "0")],
+            GpuPreference::HighPerformance => &[("DRI_PRIME", "1")],
+        }
+    }
+}
+
 /// Error that can happen while creating a glium display.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum DisplayCreationError {
     /// An error has happened while creating the backend.
     GlutinCreationError(glutin::CreationError),
@@ -71,6 +178,84 @@ impl Display {
         Self::from_gl_window(gl_window).map_err(From::from)
     }
 
+    /// Create a new glium `Display` whose context has a defined behavior for out-of-bound buffer
+    /// accesses, for applications that draw with untrusted index data.
+    ///
+    /// A robust context is requested from the given context builder, and an error is returned if
+    /// the resulting context doesn't support `GL_ARB_robust_buffer_access_behavior` or an
+    /// equivalent. See `Context::has_robust_buffer_access_behavior`.
+    pub fn with_robust_buffer_access(
+        window_builder: glutin::WindowBuilder,
+        context_builder: glutin::ContextBuilder,
+        events_loop: &glutin::EventsLoop,
+    ) -> Result<Self, DisplayCreationError>
+    {
+        let context_builder =
+            context_builder.with_gl_robustness(glutin::Robustness::RobustNoResetNotification);
+        let display = try!(Self::new(window_builder, context_builder, events_loop));
+
+        if !display.has_robust_buffer_access_behavior() {
+            let msg = "The context doesn't support robust buffer access behavior".to_owned();
+            return Err(DisplayCreationError::IncompatibleOpenGl(IncompatibleOpenGl(msg)));
+        }
+
+        Ok(display)
+    }
+
+    /// Create a new glium `Display` by trying each of the candidates in order until a context
+    /// can be created, and returns the candidate that has been used.
+    ///
+    /// `context_builder` is called once per attempt, and the version and profile of the
+    /// candidate are requested with the builder that it returns. As glutin creates the window
+    /// and its context together, a window is created for each attempt. If no candidate
+    /// succeeds, the error of the last one is returned.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if `candidates` is empty.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let events_loop: glium::glutin::EventsLoop = unsafe { ::std::mem::uninitialized() };
+    /// use glium::{Api, Profile, Version};
+    /// use glium::backend::glutin::ContextCandidate;
+    ///
+    /// let candidates = [
+    ///     ContextCandidate { version: Version(Api::Gl, 4, 5), profile: Some(Profile::Core) },
+    ///     ContextCandidate { version: Version(Api::Gl, 3, 3), profile: Some(Profile::Core) },
+    ///     ContextCandidate { version: Version(Api::GlEs, 3, 0), profile: None },
+    /// ];
+    ///
+    /// let (display, candidate) = glium::Display::with_context_candidates(
+    ///     glium::glutin::WindowBuilder::new(),
+    ///     || glium::glutin::ContextBuilder::new().with_depth_buffer(24),
+    ///     &candidates, &events_loop).unwrap();
+    /// println!("Created an {:?} context", candidate.version);
+    /// ```
+    pub fn with_context_candidates<'a, F>(
+        window_builder: glutin::WindowBuilder,
+        context_builder: F,
+        candidates: &[ContextCandidate],
+        events_loop: &glutin::EventsLoop,
+    ) -> Result<(Self, ContextCandidate), DisplayCreationError>
+        where F: Fn() -> glutin::ContextBuilder<'a>
+    {
+        assert!(!candidates.is_empty(), "No context candidate has been given");
+
+        let mut last_error = None;
+
+        for candidate in candidates {
+            let context_builder = candidate.apply(context_builder());
+            match Self::new(window_builder.clone(), context_builder, events_loop) {
+                Ok(display) => return Ok((display, *candidate)),
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        Err(last_error.unwrap())
+    }
+
     /// Create a new glium `Display`.
     ///
     /// Performs a compatibility check to make sure that all core elements of glium are supported
@@ -91,7 +276,17 @@ impl Display {
     pub fn with_debug(gl_window: glutin::GlWindow, debug: debug::DebugCallbackBehavior)
         -> Result<Self, IncompatibleOpenGl>
     {
-        Self::new_inner(gl_window, debug, true)
+        Self::new_inner(gl_window, debug, true, &[])
+    }
+
+    /// The same as the `new` constructor, but the context behaves as if the given extensions
+    /// weren't supported, to test the fallback code paths.
+    ///
+    /// See `Context::with_disabled_extensions`.
+    pub fn with_disabled_extensions(gl_window: glutin::GlWindow, disabled_extensions: &[&str])
+        -> Result<Self, IncompatibleOpenGl>
+    {
+        Self::new_inner(gl_window, Default::default(), true, disabled_extensions)
     }
 
     /// The same as the `unchecked` constructor, but allows for specifying debug callback behaviour.
@@ -100,23 +295,29 @@ impl Display {
         debug: debug::DebugCallbackBehavior,
     ) -> Result<Self, IncompatibleOpenGl>
     {
-        Self::new_inner(gl_window, debug, false)
+        Self::new_inner(gl_window, debug, false, &[])
     }
 
     fn new_inner(
         gl_window: glutin::GlWindow,
         debug: debug::DebugCallbackBehavior,
         checked: bool,
+        disabled_extensions: &[&str],
     ) -> Result<Self, IncompatibleOpenGl>
     {
         let gl_window = Rc::new(RefCell::new(gl_window));
         let glutin_backend = GlutinBackend(gl_window.clone());
         let framebuffer_dimensions = glutin_backend.get_framebuffer_dimensions();
-        let context = try!(unsafe { context::Context::new(glutin_backend, checked, debug) });
+        let context = try!(unsafe {
+            context::Context::with_disabled_extensions(glutin_backend, checked, debug,
+                                                       disabled_extensions)
+        });
         Ok(Display {
+            current_gl_window: Rc::new(RefCell::new(gl_window.clone())),
             gl_window: gl_window,
             context: context,
             last_framebuffer_dimensions: Cell::new(framebuffer_dimensions),
+            shared_gl_windows: Rc::new(shared::SharedGlWindows::new()),
         })
     }
 
@@ -147,10 +348,45 @@ impl Display {
         // Rebuild the Context.
         let backend = GlutinBackend(self.gl_window.clone());
         try!(unsafe { self.context.rebuild(backend) });
+        *self.current_gl_window.borrow_mut() = self.gl_window.clone();
 
         Ok(())
     }
 
+    /// Builds a context that shares its objects with this one, so that buffers and textures can
+    /// be created on another thread.
+    ///
+    /// The context is attached to a hidden window, which is why it must be created here rather
+    /// than on the other thread. Send the returned object to the other thread, then call `build`
+    /// there. See the documentation of `SharedContext`.
+    ///
+    /// The window stays owned by this display, as it must be destroyed by this thread. Once the
+    /// context isn't used anymore, its window is destroyed by the next call to this function or
+    /// when the display is destroyed.
+    pub fn new_shared_context(
+        &self,
+        context_builder: glutin::ContextBuilder,
+        events_loop: &glutin::EventsLoop,
+    ) -> Result<SharedContext, DisplayCreationError>
+    {
+        let window_builder = glutin::WindowBuilder::new()
+            .with_visibility(false)
+            .with_dimensions(1, 1);
+
+        let gl_window = {
+            let gl_window = self.gl_window.borrow();
+            let context_builder = context_builder.with_shared_lists(gl_window.context());
+            try!(glutin::GlWindow::new(window_builder, context_builder, events_loop))
+        };
+
+        // glutin may have made the new context current, but it must only be current on the
+        // thread that uses it
+        unsafe { self.current_gl_window.borrow().borrow().make_current().unwrap(); }
+
+        let gl_window = self.shared_gl_windows.push(gl_window);
+        Ok(shared::new_shared_context(gl_window, context::get_share_group(&self.context)))
+    }
+
     /// Borrow the inner glutin GlWindow.
     #[inline]
     pub fn gl_window(&self) -> Ref<glutin::GlWindow> {
@@ -168,6 +404,11 @@ impl Display {
     /// context will be resized accordingly before returning the `Frame`.
     #[inline]
     pub fn draw(&self) -> Frame {
+        if self.make_window_current(&self.gl_window).is_err() {
+            // reported when the frame is finished
+            context::set_context_lost(&self.context);
+        }
+
         let (w, h) = self.get_framebuffer_dimensions();
 
         // If the size of the framebuffer has changed, resize the context.
@@ -178,6 +419,21 @@ impl Display {
 
         Frame::new(self.context.clone(), (w, h))
     }
+
+    /// Makes the context draw on the given window, if it isn't already the case.
+    ///
+    /// The context stops using the previous window even if an error is returned.
+    fn make_window_current(&self, gl_window: &Rc<RefCell<glutin::GlWindow>>)
+                           -> Result<(), glutin::ContextError>
+    {
+        if Rc::ptr_eq(&*self.current_gl_window.borrow(), gl_window) {
+            return Ok(());
+        }
+
+        unsafe { context::switch_backend(&self.context, GlutinBackend(gl_window.clone())); }
+        *self.current_gl_window.borrow_mut() = gl_window.clone();
+        unsafe { gl_window.borrow().make_current() }
+    }
 }
 
 impl fmt::Display for DisplayCreationError {
@@ -196,7 +452,7 @@ impl Error for DisplayCreationError {
     }
 
     #[inline]
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         match *self {
             DisplayCreationError::GlutinCreationError(ref err) => Some(err),
             DisplayCreationError::IncompatibleOpenGl(ref err) => Some(err),
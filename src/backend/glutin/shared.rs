@@ -0,0 +1,183 @@
+//! Contexts that share their objects with a `Display`.
+
+use {IncompatibleOpenGl, SwapBuffersError};
+use debug;
+use context;
+use backend::{self, Backend};
+use std::cell::RefCell;
+use std::mem;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+use std::sync::Arc;
+use super::glutin;
+use super::glutin::GlContext;
+
+/// A context that shares its objects with a `Display`, and that can be sent to another thread.
+///
+/// Built with `Display::new_shared_context`. Call `build` on the thread that will use the
+/// context, then create buffers and textures with the returned `WorkerContext`. These objects
+/// can be sent back with `Buffer::into_shared` and the `into_shared` method of textures, and
+/// used by the `Display` after calling `Buffer::from_shared` or the `from_shared` function of
+/// textures.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// # let events_loop: glium::glutin::EventsLoop = unsafe { ::std::mem::uninitialized() };
+/// use std::sync::mpsc;
+/// use std::thread;
+/// use glium::texture::Texture2d;
+///
+/// let context_builder = glium::glutin::ContextBuilder::new();
+/// let shared = display.new_shared_context(context_builder, &events_loop).unwrap();
+/// let (sender, receiver) = mpsc::channel();
+///
+/// thread::spawn(move || {
+///     let worker = shared.build().unwrap();
+///     let texture = Texture2d::empty(&worker, 1024, 1024).unwrap();
+///     // upload the content of the texture here
+///     sender.send(texture.into_shared()).unwrap();
+/// });
+///
+/// let texture = Texture2d::from_shared(&display, receiver.recv().unwrap());
+/// ```
+pub struct SharedContext {
+    gl_window: Arc<SharedGlWindow>,
+    share_group: Arc<context::ShareGroup>,
+}
+
+/// The hidden window of a shared context.
+///
+/// The window is owned by the `Display`, which destroys it on its own thread once the worker
+/// context no longer uses it, since windows must not be destroyed by another thread. Only the
+/// context of the window is used by the other thread.
+pub struct SharedGlWindow(glutin::GlWindow);
+
+// the window is only accessed by the thread that uses the context, and it is destroyed by the
+// thread of the `Display` after this one is done with it
+unsafe impl Send for SharedGlWindow {}
+unsafe impl Sync for SharedGlWindow {}
+
+/// The hidden windows of the shared contexts of a `Display`.
+pub struct SharedGlWindows(RefCell<Vec<Arc<SharedGlWindow>>>);
+
+impl SharedGlWindows {
+    /// Builds an empty list.
+    #[inline]
+    pub fn new() -> SharedGlWindows {
+        SharedGlWindows(RefCell::new(Vec::new()))
+    }
+
+    /// Adds a window to the list, and destroys the windows whose context isn't used anymore.
+    pub fn push(&self, gl_window: glutin::GlWindow) -> Arc<SharedGlWindow> {
+        let gl_window = Arc::new(SharedGlWindow(gl_window));
+        let mut gl_windows = self.0.borrow_mut();
+        gl_windows.retain(|w| Arc::strong_count(w) >= 2);
+        gl_windows.push(gl_window.clone());
+        gl_window
+    }
+}
+
+impl Drop for SharedGlWindows {
+    fn drop(&mut self) {
+        for gl_window in self.0.borrow_mut().drain(..) {
+            // the window of a context that is still used by another thread is leaked, as it
+            // can't be destroyed by this thread
+            if let Err(gl_window) = Arc::try_unwrap(gl_window) {
+                mem::forget(gl_window);
+            }
+        }
+    }
+}
+
+/// Builds a `SharedContext` from a window whose context shares its objects with the contexts
+/// of the given share group.
+#[inline]
+pub fn new_shared_context(gl_window: Arc<SharedGlWindow>, share_group: Arc<context::ShareGroup>)
+                          -> SharedContext
+{
+    SharedContext {
+        gl_window: gl_window,
+        share_group: share_group,
+    }
+}
+
+impl SharedContext {
+    /// Makes the context current on this thread and builds a facade for it.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    #[inline]
+    pub fn build(self) -> Result<WorkerContext, IncompatibleOpenGl> {
+        self.build_with_debug(Default::default())
+    }
+
+    /// The same as `build`, but allows for specifying debug callback behaviour.
+    pub fn build_with_debug(self, debug: debug::DebugCallbackBehavior)
+                            -> Result<WorkerContext, IncompatibleOpenGl>
+    {
+        let backend = SharedBackend(self.gl_window);
+        let context = try!(unsafe { context::Context::new(backend, true, debug) });
+        context::set_share_group(&context, self.share_group);
+        Ok(WorkerContext { context: context })
+    }
+}
+
+/// A context that shares its objects with a `Display`. Built with `SharedContext::build`.
+///
+/// Its window is hidden, and drawing on its default framebuffer is not supported.
+///
+/// The window is destroyed by the `Display` after this object, so the `Display` must be kept
+/// alive until the worker context has been destroyed. Otherwise the window is leaked.
+pub struct WorkerContext {
+    context: Rc<context::Context>,
+}
+
+impl Deref for WorkerContext {
+    type Target = context::Context;
+    #[inline]
+    fn deref(&self) -> &context::Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for WorkerContext {
+    #[inline]
+    fn get_context(&self) -> &Rc<context::Context> {
+        &self.context
+    }
+}
+
+/// An implementation of the `Backend` trait for the context of a hidden window, that doesn't
+/// access the window itself.
+struct SharedBackend(Arc<SharedGlWindow>);
+
+unsafe impl Backend for SharedBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // the default framebuffer is never shown
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        (self.0).0.get_proc_address(symbol) as *const _
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        (1, 1)
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        (self.0).0.is_current()
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        (self.0).0.make_current().unwrap();
+    }
+}
@@ -0,0 +1,154 @@
+//! Additional windows drawn with the context of a `Display`.
+
+use Frame;
+use backend::{self, Backend};
+use context;
+use std::cell::{Cell, Ref, RefCell};
+use std::ops::Deref;
+use std::rc::Rc;
+use super::glutin;
+use super::glutin::GlContext;
+use super::{Display, DisplayCreationError, GlutinBackend};
+
+/// A window that is drawn with the context of a `Display`.
+///
+/// Buffers, textures and programs are shared between the display and all its windows, so there
+/// is no need to create them once per window. Built with `Display::new_window`.
+///
+/// Each window has its own OpenGL context, and glium switches to the context of a window when
+/// `draw` is called. Switching destroys the framebuffer objects and vertex array objects that
+/// glium keeps in cache, as they can't be shared between contexts. Therefore it is better to
+/// draw all the frames of one window in a row, and only one `Frame` must be alive at a time:
+/// finish the frame of a window before starting to draw on another one. The other objects that
+/// can't be shared, such as queries, must only be used while drawing on the window that was
+/// drawn on when they were created.
+///
+/// `Context::get_framebuffer_dimensions` returns the dimensions of the window that is being
+/// drawn on. Use the `get_framebuffer_dimensions` method of this object instead.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// # let events_loop: glium::glutin::EventsLoop = unsafe { ::std::mem::uninitialized() };
+/// let window = display.new_window(glium::glutin::WindowBuilder::new(),
+///                                 glium::glutin::ContextBuilder::new(), &events_loop).unwrap();
+///
+/// let mut frame = display.draw();
+/// frame.clear_color(0.0, 0.0, 1.0, 1.0);
+/// frame.finish().unwrap();
+///
+/// let mut frame = window.draw().unwrap();
+/// frame.clear_color(1.0, 0.0, 0.0, 1.0);
+/// frame.finish().unwrap();
+/// ```
+pub struct DisplayWindow {
+    display: Display,
+    gl_window: Rc<RefCell<glutin::GlWindow>>,
+    // Used to check whether the framebuffer dimensions have changed between frames.
+    last_framebuffer_dimensions: Cell<(u32, u32)>,
+}
+
+impl Display {
+    /// Opens another window that is drawn with the context of this display.
+    ///
+    /// The context builder must request the same version and profile as the one of the display,
+    /// as the contexts of the windows must share their objects. The window is closed when the
+    /// returned object is destroyed.
+    pub fn new_window(
+        &self,
+        window_builder: glutin::WindowBuilder,
+        context_builder: glutin::ContextBuilder,
+        events_loop: &glutin::EventsLoop,
+    ) -> Result<DisplayWindow, DisplayCreationError>
+    {
+        let gl_window = {
+            let main_gl_window = self.gl_window.borrow();
+            let context_builder = context_builder.with_shared_lists(main_gl_window.context());
+            try!(glutin::GlWindow::new(window_builder, context_builder, events_loop))
+        };
+
+        // glutin may have made the new context current
+        unsafe { self.current_gl_window.borrow().borrow().make_current().unwrap(); }
+
+        let gl_window = Rc::new(RefCell::new(gl_window));
+        let framebuffer_dimensions = GlutinBackend(gl_window.clone()).get_framebuffer_dimensions();
+
+        Ok(DisplayWindow {
+            display: self.clone(),
+            gl_window: gl_window,
+            last_framebuffer_dimensions: Cell::new(framebuffer_dimensions),
+        })
+    }
+}
+
+impl DisplayWindow {
+    /// Borrow the inner glutin GlWindow.
+    #[inline]
+    pub fn gl_window(&self) -> Ref<glutin::GlWindow> {
+        self.gl_window.borrow()
+    }
+
+    /// Returns the display whose context is used to draw on this window.
+    #[inline]
+    pub fn get_display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Returns the dimensions of the default framebuffer of this window.
+    #[inline]
+    pub fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        GlutinBackend(self.gl_window.clone()).get_framebuffer_dimensions()
+    }
+
+    /// Start drawing on the backbuffer of this window.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. When the `Frame` is
+    /// destroyed, the buffers are swapped.
+    ///
+    /// If the framebuffer dimensions have changed since the last call to `draw`, the inner glutin
+    /// context will be resized accordingly before returning the `Frame`.
+    ///
+    /// Returns an error if the context of the window can't be made current.
+    pub fn draw(&self) -> Result<Frame, glutin::ContextError> {
+        try!(self.display.make_window_current(&self.gl_window));
+        let (w, h) = self.get_framebuffer_dimensions();
+
+        if self.last_framebuffer_dimensions.get() != (w, h) {
+            self.last_framebuffer_dimensions.set((w, h));
+            self.gl_window.borrow().resize(w, h);
+        }
+
+        Ok(Frame::new(self.display.context.clone(), (w, h)))
+    }
+}
+
+impl Drop for DisplayWindow {
+    fn drop(&mut self) {
+        // the context must not keep the window open, so it switches back to the window of the
+        // display, which releases this one even if the switch fails
+        let is_current = Rc::ptr_eq(&*self.display.current_gl_window.borrow(), &self.gl_window);
+        if is_current {
+            let main_gl_window = self.display.gl_window.clone();
+            if self.display.make_window_current(&main_gl_window).is_err() {
+                context::set_context_lost(&self.display.context);
+            }
+        }
+    }
+}
+
+impl Deref for DisplayWindow {
+    type Target = context::Context;
+    #[inline]
+    fn deref(&self) -> &context::Context {
+        &self.display.context
+    }
+}
+
+impl backend::Facade for DisplayWindow {
+    #[inline]
+    fn get_context(&self) -> &Rc<context::Context> {
+        &self.display.context
+    }
+}
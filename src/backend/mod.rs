@@ -12,6 +12,8 @@ There are three concepts in play:
    that implements this trait. It is implemented on `Rc<Context>`.
 
 */
+use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
 use std::ops::Deref;
 use std::os::raw::c_void;
@@ -25,9 +27,19 @@ use version::Version;
 
 pub use context::Context;
 pub use context::ReleaseBehavior;
+pub use context::StateAssumptions;
+pub use context::CapabilitiesReport;
+pub use context::{Fallback, FallbackReport};
+pub use context::{MemoryInfo, Allocation, AllocationKind};
 
+#[cfg(feature = "egl")]
+pub mod egl;
 #[cfg(feature = "glutin")]
 pub mod glutin;
+#[cfg(feature = "web")]
+pub mod web;
+
+mod swap_interval;
 
 /// Trait for types that can be used as a backend for a glium context.
 ///
@@ -50,6 +62,17 @@ pub unsafe trait Backend {
 
     /// Makes the OpenGL context the current context in the current thread.
     unsafe fn make_current(&self);
+
+    /// Changes how swapping the buffers is synchronized with the refresh of the screen.
+    ///
+    /// Supposes that the context has been made current before this function is called.
+    ///
+    /// The default implementation loads `eglSwapInterval`, `wglSwapIntervalEXT`,
+    /// `glXSwapIntervalEXT`, `glXSwapIntervalMESA` or `glXSwapIntervalSGI` with
+    /// `get_proc_address`, depending on the platform of the context.
+    unsafe fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        swap_interval::set_swap_interval(|symbol| self.get_proc_address(symbol), interval)
+    }
 }
 
 unsafe impl<T> Backend for Rc<T> where T: Backend {
@@ -72,6 +95,59 @@ unsafe impl<T> Backend for Rc<T> where T: Backend {
     unsafe fn make_current(&self) {
         self.deref().make_current();
     }
+
+    unsafe fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        self.deref().set_swap_interval(interval)
+    }
+}
+
+/// How swapping the buffers is synchronized with the refresh of the screen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SwapInterval {
+    /// The buffers are swapped immediately, which can cause tearing.
+    DontWait,
+
+    /// The buffers are swapped after the given number of refreshes of the screen, which must be
+    /// at least 1. `Wait(1)` is what is usually called vsync.
+    Wait(u32),
+
+    /// The buffers are swapped at the next refresh of the screen, or immediately if the previous
+    /// refresh has been missed. Tearing only happens when the framerate drops below the refresh
+    /// rate. Also known as adaptive vsync.
+    AdaptiveWait,
+
+    /// Swapping the buffers never blocks, and the latest frame is shown at the next refresh of
+    /// the screen. OpenGL doesn't provide this mode, so only backends that present the frames
+    /// with another API can support it.
+    Mailbox,
+}
+
+/// Error that can happen when changing the swap interval.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SwapIntervalError {
+    /// The backend can't change the swap interval.
+    NotSupported,
+
+    /// The backend can change the swap interval, but doesn't support the requested one.
+    IntervalNotSupported,
+}
+
+impl fmt::Display for SwapIntervalError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for SwapIntervalError {
+    fn description(&self) -> &str {
+        match self {
+            &SwapIntervalError::NotSupported =>
+                "The backend can't change the swap interval",
+            &SwapIntervalError::IntervalNotSupported =>
+                "The backend doesn't support the requested swap interval",
+        }
+    }
 }
 
 /// Trait for types that provide a safe access for glium functions.
@@ -0,0 +1,209 @@
+//! Changes the swap interval with the functions of EGL, WGL or GLX.
+
+use std::ffi::CStr;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_uint, c_ulong, c_void};
+
+use backend::{SwapInterval, SwapIntervalError};
+
+const GLX_SCREEN: c_int = 0x800C;
+
+type EglGetCurrentDisplay = extern "system" fn() -> *const c_void;
+type EglSwapInterval = extern "system" fn(*const c_void, c_int) -> c_uint;
+type WglGetExtensionsStringExt = extern "system" fn() -> *const c_char;
+type WglSwapIntervalExt = extern "system" fn(c_int) -> c_int;
+type GlxGetCurrentDisplay = extern "C" fn() -> *mut c_void;
+type GlxGetCurrentContext = extern "C" fn() -> *mut c_void;
+type GlxGetCurrentDrawable = extern "C" fn() -> c_ulong;
+type GlxQueryExtensionsString = extern "C" fn(*mut c_void, c_int) -> *const c_char;
+type GlxQueryContext = extern "C" fn(*mut c_void, *mut c_void, c_int, *mut c_int) -> c_int;
+type GlxSwapIntervalExt = extern "C" fn(*mut c_void, c_ulong, c_int);
+type GlxSwapIntervalMesa = extern "C" fn(c_uint) -> c_int;
+type GlxSwapIntervalSgi = extern "C" fn(c_int) -> c_int;
+
+/// Changes the swap interval of the current context, by loading the functions of the platform
+/// with `get_proc_address`.
+///
+/// EGL is tried first, because an EGL implementation may return stubs for the functions of GLX.
+pub unsafe fn set_swap_interval<F>(get_proc_address: F, interval: SwapInterval)
+                                   -> Result<(), SwapIntervalError>
+                                   where F: Fn(&str) -> *const c_void
+{
+    let value: c_int = match interval {
+        SwapInterval::DontWait => 0,
+        SwapInterval::Wait(frames) => {
+            assert!(frames >= 1, "The swap interval must be at least 1");
+            frames as c_int
+        },
+        SwapInterval::AdaptiveWait => -1,
+        SwapInterval::Mailbox => return Err(SwapIntervalError::IntervalNotSupported),
+    };
+
+    if let Some(result) = set_egl(&get_proc_address, value) {
+        return result;
+    }
+
+    if let Some(result) = set_wgl(&get_proc_address, value) {
+        return result;
+    }
+
+    if let Some(result) = set_glx(&get_proc_address, value) {
+        return result;
+    }
+
+    Err(SwapIntervalError::NotSupported)
+}
+
+/// Returns `None` if the context wasn't created with EGL.
+unsafe fn set_egl<F>(get_proc_address: &F, value: c_int) -> Option<Result<(), SwapIntervalError>>
+                     where F: Fn(&str) -> *const c_void
+{
+    let get_current_display = get_proc_address("eglGetCurrentDisplay");
+    if get_current_display.is_null() {
+        return None;
+    }
+
+    let display = mem::transmute::<_, EglGetCurrentDisplay>(get_current_display)();
+    if display.is_null() {
+        return None;
+    }
+
+    let swap_interval = get_proc_address("eglSwapInterval");
+    if swap_interval.is_null() {
+        return Some(Err(SwapIntervalError::NotSupported));
+    }
+
+    // EGL clamps the interval instead of supporting negative values
+    if value < 0 {
+        return Some(Err(SwapIntervalError::IntervalNotSupported));
+    }
+
+    if mem::transmute::<_, EglSwapInterval>(swap_interval)(display, value) == 0 {
+        return Some(Err(SwapIntervalError::IntervalNotSupported));
+    }
+
+    Some(Ok(()))
+}
+
+/// Returns `None` if the context wasn't created with WGL.
+unsafe fn set_wgl<F>(get_proc_address: &F, value: c_int) -> Option<Result<(), SwapIntervalError>>
+                     where F: Fn(&str) -> *const c_void
+{
+    let get_extensions = get_proc_address("wglGetExtensionsStringEXT");
+    if get_extensions.is_null() {
+        return None;
+    }
+
+    let extensions = mem::transmute::<_, WglGetExtensionsStringExt>(get_extensions)();
+    if extensions.is_null() {
+        return None;
+    }
+
+    let extensions = CStr::from_ptr(extensions).to_string_lossy();
+    let has_extension = |name| extensions.split(' ').any(|e| e == name);
+
+    if !has_extension("WGL_EXT_swap_control") {
+        return Some(Err(SwapIntervalError::NotSupported));
+    }
+
+    if value < 0 && !has_extension("WGL_EXT_swap_control_tear") {
+        return Some(Err(SwapIntervalError::IntervalNotSupported));
+    }
+
+    let swap_interval = get_proc_address("wglSwapIntervalEXT");
+    if swap_interval.is_null() {
+        return Some(Err(SwapIntervalError::NotSupported));
+    }
+
+    if mem::transmute::<_, WglSwapIntervalExt>(swap_interval)(value) == 0 {
+        return Some(Err(SwapIntervalError::IntervalNotSupported));
+    }
+
+    Some(Ok(()))
+}
+
+/// Returns `None` if the context wasn't created with GLX.
+unsafe fn set_glx<F>(get_proc_address: &F, value: c_int) -> Option<Result<(), SwapIntervalError>>
+                     where F: Fn(&str) -> *const c_void
+{
+    let get_current_display = get_proc_address("glXGetCurrentDisplay");
+    let get_current_context = get_proc_address("glXGetCurrentContext");
+    let get_current_drawable = get_proc_address("glXGetCurrentDrawable");
+    let query_extensions = get_proc_address("glXQueryExtensionsString");
+    if get_current_display.is_null() || get_current_context.is_null() ||
+       get_current_drawable.is_null() || query_extensions.is_null()
+    {
+        return None;
+    }
+
+    let display = mem::transmute::<_, GlxGetCurrentDisplay>(get_current_display)();
+    let context = mem::transmute::<_, GlxGetCurrentContext>(get_current_context)();
+    if display.is_null() || context.is_null() {
+        return None;
+    }
+
+    let mut screen = 0;
+    let query_context = get_proc_address("glXQueryContext");
+    if !query_context.is_null() {
+        let query_context = mem::transmute::<_, GlxQueryContext>(query_context);
+        query_context(display, context, GLX_SCREEN, &mut screen);
+    }
+
+    let query_extensions = mem::transmute::<_, GlxQueryExtensionsString>(query_extensions);
+    let extensions = query_extensions(display, screen);
+    if extensions.is_null() {
+        return Some(Err(SwapIntervalError::NotSupported));
+    }
+
+    let extensions = CStr::from_ptr(extensions).to_string_lossy();
+    let has_extension = |name| extensions.split(' ').any(|e| e == name);
+
+    if has_extension("GLX_EXT_swap_control") {
+        if value < 0 && !has_extension("GLX_EXT_swap_control_tear") {
+            return Some(Err(SwapIntervalError::IntervalNotSupported));
+        }
+
+        let swap_interval = get_proc_address("glXSwapIntervalEXT");
+        if !swap_interval.is_null() {
+            let drawable = mem::transmute::<_, GlxGetCurrentDrawable>(get_current_drawable)();
+            mem::transmute::<_, GlxSwapIntervalExt>(swap_interval)(display, drawable, value);
+            return Some(Ok(()));
+        }
+    }
+
+    // the other extensions don't support negative intervals
+    if value < 0 {
+        return Some(Err(SwapIntervalError::IntervalNotSupported));
+    }
+
+    if has_extension("GLX_MESA_swap_control") {
+        let swap_interval = get_proc_address("glXSwapIntervalMESA");
+        if !swap_interval.is_null() {
+            let swap_interval = mem::transmute::<_, GlxSwapIntervalMesa>(swap_interval);
+            return Some(if swap_interval(value as c_uint) == 0 {
+                Ok(())
+            } else {
+                Err(SwapIntervalError::IntervalNotSupported)
+            });
+        }
+    }
+
+    if has_extension("GLX_SGI_swap_control") {
+        // this extension doesn't support disabling the synchronization
+        if value == 0 {
+            return Some(Err(SwapIntervalError::IntervalNotSupported));
+        }
+
+        let swap_interval = get_proc_address("glXSwapIntervalSGI");
+        if !swap_interval.is_null() {
+            let swap_interval = mem::transmute::<_, GlxSwapIntervalSgi>(swap_interval);
+            return Some(if swap_interval(value) == 0 {
+                Ok(())
+            } else {
+                Err(SwapIntervalError::IntervalNotSupported)
+            });
+        }
+    }
+
+    Some(Err(SwapIntervalError::NotSupported))
+}
@@ -0,0 +1,245 @@
+#![cfg(feature = "web")]
+/*!
+
+Backend implementation for WebGL 2, to run glium in a browser.
+
+The OpenGL functions that glium calls are implemented on top of a `WebGl2RenderingContext`
+from the `web-sys` crate. WebGL 2 is reported as OpenGL ES 3.0, and glium adjusts to it like it
+does for any other OpenGL ES 3.0 implementation.
+
+```no_run
+# extern crate glium;
+# extern crate web_sys;
+# fn main() {
+# let canvas: web_sys::HtmlCanvasElement = unsafe { ::std::mem::uninitialized() };
+use glium::Surface;
+
+let display = glium::backend::web::Display::new(&canvas).unwrap();
+
+// usually called from a `requestAnimationFrame` callback
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 1.0, 1.0);
+frame.finish().unwrap();
+# }
+```
+
+# Limitations
+
+WebGL is more restricted than OpenGL ES 3.0:
+
+ - Buffers can't be mapped in the memory of the client. Mapping a buffer is emulated by copying
+   its content into a temporary buffer, and writing it back when the mapping is dropped or
+   flushed. Persistent mapping isn't supported.
+ - There is no `glGetTexImage`. Textures are read by attaching them to a framebuffer, like with
+   any OpenGL ES context.
+ - The results of the queries and the state of the fences are only updated when the browser's
+   event loop runs. Waiting for a fence calls `glFinish` instead.
+ - Uploading the content of a texture from a pixel buffer is supported, but client-side vertex
+   and index data isn't.
+ - The frame is shown by the browser when control returns to its event loop, so swapping the
+   buffers does nothing. The swap interval can't be changed.
+
+# Features
+
+Only available if the 'web' feature is enabled.
+
+*/
+mod shim;
+
+use {Frame, IncompatibleOpenGl, SwapBuffersError};
+use debug;
+use context;
+use backend;
+use backend::{Backend, Context, SwapInterval, SwapIntervalError};
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlCanvasElement, WebGl2RenderingContext};
+
+/// A WebGL 2 context combined with a facade for drawing upon.
+#[derive(Clone)]
+pub struct Display {
+    // contains everything related to the current context and its state
+    context: Rc<context::Context>,
+    // the WebGL context used by `context`
+    backend: Rc<WebGlBackend>,
+}
+
+/// An implementation of the `Backend` trait for a `WebGl2RenderingContext`.
+pub struct WebGlBackend {
+    state: Rc<RefCell<shim::State>>,
+}
+
+/// Error that can happen while creating a glium display.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DisplayCreationError {
+    /// The browser couldn't create a WebGL 2 context for the canvas.
+    WebGl2NotSupported,
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl Display {
+    /// Create a new glium `Display` that draws on the given canvas.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(canvas: &HtmlCanvasElement) -> Result<Self, DisplayCreationError> {
+        let context = match canvas.get_context("webgl2") {
+            Ok(Some(context)) => context,
+            _ => return Err(DisplayCreationError::WebGl2NotSupported),
+        };
+
+        let context = match context.dyn_into::<WebGl2RenderingContext>() {
+            Ok(context) => context,
+            Err(_) => return Err(DisplayCreationError::WebGl2NotSupported),
+        };
+
+        Self::from_webgl2_context(context).map_err(From::from)
+    }
+
+    /// Create a new glium `Display` from an existing WebGL 2 context.
+    ///
+    /// The context must be newly-created. Performs a compatibility check to make sure that all
+    /// core elements of glium are supported by the implementation.
+    pub fn from_webgl2_context(context: WebGl2RenderingContext)
+                               -> Result<Self, IncompatibleOpenGl>
+    {
+        Self::with_debug(context, Default::default())
+    }
+
+    /// The same as `from_webgl2_context`, but allows for specifying debug callback behaviour.
+    pub fn with_debug(context: WebGl2RenderingContext, debug: debug::DebugCallbackBehavior)
+                      -> Result<Self, IncompatibleOpenGl>
+    {
+        let backend = Rc::new(WebGlBackend::new(context));
+        let context = try!(unsafe { context::Context::new(backend.clone(), true, debug) });
+
+        Ok(Display {
+            context: context,
+            backend: backend,
+        })
+    }
+
+    /// Returns the WebGL context that glium draws with.
+    #[inline]
+    pub fn webgl2_context(&self) -> WebGl2RenderingContext {
+        self.backend.webgl2_context()
+    }
+
+    /// Start drawing on the canvas.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. The content of the
+    /// frame is shown once control returns to the browser.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.backend.get_framebuffer_dimensions())
+    }
+}
+
+impl fmt::Display for DisplayCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for DisplayCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            DisplayCreationError::WebGl2NotSupported =>
+                "The browser couldn't create a WebGL 2 context for the canvas",
+            DisplayCreationError::IncompatibleOpenGl(ref err) => err.description(),
+        }
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            DisplayCreationError::WebGl2NotSupported => None,
+            DisplayCreationError::IncompatibleOpenGl(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for DisplayCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> DisplayCreationError {
+        DisplayCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl Deref for Display {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for Display {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+impl WebGlBackend {
+    /// Builds a backend from a newly-created WebGL 2 context.
+    #[inline]
+    pub fn new(context: WebGl2RenderingContext) -> WebGlBackend {
+        WebGlBackend {
+            state: Rc::new(RefCell::new(shim::State::new(context))),
+        }
+    }
+
+    /// Returns the WebGL context of this backend.
+    #[inline]
+    pub fn webgl2_context(&self) -> WebGl2RenderingContext {
+        self.state.borrow().webgl2_context().clone()
+    }
+}
+
+unsafe impl Backend for WebGlBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // the browser shows the frame when control returns to its event loop
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        shim::get_proc_address(symbol)
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        let state = self.state.borrow();
+        let context = state.webgl2_context();
+        (context.drawing_buffer_width() as u32, context.drawing_buffer_height() as u32)
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        shim::is_current(&self.state)
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        shim::make_current(&self.state);
+    }
+
+    unsafe fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        // `requestAnimationFrame` is synchronized with the refresh of the screen
+        match interval {
+            SwapInterval::Wait(1) => Ok(()),
+            _ => Err(SwapIntervalError::NotSupported),
+        }
+    }
+}
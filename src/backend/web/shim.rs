@@ -0,0 +1,1763 @@
+//! Implementation of the OpenGL ES 3.0 functions used by glium on top of WebGL 2.
+//!
+//! WebGL uses objects where OpenGL uses integer names, so each kind of object has a table that
+//! associates the names returned to glium with the WebGL objects. The functions are plain
+//! `extern "system"` functions that access the state of the context that is current on the
+//! thread, which allows `gl::Gl::load_with` to load them like the functions of a driver.
+#![allow(non_snake_case)]
+
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::ptr;
+use std::rc::{Rc, Weak};
+use std::slice;
+
+use js_sys;
+use wasm_bindgen::JsValue;
+use web_sys::{WebGl2RenderingContext, WebGlBuffer, WebGlFramebuffer, WebGlProgram, WebGlQuery};
+use web_sys::{WebGlRenderbuffer, WebGlSampler, WebGlShader, WebGlSync, WebGlTexture};
+use web_sys::{WebGlUniformLocation, WebGlVertexArrayObject};
+
+use gl;
+use gl::types::*;
+
+/// WebGL extensions that are enabled when the context is created, and the name of the OpenGL
+/// extension that they are reported as.
+const EXTENSIONS: &'static [(&'static str, &'static str)] = &[
+    ("EXT_color_buffer_float", "GL_EXT_color_buffer_float"),
+    ("EXT_texture_filter_anisotropic", "GL_EXT_texture_filter_anisotropic"),
+    ("OES_texture_float_linear", "GL_OES_texture_float_linear"),
+    ("WEBGL_compressed_texture_s3tc", "GL_EXT_texture_compression_s3tc"),
+];
+
+thread_local!(static CURRENT: RefCell<Option<Weak<RefCell<State>>>> = RefCell::new(None));
+
+/// Associates the names of OpenGL objects with WebGL objects.
+struct Objects<T> {
+    slots: Vec<Option<T>>,
+}
+
+impl<T> Objects<T> {
+    #[inline]
+    fn new() -> Objects<T> {
+        Objects { slots: Vec::new() }
+    }
+
+    /// Stores an object and returns its name. Names start at 1, as 0 means "no object".
+    fn insert(&mut self, object: T) -> GLuint {
+        match self.slots.iter().position(|slot| slot.is_none()) {
+            Some(index) => {
+                self.slots[index] = Some(object);
+                index as GLuint + 1
+            },
+            None => {
+                self.slots.push(Some(object));
+                self.slots.len() as GLuint
+            },
+        }
+    }
+
+    #[inline]
+    fn get(&self, name: GLuint) -> Option<&T> {
+        if name == 0 {
+            return None;
+        }
+
+        self.slots.get(name as usize - 1).and_then(|slot| slot.as_ref())
+    }
+
+    #[inline]
+    fn remove(&mut self, name: GLuint) -> Option<T> {
+        if name == 0 {
+            return None;
+        }
+
+        self.slots.get_mut(name as usize - 1).and_then(|slot| slot.take())
+    }
+}
+
+/// A range of a buffer that is mapped in a temporary buffer.
+struct Mapping {
+    offset: GLintptr,
+    access: GLbitfield,
+    data: Vec<u8>,
+}
+
+/// State of a WebGL context, shared with the functions of this module when it is current.
+pub struct State {
+    gl: WebGl2RenderingContext,
+
+    buffers: Objects<WebGlBuffer>,
+    framebuffers: Objects<WebGlFramebuffer>,
+    programs: Objects<WebGlProgram>,
+    queries: Objects<WebGlQuery>,
+    renderbuffers: Objects<WebGlRenderbuffer>,
+    samplers: Objects<WebGlSampler>,
+    shaders: Objects<WebGlShader>,
+    syncs: Objects<WebGlSync>,
+    textures: Objects<WebGlTexture>,
+    vertex_arrays: Objects<WebGlVertexArrayObject>,
+
+    // the locations returned by `glGetUniformLocation` are indices in these lists
+    uniform_locations: HashMap<GLuint, Vec<WebGlUniformLocation>>,
+    current_program: GLuint,
+
+    // buffers bound to `GL_PIXEL_PACK_BUFFER` and `GL_PIXEL_UNPACK_BUFFER`
+    pixel_pack_buffer: GLuint,
+    pixel_unpack_buffer: GLuint,
+    pixel_store: HashMap<GLenum, GLint>,
+
+    // mapped buffers, by binding point
+    mappings: HashMap<GLenum, Mapping>,
+
+    strings: HashMap<GLenum, CString>,
+    extensions: Vec<CString>,
+
+    // error generated by this module, returned by `glGetError` before the ones of WebGL
+    error: GLenum,
+}
+
+impl State {
+    /// Builds the state of a newly-created context, and enables the extensions that glium can
+    /// use.
+    pub fn new(gl: WebGl2RenderingContext) -> State {
+        let supported: Vec<String> = match gl.get_supported_extensions() {
+            Some(list) => list.iter().filter_map(|e| e.as_string()).collect(),
+            None => Vec::new(),
+        };
+
+        let extensions = EXTENSIONS.iter()
+            .filter(|&&(webgl, _)| supported.iter().any(|e| e == webgl))
+            .filter(|&&(webgl, _)| gl.get_extension(webgl).ok().and_then(|e| e).is_some())
+            .map(|&(_, name)| CString::new(name).unwrap())
+            .collect();
+
+        State {
+            gl: gl,
+            buffers: Objects::new(),
+            framebuffers: Objects::new(),
+            programs: Objects::new(),
+            queries: Objects::new(),
+            renderbuffers: Objects::new(),
+            samplers: Objects::new(),
+            shaders: Objects::new(),
+            syncs: Objects::new(),
+            textures: Objects::new(),
+            vertex_arrays: Objects::new(),
+            uniform_locations: HashMap::new(),
+            current_program: 0,
+            pixel_pack_buffer: 0,
+            pixel_unpack_buffer: 0,
+            pixel_store: HashMap::new(),
+            mappings: HashMap::new(),
+            strings: HashMap::new(),
+            extensions: extensions,
+            error: gl::NO_ERROR,
+        }
+    }
+
+    /// Returns the WebGL context.
+    #[inline]
+    pub fn webgl2_context(&self) -> &WebGl2RenderingContext {
+        &self.gl
+    }
+
+    /// Records an error to be returned by `glGetError`.
+    #[inline]
+    fn set_error(&mut self, error: GLenum) {
+        if self.error == gl::NO_ERROR {
+            self.error = error;
+        }
+    }
+
+    /// Calls `getParameter`. The errors generated by the parameters that WebGL doesn't know are
+    /// discarded, as they would be reported to glium as bugs.
+    fn get_parameter(&mut self, pname: GLenum) -> JsValue {
+        let previous = self.gl.get_error();
+        if previous != gl::NO_ERROR {
+            self.set_error(previous);
+        }
+
+        let value = self.gl.get_parameter(pname).unwrap_or(JsValue::NULL);
+        if value.is_null() {
+            self.gl.get_error();
+        }
+
+        value
+    }
+
+    #[inline]
+    fn uniform_location(&self, location: GLint) -> Option<&WebGlUniformLocation> {
+        if location < 0 {
+            return None;
+        }
+
+        self.uniform_locations.get(&self.current_program)
+            .and_then(|locations| locations.get(location as usize))
+    }
+
+    #[inline]
+    fn pixel_store(&self, pname: GLenum) -> usize {
+        let default = match pname {
+            gl::PACK_ALIGNMENT | gl::UNPACK_ALIGNMENT => 4,
+            _ => 0,
+        };
+
+        self.pixel_store.get(&pname).cloned().unwrap_or(default) as usize
+    }
+
+    /// Returns the number of bytes read or written by a pixel transfer, according to the
+    /// pixel storage parameters.
+    fn image_size(&self, pack: bool, width: GLsizei, height: GLsizei, depth: GLsizei,
+                  format: GLenum, ty: GLenum) -> usize
+    {
+        if width <= 0 || height <= 0 || depth <= 0 {
+            return 0;
+        }
+
+        let (width, height, depth) = (width as usize, height as usize, depth as usize);
+
+        let (alignment, row_length, image_height) = if pack {
+            (self.pixel_store(gl::PACK_ALIGNMENT), self.pixel_store(gl::PACK_ROW_LENGTH), 0)
+        } else {
+            (self.pixel_store(gl::UNPACK_ALIGNMENT), self.pixel_store(gl::UNPACK_ROW_LENGTH),
+             self.pixel_store(gl::UNPACK_IMAGE_HEIGHT))
+        };
+
+        let row_length = if row_length != 0 { row_length } else { width };
+        let image_height = if image_height != 0 { image_height } else { height };
+
+        let pixel_size = pixel_size(format, ty);
+        let row_size = (row_length * pixel_size + alignment - 1) / alignment * alignment;
+
+        row_size * (image_height * (depth - 1) + height - 1) + width * pixel_size
+    }
+}
+
+/// Makes the state the one used by the functions of this module in this thread.
+#[inline]
+pub fn make_current(state: &Rc<RefCell<State>>) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(Rc::downgrade(state)));
+}
+
+/// Returns true if the state is the one used by the functions of this module in this thread.
+#[inline]
+pub fn is_current(state: &Rc<RefCell<State>>) -> bool {
+    CURRENT.with(|current| {
+        match current.borrow().as_ref().and_then(|current| current.upgrade()) {
+            Some(current) => Rc::ptr_eq(&current, state),
+            None => false,
+        }
+    })
+}
+
+/// Calls a function with the state of the current context.
+fn with<R, F>(f: F) -> R where F: FnOnce(&mut State) -> R {
+    CURRENT.with(|current| {
+        let state = current.borrow().as_ref().and_then(|current| current.upgrade())
+                           .expect("No WebGL context is current");
+        let mut state = state.borrow_mut();
+        f(&mut state)
+    })
+}
+
+/// Returns the number of bytes of a pixel.
+fn pixel_size(format: GLenum, ty: GLenum) -> usize {
+    match ty {
+        gl::UNSIGNED_SHORT_5_6_5 | gl::UNSIGNED_SHORT_4_4_4_4 | gl::UNSIGNED_SHORT_5_5_5_1 => 2,
+        gl::UNSIGNED_INT_2_10_10_10_REV | gl::UNSIGNED_INT_10F_11F_11F_REV |
+        gl::UNSIGNED_INT_5_9_9_9_REV | gl::UNSIGNED_INT_24_8 => 4,
+        gl::FLOAT_32_UNSIGNED_INT_24_8_REV => 8,
+        _ => {
+            let components = match format {
+                gl::RG | gl::RG_INTEGER => 2,
+                gl::RGB | gl::RGB_INTEGER => 3,
+                gl::RGBA | gl::RGBA_INTEGER => 4,
+                _ => 1,
+            };
+
+            let component_size = match ty {
+                gl::SHORT | gl::UNSIGNED_SHORT | gl::HALF_FLOAT => 2,
+                gl::INT | gl::UNSIGNED_INT | gl::FLOAT => 4,
+                _ => 1,
+            };
+
+            components * component_size
+        },
+    }
+}
+
+/// Builds a typed array that points to the memory of the client. WebGL requires the type of the
+/// array to match the type of the pixels.
+///
+/// The array must not be used after the memory of the WebAssembly module has grown.
+unsafe fn array_view(ty: GLenum, data: *const c_void, size: usize) -> js_sys::Object {
+    match ty {
+        gl::BYTE => js_sys::Int8Array::view(slice::from_raw_parts(data as *const i8, size)).into(),
+        gl::SHORT => {
+            js_sys::Int16Array::view(slice::from_raw_parts(data as *const i16, size / 2)).into()
+        },
+        gl::UNSIGNED_SHORT | gl::HALF_FLOAT | gl::UNSIGNED_SHORT_5_6_5 |
+        gl::UNSIGNED_SHORT_4_4_4_4 | gl::UNSIGNED_SHORT_5_5_5_1 => {
+            js_sys::Uint16Array::view(slice::from_raw_parts(data as *const u16, size / 2)).into()
+        },
+        gl::INT => {
+            js_sys::Int32Array::view(slice::from_raw_parts(data as *const i32, size / 4)).into()
+        },
+        gl::UNSIGNED_INT | gl::UNSIGNED_INT_2_10_10_10_REV | gl::UNSIGNED_INT_10F_11F_11F_REV |
+        gl::UNSIGNED_INT_5_9_9_9_REV | gl::UNSIGNED_INT_24_8 => {
+            js_sys::Uint32Array::view(slice::from_raw_parts(data as *const u32, size / 4)).into()
+        },
+        gl::FLOAT => {
+            js_sys::Float32Array::view(slice::from_raw_parts(data as *const f32, size / 4)).into()
+        },
+        _ => js_sys::Uint8Array::view(slice::from_raw_parts(data as *const u8, size)).into(),
+    }
+}
+
+/// Converts a value returned by WebGL to a list of numbers. Booleans are converted to 0 or 1.
+fn to_numbers(value: &JsValue) -> Vec<f64> {
+    if let Some(value) = value.as_bool() {
+        return vec![if value { 1.0 } else { 0.0 }];
+    }
+
+    if let Some(value) = value.as_f64() {
+        return vec![value];
+    }
+
+    if value.is_null() || value.is_undefined() || !value.is_object() {
+        return Vec::new();
+    }
+
+    // arrays and typed arrays
+    js_sys::Array::from(value).iter().flat_map(|v| to_numbers(&v)).collect()
+}
+
+#[inline]
+fn to_int(value: f64) -> GLint {
+    if value >= GLint::max_value() as f64 {
+        GLint::max_value()
+    } else if value <= GLint::min_value() as f64 {
+        GLint::min_value()
+    } else {
+        value as GLint
+    }
+}
+
+/// Writes a value returned by WebGL to the output of a `glGet*` function.
+unsafe fn write_ints(value: &JsValue, max: usize, params: *mut GLint) {
+    let values = to_numbers(value);
+    if values.is_empty() {
+        *params = 0;
+    }
+
+    for (i, value) in values.into_iter().take(max).enumerate() {
+        *params.offset(i as isize) = to_int(value);
+    }
+}
+
+#[inline]
+unsafe fn read_string(string: *const GLchar) -> String {
+    CStr::from_ptr(string).to_string_lossy().into_owned()
+}
+
+/// Writes a string to the output of a function like `glGetShaderInfoLog`.
+unsafe fn write_string(value: &str, buf_size: GLsizei, length: *mut GLsizei, out: *mut GLchar) {
+    let len = if buf_size > 0 && !out.is_null() {
+        let len = cmp::min(value.len(), buf_size as usize - 1);
+        ptr::copy_nonoverlapping(value.as_ptr() as *const GLchar, out, len);
+        *out.offset(len as isize) = 0;
+        len
+    } else {
+        0
+    };
+
+    if !length.is_null() {
+        *length = len as GLsizei;
+    }
+}
+
+/// Returns the length of a string including the terminating zero, or 0 for an empty string,
+/// as returned by the `*_LENGTH` queries.
+#[inline]
+fn string_length(value: &str) -> GLint {
+    if value.is_empty() { 0 } else { value.len() as GLint + 1 }
+}
+
+macro_rules! objects {
+    ($gen:ident, $delete:ident, $field:ident, $create:ident, $destroy:ident) => (
+        extern "system" fn $gen(n: GLsizei, names: *mut GLuint) {
+            with(|s| {
+                for i in 0 .. n as isize {
+                    let name = match s.gl.$create() {
+                        Some(object) => s.$field.insert(object),
+                        None => 0,
+                    };
+
+                    unsafe { *names.offset(i) = name; }
+                }
+            })
+        }
+
+        extern "system" fn $delete(n: GLsizei, names: *const GLuint) {
+            with(|s| {
+                for i in 0 .. n as isize {
+                    let name = unsafe { *names.offset(i) };
+                    if let Some(object) = s.$field.remove(name) {
+                        s.gl.$destroy(Some(&object));
+                    }
+                }
+            })
+        }
+    )
+}
+
+objects!(GenBuffers, DeleteBuffers, buffers, create_buffer, delete_buffer);
+objects!(GenFramebuffers, DeleteFramebuffers, framebuffers, create_framebuffer,
+         delete_framebuffer);
+objects!(GenQueries, DeleteQueries, queries, create_query, delete_query);
+objects!(GenRenderbuffers, DeleteRenderbuffers, renderbuffers, create_renderbuffer,
+         delete_renderbuffer);
+objects!(GenSamplers, DeleteSamplers, samplers, create_sampler, delete_sampler);
+objects!(GenTextures, DeleteTextures, textures, create_texture, delete_texture);
+objects!(GenVertexArrays, DeleteVertexArrays, vertex_arrays, create_vertex_array,
+         delete_vertex_array);
+
+// state
+
+extern "system" fn ActiveTexture(texture: GLenum) {
+    with(|s| s.gl.active_texture(texture))
+}
+
+extern "system" fn BlendColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+    with(|s| s.gl.blend_color(red, green, blue, alpha))
+}
+
+extern "system" fn BlendEquationSeparate(mode_rgb: GLenum, mode_alpha: GLenum) {
+    with(|s| s.gl.blend_equation_separate(mode_rgb, mode_alpha))
+}
+
+extern "system" fn BlendFuncSeparate(src_rgb: GLenum, dst_rgb: GLenum, src_alpha: GLenum,
+                                     dst_alpha: GLenum)
+{
+    with(|s| s.gl.blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha))
+}
+
+extern "system" fn ColorMask(red: GLboolean, green: GLboolean, blue: GLboolean,
+                             alpha: GLboolean)
+{
+    with(|s| s.gl.color_mask(red != 0, green != 0, blue != 0, alpha != 0))
+}
+
+extern "system" fn CullFace(mode: GLenum) {
+    with(|s| s.gl.cull_face(mode))
+}
+
+extern "system" fn DepthFunc(func: GLenum) {
+    with(|s| s.gl.depth_func(func))
+}
+
+extern "system" fn DepthMask(flag: GLboolean) {
+    with(|s| s.gl.depth_mask(flag != 0))
+}
+
+extern "system" fn DepthRange(near: GLdouble, far: GLdouble) {
+    with(|s| s.gl.depth_range(near as GLfloat, far as GLfloat))
+}
+
+extern "system" fn DepthRangef(near: GLfloat, far: GLfloat) {
+    with(|s| s.gl.depth_range(near, far))
+}
+
+extern "system" fn Disable(cap: GLenum) {
+    with(|s| s.gl.disable(cap))
+}
+
+extern "system" fn Enable(cap: GLenum) {
+    with(|s| s.gl.enable(cap))
+}
+
+extern "system" fn Finish() {
+    with(|s| s.gl.finish())
+}
+
+extern "system" fn Flush() {
+    with(|s| s.gl.flush())
+}
+
+extern "system" fn FrontFace(mode: GLenum) {
+    with(|s| s.gl.front_face(mode))
+}
+
+extern "system" fn Hint(target: GLenum, mode: GLenum) {
+    with(|s| s.gl.hint(target, mode))
+}
+
+extern "system" fn LineWidth(width: GLfloat) {
+    with(|s| s.gl.line_width(width))
+}
+
+extern "system" fn PixelStorei(pname: GLenum, param: GLint) {
+    with(|s| {
+        s.pixel_store.insert(pname, param);
+        s.gl.pixel_storei(pname, param);
+    })
+}
+
+extern "system" fn PolygonOffset(factor: GLfloat, units: GLfloat) {
+    with(|s| s.gl.polygon_offset(factor, units))
+}
+
+extern "system" fn Scissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    with(|s| s.gl.scissor(x, y, width, height))
+}
+
+extern "system" fn StencilFunc(func: GLenum, reference: GLint, mask: GLuint) {
+    with(|s| s.gl.stencil_func(func, reference, mask))
+}
+
+extern "system" fn StencilFuncSeparate(face: GLenum, func: GLenum, reference: GLint,
+                                       mask: GLuint)
+{
+    with(|s| s.gl.stencil_func_separate(face, func, reference, mask))
+}
+
+extern "system" fn StencilMask(mask: GLuint) {
+    with(|s| s.gl.stencil_mask(mask))
+}
+
+extern "system" fn StencilMaskSeparate(face: GLenum, mask: GLuint) {
+    with(|s| s.gl.stencil_mask_separate(face, mask))
+}
+
+extern "system" fn StencilOp(fail: GLenum, zfail: GLenum, zpass: GLenum) {
+    with(|s| s.gl.stencil_op(fail, zfail, zpass))
+}
+
+extern "system" fn StencilOpSeparate(face: GLenum, fail: GLenum, zfail: GLenum, zpass: GLenum) {
+    with(|s| s.gl.stencil_op_separate(face, fail, zfail, zpass))
+}
+
+extern "system" fn Viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+    with(|s| s.gl.viewport(x, y, width, height))
+}
+
+// queries of the state
+
+extern "system" fn GetError() -> GLenum {
+    with(|s| {
+        if s.error != gl::NO_ERROR {
+            let error = s.error;
+            s.error = gl::NO_ERROR;
+            return error;
+        }
+
+        s.gl.get_error()
+    })
+}
+
+extern "system" fn GetBooleanv(pname: GLenum, data: *mut GLboolean) {
+    with(|s| {
+        // WebGL always has a shader compiler
+        if pname == gl::SHADER_COMPILER {
+            unsafe { *data = gl::TRUE; }
+            return;
+        }
+
+        let values = to_numbers(&s.get_parameter(pname));
+        if values.is_empty() {
+            unsafe { *data = gl::FALSE; }
+        }
+
+        for (i, value) in values.into_iter().enumerate() {
+            unsafe { *data.offset(i as isize) = if value != 0.0 { gl::TRUE } else { gl::FALSE }; }
+        }
+    })
+}
+
+extern "system" fn GetFloatv(pname: GLenum, data: *mut GLfloat) {
+    with(|s| {
+        let values = to_numbers(&s.get_parameter(pname));
+        if values.is_empty() {
+            unsafe { *data = 0.0; }
+        }
+
+        for (i, value) in values.into_iter().enumerate() {
+            unsafe { *data.offset(i as isize) = value as GLfloat; }
+        }
+    })
+}
+
+extern "system" fn GetIntegerv(pname: GLenum, data: *mut GLint) {
+    with(|s| {
+        if pname == gl::NUM_EXTENSIONS {
+            unsafe { *data = s.extensions.len() as GLint; }
+            return;
+        }
+
+        let value = s.get_parameter(pname);
+        unsafe { write_ints(&value, usize::max_value(), data); }
+    })
+}
+
+extern "system" fn GetString(name: GLenum) -> *const GLubyte {
+    with(|s| {
+        if !s.strings.contains_key(&name) {
+            let value = if name == gl::EXTENSIONS {
+                let names: Vec<_> = s.extensions.iter().map(|e| e.to_string_lossy()).collect();
+                names.join(" ")
+            } else {
+                match s.get_parameter(name).as_string() {
+                    Some(value) => value,
+                    None => return ptr::null(),
+                }
+            };
+
+            s.strings.insert(name, CString::new(value).unwrap());
+        }
+
+        s.strings[&name].as_ptr() as *const GLubyte
+    })
+}
+
+extern "system" fn GetStringi(name: GLenum, index: GLuint) -> *const GLubyte {
+    with(|s| {
+        match (name, s.extensions.get(index as usize)) {
+            (gl::EXTENSIONS, Some(extension)) => extension.as_ptr() as *const GLubyte,
+            _ => {
+                s.set_error(gl::INVALID_VALUE);
+                ptr::null()
+            },
+        }
+    })
+}
+
+extern "system" fn GetInternalformativ(target: GLenum, internalformat: GLenum, pname: GLenum,
+                                       buf_size: GLsizei, params: *mut GLint)
+{
+    with(|s| {
+        // WebGL only knows `GL_SAMPLES`
+        let samples = s.gl.get_internalformat_parameter(target, internalformat, gl::SAMPLES)
+                          .unwrap_or(JsValue::NULL);
+        let samples = to_numbers(&samples);
+
+        match pname {
+            gl::NUM_SAMPLE_COUNTS => unsafe { *params = samples.len() as GLint },
+            gl::SAMPLES => {
+                for (i, value) in samples.into_iter().take(buf_size as usize).enumerate() {
+                    unsafe { *params.offset(i as isize) = value as GLint; }
+                }
+            },
+            _ => s.set_error(gl::INVALID_ENUM),
+        }
+    })
+}
+
+// buffers
+
+extern "system" fn BindBuffer(target: GLenum, buffer: GLuint) {
+    with(|s| {
+        match target {
+            gl::PIXEL_PACK_BUFFER => s.pixel_pack_buffer = buffer,
+            gl::PIXEL_UNPACK_BUFFER => s.pixel_unpack_buffer = buffer,
+            _ => ()
+        }
+
+        s.gl.bind_buffer(target, s.buffers.get(buffer))
+    })
+}
+
+extern "system" fn BindBufferRange(target: GLenum, index: GLuint, buffer: GLuint,
+                                   offset: GLintptr, size: GLsizeiptr)
+{
+    with(|s| {
+        s.gl.bind_buffer_range_with_i32_and_i32(target, index, s.buffers.get(buffer),
+                                                offset as i32, size as i32)
+    })
+}
+
+extern "system" fn BufferData(target: GLenum, size: GLsizeiptr, data: *const c_void,
+                              usage: GLenum)
+{
+    with(|s| {
+        if data.is_null() {
+            s.gl.buffer_data_with_i32(target, size as i32, usage);
+        } else {
+            let data = unsafe { array_view(gl::UNSIGNED_BYTE, data, size as usize) };
+            s.gl.buffer_data_with_array_buffer_view(target, &data, usage);
+        }
+    })
+}
+
+extern "system" fn BufferSubData(target: GLenum, offset: GLintptr, size: GLsizeiptr,
+                                 data: *const c_void)
+{
+    with(|s| {
+        let data = unsafe { array_view(gl::UNSIGNED_BYTE, data, size as usize) };
+        s.gl.buffer_sub_data_with_i32_and_array_buffer_view(target, offset as i32, &data);
+    })
+}
+
+extern "system" fn CopyBufferSubData(read_target: GLenum, write_target: GLenum,
+                                     read_offset: GLintptr, write_offset: GLintptr,
+                                     size: GLsizeiptr)
+{
+    with(|s| {
+        s.gl.copy_buffer_sub_data_with_i32_and_i32_and_i32(read_target, write_target,
+                                                           read_offset as i32,
+                                                           write_offset as i32, size as i32)
+    })
+}
+
+extern "system" fn GetBufferParameteriv(target: GLenum, pname: GLenum, params: *mut GLint) {
+    with(|s| {
+        let value = s.gl.get_buffer_parameter(target, pname);
+        unsafe { write_ints(&value, 1, params); }
+    })
+}
+
+// WebGL can't map buffers, so the content is copied to a temporary buffer instead
+extern "system" fn MapBufferRange(target: GLenum, offset: GLintptr, length: GLsizeiptr,
+                                  access: GLbitfield) -> *mut c_void
+{
+    with(|s| {
+        if s.mappings.contains_key(&target) || access & gl::MAP_PERSISTENT_BIT != 0 {
+            s.set_error(gl::INVALID_OPERATION);
+            return ptr::null_mut();
+        }
+
+        let mut data = vec![0u8; length as usize];
+
+        if access & gl::MAP_READ_BIT != 0 {
+            let view = unsafe { array_view(gl::UNSIGNED_BYTE, data.as_ptr() as *const _,
+                                           data.len()) };
+            s.gl.get_buffer_sub_data_with_i32_and_array_buffer_view(target, offset as i32,
+                                                                    &view);
+        }
+
+        let ptr = data.as_mut_ptr();
+        s.mappings.insert(target, Mapping {
+            offset: offset,
+            access: access,
+            data: data,
+        });
+
+        ptr as *mut c_void
+    })
+}
+
+extern "system" fn FlushMappedBufferRange(target: GLenum, offset: GLintptr, length: GLsizeiptr) {
+    with(|s| {
+        let view = match s.mappings.get(&target) {
+            Some(mapping) => {
+                let data = &mapping.data[offset as usize .. (offset + length) as usize];
+                unsafe { array_view(gl::UNSIGNED_BYTE, data.as_ptr() as *const _, data.len()) }
+            },
+            None => {
+                s.set_error(gl::INVALID_OPERATION);
+                return;
+            },
+        };
+
+        let offset = s.mappings[&target].offset + offset;
+        s.gl.buffer_sub_data_with_i32_and_array_buffer_view(target, offset as i32, &view);
+    })
+}
+
+extern "system" fn UnmapBuffer(target: GLenum) -> GLboolean {
+    with(|s| {
+        let mapping = match s.mappings.remove(&target) {
+            Some(mapping) => mapping,
+            None => {
+                s.set_error(gl::INVALID_OPERATION);
+                return gl::FALSE;
+            },
+        };
+
+        if mapping.access & gl::MAP_WRITE_BIT != 0 &&
+           mapping.access & gl::MAP_FLUSH_EXPLICIT_BIT == 0
+        {
+            let view = unsafe { array_view(gl::UNSIGNED_BYTE, mapping.data.as_ptr() as *const _,
+                                           mapping.data.len()) };
+            s.gl.buffer_sub_data_with_i32_and_array_buffer_view(target, mapping.offset as i32,
+                                                                &view);
+        }
+
+        gl::TRUE
+    })
+}
+
+// vertex arrays and draw commands
+
+extern "system" fn BindVertexArray(array: GLuint) {
+    with(|s| s.gl.bind_vertex_array(s.vertex_arrays.get(array)))
+}
+
+extern "system" fn DisableVertexAttribArray(index: GLuint) {
+    with(|s| s.gl.disable_vertex_attrib_array(index))
+}
+
+extern "system" fn EnableVertexAttribArray(index: GLuint) {
+    with(|s| s.gl.enable_vertex_attrib_array(index))
+}
+
+extern "system" fn VertexAttribDivisor(index: GLuint, divisor: GLuint) {
+    with(|s| s.gl.vertex_attrib_divisor(index, divisor))
+}
+
+extern "system" fn VertexAttribIPointer(index: GLuint, size: GLint, ty: GLenum,
+                                        stride: GLsizei, pointer: *const c_void)
+{
+    with(|s| s.gl.vertex_attrib_i_pointer_with_i32(index, size, ty, stride, pointer as i32))
+}
+
+extern "system" fn VertexAttribPointer(index: GLuint, size: GLint, ty: GLenum,
+                                       normalized: GLboolean, stride: GLsizei,
+                                       pointer: *const c_void)
+{
+    with(|s| {
+        s.gl.vertex_attrib_pointer_with_i32(index, size, ty, normalized != 0, stride,
+                                            pointer as i32)
+    })
+}
+
+extern "system" fn DrawArrays(mode: GLenum, first: GLint, count: GLsizei) {
+    with(|s| s.gl.draw_arrays(mode, first, count))
+}
+
+extern "system" fn DrawArraysInstanced(mode: GLenum, first: GLint, count: GLsizei,
+                                       instances: GLsizei)
+{
+    with(|s| s.gl.draw_arrays_instanced(mode, first, count, instances))
+}
+
+extern "system" fn DrawElements(mode: GLenum, count: GLsizei, ty: GLenum,
+                                indices: *const c_void)
+{
+    with(|s| s.gl.draw_elements_with_i32(mode, count, ty, indices as i32))
+}
+
+extern "system" fn DrawElementsInstanced(mode: GLenum, count: GLsizei, ty: GLenum,
+                                         indices: *const c_void, instances: GLsizei)
+{
+    with(|s| s.gl.draw_elements_instanced_with_i32(mode, count, ty, indices as i32, instances))
+}
+
+extern "system" fn BeginTransformFeedback(mode: GLenum) {
+    with(|s| s.gl.begin_transform_feedback(mode))
+}
+
+extern "system" fn EndTransformFeedback() {
+    with(|s| s.gl.end_transform_feedback())
+}
+
+// framebuffers
+
+extern "system" fn BindFramebuffer(target: GLenum, framebuffer: GLuint) {
+    with(|s| s.gl.bind_framebuffer(target, s.framebuffers.get(framebuffer)))
+}
+
+extern "system" fn BindRenderbuffer(target: GLenum, renderbuffer: GLuint) {
+    with(|s| s.gl.bind_renderbuffer(target, s.renderbuffers.get(renderbuffer)))
+}
+
+extern "system" fn BlitFramebuffer(src_x0: GLint, src_y0: GLint, src_x1: GLint, src_y1: GLint,
+                                   dst_x0: GLint, dst_y0: GLint, dst_x1: GLint, dst_y1: GLint,
+                                   mask: GLbitfield, filter: GLenum)
+{
+    with(|s| {
+        s.gl.blit_framebuffer(src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1,
+                              mask, filter)
+    })
+}
+
+extern "system" fn CheckFramebufferStatus(target: GLenum) -> GLenum {
+    with(|s| s.gl.check_framebuffer_status(target))
+}
+
+extern "system" fn Clear(mask: GLbitfield) {
+    with(|s| s.gl.clear(mask))
+}
+
+extern "system" fn ClearBufferfv(buffer: GLenum, draw_buffer: GLint, value: *const GLfloat) {
+    with(|s| {
+        let len = if buffer == gl::COLOR { 4 } else { 1 };
+        let value = unsafe { slice::from_raw_parts(value, len) };
+        s.gl.clear_bufferfv_with_f32_array(buffer, draw_buffer, value)
+    })
+}
+
+extern "system" fn ClearBufferiv(buffer: GLenum, draw_buffer: GLint, value: *const GLint) {
+    with(|s| {
+        let len = if buffer == gl::COLOR { 4 } else { 1 };
+        let value = unsafe { slice::from_raw_parts(value, len) };
+        s.gl.clear_bufferiv_with_i32_array(buffer, draw_buffer, value)
+    })
+}
+
+extern "system" fn ClearBufferuiv(buffer: GLenum, draw_buffer: GLint, value: *const GLuint) {
+    with(|s| {
+        let value = unsafe { slice::from_raw_parts(value, 4) };
+        s.gl.clear_bufferuiv_with_u32_array(buffer, draw_buffer, value)
+    })
+}
+
+extern "system" fn ClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+    with(|s| s.gl.clear_color(red, green, blue, alpha))
+}
+
+extern "system" fn ClearDepth(depth: GLdouble) {
+    with(|s| s.gl.clear_depth(depth as GLfloat))
+}
+
+extern "system" fn ClearDepthf(depth: GLfloat) {
+    with(|s| s.gl.clear_depth(depth))
+}
+
+extern "system" fn ClearStencil(stencil: GLint) {
+    with(|s| s.gl.clear_stencil(stencil))
+}
+
+extern "system" fn DrawBuffers(n: GLsizei, buffers: *const GLenum) {
+    with(|s| {
+        let buffers = unsafe { slice::from_raw_parts(buffers, n as usize) };
+        let array: js_sys::Array = buffers.iter().map(|&b| JsValue::from(b)).collect();
+        s.gl.draw_buffers(&array)
+    })
+}
+
+extern "system" fn FramebufferRenderbuffer(target: GLenum, attachment: GLenum,
+                                           renderbuffer_target: GLenum, renderbuffer: GLuint)
+{
+    with(|s| {
+        s.gl.framebuffer_renderbuffer(target, attachment, renderbuffer_target,
+                                      s.renderbuffers.get(renderbuffer))
+    })
+}
+
+extern "system" fn FramebufferTexture2D(target: GLenum, attachment: GLenum, tex_target: GLenum,
+                                        texture: GLuint, level: GLint)
+{
+    with(|s| {
+        s.gl.framebuffer_texture_2d(target, attachment, tex_target, s.textures.get(texture),
+                                    level)
+    })
+}
+
+extern "system" fn FramebufferTextureLayer(target: GLenum, attachment: GLenum, texture: GLuint,
+                                           level: GLint, layer: GLint)
+{
+    with(|s| {
+        s.gl.framebuffer_texture_layer(target, attachment, s.textures.get(texture), level, layer)
+    })
+}
+
+extern "system" fn GetFramebufferAttachmentParameteriv(target: GLenum, attachment: GLenum,
+                                                       pname: GLenum, params: *mut GLint)
+{
+    with(|s| {
+        let value = s.gl.get_framebuffer_attachment_parameter(target, attachment, pname)
+                        .unwrap_or(JsValue::NULL);
+        unsafe { write_ints(&value, 1, params); }
+    })
+}
+
+extern "system" fn ReadBuffer(src: GLenum) {
+    with(|s| s.gl.read_buffer(src))
+}
+
+extern "system" fn ReadPixels(x: GLint, y: GLint, width: GLsizei, height: GLsizei,
+                              format: GLenum, ty: GLenum, pixels: *mut c_void)
+{
+    with(|s| {
+        let result = if s.pixel_pack_buffer != 0 {
+            s.gl.read_pixels_with_i32(x, y, width, height, format, ty, pixels as i32)
+        } else {
+            let size = s.image_size(true, width, height, 1, format, ty);
+            let view = unsafe { array_view(ty, pixels, size) };
+            s.gl.read_pixels_with_opt_array_buffer_view(x, y, width, height, format, ty,
+                                                        Some(&view))
+        };
+
+        if result.is_err() {
+            s.set_error(gl::INVALID_OPERATION);
+        }
+    })
+}
+
+extern "system" fn RenderbufferStorage(target: GLenum, internalformat: GLenum, width: GLsizei,
+                                       height: GLsizei)
+{
+    with(|s| s.gl.renderbuffer_storage(target, internalformat, width, height))
+}
+
+extern "system" fn RenderbufferStorageMultisample(target: GLenum, samples: GLsizei,
+                                                  internalformat: GLenum, width: GLsizei,
+                                                  height: GLsizei)
+{
+    with(|s| {
+        s.gl.renderbuffer_storage_multisample(target, samples, internalformat, width, height)
+    })
+}
+
+// textures and samplers
+
+extern "system" fn BindSampler(unit: GLuint, sampler: GLuint) {
+    with(|s| s.gl.bind_sampler(unit, s.samplers.get(sampler)))
+}
+
+extern "system" fn BindTexture(target: GLenum, texture: GLuint) {
+    with(|s| s.gl.bind_texture(target, s.textures.get(texture)))
+}
+
+extern "system" fn GenerateMipmap(target: GLenum) {
+    with(|s| s.gl.generate_mipmap(target))
+}
+
+extern "system" fn SamplerParameterf(sampler: GLuint, pname: GLenum, param: GLfloat) {
+    with(|s| {
+        if let Some(sampler) = s.samplers.get(sampler) {
+            s.gl.sampler_parameterf(sampler, pname, param);
+        }
+    })
+}
+
+extern "system" fn SamplerParameteri(sampler: GLuint, pname: GLenum, param: GLint) {
+    with(|s| {
+        if let Some(sampler) = s.samplers.get(sampler) {
+            s.gl.sampler_parameteri(sampler, pname, param);
+        }
+    })
+}
+
+extern "system" fn TexParameterf(target: GLenum, pname: GLenum, param: GLfloat) {
+    with(|s| s.gl.tex_parameterf(target, pname, param))
+}
+
+extern "system" fn TexParameteri(target: GLenum, pname: GLenum, param: GLint) {
+    with(|s| s.gl.tex_parameteri(target, pname, param))
+}
+
+extern "system" fn TexStorage2D(target: GLenum, levels: GLsizei, internalformat: GLenum,
+                                width: GLsizei, height: GLsizei)
+{
+    with(|s| s.gl.tex_storage_2d(target, levels, internalformat, width, height))
+}
+
+extern "system" fn TexStorage3D(target: GLenum, levels: GLsizei, internalformat: GLenum,
+                                width: GLsizei, height: GLsizei, depth: GLsizei)
+{
+    with(|s| s.gl.tex_storage_3d(target, levels, internalformat, width, height, depth))
+}
+
+extern "system" fn TexImage2D(target: GLenum, level: GLint, internalformat: GLint,
+                              width: GLsizei, height: GLsizei, border: GLint, format: GLenum,
+                              ty: GLenum, pixels: *const c_void)
+{
+    with(|s| {
+        let result = if s.pixel_unpack_buffer != 0 {
+            s.gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_i32(
+                target, level, internalformat, width, height, border, format, ty, pixels as i32)
+        } else if pixels.is_null() {
+            s.gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+                target, level, internalformat, width, height, border, format, ty, None)
+        } else {
+            let size = s.image_size(false, width, height, 1, format, ty);
+            let view = unsafe { array_view(ty, pixels, size) };
+            s.gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+                target, level, internalformat, width, height, border, format, ty, Some(&view))
+        };
+
+        if result.is_err() {
+            s.set_error(gl::INVALID_OPERATION);
+        }
+    })
+}
+
+extern "system" fn TexImage3D(target: GLenum, level: GLint, internalformat: GLint,
+                              width: GLsizei, height: GLsizei, depth: GLsizei, border: GLint,
+                              format: GLenum, ty: GLenum, pixels: *const c_void)
+{
+    with(|s| {
+        let result = if s.pixel_unpack_buffer != 0 {
+            s.gl.tex_image_3d_with_i32(target, level, internalformat, width, height, depth,
+                                       border, format, ty, pixels as i32)
+        } else if pixels.is_null() {
+            s.gl.tex_image_3d_with_opt_array_buffer_view(target, level, internalformat, width,
+                                                         height, depth, border, format, ty,
+                                                         None)
+        } else {
+            let size = s.image_size(false, width, height, depth, format, ty);
+            let view = unsafe { array_view(ty, pixels, size) };
+            s.gl.tex_image_3d_with_opt_array_buffer_view(target, level, internalformat, width,
+                                                         height, depth, border, format, ty,
+                                                         Some(&view))
+        };
+
+        if result.is_err() {
+            s.set_error(gl::INVALID_OPERATION);
+        }
+    })
+}
+
+extern "system" fn TexSubImage2D(target: GLenum, level: GLint, xoffset: GLint, yoffset: GLint,
+                                 width: GLsizei, height: GLsizei, format: GLenum, ty: GLenum,
+                                 pixels: *const c_void)
+{
+    with(|s| {
+        let result = if s.pixel_unpack_buffer != 0 {
+            s.gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_i32(
+                target, level, xoffset, yoffset, width, height, format, ty, pixels as i32)
+        } else {
+            let size = s.image_size(false, width, height, 1, format, ty);
+            let view = unsafe { array_view(ty, pixels, size) };
+            s.gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                target, level, xoffset, yoffset, width, height, format, ty, Some(&view))
+        };
+
+        if result.is_err() {
+            s.set_error(gl::INVALID_OPERATION);
+        }
+    })
+}
+
+extern "system" fn TexSubImage3D(target: GLenum, level: GLint, xoffset: GLint, yoffset: GLint,
+                                 zoffset: GLint, width: GLsizei, height: GLsizei, depth: GLsizei,
+                                 format: GLenum, ty: GLenum, pixels: *const c_void)
+{
+    with(|s| {
+        let result = if s.pixel_unpack_buffer != 0 {
+            s.gl.tex_sub_image_3d_with_i32(target, level, xoffset, yoffset, zoffset, width,
+                                           height, depth, format, ty, pixels as i32)
+        } else {
+            let size = s.image_size(false, width, height, depth, format, ty);
+            let view = unsafe { array_view(ty, pixels, size) };
+            s.gl.tex_sub_image_3d_with_opt_array_buffer_view(target, level, xoffset, yoffset,
+                                                             zoffset, width, height, depth,
+                                                             format, ty, Some(&view))
+        };
+
+        if result.is_err() {
+            s.set_error(gl::INVALID_OPERATION);
+        }
+    })
+}
+
+extern "system" fn CompressedTexImage2D(target: GLenum, level: GLint, internalformat: GLenum,
+                                        width: GLsizei, height: GLsizei, border: GLint,
+                                        image_size: GLsizei, data: *const c_void)
+{
+    with(|s| {
+        let view = unsafe { array_view(gl::UNSIGNED_BYTE, data, image_size as usize) };
+        s.gl.compressed_tex_image_2d_with_array_buffer_view(target, level, internalformat,
+                                                            width, height, border, &view)
+    })
+}
+
+extern "system" fn CompressedTexImage3D(target: GLenum, level: GLint, internalformat: GLenum,
+                                        width: GLsizei, height: GLsizei, depth: GLsizei,
+                                        border: GLint, image_size: GLsizei,
+                                        data: *const c_void)
+{
+    with(|s| {
+        let view = unsafe { array_view(gl::UNSIGNED_BYTE, data, image_size as usize) };
+        s.gl.compressed_tex_image_3d_with_array_buffer_view(target, level, internalformat,
+                                                            width, height, depth, border, &view)
+    })
+}
+
+extern "system" fn CompressedTexSubImage2D(target: GLenum, level: GLint, xoffset: GLint,
+                                           yoffset: GLint, width: GLsizei, height: GLsizei,
+                                           format: GLenum, image_size: GLsizei,
+                                           data: *const c_void)
+{
+    with(|s| {
+        let view = unsafe { array_view(gl::UNSIGNED_BYTE, data, image_size as usize) };
+        s.gl.compressed_tex_sub_image_2d_with_array_buffer_view(target, level, xoffset,
+                                                                yoffset, width, height, format,
+                                                                &view)
+    })
+}
+
+extern "system" fn CompressedTexSubImage3D(target: GLenum, level: GLint, xoffset: GLint,
+                                           yoffset: GLint, zoffset: GLint, width: GLsizei,
+                                           height: GLsizei, depth: GLsizei, format: GLenum,
+                                           image_size: GLsizei, data: *const c_void)
+{
+    with(|s| {
+        let view = unsafe { array_view(gl::UNSIGNED_BYTE, data, image_size as usize) };
+        s.gl.compressed_tex_sub_image_3d_with_array_buffer_view(target, level, xoffset,
+                                                                yoffset, zoffset, width, height,
+                                                                depth, format, &view)
+    })
+}
+
+// shaders and programs
+
+extern "system" fn AttachShader(program: GLuint, shader: GLuint) {
+    with(|s| {
+        if let (Some(program), Some(shader)) = (s.programs.get(program), s.shaders.get(shader)) {
+            s.gl.attach_shader(program, shader);
+        }
+    })
+}
+
+extern "system" fn CompileShader(shader: GLuint) {
+    with(|s| {
+        if let Some(shader) = s.shaders.get(shader) {
+            s.gl.compile_shader(shader);
+        }
+    })
+}
+
+extern "system" fn CreateProgram() -> GLuint {
+    with(|s| {
+        match s.gl.create_program() {
+            Some(program) => s.programs.insert(program),
+            None => 0,
+        }
+    })
+}
+
+extern "system" fn CreateShader(ty: GLenum) -> GLuint {
+    with(|s| {
+        match s.gl.create_shader(ty) {
+            Some(shader) => s.shaders.insert(shader),
+            None => 0,
+        }
+    })
+}
+
+extern "system" fn DeleteProgram(program: GLuint) {
+    with(|s| {
+        s.uniform_locations.remove(&program);
+        if let Some(object) = s.programs.remove(program) {
+            s.gl.delete_program(Some(&object));
+        }
+    })
+}
+
+extern "system" fn DeleteShader(shader: GLuint) {
+    with(|s| {
+        if let Some(object) = s.shaders.remove(shader) {
+            s.gl.delete_shader(Some(&object));
+        }
+    })
+}
+
+extern "system" fn LinkProgram(program: GLuint) {
+    with(|s| {
+        // the locations are invalidated by linking
+        s.uniform_locations.remove(&program);
+        if let Some(program) = s.programs.get(program) {
+            s.gl.link_program(program);
+        }
+    })
+}
+
+extern "system" fn ShaderSource(shader: GLuint, count: GLsizei, strings: *const *const GLchar,
+                                lengths: *const GLint)
+{
+    with(|s| {
+        let mut source = String::new();
+
+        for i in 0 .. count as isize {
+            unsafe {
+                let string = *strings.offset(i);
+                if lengths.is_null() || *lengths.offset(i) < 0 {
+                    source.push_str(&read_string(string));
+                } else {
+                    let bytes = slice::from_raw_parts(string as *const u8,
+                                                      *lengths.offset(i) as usize);
+                    source.push_str(&String::from_utf8_lossy(bytes));
+                }
+            }
+        }
+
+        if let Some(shader) = s.shaders.get(shader) {
+            s.gl.shader_source(shader, &source);
+        }
+    })
+}
+
+extern "system" fn UseProgram(program: GLuint) {
+    with(|s| {
+        s.current_program = program;
+        s.gl.use_program(s.programs.get(program))
+    })
+}
+
+extern "system" fn TransformFeedbackVaryings(program: GLuint, count: GLsizei,
+                                             varyings: *const *const GLchar, buffer_mode: GLenum)
+{
+    with(|s| {
+        let array: js_sys::Array = (0 .. count as isize)
+            .map(|i| JsValue::from(unsafe { read_string(*varyings.offset(i)) }))
+            .collect();
+
+        if let Some(program) = s.programs.get(program) {
+            s.gl.transform_feedback_varyings(program, &array, buffer_mode);
+        }
+    })
+}
+
+extern "system" fn UniformBlockBinding(program: GLuint, index: GLuint, binding: GLuint) {
+    with(|s| {
+        if let Some(program) = s.programs.get(program) {
+            s.gl.uniform_block_binding(program, index, binding);
+        }
+    })
+}
+
+// reflection
+
+extern "system" fn GetProgramiv(program: GLuint, pname: GLenum, params: *mut GLint) {
+    with(|s| {
+        let program = match s.programs.get(program) {
+            Some(program) => program,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        // WebGL doesn't have the length queries, as it returns strings
+        let value = match pname {
+            gl::INFO_LOG_LENGTH => {
+                string_length(&s.gl.get_program_info_log(program).unwrap_or(String::new()))
+            },
+            gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH => {
+                let count = to_numbers(&s.gl.get_program_parameter(program,
+                                                                   gl::ACTIVE_UNIFORM_BLOCKS));
+                let count = count.get(0).cloned().unwrap_or(0.0) as GLuint;
+                (0 .. count).filter_map(|i| s.gl.get_active_uniform_block_name(program, i))
+                            .map(|name| string_length(&name)).max().unwrap_or(0)
+            },
+            gl::TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH => {
+                let count = to_numbers(&s.gl.get_program_parameter(
+                                                program, gl::TRANSFORM_FEEDBACK_VARYINGS));
+                let count = count.get(0).cloned().unwrap_or(0.0) as GLuint;
+                (0 .. count).filter_map(|i| s.gl.get_transform_feedback_varying(program, i))
+                            .map(|info| string_length(&info.name())).max().unwrap_or(0)
+            },
+            _ => {
+                let value = s.gl.get_program_parameter(program, pname);
+                unsafe { write_ints(&value, 1, params); }
+                return;
+            },
+        };
+
+        unsafe { *params = value; }
+    })
+}
+
+extern "system" fn GetProgramInfoLog(program: GLuint, buf_size: GLsizei, length: *mut GLsizei,
+                                     info_log: *mut GLchar)
+{
+    with(|s| {
+        let log = s.programs.get(program).and_then(|p| s.gl.get_program_info_log(p))
+                           .unwrap_or(String::new());
+        unsafe { write_string(&log, buf_size, length, info_log); }
+    })
+}
+
+extern "system" fn GetShaderiv(shader: GLuint, pname: GLenum, params: *mut GLint) {
+    with(|s| {
+        let shader = match s.shaders.get(shader) {
+            Some(shader) => shader,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        match pname {
+            gl::INFO_LOG_LENGTH => {
+                let log = s.gl.get_shader_info_log(shader).unwrap_or(String::new());
+                unsafe { *params = string_length(&log); }
+            },
+            gl::SHADER_SOURCE_LENGTH => {
+                let source = s.gl.get_shader_source(shader).unwrap_or(String::new());
+                unsafe { *params = string_length(&source); }
+            },
+            _ => {
+                let value = s.gl.get_shader_parameter(shader, pname);
+                unsafe { write_ints(&value, 1, params); }
+            },
+        }
+    })
+}
+
+extern "system" fn GetShaderInfoLog(shader: GLuint, buf_size: GLsizei, length: *mut GLsizei,
+                                    info_log: *mut GLchar)
+{
+    with(|s| {
+        let log = s.shaders.get(shader).and_then(|sh| s.gl.get_shader_info_log(sh))
+                          .unwrap_or(String::new());
+        unsafe { write_string(&log, buf_size, length, info_log); }
+    })
+}
+
+extern "system" fn GetActiveAttrib(program: GLuint, index: GLuint, buf_size: GLsizei,
+                                   length: *mut GLsizei, size: *mut GLint, ty: *mut GLenum,
+                                   name: *mut GLchar)
+{
+    with(|s| {
+        let info = match s.programs.get(program).and_then(|p| s.gl.get_active_attrib(p, index)) {
+            Some(info) => info,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        unsafe {
+            *size = info.size();
+            *ty = info.type_();
+            write_string(&info.name(), buf_size, length, name);
+        }
+    })
+}
+
+extern "system" fn GetActiveUniform(program: GLuint, index: GLuint, buf_size: GLsizei,
+                                    length: *mut GLsizei, size: *mut GLint, ty: *mut GLenum,
+                                    name: *mut GLchar)
+{
+    with(|s| {
+        let info = match s.programs.get(program).and_then(|p| s.gl.get_active_uniform(p, index)) {
+            Some(info) => info,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        unsafe {
+            *size = info.size();
+            *ty = info.type_();
+            write_string(&info.name(), buf_size, length, name);
+        }
+    })
+}
+
+extern "system" fn GetActiveUniformName(program: GLuint, index: GLuint, buf_size: GLsizei,
+                                        length: *mut GLsizei, name: *mut GLchar)
+{
+    with(|s| {
+        let info = match s.programs.get(program).and_then(|p| s.gl.get_active_uniform(p, index)) {
+            Some(info) => info,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        unsafe { write_string(&info.name(), buf_size, length, name); }
+    })
+}
+
+extern "system" fn GetActiveUniformsiv(program: GLuint, count: GLsizei, indices: *const GLuint,
+                                       pname: GLenum, params: *mut GLint)
+{
+    with(|s| {
+        let program = match s.programs.get(program) {
+            Some(program) => program,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        let indices = unsafe { slice::from_raw_parts(indices, count as usize) };
+
+        let values: Vec<GLint> = if pname == gl::UNIFORM_NAME_LENGTH {
+            indices.iter().map(|&index| {
+                s.gl.get_active_uniform(program, index)
+                    .map(|info| string_length(&info.name())).unwrap_or(0)
+            }).collect()
+        } else {
+            let array: js_sys::Array = indices.iter().map(|&i| JsValue::from(i)).collect();
+            let values = s.gl.get_active_uniforms(program, &array, pname);
+            to_numbers(&values).into_iter().map(to_int).collect()
+        };
+
+        for (i, value) in values.into_iter().take(count as usize).enumerate() {
+            unsafe { *params.offset(i as isize) = value; }
+        }
+    })
+}
+
+extern "system" fn GetActiveUniformBlockiv(program: GLuint, index: GLuint, pname: GLenum,
+                                           params: *mut GLint)
+{
+    with(|s| {
+        let program = match s.programs.get(program) {
+            Some(program) => program,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        if pname == gl::UNIFORM_BLOCK_NAME_LENGTH {
+            let name = s.gl.get_active_uniform_block_name(program, index).unwrap_or(String::new());
+            unsafe { *params = string_length(&name); }
+            return;
+        }
+
+        let value = s.gl.get_active_uniform_block_parameter(program, index, pname)
+                        .unwrap_or(JsValue::NULL);
+        unsafe { write_ints(&value, usize::max_value(), params); }
+    })
+}
+
+extern "system" fn GetActiveUniformBlockName(program: GLuint, index: GLuint, buf_size: GLsizei,
+                                             length: *mut GLsizei, name: *mut GLchar)
+{
+    with(|s| {
+        let block = s.programs.get(program)
+                              .and_then(|p| s.gl.get_active_uniform_block_name(p, index))
+                              .unwrap_or(String::new());
+        unsafe { write_string(&block, buf_size, length, name); }
+    })
+}
+
+extern "system" fn GetTransformFeedbackVarying(program: GLuint, index: GLuint,
+                                               buf_size: GLsizei, length: *mut GLsizei,
+                                               size: *mut GLsizei, ty: *mut GLenum,
+                                               name: *mut GLchar)
+{
+    with(|s| {
+        let info = match s.programs.get(program)
+                                   .and_then(|p| s.gl.get_transform_feedback_varying(p, index))
+        {
+            Some(info) => info,
+            None => {
+                s.set_error(gl::INVALID_VALUE);
+                return;
+            },
+        };
+
+        unsafe {
+            *size = info.size();
+            *ty = info.type_();
+            write_string(&info.name(), buf_size, length, name);
+        }
+    })
+}
+
+extern "system" fn GetAttribLocation(program: GLuint, name: *const GLchar) -> GLint {
+    with(|s| {
+        let name = unsafe { read_string(name) };
+        s.programs.get(program).map(|p| s.gl.get_attrib_location(p, &name)).unwrap_or(-1)
+    })
+}
+
+extern "system" fn GetFragDataLocation(program: GLuint, name: *const GLchar) -> GLint {
+    with(|s| {
+        let name = unsafe { read_string(name) };
+        s.programs.get(program).map(|p| s.gl.get_frag_data_location(p, &name)).unwrap_or(-1)
+    })
+}
+
+extern "system" fn GetUniformLocation(program: GLuint, name: *const GLchar) -> GLint {
+    with(|s| {
+        let name = unsafe { read_string(name) };
+
+        let location = match s.programs.get(program)
+                                       .and_then(|p| s.gl.get_uniform_location(p, &name))
+        {
+            Some(location) => location,
+            None => return -1,
+        };
+
+        let locations = s.uniform_locations.entry(program).or_insert_with(Vec::new);
+        locations.push(location);
+        locations.len() as GLint - 1
+    })
+}
+
+// uniforms
+
+extern "system" fn Uniform1f(location: GLint, v0: GLfloat) {
+    with(|s| s.gl.uniform1f(s.uniform_location(location), v0))
+}
+
+extern "system" fn Uniform1i(location: GLint, v0: GLint) {
+    with(|s| s.gl.uniform1i(s.uniform_location(location), v0))
+}
+
+extern "system" fn Uniform1ui(location: GLint, v0: GLuint) {
+    with(|s| s.gl.uniform1ui(s.uniform_location(location), v0))
+}
+
+macro_rules! uniform_vector {
+    ($name:ident, $ty:ty, $components:expr, $method:ident) => (
+        extern "system" fn $name(location: GLint, count: GLsizei, value: *const $ty) {
+            with(|s| {
+                let value = unsafe { slice::from_raw_parts(value, count as usize * $components) };
+                s.gl.$method(s.uniform_location(location), value)
+            })
+        }
+    )
+}
+
+uniform_vector!(Uniform2fv, GLfloat, 2, uniform2fv_with_f32_array);
+uniform_vector!(Uniform3fv, GLfloat, 3, uniform3fv_with_f32_array);
+uniform_vector!(Uniform4fv, GLfloat, 4, uniform4fv_with_f32_array);
+uniform_vector!(Uniform2iv, GLint, 2, uniform2iv_with_i32_array);
+uniform_vector!(Uniform3iv, GLint, 3, uniform3iv_with_i32_array);
+uniform_vector!(Uniform4iv, GLint, 4, uniform4iv_with_i32_array);
+uniform_vector!(Uniform2uiv, GLuint, 2, uniform2uiv_with_u32_array);
+uniform_vector!(Uniform3uiv, GLuint, 3, uniform3uiv_with_u32_array);
+uniform_vector!(Uniform4uiv, GLuint, 4, uniform4uiv_with_u32_array);
+
+macro_rules! uniform_matrix {
+    ($name:ident, $components:expr, $method:ident) => (
+        extern "system" fn $name(location: GLint, count: GLsizei, transpose: GLboolean,
+                                 value: *const GLfloat)
+        {
+            with(|s| {
+                let value = unsafe { slice::from_raw_parts(value, count as usize * $components) };
+                s.gl.$method(s.uniform_location(location), transpose != 0, value)
+            })
+        }
+    )
+}
+
+uniform_matrix!(UniformMatrix2fv, 4, uniform_matrix2fv_with_f32_array);
+uniform_matrix!(UniformMatrix3fv, 9, uniform_matrix3fv_with_f32_array);
+uniform_matrix!(UniformMatrix4fv, 16, uniform_matrix4fv_with_f32_array);
+
+// queries
+
+extern "system" fn BeginQuery(target: GLenum, id: GLuint) {
+    with(|s| {
+        match s.queries.get(id) {
+            Some(query) => s.gl.begin_query(target, query),
+            None => s.set_error(gl::INVALID_OPERATION),
+        }
+    })
+}
+
+extern "system" fn EndQuery(target: GLenum) {
+    with(|s| s.gl.end_query(target))
+}
+
+extern "system" fn GetQueryObjectiv(id: GLuint, pname: GLenum, params: *mut GLint) {
+    with(|s| {
+        let value = s.queries.get(id).map(|q| s.gl.get_query_parameter(q, pname))
+                             .unwrap_or(JsValue::NULL);
+        unsafe { write_ints(&value, 1, params); }
+    })
+}
+
+extern "system" fn GetQueryObjectuiv(id: GLuint, pname: GLenum, params: *mut GLuint) {
+    with(|s| {
+        let value = s.queries.get(id).map(|q| s.gl.get_query_parameter(q, pname))
+                             .unwrap_or(JsValue::NULL);
+        let value = to_numbers(&value).get(0).cloned().unwrap_or(0.0);
+        unsafe { *params = value as GLuint; }
+    })
+}
+
+// fences
+
+#[inline]
+fn sync_name(sync: GLsync) -> GLuint {
+    sync as usize as GLuint
+}
+
+extern "system" fn FenceSync(condition: GLenum, flags: GLbitfield) -> GLsync {
+    with(|s| {
+        match s.gl.fence_sync(condition, flags) {
+            Some(sync) => s.syncs.insert(sync) as usize as GLsync,
+            None => ptr::null(),
+        }
+    })
+}
+
+extern "system" fn DeleteSync(sync: GLsync) {
+    with(|s| {
+        if let Some(object) = s.syncs.remove(sync_name(sync)) {
+            s.gl.delete_sync(Some(&object));
+        }
+    })
+}
+
+extern "system" fn ClientWaitSync(sync: GLsync, flags: GLbitfield, timeout: GLuint64) -> GLenum {
+    with(|s| {
+        let sync = match s.syncs.get(sync_name(sync)) {
+            Some(sync) => sync,
+            None => return gl::WAIT_FAILED,
+        };
+
+        // fences are only signaled when the browser's event loop runs, and WebGL doesn't allow
+        // blocking, so waiting is done by finishing all the commands instead
+        if timeout == 0 {
+            s.gl.client_wait_sync_with_u32(sync, flags, 0)
+        } else {
+            s.gl.finish();
+            gl::ALREADY_SIGNALED
+        }
+    })
+}
+
+extern "system" fn WaitSync(sync: GLsync, flags: GLbitfield, _timeout: GLuint64) {
+    with(|s| {
+        if let Some(sync) = s.syncs.get(sync_name(sync)) {
+            // the only timeout allowed by WebGL is `GL_TIMEOUT_IGNORED`
+            s.gl.wait_sync_with_i32(sync, flags, -1);
+        }
+    })
+}
+
+macro_rules! functions {
+    ($($name:ident),+) => (
+        /// Returns the address of one of the functions of this module, or null if WebGL
+        /// doesn't support the function.
+        pub fn get_proc_address(symbol: &str) -> *const c_void {
+            match symbol {
+                $(
+                    concat!("gl", stringify!($name)) => $name as *const c_void,
+                )+
+                _ => ptr::null(),
+            }
+        }
+    )
+}
+
+functions!(
+    ActiveTexture, AttachShader, BeginQuery, BeginTransformFeedback, BindBuffer, BindBufferRange,
+    BindFramebuffer, BindRenderbuffer, BindSampler, BindTexture, BindVertexArray, BlendColor,
+    BlendEquationSeparate, BlendFuncSeparate, BlitFramebuffer, BufferData, BufferSubData,
+    CheckFramebufferStatus, Clear, ClearBufferfv, ClearBufferiv, ClearBufferuiv, ClearColor,
+    ClearDepth, ClearDepthf, ClearStencil, ClientWaitSync, ColorMask, CompileShader,
+    CompressedTexImage2D, CompressedTexImage3D, CompressedTexSubImage2D, CompressedTexSubImage3D,
+    CopyBufferSubData, CreateProgram, CreateShader, CullFace, DeleteBuffers, DeleteFramebuffers,
+    DeleteProgram, DeleteQueries, DeleteRenderbuffers, DeleteSamplers, DeleteShader, DeleteSync,
+    DeleteTextures, DeleteVertexArrays, DepthFunc, DepthMask, DepthRange, DepthRangef, Disable,
+    DisableVertexAttribArray, DrawArrays, DrawArraysInstanced, DrawBuffers, DrawElements,
+    DrawElementsInstanced, Enable, EnableVertexAttribArray, EndQuery, EndTransformFeedback,
+    FenceSync, Finish, Flush, FlushMappedBufferRange, FramebufferRenderbuffer,
+    FramebufferTexture2D, FramebufferTextureLayer, FrontFace, GenBuffers, GenFramebuffers,
+    GenQueries, GenRenderbuffers, GenSamplers, GenTextures, GenVertexArrays, GenerateMipmap,
+    GetActiveAttrib, GetActiveUniform, GetActiveUniformBlockName, GetActiveUniformBlockiv,
+    GetActiveUniformName, GetActiveUniformsiv, GetAttribLocation, GetBooleanv,
+    GetBufferParameteriv, GetError, GetFloatv, GetFragDataLocation,
+    GetFramebufferAttachmentParameteriv, GetIntegerv, GetInternalformativ, GetProgramInfoLog,
+    GetProgramiv, GetQueryObjectiv, GetQueryObjectuiv, GetShaderInfoLog, GetShaderiv, GetString,
+    GetStringi, GetTransformFeedbackVarying, GetUniformLocation, Hint, LineWidth, LinkProgram,
+    MapBufferRange, PixelStorei, PolygonOffset, ReadBuffer, ReadPixels, RenderbufferStorage,
+    RenderbufferStorageMultisample, SamplerParameterf, SamplerParameteri, Scissor, ShaderSource,
+    StencilFunc, StencilFuncSeparate, StencilMask, StencilMaskSeparate, StencilOp,
+    StencilOpSeparate, TexImage2D, TexImage3D, TexParameterf, TexParameteri, TexStorage2D,
+    TexStorage3D, TexSubImage2D, TexSubImage3D, TransformFeedbackVaryings, Uniform1f, Uniform1i,
+    Uniform1ui, Uniform2fv, Uniform2iv, Uniform2uiv, Uniform3fv, Uniform3iv, Uniform3uiv,
+    Uniform4fv, Uniform4iv, Uniform4uiv, UniformBlockBinding, UniformMatrix2fv, UniformMatrix3fv,
+    UniformMatrix4fv, UnmapBuffer, UseProgram, VertexAttribDivisor, VertexAttribIPointer,
+    VertexAttribPointer, Viewport, WaitSync
+);
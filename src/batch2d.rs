@@ -0,0 +1,411 @@
+/*!
+Draws many textured quads with few draw calls.
+
+This module is only available if you enable the `batch2d` feature of glium.
+
+A `Batch` is a list of quads, each one with a texture, texture coordinates and a color, that is
+usually filled again every frame. A `Batcher` owns a program and a vertex buffer that grows
+when needed, and draws a batch with one draw call for each run of consecutive quads that use
+the same texture.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let player: glium::Texture2d = unsafe { ::std::mem::uninitialized() };
+# let tiles: glium::Texture2d = unsafe { ::std::mem::uninitialized() };
+use glium::batch2d::{self, Batch, Batcher, Sprite};
+
+let mut batcher = Batcher::new(&display).unwrap();
+
+let mut batch = Batch::new();
+for x in 0 .. 20 {
+    batch.push(&tiles, Sprite::new([x as f32 * 32.0, 0.0], [32.0, 32.0]));
+}
+batch.push(&player, Sprite::new([100.0, 32.0], [32.0, 64.0]).with_color([1.0, 0.5, 0.5, 1.0]));
+
+let mut frame = display.draw();
+let (width, height) = frame.get_dimensions();
+let draw_calls = batcher.draw(&mut frame, &batch, batch2d::pixel_projection(width, height),
+                              &Default::default()).unwrap();
+assert_eq!(draw_calls, 2);
+frame.finish().unwrap();
+```
+
+The quads are drawn in the order in which they have been pushed. If the order doesn't matter,
+for example because the sprites don't overlap, `Batch::sort_by_texture` groups the quads that
+use the same texture so that they are drawn together.
+
+*/
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use index::{IndexBuffer, PrimitiveType};
+use program::ProgramChooserCreationError;
+use texture::Texture2d;
+use texture::atlas::AtlasEntry;
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerBehavior};
+use uniforms::SamplerWrapFunction;
+use vertex::{IntoVerticesSource, VertexBuffer};
+
+use DrawError;
+use DrawParameters;
+use GlObject;
+use Program;
+use Surface;
+
+/// A corner of a quad.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vertex {
+    /// The position of the corner, which is multiplied by the matrix passed to
+    /// `Batcher::draw`.
+    pub position: [f32; 2],
+    /// The texture coordinates of the corner.
+    pub tex_coords: [f32; 2],
+    /// The color that the texture is multiplied with.
+    pub color: [f32; 4],
+}
+
+implement_vertex!(Vertex, position, tex_coords, color);
+
+/// An axis-aligned quad.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sprite {
+    /// The position of the bottom-left corner.
+    pub position: [f32; 2],
+    /// The width and height of the quad.
+    pub size: [f32; 2],
+    /// The texture coordinates of the bottom-left corner.
+    pub uv_min: [f32; 2],
+    /// The texture coordinates of the top-right corner.
+    pub uv_max: [f32; 2],
+    /// The color that the texture is multiplied with.
+    pub color: [f32; 4],
+}
+
+impl Sprite {
+    /// Builds a sprite that shows the whole texture, without tinting it.
+    #[inline]
+    pub fn new(position: [f32; 2], size: [f32; 2]) -> Sprite {
+        Sprite {
+            position: position,
+            size: size,
+            uv_min: [0.0, 0.0],
+            uv_max: [1.0, 1.0],
+            color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+
+    /// Shows only a part of the texture.
+    #[inline]
+    pub fn with_uv(mut self, uv_min: [f32; 2], uv_max: [f32; 2]) -> Sprite {
+        self.uv_min = uv_min;
+        self.uv_max = uv_max;
+        self
+    }
+
+    /// Shows an image of an atlas. The texture passed to `Batch::push` must be the texture of
+    /// the atlas.
+    #[inline]
+    pub fn with_atlas_entry(self, entry: &AtlasEntry) -> Sprite {
+        self.with_uv(entry.uv_min, entry.uv_max)
+    }
+
+    /// Multiplies the texture with a color.
+    #[inline]
+    pub fn with_color(mut self, color: [f32; 4]) -> Sprite {
+        self.color = color;
+        self
+    }
+
+    /// Returns the four corners of the quad, counter-clockwise from the bottom-left one.
+    pub fn vertices(&self) -> [Vertex; 4] {
+        let (x, y, w, h) = (self.position[0], self.position[1], self.size[0], self.size[1]);
+        let (u0, v0, u1, v1) = (self.uv_min[0], self.uv_min[1], self.uv_max[0], self.uv_max[1]);
+
+        [
+            Vertex { position: [x, y], tex_coords: [u0, v0], color: self.color },
+            Vertex { position: [x + w, y], tex_coords: [u1, v0], color: self.color },
+            Vertex { position: [x + w, y + h], tex_coords: [u1, v1], color: self.color },
+            Vertex { position: [x, y + h], tex_coords: [u0, v1], color: self.color },
+        ]
+    }
+}
+
+/// Returns a matrix that maps pixel coordinates to a target of `width` by `height` pixels.
+/// `(0.0, 0.0)` is the bottom-left corner of the target.
+pub fn pixel_projection(width: u32, height: u32) -> [[f32; 4]; 4] {
+    [
+        [2.0 / width as f32, 0.0, 0.0, 0.0],
+        [0.0, 2.0 / height as f32, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [-1.0, -1.0, 0.0, 1.0],
+    ]
+}
+
+/// A list of textured quads.
+#[derive(Clone, Default)]
+pub struct Batch<'t> {
+    quads: Vec<(&'t Texture2d, [Vertex; 4])>,
+}
+
+impl<'t> Batch<'t> {
+    /// Builds an empty batch.
+    #[inline]
+    pub fn new() -> Batch<'t> {
+        Batch {
+            quads: Vec::new(),
+        }
+    }
+
+    /// Adds a sprite at the end of the batch.
+    #[inline]
+    pub fn push(&mut self, texture: &'t Texture2d, sprite: Sprite) {
+        self.quads.push((texture, sprite.vertices()));
+    }
+
+    /// Adds a quad at the end of the batch. The corners must be counter-clockwise, starting
+    /// from the one that would be the bottom-left corner of a sprite.
+    ///
+    /// This is useful for quads that are rotated or skewed.
+    #[inline]
+    pub fn push_vertices(&mut self, texture: &'t Texture2d, vertices: [Vertex; 4]) {
+        self.quads.push((texture, vertices));
+    }
+
+    /// Groups the quads that use the same texture, which minimizes the number of draw calls.
+    ///
+    /// The order of the quads that use the same texture is kept, but quads that use different
+    /// textures may be drawn in a different order.
+    #[inline]
+    pub fn sort_by_texture(&mut self) {
+        self.quads.sort_by_key(|q| q.0.get_id());
+    }
+
+    /// Returns the number of quads in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.quads.len()
+    }
+
+    /// Returns true if the batch doesn't contain any quad.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.quads.is_empty()
+    }
+
+    /// Removes all the quads from the batch.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.quads.clear();
+    }
+}
+
+/// Draws batches.
+pub struct Batcher {
+    context: Rc<Context>,
+    program: Program,
+    sampler: SamplerBehavior,
+    vertex_buffer: Option<VertexBuffer<Vertex>>,
+    index_buffer: Option<IndexBuffer<u32>>,
+}
+
+impl Batcher {
+    /// Builds the program of the batcher. The buffers are created by the first call to `draw`.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<Batcher, ProgramChooserCreationError>
+                          where F: Facade
+    {
+        let program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform mat4 matrix;
+
+                    in vec2 position;
+                    in vec2 tex_coords;
+                    in vec4 color;
+
+                    out vec2 v_tex_coords;
+                    out vec4 v_color;
+
+                    void main() {
+                        v_tex_coords = tex_coords;
+                        v_color = color;
+                        gl_Position = matrix * vec4(position, 0.0, 1.0);
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D tex;
+
+                    in vec2 v_tex_coords;
+                    in vec4 v_color;
+
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = v_color * texture(tex, v_tex_coords);
+                    }
+                "
+            },
+            100 => {
+                vertex: "
+                    #version 100
+
+                    uniform lowp mat4 matrix;
+
+                    attribute lowp vec2 position;
+                    attribute lowp vec2 tex_coords;
+                    attribute lowp vec4 color;
+
+                    varying lowp vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        v_tex_coords = tex_coords;
+                        v_color = color;
+                        gl_Position = matrix * vec4(position, 0.0, 1.0);
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    uniform lowp sampler2D tex;
+
+                    varying lowp vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = v_color * texture2D(tex, v_tex_coords);
+                    }
+                "
+            },
+        ));
+
+        Ok(Batcher {
+            context: facade.get_context().clone(),
+            program: program,
+            sampler: SamplerBehavior {
+                wrap_function: (SamplerWrapFunction::Clamp, SamplerWrapFunction::Clamp,
+                                SamplerWrapFunction::Clamp),
+                minify_filter: MinifySamplerFilter::Linear,
+                magnify_filter: MagnifySamplerFilter::Linear,
+                .. Default::default()
+            },
+            vertex_buffer: None,
+            index_buffer: None,
+        })
+    }
+
+    /// Changes how the textures are sampled. The default behavior uses linear filtering and
+    /// clamps the texture coordinates, and `Nearest` filters are better suited for pixel art.
+    #[inline]
+    pub fn set_sampler_behavior(&mut self, behavior: SamplerBehavior) {
+        self.sampler = behavior;
+    }
+
+    /// Returns the number of quads that fit in the buffers without recreating them.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vertex_buffer.as_ref().map(|b| b.len() / 4).unwrap_or(0)
+    }
+
+    /// Draws the quads of a batch, with the positions of their corners multiplied by `matrix`.
+    /// Returns the number of draw calls.
+    ///
+    /// If the buffers are too small for the batch, they are replaced with buffers whose
+    /// capacity is the next power of two.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffers can't be created.
+    pub fn draw<S: ?Sized>(&mut self, target: &mut S, batch: &Batch, matrix: [[f32; 4]; 4],
+                           draw_parameters: &DrawParameters) -> Result<usize, DrawError>
+                           where S: Surface
+    {
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        self.reserve(batch.len());
+
+        let vertex_buffer = self.vertex_buffer.as_ref().unwrap();
+        let index_buffer = self.index_buffer.as_ref().unwrap();
+
+        let vertices = batch.quads.iter().flat_map(|q| q.1.iter().cloned()).collect::<Vec<_>>();
+        vertex_buffer.slice(0 .. vertices.len()).unwrap().write(&vertices);
+
+        let mut draw_calls = 0;
+        let mut start = 0;
+
+        while start < batch.quads.len() {
+            let texture = batch.quads[start].0;
+            let mut end = start + 1;
+            while end < batch.quads.len() && batch.quads[end].0.get_id() == texture.get_id() {
+                end += 1;
+            }
+
+            let uniforms = uniform! {
+                matrix: matrix,
+                tex: Sampler(texture, self.sampler),
+            };
+
+            let indices = index_buffer.slice(start * 6 .. end * 6).unwrap();
+            try!(target.draw_dyn(&[vertex_buffer.into_vertices_source()], indices.into(),
+                                 &self.program, &uniforms, draw_parameters));
+
+            draw_calls += 1;
+            start = end;
+        }
+
+        Ok(draw_calls)
+    }
+
+    /// Makes sure that the buffers can contain `quads` quads.
+    fn reserve(&mut self, quads: usize) {
+        if self.capacity() >= quads {
+            return;
+        }
+
+        let capacity = quads.next_power_of_two();
+
+        let indices = (0 .. capacity as u32).flat_map(|q| {
+            let first = q * 4;
+            vec![first, first + 1, first + 2, first, first + 2, first + 3]
+        }).collect::<Vec<_>>();
+
+        self.vertex_buffer = Some(VertexBuffer::empty_dynamic(&self.context, capacity * 4)
+                                    .expect("Failed to create the vertex buffer of the batcher"));
+        self.index_buffer = Some(IndexBuffer::new(&self.context, PrimitiveType::TrianglesList,
+                                                  &indices)
+                                    .expect("Failed to create the index buffer of the batcher"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pixel_projection, Sprite};
+
+    #[test]
+    fn sprite_vertices() {
+        let vertices = Sprite::new([10.0, 20.0], [4.0, 2.0])
+                            .with_uv([0.5, 0.0], [1.0, 0.25])
+                            .vertices();
+
+        assert_eq!(vertices[0].position, [10.0, 20.0]);
+        assert_eq!(vertices[2].position, [14.0, 22.0]);
+        assert_eq!(vertices[1].tex_coords, [1.0, 0.0]);
+        assert_eq!(vertices[3].tex_coords, [0.5, 0.25]);
+        assert_eq!(vertices[3].color, [1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn projection() {
+        let m = pixel_projection(200, 100);
+        let project = |x: f32, y: f32| [m[0][0] * x + m[3][0], m[1][1] * y + m[3][1]];
+
+        assert_eq!(project(0.0, 0.0), [-1.0, -1.0]);
+        assert_eq!(project(200.0, 100.0), [1.0, 1.0]);
+    }
+}
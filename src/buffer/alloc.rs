@@ -1,5 +1,6 @@
 use backend::Facade;
-use context::CommandContext;
+use context;
+use context::{CommandContext, Fallback, PendingDestruction, ShareGroup};
 use context::Context;
 use version::Version;
 use CapabilitiesSource;
@@ -11,10 +12,12 @@ use std::{fmt, mem, ptr};
 use std::cell::Cell;
 use std::rc::Rc;
 use std::ops::{Deref, DerefMut, Range};
+use std::sync::Arc;
 use GlObject;
 use TransformFeedbackSessionExt;
 
 use buffer::{Content, BufferType, BufferMode, BufferCreationError};
+use sync::{self, LinearSyncFence};
 use vertex::TransformFeedbackSession;
 use vertex_array_object::VertexAttributesSystem;
 
@@ -22,6 +25,7 @@ use version::Api;
 
 /// Error that can happen when reading from a buffer.
 #[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
 pub enum ReadError {
     /// The backend doesn't support reading from a buffer.
     NotSupported,
@@ -46,8 +50,36 @@ impl Error for ReadError {
     }
 }
 
+/// Error that can happen when mapping a buffer.
+#[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
+pub enum MapError {
+    /// The backend doesn't support mapping buffers.
+    NotSupported,
+
+    /// The context has been lost. The mapping would contain garbage data.
+    ContextLost,
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for MapError {
+    fn description(&self) -> &str {
+        use self::MapError::*;
+        match *self {
+            NotSupported => "The backend doesn't support mapping buffers",
+            ContextLost => "The context has been lost. The mapping would contain garbage data",
+        }
+    }
+}
+
 /// Error that can happen when copying data between buffers.
 #[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
 pub enum CopyError {
     /// The backend doesn't support copying between buffers.
     NotSupported,
@@ -103,6 +135,51 @@ pub struct Alloc {
     latest_shader_write: Cell<u64>,
 }
 
+/// A buffer that has been detached from its context. Private object.
+pub struct SharedAlloc {
+    id: gl::types::GLuint,
+    ty: BufferType,
+    size: usize,
+    persistent_mapping: Option<*mut raw::c_void>,
+    immutable: bool,
+    creation_mode: BufferMode,
+    created_with_buffer_storage: bool,
+    share_group: Arc<ShareGroup>,
+
+    /// Signaled when the commands that use the buffer are finished. `None` if the backend
+    /// doesn't support fences, in which case these commands have already finished.
+    fence: Option<LinearSyncFence>,
+}
+
+// the persistent mapping can be accessed from any thread
+unsafe impl Send for SharedAlloc {}
+
+impl SharedAlloc {
+    /// Returns the total size in bytes of the buffer.
+    #[inline]
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+}
+
+impl fmt::Debug for SharedAlloc {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "Shared buffer #{} (size: {} bytes)", self.id, self.size)
+    }
+}
+
+impl Drop for SharedAlloc {
+    fn drop(&mut self) {
+        // there is no context here, so the buffer is destroyed by the next context of the share
+        // group that collects its objects
+        if let Some(fence) = self.fence.take() {
+            context::orphan_shared_object(&self.share_group, PendingDestruction::Fence(fence));
+        }
+
+        context::orphan_shared_object(&self.share_group, PendingDestruction::Buffer(self.id));
+    }
+}
+
 impl Alloc {
     /// Builds a new buffer containing the given data. The size of the buffer is equal to the
     /// size of the data.
@@ -118,6 +195,9 @@ impl Alloc {
             create_buffer(&mut ctxt, size, Some(data), ty, mode)
         });
 
+        context::register_allocation(facade.get_context(), context::AllocationKind::Buffer, id,
+                                     size, None);
+
         Ok(Alloc {
             context: facade.get_context().clone(),
             id: id,
@@ -142,6 +222,9 @@ impl Alloc {
             create_buffer::<()>(&mut ctxt, size, None, ty, mode)
         });
 
+        context::register_allocation(facade.get_context(), context::AllocationKind::Buffer, id,
+                                     size, None);
+
         Ok(Alloc {
             context: facade.get_context().clone(),
             id: id,
@@ -156,12 +239,161 @@ impl Alloc {
         })
     }
 
+    /// Builds an `Alloc` from an existing OpenGL buffer. The size of the buffer and whether it
+    /// has an immutable storage are queried from OpenGL.
+    ///
+    /// # Unsafety
+    ///
+    /// The buffer must belong to the context of `facade`, must not be mapped, and must not be
+    /// destroyed by someone else. It is destroyed when the `Alloc` is destroyed. If it has an
+    /// immutable storage, it can only be mapped if it was created with the corresponding flags.
+    pub unsafe fn from_id<F: ?Sized>(facade: &F, id: gl::types::GLuint, ty: BufferType) -> Alloc
+                                     where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+
+        let size = get_buffer_parameter(&mut ctxt, id, ty, gl::BUFFER_SIZE) as usize;
+
+        let created_with_buffer_storage = if ctxt.version >= &Version(Api::Gl, 4, 4) ||
+                                             ctxt.extensions.gl_arb_buffer_storage ||
+                                             ctxt.extensions.gl_ext_buffer_storage
+        {
+            get_buffer_parameter(&mut ctxt, id, ty, gl::BUFFER_IMMUTABLE_STORAGE) != 0
+        } else {
+            false
+        };
+
+        // an immutable storage without this flag can't be modified with `glBufferSubData`
+        let immutable = created_with_buffer_storage && {
+            let flags = get_buffer_parameter(&mut ctxt, id, ty, gl::BUFFER_STORAGE_FLAGS);
+            (flags as gl::types::GLenum & gl::DYNAMIC_STORAGE_BIT) == 0
+        };
+
+        context::register_allocation(facade.get_context(), context::AllocationKind::Buffer, id,
+                                     size, None);
+
+        Alloc {
+            context: facade.get_context().clone(),
+            id: id,
+            ty: ty,
+            size: size,
+            persistent_mapping: None,
+            immutable: immutable,
+            created_with_buffer_storage: created_with_buffer_storage,
+            creation_mode: BufferMode::Default,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+        }
+    }
+
+    /// Detaches the buffer from its context, so that it can be attached to another context of the
+    /// same share group with `from_shared`.
+    ///
+    /// The commands that have been issued are submitted, and the returned object contains a fence
+    /// that is signaled when they are finished.
+    pub fn into_shared(self) -> SharedAlloc {
+        let shared = {
+            let mut ctxt = self.context.make_current();
+            self.assert_unmapped(&mut ctxt);
+            self.assert_not_transform_feedback(&mut ctxt);
+            VertexAttributesSystem::purge_buffer(&mut ctxt, self.id);
+            unsafe { unbind_buffer(&mut ctxt, self.id); }
+
+            let fence = match unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+                Ok(fence) => {
+                    unsafe { ctxt.gl.Flush(); }
+                    Some(fence)
+                },
+                Err(_) => {
+                    unsafe { ctxt.gl.Finish(); }
+                    ctxt.fallbacks.record(Fallback::FinishInsteadOfFence);
+                    None
+                },
+            };
+
+            SharedAlloc {
+                id: self.id,
+                ty: self.ty,
+                size: self.size,
+                persistent_mapping: self.persistent_mapping,
+                immutable: self.immutable,
+                creation_mode: self.creation_mode,
+                created_with_buffer_storage: self.created_with_buffer_storage,
+                share_group: context::get_share_group(&self.context),
+                fence: fence,
+            }
+        };
+
+        context::unregister_allocation(&self.context, context::AllocationKind::Buffer, self.id);
+
+        // the OpenGL buffer now belongs to the `SharedAlloc`, so only the context is released
+        let context = unsafe { ptr::read(&self.context) };
+        mem::forget(self);
+        drop(context);
+
+        shared
+    }
+
+    /// Attaches a buffer that has been detached with `into_shared` to the context of `facade`.
+    /// The next commands of this context wait until the commands that were using the buffer in
+    /// the other context are finished.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the context doesn't share its objects with the context that detached the buffer.
+    pub fn from_shared<F: ?Sized>(facade: &F, mut shared: SharedAlloc) -> Alloc where F: Facade {
+        assert!(Arc::ptr_eq(&shared.share_group,
+                            &context::get_share_group(facade.get_context())),
+                "The context doesn't share its objects with the context of the buffer");
+
+        if let Some(fence) = shared.fence.take() {
+            let mut ctxt = facade.get_context().make_current();
+            unsafe { sync::server_wait_linear_sync_fence_and_drop(fence, &mut ctxt); }
+        }
+
+        context::register_allocation(facade.get_context(), context::AllocationKind::Buffer,
+                                     shared.id, shared.size, None);
+
+        let alloc = Alloc {
+            context: facade.get_context().clone(),
+            id: shared.id,
+            ty: shared.ty,
+            size: shared.size,
+            persistent_mapping: shared.persistent_mapping,
+            immutable: shared.immutable,
+            created_with_buffer_storage: shared.created_with_buffer_storage,
+            creation_mode: shared.creation_mode,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+        };
+
+        // the OpenGL buffer now belongs to the `Alloc`, so only the share group is released
+        let share_group = unsafe { ptr::read(&shared.share_group) };
+        mem::forget(shared);
+        drop(share_group);
+
+        alloc
+    }
+
     /// Returns the context corresponding to this buffer.
     #[inline]
     pub fn get_context(&self) -> &Rc<Context> {
         &self.context
     }
 
+    /// Sets the label of this buffer, for debugging purposes.
+    ///
+    /// The label is also shown by `Context::dump_resources`, even if the backend doesn't support
+    /// labels.
+    #[inline]
+    pub fn set_object_label(&self, label: &str) -> Result<(), ()> {
+        context::set_allocation_name(&self.context, context::AllocationKind::Buffer, self.id,
+                                     label);
+
+        let ctxt = self.context.make_current();
+        context::set_object_label(&ctxt, gl::BUFFER, self.id, label)
+    }
+
     /// Returns the total size in bytes of this buffer.
     #[inline]
     pub fn get_size(&self) -> usize {
@@ -351,7 +583,7 @@ impl Alloc {
     /// Makes sure that the buffer is bound to the indexed `GL_SHARED_STORAGE_BUFFER` point and calls
     /// `glMemoryBarrier(GL_SHADER_STORAGE_BARRIER_BIT)` if necessary.
     pub fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint,
-                                               range: Range<usize>)
+                                               range: Range<usize>, write: bool)
     {
         self.assert_unmapped(ctxt);
         self.assert_not_transform_feedback(ctxt);
@@ -363,7 +595,28 @@ impl Alloc {
 
         self.indexed_bind(ctxt, BufferType::ShaderStorageBuffer, index, range);
 
-        self.latest_shader_write.set(ctxt.state.next_draw_call_id);        // TODO: put this somewhere else
+        if write {
+            self.latest_shader_write.set(ctxt.state.next_draw_call_id);        // TODO: put this somewhere else
+        }
+    }
+
+    /// Makes sure that the buffer is bound to the indexed `GL_ATOMIC_COUNTER_BUFFER` point and
+    /// calls `glMemoryBarrier(GL_ATOMIC_COUNTER_BARRIER_BIT)` if necessary.
+    pub fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext,
+                                               index: gl::types::GLuint, range: Range<usize>)
+    {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_atomic_counter {
+            unsafe { ctxt.gl.MemoryBarrier(gl::ATOMIC_COUNTER_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_atomic_counter = ctxt.state.next_draw_call_id;
+        }
+
+        self.indexed_bind(ctxt, BufferType::AtomicCounterBuffer, index, range);
+
+        // the counters are always modified by the shader
+        self.latest_shader_write.set(ctxt.state.next_draw_call_id);
     }
 
     /// Binds the buffer to `GL_TRANSFORM_FEEDBACk_BUFFER` regardless of the current transform
@@ -424,12 +677,18 @@ impl Alloc {
     {
         assert!(offset_bytes + mem::size_of_val(data) <= self.size);
 
+        // the content of the buffer is lost anyway, and the buffer can't be mapped anymore
+        if context::check_context_lost(&mut self.context.make_current()) {
+            return;
+        }
+
         if self.persistent_mapping.is_some() {
             let mapping = Mapping { mapping: self.map_shared(offset_bytes .. offset_bytes + mem::size_of_val(data), false, true) };
             ptr::copy_nonoverlapping(data.to_void_ptr() as *const u8, <D as Content>::to_void_ptr(&mapping) as *mut u8, mem::size_of_val(data));
 
         } else if self.immutable {
             let mut ctxt = self.context.make_current();
+            ctxt.fallbacks.record(Fallback::UploadThroughTemporaryBuffer);
             self.barrier_for_buffer_update(&mut ctxt);
 
             self.assert_unmapped(&mut ctxt);
@@ -597,6 +856,7 @@ impl Alloc {
             let size_bytes = bytes_range.end - bytes_range.start;
 
             let mut ctxt = self.context.make_current();
+            ctxt.fallbacks.record(Fallback::MappingThroughTemporaryBuffer);
 
             // we have to construct a temporary buffer that we will map in memory
             // then after the Mapping is destroyed, we will copy from the temporary buffer to the
@@ -679,6 +939,9 @@ impl Alloc {
                     let ptr = map_buffer(&mut ctxt, self.id, self.ty, bytes_range.clone(),
                                          read, write)
                                         .expect("Buffer mapping is not supported by the backend");
+                    if ptr.is_null() && context::check_context_lost(&mut ctxt) {
+                        panic!("Can't map a buffer after the OpenGL context has been lost");
+                    }
                     self.mapped.set(true);
                     ptr
                 };
@@ -720,6 +983,26 @@ impl Alloc {
         }
     }
 
+    /// Returns an error if `map`, `map_read` or `map_write` would panic because the buffer
+    /// can't be mapped.
+    pub fn check_mappable(&self) -> Result<(), MapError> {
+        if self.persistent_mapping.is_some() {
+            return Ok(());
+        }
+
+        let ctxt = self.context.make_current();
+
+        if ctxt.state.lost_context {
+            return Err(MapError::ContextLost);
+        }
+
+        if !is_buffer_mapping_supported(&ctxt) {
+            return Err(MapError::NotSupported);
+        }
+
+        Ok(())
+    }
+
     /// Returns a read-only mapping in memory of the content of the buffer.
     ///
     /// # Panic
@@ -859,16 +1142,34 @@ impl fmt::Debug for Alloc {
 
 impl Drop for Alloc {
     fn drop(&mut self) {
+        // deleting a buffer that is still mapped unmaps it
+        if context::is_destruction_deferred(&self.context) {
+            context::defer_destruction(&self.context,
+                                       context::PendingDestruction::Buffer(self.id));
+            return;
+        }
+
         unsafe {
             let mut ctxt = self.context.make_current();
             self.assert_unmapped(&mut ctxt);
             self.assert_not_transform_feedback(&mut ctxt);
             VertexAttributesSystem::purge_buffer(&mut ctxt, self.id);
             destroy_buffer(&mut ctxt, self.id);
+            context::unregister_allocation(&self.context, context::AllocationKind::Buffer,
+                                           self.id);
         }
     }
 }
 
+/// Destroys a buffer whose `Alloc` has been dropped while the destruction was deferred, and
+/// removes it from the caches of the context.
+#[doc(hidden)]
+pub unsafe fn destroy_dropped_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint) {
+    TransformFeedbackSession::ensure_buffer_out_of_transform_feedback(ctxt, id);
+    VertexAttributesSystem::purge_buffer(ctxt, id);
+    destroy_buffer(ctxt, id);
+}
+
 impl GlObject for Alloc {
     type Id = gl::types::GLuint;
 
@@ -1084,6 +1385,13 @@ impl<'b, D> WriteMapping<'b, [D]> where [D]: Content, D: Copy {
     }
 }
 
+/// Returns true if mapping a buffer that isn't persistent-mapped is supported by the backend.
+pub fn is_buffer_mapping_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_version() >= &Version(Api::Gl, 3, 0) ||
+    ctxt.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    ctxt.get_extensions().gl_arb_map_buffer_range
+}
+
 /// Returns true if reading from a buffer is supported by the backend.
 pub fn is_buffer_read_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
     if ctxt.get_version() >= &Version(Api::Gl, 4, 5) {
@@ -1114,8 +1422,12 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
                                              BufferCreationError>
                                    where D: Content
 {
+    if context::check_context_lost(ctxt) {
+        return Err(BufferCreationError::ContextLost);
+    }
+
     if !is_buffer_type_supported(ctxt, ty) {
-        return Err(BufferCreationError::BufferTypeNotSupported);
+        return Err(BufferCreationError::BufferTypeNotSupported(ty));
     }
 
     if let Some(data) = data {
@@ -1288,6 +1600,7 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
             Some(ptr)
 
         } else {
+            ctxt.fallbacks.record(Fallback::NoPersistentMapping);
             None
         }
     } else {
@@ -1375,6 +1688,35 @@ fn is_buffer_type_supported(ctxt: &mut CommandContext, ty: BufferType) -> bool {
 /// ## Unsafety
 ///
 /// Assumes that the type of buffer is supported by the backend.
+/// Returns the value of a parameter of a buffer.
+unsafe fn get_buffer_parameter(ctxt: &mut CommandContext, id: gl::types::GLuint, ty: BufferType,
+                               parameter: gl::types::GLenum) -> gl::types::GLint
+{
+    let mut value = 0;
+
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+        ctxt.gl.GetNamedBufferParameteriv(id, parameter, &mut value);
+
+    } else if ctxt.extensions.gl_ext_direct_state_access {
+        ctxt.gl.GetNamedBufferParameterivEXT(id, parameter, &mut value);
+
+    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+        ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        let bind = bind_buffer(ctxt, id, ty);
+        ctxt.gl.GetBufferParameteriv(bind, parameter, &mut value);
+
+    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+        let bind = bind_buffer(ctxt, id, ty);
+        ctxt.gl.GetBufferParameterivARB(bind, parameter, &mut value);
+
+    } else {
+        unreachable!();
+    }
+
+    value
+}
+
 unsafe fn bind_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint, ty: BufferType)
                       -> gl::types::GLenum
 {
@@ -1612,6 +1954,21 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint) {
     // FIXME: uncomment this and move it from Buffer's destructor
     //self.context.vertex_array_objects.purge_buffer(&mut ctxt, id);
 
+    unbind_buffer(ctxt, id);
+
+    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+        ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
+    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
+    } else {
+        unreachable!();
+    }
+}
+
+/// Removes a buffer from the bind points of the state.
+unsafe fn unbind_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint) {
     if ctxt.state.array_buffer_binding == id {
         ctxt.state.array_buffer_binding = 0;
     }
@@ -1684,16 +2041,6 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint) {
             point.buffer = 0;
         }
     }
-
-    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-        ctxt.version >= &Version(Api::GlEs, 2, 0)
-    {
-        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
-    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
-    } else {
-        unreachable!();
-    }
 }
 
 /// Flushes a range of a mapped buffer.
@@ -7,8 +7,9 @@ use smallvec::SmallVec;
 use std::cell::RefCell;
 use std::ops::Range;
 
-use context::CommandContext;
+use context::{self, CommandContext, Context, PendingDestruction};
 use sync::{self, LinearSyncFence};
+use ContextExt;
 
 /// Contains a list of fences.
 pub struct Fences {
@@ -57,6 +58,27 @@ impl Fences {
             unsafe { sync::destroy_linear_sync_fence(ctxt, sync) };
         }
     }
+
+    /// Same as `clean`, but called when the buffer is destroyed. The fences are destroyed later
+    /// if the context defers the destruction of objects.
+    pub fn clean_on_drop(&mut self, context: &Context) {
+        let mut fences = self.fences.borrow_mut();
+
+        if fences.is_empty() {
+            return;
+        }
+
+        if context::is_destruction_deferred(context) {
+            for (_, sync) in fences.drain() {
+                context::defer_destruction(context, PendingDestruction::Fence(sync));
+            }
+        } else {
+            let mut ctxt = context.make_current();
+            for (_, sync) in fences.drain() {
+                unsafe { sync::destroy_linear_sync_fence(&mut ctxt, sync) };
+            }
+        }
+    }
 }
 
 /// Allows inserting a fence in the list.
@@ -45,9 +45,11 @@
 //! ```
 //!
 pub use self::view::{Buffer, BufferAny, BufferMutSlice};
-pub use self::view::{BufferSlice, BufferAnySlice};
-pub use self::alloc::{Mapping, WriteMapping, ReadMapping, ReadError, CopyError};
+pub use self::view::{BufferSlice, BufferAnySlice, SharedBuffer};
+pub use self::alloc::{Mapping, WriteMapping, ReadMapping, ReadError, CopyError, MapError};
 pub use self::alloc::{is_buffer_read_supported};
+#[doc(hidden)]
+pub use self::alloc::destroy_dropped_buffer;
 pub use self::fences::Inserter;
 
 /// DEPRECATED. Only here for backward compatibility.
@@ -173,17 +175,25 @@ unsafe impl<T> Content for [T] where T: Copy {
 
 /// Error that can happen when creating a buffer.
 #[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
 pub enum BufferCreationError {
     /// Not enough memory to create the buffer.
     OutOfMemory,
 
     /// This type of buffer is not supported.
-    BufferTypeNotSupported,
+    BufferTypeNotSupported(BufferType),
+
+    /// The OpenGL context has been lost. See the documentation of the `recovery` module.
+    ContextLost,
 }
 
 impl fmt::Display for BufferCreationError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}", self.description())
+        match *self {
+            BufferCreationError::BufferTypeNotSupported(ty) =>
+                write!(fmt, "{}: {:?} (0x{:x})", self.description(), ty, ty.to_glenum()),
+            _ => write!(fmt, "{}", self.description()),
+        }
     }
 }
 
@@ -191,7 +201,9 @@ impl Error for BufferCreationError {
     fn description(&self) -> &str {
         match self {
             &BufferCreationError::OutOfMemory => "Not enough memory to create the buffer",
-            &BufferCreationError::BufferTypeNotSupported => "This type of buffer is not supported",
+            &BufferCreationError::BufferTypeNotSupported(_) =>
+                "This type of buffer is not supported",
+            &BufferCreationError::ContextLost => "The OpenGL context has been lost",
         }
     }
 }
@@ -24,11 +24,13 @@ use buffer::Content;
 use buffer::fences::Fences;
 use buffer::fences::Inserter;
 use buffer::alloc::Alloc;
+use buffer::alloc::SharedAlloc;
 use buffer::alloc::Mapping;
 use buffer::alloc::ReadMapping;
 use buffer::alloc::WriteMapping;
 use buffer::alloc::ReadError;
 use buffer::alloc::CopyError;
+use buffer::alloc::MapError;
 
 /// Represents a view of a buffer.
 pub struct Buffer<T: ?Sized> where T: Content {
@@ -65,6 +67,67 @@ impl<T: ?Sized> Buffer<T> where T: Content {
             })
     }
 
+    /// Builds a buffer from an existing OpenGL buffer. The buffer is destroyed when the
+    /// `Buffer` is destroyed.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the size of the buffer is not suitable for the content.
+    ///
+    /// # Unsafety
+    ///
+    /// The buffer must belong to the context of `facade` and must not be destroyed by someone
+    /// else. See `Alloc::from_id` for the other restrictions.
+    pub unsafe fn from_id<F: ?Sized>(facade: &F, id: gl::types::GLuint, ty: BufferType)
+                                     -> Buffer<T> where F: Facade
+    {
+        let alloc = Alloc::from_id(facade, id, ty);
+        assert!(<T as Content>::is_size_suitable(alloc.get_size()),
+                "The size of the buffer is not suitable for the content");
+
+        Buffer {
+            alloc: Some(alloc),
+            fence: Some(Fences::new()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Detaches the buffer from its context, so that it can be sent to another thread whose
+    /// context shares its objects with this one.
+    ///
+    /// See the documentation of `SharedBuffer`.
+    pub fn into_shared(mut self) -> SharedBuffer<T> {
+        let alloc = self.alloc.take().unwrap();
+        let mut fence = self.fence.take().unwrap();
+
+        {
+            // the content of a persistent mapping can be modified as soon as it is attached
+            let mut ctxt = alloc.get_context().make_current();
+            fence.wait(&mut ctxt, 0 .. alloc.get_size());
+            fence.clean(&mut ctxt);
+        }
+
+        SharedBuffer {
+            alloc: alloc.into_shared(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Attaches a buffer that has been detached with `into_shared` to the context of `facade`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the context doesn't share its objects with the context that detached the buffer.
+    pub fn from_shared<F: ?Sized>(facade: &F, shared: SharedBuffer<T>) -> Buffer<T>
+                                  where F: Facade
+    {
+        Buffer {
+            alloc: Some(Alloc::from_shared(facade, shared.alloc)),
+            fence: Some(Fences::new()),
+            marker: PhantomData,
+        }
+    }
+
     /// Builds a new buffer of the given size.
     pub fn empty_unsized<F: ?Sized>(facade: &F, ty: BufferType, size: usize, mode: BufferMode)
                             -> Result<Buffer<T>, BufferCreationError> where F: Facade
@@ -87,6 +150,15 @@ impl<T: ?Sized> Buffer<T> where T: Content {
         self.alloc.as_ref().unwrap().get_context()
     }
 
+    /// Sets the label of this buffer. If you use an OpenGL debugger, the buffer will be shown
+    /// with this name.
+    ///
+    /// Returns `Err` if the backend doesn't support labels.
+    #[inline]
+    pub fn set_object_label(&self, label: &str) -> Result<(), ()> {
+        self.alloc.as_ref().unwrap().set_object_label(label)
+    }
+
     /// Returns the size in bytes of this buffer.
     #[inline]
     pub fn get_size(&self) -> usize {
@@ -202,6 +274,30 @@ impl<T: ?Sized> Buffer<T> where T: Content {
         unsafe { self.alloc.as_mut().unwrap().map_write(0 .. size) }
     }
 
+    /// Same as `map`, but returns an error instead of panicking if the backend doesn't support
+    /// mapping buffers.
+    #[inline]
+    pub fn try_map(&mut self) -> Result<Mapping<T>, MapError> {
+        try!(self.alloc.as_ref().unwrap().check_mappable());
+        Ok(self.map())
+    }
+
+    /// Same as `map_read`, but returns an error instead of panicking if the backend doesn't
+    /// support mapping buffers.
+    #[inline]
+    pub fn try_map_read(&mut self) -> Result<ReadMapping<T>, MapError> {
+        try!(self.alloc.as_ref().unwrap().check_mappable());
+        Ok(self.map_read())
+    }
+
+    /// Same as `map_write`, but returns an error instead of panicking if the backend doesn't
+    /// support mapping buffers.
+    #[inline]
+    pub fn try_map_write(&mut self) -> Result<WriteMapping<T>, MapError> {
+        try!(self.alloc.as_ref().unwrap().check_mappable());
+        Ok(self.map_write())
+    }
+
     /// Copies the content of the buffer to another buffer.
     ///
     /// # Panic
@@ -377,6 +473,20 @@ impl<T> Buffer<[T]> where [T]: Content, T: Copy {
     pub fn slice_mut<R: RangeArgument<usize>>(&mut self, range: R) -> Option<BufferMutSlice<[T]>> {
         self.as_mut_slice().slice(range)
     }
+
+    /// Writes the elements produced by an iterator in the buffer, without building an
+    /// intermediate `Vec`.
+    ///
+    /// Use `slice_mut(range)` then `update_from_iter` to only update a part of the buffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the iterator doesn't produce exactly as many elements as the length of the
+    /// buffer.
+    #[inline]
+    pub fn update_from_iter<I>(&mut self, iter: I) where I: IntoIterator<Item = T> {
+        self.as_mut_slice().update_from_iter(iter)
+    }
 }
 
 impl<T> Buffer<[T]> where T: PixelValue {
@@ -399,11 +509,46 @@ impl<T: ?Sized> Drop for Buffer<T> where T: Content {
     #[inline]
     fn drop(&mut self) {
         if let (Some(alloc), Some(mut fence)) = (self.alloc.take(), self.fence.take()) {
-            fence.clean(&mut alloc.get_context().make_current());
+            fence.clean_on_drop(alloc.get_context());
         }
     }
 }
 
+/// A buffer that has been detached from its context, so that it can be sent to another thread.
+///
+/// Obtained with `Buffer::into_shared`, and turned back into a buffer with `Buffer::from_shared`.
+/// The context that receives the buffer must share its objects with the context that detached
+/// it, which is the case of the contexts created with `Display::new_shared_context`.
+///
+/// The commands that use the buffer are submitted when it is detached, and the context that
+/// receives it waits for them to be finished before executing its own commands.
+///
+/// If this object is destroyed without being turned back into a buffer, the buffer is destroyed
+/// by the next context of the share group that calls `Context::collect`, which happens
+/// automatically when a frame is finished.
+pub struct SharedBuffer<T: ?Sized> where T: Content {
+    alloc: SharedAlloc,
+    marker: PhantomData<T>,
+}
+
+// the content of a buffer is plain data
+unsafe impl<T: ?Sized> Send for SharedBuffer<T> where T: Content {}
+
+impl<T: ?Sized> SharedBuffer<T> where T: Content {
+    /// Returns the size in bytes of the buffer.
+    #[inline]
+    pub fn get_size(&self) -> usize {
+        self.alloc.get_size()
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for SharedBuffer<T> where T: Content {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{:?}", self.alloc)
+    }
+}
+
 impl<T: ?Sized> BufferExt for Buffer<T> where T: Content {
     #[inline]
     fn get_offset_bytes(&self) -> usize {
@@ -480,9 +625,19 @@ impl<T: ?Sized> BufferExt for Buffer<T> where T: Content {
     }
 
     #[inline]
-    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
+    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint, write: bool)
+    {
         let alloc = self.alloc.as_ref().unwrap();
-        alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. alloc.get_size());
+        alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. alloc.get_size(), write);
+    }
+
+    #[inline]
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint)
+    {
+        let alloc = self.alloc.as_ref().unwrap();
+        alloc.prepare_and_bind_for_atomic_counter(ctxt, index, 0 .. alloc.get_size());
     }
 
     #[inline]
@@ -793,12 +948,23 @@ impl<'a, T: ?Sized> BufferExt for BufferSlice<'a, T> where T: Content {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
+    }
+
+    #[inline]
+    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint, write: bool)
+    {
+        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index,
+                                                       self.bytes_start .. self.bytes_end, write);
     }
 
     #[inline]
-    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint)
+    {
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index,
+                                                       self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
@@ -875,6 +1041,30 @@ impl<'a, T: ?Sized> BufferMutSlice<'a, T> where T: Content + 'a {
         unsafe { self.alloc.map_write(self.bytes_start .. self.bytes_end) }
     }
 
+    /// Same as `map`, but returns an error instead of panicking if the backend doesn't support
+    /// mapping buffers.
+    #[inline]
+    pub fn try_map(self) -> Result<Mapping<'a, T>, MapError> {
+        try!(self.alloc.check_mappable());
+        Ok(self.map())
+    }
+
+    /// Same as `map_read`, but returns an error instead of panicking if the backend doesn't
+    /// support mapping buffers.
+    #[inline]
+    pub fn try_map_read(self) -> Result<ReadMapping<'a, T>, MapError> {
+        try!(self.alloc.check_mappable());
+        Ok(self.map_read())
+    }
+
+    /// Same as `map_write`, but returns an error instead of panicking if the backend doesn't
+    /// support mapping buffers.
+    #[inline]
+    pub fn try_map_write(self) -> Result<WriteMapping<'a, T>, MapError> {
+        try!(self.alloc.check_mappable());
+        Ok(self.map_write())
+    }
+
     /// Uploads some data in this buffer.
     ///
     /// # Implementation
@@ -890,6 +1080,8 @@ impl<'a, T: ?Sized> BufferMutSlice<'a, T> where T: Content + 'a {
     /// Panics if the length of `data` is different from the length of this buffer.
     #[inline]
     pub fn write(&self, data: &T) {
+        assert_eq!(mem::size_of_val(data), self.get_size());
+
         self.fence.wait(&mut self.alloc.get_context().make_current(),
                         self.bytes_start .. self.bytes_end);
         unsafe { self.alloc.upload(self.bytes_start, data); }
@@ -1032,6 +1224,31 @@ impl<'a, T> BufferMutSlice<'a, [T]> where [T]: Content, T: Copy + 'a {
             marker: PhantomData,
         })
     }
+
+    /// Writes the elements produced by an iterator in this slice, without building an
+    /// intermediate `Vec`.
+    ///
+    /// # Implementation
+    ///
+    /// Maps the slice with `map_write` and writes each element in the mapping.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the iterator doesn't produce exactly as many elements as the length of the
+    /// slice.
+    pub fn update_from_iter<I>(self, iter: I) where I: IntoIterator<Item = T> {
+        let len = self.len();
+        let mut mapping = self.map_write();
+        let mut written = 0;
+
+        for value in iter {
+            assert!(written < len, "The iterator produced more elements than the slice contains");
+            mapping.set(written, value);
+            written += 1;
+        }
+
+        assert!(written == len, "The iterator produced fewer elements than the slice contains");
+    }
 }
 
 impl<'a, T> BufferMutSlice<'a, [T]> where T: PixelValue + 'a {
@@ -1079,6 +1296,34 @@ pub struct BufferAny {
 }
 
 impl BufferAny {
+    /// Builds a new buffer containing the given bytes, which are made of elements of
+    /// `elements_size` bytes each.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `elements_size` is 0 or if the length of `data` is not a multiple of it.
+    pub fn from_bytes<F: ?Sized>(facade: &F, data: &[u8], elements_size: usize, ty: BufferType,
+                                 mode: BufferMode) -> Result<BufferAny, BufferCreationError>
+                                 where F: Facade
+    {
+        assert!(elements_size != 0);
+        assert!(data.len() % elements_size == 0);
+
+        let buffer = try!(Buffer::new(facade, data, ty, mode));
+        let mut buffer: BufferAny = buffer.into();
+        buffer.elements_size = elements_size;
+        Ok(buffer)
+    }
+
+    /// Sets the label of this buffer. If you use an OpenGL debugger, the buffer will be shown
+    /// with this name.
+    ///
+    /// Returns `Err` if the backend doesn't support labels.
+    #[inline]
+    pub fn set_object_label(&self, label: &str) -> Result<(), ()> {
+        self.alloc.set_object_label(label)
+    }
+
     /// Builds a slice-any containing the whole subbuffer.
     #[inline]
     pub fn as_slice_any(&self) -> BufferAnySlice {
@@ -1186,7 +1431,7 @@ impl<T: ?Sized> From<Buffer<T>> for BufferAny where T: Content + Send + 'static
 impl Drop for BufferAny {
     #[inline]
     fn drop(&mut self) {
-        self.fence.clean(&mut self.alloc.get_context().make_current());
+        self.fence.clean_on_drop(self.alloc.get_context());
     }
 }
 
@@ -1264,8 +1509,18 @@ impl BufferExt for BufferAny {
     }
 
     #[inline]
-    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
+    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint, write: bool)
+    {
+        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size(),
+                                                       write);
+    }
+
+    #[inline]
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint)
+    {
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, 0 .. self.alloc.get_size());
     }
 
     #[inline]
@@ -1284,6 +1539,15 @@ pub struct BufferAnySlice<'a> {
     fence: &'a Fences,
 }
 
+impl GlObject for BufferAny {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.alloc.get_id()
+    }
+}
+
 impl<'a> GlObject for BufferAnySlice<'a> {
     type Id = gl::types::GLuint;
 
@@ -1323,6 +1587,39 @@ impl<'a> BufferAnySlice<'a> {
         self.alloc.invalidate(self.bytes_start, self.get_size());
     }
 
+    /// Builds a subslice of this slice, in number of elements. Returns `None` if out of range.
+    #[inline]
+    pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<BufferAnySlice<'a>> {
+        let len = self.get_elements_count();
+        let start = range.start().map_or(0, |e| *e);
+        let end = range.end().map_or(len, |e| *e);
+
+        if start > end || end > len {
+            return None;
+        }
+
+        Some(BufferAnySlice {
+            bytes_start: self.bytes_start + start * self.elements_size,
+            bytes_end: self.bytes_start + end * self.elements_size,
+            .. *self
+        })
+    }
+
+    /// Returns the same slice, but considers that each element is `elements_size` bytes long.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `elements_size` is 0.
+    #[inline]
+    pub fn with_elements_size(self, elements_size: usize) -> BufferAnySlice<'a> {
+        assert!(elements_size != 0);
+
+        BufferAnySlice {
+            elements_size: elements_size,
+            .. self
+        }
+    }
+
     /// Returns the context corresponding to this buffer.
     #[inline]
     pub fn get_context(&self) -> &Rc<Context> {
@@ -1411,12 +1708,23 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
-    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
+    fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint, write: bool)
+    {
+        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index,
+                                                       self.bytes_start .. self.bytes_end, write);
+    }
+
+    #[inline]
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext,
+                                           index: gl::types::GLuint)
+    {
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index,
+                                                       self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
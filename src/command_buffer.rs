@@ -0,0 +1,454 @@
+/*!
+Recording of draw commands on any thread.
+
+Glium objects such as buffers, textures and programs can't be sent to other threads. In order to
+generate draw commands from multiple threads, you first need to register the objects that will
+be used in a `Resources` table on the thread of the context. This table gives you IDs that can be
+freely sent to other threads.
+
+These IDs can then be used to record draw commands in a `CommandBuffer`. Recording a command
+doesn't call any OpenGL function, and `CommandBuffer`s implement `Send`. Once the command buffers
+have been sent back to the thread of the context, they can be replayed on a surface.
+
+```no_run
+# use glium::Surface;
+# use std::thread;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let vertex_buffer: glium::vertex::VertexBufferAny = unsafe { ::std::mem::uninitialized() };
+# let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+# let texture: glium::texture::Texture2d = unsafe { ::std::mem::uninitialized() };
+use glium::command_buffer::{CommandBuffer, CommandIndices, Resources};
+use glium::index::PrimitiveType;
+
+let mut resources = Resources::new();
+let vb = resources.add_vertex_buffer(&vertex_buffer);
+let program_id = resources.add_program(&program);
+let tex = resources.add_uniform(&texture);
+
+let commands = thread::spawn(move || {
+    let mut commands = CommandBuffer::new();
+    commands.draw(vb, CommandIndices::NoIndices(PrimitiveType::TrianglesList), program_id,
+                  &[("tex", tex.into()), ("scale", 2.0f32.into())],
+                  &Default::default()).unwrap();
+    commands
+}).join().unwrap();
+
+let mut target = display.draw();
+commands.replay(&mut target, &resources).unwrap();
+target.finish().unwrap();
+```
+
+*/
+use DrawError;
+use DrawParameters;
+use Program;
+use Surface;
+
+use std::error::Error;
+use std::fmt;
+
+use index::{IndexBufferAny, IndicesSource, NoIndices, PrimitiveType};
+use uniforms::{AsUniformValue, Uniforms, UniformValue};
+use vertex::VertexBufferAny;
+
+/// Table of objects that can be referenced by the commands of a `CommandBuffer`.
+///
+/// This table must be built and used on the thread of the context.
+pub struct Resources<'a> {
+    vertex_buffers: Vec<&'a VertexBufferAny>,
+    index_buffers: Vec<&'a IndexBufferAny>,
+    programs: Vec<&'a Program>,
+    uniforms: Vec<Box<AsUniformValue + 'a>>,
+}
+
+/// Identifier of a vertex buffer registered in a `Resources` table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VertexBufferId(usize);
+
+/// Identifier of an index buffer registered in a `Resources` table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct IndexBufferId(usize);
+
+/// Identifier of a program registered in a `Resources` table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ProgramId(usize);
+
+/// Identifier of a uniform value (a texture, a buffer, etc.) registered in a `Resources` table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct UniformId(usize);
+
+impl<'a> Resources<'a> {
+    /// Builds a new empty table.
+    #[inline]
+    pub fn new() -> Resources<'a> {
+        Resources {
+            vertex_buffers: Vec::new(),
+            index_buffers: Vec::new(),
+            programs: Vec::new(),
+            uniforms: Vec::new(),
+        }
+    }
+
+    /// Registers a vertex buffer.
+    #[inline]
+    pub fn add_vertex_buffer(&mut self, buffer: &'a VertexBufferAny) -> VertexBufferId {
+        self.vertex_buffers.push(buffer);
+        VertexBufferId(self.vertex_buffers.len() - 1)
+    }
+
+    /// Registers an index buffer.
+    #[inline]
+    pub fn add_index_buffer(&mut self, buffer: &'a IndexBufferAny) -> IndexBufferId {
+        self.index_buffers.push(buffer);
+        IndexBufferId(self.index_buffers.len() - 1)
+    }
+
+    /// Registers a program.
+    #[inline]
+    pub fn add_program(&mut self, program: &'a Program) -> ProgramId {
+        self.programs.push(program);
+        ProgramId(self.programs.len() - 1)
+    }
+
+    /// Registers a value that can be bound to a uniform, like a texture or a uniform buffer.
+    #[inline]
+    pub fn add_uniform<U>(&mut self, value: U) -> UniformId where U: AsUniformValue + 'a {
+        self.uniforms.push(Box::new(value));
+        UniformId(self.uniforms.len() - 1)
+    }
+}
+
+impl<'a> Default for Resources<'a> {
+    #[inline]
+    fn default() -> Resources<'a> {
+        Resources::new()
+    }
+}
+
+/// Indices of a recorded draw command.
+#[derive(Debug, Copy, Clone)]
+pub enum CommandIndices {
+    /// Use an index buffer of the `Resources` table.
+    Buffer(IndexBufferId),
+
+    /// Don't use indices. See `glium::index::NoIndices`.
+    NoIndices(PrimitiveType),
+}
+
+/// Value of a uniform in a recorded draw command.
+///
+/// Contains either a plain value, or a reference to an object of the `Resources` table.
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone)]
+pub enum CommandValue {
+    Resource(UniformId),
+    SignedInt(i32),
+    UnsignedInt(u32),
+    Float(f32),
+    Mat2([[f32; 2]; 2]),
+    Mat3([[f32; 3]; 3]),
+    Mat4([[f32; 4]; 4]),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    IntVec2([i32; 2]),
+    IntVec3([i32; 3]),
+    IntVec4([i32; 4]),
+    UnsignedIntVec2([u32; 2]),
+    UnsignedIntVec3([u32; 3]),
+    UnsignedIntVec4([u32; 4]),
+    Bool(bool),
+}
+
+macro_rules! impl_from_value {
+    ($t:ty, $variant:ident) => (
+        impl From<$t> for CommandValue {
+            #[inline]
+            fn from(value: $t) -> CommandValue {
+                CommandValue::$variant(value)
+            }
+        }
+    );
+}
+
+impl_from_value!(UniformId, Resource);
+impl_from_value!(i32, SignedInt);
+impl_from_value!(u32, UnsignedInt);
+impl_from_value!(f32, Float);
+impl_from_value!([[f32; 2]; 2], Mat2);
+impl_from_value!([[f32; 3]; 3], Mat3);
+impl_from_value!([[f32; 4]; 4], Mat4);
+impl_from_value!([f32; 2], Vec2);
+impl_from_value!([f32; 3], Vec3);
+impl_from_value!([f32; 4], Vec4);
+impl_from_value!([i32; 2], IntVec2);
+impl_from_value!([i32; 3], IntVec3);
+impl_from_value!([i32; 4], IntVec4);
+impl_from_value!([u32; 2], UnsignedIntVec2);
+impl_from_value!([u32; 3], UnsignedIntVec3);
+impl_from_value!([u32; 4], UnsignedIntVec4);
+impl_from_value!(bool, Bool);
+
+/// Error that can happen when recording a draw command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RecordError {
+    /// The draw parameters contain a query, a conditional rendering or a transform feedback
+    /// session. These objects can't be used from other threads.
+    NotSendable,
+
+    /// The depth range is outside of `(0, 1)`.
+    InvalidDepthRange,
+
+    /// The same uniform has been given several values.
+    DuplicateUniform(String),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            RecordError::DuplicateUniform(ref name) => {
+                write!(fmt, "{}: `{}`", self.description(), name)
+            },
+            _ => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for RecordError {
+    fn description(&self) -> &str {
+        match *self {
+            RecordError::NotSendable => {
+                "Queries and transform feedback can't be recorded in a command buffer"
+            },
+            RecordError::InvalidDepthRange => "The depth range is outside of `(0, 1)`",
+            RecordError::DuplicateUniform(_) => "The same uniform has been given several values",
+        }
+    }
+}
+
+/// Error that can happen when replaying a command buffer.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ReplayError {
+    /// A command uses an ID that doesn't exist in the `Resources` table.
+    UnknownResource,
+
+    /// A command couldn't be drawn.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ReplayError {
+    fn description(&self) -> &str {
+        match *self {
+            ReplayError::UnknownResource => {
+                "A command uses an ID that doesn't exist in the resources table"
+            },
+            ReplayError::DrawError(ref err) => err.description(),
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            ReplayError::DrawError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DrawError> for ReplayError {
+    #[inline]
+    fn from(err: DrawError) -> ReplayError {
+        ReplayError::DrawError(err)
+    }
+}
+
+/// List of recorded draw commands.
+///
+/// Recording commands doesn't require a context, and the buffer can be sent between threads.
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+}
+
+struct Command {
+    vertices: VertexBufferId,
+    indices: CommandIndices,
+    program: ProgramId,
+    uniforms: Vec<(String, CommandValue)>,
+    draw_parameters: SendDrawParameters,
+}
+
+/// Draw parameters that don't contain any reference to a query or a transform feedback session.
+struct SendDrawParameters(DrawParameters<'static>);
+
+// The only members of `DrawParameters` that are not `Send` are the queries and the transform
+// feedback session, and `CommandBuffer::draw` checks that they are all `None`.
+unsafe impl Send for SendDrawParameters {}
+
+impl CommandBuffer {
+    /// Builds a new empty command buffer.
+    #[inline]
+    pub fn new() -> CommandBuffer {
+        CommandBuffer {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Records a draw command.
+    ///
+    /// The parameters that don't depend on the context are checked here, and the command isn't
+    /// recorded if they are invalid. The IDs are checked when the buffer is replayed.
+    pub fn draw(&mut self, vertices: VertexBufferId, indices: CommandIndices, program: ProgramId,
+                uniforms: &[(&str, CommandValue)], draw_parameters: &DrawParameters<'static>)
+                -> Result<(), RecordError>
+    {
+        if draw_parameters.samples_passed_query.is_some() ||
+           draw_parameters.time_elapsed_query.is_some() ||
+           draw_parameters.primitives_generated_query.is_some() ||
+           draw_parameters.transform_feedback_primitives_written_query.is_some() ||
+           draw_parameters.condition.is_some() ||
+           draw_parameters.transform_feedback.is_some()
+        {
+            return Err(RecordError::NotSendable);
+        }
+
+        let (near, far) = draw_parameters.depth.range;
+        if near < 0.0 || near > 1.0 || far < 0.0 || far > 1.0 {
+            return Err(RecordError::InvalidDepthRange);
+        }
+
+        for (index, &(name, _)) in uniforms.iter().enumerate() {
+            if uniforms[.. index].iter().any(|&(n, _)| n == name) {
+                return Err(RecordError::DuplicateUniform(name.to_owned()));
+            }
+        }
+
+        self.commands.push(Command {
+            vertices: vertices,
+            indices: indices,
+            program: program,
+            uniforms: uniforms.iter().map(|&(n, v)| (n.to_owned(), v)).collect(),
+            draw_parameters: SendDrawParameters(draw_parameters.clone()),
+        });
+
+        Ok(())
+    }
+
+    /// Moves all the commands of `other` at the end of this buffer.
+    #[inline]
+    pub fn append(&mut self, other: &mut CommandBuffer) {
+        self.commands.append(&mut other.commands);
+    }
+
+    /// Executes all the commands of the buffer on a surface, in the order they were recorded.
+    ///
+    /// Stops at the first command that fails and returns its error. A command that uses an ID
+    /// that doesn't exist in `resources` fails without drawing anything.
+    pub fn replay<S>(&self, surface: &mut S, resources: &Resources) -> Result<(), ReplayError>
+                     where S: Surface
+    {
+        for command in &self.commands {
+            let vertices = match resources.vertex_buffers.get(command.vertices.0) {
+                Some(vertices) => *vertices,
+                None => return Err(ReplayError::UnknownResource),
+            };
+
+            let program = match resources.programs.get(command.program.0) {
+                Some(program) => *program,
+                None => return Err(ReplayError::UnknownResource),
+            };
+
+            let indices = match command.indices {
+                CommandIndices::Buffer(id) => match resources.index_buffers.get(id.0) {
+                    Some(buffer) => IndicesSource::from(*buffer),
+                    None => return Err(ReplayError::UnknownResource),
+                },
+                CommandIndices::NoIndices(ty) => IndicesSource::from(NoIndices(ty)),
+            };
+
+            let unknown_uniform = command.uniforms.iter().any(|&(_, value)| match value {
+                CommandValue::Resource(id) => id.0 >= resources.uniforms.len(),
+                _ => false,
+            });
+
+            if unknown_uniform {
+                return Err(ReplayError::UnknownResource);
+            }
+
+            let uniforms = ReplayUniforms {
+                values: &command.uniforms,
+                resources: resources,
+            };
+
+            try!(surface.draw(vertices, indices, program, &uniforms,
+                              &command.draw_parameters.0));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of commands in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if the buffer doesn't contain any command.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Removes all the commands from the buffer.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl Default for CommandBuffer {
+    #[inline]
+    fn default() -> CommandBuffer {
+        CommandBuffer::new()
+    }
+}
+
+/// Uniforms of a command being replayed.
+struct ReplayUniforms<'r, 'a: 'r> {
+    values: &'r [(String, CommandValue)],
+    resources: &'r Resources<'a>,
+}
+
+impl<'r, 'a> Uniforms for ReplayUniforms<'r, 'a> {
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        for &(ref name, value) in self.values {
+            let value = match value {
+                CommandValue::Resource(id) => self.resources.uniforms[id.0].as_uniform_value(),
+                CommandValue::SignedInt(v) => UniformValue::SignedInt(v),
+                CommandValue::UnsignedInt(v) => UniformValue::UnsignedInt(v),
+                CommandValue::Float(v) => UniformValue::Float(v),
+                CommandValue::Mat2(v) => UniformValue::Mat2(v),
+                CommandValue::Mat3(v) => UniformValue::Mat3(v),
+                CommandValue::Mat4(v) => UniformValue::Mat4(v),
+                CommandValue::Vec2(v) => UniformValue::Vec2(v),
+                CommandValue::Vec3(v) => UniformValue::Vec3(v),
+                CommandValue::Vec4(v) => UniformValue::Vec4(v),
+                CommandValue::IntVec2(v) => UniformValue::IntVec2(v),
+                CommandValue::IntVec3(v) => UniformValue::IntVec3(v),
+                CommandValue::IntVec4(v) => UniformValue::IntVec4(v),
+                CommandValue::UnsignedIntVec2(v) => UniformValue::UnsignedIntVec2(v),
+                CommandValue::UnsignedIntVec3(v) => UniformValue::UnsignedIntVec3(v),
+                CommandValue::UnsignedIntVec4(v) => UniformValue::UnsignedIntVec4(v),
+                CommandValue::Bool(v) => UniformValue::Bool(v),
+            };
+
+            output(name, value);
+        }
+    }
+}
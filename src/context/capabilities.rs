@@ -5,6 +5,7 @@ use version::Api;
 use std::cmp;
 use std::collections::HashMap;
 use std::ffi::CStr;
+use std::fmt;
 use std::hash::BuildHasherDefault;
 use std::mem;
 
@@ -17,7 +18,8 @@ use CapabilitiesSource;
 use image_format::TextureFormat;
 
 /// Describes the OpenGL context profile.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Profile {
     /// The context uses only future-compatible functions and definitions.
     Core,
@@ -62,6 +64,22 @@ pub struct Capabilities {
     /// True if out-of-bound access on the GPU side can't result in crashes.
     pub robustness: bool,
 
+    /// True if out-of-bound access on the GPU side has a defined behavior: reads return values
+    /// from inside the buffer or zero, and writes are discarded or stay inside the buffer. This
+    /// includes vertices fetched with out-of-bound indices.
+    ///
+    /// Requires a robust context and `GL_ARB_robust_buffer_access_behavior` or an equivalent.
+    pub robust_buffer_access_behavior: bool,
+
+    /// True if the context was created with `GL_KHR_no_error`, which means that errors result in
+    /// undefined behavior instead of being reported.
+    ///
+    /// Glium still performs its own validation with such a context, unless it is lowered with
+    /// `Context::set_validation_level`. Such a context can be requested with
+    /// `backend::egl::Headless::with_no_error`. The version of glutin that glium uses can't
+    /// request it.
+    pub no_error: bool,
+
     /// True if it is possible for the OpenGL context to be lost.
     pub can_lose_context: bool,
 
@@ -91,6 +109,10 @@ pub struct Capabilities {
     /// `glActiveTexture` must be between `GL_TEXTURE0` and `GL_TEXTURE0` + this value - 1.
     pub max_combined_texture_image_units: gl::types::GLint,
 
+    /// Maximum number of textures that can be accessed from the fragment shader, which is
+    /// also the maximum size of an array of samplers in the fragment shader.
+    pub max_texture_image_units: gl::types::GLint,
+
     /// Maximum value for `GL_TEXTURE_MAX_ANISOTROPY_EXT​`.
     ///
     /// `None` if the extension is not supported by the hardware.
@@ -120,6 +142,16 @@ pub struct Capabilities {
     /// Number of available buffer bind points for `GL_UNIFORM_BUFFER`.
     pub max_indexed_uniform_buffer: gl::types::GLint,
 
+    /// Alignment in bytes of the offset of a buffer range bound to `GL_UNIFORM_BUFFER`.
+    pub uniform_buffer_offset_alignment: gl::types::GLint,
+
+    /// Alignment in bytes of the offset of a buffer range bound to `GL_SHADER_STORAGE_BUFFER`.
+    pub shader_storage_buffer_offset_alignment: gl::types::GLint,
+
+    /// Number of image units that textures can be bound to with `glBindImageTexture`. `0` if
+    /// image load and store is not supported.
+    pub max_image_units: gl::types::GLint,
+
     /// Number of work groups for compute shaders.
     pub max_compute_work_group_count: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
 
@@ -179,6 +211,26 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         (false, false)
     };
 
+    let robustness = if version >= &Version(Api::Gl, 4, 5) ||
+                        version >= &Version(Api::GlEs, 3, 2) ||
+                        (version >= &Version(Api::Gl, 3, 0) && extensions.gl_arb_robustness)
+    {
+        // TODO: there seems to be no way to query `GL_CONTEXT_FLAGS` before OpenGL 3.0, even
+        //       if `GL_ARB_robustness` is there
+        let mut val = mem::uninitialized();
+        gl.GetIntegerv(gl::CONTEXT_FLAGS, &mut val);
+        let val = val as gl::types::GLenum;
+        (val & gl::CONTEXT_FLAG_ROBUST_ACCESS_BIT) != 0
+
+    } else if extensions.gl_khr_robustness || extensions.gl_ext_robustness {
+        let mut val = mem::uninitialized();
+        gl.GetBooleanv(gl::CONTEXT_ROBUST_ACCESS, &mut val);
+        val != 0
+
+    } else {
+        false
+    };
+
     // getting the value of `GL_RENDERER`
     let renderer = {
         let s = gl.GetString(gl::RENDERER);
@@ -227,21 +279,22 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
 
         forward_compatible: forward_compatible,
 
-        robustness: if version >= &Version(Api::Gl, 4, 5) || version >= &Version(Api::GlEs, 3, 2) ||
-                       (version >= &Version(Api::Gl, 3, 0) && extensions.gl_arb_robustness)
+        robustness: robustness,
+
+        // the behavior is part of OpenGL 4.3 and OpenGL ES 3.2, but only with robust contexts
+        robust_buffer_access_behavior: robustness &&
+                                       (version >= &Version(Api::Gl, 4, 3) ||
+                                        version >= &Version(Api::GlEs, 3, 2) ||
+                                        extensions.gl_arb_robust_buffer_access_behavior ||
+                                        extensions.gl_khr_robust_buffer_access_behavior),
+
+        no_error: if (version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 2)) &&
+                     (version >= &Version(Api::Gl, 4, 6) || extensions.gl_khr_no_error)
         {
-            // TODO: there seems to be no way to query `GL_CONTEXT_FLAGS` before OpenGL 3.0, even
-            //       if `GL_ARB_robustness` is there
             let mut val = mem::uninitialized();
             gl.GetIntegerv(gl::CONTEXT_FLAGS, &mut val);
             let val = val as gl::types::GLenum;
-            (val & gl::CONTEXT_FLAG_ROBUST_ACCESS_BIT) != 0
-
-        } else if extensions.gl_khr_robustness || extensions.gl_ext_robustness {
-            let mut val = mem::uninitialized();
-            gl.GetBooleanv(gl::CONTEXT_ROBUST_ACCESS, &mut val);
-            val != 0
-
+            (val & gl::CONTEXT_FLAG_NO_ERROR_BIT_KHR) != 0
         } else {
             false
         },
@@ -397,6 +450,12 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             val
         },
 
+        max_texture_image_units: {
+            let mut val = 2;
+            gl.GetIntegerv(gl::MAX_TEXTURE_IMAGE_UNITS, &mut val);
+            val
+        },
+
         max_texture_max_anisotropy: if !extensions.gl_ext_texture_filter_anisotropic {
             None
 
@@ -444,7 +503,7 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         },
 
         max_patch_vertices: if version >= &Version(Api::Gl, 4, 0) ||
-            extensions.gl_arb_tessellation_shader
+            version >= &Version(Api::GlEs, 3, 2) || extensions.gl_arb_tessellation_shader
         {
             Some({
                 let mut val = mem::uninitialized();
@@ -498,6 +557,40 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        uniform_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 3, 1) || extensions.gl_arb_uniform_buffer_object {      // TODO: GLES
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
+        shader_storage_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_storage_buffer_object
+            {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
+        max_image_units: {
+            if version >= &Version(Api::Gl, 4, 2) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_image_load_store
+            {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::MAX_IMAGE_UNITS, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
         max_compute_work_group_count: if version >= &Version(Api::Gl, 4, 3) ||
                                          version >= &Version(Api::GlEs, 3, 1) ||
                                          extensions.gl_arb_compute_shader
@@ -586,6 +679,334 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
     }
 }
 
+/// Structured report of the capabilities and limits of the backend.
+///
+/// This is returned by `Context::capabilities()`. The `Display` implementation prints the
+/// report in a plain text format that can be attached to bug reports. With the `serde` feature,
+/// the report implements `serde::Serialize` so that it can be stored in a structured format.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CapabilitiesReport {
+    /// Version of OpenGL of the backend.
+    pub opengl_version: Version,
+
+    /// Versions of GLSL that are supported by the compiler.
+    pub supported_glsl_versions: Vec<Version>,
+
+    /// Value of `GL_VERSION`.
+    pub version_string: String,
+
+    /// Value of `GL_VENDOR`.
+    pub vendor: String,
+
+    /// Value of `GL_RENDERER`.
+    pub renderer: String,
+
+    /// The OpenGL context profile, if available.
+    pub profile: Option<Profile>,
+
+    /// True if the context is in debug mode.
+    pub debug: bool,
+
+    /// True if the context is forward-compatible.
+    pub forward_compatible: bool,
+
+    /// True if out-of-bound access on the GPU side can't result in crashes.
+    pub robustness: bool,
+
+    /// True if out-of-bound access on the GPU side has a defined behavior.
+    pub robust_buffer_access_behavior: bool,
+
+    /// True if errors are not reported by the context.
+    pub no_error: bool,
+
+    /// True if it is possible for the OpenGL context to be lost.
+    pub can_lose_context: bool,
+
+    /// Maximum width and height of 1D and 2D textures.
+    pub max_texture_size: gl::types::GLint,
+
+    /// Maximum width, height and depth of 3D textures. `None` if not supported.
+    pub max_3d_texture_size: Option<gl::types::GLint>,
+
+    /// Maximum width and height of cubemaps.
+    pub max_cube_map_texture_size: gl::types::GLint,
+
+    /// Maximum number of layers of array textures. `None` if not supported.
+    pub max_array_texture_layers: Option<gl::types::GLint>,
+
+    /// Maximum width and height of renderbuffers. `None` if not supported.
+    pub max_renderbuffer_size: Option<gl::types::GLint>,
+
+    /// Maximum number of samples of multisample textures and renderbuffers. `None` if
+    /// multisampling is not supported.
+    pub max_samples: Option<gl::types::GLint>,
+
+    /// Maximum value for the anisotropy of samplers. `None` if not supported.
+    pub max_texture_max_anisotropy: Option<gl::types::GLfloat>,
+
+    /// Maximum number of textures that can be bound to a program.
+    pub max_combined_texture_image_units: gl::types::GLint,
+
+    /// Maximum number of textures that can be accessed from the fragment shader.
+    pub max_texture_image_units: gl::types::GLint,
+
+    /// Number of image units. `0` if image load and store is not supported.
+    pub max_image_units: gl::types::GLint,
+
+    /// Maximum number of vertex attributes.
+    pub max_vertex_attribs: gl::types::GLint,
+
+    /// Maximum size in bytes of a uniform block. `None` if uniform buffers are not supported.
+    pub max_uniform_block_size: Option<gl::types::GLint>,
+
+    /// Number of available buffer bind points for `GL_UNIFORM_BUFFER`.
+    pub max_uniform_buffer_bindings: gl::types::GLint,
+
+    /// Maximum size in bytes of a shader storage block. `None` if shader storage buffers are
+    /// not supported.
+    pub max_shader_storage_block_size: Option<gl::types::GLint>,
+
+    /// Number of available buffer bind points for `GL_SHADER_STORAGE_BUFFER`.
+    pub max_shader_storage_buffer_bindings: gl::types::GLint,
+
+    /// Maximum number of elements that can be passed with `glDrawBuffers`.
+    pub max_draw_buffers: gl::types::GLint,
+
+    /// Maximum number of color attachments of a framebuffer.
+    pub max_color_attachments: gl::types::GLint,
+
+    /// Maximum width and height of the viewport.
+    pub max_viewport_dims: (gl::types::GLint, gl::types::GLint),
+
+    /// Maximum number of vertices per patch. `None` if tessellation is not supported.
+    pub max_patch_vertices: Option<gl::types::GLint>,
+
+    /// Maximum number of work groups of a compute dispatch. `None` if compute shaders are not
+    /// supported.
+    pub max_compute_work_group_count: Option<(gl::types::GLint, gl::types::GLint,
+                                              gl::types::GLint)>,
+
+    /// Maximum size of a work group. `None` if compute shaders are not supported.
+    pub max_compute_work_group_size: Option<(gl::types::GLint, gl::types::GLint,
+                                             gl::types::GLint)>,
+
+    /// Maximum number of invocations in a work group. `None` if compute shaders are not
+    /// supported.
+    pub max_compute_work_group_invocations: Option<gl::types::GLint>,
+
+    /// Maximum size in bytes of the shared memory of a work group. `None` if compute shaders
+    /// are not supported.
+    pub max_compute_shared_memory_size: Option<gl::types::GLint>,
+
+    /// List of extensions supported by the backend, as reported by the driver, without the
+    /// disabled ones.
+    pub extensions: Vec<String>,
+}
+
+impl fmt::Display for CapabilitiesReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fn write_opt<T: fmt::Debug>(fmt: &mut fmt::Formatter, name: &str, value: &Option<T>)
+                                    -> fmt::Result
+        {
+            match *value {
+                Some(ref v) => writeln!(fmt, "{}: {:?}", name, v),
+                None => writeln!(fmt, "{}: unsupported", name),
+            }
+        }
+
+        try!(writeln!(fmt, "OpenGL version: {:?}", self.opengl_version));
+        try!(writeln!(fmt, "GLSL versions: {:?}", self.supported_glsl_versions));
+        try!(writeln!(fmt, "GL_VERSION: {}", self.version_string));
+        try!(writeln!(fmt, "GL_VENDOR: {}", self.vendor));
+        try!(writeln!(fmt, "GL_RENDERER: {}", self.renderer));
+        try!(write_opt(fmt, "profile", &self.profile));
+        try!(writeln!(fmt, "debug: {}", self.debug));
+        try!(writeln!(fmt, "forward compatible: {}", self.forward_compatible));
+        try!(writeln!(fmt, "robustness: {}", self.robustness));
+        try!(writeln!(fmt, "robust buffer access behavior: {}",
+                      self.robust_buffer_access_behavior));
+        try!(writeln!(fmt, "no error: {}", self.no_error));
+        try!(writeln!(fmt, "can lose context: {}", self.can_lose_context));
+        try!(writeln!(fmt, "max texture size: {}", self.max_texture_size));
+        try!(write_opt(fmt, "max 3D texture size", &self.max_3d_texture_size));
+        try!(writeln!(fmt, "max cubemap size: {}", self.max_cube_map_texture_size));
+        try!(write_opt(fmt, "max array texture layers", &self.max_array_texture_layers));
+        try!(write_opt(fmt, "max renderbuffer size", &self.max_renderbuffer_size));
+        try!(write_opt(fmt, "max samples", &self.max_samples));
+        try!(write_opt(fmt, "max anisotropy", &self.max_texture_max_anisotropy));
+        try!(writeln!(fmt, "max combined texture image units: {}",
+                      self.max_combined_texture_image_units));
+        try!(writeln!(fmt, "max texture image units: {}", self.max_texture_image_units));
+        try!(writeln!(fmt, "max image units: {}", self.max_image_units));
+        try!(writeln!(fmt, "max vertex attribs: {}", self.max_vertex_attribs));
+        try!(write_opt(fmt, "max uniform block size", &self.max_uniform_block_size));
+        try!(writeln!(fmt, "max uniform buffer bindings: {}", self.max_uniform_buffer_bindings));
+        try!(write_opt(fmt, "max shader storage block size",
+                       &self.max_shader_storage_block_size));
+        try!(writeln!(fmt, "max shader storage buffer bindings: {}",
+                      self.max_shader_storage_buffer_bindings));
+        try!(writeln!(fmt, "max draw buffers: {}", self.max_draw_buffers));
+        try!(writeln!(fmt, "max color attachments: {}", self.max_color_attachments));
+        try!(writeln!(fmt, "max viewport dimensions: {:?}", self.max_viewport_dims));
+        try!(write_opt(fmt, "max patch vertices", &self.max_patch_vertices));
+        try!(write_opt(fmt, "max compute work group count", &self.max_compute_work_group_count));
+        try!(write_opt(fmt, "max compute work group size", &self.max_compute_work_group_size));
+        try!(write_opt(fmt, "max compute work group invocations",
+                       &self.max_compute_work_group_invocations));
+        try!(write_opt(fmt, "max compute shared memory size",
+                       &self.max_compute_shared_memory_size));
+
+        try!(writeln!(fmt, "extensions ({}):", self.extensions.len()));
+        for extension in &self.extensions {
+            try!(writeln!(fmt, "  {}", extension));
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a report of the capabilities of the backend.
+///
+/// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
+pub unsafe fn get_capabilities_report(gl: &gl::Gl, version: &Version,
+                                      extensions: &ExtensionsList, extension_names: &[String],
+                                      capabilities: &Capabilities)
+                                      -> CapabilitiesReport
+{
+    let get = |name| {
+        let mut val = 0;
+        gl.GetIntegerv(name, &mut val);
+        val
+    };
+
+    let get_indexed = |name| {
+        let mut val = (0, 0, 0);
+        gl.GetIntegeri_v(name, 0, &mut val.0);
+        gl.GetIntegeri_v(name, 1, &mut val.1);
+        gl.GetIntegeri_v(name, 2, &mut val.2);
+        val
+    };
+
+    let compute = version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+                  extensions.gl_arb_compute_shader;
+
+    CapabilitiesReport {
+        opengl_version: *version,
+        supported_glsl_versions: capabilities.supported_glsl_versions.clone(),
+        version_string: capabilities.version.clone(),
+        vendor: capabilities.vendor.clone(),
+        renderer: capabilities.renderer.clone(),
+        profile: capabilities.profile,
+        debug: capabilities.debug,
+        forward_compatible: capabilities.forward_compatible,
+        robustness: capabilities.robustness,
+        robust_buffer_access_behavior: capabilities.robust_buffer_access_behavior,
+        no_error: capabilities.no_error,
+        can_lose_context: capabilities.can_lose_context,
+
+        max_texture_size: get(gl::MAX_TEXTURE_SIZE),
+
+        max_3d_texture_size: if version >= &Version(Api::Gl, 1, 2) ||
+                                version >= &Version(Api::GlEs, 3, 0) ||
+                                extensions.gl_ext_texture3d || extensions.gl_oes_texture_3d
+        {
+            Some(get(gl::MAX_3D_TEXTURE_SIZE))
+        } else {
+            None
+        },
+
+        max_cube_map_texture_size: get(gl::MAX_CUBE_MAP_TEXTURE_SIZE),
+
+        max_array_texture_layers: if version >= &Version(Api::Gl, 3, 0) ||
+                                     version >= &Version(Api::GlEs, 3, 0) ||
+                                     extensions.gl_ext_texture_array
+        {
+            Some(get(gl::MAX_ARRAY_TEXTURE_LAYERS))
+        } else {
+            None
+        },
+
+        max_renderbuffer_size: if version >= &Version(Api::Gl, 3, 0) ||
+                                  version >= &Version(Api::GlEs, 2, 0) ||
+                                  extensions.gl_arb_framebuffer_object ||
+                                  extensions.gl_ext_framebuffer_object
+        {
+            Some(get(gl::MAX_RENDERBUFFER_SIZE))
+        } else {
+            None
+        },
+
+        max_samples: if version >= &Version(Api::Gl, 3, 0) ||
+                        version >= &Version(Api::GlEs, 3, 0) ||
+                        extensions.gl_arb_framebuffer_object ||
+                        extensions.gl_ext_framebuffer_multisample
+        {
+            Some(get(gl::MAX_SAMPLES))
+        } else {
+            None
+        },
+
+        max_texture_max_anisotropy: capabilities.max_texture_max_anisotropy,
+        max_combined_texture_image_units: capabilities.max_combined_texture_image_units,
+        max_texture_image_units: capabilities.max_texture_image_units,
+        max_image_units: capabilities.max_image_units,
+        max_vertex_attribs: get(gl::MAX_VERTEX_ATTRIBS),
+
+        max_uniform_block_size: if version >= &Version(Api::Gl, 3, 1) ||
+                                   version >= &Version(Api::GlEs, 3, 0) ||
+                                   extensions.gl_arb_uniform_buffer_object
+        {
+            Some(get(gl::MAX_UNIFORM_BLOCK_SIZE))
+        } else {
+            None
+        },
+
+        max_uniform_buffer_bindings: capabilities.max_indexed_uniform_buffer,
+
+        max_shader_storage_block_size: if version >= &Version(Api::Gl, 4, 3) ||
+                                          version >= &Version(Api::GlEs, 3, 1) ||
+                                          extensions.gl_arb_shader_storage_buffer_object
+        {
+            Some(get(gl::MAX_SHADER_STORAGE_BLOCK_SIZE))
+        } else {
+            None
+        },
+
+        max_shader_storage_buffer_bindings: capabilities.max_indexed_shader_storage_buffer,
+        max_draw_buffers: capabilities.max_draw_buffers,
+        max_color_attachments: capabilities.max_color_attachments,
+        max_viewport_dims: capabilities.max_viewport_dims,
+        max_patch_vertices: capabilities.max_patch_vertices,
+
+        max_compute_work_group_count: if compute {
+            Some(capabilities.max_compute_work_group_count)
+        } else {
+            None
+        },
+
+        max_compute_work_group_size: if compute {
+            Some(get_indexed(gl::MAX_COMPUTE_WORK_GROUP_SIZE))
+        } else {
+            None
+        },
+
+        max_compute_work_group_invocations: if compute {
+            Some(get(gl::MAX_COMPUTE_WORK_GROUP_INVOCATIONS))
+        } else {
+            None
+        },
+
+        max_compute_shared_memory_size: if compute {
+            Some(get(gl::MAX_COMPUTE_SHARED_MEMORY_SIZE))
+        } else {
+            None
+        },
+
+        extensions: extension_names.to_vec(),
+    }
+}
+
 /// Gets the list of GLSL versions supported by the backend.
 ///
 /// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
@@ -13,27 +13,15 @@ macro_rules! extensions {
             )+
         }
 
-        /// Returns the list of extensions supported by the backend.
-        ///
-        /// The version must match the one of the backend.
-        ///
-        /// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
-        ///
-        /// ## Panic
-        ///
-        /// Can panic if the version number doesn't match the backend, leading to unloaded functions
-        /// being called.
-        ///
-        pub unsafe fn get_extensions(gl: &gl::Gl, version: &Version) -> ExtensionsList {
-            let strings = get_extensions_strings(gl, version);
-
+        /// Builds the list of extensions from their names.
+        pub fn parse_extensions(strings: &[String]) -> ExtensionsList {
             let mut extensions = ExtensionsList {
                 $(
                     $field: false,
                 )+
             };
 
-            for extension in strings.into_iter() {
+            for extension in strings {
                 match &extension[..] {
                     $(
                         $string => extensions.$field = true,
@@ -50,12 +38,19 @@ macro_rules! extensions {
 extensions! {
     "GL_AMD_depth_clamp_separate" => gl_amd_depth_clamp_separate,
     "GL_AMD_query_buffer_object" => gl_amd_query_buffer_object,
+    "GL_ANGLE_depth_texture" => gl_angle_depth_texture,
+    "GL_ANGLE_framebuffer_blit" => gl_angle_framebuffer_blit,
     "GL_ANGLE_framebuffer_multisample" => gl_angle_framebuffer_multisample,
+    "GL_ANGLE_instanced_arrays" => gl_angle_instanced_arrays,
+    "GL_ANGLE_texture_compression_dxt3" => gl_angle_texture_compression_dxt3,
+    "GL_ANGLE_texture_compression_dxt5" => gl_angle_texture_compression_dxt5,
     "GL_APPLE_framebuffer_multisample" => gl_apple_framebuffer_multisample,
     "GL_APPLE_sync" => gl_apple_sync,
     "GL_APPLE_vertex_array_object" => gl_apple_vertex_array_object,
     "GL_ARB_bindless_texture" => gl_arb_bindless_texture,
     "GL_ARB_buffer_storage" => gl_arb_buffer_storage,
+    "GL_ARB_cl_event" => gl_arb_cl_event,
+    "GL_ARB_clear_texture" => gl_arb_clear_texture,
     "GL_ARB_compute_shader" => gl_arb_compute_shader,
     "GL_ARB_copy_buffer" => gl_arb_copy_buffer,
     "GL_ARB_debug_output" => gl_arb_debug_output,
@@ -82,6 +77,7 @@ extensions! {
     "GL_ARB_invalidate_subdata" => gl_arb_invalidate_subdata,
     "GL_ARB_occlusion_query" => gl_arb_occlusion_query,
     "GL_ARB_occlusion_query2" => gl_arb_occlusion_query2,
+    "GL_ARB_pipeline_statistics_query" => gl_arb_pipeline_statistics_query,
     "GL_ARB_pixel_buffer_object" => gl_arb_pixel_buffer_object,
     "GL_ARB_program_interface_query" => gl_arb_program_interface_query,
     "GL_ARB_query_buffer_object" => gl_arb_query_buffer_object,
@@ -114,6 +110,7 @@ extensions! {
     "GL_ARB_transform_feedback3" => gl_arb_transform_feedback3,
     "GL_ARB_uniform_buffer_object" => gl_arb_uniform_buffer_object,
     "GL_ARB_vertex_array_object" => gl_arb_vertex_array_object,
+    "GL_ARB_vertex_attrib_binding" => gl_arb_vertex_attrib_binding,
     "GL_ARB_vertex_buffer_object" => gl_arb_vertex_buffer_object,
     "GL_ARB_vertex_half_float" => gl_arb_vertex_half_float,
     "GL_ARB_vertex_shader" => gl_arb_vertex_shader,
@@ -135,6 +132,9 @@ extensions! {
     "GL_EXT_geometry_shader" => gl_ext_geometry_shader,
     "GL_EXT_geometry_shader4" => gl_ext_geometry_shader4,
     "GL_EXT_gpu_shader4" => gl_ext_gpu_shader4,
+    "GL_EXT_memory_object" => gl_ext_memory_object,
+    "GL_EXT_memory_object_fd" => gl_ext_memory_object_fd,
+    "GL_EXT_memory_object_win32" => gl_ext_memory_object_win32,
     "GL_EXT_multi_draw_indirect" => gl_ext_multi_draw_indirect,
     "GL_EXT_multisampled_render_to_texture" => gl_ext_multisampled_render_to_texture,
     "GL_EXT_occlusion_query_boolean" => gl_ext_occlusion_query_boolean,
@@ -142,12 +142,17 @@ extensions! {
     "GL_EXT_packed_float" => gl_ext_packed_float,
     "GL_EXT_primitive_bounding_box" => gl_ext_primitive_bounding_box,
     "GL_EXT_provoking_vertex" => gl_ext_provoking_vertex,
+    "GL_EXT_read_format_bgra" => gl_ext_read_format_bgra,
     "GL_EXT_robustness" => gl_ext_robustness,
     "GL_EXT_sRGB_write_control" => gl_ext_srgb_write_control,
+    "GL_EXT_semaphore" => gl_ext_semaphore,
+    "GL_EXT_semaphore_fd" => gl_ext_semaphore_fd,
+    "GL_EXT_semaphore_win32" => gl_ext_semaphore_win32,
     "GL_EXT_texture3D" => gl_ext_texture3d,
     "GL_EXT_texture_array" => gl_ext_texture_array,
     "GL_EXT_texture_buffer" => gl_ext_texture_buffer,
     "GL_EXT_texture_buffer_object" => gl_ext_texture_buffer_object,
+    "GL_EXT_texture_compression_dxt1" => gl_ext_texture_compression_dxt1,
     "GL_EXT_texture_compression_s3tc" => gl_ext_texture_compression_s3tc,
     "GL_EXT_texture_cube_map" => gl_ext_texture_cube_map,
     "GL_EXT_texture_cube_map_array" => gl_ext_texture_cube_map_array,
@@ -157,9 +162,11 @@ extensions! {
     "GL_EXT_texture_snorm" => gl_ext_texture_snorm,
     "GL_EXT_texture_sRGB" => gl_ext_texture_srgb,
     "GL_EXT_transform_feedback" => gl_ext_transform_feedback,
+    "GL_EXT_unpack_subimage" => gl_ext_unpack_subimage,
     "GL_GREMEDY_string_marker" => gl_gremedy_string_marker,
     "GL_KHR_debug" => gl_khr_debug,
     "GL_KHR_context_flush_control" => gl_khr_context_flush_control,
+    "GL_KHR_no_error" => gl_khr_no_error,
     "GL_KHR_robustness" => gl_khr_robustness,
     "GL_KHR_robust_buffer_access_behavior" => gl_khr_robust_buffer_access_behavior,
     "GL_NV_fbo_color_attachments" => gl_nv_fbo_color_attachments,
@@ -169,6 +176,7 @@ extensions! {
     "GL_NV_framebuffer_multisample" => gl_nv_framebuffer_multisample,
     "GL_NV_half_float" => gl_nv_half_float,
     "GL_NV_internalformat_sample_query" => gl_nv_internalformat_sample_query,
+    "GL_NV_pack_subimage" => gl_nv_pack_subimage,
     "GL_NV_pixel_buffer_object" => gl_nv_pixel_buffer_object,
     "GL_NV_read_depth" => gl_nv_read_depth,
     "GL_NV_read_stencil" => gl_nv_read_stencil,
@@ -181,6 +189,7 @@ extensions! {
     "GL_NVX_gpu_memory_info" => gl_nvx_gpu_memory_info,
     "GL_OES_depth_texture" => gl_oes_depth_texture,
     "GL_OES_draw_elements_base_vertex" => gl_oes_draw_elements_base_vertex,
+    "GL_OES_EGL_image" => gl_oes_egl_image,
     "GL_OES_element_index_uint" => gl_oes_element_index_uint,
     "GL_OES_fixed_point" => gl_oes_fixed_point,
     "GL_OES_geometry_shader" => gl_oes_geometry_shader,
@@ -211,7 +220,7 @@ extensions! {
 /// Can panic if the version number doesn't match the backend, leading to unloaded functions
 /// being called.
 ///
-unsafe fn get_extensions_strings(gl: &gl::Gl, version: &Version) -> Vec<String> {
+pub unsafe fn get_extensions_strings(gl: &gl::Gl, version: &Version) -> Vec<String> {
     if version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) {
         let mut num_extensions = 0;
         gl.GetIntegerv(gl::NUM_EXTENSIONS, &mut num_extensions);
@@ -0,0 +1,181 @@
+//! Records the slower code paths that glium takes when the backend lacks a feature.
+
+use std::cell::Cell;
+use std::fmt;
+
+/// A slower code path that glium takes silently when the backend lacks a feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Fallback {
+    /// The backend doesn't support sync objects, so glium has waited for all the commands to
+    /// finish with `glFinish` instead of inserting a fence.
+    FinishInsteadOfFence,
+
+    /// A buffer created with `BufferMode::Persistent` couldn't be persistently mapped, because
+    /// the backend doesn't support `GL_ARB_buffer_storage`.
+    NoPersistentMapping,
+
+    /// A buffer has been mapped by copying its content to a temporary buffer, and back when the
+    /// mapping was dropped.
+    MappingThroughTemporaryBuffer,
+
+    /// A buffer with an immutable storage has been modified by copying the data from a temporary
+    /// buffer.
+    UploadThroughTemporaryBuffer,
+
+    /// The backend doesn't support vertex array objects, so the vertex attributes have been
+    /// bound before a draw command.
+    NoVertexArrayObjects,
+
+    /// A texture has been read by drawing it on a framebuffer, because the backend doesn't
+    /// support `glGetTexImage`.
+    TextureReadThroughFramebuffer,
+}
+
+/// All the fallbacks, in the order of their discriminants.
+const FALLBACKS: [Fallback; 6] = [
+    Fallback::FinishInsteadOfFence,
+    Fallback::NoPersistentMapping,
+    Fallback::MappingThroughTemporaryBuffer,
+    Fallback::UploadThroughTemporaryBuffer,
+    Fallback::NoVertexArrayObjects,
+    Fallback::TextureReadThroughFramebuffer,
+];
+
+impl Fallback {
+    /// Returns a short description of the fallback.
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Fallback::FinishInsteadOfFence => "glFinish has been called instead of using a fence",
+            Fallback::NoPersistentMapping => "a persistent buffer couldn't be persistently mapped",
+            Fallback::MappingThroughTemporaryBuffer => {
+                "a buffer has been mapped through a temporary buffer"
+            },
+            Fallback::UploadThroughTemporaryBuffer => {
+                "an immutable buffer has been modified through a temporary buffer"
+            },
+            Fallback::NoVertexArrayObjects => {
+                "the vertex attributes have been bound without a vertex array object"
+            },
+            Fallback::TextureReadThroughFramebuffer => {
+                "a texture has been read by drawing it on a framebuffer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for Fallback {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Counts the number of times that each fallback has been taken by a context.
+pub struct FallbackCounters {
+    counts: [Cell<usize>; 6],
+}
+
+impl FallbackCounters {
+    /// Builds counters that are all zero.
+    #[inline]
+    pub fn new() -> FallbackCounters {
+        FallbackCounters {
+            counts: Default::default(),
+        }
+    }
+
+    /// Records that a fallback has been taken.
+    #[inline]
+    pub fn record(&self, fallback: Fallback) {
+        let count = &self.counts[fallback as usize];
+        count.set(count.get() + 1);
+    }
+
+    /// Builds a report of the fallbacks that have been taken.
+    pub fn report(&self) -> FallbackReport {
+        FallbackReport {
+            fallbacks: FALLBACKS.iter().zip(self.counts.iter())
+                                .filter(|&(_, count)| count.get() != 0)
+                                .map(|(&fallback, count)| (fallback, count.get()))
+                                .collect(),
+        }
+    }
+
+    /// Resets all the counters to zero.
+    #[inline]
+    pub fn clear(&self) {
+        for count in self.counts.iter() {
+            count.set(0);
+        }
+    }
+}
+
+/// Report of the slower code paths that a context has taken because the backend lacks a
+/// feature.
+///
+/// The report can be printed with `{}` to find out which fallbacks an application hits on a
+/// given machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackReport {
+    /// The fallbacks that have been taken at least once, with the number of times.
+    pub fallbacks: Vec<(Fallback, usize)>,
+}
+
+impl FallbackReport {
+    /// Returns true if no fallback has been taken.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fallbacks.is_empty()
+    }
+
+    /// Returns the number of times that the given fallback has been taken.
+    #[inline]
+    pub fn count(&self, fallback: Fallback) -> usize {
+        self.fallbacks.iter().find(|&&(f, _)| f == fallback).map(|&(_, c)| c).unwrap_or(0)
+    }
+}
+
+impl fmt::Display for FallbackReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.fallbacks.is_empty() {
+            return writeln!(fmt, "no fallback has been taken");
+        }
+
+        for &(fallback, count) in &self.fallbacks {
+            try!(writeln!(fmt, "{}: {} time(s)", fallback, count));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fallback, FallbackCounters, FALLBACKS};
+
+    #[test]
+    fn discriminants_match_the_list() {
+        for (index, &fallback) in FALLBACKS.iter().enumerate() {
+            assert_eq!(fallback as usize, index);
+        }
+    }
+
+    #[test]
+    fn report_counts() {
+        let counters = FallbackCounters::new();
+        assert!(counters.report().is_empty());
+
+        counters.record(Fallback::NoVertexArrayObjects);
+        counters.record(Fallback::NoVertexArrayObjects);
+        counters.record(Fallback::FinishInsteadOfFence);
+
+        let report = counters.report();
+        assert_eq!(report.count(Fallback::NoVertexArrayObjects), 2);
+        assert_eq!(report.count(Fallback::FinishInsteadOfFence), 1);
+        assert_eq!(report.count(Fallback::NoPersistentMapping), 0);
+        assert_eq!(report.fallbacks.len(), 2);
+
+        counters.clear();
+        assert!(counters.report().is_empty());
+    }
+}
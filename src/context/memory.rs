@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::BuildHasherDefault;
+use std::time::{Duration, Instant};
+
+use fnv::FnvHasher;
+
+use gl;
+
+/// Information about the video memory used by the application.
+///
+/// The amounts are in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryInfo {
+    /// Total amount of video memory, if the backend reports it.
+    ///
+    /// Requires `GL_NVX_gpu_memory_info`.
+    pub total_video_memory: Option<usize>,
+
+    /// Estimate of the amount of video memory that is still available, if the backend reports
+    /// it.
+    ///
+    /// Requires `GL_NVX_gpu_memory_info` or `GL_ATI_meminfo`. With `GL_ATI_meminfo`, this is
+    /// the memory available for textures.
+    pub free_video_memory: Option<usize>,
+
+    /// Amount of memory that was evicted from the video memory since the context was created,
+    /// if the backend reports it.
+    ///
+    /// Requires `GL_NVX_gpu_memory_info`.
+    pub evicted_memory: Option<usize>,
+
+    /// Number of buffers that were created by glium and are still alive.
+    pub buffers_count: usize,
+
+    /// Total size of the buffers that were created by glium and are still alive.
+    pub buffers_memory: usize,
+
+    /// Number of textures that were created by glium and are still alive.
+    pub textures_count: usize,
+
+    /// Estimate of the total size of the textures that were created by glium and are still
+    /// alive.
+    pub textures_memory: usize,
+}
+
+/// Kind of an allocation made by glium.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AllocationKind {
+    /// A buffer.
+    Buffer,
+    /// A texture.
+    Texture,
+}
+
+/// An object created by glium that is still alive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Allocation {
+    /// Kind of object.
+    pub kind: AllocationKind,
+
+    /// OpenGL identifier of the object.
+    pub id: gl::types::GLuint,
+
+    /// Size of the object in bytes. For textures this is an estimate, as the driver is free to
+    /// pick a different internal format or to add padding.
+    pub size: usize,
+
+    /// The name given to the object with its `set_object_label` method, if any.
+    pub name: Option<String>,
+
+    /// The format that was requested when creating a texture. `None` for buffers.
+    pub format: Option<String>,
+
+    /// Time elapsed since the object was created.
+    pub age: Duration,
+}
+
+impl fmt::Display for Allocation {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(fmt, "{:?} #{}", self.kind, self.id));
+
+        if let Some(ref name) = self.name {
+            try!(write!(fmt, " \"{}\"", name));
+        }
+
+        try!(write!(fmt, ": {} bytes", self.size));
+
+        if let Some(ref format) = self.format {
+            try!(write!(fmt, ", {}", format));
+        }
+
+        write!(fmt, ", created {}.{:03}s ago", self.age.as_secs(),
+               self.age.subsec_nanos() / 1000000)
+    }
+}
+
+/// Informations about an object of the registry.
+struct Entry {
+    size: usize,
+    name: Option<String>,
+    format: Option<String>,
+    created: Instant,
+}
+
+/// Registry of the buffers and textures that are alive.
+pub struct Allocations {
+    buffers: HashMap<gl::types::GLuint, Entry, BuildHasherDefault<FnvHasher>>,
+    textures: HashMap<gl::types::GLuint, Entry, BuildHasherDefault<FnvHasher>>,
+}
+
+impl Allocations {
+    #[inline]
+    pub fn new() -> Allocations {
+        Allocations {
+            buffers: HashMap::with_hasher(Default::default()),
+            textures: HashMap::with_hasher(Default::default()),
+        }
+    }
+
+    /// Adds an object to the registry.
+    #[inline]
+    pub fn insert(&mut self, kind: AllocationKind, id: gl::types::GLuint, size: usize,
+                  format: Option<String>)
+    {
+        self.map(kind).insert(id, Entry {
+            size: size,
+            name: None,
+            format: format,
+            created: Instant::now(),
+        });
+    }
+
+    /// Sets the name of an object. Does nothing if it isn't registered.
+    #[inline]
+    pub fn set_name(&mut self, kind: AllocationKind, id: gl::types::GLuint, name: &str) {
+        if let Some(entry) = self.map(kind).get_mut(&id) {
+            entry.name = Some(name.to_owned());
+        }
+    }
+
+    /// Removes an object from the registry. Does nothing if it wasn't registered.
+    #[inline]
+    pub fn remove(&mut self, kind: AllocationKind, id: gl::types::GLuint) {
+        self.map(kind).remove(&id);
+    }
+
+    /// Returns the number of objects of the given kind and their total size.
+    pub fn totals(&self, kind: AllocationKind) -> (usize, usize) {
+        let map = match kind {
+            AllocationKind::Buffer => &self.buffers,
+            AllocationKind::Texture => &self.textures,
+        };
+
+        (map.len(), map.values().map(|e| e.size).sum())
+    }
+
+    /// Returns the list of all the objects, sorted by kind and identifier.
+    pub fn list(&self) -> Vec<Allocation> {
+        let now = Instant::now();
+
+        let entry_to_allocation = |kind, (&id, entry): (&gl::types::GLuint, &Entry)| {
+            Allocation {
+                kind: kind,
+                id: id,
+                size: entry.size,
+                name: entry.name.clone(),
+                format: entry.format.clone(),
+                age: now.duration_since(entry.created),
+            }
+        };
+
+        let buffers = self.buffers.iter().map(|e| entry_to_allocation(AllocationKind::Buffer, e));
+        let textures = self.textures.iter()
+                                    .map(|e| entry_to_allocation(AllocationKind::Texture, e));
+
+        let mut list: Vec<_> = buffers.chain(textures).collect();
+        list.sort_by_key(|a| (a.kind == AllocationKind::Texture, a.id));
+        list
+    }
+
+    #[inline]
+    fn map(&mut self, kind: AllocationKind)
+           -> &mut HashMap<gl::types::GLuint, Entry, BuildHasherDefault<FnvHasher>>
+    {
+        match kind {
+            AllocationKind::Buffer => &mut self.buffers,
+            AllocationKind::Texture => &mut self.textures,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Allocations, AllocationKind};
+
+    #[test]
+    fn totals() {
+        let mut allocs = Allocations::new();
+        allocs.insert(AllocationKind::Buffer, 1, 64, None);
+        allocs.insert(AllocationKind::Buffer, 2, 32, None);
+        allocs.insert(AllocationKind::Texture, 1, 1024, None);
+        assert_eq!(allocs.totals(AllocationKind::Buffer), (2, 96));
+        assert_eq!(allocs.totals(AllocationKind::Texture), (1, 1024));
+
+        allocs.remove(AllocationKind::Buffer, 1);
+        allocs.remove(AllocationKind::Texture, 5);
+        assert_eq!(allocs.totals(AllocationKind::Buffer), (1, 32));
+        assert_eq!(allocs.list().len(), 2);
+        assert_eq!(allocs.list()[0].kind, AllocationKind::Buffer);
+    }
+
+    #[test]
+    fn names_and_formats() {
+        let mut allocs = Allocations::new();
+        allocs.insert(AllocationKind::Buffer, 3, 64, None);
+        allocs.insert(AllocationKind::Texture, 1, 1024, Some("U8U8U8U8".to_owned()));
+        allocs.set_name(AllocationKind::Texture, 1, "shadow map");
+        allocs.set_name(AllocationKind::Texture, 2, "unknown");
+
+        let list = allocs.list();
+        assert_eq!(list[0].name, None);
+        assert_eq!(list[1].name, Some("shadow map".to_owned()));
+
+        let line = list[1].to_string();
+        assert!(line.starts_with("Texture #1 \"shadow map\": 1024 bytes, U8U8U8U8, created "),
+                "{}", line);
+        assert!(list[0].to_string().starts_with("Buffer #3: 64 bytes, created "));
+    }
+}
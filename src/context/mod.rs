@@ -3,7 +3,7 @@
 use gl;
 use backtrace;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::mem;
 use std::ptr;
 use std::str;
@@ -12,6 +12,8 @@ use std::cell::{Cell, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::ffi::CStr;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::os::raw;
 use std::hash::BuildHasherDefault;
 
@@ -21,25 +23,37 @@ use IncompatibleOpenGl;
 use SwapBuffersError;
 use CapabilitiesSource;
 use ContextExt;
-use backend::Backend;
+use QueryExt;
+use TransformFeedbackSessionExt;
+use backend::{Backend, SwapInterval, SwapIntervalError};
 use version;
 use version::Api;
 use version::Version;
 
+use buffer;
 use debug;
+use draw_parameters;
 use fbo;
 use ops;
 use sampler_object;
+use sync::{self, LinearSyncFence};
 use texture;
 use uniforms;
+use vertex;
 use vertex_array_object;
 
-pub use self::capabilities::{ReleaseBehavior, Capabilities, Profile};
+pub use self::capabilities::{ReleaseBehavior, Capabilities, CapabilitiesReport, Profile};
 pub use self::extensions::ExtensionsList;
-pub use self::state::GlState;
+pub use self::fallbacks::{Fallback, FallbackCounters, FallbackReport};
+pub use self::memory::{MemoryInfo, Allocation, AllocationKind};
+pub use self::state::{GlState, ImageUnitState};
+
+use self::memory::Allocations;
 
 mod capabilities;
 mod extensions;
+mod fallbacks;
+mod memory;
 mod state;
 
 /// Stores the state and information required for glium to execute commands. Most public glium
@@ -58,6 +72,9 @@ pub struct Context {
     /// Tells whether or not the backend supports each extension.
     extensions: ExtensionsList,
 
+    /// Names of the extensions supported by the backend, sorted, without the disabled ones.
+    extension_names: Vec<String>,
+
     /// Constants defined by the backend and retrieved at initialization. For example, number
     /// of texture units, maximum size of the viewport, etc.
     capabilities: Capabilities,
@@ -71,13 +88,24 @@ pub struct Context {
     check_current_context: bool,
 
     /// The callback that is used by the debug output feature.
-    debug_callback: Option<debug::DebugCallback>,
+    debug_callback: RefCell<Option<debug::DebugCallback>>,
 
     /// Whether or not errors triggered by ARB_debug_output (and similar extensions) should be
     /// reported to the user when `DebugCallbackBehavior::DebugMessageOnError` is used. This must
     /// be set to `false` in some situations, like compiling/linking shaders.
     report_debug_output_errors: Cell<bool>,
 
+    /// Whether `glGetError` must be called at the end of each glium function. Set with
+    /// `DebugCallbackBehavior::CheckErrors`.
+    check_gl_errors: Cell<bool>,
+
+    /// Error that has been returned by `glGetError` at the end of a glium function, and that
+    /// must be returned by the next call to `CommandContext::get_error`.
+    pending_gl_error: Cell<Option<gl::types::GLenum>>,
+
+    /// How much checking glium performs before each draw command.
+    validation_level: Cell<ValidationLevel>,
+
     /// We maintain a cache of FBOs.
     /// The `Option` is here in order to destroy the container. It must be filled at all time
     /// is a normal situation.
@@ -96,6 +124,33 @@ pub struct Context {
     /// List of images handles that are resident. We need to call `MakeImageHandleResidentARB`
     /// when rebuilding the context.
     resident_image_handles: RefCell<Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
+
+    /// List of the buffers and textures created by glium that are still alive, with their size.
+    allocations: RefCell<Allocations>,
+
+    /// Shared by all the contexts whose objects are shared with this one.
+    share_group: RefCell<Arc<ShareGroup>>,
+
+    /// Maximum number of frames that can be processed by the GPU after the buffers have been
+    /// swapped. `None` if this number is not limited by glium.
+    max_frames_in_flight: Cell<Option<u32>>,
+
+    /// Fences inserted after swapping the buffers, from the oldest to the newest.
+    frame_fences: RefCell<VecDeque<LinearSyncFence>>,
+
+    /// Whether the buffers and textures that are dropped are destroyed by the next call to
+    /// `collect` instead of immediately.
+    deferred_destruction: Cell<bool>,
+
+    /// Objects that have been dropped since the last call to `collect`.
+    pending_destructions: RefCell<Vec<PendingDestruction>>,
+
+    /// Objects that have been dropped before a call to `collect`, along with a fence inserted by
+    /// this call. They are destroyed once the fence is signaled, from the oldest to the newest.
+    retired_destructions: RefCell<VecDeque<(LinearSyncFence, Vec<PendingDestruction>)>>,
+
+    /// The number of times each slower code path has been taken.
+    fallbacks: FallbackCounters,
 }
 
 /// This struct is a guard that is returned when you want to access the OpenGL backend.
@@ -120,6 +175,12 @@ pub struct CommandContext<'a> {
     /// reported to the user (by panicking).
     pub report_debug_output_errors: &'a Cell<bool>,
 
+    /// How much checking glium performs before each draw command.
+    pub validation_level: ValidationLevel,
+
+    /// Records the slower code paths that are taken because the backend lacks a feature.
+    pub fallbacks: &'a FallbackCounters,
+
     /// The list of vertex array objects.
     pub vertex_array_objects: &'a vertex_array_object::VertexAttributesSystem,
 
@@ -135,6 +196,15 @@ pub struct CommandContext<'a> {
     /// List of image handles and their access that need to be made resident.
     pub resident_image_handles: RefMut<'a, Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
 
+    /// If true, `glGetError` is called when the `CommandContext` is destroyed.
+    check_gl_errors: bool,
+
+    /// The error found when the `CommandContext` is destroyed is kept here.
+    pending_gl_error: &'a Cell<Option<gl::types::GLenum>>,
+
+    /// The callback that the error found when the `CommandContext` is destroyed is reported to.
+    debug_callback: &'a RefCell<Option<debug::DebugCallback>>,
+
     /// This marker is here to prevent `CommandContext` from implementing `Send`
     // TODO: use this when possible
     //impl<'a, 'b> !Send for CommandContext<'a, 'b> {}
@@ -160,6 +230,25 @@ impl Context {
         callback_behavior: DebugCallbackBehavior,
     ) -> Result<Rc<Context>, IncompatibleOpenGl>
         where B: Backend + 'static
+    {
+        Context::with_disabled_extensions(backend, check_current_context, callback_behavior, &[])
+    }
+
+    /// Builds a new context that behaves as if the backend didn't support the given extensions,
+    /// for example `"GL_ARB_buffer_storage"`.
+    ///
+    /// This is meant to test the code paths that glium and the application use when an
+    /// extension is missing. Features that are part of the version of OpenGL of the backend
+    /// are still used.
+    ///
+    /// See `new` for the other parameters.
+    pub unsafe fn with_disabled_extensions<B>(
+        backend: B,
+        check_current_context: bool,
+        callback_behavior: DebugCallbackBehavior,
+        disabled_extensions: &[&str],
+    ) -> Result<Rc<Context>, IncompatibleOpenGl>
+        where B: Backend + 'static
     {
         backend.make_current();
 
@@ -167,7 +256,14 @@ impl Context {
         let gl_state: RefCell<GlState> = RefCell::new(Default::default());
 
         let version = version::get_gl_version(&gl);
-        let extensions = extensions::get_extensions(&gl, &version);
+        let extension_names = {
+            let mut names = extensions::get_extensions_strings(&gl, &version);
+            names.retain(|name| !name.is_empty() && !disabled_extensions.contains(&&name[..]));
+            names.sort();
+            names.dedup();
+            names
+        };
+        let extensions = extensions::parse_extensions(&extension_names);
         try!(check_gl_compatibility(&version, &extensions));
 
         let capabilities = capabilities::get_capabilities(&gl, &version, &extensions);
@@ -183,27 +279,21 @@ impl Context {
         let resident_texture_handles = RefCell::new(Vec::new());
         let resident_image_handles = RefCell::new(Vec::new());
 
-        let (debug_callback, synchronous) = match callback_behavior {
-            DebugCallbackBehavior::Ignore => (None, false),
-            DebugCallbackBehavior::DebugMessageOnError => {
-                (Some(Box::new(default_debug_callback) as debug::DebugCallback), true)
-            },
-            DebugCallbackBehavior::PrintAll => {
-                (Some(Box::new(printall_debug_callback) as debug::DebugCallback), false)
-            },
-            DebugCallbackBehavior::Custom { callback, synchronous } => {
-                (Some(callback), synchronous)
-            },
-        };
+        let check_gl_errors = Cell::new(callback_behavior.checks_errors());
+        let (debug_callback, synchronous) = callback_behavior.into_callback();
 
         let context = Rc::new(Context {
             gl: gl,
             state: gl_state,
             version: version,
             extensions: extensions,
+            extension_names: extension_names,
             capabilities: capabilities,
-            debug_callback: debug_callback,
+            debug_callback: RefCell::new(debug_callback),
             report_debug_output_errors: report_debug_output_errors,
+            check_gl_errors: check_gl_errors,
+            pending_gl_error: Cell::new(None),
+            validation_level: Cell::new(ValidationLevel::Full),
             backend: RefCell::new(Box::new(backend)),
             check_current_context: check_current_context,
             framebuffer_objects: Some(framebuffer_objects),
@@ -211,9 +301,17 @@ impl Context {
             samplers: samplers,
             resident_texture_handles: resident_texture_handles,
             resident_image_handles: resident_image_handles,
+            allocations: RefCell::new(Allocations::new()),
+            share_group: RefCell::new(Arc::new(ShareGroup::new())),
+            max_frames_in_flight: Cell::new(None),
+            frame_fences: RefCell::new(VecDeque::new()),
+            deferred_destruction: Cell::new(false),
+            pending_destructions: RefCell::new(Vec::new()),
+            retired_destructions: RefCell::new(VecDeque::new()),
+            fallbacks: FallbackCounters::new(),
         });
 
-        if context.debug_callback.is_some() {
+        if context.debug_callback.borrow().is_some() {
             init_debug_callback(&context, synchronous);
         }
 
@@ -232,12 +330,87 @@ impl Context {
         Ok(context)
     }
 
+    /// Builds a new context that renders into an OpenGL context created by another library,
+    /// like Qt, SDL2, GTK or a game engine.
+    ///
+    /// `loader` must return the address of the OpenGL function whose name is passed. The
+    /// OpenGL context must be current in the thread whenever glium is used, and glium never
+    /// makes it current itself. Since glium doesn't control the window, the frames must be
+    /// built with `Frame::new`, and `Frame::finish` doesn't swap the buffers. The dimensions
+    /// returned by `get_framebuffer_dimensions` are the ones of the viewport at the time when
+    /// the context is adopted.
+    ///
+    /// `state_assumptions` tells whether the state of the context may have been modified
+    /// before this call. If the other library keeps using the context afterwards, you must call
+    /// `reset_state` before using glium again.
+    pub unsafe fn from_raw_parts<F>(loader: F, state_assumptions: StateAssumptions)
+                                    -> Result<Rc<Context>, IncompatibleOpenGl>
+                                    where F: Fn(&str) -> *const raw::c_void + 'static
+    {
+        let dimensions = Rc::new(Cell::new((0, 0)));
+
+        let backend = RawBackend {
+            loader: Box::new(loader),
+            dimensions: dimensions.clone(),
+        };
+
+        let context = try!(Context::new(backend, false, Default::default()));
+
+        {
+            let mut ctxt = context.make_current();
+
+            let mut viewport = [0; 4];
+            ctxt.gl.GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            dimensions.set((viewport[2] as u32, viewport[3] as u32));
+
+            if state_assumptions == StateAssumptions::Unknown {
+                state::reset_state(&mut ctxt);
+            }
+        }
+
+        Ok(context)
+    }
+
     /// Calls `get_framebuffer_dimensions` on the backend object stored by this context.
     #[inline]
     pub fn get_framebuffer_dimensions(&self) -> (u32, u32) {
         self.backend.borrow().get_framebuffer_dimensions()
     }
 
+    /// Changes how much checking glium performs before each draw command.
+    ///
+    /// The default is `ValidationLevel::Full`, including with contexts created with
+    /// `GL_KHR_no_error`, where the checks of glium are the only ones left.
+    ///
+    /// # Safety
+    ///
+    /// Below `ValidationLevel::Full`, the types of the uniforms and the layouts of the uniform
+    /// blocks are no longer checked against the program. The caller must guarantee that every
+    /// draw command matches its program, otherwise the driver may read or write out of the
+    /// bounds of the buffers, which is undefined behavior.
+    #[inline]
+    pub unsafe fn set_validation_level(&self, level: ValidationLevel) {
+        self.validation_level.set(level);
+    }
+
+    /// Returns how much checking glium performs before each draw command.
+    #[inline]
+    pub fn get_validation_level(&self) -> ValidationLevel {
+        self.validation_level.get()
+    }
+
+    /// Changes how swapping the buffers is synchronized with the refresh of the screen.
+    ///
+    /// Contrary to the vsync option of the window builder, this can be changed at any time.
+    pub fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        let backend = self.backend.borrow();
+        if !backend.is_current() {
+            unsafe { backend.make_current() };
+        }
+
+        unsafe { backend.set_swap_interval(interval) }
+    }
+
     /// Changes the OpenGL context associated with this context.
     ///
     /// The new context **must** have lists shared with the old one.
@@ -317,9 +490,37 @@ impl Context {
         if let Err(SwapBuffersError::ContextLost) = err {
             self.state.borrow_mut().lost_context = true;
         }
+
+        if err.is_ok() {
+            if let Some(max) = self.max_frames_in_flight.get() {
+                self.pace_frames(max);
+            }
+        }
+
+        self.collect();
+
         err
     }
 
+    /// Inserts a fence after the frame that has just been submitted, then waits until at most
+    /// `max` frames are in flight.
+    fn pace_frames(&self, max: u32) {
+        let mut ctxt = self.make_current();
+
+        match unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+            Ok(fence) => self.frame_fences.borrow_mut().push_back(fence),
+            Err(_) => {
+                // without fences, the only way to limit the number of frames is to wait for all
+                // of them
+                unsafe { ctxt.gl.Finish(); }
+                ctxt.fallbacks.record(Fallback::FinishInsteadOfFence);
+                return;
+            },
+        }
+
+        wait_for_frames_in_flight(&mut ctxt, &mut self.frame_fences.borrow_mut(), max as usize);
+    }
+
     /// DEPRECATED. Use `get_opengl_version` instead.
     #[inline]
     pub fn get_version(&self) -> &Version {
@@ -341,7 +542,7 @@ impl Context {
     /// Returns true if the given GLSL version is supported.
     #[inline]
     pub fn is_glsl_version_supported(&self, version: &Version) -> bool {
-        self.capabilities().supported_glsl_versions.iter().find(|&v| v == version).is_some()
+        self.get_capabilities().supported_glsl_versions.iter().find(|&v| v == version).is_some()
     }
 
     /// Returns a string containing this GL version or release number used by this context.
@@ -349,13 +550,13 @@ impl Context {
     /// Vendor-specific information may follow the version number.
     #[inline]
     pub fn get_opengl_version_string(&self) -> &str {
-        &self.capabilities().version
+        &self.get_capabilities().version
     }
 
     /// Returns a string containing the company responsible for this GL implementation.
     #[inline]
     pub fn get_opengl_vendor_string(&self) -> &str {
-        &self.capabilities().vendor
+        &self.get_capabilities().vendor
     }
 
     /// Returns a string containing the name of the GL renderer used by this context.
@@ -363,7 +564,23 @@ impl Context {
     /// This name is typically specific to a particular configuration of a hardware platform.
     #[inline]
     pub fn get_opengl_renderer_string(&self) -> &str {
-        &self.capabilities().renderer
+        &self.get_capabilities().renderer
+    }
+
+    /// Returns true if the backend supports the extension with the given name, for example
+    /// `"GL_ARB_buffer_storage"`.
+    ///
+    /// Extensions that have been disabled when creating the context are not supported.
+    #[inline]
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extension_names.binary_search_by(|e| e[..].cmp(name)).is_ok()
+    }
+
+    /// Returns the sorted names of all the extensions supported by the backend, including the
+    /// ones that glium doesn't use.
+    #[inline]
+    pub fn get_extension_names(&self) -> &[String] {
+        &self.extension_names
     }
 
     /// Returns true if the context is in debug mode.
@@ -371,7 +588,7 @@ impl Context {
     /// Debug mode may provide additional error and performance issue reporting functionality.
     #[inline]
     pub fn is_debug(&self) -> bool {
-        self.capabilities().debug
+        self.get_capabilities().debug
     }
 
     /// Returns true if the context is in "forward-compatible" mode.
@@ -379,14 +596,14 @@ impl Context {
     /// Forward-compatible mode means that no deprecated functionality will be supported.
     #[inline]
     pub fn is_forward_compatible(&self) -> bool {
-        self.capabilities().forward_compatible
+        self.get_capabilities().forward_compatible
     }
 
     /// Returns this context's OpenGL profile if available.
     ///
     /// The context profile is available from OpenGL 3.2 onwards. Returns `None` if not supported.
     pub fn get_opengl_profile(&self) -> Option<Profile> {
-        self.capabilities().profile
+        self.get_capabilities().profile
     }
 
     /// Returns true if out-of-bound buffer access from the GPU side (inside a program) cannot
@@ -395,13 +612,23 @@ impl Context {
     /// You should take extra care if `is_robust` returns false.
     #[inline]
     pub fn is_robust(&self) -> bool {
-        self.capabilities().robustness
+        self.get_capabilities().robustness
+    }
+
+    /// Returns true if out-of-bound buffer access from the GPU side has a defined behavior.
+    ///
+    /// Out-of-bound reads return values from inside the buffer or zero, and out-of-bound writes
+    /// are discarded or stay inside the buffer. This is the case for vertices that are fetched
+    /// with an out-of-bound index, which makes it possible to draw with untrusted index data.
+    #[inline]
+    pub fn has_robust_buffer_access_behavior(&self) -> bool {
+        self.get_capabilities().robust_buffer_access_behavior
     }
 
     /// Returns true if a context loss is possible.
     #[inline]
     pub fn is_context_loss_possible(&self) -> bool {
-        self.capabilities().can_lose_context
+        self.get_capabilities().can_lose_context
     }
 
     /// Returns true if the context has been lost and needs to be recreated.
@@ -417,22 +644,7 @@ impl Context {
         }
 
         let mut ctxt = self.make_current();
-
-        let lost = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
-                      ctxt.version >= &Version(Api::GlEs, 3, 2) ||
-                      ctxt.extensions.gl_khr_robustness
-        {
-            unsafe { ctxt.gl.GetGraphicsResetStatus() != gl::NO_ERROR }
-        } else if ctxt.extensions.gl_ext_robustness {
-            unsafe { ctxt.gl.GetGraphicsResetStatusEXT() != gl::NO_ERROR }
-        } else if ctxt.extensions.gl_arb_robustness {
-            unsafe { ctxt.gl.GetGraphicsResetStatusARB() != gl::NO_ERROR }
-        } else {
-            false
-        };
-
-        if lost { ctxt.state.lost_context = true; }
-        lost
+        query_context_lost(&mut ctxt)
     }
 
     /// Returns the behavior when the current OpenGL context is changed.
@@ -441,14 +653,14 @@ impl Context {
     /// during creation.
     #[inline]
     pub fn get_release_behavior(&self) -> ReleaseBehavior {
-        self.capabilities().release_behavior
+        self.get_capabilities().release_behavior
     }
 
     /// Returns the maximum value that can be used for anisotropic filtering, or `None`
     /// if the hardware doesn't support it.
     #[inline]
     pub fn get_max_anisotropy_support(&self) -> Option<u16> {
-        self.capabilities().max_texture_max_anisotropy.map(|v| v as u16)
+        self.get_capabilities().max_texture_max_anisotropy.map(|v| v as u16)
     }
 
     /// Returns the maximum dimensions of the viewport.
@@ -456,7 +668,7 @@ impl Context {
     /// Glium will panic if you request a larger viewport than this when drawing.
     #[inline]
     pub fn get_max_viewport_dimensions(&self) -> (u32, u32) {
-        let d = self.capabilities().max_viewport_dims;
+        let d = self.get_capabilities().max_viewport_dims;
         (d.0 as u32, d.1 as u32)
     }
 
@@ -501,6 +713,235 @@ impl Context {
         }
     }
 
+    /// Returns true if an event that makes the results of time queries meaningless, like a
+    /// change of frequency of the GPU, has happened since the last call to this function.
+    ///
+    /// This is only reported by OpenGL ES backends with `GL_EXT_disjoint_timer_query`, and
+    /// always returns `false` otherwise. The flag is reset when this function is called.
+    pub fn is_gpu_disjoint(&self) -> bool {
+        let ctxt = self.make_current();
+
+        if !ctxt.extensions.gl_ext_disjoint_timer_query {
+            return false;
+        }
+
+        unsafe {
+            let mut value = 0;
+            ctxt.gl.GetIntegerv(gl::GPU_DISJOINT_EXT, &mut value);
+            value != 0
+        }
+    }
+
+    /// Returns a report of the capabilities and limits of the backend.
+    ///
+    /// The report can be printed with `{}` and attached to bug reports.
+    pub fn capabilities(&self) -> CapabilitiesReport {
+        let ctxt = self.make_current();
+        unsafe {
+            capabilities::get_capabilities_report(ctxt.gl, &self.version, &self.extensions,
+                                                  &self.extension_names, &self.capabilities)
+        }
+    }
+
+    /// Returns a report of the slower code paths that glium has taken since the creation of the
+    /// context, because the backend lacks a feature.
+    ///
+    /// The report can be printed with `{}` to find out which fallbacks an application hits on a
+    /// given machine.
+    #[inline]
+    pub fn fallback_report(&self) -> FallbackReport {
+        self.fallbacks.report()
+    }
+
+    /// Resets the counters of the report returned by `fallback_report`.
+    #[inline]
+    pub fn clear_fallback_report(&self) {
+        self.fallbacks.clear();
+    }
+
+    /// Returns information about the video memory used by the application.
+    ///
+    /// The values reported by the backend require either `GL_NVX_gpu_memory_info` or
+    /// `GL_ATI_meminfo`, and are `None` otherwise. The amount of memory used by the buffers and
+    /// textures created by glium is always available.
+    pub fn memory_info(&self) -> MemoryInfo {
+        let (total, free, evicted) = unsafe {
+            let ctxt = self.make_current();
+
+            let mut value: [gl::types::GLint; 4] = [0; 4];
+
+            if ctxt.extensions.gl_nvx_gpu_memory_info {
+                ctxt.gl.GetIntegerv(gl::GPU_MEMORY_INFO_TOTAL_AVAILABLE_MEMORY_NVX,
+                                    &mut value[0]);
+                ctxt.gl.GetIntegerv(gl::GPU_MEMORY_INFO_CURRENT_AVAILABLE_VIDMEM_NVX,
+                                    &mut value[1]);
+                ctxt.gl.GetIntegerv(gl::GPU_MEMORY_INFO_EVICTED_MEMORY_NVX, &mut value[2]);
+                (Some(value[0] as usize * 1024), Some(value[1] as usize * 1024),
+                 Some(value[2] as usize * 1024))
+
+            } else if ctxt.extensions.gl_ati_meminfo {
+                ctxt.gl.GetIntegerv(gl::TEXTURE_FREE_MEMORY_ATI, &mut value[0]);
+                (None, Some(value[0] as usize * 1024), None)
+
+            } else {
+                (None, None, None)
+            }
+        };
+
+        let allocations = self.allocations.borrow();
+        let (buffers_count, buffers_memory) = allocations.totals(AllocationKind::Buffer);
+        let (textures_count, textures_memory) = allocations.totals(AllocationKind::Texture);
+
+        MemoryInfo {
+            total_video_memory: total,
+            free_video_memory: free,
+            evicted_memory: evicted,
+            buffers_count: buffers_count,
+            buffers_memory: buffers_memory,
+            textures_count: textures_count,
+            textures_memory: textures_memory,
+        }
+    }
+
+    /// Returns the list of the buffers and textures created by glium that are still alive,
+    /// with their size in bytes, their name and their age.
+    ///
+    /// The name of an object is the one given with its `set_object_label` method.
+    ///
+    /// The list is sorted by kind, then by identifier.
+    #[inline]
+    pub fn get_allocations(&self) -> Vec<Allocation> {
+        self.allocations.borrow().list()
+    }
+
+    /// Returns a human-readable description of the buffers and textures that are alive, with
+    /// one line per object.
+    ///
+    /// This is meant to be printed or shown in a debug overlay in order to find leaked objects.
+    /// See `get_allocations` if you want to process the list.
+    pub fn dump_resources(&self) -> String {
+        let list = self.allocations.borrow().list();
+        let total = list.iter().map(|a| a.size).sum::<usize>();
+
+        let mut result = format!("{} objects, {} bytes\n", list.len(), total);
+        for allocation in list {
+            result.push_str(&allocation.to_string());
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// Chooses whether the buffers and textures that are dropped are destroyed immediately,
+    /// which is the default, or later.
+    ///
+    /// Destroying an object requires making the context current and removing the object from
+    /// the caches of glium. When the destruction is deferred, dropping an object only adds it
+    /// to a list, and the objects of this list are destroyed by `collect` once the GPU has
+    /// finished executing the commands that were submitted before they were dropped. This
+    /// means that a buffer that is still read by a draw command of a frame in flight isn't
+    /// deleted before the frame is finished.
+    ///
+    /// The objects that are waiting to be destroyed are still counted by `get_allocations`.
+    /// If you disable deferred destruction, glium waits for the GPU if the backend supports
+    /// fences, and destroys them immediately.
+    pub fn set_deferred_destruction(&self, deferred: bool) {
+        self.deferred_destruction.set(deferred);
+
+        if !deferred {
+            let pending = mem::replace(&mut *self.pending_destructions.borrow_mut(), Vec::new());
+            let retired = mem::replace(&mut *self.retired_destructions.borrow_mut(),
+                                       VecDeque::new());
+
+            if pending.is_empty() && retired.is_empty() {
+                return;
+            }
+
+            let mut ctxt = self.make_current();
+
+            for (fence, objects) in retired {
+                unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt); }
+                for object in objects {
+                    unsafe { destroy_pending(&mut ctxt, &self.allocations, object); }
+                }
+            }
+
+            // waiting for the commands that were submitted before the objects were dropped
+            // if the backend supports fences, otherwise they are destroyed immediately like
+            // when the destruction isn't deferred
+            if !pending.is_empty() {
+                if let Ok(fence) = unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+                    unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt); }
+                }
+            }
+
+            for object in pending {
+                unsafe { destroy_pending(&mut ctxt, &self.allocations, object); }
+            }
+        }
+    }
+
+    /// Returns true if the destruction of buffers and textures is deferred. See
+    /// `set_deferred_destruction`.
+    #[inline]
+    pub fn is_destruction_deferred(&self) -> bool {
+        self.deferred_destruction.get()
+    }
+
+    /// Destroys the buffers and textures that have been dropped while their destruction was
+    /// deferred, and that are no longer used by the GPU. Never blocks.
+    ///
+    /// The objects dropped since the last call are destroyed by a later call, once the commands
+    /// submitted before this call have finished executing. If the backend doesn't support
+    /// fences, they are destroyed immediately.
+    ///
+    /// The buffers and textures that have been detached with `into_shared` by a context that
+    /// shares its objects with this one, and that have been dropped without being attached
+    /// again, are destroyed immediately as well.
+    ///
+    /// This is called automatically when the buffers are swapped.
+    pub fn collect(&self) {
+        let pending = mem::replace(&mut *self.pending_destructions.borrow_mut(), Vec::new());
+        let mut retired = self.retired_destructions.borrow_mut();
+        let orphans = self.share_group.borrow().take_orphans();
+
+        if pending.is_empty() && retired.is_empty() && orphans.is_empty() {
+            return;
+        }
+
+        let mut ctxt = self.make_current();
+
+        // the objects of the share group aren't used by this context, and OpenGL waits for the
+        // other contexts before deleting them
+        for object in orphans {
+            unsafe { destroy_pending(&mut ctxt, &self.allocations, object); }
+        }
+
+        if !pending.is_empty() {
+            match unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+                Ok(fence) => retired.push_back((fence, pending)),
+                Err(_) => {
+                    // without fences there is no way to know when the GPU is done, so the
+                    // objects are destroyed immediately like when the destruction isn't deferred
+                    for object in pending {
+                        unsafe { destroy_pending(&mut ctxt, &self.allocations, object); }
+                    }
+                },
+            }
+        }
+
+        while retired.front().map_or(false, |&(ref fence, _)| unsafe {
+            sync::is_linear_sync_fence_signaled(&mut ctxt, fence)
+        }) {
+            let (fence, objects) = retired.pop_front().unwrap();
+            unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence); }
+
+            for object in objects {
+                unsafe { destroy_pending(&mut ctxt, &self.allocations, object); }
+            }
+        }
+    }
+
     /// Reads the content of the front buffer.
     ///
     /// You will only see the data that has finished being drawn.
@@ -526,7 +967,7 @@ impl Context {
 
         let mut data = Vec::with_capacity(0);
         ops::read(&mut ctxt, ops::Source::DefaultFramebuffer(gl::FRONT_LEFT), &rect,
-                          &mut data, false);
+                          &mut data, false, texture::ChannelOrder::Rgba);
         T::from_raw(Cow::Owned(data), dimensions.0, dimensions.1)
     }
 
@@ -544,6 +985,43 @@ impl Context {
         action()
     }
 
+    /// Calls a function with the raw OpenGL bindings, for example to use an extension that glium
+    /// doesn't support.
+    ///
+    /// Before the function is called, the default vertex array object is bound so that the
+    /// function can't modify glium's ones, and conditional rendering and transform feedback are
+    /// stopped. Afterwards, the state is reset as with `reset_state`, so the function is free to
+    /// modify the state of the context.
+    ///
+    /// The function must not delete, modify the content of, or leave bound as a framebuffer
+    /// attachment the objects created by glium, and must not leave a query active.
+    pub unsafe fn exec_with_raw_gl<F, R>(&self, action: F) -> R where F: FnOnce(&gl::Gl) -> R {
+        let mut ctxt = self.make_current();
+
+        vertex_array_object::VertexAttributesSystem::unbind(&mut ctxt);
+        <draw_parameters::TimeElapsedQuery as QueryExt>::end_conditional_render(&mut ctxt);
+        <vertex::TransformFeedbackSession as TransformFeedbackSessionExt>::unbind(&mut ctxt);
+
+        let result = action(ctxt.gl);
+
+        state::reset_state(&mut ctxt);
+        result
+    }
+
+    /// Tells glium that code outside of glium has used the OpenGL context, and that the state
+    /// of the context is unknown.
+    ///
+    /// glium keeps a copy of the state of the context in order to avoid redundant OpenGL calls.
+    /// This function resets some parts of the state to their default value, and makes glium set
+    /// all the other parts again the next time they are needed.
+    ///
+    /// The other code must not leave a query, conditional rendering or transform feedback
+    /// active, and must not modify the objects created by glium.
+    pub fn reset_state(&self) {
+        let mut ctxt = self.make_current();
+        state::reset_state(&mut ctxt);
+    }
+
     /// Asserts that there are no OpenGL errors pending.
     ///
     /// This function should be used in tests.
@@ -629,6 +1107,104 @@ impl Context {
             Ok(())
         }
     }
+
+    /// Replaces the callback that receives the messages of the debug output.
+    ///
+    /// This has the same effect as passing a `DebugCallbackBehavior` when building the context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within the debug callback.
+    pub fn set_debug_callback(&self, behavior: DebugCallbackBehavior) {
+        self.check_gl_errors.set(behavior.checks_errors());
+        let (callback, synchronous) = behavior.into_callback();
+        let enable = callback.is_some();
+
+        *self.debug_callback.borrow_mut() = callback;
+
+        if enable {
+            init_debug_callback(self, synchronous);
+        }
+    }
+
+    /// Opens a named group of commands. If you use an OpenGL debugger, the commands that are
+    /// executed until the matching call to `pop_debug_group` will be shown in a hierarchy.
+    ///
+    /// Returns `Err` if the backend doesn't support this functionnality.
+    pub fn push_debug_group(&self, name: &str) -> Result<(), ()> {
+        let ctxt = self.make_current();
+        let name = name.as_bytes();
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+               (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+            {
+                ctxt.gl.PushDebugGroup(gl::DEBUG_SOURCE_APPLICATION, 0,
+                                       name.len() as gl::types::GLsizei,
+                                       name.as_ptr() as *const _);
+
+            } else if ctxt.extensions.gl_khr_debug {
+                ctxt.gl.PushDebugGroupKHR(gl::DEBUG_SOURCE_APPLICATION, 0,
+                                          name.len() as gl::types::GLsizei,
+                                          name.as_ptr() as *const _);
+
+            } else if ctxt.extensions.gl_ext_debug_marker {
+                ctxt.gl.PushGroupMarkerEXT(name.len() as gl::types::GLsizei,
+                                           name.as_ptr() as *const _);
+
+            } else {
+                return Err(());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Closes the group opened by the latest call to `push_debug_group`.
+    ///
+    /// Returns `Err` if the backend doesn't support this functionnality.
+    pub fn pop_debug_group(&self) -> Result<(), ()> {
+        let ctxt = self.make_current();
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+               (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+            {
+                ctxt.gl.PopDebugGroup();
+            } else if ctxt.extensions.gl_khr_debug {
+                ctxt.gl.PopDebugGroupKHR();
+            } else if ctxt.extensions.gl_ext_debug_marker {
+                ctxt.gl.PopGroupMarkerEXT();
+            } else {
+                return Err(());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Executes a closure inside of a named group of commands. See `push_debug_group`.
+    ///
+    /// If the backend doesn't support debug groups, the closure is simply executed.
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+    /// display.debug_group("shadows", || {
+    ///     // draw the shadows here
+    /// });
+    /// ```
+    pub fn debug_group<F, R>(&self, name: &str, f: F) -> R where F: FnOnce() -> R {
+        let pushed = self.push_debug_group(name).is_ok();
+        let result = f();
+
+        if pushed {
+            let _ = self.pop_debug_group();
+        }
+
+        result
+    }
 }
 
 impl ContextExt for Context {
@@ -653,19 +1229,19 @@ impl ContextExt for Context {
             extensions: &self.extensions,
             capabilities: &self.capabilities,
             report_debug_output_errors: &self.report_debug_output_errors,
+            validation_level: self.validation_level.get(),
+            fallbacks: &self.fallbacks,
             vertex_array_objects: &self.vertex_array_objects,
             framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
             samplers: self.samplers.borrow_mut(),
             resident_texture_handles: self.resident_texture_handles.borrow_mut(),
             resident_image_handles: self.resident_image_handles.borrow_mut(),
+            check_gl_errors: self.check_gl_errors.get(),
+            pending_gl_error: &self.pending_gl_error,
+            debug_callback: &self.debug_callback,
             marker: PhantomData,
         }
     }
-
-    #[inline]
-    fn capabilities(&self) -> &Capabilities {
-        &self.capabilities
-    }
 }
 
 impl CapabilitiesSource for Context {
@@ -704,17 +1280,41 @@ impl Drop for Context {
                 extensions: &self.extensions,
                 capabilities: &self.capabilities,
                 report_debug_output_errors: &self.report_debug_output_errors,
+                validation_level: self.validation_level.get(),
+                fallbacks: &self.fallbacks,
                 vertex_array_objects: &self.vertex_array_objects,
                 framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
                 samplers: self.samplers.borrow_mut(),
                 resident_texture_handles: self.resident_texture_handles.borrow_mut(),
                 resident_image_handles: self.resident_image_handles.borrow_mut(),
+                check_gl_errors: false,
+                pending_gl_error: &self.pending_gl_error,
+                debug_callback: &self.debug_callback,
                 marker: PhantomData,
             };
 
+            for (fence, objects) in self.retired_destructions.borrow_mut().drain(..) {
+                sync::destroy_linear_sync_fence(&mut ctxt, fence);
+                for object in objects {
+                    destroy_pending(&mut ctxt, &self.allocations, object);
+                }
+            }
+
+            for object in self.pending_destructions.borrow_mut().drain(..) {
+                destroy_pending(&mut ctxt, &self.allocations, object);
+            }
+
+            for object in self.share_group.borrow().take_orphans() {
+                destroy_pending(&mut ctxt, &self.allocations, object);
+            }
+
             fbo::FramebuffersContainer::cleanup(&mut ctxt);
             vertex_array_object::VertexAttributesSystem::cleanup(&mut ctxt);
 
+            for fence in self.frame_fences.borrow_mut().drain(..) {
+                sync::destroy_linear_sync_fence(&mut ctxt, fence);
+            }
+
             for (_, s) in mem::replace(&mut *ctxt.samplers, HashMap::with_hasher(Default::default())) {
                 s.destroy(&mut ctxt);
             }
@@ -735,6 +1335,45 @@ impl Drop for Context {
     }
 }
 
+impl<'a> CommandContext<'a> {
+    /// Returns the value of `glGetError`, or the error that has been found at the end of a
+    /// previous glium function if `DebugCallbackBehavior::CheckErrors` is used.
+    pub fn get_error(&mut self) -> gl::types::GLenum {
+        match self.pending_gl_error.take() {
+            Some(error) => error,
+            None => unsafe { self.gl.GetError() },
+        }
+    }
+}
+
+impl<'a> Drop for CommandContext<'a> {
+    fn drop(&mut self) {
+        if !self.check_gl_errors || thread::panicking() || self.pending_gl_error.get().is_some() {
+            return;
+        }
+
+        let error = unsafe { self.gl.GetError() };
+        let name = match ::describe_gl_error(error) {
+            Some(name) => name,
+            None => return,
+        };
+
+        // `glGetError` has consumed the error, so it is kept for the next call to `get_error`
+        self.pending_gl_error.set(Some(error));
+
+        let message = format!("A glium function has triggered an OpenGL error: {}", name);
+
+        // the callback may be already borrowed if the error has been triggered by one of its
+        // OpenGL calls, in which case the error isn't reported to it
+        if let Ok(mut callback) = self.debug_callback.try_borrow_mut() {
+            if let Some(callback) = callback.as_mut() {
+                callback(debug::Source::Api, debug::MessageType::Error, debug::Severity::High,
+                         error, self.report_debug_output_errors.get(), &message);
+            }
+        }
+    }
+}
+
 impl<'a> CapabilitiesSource for CommandContext<'a> {
     #[inline]
     fn get_version(&self) -> &Version {
@@ -792,6 +1431,367 @@ fn check_gl_compatibility(version: &Version, extensions: &ExtensionsList)
     }
 }
 
+/// Sets the label of an OpenGL object, so that it can be identified in debuggers.
+///
+/// Returns `Err` if the backend doesn't support labels.
+pub fn set_object_label(ctxt: &CommandContext, identifier: gl::types::GLenum,
+                        name: gl::types::GLuint, label: &str) -> Result<(), ()>
+{
+    let label = label.as_bytes();
+
+    unsafe {
+        if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+           ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            ctxt.gl.ObjectLabel(identifier, name, label.len() as gl::types::GLsizei,
+                                label.as_ptr() as *const _);
+
+        } else if ctxt.extensions.gl_khr_debug {
+            ctxt.gl.ObjectLabelKHR(identifier, name, label.len() as gl::types::GLsizei,
+                                   label.as_ptr() as *const _);
+
+        } else {
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds a buffer or a texture to the list returned by `Context::get_allocations`.
+#[inline]
+pub fn register_allocation(context: &Context, kind: AllocationKind, id: gl::types::GLuint,
+                           size: usize, format: Option<String>)
+{
+    context.allocations.borrow_mut().insert(kind, id, size, format);
+}
+
+/// Sets the name shown by `Context::get_allocations` and `Context::dump_resources`.
+#[inline]
+pub fn set_allocation_name(context: &Context, kind: AllocationKind, id: gl::types::GLuint,
+                           name: &str)
+{
+    context.allocations.borrow_mut().set_name(kind, id, name);
+}
+
+/// The objects that can be accessed by several contexts. Two contexts share their objects if
+/// they point to the same group.
+pub struct ShareGroup {
+    /// Objects that have been detached from their context and dropped without being attached to
+    /// another one. They are destroyed by the next context of the group that calls `collect`.
+    orphans: Mutex<Vec<PendingDestruction>>,
+}
+
+impl ShareGroup {
+    #[inline]
+    fn new() -> ShareGroup {
+        ShareGroup {
+            orphans: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Takes the objects that must be destroyed by one of the contexts of the group.
+    fn take_orphans(&self) -> Vec<PendingDestruction> {
+        match self.orphans.lock() {
+            Ok(mut orphans) => mem::replace(&mut *orphans, Vec::new()),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl Drop for ShareGroup {
+    fn drop(&mut self) {
+        // all the contexts of the group have been destroyed, and their objects with them
+        if let Ok(orphans) = self.orphans.get_mut() {
+            for object in orphans.drain(..) {
+                mem::forget(object);
+            }
+        }
+    }
+}
+
+/// Returns the group of the objects that the context can access.
+#[inline]
+pub fn get_share_group(context: &Context) -> Arc<ShareGroup> {
+    context.share_group.borrow().clone()
+}
+
+/// Declares that the context shares its objects with the contexts of the given group.
+#[inline]
+pub fn set_share_group(context: &Context, share_group: Arc<ShareGroup>) {
+    *context.share_group.borrow_mut() = share_group;
+}
+
+/// Hands an object that has been detached from its context and that isn't attached to any other
+/// context to the share group, so that it is destroyed by one of the contexts of the group.
+pub fn orphan_shared_object(share_group: &ShareGroup, object: PendingDestruction) {
+    match share_group.orphans.lock() {
+        Ok(mut orphans) => orphans.push(object),
+        // the fence can't be destroyed without a context
+        Err(_) => mem::forget(object),
+    }
+}
+
+/// Makes the context use the OpenGL context of another backend, whose objects are shared with
+/// the current one. This is used to draw on several windows with the same context.
+///
+/// Contrary to `Context::rebuild`, only what belongs to the current OpenGL context is forgotten:
+/// its framebuffer objects and vertex array objects are destroyed, and the state cache is reset
+/// as the state of the other OpenGL context is unknown. The new backend isn't made current.
+pub unsafe fn switch_backend<B>(context: &Context, new_backend: B) where B: Backend + 'static {
+    {
+        let mut ctxt = context.make_current();
+        fbo::FramebuffersContainer::purge_all(&mut ctxt);
+        vertex_array_object::VertexAttributesSystem::purge_all(&mut ctxt);
+    }
+
+    {
+        let mut state = context.state.borrow_mut();
+        let lost_context = state.lost_context;
+        *state = Default::default();
+        state.lost_context = lost_context;
+    }
+
+    *context.backend.borrow_mut() = Box::new(new_backend);
+}
+
+/// Declares that the context has been lost, for example because the backend couldn't make it
+/// current. The next call to `swap_buffers` returns `SwapBuffersError::ContextLost`.
+#[inline]
+pub fn set_context_lost(context: &Context) {
+    context.state.borrow_mut().lost_context = true;
+}
+
+/// Sets the maximum number of frames that can be processed by the GPU after the buffers have
+/// been swapped. `None` removes the limit and destroys the fences of the frames in flight.
+pub fn set_max_frames_in_flight(context: &Context, max: Option<u32>) {
+    context.max_frames_in_flight.set(max);
+
+    if max.is_none() {
+        let mut ctxt = context.make_current();
+        for fence in context.frame_fences.borrow_mut().drain(..) {
+            unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence); }
+        }
+    }
+}
+
+/// Returns the maximum number of frames in flight set with `set_max_frames_in_flight`.
+#[inline]
+pub fn get_max_frames_in_flight(context: &Context) -> Option<u32> {
+    context.max_frames_in_flight.get()
+}
+
+/// An object that has been dropped while the destruction of objects was deferred.
+pub enum PendingDestruction {
+    /// A buffer.
+    Buffer(gl::types::GLuint),
+    /// A texture, which is deleted only if glium owns it.
+    Texture {
+        /// Identifier of the texture.
+        id: gl::types::GLuint,
+        /// True if the texture must be deleted.
+        owned: bool,
+    },
+    /// A fence of a buffer.
+    Fence(LinearSyncFence),
+}
+
+/// Returns true if the objects that are dropped must be passed to `defer_destruction` instead
+/// of being destroyed.
+#[inline]
+pub fn is_destruction_deferred(context: &Context) -> bool {
+    context.deferred_destruction.get()
+}
+
+/// Adds an object to the list of objects destroyed by `Context::collect` once the GPU no longer
+/// uses them.
+#[inline]
+pub fn defer_destruction(context: &Context, object: PendingDestruction) {
+    context.pending_destructions.borrow_mut().push(object);
+}
+
+/// Destroys an object whose destruction has been deferred.
+unsafe fn destroy_pending(ctxt: &mut CommandContext, allocations: &RefCell<Allocations>,
+                          object: PendingDestruction)
+{
+    match object {
+        PendingDestruction::Buffer(id) => {
+            buffer::destroy_dropped_buffer(ctxt, id);
+            allocations.borrow_mut().remove(AllocationKind::Buffer, id);
+        },
+        PendingDestruction::Texture { id, owned } => {
+            texture::destroy_dropped_texture(ctxt, id, owned);
+            if owned {
+                allocations.borrow_mut().remove(AllocationKind::Texture, id);
+            }
+        },
+        PendingDestruction::Fence(fence) => {
+            sync::destroy_linear_sync_fence(ctxt, fence);
+        },
+    }
+}
+
+/// Returns the number of frames whose fence hasn't been waited for yet.
+#[inline]
+pub fn get_frames_in_flight(context: &Context) -> usize {
+    context.frame_fences.borrow().len()
+}
+
+/// Blocks until fewer than the maximum number of frames are in flight, so that the next frame
+/// can be submitted without blocking. Does nothing if the number of frames isn't limited.
+pub fn wait_for_frame_slot(context: &Context) {
+    let max = match context.max_frames_in_flight.get() {
+        Some(max) => max as usize,
+        None => return,
+    };
+
+    let mut ctxt = context.make_current();
+    let mut fences = context.frame_fences.borrow_mut();
+    wait_for_frames_in_flight(&mut ctxt, &mut fences, max.saturating_sub(1));
+}
+
+/// Waits for the oldest fences until at most `max` of them remain.
+fn wait_for_frames_in_flight(ctxt: &mut CommandContext, fences: &mut VecDeque<LinearSyncFence>,
+                             max: usize)
+{
+    while fences.len() > max {
+        let fence = fences.pop_front().unwrap();
+        unsafe { sync::wait_linear_sync_fence_and_drop(fence, ctxt); }
+    }
+}
+
+/// Returns true if the context has been lost.
+///
+/// The reset status is only queried if the context can be lost, which makes this function cheap
+/// enough to be called before each draw command.
+pub fn check_context_lost(ctxt: &mut CommandContext) -> bool {
+    if ctxt.state.lost_context {
+        return true;
+    }
+
+    if !ctxt.capabilities.can_lose_context {
+        return false;
+    }
+
+    query_context_lost(ctxt)
+}
+
+/// Calls `glGetGraphicsResetStatus` and remembers the result.
+fn query_context_lost(ctxt: &mut CommandContext) -> bool {
+    let lost = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+                  ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                  ctxt.extensions.gl_khr_robustness
+    {
+        unsafe { ctxt.gl.GetGraphicsResetStatus() != gl::NO_ERROR }
+    } else if ctxt.extensions.gl_ext_robustness {
+        unsafe { ctxt.gl.GetGraphicsResetStatusEXT() != gl::NO_ERROR }
+    } else if ctxt.extensions.gl_arb_robustness {
+        unsafe { ctxt.gl.GetGraphicsResetStatusARB() != gl::NO_ERROR }
+    } else {
+        false
+    };
+
+    if lost { ctxt.state.lost_context = true; }
+    lost
+}
+
+/// Returns the address of a function of the backend, for example an EGL extension function.
+///
+/// # Unsafety
+///
+/// The context must be current.
+#[inline]
+pub unsafe fn get_proc_address(context: &Context, symbol: &str) -> *const raw::c_void {
+    context.backend.borrow().get_proc_address(symbol)
+}
+
+/// Removes a buffer or a texture from the list returned by `Context::get_allocations`.
+#[inline]
+pub fn unregister_allocation(context: &Context, kind: AllocationKind, id: gl::types::GLuint) {
+    context.allocations.borrow_mut().remove(kind, id);
+}
+
+/// What glium may assume about the state of an OpenGL context that it didn't create.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StateAssumptions {
+    /// The context has just been created and its state has never been modified. This is what
+    /// `Context::new` assumes.
+    Default,
+
+    /// The context may have been used before. glium resets its state when adopting it, as if
+    /// `Context::reset_state` was called.
+    Unknown,
+}
+
+/// Backend of a context built with `Context::from_raw_parts`.
+struct RawBackend {
+    loader: Box<Fn(&str) -> *const raw::c_void>,
+    dimensions: Rc<Cell<(u32, u32)>>,
+}
+
+unsafe impl Backend for RawBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // the buffers are swapped by the library that owns the window
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const raw::c_void {
+        (self.loader)(symbol)
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.dimensions.get()
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+    }
+}
+
+/// How much checking glium performs before each draw command.
+///
+/// The checks detect mistakes such as a uniform whose type doesn't match the program, and turn
+/// them into a `DrawError` instead of undefined behavior. Lowering the level reduces the CPU
+/// cost of each draw, and is meant for shipping builds of programs that have been tested with
+/// `Full`. Errors that prevent glium from executing the command, like unsupported features, are
+/// always reported.
+///
+/// Lowering the level is unsafe. See `Context::set_validation_level`.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// use glium::debug::ValidationLevel;
+///
+/// // the program has been tested with `ValidationLevel::Full`
+/// if !cfg!(debug_assertions) {
+///     unsafe { display.set_validation_level(ValidationLevel::DrawParametersOnly); }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValidationLevel {
+    /// Everything is checked. This is the default.
+    Full,
+
+    /// Only the draw parameters are checked against the surface and the capabilities of the
+    /// backend. The types of the uniforms and the layouts of the uniform blocks are not checked.
+    DrawParametersOnly,
+
+    /// Nothing is checked. Drawing with a depth test on a surface without a depth buffer, or with
+    /// a viewport that is too large, is not reported either.
+    None,
+}
+
 /// Describes the behavior that the debug output should have.
 pub enum DebugCallbackBehavior {
     /// Don't do anything. This is the default behavior in release.
@@ -811,6 +1811,42 @@ pub enum DebugCallbackBehavior {
         /// Whether or not it should be called immediately (true) or asynchronously (false).
         synchronous: bool,
     },
+
+    /// Same as `DebugMessageOnError`, but additionally calls `glGetError` at the end of each
+    /// glium function and reports the error to the callback, which prints it along with a
+    /// backtrace. This allows you to know which function triggered the error, even if the
+    /// backend doesn't support debug output. The error is still returned by the next call to
+    /// `assert_no_error`.
+    ///
+    /// This is very slow. In release mode, the errors are not checked.
+    CheckErrors,
+}
+
+impl DebugCallbackBehavior {
+    /// Returns true if `glGetError` must be called at the end of each glium function.
+    #[inline]
+    fn checks_errors(&self) -> bool {
+        match *self {
+            DebugCallbackBehavior::CheckErrors => cfg!(debug_assertions),
+            _ => false,
+        }
+    }
+
+    /// Turns the behavior into the callback to call and whether it should be synchronous.
+    fn into_callback(self) -> (Option<debug::DebugCallback>, bool) {
+        match self {
+            DebugCallbackBehavior::Ignore => (None, false),
+            DebugCallbackBehavior::DebugMessageOnError | DebugCallbackBehavior::CheckErrors => {
+                (Some(Box::new(default_debug_callback) as debug::DebugCallback), true)
+            },
+            DebugCallbackBehavior::PrintAll => {
+                (Some(Box::new(printall_debug_callback) as debug::DebugCallback), false)
+            },
+            DebugCallbackBehavior::Custom { callback, synchronous } => {
+                (Some(callback), synchronous)
+            },
+        }
+    }
 }
 
 impl Default for DebugCallbackBehavior {
@@ -847,31 +1883,35 @@ fn default_debug_callback(_: debug::Source, ty: debug::MessageType, severity: de
                 Please report this error: https://github.com/tomaka/glium/issues\n\
                 Backtrace:",
                 message);
+        print_backtrace();
+    }
+}
 
-        let mut frame_id = 1;
-        backtrace::trace(|frame| {
-            let ip = frame.ip();
-            print!("\n{:>#4} - {:p}", frame_id, ip);
-
-            backtrace::resolve(ip, |symbol| {
-                let name = symbol.name()
-                                 .map(|n| n.as_str().unwrap_or("<not-utf8>"))
+/// Prints the current backtrace on stdout.
+fn print_backtrace() {
+    let mut frame_id = 1;
+    backtrace::trace(|frame| {
+        let ip = frame.ip();
+        print!("\n{:>#4} - {:p}", frame_id, ip);
+
+        backtrace::resolve(ip, |symbol| {
+            let name = symbol.name()
+                             .map(|n| n.as_str().unwrap_or("<not-utf8>"))
+                             .unwrap_or("<unknown>");
+            let filename = symbol.filename()
+                                 .map(|p| p.to_str().unwrap_or("<not-utf8>"))
                                  .unwrap_or("<unknown>");
-                let filename = symbol.filename()
-                                     .map(|p| p.to_str().unwrap_or("<not-utf8>"))
-                                     .unwrap_or("<unknown>");
-                let line = symbol.lineno().map(|l| l.to_string())
-                                          .unwrap_or_else(|| "??".to_owned());
-
-                print!("\n         {} at {}:{}", name, filename, line);
-            });
+            let line = symbol.lineno().map(|l| l.to_string())
+                                      .unwrap_or_else(|| "??".to_owned());
 
-            frame_id += 1;
-            true
+            print!("\n         {} at {}:{}", name, filename, line);
         });
 
-        println!("\n");
-    }
+        frame_id += 1;
+        true
+    });
+
+    println!("\n");
 }
 
 /// The callback corresponding to `DebugMessageOnError`.
@@ -884,7 +1924,7 @@ fn printall_debug_callback(source: debug::Source, ty: debug::MessageType, severi
 
 /// Initializes `GL_KHR_debug`, `GL_ARB_debug`, or a similar extension so that the debug output
 /// is reported.
-fn init_debug_callback(context: &Rc<Context>, synchronous: bool) {
+fn init_debug_callback(context: &Context, synchronous: bool) {
     // this is the C callback
     extern "system" fn callback_wrapper(source: gl::types::GLenum, ty: gl::types::GLenum,
                                         id: gl::types::GLuint, severity: gl::types::GLenum,
@@ -897,7 +1937,7 @@ fn init_debug_callback(context: &Rc<Context>, synchronous: bool) {
         // destructor
 
         let user_param = user_param as *const Context;
-        let user_param: &mut Context = unsafe { mem::transmute(user_param) };
+        let user_param: &Context = unsafe { &*user_param };
 
         let message = unsafe {
             String::from_utf8(CStr::from_ptr(message).to_bytes().to_vec()).unwrap()
@@ -934,16 +1974,20 @@ fn init_debug_callback(context: &Rc<Context>, synchronous: bool) {
             _ => return,        // TODO: what to do in this situation?
         };
 
-        if let Some(callback) = user_param.debug_callback.as_mut() {
-            // FIXME: catch_panic here once it's stable
-            callback(source, ty, severity, id, user_param.report_debug_output_errors.get(),
-                     &message);
+        // the callback may be already borrowed if it calls an OpenGL function that generates
+        // a message itself, in which case the message is ignored
+        if let Ok(mut callback) = user_param.debug_callback.try_borrow_mut() {
+            if let Some(callback) = callback.as_mut() {
+                // FIXME: catch_panic here once it's stable
+                callback(source, ty, severity, id, user_param.report_debug_output_errors.get(),
+                         &message);
+            }
         }
     }
 
     struct ContextRawPtr(*const Context);
     unsafe impl Send for ContextRawPtr {}
-    let context_raw_ptr = ContextRawPtr(&**context);
+    let context_raw_ptr = ContextRawPtr(context);
 
     unsafe {
         let mut ctxt = context.make_current();
@@ -2,6 +2,13 @@ use Handle;
 use gl;
 use smallvec::SmallVec;
 
+use context::CommandContext;
+use version::{Api, Version};
+
+/// Value of the cached object names and enums whose actual value is unknown. It never matches
+/// a value that glium wants, so the next function that needs the state sets it.
+const UNKNOWN: gl::types::GLuint = !0;
+
 /// Represents the current OpenGL state.
 ///
 /// The current state is passed to each function and can be freely updated.
@@ -205,6 +212,12 @@ pub struct GlState {
     /// The latest value passed to `glPointSize`.
     pub point_size: gl::types::GLfloat,
 
+    /// The latest value passed to `glPointParameter` with `GL_POINT_FADE_THRESHOLD_SIZE`.
+    pub point_fade_threshold_size: gl::types::GLfloat,
+
+    /// The latest value passed to `glPointParameter` with `GL_POINT_SPRITE_COORD_ORIGIN`.
+    pub point_sprite_coord_origin: gl::types::GLenum,
+
     /// The latest value passed to `glCullFace`.
     pub cull_face: gl::types::GLenum,
 
@@ -237,6 +250,14 @@ pub struct GlState {
     /// List of texture units.
     pub texture_units: SmallVec<[TextureUnitState ; 32]>,
 
+    /// The latest values passed to `glBindImageTexture`, indexed by image unit. The units past
+    /// the end of the list are in an unknown state.
+    pub image_units: SmallVec<[ImageUnitState; 8]>,
+
+    /// The draw call ID of the latest draw command that had a texture bound to an image unit
+    /// with write access.
+    pub latest_image_write: u64,
+
     /// Current query being used for GL_SAMPLES_PASSED​.
     pub samples_passed_query: gl::types::GLuint,
 
@@ -255,6 +276,10 @@ pub struct GlState {
     /// Current query being used for GL_TIME_ELAPSED​.
     pub time_elapsed_query: gl::types::GLuint,
 
+    /// Current queries being used for each of the pipeline statistics targets, indexed by
+    /// `PipelineStatistic`.
+    pub pipeline_statistics_queries: [gl::types::GLuint; 11],
+
     /// Latest value passed to `glBeginConditionalRender​`.
     pub conditional_render: Option<(gl::types::GLuint, gl::types::GLenum)>,
 
@@ -269,6 +294,11 @@ pub struct GlState {
     /// The latest value passed to `glPrimitiveBoundingBox`.
     pub primitive_bounding_box: (f32, f32, f32, f32, f32, f32, f32, f32),
 
+    /// Incremented each time the values of the uniforms that the programs keep may have been
+    /// modified by code outside of glium. The programs compare it with the value they have
+    /// recorded to know whether the values of their uniforms are still known.
+    pub uniform_values_generation: u64,
+
     /// Current draw call ID.
     /// We maintain a counter that is incremented at each draw call.
     pub next_draw_call_id: u64,
@@ -338,6 +368,32 @@ pub struct TextureUnitState {
 
     /// Id of the sampler.
     pub sampler: gl::types::GLuint,
+
+    /// The draw call ID of the latest command that used this unit. When all the units contain a
+    /// texture, the one that has been used the least recently is replaced.
+    pub latest_use: u64,
+}
+
+/// State of an image unit (`glBindImageTexture`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImageUnitState {
+    /// Id of the texture, or `0` if no texture is bound.
+    pub texture: gl::types::GLuint,
+
+    /// The mipmap level.
+    pub level: gl::types::GLint,
+
+    /// Whether all the layers of the texture are bound.
+    pub layered: gl::types::GLboolean,
+
+    /// The layer, if `layered` is false.
+    pub layer: gl::types::GLint,
+
+    /// `GL_READ_ONLY`, `GL_WRITE_ONLY` or `GL_READ_WRITE`.
+    pub access: gl::types::GLenum,
+
+    /// The format used to interpret the texels.
+    pub format: gl::types::GLenum,
 }
 
 /// State of an indexed buffer target (`glBindBufferRange`/`glBindBufferBase`).
@@ -428,6 +484,8 @@ impl Default for GlState {
             scissor: None,
             line_width: 1.0,
             point_size: 1.0,
+            point_fade_threshold_size: 1.0,
+            point_sprite_coord_origin: gl::UPPER_LEFT,
             cull_face: gl::BACK,
             polygon_mode: gl::FILL,
             smooth: (gl::DONT_CARE, gl::DONT_CARE),
@@ -438,17 +496,21 @@ impl Default for GlState {
             patch_patch_vertices: 3,
             active_texture: 0,
             texture_units: small_vec_one(),
+            image_units: SmallVec::new(),
+            latest_image_write: 0,
             samples_passed_query: 0,
             any_samples_passed_query: 0,
             any_samples_passed_conservative_query: 0,
             primitives_generated_query: 0,
             transform_feedback_primitives_written_query: 0,
             time_elapsed_query: 0,
+            pipeline_statistics_queries: [0; 11],
             conditional_render: None,
             transform_feedback_enabled: None,
             transform_feedback_paused: false,
-            primitive_bounding_box: (-1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0),
+            primitive_bounding_box: (-1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0, 1.0),
 
+            uniform_values_generation: 0,
             next_draw_call_id: 1,
             latest_memory_barrier_vertex_attrib_array: 1,
             latest_memory_barrier_element_array: 1,
@@ -474,6 +536,7 @@ impl Default for TextureUnitState {
         TextureUnitState {
             texture: 0,
             sampler: 0,
+            latest_use: 0,
         }
     }
 }
@@ -488,3 +551,190 @@ impl Default for IndexedBufferState {
         }
     }
 }
+
+/// Makes the cached state match the actual state after OpenGL has been used by code outside
+/// of glium.
+///
+/// The capabilities (`glEnable`/`glDisable`), the active texture unit, the write masks of the
+/// depth and stencil buffers, the stencil clear value and the pixel store parameters are reset
+/// to their default value. All the other values, including the values of the uniforms of the
+/// programs, are marked as unknown, so that glium sets them again the next time they are needed.
+///
+/// The queries, the conditional rendering and the transform feedback must not be active.
+pub fn reset_state(ctxt: &mut CommandContext) {
+    let max_texture_units = ctxt.capabilities.max_combined_texture_image_units as usize;
+
+    unsafe {
+        for &cap in &[gl::BLEND, gl::CULL_FACE, gl::DEPTH_TEST, gl::DITHER,
+                      gl::POLYGON_OFFSET_FILL, gl::SAMPLE_ALPHA_TO_COVERAGE, gl::SAMPLE_COVERAGE,
+                      gl::SCISSOR_TEST, gl::STENCIL_TEST]
+        {
+            ctxt.gl.Disable(cap);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 1, 0) {
+            ctxt.gl.Enable(gl::MULTISAMPLE);
+            ctxt.gl.Disable(gl::LINE_SMOOTH);
+            ctxt.gl.Disable(gl::POLYGON_SMOOTH);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 2, 0) {
+            ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
+           ctxt.extensions.gl_nv_depth_clamp
+        {
+            ctxt.gl.Disable(gl::DEPTH_CLAMP);
+        }
+
+        if ctxt.extensions.gl_amd_depth_clamp_separate {
+            ctxt.gl.Disable(gl::DEPTH_CLAMP_NEAR_AMD);
+            ctxt.gl.Disable(gl::DEPTH_CLAMP_FAR_AMD);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+           ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
+        {
+            ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 3, 1) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+           ctxt.extensions.gl_arb_es3_compatibility
+        {
+            ctxt.gl.Disable(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+            ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
+        } else if ctxt.extensions.gl_ext_transform_feedback {
+            ctxt.gl.Disable(gl::RASTERIZER_DISCARD_EXT);
+        }
+
+        ctxt.gl.ActiveTexture(gl::TEXTURE0);
+        ctxt.gl.DepthMask(gl::TRUE);
+        ctxt.gl.StencilMask(0xffffffff);
+        ctxt.gl.ClearStencil(0);
+        ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+        ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 4);
+
+        if ctxt.version >= &Version(Api::Gl, 1, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+            for &param in &[gl::UNPACK_ROW_LENGTH, gl::UNPACK_SKIP_PIXELS, gl::UNPACK_SKIP_ROWS,
+                            gl::PACK_ROW_LENGTH, gl::PACK_SKIP_PIXELS, gl::PACK_SKIP_ROWS]
+            {
+                ctxt.gl.PixelStorei(param, 0);
+            }
+        } else {
+            if ctxt.extensions.gl_ext_unpack_subimage {
+                ctxt.gl.PixelStorei(gl::UNPACK_ROW_LENGTH, 0);
+                ctxt.gl.PixelStorei(gl::UNPACK_SKIP_PIXELS, 0);
+                ctxt.gl.PixelStorei(gl::UNPACK_SKIP_ROWS, 0);
+            }
+
+            if ctxt.extensions.gl_nv_pack_subimage {
+                ctxt.gl.PixelStorei(gl::PACK_ROW_LENGTH, 0);
+                ctxt.gl.PixelStorei(gl::PACK_SKIP_PIXELS, 0);
+                ctxt.gl.PixelStorei(gl::PACK_SKIP_ROWS, 0);
+            }
+        }
+    }
+
+    let state = &mut *ctxt.state;
+    let unknown_float = ::std::f32::NAN;
+
+    state.enabled_blend = false;
+    state.enabled_cull_face = false;
+    state.enabled_depth_test = false;
+    state.enabled_depth_clamp_near = false;
+    state.enabled_depth_clamp_far = false;
+    state.enabled_dither = false;
+    state.enabled_framebuffer_srgb = false;
+    state.enabled_multisample = true;
+    state.enabled_polygon_offset_fill = false;
+    state.enabled_primitive_fixed_restart = false;
+    state.enabled_rasterizer_discard = false;
+    state.enabled_sample_alpha_to_coverage = false;
+    state.enabled_sample_coverage = false;
+    state.enabled_scissor_test = false;
+    state.enabled_stencil_test = false;
+    state.enabled_line_smooth = false;
+    state.enabled_polygon_smooth = false;
+    state.enabled_program_point_size = false;
+    state.active_texture = 0;
+    state.depth_mask = true;
+    state.stencil_mask_front = 0xffffffff;
+    state.stencil_mask_back = 0xffffffff;
+    state.clear_stencil = 0;
+    state.pixel_store_unpack_alignment = 4;
+    state.pixel_store_pack_alignment = 4;
+
+    state.program = Handle::Id(UNKNOWN);
+    state.uniform_values_generation += 1;
+    state.vertex_array = UNKNOWN;
+    state.clear_color = (unknown_float, unknown_float, unknown_float, unknown_float);
+    state.clear_depth = unknown_float;
+    state.color_mask = (2, 2, 2, 2);
+
+    state.array_buffer_binding = UNKNOWN;
+    state.pixel_pack_buffer_binding = UNKNOWN;
+    state.pixel_unpack_buffer_binding = UNKNOWN;
+    state.uniform_buffer_binding = UNKNOWN;
+    state.copy_read_buffer_binding = UNKNOWN;
+    state.copy_write_buffer_binding = UNKNOWN;
+    state.dispatch_indirect_buffer_binding = UNKNOWN;
+    state.draw_indirect_buffer_binding = UNKNOWN;
+    state.query_buffer_binding = UNKNOWN;
+    state.texture_buffer_binding = UNKNOWN;
+    state.atomic_counter_buffer_binding = UNKNOWN;
+    state.shader_storage_buffer_binding = UNKNOWN;
+
+    for binding in state.indexed_uniform_buffer_bindings.iter_mut()
+                        .chain(state.indexed_atomic_counter_buffer_bindings.iter_mut())
+                        .chain(state.indexed_shader_storage_buffer_bindings.iter_mut())
+                        .chain(state.indexed_transform_feedback_buffer_bindings.iter_mut())
+    {
+        binding.buffer = UNKNOWN;
+    }
+
+    state.read_framebuffer = UNKNOWN;
+    state.draw_framebuffer = UNKNOWN;
+    state.default_framebuffer_read = None;
+    state.renderbuffer = UNKNOWN;
+
+    state.blend_equation = (UNKNOWN, UNKNOWN);
+    state.blend_func = (UNKNOWN, UNKNOWN, UNKNOWN, UNKNOWN);
+    state.blend_color = (unknown_float, unknown_float, unknown_float, unknown_float);
+    state.depth_func = UNKNOWN;
+    state.depth_range = (unknown_float, unknown_float);
+    state.stencil_func_front = (UNKNOWN, 0, 0);
+    state.stencil_func_back = (UNKNOWN, 0, 0);
+    state.stencil_op_front = (UNKNOWN, UNKNOWN, UNKNOWN);
+    state.stencil_op_back = (UNKNOWN, UNKNOWN, UNKNOWN);
+    state.viewport = None;
+    state.scissor = None;
+    state.line_width = unknown_float;
+    state.point_size = unknown_float;
+    state.point_fade_threshold_size = unknown_float;
+    state.point_sprite_coord_origin = UNKNOWN;
+    state.cull_face = UNKNOWN;
+    state.polygon_mode = UNKNOWN;
+    state.smooth = (UNKNOWN, UNKNOWN);
+    state.provoking_vertex = UNKNOWN;
+    state.clamp_color = UNKNOWN;
+    state.patch_patch_vertices = 0;
+    state.primitive_bounding_box = (unknown_float, unknown_float, unknown_float, unknown_float,
+                                    unknown_float, unknown_float, unknown_float, unknown_float);
+
+    // units that glium hasn't used yet may have textures or samplers bound too
+    if state.texture_units.len() < max_texture_units {
+        let missing = max_texture_units - state.texture_units.len();
+        state.texture_units.extend((0 .. missing).map(|_| Default::default()));
+    }
+
+    for unit in state.texture_units.iter_mut() {
+        unit.texture = UNKNOWN;
+        unit.sampler = UNKNOWN;
+    }
+
+    state.image_units.clear();
+}
@@ -0,0 +1,217 @@
+/*!
+Triggers frame captures of graphics debuggers programmatically.
+
+This module is only available if you enable the `capture` feature of glium.
+
+Capturing frames manually is usually done by pressing a key in the window of the application.
+This is not always practical, for example when the bug happens in a headless context or only
+under some conditions. Instead you can ask the debugger to capture the commands that are
+submitted between two points of your program.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+use glium::debug::capture::RenderDoc;
+
+// returns `None` if the program is not running under RenderDoc
+if let Some(renderdoc) = RenderDoc::new() {
+    renderdoc.capture(|| {
+        display.debug_group("failing draw", || {
+            // draw here
+        });
+    });
+}
+```
+
+The labels set with the `set_object_label` methods of buffers, textures and programs, and the
+groups opened with `Context::debug_group`, are shown by the debugger in the capture.
+
+## Supported platforms
+
+RenderDoc captures are supported on Linux and Windows. On the other platforms, `RenderDoc::new`
+always returns `None`.
+
+*/
+use std::os::raw::{c_int, c_void};
+use std::marker::PhantomData;
+use std::ptr;
+
+/// Version of the in-application API that we request (1.1.0).
+const API_VERSION_1_1_0: c_int = 10100;
+
+type GetApiFn = extern "C" fn(version: c_int, out: *mut *mut c_void) -> c_int;
+
+/// Table of functions of the RenderDoc in-application API, version 1.1.0.
+///
+/// Functions that are not used by glium are declared as raw pointers.
+#[repr(C)]
+#[allow(dead_code)]
+struct RenderDocApi {
+    get_api_version: extern "C" fn(*mut c_int, *mut c_int, *mut c_int),
+    set_capture_option_u32: *const c_void,
+    set_capture_option_f32: *const c_void,
+    get_capture_option_u32: *const c_void,
+    get_capture_option_f32: *const c_void,
+    set_focus_toggle_keys: *const c_void,
+    set_capture_keys: *const c_void,
+    get_overlay_bits: *const c_void,
+    mask_overlay_bits: *const c_void,
+    shutdown: *const c_void,
+    unload_crash_handler: *const c_void,
+    set_log_file_path_template: *const c_void,
+    get_log_file_path_template: *const c_void,
+    get_num_captures: extern "C" fn() -> u32,
+    get_capture: *const c_void,
+    trigger_capture: extern "C" fn(),
+    is_target_control_connected: *const c_void,
+    launch_replay_ui: *const c_void,
+    set_active_window: *const c_void,
+    start_frame_capture: extern "C" fn(*mut c_void, *mut c_void),
+    is_frame_capturing: extern "C" fn() -> u32,
+    end_frame_capture: extern "C" fn(*mut c_void, *mut c_void) -> u32,
+    trigger_multi_frame_capture: extern "C" fn(u32),
+}
+
+/// Connection to RenderDoc, when the application is running under it.
+pub struct RenderDoc {
+    api: *const RenderDocApi,
+    // the API is not thread-safe
+    marker: PhantomData<*mut u8>,
+}
+
+impl RenderDoc {
+    /// Connects to RenderDoc. Returns `None` if the program was not launched by RenderDoc, or
+    /// if the platform is not supported.
+    ///
+    /// This never loads RenderDoc by itself.
+    pub fn new() -> Option<RenderDoc> {
+        unsafe {
+            let get_api = match get_api_entry_point() {
+                Some(f) => f,
+                None => return None
+            };
+
+            let mut api = ptr::null_mut();
+            if get_api(API_VERSION_1_1_0, &mut api) != 1 || api.is_null() {
+                return None;
+            }
+
+            Some(RenderDoc {
+                api: api as *const RenderDocApi,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    /// Returns the version of RenderDoc, as `(major, minor, patch)`.
+    pub fn get_api_version(&self) -> (u32, u32, u32) {
+        let (mut major, mut minor, mut patch) = (0, 0, 0);
+        unsafe { ((*self.api).get_api_version)(&mut major, &mut minor, &mut patch) };
+        (major as u32, minor as u32, patch as u32)
+    }
+
+    /// Asks RenderDoc to capture the next frame, which is the frame between the next two
+    /// calls to `Frame::finish`.
+    #[inline]
+    pub fn trigger_capture(&self) {
+        unsafe { ((*self.api).trigger_capture)() }
+    }
+
+    /// Asks RenderDoc to capture the given number of frames, starting with the next one.
+    #[inline]
+    pub fn trigger_multi_frame_capture(&self, frames: u32) {
+        unsafe { ((*self.api).trigger_multi_frame_capture)(frames) }
+    }
+
+    /// Starts capturing the commands of the context that is current on this thread.
+    #[inline]
+    pub fn start_frame_capture(&self) {
+        unsafe { ((*self.api).start_frame_capture)(ptr::null_mut(), ptr::null_mut()) }
+    }
+
+    /// Stops the capture started with `start_frame_capture`. Returns `false` if the capture
+    /// failed.
+    #[inline]
+    pub fn end_frame_capture(&self) -> bool {
+        unsafe { ((*self.api).end_frame_capture)(ptr::null_mut(), ptr::null_mut()) != 0 }
+    }
+
+    /// Returns true if a capture is in progress.
+    #[inline]
+    pub fn is_frame_capturing(&self) -> bool {
+        unsafe { ((*self.api).is_frame_capturing)() != 0 }
+    }
+
+    /// Returns the number of captures that have been made so far.
+    #[inline]
+    pub fn get_num_captures(&self) -> u32 {
+        unsafe { ((*self.api).get_num_captures)() }
+    }
+
+    /// Captures all the commands submitted by the closure. Returns `false` if the capture
+    /// failed.
+    ///
+    /// Note that the context must be current when calling this function, which is the case
+    /// after any glium function has been called on this thread.
+    pub fn capture<F>(&self, f: F) -> bool where F: FnOnce() {
+        self.start_frame_capture();
+        f();
+        self.end_frame_capture()
+    }
+}
+
+/// Returns the `RENDERDOC_GetAPI` function if the RenderDoc library is loaded in the process.
+#[cfg(target_os = "linux")]
+unsafe fn get_api_entry_point() -> Option<GetApiFn> {
+    use std::mem;
+    use std::os::raw::c_char;
+
+    const RTLD_NOW: c_int = 2;
+    const RTLD_NOLOAD: c_int = 4;
+
+    #[link(name = "dl")]
+    extern "C" {
+        fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    let lib = dlopen(b"librenderdoc.so\0".as_ptr() as *const _, RTLD_NOW | RTLD_NOLOAD);
+    if lib.is_null() {
+        return None;
+    }
+
+    let symbol = dlsym(lib, b"RENDERDOC_GetAPI\0".as_ptr() as *const _);
+    if symbol.is_null() {
+        return None;
+    }
+
+    Some(mem::transmute::<*mut c_void, GetApiFn>(symbol))
+}
+
+/// Returns the `RENDERDOC_GetAPI` function if the RenderDoc library is loaded in the process.
+#[cfg(target_os = "windows")]
+unsafe fn get_api_entry_point() -> Option<GetApiFn> {
+    use std::mem;
+    use std::os::raw::c_char;
+
+    extern "system" {
+        fn GetModuleHandleA(name: *const c_char) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+    }
+
+    let module = GetModuleHandleA(b"renderdoc.dll\0".as_ptr() as *const _);
+    if module.is_null() {
+        return None;
+    }
+
+    let symbol = GetProcAddress(module, b"RENDERDOC_GetAPI\0".as_ptr() as *const _);
+    if symbol.is_null() {
+        return None;
+    }
+
+    Some(mem::transmute::<*mut c_void, GetApiFn>(symbol))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+unsafe fn get_api_entry_point() -> Option<GetApiFn> {
+    None
+}
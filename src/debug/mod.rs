@@ -12,7 +12,11 @@ use gl;
 use std::rc::Rc;
 use std::mem;
 
-pub use context::DebugCallbackBehavior;
+pub use context::{DebugCallbackBehavior, ValidationLevel};
+
+#[cfg(feature = "capture")]
+pub mod capture;
+pub mod profiler;
 
 /// Represents a callback that can be used for the debug output feature of OpenGL.
 ///
@@ -124,36 +128,44 @@ pub struct TimestampQuery {
 impl TimestampQuery {
     /// Creates a new `TimestampQuery`. Returns `None` if the backend doesn't support it.
     pub fn new<F: ?Sized>(facade: &F) -> Option<TimestampQuery> where F: Facade {
-        let ctxt = facade.get_context().make_current();
-
-        let id = if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
-            unsafe {
-                let mut id = mem::uninitialized();
-                ctxt.gl.GenQueries(1, &mut id);
-
-                ctxt.gl.QueryCounter(id, gl::TIMESTAMP);
-
-                Some(id)
-            }
-
-        } else if ctxt.extensions.gl_ext_disjoint_timer_query {
-            unsafe {
-                let mut id = mem::uninitialized();
-                ctxt.gl.GenQueriesEXT(1, &mut id);
+        if !TimestampQuery::is_supported(facade.get_context()) {
+            return None;
+        }
 
-                ctxt.gl.QueryCounterEXT(id, gl::TIMESTAMP);
+        Some(TimestampQuery::new_supported(facade.get_context()))
+    }
 
-                Some(id)
+    /// Creates a new `TimestampQuery`. The backend must support timestamp queries.
+    fn new_supported(context: &Rc<Context>) -> TimestampQuery {
+        let id = {
+            let ctxt = context.make_current();
+
+            if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
+                unsafe {
+                    let mut id = mem::uninitialized();
+                    ctxt.gl.GenQueries(1, &mut id);
+                    id
+                }
+
+            } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+                unsafe {
+                    let mut id = mem::uninitialized();
+                    ctxt.gl.GenQueriesEXT(1, &mut id);
+                    id
+                }
+
+            } else {
+                unreachable!();
             }
+        };
 
-        } else {
-            None
+        let query = TimestampQuery {
+            context: context.clone(),
+            id: id,
         };
 
-        id.map(|q| TimestampQuery {
-            context: facade.get_context().clone(),
-            id: q
-        })
+        query.record();
+        query
     }
 
     /// Queries the counter to see if the timestamp is already available.
@@ -161,11 +173,9 @@ impl TimestampQuery {
     /// It takes some time to retreive the value, during which you can execute other
     /// functions.
     pub fn is_ready(&self) -> bool {
-        use std::mem;
-
         let ctxt = self.context.make_current();
 
-        if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
+        if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
             unsafe {
                 let mut value = mem::uninitialized();
                 ctxt.gl.GetQueryObjectiv(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
@@ -184,19 +194,52 @@ impl TimestampQuery {
         }
     }
 
-    /// Returns the value of the timestamp. Blocks until it is available.
+    /// Returns the value of the timestamp, in nanoseconds. Blocks until it is available.
     ///
     /// This function doesn't block if `is_ready` returns true.
+    #[inline]
     pub fn get(self) -> u64 {
-        use std::mem;
+        self.raw_get()
+    }
+
+    /// Returns the value of the timestamp, in nanoseconds, if it is available. Returns `None`
+    /// if the GPU hasn't reached the point where the timestamp was recorded yet. Never blocks.
+    #[inline]
+    pub fn try_get(&self) -> Option<u64> {
+        if self.is_ready() {
+            Some(self.raw_get())
+        } else {
+            None
+        }
+    }
 
+    /// Returns true if the backend supports timestamp queries.
+    fn is_supported(context: &Context) -> bool {
+        let ctxt = context.make_current();
+        ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query ||
+            ctxt.extensions.gl_ext_disjoint_timer_query
+    }
+
+    /// Records the current timestamp again, replacing the previous value of the query.
+    fn record(&self) {
         let ctxt = self.context.make_current();
 
-        if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
+        if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
+            unsafe { ctxt.gl.QueryCounter(self.id, gl::TIMESTAMP); }
+        } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+            unsafe { ctxt.gl.QueryCounterEXT(self.id, gl::TIMESTAMP); }
+        } else {
+            unreachable!();
+        }
+    }
+
+    fn raw_get(&self) -> u64 {
+        let ctxt = self.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
             unsafe {
                 let mut value = mem::uninitialized();
                 ctxt.gl.GetQueryObjectui64v(self.id, gl::QUERY_RESULT, &mut value);
-                ctxt.gl.DeleteQueries(1, [self.id].as_ptr());
                 value
             }
 
@@ -204,7 +247,6 @@ impl TimestampQuery {
             unsafe {
                 let mut value = mem::uninitialized();
                 ctxt.gl.GetQueryObjectui64vEXT(self.id, gl::QUERY_RESULT_EXT, &mut value);
-                ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
                 value
             }
 
@@ -213,3 +255,19 @@ impl TimestampQuery {
         }
     }
 }
+
+impl Drop for TimestampQuery {
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
+                ctxt.gl.DeleteQueries(1, [self.id].as_ptr());
+            } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+                ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
+            } else {
+                unreachable!();
+            }
+        }
+    }
+}
@@ -0,0 +1,329 @@
+/*!
+Measures the GPU time spent in named sections of a frame.
+
+The profiler surrounds each scope with two `TimestampQuery`s. Since reading the value of a query
+immediately would stall the CPU until the GPU has finished executing the commands, the results
+of a frame are only read when they are available, usually one or two frames later. The queries
+are reused from frame to frame.
+
+```no_run
+# use glium::Surface;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+use glium::debug::profiler::Profiler;
+
+let profiler = Profiler::new(&display).unwrap();
+
+loop {
+    let mut target = display.draw();
+
+    {
+        let _frame = profiler.scope("frame");
+
+        {
+            let _shadows = profiler.scope("shadows");
+            // draw the shadows here
+        }
+
+        {
+            let _scene = profiler.scope("scene");
+            // draw the scene here
+        }
+    }
+
+    target.finish().unwrap();
+
+    if let Some(report) = profiler.end_frame() {
+        println!("{}", report);
+    }
+}
+```
+
+*/
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+
+use super::TimestampQuery;
+
+/// Number of frames whose results can be waited for. Each one has its own queries, which are
+/// reused when the frame is recorded again. If the results of a frame aren't available when
+/// its queries must be reused, they are discarded.
+const FRAME_SLOTS: usize = 3;
+
+/// Error that happens when the backend doesn't support timestamp queries.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ProfilerNotSupportedError;
+
+impl fmt::Display for ProfilerNotSupportedError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ProfilerNotSupportedError {
+    fn description(&self) -> &str {
+        "The backend doesn't support timestamp queries"
+    }
+}
+
+/// Records the GPU time spent in named scopes, frame by frame.
+pub struct Profiler {
+    context: Rc<Context>,
+    frames: RefCell<Vec<FrameRecording>>,
+    // index within `frames` of the frame that is being recorded
+    current: Cell<usize>,
+    // number of frames recorded before the current one whose results haven't been read yet
+    pending: Cell<usize>,
+}
+
+/// Queries of a frame.
+struct FrameRecording {
+    scopes: Vec<RecordedScope>,
+    // indices within `scopes` of the scopes that are still open
+    stack: Vec<usize>,
+    // the queries of the frame, which are kept when the frame is recorded again
+    queries: Vec<TimestampQuery>,
+    // number of elements of `queries` that have been recorded during this frame
+    used_queries: usize,
+}
+
+struct RecordedScope {
+    name: String,
+    parent: Option<usize>,
+    // indices within `queries`
+    begin: usize,
+    end: Option<usize>,
+}
+
+impl Profiler {
+    /// Builds a new profiler. Returns an error if the backend doesn't support timestamp
+    /// queries.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<Profiler, ProfilerNotSupportedError>
+                          where F: Facade
+    {
+        if !TimestampQuery::is_supported(facade.get_context()) {
+            return Err(ProfilerNotSupportedError);
+        }
+
+        Ok(Profiler {
+            context: facade.get_context().clone(),
+            frames: RefCell::new((0 .. FRAME_SLOTS).map(|_| FrameRecording::new()).collect()),
+            current: Cell::new(0),
+            pending: Cell::new(0),
+        })
+    }
+
+    /// Starts measuring a scope. The measure stops when the returned object is destroyed.
+    ///
+    /// Scopes that are started while another scope is alive are children of this scope in the
+    /// report.
+    pub fn scope(&self, name: &str) -> ProfilerScope {
+        let mut frames = self.frames.borrow_mut();
+        let current = &mut frames[self.current.get()];
+
+        let begin = current.record_timestamp(&self.context);
+        let parent = current.stack.last().cloned();
+        current.scopes.push(RecordedScope {
+            name: name.to_owned(),
+            parent: parent,
+            begin: begin,
+            end: None,
+        });
+
+        let index = current.scopes.len() - 1;
+        current.stack.push(index);
+
+        ProfilerScope { profiler: self }
+    }
+
+    /// Marks the end of the current frame.
+    ///
+    /// Returns the report of the oldest frame whose results are available, if any. Never
+    /// blocks: if the results of a frame are still unavailable after the next two frames have
+    /// been recorded, they are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a scope of the current frame is still alive.
+    pub fn end_frame(&self) -> Option<FrameReport> {
+        let mut frames = self.frames.borrow_mut();
+        let current = self.current.get();
+        assert!(frames[current].stack.is_empty(), "A profiler scope is still alive at the end \
+                                                   of the frame");
+
+        let mut pending = self.pending.get() + 1;
+
+        let oldest = (current + FRAME_SLOTS + 1 - pending) % FRAME_SLOTS;
+        let report = if frames[oldest].is_ready() {
+            pending -= 1;
+            let mut report = frames[oldest].take_report();
+            report.disjoint = self.context.is_gpu_disjoint();
+            Some(report)
+        } else {
+            None
+        };
+
+        // the queries of the next slot are reused, even if its results are still unavailable
+        let next = (current + 1) % FRAME_SLOTS;
+        if pending == FRAME_SLOTS {
+            pending -= 1;
+        }
+
+        frames[next].clear();
+        self.current.set(next);
+        self.pending.set(pending);
+
+        report
+    }
+}
+
+impl FrameRecording {
+    #[inline]
+    fn new() -> FrameRecording {
+        FrameRecording {
+            scopes: Vec::new(),
+            stack: Vec::new(),
+            queries: Vec::new(),
+            used_queries: 0,
+        }
+    }
+
+    /// Records the current timestamp with the next unused query of the frame, and returns the
+    /// index of this query.
+    fn record_timestamp(&mut self, context: &Rc<Context>) -> usize {
+        if self.used_queries < self.queries.len() {
+            self.queries[self.used_queries].record();
+        } else {
+            self.queries.push(TimestampQuery::new_supported(context));
+        }
+
+        self.used_queries += 1;
+        self.used_queries - 1
+    }
+
+    /// Forgets the scopes of the frame, so that its queries can be used again.
+    fn clear(&mut self) {
+        self.scopes.clear();
+        self.stack.clear();
+        self.used_queries = 0;
+    }
+
+    /// Returns true if all the queries of this frame have their result available.
+    fn is_ready(&self) -> bool {
+        self.queries[.. self.used_queries].iter().all(|q| q.is_ready())
+    }
+
+    /// Reads the results of the queries and forgets the scopes. Blocks if the results are not
+    /// available.
+    fn take_report(&mut self) -> FrameReport {
+        let mut reports = Vec::with_capacity(self.scopes.len());
+        let mut parents = Vec::with_capacity(self.scopes.len());
+
+        for scope in self.scopes.drain(..) {
+            let begin = self.queries[scope.begin].raw_get();
+            let end = self.queries[scope.end.unwrap()].raw_get();
+
+            reports.push(Some(ScopeReport {
+                name: scope.name,
+                gpu_time_ns: end.saturating_sub(begin),
+                children: Vec::new(),
+            }));
+            parents.push(scope.parent);
+        }
+
+        self.used_queries = 0;
+
+        // children always come after their parent, so we move each scope into its parent by
+        // iterating in reverse order
+        let mut roots = Vec::new();
+        for index in (0 .. reports.len()).rev() {
+            let report = reports[index].take().unwrap();
+
+            match parents[index] {
+                Some(parent) => reports[parent].as_mut().unwrap().children.insert(0, report),
+                None => roots.insert(0, report),
+            }
+        }
+
+        FrameReport {
+            scopes: roots,
+            disjoint: false,
+        }
+    }
+}
+
+/// Measure of a scope. The measure stops when this object is destroyed.
+pub struct ProfilerScope<'a> {
+    profiler: &'a Profiler,
+}
+
+impl<'a> Drop for ProfilerScope<'a> {
+    fn drop(&mut self) {
+        let mut frames = self.profiler.frames.borrow_mut();
+        let current = &mut frames[self.profiler.current.get()];
+
+        let end = current.record_timestamp(&self.profiler.context);
+        let index = current.stack.pop().unwrap();
+        current.scopes[index].end = Some(end);
+    }
+}
+
+/// Timings of a frame.
+#[derive(Debug, Clone)]
+pub struct FrameReport {
+    /// The scopes that were started while no other scope was alive, in the order in which
+    /// they were started.
+    pub scopes: Vec<ScopeReport>,
+
+    /// True if the GPU reported an event that makes the timings meaningless, like a change of
+    /// frequency. Only OpenGL ES backends report such events. See `Context::is_gpu_disjoint`.
+    pub disjoint: bool,
+}
+
+/// Timing of a scope.
+#[derive(Debug, Clone)]
+pub struct ScopeReport {
+    /// Name passed to `Profiler::scope`.
+    pub name: String,
+
+    /// Number of nanoseconds that the GPU spent between the start and the end of the scope.
+    pub gpu_time_ns: u64,
+
+    /// Scopes that were started while this scope was alive.
+    pub children: Vec<ScopeReport>,
+}
+
+impl FrameReport {
+    /// Returns the sum of the GPU times of the top-level scopes, in nanoseconds.
+    #[inline]
+    pub fn total_gpu_time_ns(&self) -> u64 {
+        self.scopes.iter().map(|s| s.gpu_time_ns).sum()
+    }
+}
+
+impl fmt::Display for FrameReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fn write_scope(fmt: &mut fmt::Formatter, scope: &ScopeReport, depth: usize)
+                       -> fmt::Result
+        {
+            try!(writeln!(fmt, "{:indent$}{}: {:.3} ms", "", scope.name,
+                          scope.gpu_time_ns as f64 / 1_000_000.0, indent = depth * 2));
+
+            for child in &scope.children {
+                try!(write_scope(fmt, child, depth + 1));
+            }
+
+            Ok(())
+        }
+
+        for scope in &self.scopes {
+            try!(write_scope(fmt, scope, 0));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+/*!
+Draws lines and simple shapes without writing a pipeline, for debugging purposes.
+
+A `DebugDraw` accumulates lines during the frame, for example the bounding boxes of a physics
+engine or the frustum of a camera, and draws all of them with one draw call when `flush` is
+called.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let view_projection: [[f32; 4]; 4] = unsafe { ::std::mem::uninitialized() };
+use glium::debug_draw::DebugDraw;
+
+let mut debug = DebugDraw::new(&display).unwrap();
+
+debug.axis([0.0, 0.0, 0.0], 1.0);
+debug.aabb([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0], [1.0, 1.0, 0.0, 1.0]);
+debug.sphere([0.0, 2.0, 0.0], 0.5, [0.0, 1.0, 1.0, 1.0]);
+debug.line([0.0, 0.0, 0.0], [3.0, 3.0, 3.0], [1.0, 1.0, 1.0, 1.0]);
+
+let mut frame = display.draw();
+debug.flush(&mut frame, view_projection, &Default::default()).unwrap();
+frame.finish().unwrap();
+```
+
+The positions are multiplied by the matrix passed to `flush`, which is usually the same
+view-projection matrix as the rest of the scene. Pass draw parameters with a depth test if the
+lines must be hidden by the scene.
+
+*/
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use index::{NoIndices, PrimitiveType};
+use program::ProgramChooserCreationError;
+use vertex::{IntoVerticesSource, VertexBuffer};
+
+use DrawError;
+use DrawParameters;
+use Program;
+use Surface;
+
+/// Number of segments of each circle of `DebugDraw::sphere`.
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// An end of a line.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vertex {
+    /// The position of the vertex.
+    pub position: [f32; 3],
+    /// The color of the vertex.
+    pub color: [f32; 4],
+}
+
+implement_vertex!(Vertex, position, color);
+
+/// Accumulates lines and draws them.
+pub struct DebugDraw {
+    context: Rc<Context>,
+    program: Program,
+    vertices: Vec<Vertex>,
+    vertex_buffer: Option<VertexBuffer<Vertex>>,
+}
+
+impl DebugDraw {
+    /// Builds the program. The vertex buffer is created by the first call to `flush`.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<DebugDraw, ProgramChooserCreationError>
+                          where F: Facade
+    {
+        let program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform mat4 matrix;
+
+                    in vec3 position;
+                    in vec4 color;
+
+                    out vec4 v_color;
+
+                    void main() {
+                        v_color = color;
+                        gl_Position = matrix * vec4(position, 1.0);
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    in vec4 v_color;
+
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = v_color;
+                    }
+                "
+            },
+            100 => {
+                vertex: "
+                    #version 100
+
+                    uniform lowp mat4 matrix;
+
+                    attribute lowp vec3 position;
+                    attribute lowp vec4 color;
+
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        v_color = color;
+                        gl_Position = matrix * vec4(position, 1.0);
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = v_color;
+                    }
+                "
+            },
+        ));
+
+        Ok(DebugDraw {
+            context: facade.get_context().clone(),
+            program: program,
+            vertices: Vec::new(),
+            vertex_buffer: None,
+        })
+    }
+
+    /// Adds a line.
+    #[inline]
+    pub fn line(&mut self, from: [f32; 3], to: [f32; 3], color: [f32; 4]) {
+        self.vertices.push(Vertex { position: from, color: color });
+        self.vertices.push(Vertex { position: to, color: color });
+    }
+
+    /// Adds the twelve edges of an axis-aligned box.
+    pub fn aabb(&mut self, min: [f32; 3], max: [f32; 3], color: [f32; 4]) {
+        let corner = |i: usize| [
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        ];
+
+        // each edge joins two corners whose indices differ by one bit
+        for i in 0 .. 8 {
+            for &bit in &[1, 2, 4] {
+                if i & bit == 0 {
+                    self.line(corner(i), corner(i | bit), color);
+                }
+            }
+        }
+    }
+
+    /// Adds three circles around the X, Y and Z axes, which show the outline of a sphere.
+    pub fn sphere(&mut self, center: [f32; 3], radius: f32, color: [f32; 4]) {
+        for axis in 0 .. 3 {
+            let point = |segment: usize| {
+                let angle = 2.0 * PI * segment as f32 / CIRCLE_SEGMENTS as f32;
+                let mut point = center;
+                point[(axis + 1) % 3] += radius * angle.cos();
+                point[(axis + 2) % 3] += radius * angle.sin();
+                point
+            };
+
+            for segment in 0 .. CIRCLE_SEGMENTS {
+                self.line(point(segment), point(segment + 1), color);
+            }
+        }
+    }
+
+    /// Adds the X, Y and Z axes of length `size` starting at `origin`, in red, green and blue.
+    pub fn axis(&mut self, origin: [f32; 3], size: f32) {
+        for axis in 0 .. 3 {
+            let mut end = origin;
+            end[axis] += size;
+
+            let mut color = [0.0, 0.0, 0.0, 1.0];
+            color[axis] = 1.0;
+
+            self.line(origin, end, color);
+        }
+    }
+
+    /// Returns the number of lines that have been added since the last call to `flush`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vertices.len() / 2
+    }
+
+    /// Returns true if no line has been added since the last call to `flush`.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Removes the lines without drawing them.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    /// Draws the lines, with their positions multiplied by `matrix`, and removes them.
+    ///
+    /// If the vertex buffer is too small, it is replaced with a buffer whose capacity is the
+    /// next power of two.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the vertex buffer can't be created.
+    pub fn flush<S: ?Sized>(&mut self, target: &mut S, matrix: [[f32; 4]; 4],
+                            draw_parameters: &DrawParameters) -> Result<(), DrawError>
+                            where S: Surface
+    {
+        if self.vertices.is_empty() {
+            return Ok(());
+        }
+
+        let capacity = self.vertex_buffer.as_ref().map(|b| b.len()).unwrap_or(0);
+        if capacity < self.vertices.len() {
+            let capacity = self.vertices.len().next_power_of_two();
+            self.vertex_buffer = Some(VertexBuffer::empty_dynamic(&self.context, capacity)
+                                .expect("Failed to create the vertex buffer of the debug lines"));
+        }
+
+        let result = {
+            let vertex_buffer = self.vertex_buffer.as_ref().unwrap();
+            let vertices = vertex_buffer.slice(0 .. self.vertices.len()).unwrap();
+            vertices.write(&self.vertices);
+
+            let uniforms = uniform! { matrix: matrix };
+            let indices = NoIndices(PrimitiveType::LinesList);
+            target.draw_dyn(&[vertices.into_vertices_source()], indices.into(), &self.program,
+                            &uniforms, draw_parameters)
+        };
+
+        self.vertices.clear();
+        result
+    }
+}
@@ -0,0 +1,562 @@
+/*!
+Retained list of draw commands.
+
+A `DrawList` records draw commands instead of executing them immediately. The commands can then
+be sorted in order to minimize the number of state changes between them (mostly program and
+buffer switches), and submitted to a surface all at once.
+
+```no_run
+#[macro_use]
+extern crate glium;
+# use glium::Surface;
+# fn main() {
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let vertex_buffer: glium::vertex::VertexBufferAny = unsafe { ::std::mem::uninitialized() };
+# let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+# let program_a: glium::Program = unsafe { ::std::mem::uninitialized() };
+# let program_b: glium::Program = unsafe { ::std::mem::uninitialized() };
+let uniforms = uniform! { scale: 2.0f32 };
+let params = Default::default();
+
+let mut list = glium::draw_list::DrawList::new();
+list.draw(&vertex_buffer, &indices, &program_a, &uniforms, &params);
+list.draw(&vertex_buffer, &indices, &program_b, &uniforms, &params);
+list.draw(&vertex_buffer, &indices, &program_a, &uniforms, &params);
+list.sort();
+
+let mut target = display.draw();
+list.submit(&mut target).unwrap();
+target.finish().unwrap();
+# }
+```
+
+The sort is stable, which means that commands that share the same state keep the order in
+which they were recorded. However commands that use different programs or buffers can be
+reordered, so you shouldn't call `sort` on a list whose result depends on the drawing order
+(for example when blending transparent objects).
+
+# Instancing
+
+Drawing many objects that share the same geometry and only differ by a few values, such as their
+position or color, usually costs one draw call per object. `submit_instanced` can merge these
+draw calls into a single instanced draw call.
+
+In order to do so, the per-object values are passed as uniforms whose names are vertex
+attributes of the program, instead of uniforms. When submitting, the values of consecutive
+commands that only differ by these attributes are packed in a temporary per-instance buffer,
+and the commands are drawn with one draw call.
+
+```no_run
+#[macro_use]
+extern crate glium;
+# use glium::Surface;
+# fn main() {
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let vertex_buffer: glium::vertex::VertexBufferAny = unsafe { ::std::mem::uninitialized() };
+# let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+// `offset` is an attribute, and `scale` is a regular uniform
+let program = program!(&display,
+    140 => {
+        vertex: "
+            #version 140
+            in vec2 position;
+            in vec2 offset;
+            uniform float scale;
+            void main() {
+                gl_Position = vec4(position * scale + offset, 0.0, 1.0);
+            }
+        ",
+        fragment: "
+            #version 140
+            out vec4 color;
+            void main() {
+                color = vec4(1.0);
+            }
+        "
+    },
+).unwrap();
+
+let objects = (0 .. 100).map(|i| uniform! { offset: [i as f32 / 50.0 - 1.0, 0.0], scale: 0.1f32 })
+                        .collect::<Vec<_>>();
+let params = Default::default();
+
+let mut list = glium::draw_list::DrawList::new();
+for uniforms in &objects {
+    list.draw(&vertex_buffer, &indices, &program, uniforms, &params);
+}
+
+let mut target = display.draw();
+let report = list.submit_instanced(&display, &mut target).unwrap();
+assert_eq!(report.draw_calls(), 1);
+target.finish().unwrap();
+# }
+```
+
+Commands are merged if they use the same program, the same vertex and index buffers, the same
+draw parameters and the same values for the other uniforms. The draw parameters must not
+contain any query or transform feedback session. Only consecutive commands are merged, which
+means that you should call `sort` first if the order doesn't matter.
+
+The per-object values can be floats, vectors or square matrices of `f32`s, and signed or
+unsigned integers or vectors of integers.
+
+*/
+use std::borrow::Cow;
+use std::{mem, ptr, slice};
+use std::vec::IntoIter;
+
+use gl;
+use BufferExt;
+use DrawError;
+use DrawParameters;
+use GlObject;
+use Program;
+use Surface;
+
+use backend::Facade;
+use index::IndicesSource;
+use uniforms::{Uniforms, UniformValue};
+use vertex::{AttributeType, IntoVerticesSource, MultiVerticesSource, VertexBufferAny};
+use vertex::VerticesSource;
+
+/// A list of recorded draw commands.
+pub struct DrawList<'a> {
+    commands: Vec<DrawCommand<'a>>,
+}
+
+/// A single recorded draw command.
+struct DrawCommand<'a> {
+    key: SortKey,
+    vertices: Vec<VerticesSource<'a>>,
+    indices: IndicesSource<'a>,
+    program: &'a Program,
+    uniforms: RecordedUniforms<'a>,
+    draw_parameters: DrawParameters<'a>,
+}
+
+/// Key used to sort the commands. Commands with equal keys are grouped together.
+///
+/// Contains the address of the program, then the ID of the first vertex buffer and the ID of
+/// the index buffer.
+type SortKey = (usize, gl::types::GLuint, gl::types::GLuint);
+
+impl<'a> DrawList<'a> {
+    /// Builds a new empty list.
+    #[inline]
+    pub fn new() -> DrawList<'a> {
+        DrawList {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Records a draw command.
+    ///
+    /// The parameters are the same as `Surface::draw`. The values of the uniforms are read
+    /// immediately, but the objects they reference must outlive the list.
+    ///
+    /// No error checking is done at this point. Errors are reported when the list is submitted.
+    pub fn draw<V, I, U>(&mut self, vertices: V, indices: I, program: &'a Program,
+                         uniforms: &'a U, draw_parameters: &DrawParameters<'a>)
+                         where V: MultiVerticesSource<'a>, I: Into<IndicesSource<'a>>,
+                               U: Uniforms
+    {
+        let vertices = vertices.iter().collect::<Vec<_>>();
+        let indices = indices.into();
+
+        let mut recorded = Vec::new();
+        uniforms.visit_values(|name, value| recorded.push((name.to_owned(), value)));
+
+        let vertex_key = vertices.iter().filter_map(|v| match v {
+            &VerticesSource::VertexBuffer(ref buffer, _, _) => Some(buffer.get_id()),
+            &VerticesSource::Marker { .. } => None,
+        }).next().unwrap_or(0);
+
+        let index_key = match &indices {
+            &IndicesSource::IndexBuffer { ref buffer, .. } => buffer.get_id(),
+            &IndicesSource::MultidrawArray { ref buffer, .. } => buffer.get_id(),
+            &IndicesSource::MultidrawElement { ref indices, .. } => indices.get_id(),
+            &IndicesSource::NoIndices { .. } => 0,
+        };
+
+        self.commands.push(DrawCommand {
+            key: (program as *const Program as usize, vertex_key, index_key),
+            vertices: vertices,
+            indices: indices,
+            program: program,
+            uniforms: RecordedUniforms(recorded),
+            draw_parameters: draw_parameters.clone(),
+        });
+    }
+
+    /// Sorts the commands in order to minimize the number of state changes when submitting.
+    ///
+    /// Commands are grouped by program, then by vertex buffer and index buffer. Commands that
+    /// have the same state keep their relative order.
+    #[inline]
+    pub fn sort(&mut self) {
+        self.commands.sort_by_key(|c| c.key);
+    }
+
+    /// Draws all the commands of the list on a surface, in order.
+    ///
+    /// Stops at the first command that fails and returns its error. The list is left untouched
+    /// and can be submitted again.
+    pub fn submit<S>(&self, surface: &mut S) -> Result<(), DrawError> where S: Surface {
+        for command in &self.commands {
+            try!(surface.draw(RecordedVertices(command.vertices.clone()),
+                              command.indices.clone(), command.program, &command.uniforms,
+                              &command.draw_parameters));
+        }
+
+        Ok(())
+    }
+
+    /// Draws all the commands of the list on a surface, in order, and merges consecutive
+    /// commands into instanced draw calls when possible. See the module-level documentation.
+    ///
+    /// The uniforms whose names are attributes of the program are passed as per-instance
+    /// attributes, even if the command can't be merged with its neighbours.
+    ///
+    /// Stops at the first draw call that fails and returns its error. Returns
+    /// `InstancingNotSupported` if a command needs per-instance attributes and the backend
+    /// doesn't support instancing.
+    pub fn submit_instanced<F: ?Sized, S>(&self, facade: &F, surface: &mut S)
+                                          -> Result<BatchReport, DrawError>
+                                          where F: Facade, S: Surface
+    {
+        let mut report = BatchReport { batch_sizes: Vec::new() };
+        let mut start = 0;
+
+        while start < self.commands.len() {
+            let first = &self.commands[start];
+            let attributes = per_object_attributes(first);
+
+            if attributes.is_empty() {
+                try!(surface.draw(RecordedVertices(first.vertices.clone()), first.indices.clone(),
+                                  first.program, &first.uniforms, &first.draw_parameters));
+                report.batch_sizes.push(1);
+                start += 1;
+                continue;
+            }
+
+            let mut end = start + 1;
+            while end < self.commands.len() &&
+                  can_merge(first, &self.commands[end], &attributes)
+            {
+                end += 1;
+            }
+
+            try!(draw_batch(facade, surface, &self.commands[start .. end], &attributes));
+            report.batch_sizes.push(end - start);
+            start = end;
+        }
+
+        Ok(report)
+    }
+
+    /// Returns the number of commands in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if the list doesn't contain any command.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Removes all the commands from the list.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl<'a> Default for DrawList<'a> {
+    #[inline]
+    fn default() -> DrawList<'a> {
+        DrawList::new()
+    }
+}
+
+/// Result of a call to `DrawList::submit_instanced`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    /// The number of commands drawn by each draw call, in the order of submission.
+    pub batch_sizes: Vec<usize>,
+}
+
+impl BatchReport {
+    /// Returns the number of draw calls that have been submitted.
+    #[inline]
+    pub fn draw_calls(&self) -> usize {
+        self.batch_sizes.len()
+    }
+
+    /// Returns the number of commands that have been drawn.
+    #[inline]
+    pub fn commands(&self) -> usize {
+        self.batch_sizes.iter().sum()
+    }
+
+    /// Returns the largest number of commands drawn by a single draw call.
+    #[inline]
+    pub fn largest_batch(&self) -> usize {
+        self.batch_sizes.iter().cloned().max().unwrap_or(0)
+    }
+}
+
+/// Returns the names and types of the uniforms of a command that must be passed as
+/// per-instance attributes. Returns an empty list if the command already uses instancing.
+fn per_object_attributes(command: &DrawCommand) -> Vec<(String, AttributeType)> {
+    let mut provided = Vec::new();
+
+    for source in &command.vertices {
+        match *source {
+            VerticesSource::VertexBuffer(_, _, Some(_)) => return Vec::new(),
+            VerticesSource::Marker { per_instance: true, .. } => return Vec::new(),
+            VerticesSource::VertexBuffer(_, bindings, None) => {
+                provided.extend(bindings.iter().map(|b| &b.0[..]));
+            },
+            VerticesSource::Marker { .. } => (),
+        }
+    }
+
+    command.uniforms.0.iter().filter(|&&(ref name, _)| {
+        command.program.get_attribute(name).is_some() && !provided.contains(&&name[..])
+    }).filter_map(|&(ref name, ref value)| {
+        as_attribute(value).map(|(ty, _)| (name.clone(), ty))
+    }).collect()
+}
+
+/// Returns true if a command can be drawn in the same draw call as the first command of a
+/// batch.
+fn can_merge(first: &DrawCommand, other: &DrawCommand, attributes: &[(String, AttributeType)])
+             -> bool
+{
+    if !ptr::eq(first.program, other.program) || first.vertices.len() != other.vertices.len() ||
+       !same_indices(&first.indices, &other.indices) ||
+       !same_draw_parameters(&first.draw_parameters, &other.draw_parameters) ||
+       first.uniforms.0.len() != other.uniforms.0.len()
+    {
+        return false;
+    }
+
+    if !first.vertices.iter().zip(other.vertices.iter()).all(|(a, b)| same_vertices(a, b)) {
+        return false;
+    }
+
+    other.uniforms.0.iter().all(|&(ref name, ref value)| {
+        let first_value = match first.uniforms.get(name) {
+            Some(v) => v,
+            None => return false,
+        };
+
+        match attributes.iter().find(|a| &a.0 == name) {
+            Some(&(_, ty)) => as_attribute(value).map(|(t, _)| t) == Some(ty),
+            None => same_uniform_value(first_value, value),
+        }
+    })
+}
+
+/// Draws a batch of commands with an instanced draw call.
+fn draw_batch<F: ?Sized, S>(facade: &F, surface: &mut S, commands: &[DrawCommand],
+                            attributes: &[(String, AttributeType)]) -> Result<(), DrawError>
+                            where F: Facade, S: Surface
+{
+    let mut bindings = Vec::with_capacity(attributes.len());
+    let mut instance_size = 0;
+    for &(ref name, ty) in attributes {
+        bindings.push((Cow::Owned(name.clone()), instance_size, ty, false));
+        instance_size += ty.get_size_bytes();
+    }
+
+    let mut data = Vec::with_capacity(instance_size * commands.len());
+    for command in commands {
+        for &(ref name, _) in attributes {
+            // `can_merge` has checked that all the commands have these values
+            let value = command.uniforms.get(name).unwrap();
+            data.extend_from_slice(as_attribute(value).unwrap().1);
+        }
+    }
+
+    // the types of the attributes are supported by all the backends that support instancing
+    let instances = VertexBufferAny::new_raw(facade, &data, Cow::Owned(bindings), instance_size)
+                        .expect("Failed to create the buffer of per-instance attributes");
+    let instances = match instances.per_instance() {
+        Ok(i) => i,
+        Err(_) => return Err(DrawError::InstancingNotSupported),
+    };
+
+    let first = &commands[0];
+
+    let mut vertices = first.vertices.clone();
+    vertices.push(instances.into_vertices_source());
+
+    let uniforms = RecordedUniforms(first.uniforms.0.iter().filter(|&&(ref name, _)| {
+        !attributes.iter().any(|a| &a.0 == name)
+    }).cloned().collect());
+
+    surface.draw(RecordedVertices(vertices), first.indices.clone(), first.program, &uniforms,
+                 &first.draw_parameters)
+}
+
+/// Returns the type of attribute that corresponds to a uniform value, and the content of the
+/// value.
+fn as_attribute<'b>(value: &'b UniformValue) -> Option<(AttributeType, &'b [u8])> {
+    fn bytes<T>(value: &T) -> &[u8] {
+        unsafe { slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) }
+    }
+
+    Some(match *value {
+        UniformValue::Float(ref v) => (AttributeType::F32, bytes(v)),
+        UniformValue::Vec2(ref v) => (AttributeType::F32F32, bytes(v)),
+        UniformValue::Vec3(ref v) => (AttributeType::F32F32F32, bytes(v)),
+        UniformValue::Vec4(ref v) => (AttributeType::F32F32F32F32, bytes(v)),
+        UniformValue::Mat2(ref v) => (AttributeType::F32x2x2, bytes(v)),
+        UniformValue::Mat3(ref v) => (AttributeType::F32x3x3, bytes(v)),
+        UniformValue::Mat4(ref v) => (AttributeType::F32x4x4, bytes(v)),
+        UniformValue::SignedInt(ref v) => (AttributeType::I32, bytes(v)),
+        UniformValue::IntVec2(ref v) => (AttributeType::I32I32, bytes(v)),
+        UniformValue::IntVec3(ref v) => (AttributeType::I32I32I32, bytes(v)),
+        UniformValue::IntVec4(ref v) => (AttributeType::I32I32I32I32, bytes(v)),
+        UniformValue::UnsignedInt(ref v) => (AttributeType::U32, bytes(v)),
+        UniformValue::UnsignedIntVec2(ref v) => (AttributeType::U32U32, bytes(v)),
+        UniformValue::UnsignedIntVec3(ref v) => (AttributeType::U32U32U32, bytes(v)),
+        UniformValue::UnsignedIntVec4(ref v) => (AttributeType::U32U32U32U32, bytes(v)),
+        _ => return None,
+    })
+}
+
+/// Returns true if two uniform values are known to be the same. Values whose content can't be
+/// compared, like buffers, are never the same.
+fn same_uniform_value(a: &UniformValue, b: &UniformValue) -> bool {
+    if let (Some(a), Some(b)) = (as_attribute(a), as_attribute(b)) {
+        return a == b;
+    }
+
+    macro_rules! compare {
+        ($($texture:ident),+) => (
+            match (*a, *b) {
+                (UniformValue::Bool(a), UniformValue::Bool(b)) => a == b,
+                (UniformValue::TextureHandle(a), UniformValue::TextureHandle(b)) => a == b,
+                $(
+                    (UniformValue::$texture(a, sa), UniformValue::$texture(b, sb)) => {
+                        ptr::eq(a, b) && sa == sb
+                    },
+                )+
+                _ => false,
+            }
+        );
+    }
+
+    compare!(Texture1d, CompressedTexture1d, SrgbTexture1d, CompressedSrgbTexture1d,
+             IntegralTexture1d, UnsignedTexture1d, DepthTexture1d, Texture2d, CompressedTexture2d,
+             SrgbTexture2d, CompressedSrgbTexture2d, IntegralTexture2d, UnsignedTexture2d,
+             DepthTexture2d, Texture2dMultisample, SrgbTexture2dMultisample,
+             IntegralTexture2dMultisample, UnsignedTexture2dMultisample,
+             DepthTexture2dMultisample, Texture3d, CompressedTexture3d, SrgbTexture3d,
+             CompressedSrgbTexture3d, IntegralTexture3d, UnsignedTexture3d, DepthTexture3d,
+             Texture1dArray, CompressedTexture1dArray, SrgbTexture1dArray,
+             CompressedSrgbTexture1dArray, IntegralTexture1dArray, UnsignedTexture1dArray,
+             DepthTexture1dArray, Texture2dArray, CompressedTexture2dArray, SrgbTexture2dArray,
+             CompressedSrgbTexture2dArray, IntegralTexture2dArray, UnsignedTexture2dArray,
+             DepthTexture2dArray, Texture2dMultisampleArray, SrgbTexture2dMultisampleArray,
+             IntegralTexture2dMultisampleArray, UnsignedTexture2dMultisampleArray,
+             DepthTexture2dMultisampleArray, Cubemap, CompressedCubemap, SrgbCubemap,
+             CompressedSrgbCubemap, IntegralCubemap, UnsignedCubemap, DepthCubemap,
+             CubemapArray, CompressedCubemapArray, SrgbCubemapArray, CompressedSrgbCubemapArray,
+             IntegralCubemapArray, UnsignedCubemapArray, DepthCubemapArray)
+}
+
+/// Returns true if two vertex sources read the same data.
+fn same_vertices(a: &VerticesSource, b: &VerticesSource) -> bool {
+    match (a, b) {
+        (&VerticesSource::VertexBuffer(ref a, fa, da),
+         &VerticesSource::VertexBuffer(ref b, fb, db)) =>
+        {
+            a.get_id() == b.get_id() && a.get_offset_bytes() == b.get_offset_bytes() &&
+            a.get_size() == b.get_size() && (ptr::eq(fa, fb) || fa == fb) && da == db
+        },
+        (&VerticesSource::Marker { len: la, per_instance: pa },
+         &VerticesSource::Marker { len: lb, per_instance: pb }) => la == lb && pa == pb,
+        _ => false,
+    }
+}
+
+/// Returns true if two sources of indices draw the same primitives.
+fn same_indices(a: &IndicesSource, b: &IndicesSource) -> bool {
+    match (a, b) {
+        (&IndicesSource::IndexBuffer { buffer: ref a, data_type: ta, primitives: pa,
+                                       vertices_range: ra },
+         &IndicesSource::IndexBuffer { buffer: ref b, data_type: tb, primitives: pb,
+                                       vertices_range: rb }) =>
+        {
+            a.get_id() == b.get_id() && a.get_offset_bytes() == b.get_offset_bytes() &&
+            a.get_size() == b.get_size() && ta == tb && pa == pb && ra == rb
+        },
+        (&IndicesSource::NoIndices { primitives: a },
+         &IndicesSource::NoIndices { primitives: b }) => a == b,
+        _ => false,
+    }
+}
+
+/// Returns true if two commands can be drawn with the same draw parameters. Parameters that
+/// contain queries or a transform feedback session are never the same.
+fn same_draw_parameters(a: &DrawParameters, b: &DrawParameters) -> bool {
+    fn has_objects(p: &DrawParameters) -> bool {
+        p.samples_passed_query.is_some() || p.time_elapsed_query.is_some() ||
+        p.primitives_generated_query.is_some() ||
+        p.transform_feedback_primitives_written_query.is_some() || p.condition.is_some() ||
+        p.transform_feedback.is_some()
+    }
+
+    !has_objects(a) && !has_objects(b) &&
+    a.depth == b.depth && a.stencil == b.stencil && a.blend == b.blend &&
+    a.color_mask == b.color_mask && a.line_width == b.line_width &&
+    a.point_size == b.point_size && a.program_point_size == b.program_point_size &&
+    a.point_fade_threshold_size == b.point_fade_threshold_size &&
+    a.point_sprite_coord_origin == b.point_sprite_coord_origin &&
+    a.backface_culling == b.backface_culling && a.polygon_mode == b.polygon_mode &&
+    a.multisampling == b.multisampling && a.dithering == b.dithering &&
+    a.viewport == b.viewport && a.scissor == b.scissor &&
+    a.draw_primitives == b.draw_primitives && a.smooth == b.smooth &&
+    a.provoking_vertex == b.provoking_vertex &&
+    a.primitive_bounding_box == b.primitive_bounding_box &&
+    a.primitive_restart_index == b.primitive_restart_index &&
+    a.missing_uniforms == b.missing_uniforms &&
+    a.ignore_missing_attributes == b.ignore_missing_attributes
+}
+
+/// Uniform values that have been read from a `Uniforms` object.
+struct RecordedUniforms<'a>(Vec<(String, UniformValue<'a>)>);
+
+impl<'a> RecordedUniforms<'a> {
+    /// Returns the value of a uniform.
+    #[inline]
+    fn get(&self, name: &str) -> Option<&UniformValue<'a>> {
+        self.0.iter().find(|v| v.0 == name).map(|v| &v.1)
+    }
+}
+
+impl<'a> Uniforms for RecordedUniforms<'a> {
+    #[inline]
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        for &(ref name, value) in &self.0 {
+            output(name, value);
+        }
+    }
+}
+
+/// List of vertex sources of a recorded command.
+struct RecordedVertices<'a>(Vec<VerticesSource<'a>>);
+
+impl<'a> MultiVerticesSource<'a> for RecordedVertices<'a> {
+    type Iterator = IntoIter<VerticesSource<'a>>;
+
+    #[inline]
+    fn iter(self) -> IntoIter<VerticesSource<'a>> {
+        self.0.into_iter()
+    }
+}
@@ -0,0 +1,355 @@
+use std::ops::Range;
+
+use uniforms::MissingUniforms;
+use vertex::TransformFeedbackSession;
+use Rect;
+
+use super::{BackfaceCullingMode, Blend, ConditionalRendering, Depth, DepthTest, DrawParameters};
+use super::{PointSpriteCoordOrigin, PolygonMode, PrimitivesGeneratedQuery, ProvokingVertex};
+use super::{SamplesQueryParam, Smooth, Stencil, TimeElapsedQuery};
+use super::TransformFeedbackPrimitivesWrittenQuery;
+
+/// Builds `DrawParameters` with chained setters.
+///
+/// Contrary to a struct literal with `.. Default::default()`, code that uses the builder
+/// doesn't need to be updated when a field is added to `DrawParameters`. Each parameter that
+/// isn't set keeps its default value.
+///
+/// # Example
+///
+/// ```
+/// use glium::draw_parameters::{DrawParametersBuilder, BackfaceCullingMode};
+///
+/// let params = DrawParametersBuilder::opaque_3d()
+///     .backface_culling(BackfaceCullingMode::CullingDisabled)
+///     .line_width(2.0)
+///     .build();
+///
+/// assert!(params.depth.write);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DrawParametersBuilder<'a> {
+    params: DrawParameters<'a>,
+}
+
+impl<'a> DrawParametersBuilder<'a> {
+    /// Builds a new builder whose parameters all have their default value.
+    #[inline]
+    pub fn new() -> DrawParametersBuilder<'a> {
+        DrawParametersBuilder {
+            params: Default::default(),
+        }
+    }
+
+    /// Preset for opaque 3D geometry: the depth test is `IfLess`, depth writes are enabled and
+    /// clockwise triangles are culled.
+    ///
+    /// This assumes that the front faces of your models are counter-clockwise, like the shapes
+    /// of the `shapes` module.
+    #[inline]
+    pub fn opaque_3d() -> DrawParametersBuilder<'a> {
+        DrawParametersBuilder::new()
+            .depth_test(DepthTest::IfLess)
+            .depth_write(true)
+            .backface_culling(BackfaceCullingMode::CullClockwise)
+    }
+
+    /// Preset for user interfaces and other transparent 2D elements drawn on top of the scene:
+    /// alpha blending is enabled, and the depth buffer is neither tested nor written.
+    #[inline]
+    pub fn alpha_blended_ui() -> DrawParametersBuilder<'a> {
+        DrawParametersBuilder::new()
+            .blend(Blend::alpha_blending())
+    }
+
+    /// Preset for rendering a shadow map: the depth test is `IfLess`, depth writes are enabled
+    /// and no color component is written.
+    ///
+    /// Counter-clockwise triangles, which are the front faces of models that follow the
+    /// conventions of `opaque_3d`, are culled. Storing the depth of the back faces reduces
+    /// shadow acne on lit surfaces.
+    #[inline]
+    pub fn shadow_pass() -> DrawParametersBuilder<'a> {
+        DrawParametersBuilder::new()
+            .depth_test(DepthTest::IfLess)
+            .depth_write(true)
+            .color_mask(false, false, false, false)
+            .backface_culling(BackfaceCullingMode::CullCounterClockwise)
+    }
+
+    /// Sets all the depth parameters. See `DrawParameters::depth`.
+    #[inline]
+    pub fn depth(mut self, depth: Depth) -> DrawParametersBuilder<'a> {
+        self.params.depth = depth;
+        self
+    }
+
+    /// Sets the depth test, without changing the other depth parameters.
+    #[inline]
+    pub fn depth_test(mut self, test: DepthTest) -> DrawParametersBuilder<'a> {
+        self.params.depth.test = test;
+        self
+    }
+
+    /// Sets whether depth values are written, without changing the other depth parameters.
+    #[inline]
+    pub fn depth_write(mut self, write: bool) -> DrawParametersBuilder<'a> {
+        self.params.depth.write = write;
+        self
+    }
+
+    /// See `DrawParameters::stencil`.
+    #[inline]
+    pub fn stencil(mut self, stencil: Stencil) -> DrawParametersBuilder<'a> {
+        self.params.stencil = stencil;
+        self
+    }
+
+    /// See `DrawParameters::blend`.
+    #[inline]
+    pub fn blend(mut self, blend: Blend) -> DrawParametersBuilder<'a> {
+        self.params.blend = blend;
+        self
+    }
+
+    /// Sets which of the red, green, blue and alpha components are written. See
+    /// `DrawParameters::color_mask`.
+    #[inline]
+    pub fn color_mask(mut self, red: bool, green: bool, blue: bool, alpha: bool)
+                      -> DrawParametersBuilder<'a>
+    {
+        self.params.color_mask = (red, green, blue, alpha);
+        self
+    }
+
+    /// See `DrawParameters::line_width`.
+    #[inline]
+    pub fn line_width(mut self, width: f32) -> DrawParametersBuilder<'a> {
+        self.params.line_width = Some(width);
+        self
+    }
+
+    /// See `DrawParameters::point_size`.
+    #[inline]
+    pub fn point_size(mut self, size: f32) -> DrawParametersBuilder<'a> {
+        self.params.point_size = Some(size);
+        self
+    }
+
+    /// See `DrawParameters::program_point_size`.
+    #[inline]
+    pub fn program_point_size(mut self, enabled: bool) -> DrawParametersBuilder<'a> {
+        self.params.program_point_size = Some(enabled);
+        self
+    }
+
+    /// See `DrawParameters::point_fade_threshold_size`.
+    #[inline]
+    pub fn point_fade_threshold_size(mut self, size: f32) -> DrawParametersBuilder<'a> {
+        self.params.point_fade_threshold_size = Some(size);
+        self
+    }
+
+    /// See `DrawParameters::point_sprite_coord_origin`.
+    #[inline]
+    pub fn point_sprite_coord_origin(mut self, origin: PointSpriteCoordOrigin)
+                                     -> DrawParametersBuilder<'a>
+    {
+        self.params.point_sprite_coord_origin = origin;
+        self
+    }
+
+    /// See `DrawParameters::backface_culling`.
+    #[inline]
+    pub fn backface_culling(mut self, mode: BackfaceCullingMode) -> DrawParametersBuilder<'a> {
+        self.params.backface_culling = mode;
+        self
+    }
+
+    /// See `DrawParameters::polygon_mode`.
+    #[inline]
+    pub fn polygon_mode(mut self, mode: PolygonMode) -> DrawParametersBuilder<'a> {
+        self.params.polygon_mode = mode;
+        self
+    }
+
+    /// See `DrawParameters::multisampling`.
+    #[inline]
+    pub fn multisampling(mut self, enabled: bool) -> DrawParametersBuilder<'a> {
+        self.params.multisampling = enabled;
+        self
+    }
+
+    /// See `DrawParameters::dithering`.
+    #[inline]
+    pub fn dithering(mut self, enabled: bool) -> DrawParametersBuilder<'a> {
+        self.params.dithering = enabled;
+        self
+    }
+
+    /// See `DrawParameters::viewport`.
+    #[inline]
+    pub fn viewport(mut self, viewport: Rect) -> DrawParametersBuilder<'a> {
+        self.params.viewport = Some(viewport);
+        self
+    }
+
+    /// See `DrawParameters::scissor`.
+    #[inline]
+    pub fn scissor(mut self, scissor: Rect) -> DrawParametersBuilder<'a> {
+        self.params.scissor = Some(scissor);
+        self
+    }
+
+    /// See `DrawParameters::draw_primitives`.
+    #[inline]
+    pub fn draw_primitives(mut self, enabled: bool) -> DrawParametersBuilder<'a> {
+        self.params.draw_primitives = enabled;
+        self
+    }
+
+    /// See `DrawParameters::samples_passed_query`.
+    #[inline]
+    pub fn samples_passed_query<Q>(mut self, query: Q) -> DrawParametersBuilder<'a>
+                                   where Q: Into<SamplesQueryParam<'a>>
+    {
+        self.params.samples_passed_query = Some(query.into());
+        self
+    }
+
+    /// See `DrawParameters::time_elapsed_query`.
+    #[inline]
+    pub fn time_elapsed_query(mut self, query: &'a TimeElapsedQuery)
+                              -> DrawParametersBuilder<'a>
+    {
+        self.params.time_elapsed_query = Some(query);
+        self
+    }
+
+    /// See `DrawParameters::primitives_generated_query`.
+    #[inline]
+    pub fn primitives_generated_query(mut self, query: &'a PrimitivesGeneratedQuery)
+                                      -> DrawParametersBuilder<'a>
+    {
+        self.params.primitives_generated_query = Some(query);
+        self
+    }
+
+    /// See `DrawParameters::transform_feedback_primitives_written_query`.
+    #[inline]
+    pub fn transform_feedback_primitives_written_query(
+        mut self, query: &'a TransformFeedbackPrimitivesWrittenQuery)
+        -> DrawParametersBuilder<'a>
+    {
+        self.params.transform_feedback_primitives_written_query = Some(query);
+        self
+    }
+
+    /// See `DrawParameters::condition`.
+    #[inline]
+    pub fn condition(mut self, condition: ConditionalRendering<'a>) -> DrawParametersBuilder<'a> {
+        self.params.condition = Some(condition);
+        self
+    }
+
+    /// See `DrawParameters::transform_feedback`.
+    #[inline]
+    pub fn transform_feedback(mut self, session: &'a TransformFeedbackSession<'a>)
+                              -> DrawParametersBuilder<'a>
+    {
+        self.params.transform_feedback = Some(session);
+        self
+    }
+
+    /// See `DrawParameters::smooth`.
+    #[inline]
+    pub fn smooth(mut self, smooth: Smooth) -> DrawParametersBuilder<'a> {
+        self.params.smooth = Some(smooth);
+        self
+    }
+
+    /// See `DrawParameters::provoking_vertex`.
+    #[inline]
+    pub fn provoking_vertex(mut self, vertex: ProvokingVertex) -> DrawParametersBuilder<'a> {
+        self.params.provoking_vertex = vertex;
+        self
+    }
+
+    /// See `DrawParameters::primitive_bounding_box`.
+    #[inline]
+    pub fn primitive_bounding_box(mut self, x: Range<f32>, y: Range<f32>, z: Range<f32>,
+                                  w: Range<f32>) -> DrawParametersBuilder<'a>
+    {
+        self.params.primitive_bounding_box = Some((x, y, z, w));
+        self
+    }
+
+    /// See `DrawParameters::primitive_restart_index`.
+    #[inline]
+    pub fn primitive_restart_index(mut self, enabled: bool) -> DrawParametersBuilder<'a> {
+        self.params.primitive_restart_index = enabled;
+        self
+    }
+
+    /// See `DrawParameters::missing_uniforms`.
+    #[inline]
+    pub fn missing_uniforms(mut self, policy: MissingUniforms) -> DrawParametersBuilder<'a> {
+        self.params.missing_uniforms = Some(policy);
+        self
+    }
+
+    /// See `DrawParameters::ignore_missing_attributes`.
+    #[inline]
+    pub fn ignore_missing_attributes(mut self, ignore: bool) -> DrawParametersBuilder<'a> {
+        self.params.ignore_missing_attributes = ignore;
+        self
+    }
+
+    /// Builds the `DrawParameters`.
+    #[inline]
+    pub fn build(self) -> DrawParameters<'a> {
+        self.params
+    }
+}
+
+impl<'a> From<DrawParametersBuilder<'a>> for DrawParameters<'a> {
+    #[inline]
+    fn from(builder: DrawParametersBuilder<'a>) -> DrawParameters<'a> {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DrawParametersBuilder;
+    use draw_parameters::{BackfaceCullingMode, Blend, DepthTest};
+
+    #[test]
+    fn presets() {
+        let opaque = DrawParametersBuilder::opaque_3d().build();
+        assert_eq!(opaque.depth.test, DepthTest::IfLess);
+        assert!(opaque.depth.write);
+        assert_eq!(opaque.backface_culling, BackfaceCullingMode::CullClockwise);
+
+        let ui = DrawParametersBuilder::alpha_blended_ui().build();
+        assert_eq!(ui.blend, Blend::alpha_blending());
+        assert!(!ui.depth.write);
+
+        let shadow = DrawParametersBuilder::shadow_pass().build();
+        assert!(shadow.depth.write);
+        assert_eq!(shadow.color_mask, (false, false, false, false));
+    }
+
+    #[test]
+    fn setters_keep_other_parameters() {
+        let params = DrawParametersBuilder::opaque_3d()
+            .depth_test(DepthTest::IfLessOrEqual)
+            .point_size(4.0)
+            .build();
+
+        assert_eq!(params.depth.test, DepthTest::IfLessOrEqual);
+        assert!(params.depth.write);
+        assert_eq!(params.point_size, Some(4.0));
+        assert!(params.multisampling);
+    }
+}
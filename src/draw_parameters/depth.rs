@@ -6,7 +6,7 @@ use DrawError;
 use gl;
 
 /// Represents the depth parameters of a draw command.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Depth {
     /// The function that the GPU will use to determine whether to write over an existing pixel
     /// on the target. Don't forget to set `depth_write` appropriately if you use a depth test.
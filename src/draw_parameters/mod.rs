@@ -14,6 +14,19 @@
 //! };
 //! ```
 //!
+//! The same parameters can be built with a `DrawParametersBuilder`, which doesn't break when
+//! fields are added to `DrawParameters` and provides presets for common situations:
+//!
+//! ```rust
+//! let params = glium::DrawParameters::builder()
+//!     .depth_test(glium::draw_parameters::DepthTest::IfLess)
+//!     .depth_write(true)
+//!     .scissor(glium::Rect { bottom: 0, left: 100, width: 100, height: 200 })
+//!     .build();
+//!
+//! let shadow_params = glium::draw_parameters::DrawParametersBuilder::shadow_pass().build();
+//! ```
+//!
 //! # Queries
 //!
 //! Query objects allow you to obtain information about the rendering process. For example, a
@@ -35,8 +48,18 @@
 //! let value = query.get();
 //! ```
 //!
-//! This operation will consume the query and block until the GPU has finished drawing. Instead,
-//! you can also use the query as a condition for drawing:
+//! This operation will consume the query and block until the GPU has finished drawing. If you
+//! don't want to block, you can call `try_get` instead, which returns `None` as long as the
+//! result is not available. This is usually done once per frame.
+//!
+//! ```no_run
+//! # let query: glium::draw_parameters::SamplesPassedQuery = unsafe { std::mem::uninitialized() };
+//! if let Some(value) = query.try_get() {
+//!     println!("{} samples passed", value);
+//! }
+//! ```
+//!
+//! You can also use the query as a condition for drawing:
 //!
 //! ```no_run
 //! # let query: glium::draw_parameters::SamplesPassedQuery = unsafe { std::mem::uninitialized() };
@@ -75,19 +98,26 @@ use DrawError;
 use Rect;
 use ToGlEnum;
 use vertex::TransformFeedbackSession;
+use uniforms::MissingUniforms;
 
 use std::ops::Range;
 
 pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor};
+pub use self::builder::DrawParametersBuilder;
 pub use self::depth::{Depth, DepthTest, DepthClamp};
 pub use self::query::{QueryCreationError};
 pub use self::query::{SamplesPassedQuery, TimeElapsedQuery, PrimitivesGeneratedQuery};
 pub use self::query::{AnySamplesPassedQuery, TransformFeedbackPrimitivesWrittenQuery};
+pub use self::query::OcclusionQuery;
+pub use self::query::{PipelineStatistic, PipelineStatisticsQuery, PipelineStatisticsScope};
+pub use self::scissor::ScissorStack;
 pub use self::stencil::{StencilTest, StencilOperation, Stencil};
 
 mod blend;
+mod builder;
 mod depth;
 mod query;
+mod scissor;
 mod stencil;
 
 /// Describes how triangles should be filtered before the fragment processing. Backface culling
@@ -227,6 +257,27 @@ pub enum ProvokingVertex {
     FirstVertex,
 }
 
+/// Where the origin of the texture coordinates of point sprites (`gl_PointCoord`) is located.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointSpriteCoordOrigin {
+    /// `gl_PointCoord` is `(0, 0)` at the upper-left corner of the point. This is the default
+    /// in OpenGL and the only value supported by OpenGL ES.
+    UpperLeft,
+
+    /// `gl_PointCoord` is `(0, 0)` at the lower-left corner of the point.
+    LowerLeft,
+}
+
+impl ToGlEnum for PointSpriteCoordOrigin {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            PointSpriteCoordOrigin::UpperLeft => gl::UPPER_LEFT,
+            PointSpriteCoordOrigin::LowerLeft => gl::LOWER_LEFT,
+        }
+    }
+}
+
 /// Represents the parameters to use when drawing.
 ///
 /// Example:
@@ -274,6 +325,31 @@ pub struct DrawParameters<'a> {
     /// `None` means "don't care". Use this when you don't draw points.
     pub point_size: Option<f32>,
 
+    /// Whether the size of the points is determined by the value of `gl_PointSize` written by
+    /// the program (`GL_PROGRAM_POINT_SIZE`) rather than by `point_size`.
+    ///
+    /// `None` means that glium decides by looking at whether the program writes to
+    /// `gl_PointSize`. This is the default value.
+    ///
+    /// Any other value can trigger a `ProgramPointSizeNotSupported` error if OpenGL 3.2 or
+    /// `GL_ARB_geometry_shader4` isn't available, which is the case on OpenGL ES and WebGL.
+    pub program_point_size: Option<bool>,
+
+    /// Threshold in pixels below which multisampled points are faded out instead of being
+    /// shrunk further. The default value is `None`, which corresponds to OpenGL's default of
+    /// `1.0`.
+    ///
+    /// Any other value can potentially trigger a `PointParametersNotSupported` error. Most
+    /// notably OpenGL ES doesn't support this parameter.
+    pub point_fade_threshold_size: Option<f32>,
+
+    /// Where the origin of `gl_PointCoord` is located when drawing point sprites. The default
+    /// value is `UpperLeft`.
+    ///
+    /// Any other value can potentially trigger a `PointParametersNotSupported` error. Most
+    /// notably OpenGL ES doesn't support anything else but `UpperLeft`.
+    pub point_sprite_coord_origin: PointSpriteCoordOrigin,
+
     /// Whether or not the GPU should filter out some faces.
     ///
     /// After the vertex shader stage, the GPU will try to remove the faces that aren't facing
@@ -314,6 +390,8 @@ pub struct DrawParameters<'a> {
     ///
     /// This is different from a viewport. The image will stretch to fill the viewport, but
     /// not the scissor box.
+    ///
+    /// See also `ScissorStack` if you need to clip nested elements.
     pub scissor: Option<Rect>,
 
     /// If `false`, the pipeline will stop after the primitives generation stage. The default
@@ -374,10 +452,15 @@ pub struct DrawParameters<'a> {
     /// drawn is implementation-specific.
     ///
     /// This field is useless if you're not using a geometry shader or tessellation shader.
+    /// This is mostly useful on tile-based GPUs (`GL_OES_primitive_bounding_box` and
+    /// `GL_EXT_primitive_bounding_box`), which can use it to cull patches early.
+    ///
+    /// `None` means "don't care", and leaves the current value untouched. This is the default
+    /// value.
     ///
     /// Since this is purely an optimization, this parameter is ignored if the backend doesn't
     /// support it.
-    pub primitive_bounding_box: (Range<f32>, Range<f32>, Range<f32>, Range<f32>),
+    pub primitive_bounding_box: Option<(Range<f32>, Range<f32>, Range<f32>, Range<f32>)>,
     
     /// If enabled, will split the index buffer (if any is used in the draw call) 
     /// at the MAX value of the IndexType (u8::MAX, u16::MAX or u32::MAX) and start a new primitive
@@ -385,6 +468,30 @@ pub struct DrawParameters<'a> {
     /// If the backend does not support GL_PRIMITIVE_RESTART_FIXED_INDEX, an Error 
     /// of type `FixedIndexRestartingNotSupported` will be returned.
     pub primitive_restart_index: bool,
+
+    /// What to do with the uniforms and uniform blocks of the program that have no value and no
+    /// default value.
+    ///
+    /// If `None`, the policy of the program is used, which is `MissingUniforms::Ignore` unless
+    /// changed with `Program::set_missing_uniforms`. The default value is `None`.
+    pub missing_uniforms: Option<MissingUniforms>,
+
+    /// If true, the attributes of the program that aren't provided by the vertex sources are
+    /// ignored instead of returning `DrawError::AttributeMissing`. The shader then reads the
+    /// current value of the generic vertex attribute, which is `(0, 0, 0, 1)` by default.
+    ///
+    /// The default value is `false`.
+    pub ignore_missing_attributes: bool,
+}
+
+impl<'a> DrawParameters<'a> {
+    /// Returns a builder whose parameters all have their default value.
+    ///
+    /// See `DrawParametersBuilder` for presets.
+    #[inline]
+    pub fn builder() -> DrawParametersBuilder<'a> {
+        DrawParametersBuilder::new()
+    }
 }
 
 /// Condition whether to render or not.
@@ -434,6 +541,9 @@ impl<'a> Default for DrawParameters<'a> {
             color_mask: (true, true, true, true),
             line_width: None,
             point_size: None,
+            program_point_size: None,
+            point_fade_threshold_size: None,
+            point_sprite_coord_origin: PointSpriteCoordOrigin::UpperLeft,
             backface_culling: BackfaceCullingMode::CullingDisabled,
             polygon_mode: PolygonMode::Fill,
             multisampling: true,
@@ -449,8 +559,10 @@ impl<'a> Default for DrawParameters<'a> {
             transform_feedback: None,
             smooth: None,
             provoking_vertex: ProvokingVertex::LastVertex,
-            primitive_bounding_box: (-1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0),
+            primitive_bounding_box: None,
             primitive_restart_index: false,
+            missing_uniforms: None,
+            ignore_missing_attributes: false,
         }
     }
 }
@@ -482,6 +594,9 @@ pub fn sync(ctxt: &mut context::CommandContext, draw_parameters: &DrawParameters
     sync_color_mask(ctxt, draw_parameters.color_mask);
     sync_line_width(ctxt, draw_parameters.line_width);
     sync_point_size(ctxt, draw_parameters.point_size);
+    try!(sync_program_point_size(ctxt, draw_parameters.program_point_size));
+    try!(sync_point_parameters(ctxt, draw_parameters.point_fade_threshold_size,
+                               draw_parameters.point_sprite_coord_origin));
     sync_polygon_mode(ctxt, draw_parameters.backface_culling, draw_parameters.polygon_mode);
     sync_multisampling(ctxt, draw_parameters.multisampling);
     sync_dithering(ctxt, draw_parameters.dithering);
@@ -495,7 +610,9 @@ pub fn sync(ctxt: &mut context::CommandContext, draw_parameters: &DrawParameters
     sync_conditional_render(ctxt, draw_parameters.condition);
     try!(sync_smooth(ctxt, draw_parameters.smooth, primitives_types));
     try!(sync_provoking_vertex(ctxt, draw_parameters.provoking_vertex));
-    sync_primitive_bounding_box(ctxt, &draw_parameters.primitive_bounding_box);
+    if let Some(ref bb) = draw_parameters.primitive_bounding_box {
+        sync_primitive_bounding_box(ctxt, bb);
+    }
     try!(sync_primitive_restart_index(ctxt, draw_parameters.primitive_restart_index));
 
     Ok(())
@@ -540,6 +657,67 @@ fn sync_point_size(ctxt: &mut context::CommandContext, point_size: Option<f32>)
     }
 }
 
+fn sync_program_point_size(ctxt: &mut context::CommandContext, program_point_size: Option<bool>)
+                           -> Result<(), DrawError>
+{
+    // `None` means that the value set by `Program::use_program` is kept
+    if let Some(program_point_size) = program_point_size {
+        if ctxt.state.enabled_program_point_size != program_point_size {
+            // OpenGL ES and WebGL don't have `GL_PROGRAM_POINT_SIZE`
+            if !(ctxt.version >= &Version(Api::Gl, 3, 2)) &&
+               !ctxt.extensions.gl_arb_geometry_shader4
+            {
+                return Err(DrawError::ProgramPointSizeNotSupported);
+            }
+
+            unsafe {
+                if program_point_size {
+                    ctxt.gl.Enable(gl::PROGRAM_POINT_SIZE);
+                } else {
+                    ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE);
+                }
+            }
+
+            ctxt.state.enabled_program_point_size = program_point_size;
+        }
+    }
+
+    Ok(())
+}
+
+fn sync_point_parameters(ctxt: &mut context::CommandContext, fade_threshold: Option<f32>,
+                         origin: PointSpriteCoordOrigin) -> Result<(), DrawError>
+{
+    let fade_threshold = fade_threshold.unwrap_or(1.0);
+    let origin = origin.to_glenum();
+
+    if ctxt.state.point_fade_threshold_size == fade_threshold &&
+       ctxt.state.point_sprite_coord_origin == origin
+    {
+        return Ok(());
+    }
+
+    // OpenGL ES doesn't have `glPointParameter`, and always uses the default values
+    if !(ctxt.version >= &Version(Api::Gl, 2, 0)) {
+        return Err(DrawError::PointParametersNotSupported);
+    }
+
+    unsafe {
+        if ctxt.state.point_fade_threshold_size != fade_threshold {
+            ctxt.gl.PointParameterf(gl::POINT_FADE_THRESHOLD_SIZE, fade_threshold);
+            ctxt.state.point_fade_threshold_size = fade_threshold;
+        }
+
+        if ctxt.state.point_sprite_coord_origin != origin {
+            ctxt.gl.PointParameteri(gl::POINT_SPRITE_COORD_ORIGIN,
+                                    origin as gl::types::GLint);
+            ctxt.state.point_sprite_coord_origin = origin;
+        }
+    }
+
+    Ok(())
+}
+
 fn sync_polygon_mode(ctxt: &mut context::CommandContext, backface_culling: BackfaceCullingMode,
                      polygon_mode: PolygonMode)
 {
@@ -898,6 +1076,10 @@ fn sync_primitive_restart_index(ctxt: &mut context::CommandContext,
 {
     // TODO: use GL_PRIMITIVE_RESTART (if possible) if 
     // GL_PRIMITIVE_RESTART_FIXED_INDEX is not supported
+    if ctxt.state.enabled_primitive_fixed_restart == enabled {
+        return Ok(());
+    }
+
     if ctxt.version >= &Version(Api::Gl, 3, 1)   || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
     ctxt.extensions.gl_arb_es3_compatibility
     {
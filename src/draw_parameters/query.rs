@@ -40,6 +40,7 @@ pub enum QueryType {
     Timestamp,
     PrimitivesGenerated,
     TransformFeedbackPrimitivesWritten,
+    PipelineStatistic(PipelineStatistic),
 }
 
 impl ToGlEnum for QueryType {
@@ -55,12 +56,72 @@ impl ToGlEnum for QueryType {
             QueryType::TransformFeedbackPrimitivesWritten => {
                 gl::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN
             },
+            QueryType::PipelineStatistic(stat) => stat.to_glenum(),
+        }
+    }
+}
+
+/// A counter of the pipeline that can be measured with a `PipelineStatisticsQuery`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PipelineStatistic {
+    /// Number of vertices submitted to the primitive assembler.
+    VerticesSubmitted,
+    /// Number of primitives submitted to the primitive assembler.
+    PrimitivesSubmitted,
+    /// Number of times the vertex shader has been invoked.
+    VertexShaderInvocations,
+    /// Number of patches processed by the tessellation control shader.
+    TessControlShaderPatches,
+    /// Number of times the tessellation evaluation shader has been invoked.
+    TessEvaluationShaderInvocations,
+    /// Number of times the geometry shader has been invoked.
+    GeometryShaderInvocations,
+    /// Number of primitives emitted by the geometry shader.
+    GeometryShaderPrimitivesEmitted,
+    /// Number of times the fragment shader has been invoked.
+    FragmentShaderInvocations,
+    /// Number of times the compute shader has been invoked.
+    ComputeShaderInvocations,
+    /// Number of primitives that reached the clipping stage.
+    ClippingInputPrimitives,
+    /// Number of primitives that were output by the clipping stage.
+    ClippingOutputPrimitives,
+}
+
+impl PipelineStatistic {
+    /// Returns the index of this counter in `GlState::pipeline_statistics_queries`.
+    #[inline]
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl ToGlEnum for PipelineStatistic {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            PipelineStatistic::VerticesSubmitted => gl::VERTICES_SUBMITTED_ARB,
+            PipelineStatistic::PrimitivesSubmitted => gl::PRIMITIVES_SUBMITTED_ARB,
+            PipelineStatistic::VertexShaderInvocations => gl::VERTEX_SHADER_INVOCATIONS_ARB,
+            PipelineStatistic::TessControlShaderPatches => gl::TESS_CONTROL_SHADER_PATCHES_ARB,
+            PipelineStatistic::TessEvaluationShaderInvocations => {
+                gl::TESS_EVALUATION_SHADER_INVOCATIONS_ARB
+            },
+            PipelineStatistic::GeometryShaderInvocations => gl::GEOMETRY_SHADER_INVOCATIONS,
+            PipelineStatistic::GeometryShaderPrimitivesEmitted => {
+                gl::GEOMETRY_SHADER_PRIMITIVES_EMITTED_ARB
+            },
+            PipelineStatistic::FragmentShaderInvocations => gl::FRAGMENT_SHADER_INVOCATIONS_ARB,
+            PipelineStatistic::ComputeShaderInvocations => gl::COMPUTE_SHADER_INVOCATIONS_ARB,
+            PipelineStatistic::ClippingInputPrimitives => gl::CLIPPING_INPUT_PRIMITIVES_ARB,
+            PipelineStatistic::ClippingOutputPrimitives => gl::CLIPPING_OUTPUT_PRIMITIVES_ARB,
         }
     }
 }
 
 /// Error that can happen when creating a query object.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum QueryCreationError {
     /// The given query type is not supported.
     NotSupported,
@@ -83,6 +144,7 @@ impl Error for QueryCreationError {
 
 /// Error that can happen when writing the value of a query to a buffer.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum ToBufferError {
     /// Writing the result to a buffer is not supported.
     NotSupported,
@@ -124,6 +186,9 @@ impl RawQuery {
                     QueryType::AnySamplesPassedConservative if
                             ctxt.extensions.gl_arb_es3_compatibility ||
                             ctxt.version >= &Version(Api:: Gl, 4, 3) => (),
+                    QueryType::PipelineStatistic(_) if
+                            ctxt.extensions.gl_arb_pipeline_statistics_query ||
+                            ctxt.version >= &Version(Api:: Gl, 4, 6) => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
@@ -142,6 +207,8 @@ impl RawQuery {
                     QueryType::AnySamplesPassed if ctxt.extensions.gl_arb_occlusion_query2 => (),
                     QueryType::AnySamplesPassedConservative if ctxt.extensions.gl_arb_es3_compatibility => (),
                     QueryType::TimeElapsed if ctxt.extensions.gl_arb_timer_query => (),
+                    QueryType::PipelineStatistic(_) if
+                            ctxt.extensions.gl_arb_pipeline_statistics_query => (),
 
                     _ => return Err(QueryCreationError::NotSupported)
                 };
@@ -171,14 +238,19 @@ impl RawQuery {
                 match ty {
                     QueryType::AnySamplesPassed | QueryType::AnySamplesPassedConservative |
                     QueryType::TransformFeedbackPrimitivesWritten => (),
+                    QueryType::TimeElapsed if ctxt.extensions.gl_ext_disjoint_timer_query => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
                 ctxt.gl.GenQueries(1, &mut id);
 
-            } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                      ctxt.extensions.gl_ext_disjoint_timer_query
+            {
                 match ty {
-                    QueryType::AnySamplesPassed | QueryType::AnySamplesPassedConservative => (),
+                    QueryType::AnySamplesPassed | QueryType::AnySamplesPassedConservative
+                        if ctxt.extensions.gl_ext_occlusion_query_boolean => (),
+                    QueryType::TimeElapsed if ctxt.extensions.gl_ext_disjoint_timer_query => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
@@ -221,7 +293,9 @@ impl RawQuery {
             } else if ctxt.extensions.gl_arb_occlusion_query {
                 ctxt.gl.GetQueryObjectuivARB(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
 
-            } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                      ctxt.extensions.gl_ext_disjoint_timer_query
+            {
                 ctxt.gl.GetQueryObjectuivEXT(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
 
             } else {
@@ -289,7 +363,9 @@ impl RawQuery {
         } else if ctxt.extensions.gl_arb_occlusion_query {
             ctxt.gl.GetQueryObjectuivARB(self.id, gl::QUERY_RESULT, target);
 
-        } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+        } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                  ctxt.extensions.gl_ext_disjoint_timer_query
+        {
             ctxt.gl.GetQueryObjectuivEXT(self.id, gl::QUERY_RESULT, target);
 
         } else {
@@ -331,6 +407,10 @@ impl RawQuery {
             ctxt.gl.GetQueryObjectui64v(self.id, gl::QUERY_RESULT, target);
             Ok(())
 
+        } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+            ctxt.gl.GetQueryObjectui64vEXT(self.id, gl::QUERY_RESULT, target);
+            Ok(())
+
         } else {
             Err(())
         }
@@ -375,6 +455,37 @@ impl RawQuery {
             unsafe { raw_end_query(ctxt, gl::TIME_ELAPSED) };
             ctxt.state.time_elapsed_query = 0;
         }
+
+        if let QueryType::PipelineStatistic(stat) = self.ty {
+            if ctxt.state.pipeline_statistics_queries[stat.index()] == self.id {
+                unsafe { raw_end_query(ctxt, stat.to_glenum()) };
+                ctxt.state.pipeline_statistics_queries[stat.index()] = 0;
+            }
+        }
+    }
+
+    /// Starts counting with a pipeline statistics query. Any other query that is counting the
+    /// same statistic is stopped first.
+    fn begin_pipeline_statistic(&self, ctxt: &mut CommandContext) {
+        let stat = match self.ty {
+            QueryType::PipelineStatistic(stat) => stat,
+            _ => unreachable!()
+        };
+
+        let current = ctxt.state.pipeline_statistics_queries[stat.index()];
+        if current == self.id {
+            return;
+        }
+
+        unsafe {
+            if current != 0 {
+                raw_end_query(ctxt, stat.to_glenum());
+            }
+            raw_begin_query(ctxt, stat.to_glenum(), self.id);
+        }
+
+        self.has_been_used.set(true);
+        ctxt.state.pipeline_statistics_queries[stat.index()] = self.id;
     }
 }
 
@@ -398,7 +509,9 @@ impl Drop for RawQuery {
             } else if ctxt.extensions.gl_arb_occlusion_query {
                 ctxt.gl.DeleteQueriesARB(1, [self.id].as_ptr());
 
-            } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                      ctxt.extensions.gl_ext_disjoint_timer_query
+            {
                 ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
 
             } else {
@@ -515,6 +628,9 @@ impl QueryExt for RawQuery {
 
             QueryType::Timestamp => panic!(),
 
+            // pipeline statistics queries are never passed through the draw parameters
+            QueryType::PipelineStatistic(_) => panic!(),
+
             QueryType::PrimitivesGenerated => {
                 if ctxt.state.primitives_generated_query != self.id {
                     if self.has_been_used.get() {
@@ -691,7 +807,9 @@ unsafe fn raw_begin_query(ctxt: &mut CommandContext, ty: gl::types::GLenum, id:
     } else if ctxt.extensions.gl_arb_occlusion_query {
         ctxt.gl.BeginQueryARB(ty, id);
 
-    } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+    } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+              ctxt.extensions.gl_ext_disjoint_timer_query
+    {
         ctxt.gl.BeginQueryEXT(ty, id);
 
     } else {
@@ -713,7 +831,9 @@ unsafe fn raw_end_query(ctxt: &mut CommandContext, ty: gl::types::GLenum) {
     } else if ctxt.extensions.gl_arb_occlusion_query {
         ctxt.gl.EndQueryARB(ty);
 
-    } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+    } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+              ctxt.extensions.gl_ext_disjoint_timer_query
+    {
         ctxt.gl.EndQueryEXT(ty);
 
     } else {
@@ -744,6 +864,20 @@ macro_rules! impl_helper {
                 self.query.$get_fn()
             }
 
+            /// Returns the value of the query if it is available, or `None` if the GPU hasn't
+            /// finished executing the commands yet. Never blocks.
+            ///
+            /// Contrary to `get`, this function doesn't consume the query, which means that you
+            /// can poll it once per frame until the result is available.
+            #[inline]
+            pub fn try_get(&self) -> Option<$ret> {
+                if self.query.is_ready() {
+                    Some(self.query.$get_fn())
+                } else {
+                    None
+                }
+            }
+
             /// Writes the result of the query to a buffer when it is available.
             ///
             /// This function doesn't block. Instead it submits a commands to the GPU's commands
@@ -831,10 +965,19 @@ impl SamplesPassedQuery {
 
 impl_helper!(SamplesPassedQuery, u32, get_u32);
 
+/// Alias for `SamplesPassedQuery`, under the name that is usually used for occlusion culling.
+pub type OcclusionQuery = SamplesPassedQuery;
+
 /// A query that allows you to know the number of nanoseconds that have elapsed
 /// during the draw operations.
 ///
-/// TODO: not sure that it's nanoseconds
+/// In order to measure the time between two points of the commands queue instead, see
+/// `glium::debug::TimestampQuery`.
+///
+/// On OpenGL ES, this requires the `GL_EXT_disjoint_timer_query` extension. With this extension
+/// the GPU can signal that an event (for example a change of frequency) happened while the
+/// query was running and that its result is meaningless. Call `Context::is_gpu_disjoint` after
+/// reading the results of your queries to know whether you must discard them.
 #[derive(Debug)]
 pub struct TimeElapsedQuery {
     query: RawQuery,
@@ -931,3 +1074,69 @@ impl TransformFeedbackPrimitivesWrittenQuery {
 }
 
 impl_helper!(TransformFeedbackPrimitivesWrittenQuery, u32, get_u32);
+
+/// Query that counts one of the statistics of the pipeline, like the number of times the
+/// fragment shader has been invoked.
+///
+/// Contrary to the other queries, this query is not passed to the draw parameters. Instead the
+/// counter is active as long as the `PipelineStatisticsScope` returned by `begin` is alive.
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// use glium::draw_parameters::{PipelineStatistic, PipelineStatisticsQuery};
+///
+/// let query = PipelineStatisticsQuery::new(&display,
+///                                          PipelineStatistic::FragmentShaderInvocations).unwrap();
+///
+/// {
+///     let _scope = query.begin();
+///     // draw here
+/// }
+///
+/// println!("{} fragment shader invocations", query.get());
+/// ```
+///
+/// ## OpenGL
+///
+/// Requires OpenGL 4.6 or the `GL_ARB_pipeline_statistics_query` extension.
+#[derive(Debug)]
+pub struct PipelineStatisticsQuery {
+    query: RawQuery,
+}
+
+impl PipelineStatisticsQuery {
+    /// Builds a new query.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, statistic: PipelineStatistic)
+                          -> Result<PipelineStatisticsQuery, QueryCreationError>
+                          where F: Facade
+    {
+        RawQuery::new(facade, QueryType::PipelineStatistic(statistic))
+                                                    .map(|q| PipelineStatisticsQuery { query: q })
+    }
+
+    /// Starts counting. The counter stops when the returned object is destroyed.
+    ///
+    /// Starting the same query a second time resets its value.
+    pub fn begin(&self) -> PipelineStatisticsScope {
+        let mut ctxt = self.query.context.make_current();
+        self.query.begin_pipeline_statistic(&mut ctxt);
+        PipelineStatisticsScope { query: self }
+    }
+}
+
+impl_helper!(PipelineStatisticsQuery, u64, get_u64);
+
+/// Active counting of a `PipelineStatisticsQuery`. Stops counting when destroyed.
+pub struct PipelineStatisticsScope<'a> {
+    query: &'a PipelineStatisticsQuery,
+}
+
+impl<'a> Drop for PipelineStatisticsScope<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut ctxt = self.query.query.context.make_current();
+        self.query.query.deactivate(&mut ctxt);
+    }
+}
@@ -0,0 +1,135 @@
+use std::cmp;
+
+use Rect;
+
+/// Stack of nested scissor rectangles.
+///
+/// Each rectangle that is pushed is intersected with the rectangle currently at the top of the
+/// stack, so that a nested element can never draw outside of the area of its parent. This is
+/// typically used by user interfaces where widgets are clipped by the widgets that contain them.
+///
+/// # Example
+///
+/// ```
+/// use glium::draw_parameters::ScissorStack;
+///
+/// let mut stack = ScissorStack::new();
+/// stack.push(glium::Rect { left: 0, bottom: 0, width: 100, height: 100 });
+/// stack.push(glium::Rect { left: 50, bottom: 50, width: 100, height: 100 });
+///
+/// let params = glium::DrawParameters {
+///     scissor: stack.current(),
+///     .. Default::default()
+/// };
+///
+/// assert_eq!(params.scissor, Some(glium::Rect { left: 50, bottom: 50, width: 50, height: 50 }));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScissorStack {
+    rects: Vec<Rect>,
+}
+
+impl ScissorStack {
+    /// Builds a new empty stack.
+    #[inline]
+    pub fn new() -> ScissorStack {
+        ScissorStack {
+            rects: Vec::new(),
+        }
+    }
+
+    /// Pushes a rectangle on the stack.
+    ///
+    /// The rectangle is intersected with the current top of the stack. If the two rectangles
+    /// don't overlap, a rectangle with a width and a height of zero is pushed, which means that
+    /// nothing will be drawn until it is popped.
+    pub fn push(&mut self, rect: Rect) {
+        let rect = match self.rects.last() {
+            Some(top) => intersection(top, &rect),
+            None => rect,
+        };
+
+        self.rects.push(rect);
+    }
+
+    /// Removes the rectangle at the top of the stack and returns it.
+    #[inline]
+    pub fn pop(&mut self) -> Option<Rect> {
+        self.rects.pop()
+    }
+
+    /// Returns the rectangle at the top of the stack, or `None` if the stack is empty.
+    ///
+    /// The returned value can be directly used as the `scissor` member of the draw parameters.
+    #[inline]
+    pub fn current(&self) -> Option<Rect> {
+        self.rects.last().cloned()
+    }
+
+    /// Returns the number of rectangles in the stack.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    /// Returns true if the stack is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Removes all the rectangles from the stack.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+}
+
+/// Returns the intersection of two rectangles. Returns an empty rectangle if they don't overlap.
+fn intersection(a: &Rect, b: &Rect) -> Rect {
+    let left = cmp::max(a.left, b.left);
+    let bottom = cmp::max(a.bottom, b.bottom);
+    let right = cmp::min(a.left.saturating_add(a.width), b.left.saturating_add(b.width));
+    let top = cmp::min(a.bottom.saturating_add(a.height), b.bottom.saturating_add(b.height));
+
+    if right <= left || top <= bottom {
+        return Rect { left: left, bottom: bottom, width: 0, height: 0 };
+    }
+
+    Rect {
+        left: left,
+        bottom: bottom,
+        width: right - left,
+        height: top - bottom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScissorStack;
+    use Rect;
+
+    #[test]
+    fn nested() {
+        let mut stack = ScissorStack::new();
+        assert_eq!(stack.current(), None);
+
+        stack.push(Rect { left: 10, bottom: 10, width: 100, height: 100 });
+        stack.push(Rect { left: 50, bottom: 0, width: 100, height: 30 });
+        assert_eq!(stack.current(), Some(Rect { left: 50, bottom: 10, width: 60, height: 20 }));
+
+        stack.pop();
+        assert_eq!(stack.current(), Some(Rect { left: 10, bottom: 10, width: 100, height: 100 }));
+    }
+
+    #[test]
+    fn disjoint() {
+        let mut stack = ScissorStack::new();
+        stack.push(Rect { left: 0, bottom: 0, width: 10, height: 10 });
+        stack.push(Rect { left: 20, bottom: 20, width: 10, height: 10 });
+
+        let current = stack.current().unwrap();
+        assert_eq!(current.width, 0);
+        assert_eq!(current.height, 0);
+    }
+}
@@ -3,7 +3,7 @@ use context;
 use ToGlEnum;
 
 /// Describes the parameters that must be used for the stencil operations when drawing.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Stencil {
     /// A comparison against the existing value in the stencil buffer.
     ///
@@ -0,0 +1,368 @@
+/*!
+Helpers for post-processing effects.
+
+Effects like bloom, FXAA or tone mapping are usually implemented by drawing a triangle that
+covers the whole target with a fragment shader that reads the previous image. This module
+provides the pieces that every chain of effects needs:
+
+ - A `FullscreenTriangle` that draws a program on every pixel of a surface.
+ - A `PingPong` pair of textures, so that each pass can read the output of the previous one.
+ - A `PassList`, where each `Pass` is a program and its uniforms.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let scene: glium::Texture2d = unsafe { ::std::mem::uninitialized() };
+# let tonemap: glium::Program = unsafe { ::std::mem::uninitialized() };
+# let fxaa: glium::Program = unsafe { ::std::mem::uninitialized() };
+use glium::effects::{FullscreenTriangle, Pass, PassList, PingPong};
+use glium::texture::UncompressedFloatFormat;
+
+let fullscreen = FullscreenTriangle::new(&display).unwrap();
+let mut buffers = PingPong::new(&display, UncompressedFloatFormat::F16F16F16F16,
+                                1024, 768).unwrap();
+
+let passes = PassList::new()
+    .with_pass(Pass::new(&tonemap).with_uniform("exposure", 1.5f32))
+    .with_pass(Pass::new(&fxaa));
+
+let mut frame = display.draw();
+passes.apply(&fullscreen, &scene, &mut buffers, &mut frame).unwrap();
+frame.finish().unwrap();
+```
+
+The programs of the passes must read the vertex attributes of `shapes::Vertex`, for example
+with the `VERTEX_SHADER` of this module, and the image produced by the previous pass from a
+`sampler2D` named `input`.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use framebuffer::{SimpleFrameBuffer, ValidationError};
+use index::{NoIndices, PrimitiveType};
+use shapes;
+use texture::{MipmapsOption, Texture2d, TextureCreationError, UncompressedFloatFormat};
+use uniforms::{AsUniformValue, DynamicUniforms, MagnifySamplerFilter, MinifySamplerFilter};
+use uniforms::{Sampler, SamplerWrapFunction, Uniforms, UniformsStorage};
+use vertex::{self, IntoVerticesSource, VertexBuffer};
+
+use DrawError;
+use DrawParameters;
+use Program;
+use Surface;
+
+/// A GLSL 1.40 vertex shader that can be used with `FullscreenTriangle`.
+///
+/// It passes the texture coordinates to the fragment shader in a `vec2` named `v_tex_coords`.
+pub const VERTEX_SHADER: &'static str = "
+    #version 140
+
+    in vec3 position;
+    in vec2 tex_coords;
+
+    out vec2 v_tex_coords;
+
+    void main() {
+        v_tex_coords = tex_coords;
+        gl_Position = vec4(position, 1.0);
+    }
+";
+
+/// A triangle that covers the whole target. See `shapes::fullscreen_triangle`.
+pub struct FullscreenTriangle {
+    vertices: VertexBuffer<shapes::Vertex>,
+}
+
+impl FullscreenTriangle {
+    /// Uploads the triangle.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F)
+                          -> Result<FullscreenTriangle, vertex::BufferCreationError>
+                          where F: Facade
+    {
+        Ok(FullscreenTriangle {
+            vertices: try!(shapes::fullscreen_triangle().vertex_buffer(facade)),
+        })
+    }
+
+    /// Runs a program on every pixel of the target.
+    #[inline]
+    pub fn draw<S: ?Sized, U>(&self, target: &mut S, program: &Program, uniforms: &U,
+                              draw_parameters: &DrawParameters) -> Result<(), DrawError>
+                              where S: Surface, U: Uniforms
+    {
+        let indices = NoIndices(PrimitiveType::TrianglesList);
+        target.draw_dyn(&[(&self.vertices).into_vertices_source()], indices.into(), program,
+                        uniforms, draw_parameters)
+    }
+}
+
+/// Two textures with the same dimensions and format. One of them contains the last image that
+/// has been drawn, and the other one is the next one to draw to.
+pub struct PingPong {
+    context: Rc<Context>,
+    textures: [Texture2d; 2],
+    front: usize,
+}
+
+impl PingPong {
+    /// Builds the two textures.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32)
+                          -> Result<PingPong, TextureCreationError>
+                          where F: Facade
+    {
+        let first = try!(Texture2d::empty_with_format(facade, format, MipmapsOption::NoMipmap,
+                                                      width, height));
+        let second = try!(Texture2d::empty_with_format(facade, format, MipmapsOption::NoMipmap,
+                                                       width, height));
+
+        Ok(PingPong {
+            context: facade.get_context().clone(),
+            textures: [first, second],
+            front: 0,
+        })
+    }
+
+    /// Returns the dimensions of the textures.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        self.textures[0].dimensions()
+    }
+
+    /// Returns the texture that contains the last image.
+    #[inline]
+    pub fn front(&self) -> &Texture2d {
+        &self.textures[self.front]
+    }
+
+    /// Returns the texture to draw the next image to.
+    #[inline]
+    pub fn back(&self) -> &Texture2d {
+        &self.textures[1 - self.front]
+    }
+
+    /// Builds a framebuffer that draws to the back texture.
+    #[inline]
+    pub fn back_framebuffer(&self) -> Result<SimpleFrameBuffer, ValidationError> {
+        SimpleFrameBuffer::new(&self.context, self.back())
+    }
+
+    /// Exchanges the two textures. Call this after drawing to the back texture.
+    #[inline]
+    pub fn swap(&mut self) {
+        self.front = 1 - self.front;
+    }
+}
+
+/// A program to run on every pixel, and its uniforms.
+pub struct Pass<'a> {
+    program: &'a Program,
+    uniforms: DynamicUniforms<'a>,
+    draw_parameters: DrawParameters<'a>,
+    input_name: String,
+}
+
+impl<'a> Pass<'a> {
+    /// Builds a pass that runs a program. The previous image is bound to a uniform named
+    /// `input`.
+    #[inline]
+    pub fn new(program: &'a Program) -> Pass<'a> {
+        Pass {
+            program: program,
+            uniforms: DynamicUniforms::new(),
+            draw_parameters: Default::default(),
+            input_name: "input".to_owned(),
+        }
+    }
+
+    /// Adds a uniform. Replaces the previous value that had the same name, if any.
+    #[inline]
+    pub fn with_uniform<S, T>(mut self, name: S, value: T) -> Pass<'a>
+                              where S: Into<String>, T: AsUniformValue + 'a
+    {
+        self.uniforms.add(name, value);
+        self
+    }
+
+    /// Changes the name of the uniform that receives the previous image.
+    #[inline]
+    pub fn with_input_name<S>(mut self, name: S) -> Pass<'a> where S: Into<String> {
+        self.input_name = name.into();
+        self
+    }
+
+    /// Changes the draw parameters, for example to blend the last pass with the content of the
+    /// target.
+    #[inline]
+    pub fn with_draw_parameters(mut self, draw_parameters: DrawParameters<'a>) -> Pass<'a> {
+        self.draw_parameters = draw_parameters;
+        self
+    }
+
+    /// Returns the uniforms of the pass, so that they can be modified between frames.
+    #[inline]
+    pub fn uniforms_mut(&mut self) -> &mut DynamicUniforms<'a> {
+        &mut self.uniforms
+    }
+
+    /// Runs the pass on every pixel of `target`, with `input` as the previous image.
+    pub fn draw<S: ?Sized>(&self, fullscreen: &FullscreenTriangle, input: &Texture2d,
+                           target: &mut S) -> Result<(), DrawError>
+                           where S: Surface
+    {
+        let sampler = Sampler::new(input)
+                        .wrap_function(SamplerWrapFunction::Clamp)
+                        .minify_filter(MinifySamplerFilter::Linear)
+                        .magnify_filter(MagnifySamplerFilter::Linear);
+
+        let uniforms = (&self.uniforms).chain(UniformsStorage::new(&self.input_name, sampler));
+        fullscreen.draw(target, self.program, &uniforms, &self.draw_parameters)
+    }
+}
+
+/// A list of passes that are run one after the other.
+#[derive(Default)]
+pub struct PassList<'a> {
+    passes: Vec<Pass<'a>>,
+}
+
+impl<'a> PassList<'a> {
+    /// Builds an empty list.
+    #[inline]
+    pub fn new() -> PassList<'a> {
+        PassList {
+            passes: Vec::new(),
+        }
+    }
+
+    /// Adds a pass at the end of the list.
+    #[inline]
+    pub fn with_pass(mut self, pass: Pass<'a>) -> PassList<'a> {
+        self.passes.push(pass);
+        self
+    }
+
+    /// Adds a pass at the end of the list.
+    #[inline]
+    pub fn push(&mut self, pass: Pass<'a>) {
+        self.passes.push(pass);
+    }
+
+    /// Returns the passes of the list.
+    #[inline]
+    pub fn passes(&self) -> &[Pass<'a>] {
+        &self.passes
+    }
+
+    /// Returns the passes of the list, so that their uniforms can be modified.
+    #[inline]
+    pub fn passes_mut(&mut self) -> &mut [Pass<'a>] {
+        &mut self.passes
+    }
+
+    /// Runs the passes. The first one reads `source`, each other one reads the output of the
+    /// previous pass, and the last one draws to `target`. The intermediate images are drawn
+    /// to `buffers`.
+    ///
+    /// `source` must not be the back texture of `buffers`. Nothing is drawn if the list is
+    /// empty.
+    #[inline]
+    pub fn apply<S: ?Sized>(&self, fullscreen: &FullscreenTriangle, source: &Texture2d,
+                            buffers: &mut PingPong, target: &mut S) -> Result<(), EffectError>
+                            where S: Surface
+    {
+        self.run(fullscreen, Some(source), buffers, target)
+    }
+
+    /// Same as `apply`, but the first pass reads the front texture of `buffers`.
+    ///
+    /// This is useful if the scene has been drawn to the back texture before calling `swap`.
+    #[inline]
+    pub fn apply_in_place<S: ?Sized>(&self, fullscreen: &FullscreenTriangle,
+                                     buffers: &mut PingPong, target: &mut S)
+                                     -> Result<(), EffectError>
+                                     where S: Surface
+    {
+        self.run(fullscreen, None, buffers, target)
+    }
+
+    fn run<S: ?Sized>(&self, fullscreen: &FullscreenTriangle, source: Option<&Texture2d>,
+                      buffers: &mut PingPong, target: &mut S) -> Result<(), EffectError>
+                      where S: Surface
+    {
+        let (last, intermediate) = match self.passes.split_last() {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+
+        let mut source = source;
+
+        for pass in intermediate {
+            {
+                let input = source.unwrap_or_else(|| buffers.front());
+                let mut framebuffer = try!(buffers.back_framebuffer());
+                try!(pass.draw(fullscreen, input, &mut framebuffer));
+            }
+
+            buffers.swap();
+            source = None;
+        }
+
+        let input = source.unwrap_or_else(|| buffers.front());
+        try!(last.draw(fullscreen, input, target));
+        Ok(())
+    }
+}
+
+/// Error that can happen when running a list of passes.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum EffectError {
+    /// Failed to build a framebuffer that draws to a texture of the `PingPong`.
+    FramebufferCreation(ValidationError),
+
+    /// Failed to draw one of the passes.
+    Draw(DrawError),
+}
+
+impl fmt::Display for EffectError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for EffectError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            EffectError::FramebufferCreation(_) =>
+                "Failed to build a framebuffer that draws to a texture of the `PingPong`",
+            EffectError::Draw(_) => "Failed to draw one of the passes",
+        }
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            EffectError::FramebufferCreation(ref err) => Some(err),
+            EffectError::Draw(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ValidationError> for EffectError {
+    #[inline]
+    fn from(err: ValidationError) -> EffectError {
+        EffectError::FramebufferCreation(err)
+    }
+}
+
+impl From<DrawError> for EffectError {
+    #[inline]
+    fn from(err: DrawError) -> EffectError {
+        EffectError::Draw(err)
+    }
+}
@@ -0,0 +1,469 @@
+/*!
+Imports memory and semaphores exported by another API, such as Vulkan.
+
+With the `GL_EXT_memory_object` extension, memory that has been allocated by Vulkan and exported
+as a file descriptor or a Win32 handle can be imported in OpenGL. Buffers and textures can then
+be created from this memory, which makes it possible for example to render an image with Vulkan
+and present it with OpenGL, or to display the frames produced by a Vulkan video decoder without
+copying them.
+
+Accesses to the shared memory must be synchronized between the two APIs with semaphores, which
+are imported with the `GL_EXT_semaphore` extension.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let (memory_fd, semaphore_fd, allocation_size) = (0, 0, 0);
+use glium::external::{ExternalHandle, MemoryObject, Semaphore, TextureLayout};
+use glium::texture::UncompressedFloatFormat;
+
+// the file descriptors are returned by `vkGetMemoryFdKHR` and `vkGetSemaphoreFdKHR`
+let memory = unsafe {
+    MemoryObject::import(&display, ExternalHandle::Fd(memory_fd), allocation_size, true)
+}.unwrap();
+let texture = unsafe {
+    memory.create_texture_2d(UncompressedFloatFormat::U8U8U8U8, 1, 1920, 1080, 0)
+}.unwrap();
+let ready = unsafe { Semaphore::import(&display, ExternalHandle::Fd(semaphore_fd)) }.unwrap();
+
+// waiting for Vulkan to signal the semaphore before using the texture
+ready.wait(&[], &[(&texture, TextureLayout::ShaderReadOnly)]);
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::os::raw::{c_int, c_void};
+use std::rc::Rc;
+
+use backend::Facade;
+use buffer::{Buffer, BufferAnySlice, BufferType, Content};
+use context::Context;
+use image_format::{self, RequestType, TextureFormatRequest};
+use texture::{Dimensions, MipmapsOption, TextureAny, TextureFormat};
+use texture::{SrgbFormat, SrgbTexture2d, Texture2d, UncompressedFloatFormat};
+use version::{Api, Version};
+
+use gl;
+use ContextExt;
+use GlObject;
+
+/// Handle exported by another API.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExternalHandle {
+    /// An opaque file descriptor, as returned by `vkGetMemoryFdKHR` or `vkGetSemaphoreFdKHR`.
+    ///
+    /// The ownership of the file descriptor is transferred to OpenGL if the import succeeds.
+    Fd(c_int),
+
+    /// An opaque Win32 handle, as returned by `vkGetMemoryWin32HandleKHR` or
+    /// `vkGetSemaphoreWin32HandleKHR`.
+    ///
+    /// OpenGL doesn't take the ownership of the handle, which must be closed by the caller.
+    Win32(*mut c_void),
+}
+
+/// Error that can happen when importing an object or creating an object from imported memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ImportError {
+    /// The backend doesn't support importing this kind of object, or this kind of handle.
+    NotSupported,
+
+    /// The format of the texture is not supported by the backend.
+    FormatNotSupported,
+
+    /// The requested range is outside of the memory object.
+    OutOfBounds,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ImportError {
+    fn description(&self) -> &str {
+        match self {
+            &ImportError::NotSupported => "Importing this kind of object is not supported",
+            &ImportError::FormatNotSupported => "The format of the texture is not supported",
+            &ImportError::OutOfBounds => "The requested range is outside of the memory object",
+        }
+    }
+}
+
+/// Layout of a texture when it is handed to or received from the other API. Corresponds to the
+/// `VkImageLayout` of the Vulkan image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextureLayout {
+    /// `VK_IMAGE_LAYOUT_UNDEFINED`. The content of the texture is not preserved.
+    Undefined,
+    /// `VK_IMAGE_LAYOUT_GENERAL`.
+    General,
+    /// `VK_IMAGE_LAYOUT_COLOR_ATTACHMENT_OPTIMAL`.
+    ColorAttachment,
+    /// `VK_IMAGE_LAYOUT_DEPTH_STENCIL_ATTACHMENT_OPTIMAL`.
+    DepthStencilAttachment,
+    /// `VK_IMAGE_LAYOUT_DEPTH_STENCIL_READ_ONLY_OPTIMAL`.
+    DepthStencilReadOnly,
+    /// `VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL`.
+    ShaderReadOnly,
+    /// `VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL`.
+    TransferSrc,
+    /// `VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL`.
+    TransferDst,
+    /// `VK_IMAGE_LAYOUT_DEPTH_READ_ONLY_STENCIL_ATTACHMENT_OPTIMAL`.
+    DepthReadOnlyStencilAttachment,
+    /// `VK_IMAGE_LAYOUT_DEPTH_ATTACHMENT_STENCIL_READ_ONLY_OPTIMAL`.
+    DepthAttachmentStencilReadOnly,
+}
+
+// these values of `GL_EXT_semaphore` are missing from the registry used to generate `gl`
+const LAYOUT_DEPTH_READ_ONLY_STENCIL_ATTACHMENT_EXT: gl::types::GLenum = 0x9530;
+const LAYOUT_DEPTH_ATTACHMENT_STENCIL_READ_ONLY_EXT: gl::types::GLenum = 0x9531;
+
+impl TextureLayout {
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            TextureLayout::Undefined => gl::NONE,
+            TextureLayout::General => gl::LAYOUT_GENERAL_EXT,
+            TextureLayout::ColorAttachment => gl::LAYOUT_COLOR_ATTACHMENT_EXT,
+            TextureLayout::DepthStencilAttachment => gl::LAYOUT_DEPTH_STENCIL_ATTACHMENT_EXT,
+            TextureLayout::DepthStencilReadOnly => gl::LAYOUT_DEPTH_STENCIL_READ_ONLY_EXT,
+            TextureLayout::ShaderReadOnly => gl::LAYOUT_SHADER_READ_ONLY_EXT,
+            TextureLayout::TransferSrc => gl::LAYOUT_TRANSFER_SRC_EXT,
+            TextureLayout::TransferDst => gl::LAYOUT_TRANSFER_DST_EXT,
+            TextureLayout::DepthReadOnlyStencilAttachment =>
+                LAYOUT_DEPTH_READ_ONLY_STENCIL_ATTACHMENT_EXT,
+            TextureLayout::DepthAttachmentStencilReadOnly =>
+                LAYOUT_DEPTH_ATTACHMENT_STENCIL_READ_ONLY_EXT,
+        }
+    }
+}
+
+/// Memory allocated by another API and imported in OpenGL.
+///
+/// The buffers and textures created from this memory keep it alive, even after the
+/// `MemoryObject` has been destroyed.
+pub struct MemoryObject {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    size: u64,
+}
+
+impl MemoryObject {
+    /// Imports memory exported by another API.
+    ///
+    /// `size` is the size in bytes of the allocation. `dedicated` must be true if the memory
+    /// was allocated for a single image or buffer, in other words with
+    /// `VkMemoryDedicatedAllocateInfo` in Vulkan.
+    ///
+    /// # Unsafety
+    ///
+    /// The handle and the size must describe a valid allocation exported by an API that runs
+    /// on the same device as the OpenGL context.
+    pub unsafe fn import<F: ?Sized>(facade: &F, handle: ExternalHandle, size: u64,
+                                    dedicated: bool) -> Result<MemoryObject, ImportError>
+                                    where F: Facade
+    {
+        let ctxt = facade.get_context().make_current();
+
+        if !ctxt.extensions.gl_ext_memory_object {
+            return Err(ImportError::NotSupported);
+        }
+
+        match handle {
+            ExternalHandle::Fd(_) if !ctxt.extensions.gl_ext_memory_object_fd => {
+                return Err(ImportError::NotSupported);
+            },
+            ExternalHandle::Win32(_) if !ctxt.extensions.gl_ext_memory_object_win32 => {
+                return Err(ImportError::NotSupported);
+            },
+            _ => ()
+        }
+
+        let mut id = 0;
+        ctxt.gl.CreateMemoryObjectsEXT(1, &mut id);
+
+        if dedicated {
+            let value = gl::TRUE as gl::types::GLint;
+            ctxt.gl.MemoryObjectParameterivEXT(id, gl::DEDICATED_MEMORY_OBJECT_EXT, &value);
+        }
+
+        match handle {
+            ExternalHandle::Fd(fd) => {
+                ctxt.gl.ImportMemoryFdEXT(id, size, gl::HANDLE_TYPE_OPAQUE_FD_EXT, fd);
+            },
+            ExternalHandle::Win32(handle) => {
+                ctxt.gl.ImportMemoryWin32HandleEXT(id, size, gl::HANDLE_TYPE_OPAQUE_WIN32_EXT,
+                                                   handle);
+            },
+        }
+
+        Ok(MemoryObject {
+            context: facade.get_context().clone(),
+            id: id,
+            size: size,
+        })
+    }
+
+    /// Returns the size in bytes of the memory.
+    #[inline]
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Creates a buffer whose storage is `size` bytes of the memory, starting at `offset`.
+    ///
+    /// The buffer has an immutable storage. Like other buffers, it can still be modified with
+    /// `write` or by the GPU.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `size` is not suitable for the content of the buffer.
+    ///
+    /// # Unsafety
+    ///
+    /// The range must correspond to the memory bound to a buffer by the other API, and the
+    /// content of the memory must be valid for `T`.
+    pub unsafe fn create_buffer<T: ?Sized>(&self, ty: BufferType, offset: u64, size: usize)
+                                           -> Result<Buffer<T>, ImportError> where T: Content
+    {
+        if offset + size as u64 > self.size {
+            return Err(ImportError::OutOfBounds);
+        }
+
+        let id = {
+            let mut ctxt = self.context.make_current();
+            let mut id = 0;
+
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                ctxt.gl.CreateBuffers(1, &mut id);
+                ctxt.gl.NamedBufferStorageMemEXT(id, size as gl::types::GLsizeiptr, self.id,
+                                                 offset);
+            } else {
+                ctxt.gl.GenBuffers(1, &mut id);
+                ctxt.gl.BindBuffer(gl::ARRAY_BUFFER, id);
+                ctxt.state.array_buffer_binding = id;
+                ctxt.gl.BufferStorageMemEXT(gl::ARRAY_BUFFER, size as gl::types::GLsizeiptr,
+                                            self.id, offset);
+            }
+
+            id
+        };
+
+        Ok(Buffer::from_id(&self.context, id, ty))
+    }
+
+    /// Creates a two-dimensional texture whose storage starts at `offset` in the memory.
+    ///
+    /// `levels` is the number of mipmap levels of the texture, including the main level.
+    ///
+    /// # Unsafety
+    ///
+    /// The parameters must match the image that the memory was bound to by the other API. With
+    /// Vulkan, this image must have been created with `VK_IMAGE_TILING_OPTIMAL`.
+    pub unsafe fn create_texture_2d(&self, format: UncompressedFloatFormat, levels: u32,
+                                    width: u32, height: u32, offset: u64)
+                                    -> Result<Texture2d, ImportError>
+    {
+        let id = try!(self.create_texture_storage_2d(format.to_texture_format(), levels, width,
+                                                     height, offset));
+        Ok(Texture2d::from_id(&self.context, format, id, true,
+                              MipmapsOption::EmptyMipmapsMax(levels - 1),
+                              Dimensions::Texture2d { width: width, height: height }))
+    }
+
+    /// Creates a two-dimensional sRGB texture whose storage starts at `offset` in the memory.
+    ///
+    /// See `create_texture_2d`.
+    pub unsafe fn create_srgb_texture_2d(&self, format: SrgbFormat, levels: u32, width: u32,
+                                         height: u32, offset: u64)
+                                         -> Result<SrgbTexture2d, ImportError>
+    {
+        let id = try!(self.create_texture_storage_2d(format.to_texture_format(), levels, width,
+                                                     height, offset));
+        Ok(SrgbTexture2d::from_id(&self.context, format, id, true,
+                                  MipmapsOption::EmptyMipmapsMax(levels - 1),
+                                  Dimensions::Texture2d { width: width, height: height }))
+    }
+
+    /// Creates an OpenGL texture with `glTexStorageMem2DEXT` and returns its id.
+    unsafe fn create_texture_storage_2d(&self, format: TextureFormat, levels: u32, width: u32,
+                                        height: u32, offset: u64)
+                                        -> Result<gl::types::GLuint, ImportError>
+    {
+        assert!(levels >= 1);
+
+        if offset >= self.size {
+            return Err(ImportError::OutOfBounds);
+        }
+
+        let internal_format = try!(image_format::format_request_to_glenum(&self.context,
+                                        TextureFormatRequest::Specific(format),
+                                        RequestType::TexStorage)
+                                   .map_err(|_| ImportError::FormatNotSupported));
+
+        let mut ctxt = self.context.make_current();
+
+        let mut id = 0;
+        ctxt.gl.GenTextures(1, &mut id);
+
+        {
+            ctxt.gl.BindTexture(gl::TEXTURE_2D, id);
+            let act = ctxt.state.active_texture as usize;
+            ctxt.state.texture_units[act].texture = id;
+        }
+
+        let min_filter = if levels > 1 { gl::LINEAR_MIPMAP_LINEAR } else { gl::LINEAR };
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min_filter as i32);
+
+        ctxt.gl.TexStorageMem2DEXT(gl::TEXTURE_2D, levels as gl::types::GLsizei,
+                                   internal_format, width as gl::types::GLsizei,
+                                   height as gl::types::GLsizei, self.id, offset);
+
+        Ok(id)
+    }
+}
+
+impl GlObject for MemoryObject {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl fmt::Debug for MemoryObject {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "Memory object #{} ({} bytes)", self.id, self.size)
+    }
+}
+
+impl Drop for MemoryObject {
+    #[inline]
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+        unsafe { ctxt.gl.DeleteMemoryObjectsEXT(1, &self.id) };
+    }
+}
+
+/// A semaphore shared with another API.
+///
+/// The other API signals the semaphore when it has finished using the shared buffers and
+/// textures, and waits for it before using them again.
+pub struct Semaphore {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+}
+
+impl Semaphore {
+    /// Imports a semaphore exported by another API.
+    ///
+    /// # Unsafety
+    ///
+    /// The handle must be a valid semaphore exported by an API that runs on the same device as
+    /// the OpenGL context.
+    pub unsafe fn import<F: ?Sized>(facade: &F, handle: ExternalHandle)
+                                    -> Result<Semaphore, ImportError> where F: Facade
+    {
+        let ctxt = facade.get_context().make_current();
+
+        if !ctxt.extensions.gl_ext_semaphore {
+            return Err(ImportError::NotSupported);
+        }
+
+        let mut id = 0;
+
+        match handle {
+            ExternalHandle::Fd(fd) if ctxt.extensions.gl_ext_semaphore_fd => {
+                ctxt.gl.GenSemaphoresEXT(1, &mut id);
+                ctxt.gl.ImportSemaphoreFdEXT(id, gl::HANDLE_TYPE_OPAQUE_FD_EXT, fd);
+            },
+            ExternalHandle::Win32(handle) if ctxt.extensions.gl_ext_semaphore_win32 => {
+                ctxt.gl.GenSemaphoresEXT(1, &mut id);
+                ctxt.gl.ImportSemaphoreWin32HandleEXT(id, gl::HANDLE_TYPE_OPAQUE_WIN32_EXT,
+                                                      handle);
+            },
+            _ => return Err(ImportError::NotSupported)
+        }
+
+        Ok(Semaphore {
+            context: facade.get_context().clone(),
+            id: id,
+        })
+    }
+
+    /// Makes the next OpenGL commands wait until the other API has signaled the semaphore.
+    ///
+    /// `buffers` and `textures` are the objects that the other API has used before signaling
+    /// the semaphore. The layout of each texture is the layout that the other API has left it
+    /// in.
+    pub fn wait(&self, buffers: &[BufferAnySlice], textures: &[(&TextureAny, TextureLayout)]) {
+        let ctxt = self.context.make_current();
+        let (buffers, textures, layouts) = barriers(buffers, textures);
+
+        unsafe {
+            ctxt.gl.WaitSemaphoreEXT(self.id, buffers.len() as gl::types::GLuint,
+                                     buffers.as_ptr(), textures.len() as gl::types::GLuint,
+                                     textures.as_ptr(), layouts.as_ptr());
+        }
+    }
+
+    /// Signals the semaphore once the OpenGL commands that have been issued have finished, and
+    /// submits these commands.
+    ///
+    /// `buffers` and `textures` are the objects that the other API is going to use after
+    /// waiting for the semaphore. The layout of each texture is the layout that the other API
+    /// expects.
+    pub fn signal(&self, buffers: &[BufferAnySlice], textures: &[(&TextureAny, TextureLayout)]) {
+        let ctxt = self.context.make_current();
+        let (buffers, textures, layouts) = barriers(buffers, textures);
+
+        unsafe {
+            ctxt.gl.SignalSemaphoreEXT(self.id, buffers.len() as gl::types::GLuint,
+                                       buffers.as_ptr(), textures.len() as gl::types::GLuint,
+                                       textures.as_ptr(), layouts.as_ptr());
+
+            // the other API can't see the signal before the commands are submitted
+            ctxt.gl.Flush();
+        }
+    }
+}
+
+impl GlObject for Semaphore {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "Semaphore #{}", self.id)
+    }
+}
+
+impl Drop for Semaphore {
+    #[inline]
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+        unsafe { ctxt.gl.DeleteSemaphoresEXT(1, &self.id) };
+    }
+}
+
+/// Builds the lists of objects passed to `glWaitSemaphoreEXT` and `glSignalSemaphoreEXT`.
+fn barriers(buffers: &[BufferAnySlice], textures: &[(&TextureAny, TextureLayout)])
+            -> (Vec<gl::types::GLuint>, Vec<gl::types::GLuint>, Vec<gl::types::GLenum>)
+{
+    let buffers = buffers.iter().map(|b| b.get_id()).collect();
+    let layouts = textures.iter().map(|&(_, l)| l.to_glenum()).collect();
+    let textures = textures.iter().map(|&(t, _)| t.get_id()).collect();
+    (buffers, textures, layouts)
+}
@@ -566,6 +566,7 @@ impl<'a> ValidatedAttachments<'a> {
 
 /// An error that can happen while validating attachments.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ValidationError {
     /// You requested an empty framebuffer object, but they are not supported.
     EmptyFramebufferObjectsNotSupported,
@@ -824,6 +825,21 @@ impl FramebuffersContainer {
         unsafe { ctxt.gl.ReadBuffer(read_buffer) };     // TODO: cache
     }
 
+    /// Binds a framebuffer to `GL_READ_FRAMEBUFFER` or `GL_FRAMEBUFFER` so that its depth and
+    /// stencil buffers become the source of `glReadPixels`. `None` means the default framebuffer.
+    ///
+    /// # Unsafety
+    ///
+    /// After calling this function, you **must** make sure to call `purge_texture`
+    /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
+    #[inline]
+    pub unsafe fn bind_framebuffer_for_reading_depth_stencil(ctxt: &mut CommandContext,
+                                                             attachments: Option<&ValidatedAttachments>)
+    {
+        let framebuffer = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, attachments);
+        bind_framebuffer(ctxt, framebuffer, false, true);
+    }
+
     /// Binds a framebuffer to `GL_READ_FRAMEBUFFER` or `GL_FRAMEBUFFER` so that it becomes the
     /// target of `glReadPixels`, `glCopyTexImage2D`, etc.
     ///
@@ -1162,6 +1178,14 @@ impl GlObject for FrameBufferObject {
 pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext, fbo_id: gl::types::GLuint,
                                draw: bool, read: bool)
 {
+    // the attachments may have been written through an image unit by a previous command
+    if fbo_id != 0 &&
+       ctxt.state.latest_image_write >= ctxt.state.latest_memory_barrier_framebuffer
+    {
+        ctxt.gl.MemoryBarrier(gl::FRAMEBUFFER_BARRIER_BIT);
+        ctxt.state.latest_memory_barrier_framebuffer = ctxt.state.next_draw_call_id;
+    }
+
     if draw && read {
         if ctxt.state.draw_framebuffer != fbo_id || ctxt.state.read_framebuffer != fbo_id {
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
@@ -1185,7 +1209,9 @@ pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext, fbo_id: gl::types::GLu
 
         if draw && ctxt.state.draw_framebuffer != fbo_id {
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-               ctxt.extensions.gl_arb_framebuffer_object
+               ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+               ctxt.extensions.gl_arb_framebuffer_object ||
+               ctxt.extensions.gl_angle_framebuffer_blit
             {
                 ctxt.gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, fbo_id);
                 ctxt.state.draw_framebuffer = fbo_id;
@@ -1204,7 +1230,9 @@ pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext, fbo_id: gl::types::GLu
 
         if read && ctxt.state.read_framebuffer != fbo_id {
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-               ctxt.extensions.gl_arb_framebuffer_object
+               ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+               ctxt.extensions.gl_arb_framebuffer_object ||
+               ctxt.extensions.gl_angle_framebuffer_blit
             {
                 ctxt.gl.BindFramebuffer(gl::READ_FRAMEBUFFER, fbo_id);
                 ctxt.state.read_framebuffer = fbo_id;
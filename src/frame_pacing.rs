@@ -0,0 +1,236 @@
+/*!
+Controls how far the GPU can lag behind, and when the frames are shown.
+
+The OpenGL driver usually lets the application submit several frames while the GPU is still
+processing the previous ones. This improves the throughput, but each frame is then shown a long
+time after the input that it reflects has been read.
+
+Limiting the number of frames in flight with `set_max_frames_in_flight` makes `Frame::finish`
+insert a fence after swapping the buffers, and block until at most this number of frames are
+being processed by the GPU. Calling `wait_for_frame_slot` before reading the input reduces the
+latency further, as the input is then read as late as possible.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+use glium::frame_pacing;
+
+frame_pacing::set_max_frames_in_flight(&display, Some(1));
+
+loop {
+    frame_pacing::wait_for_frame_slot(&display);
+    // read the input here
+    let frame = display.draw();
+    // draw the scene here
+    frame.finish().unwrap();
+}
+```
+
+Some platforms also give control over the moment when the frames are shown:
+
+ - With `GLX_OML_sync_control`, `get_swap_timing` returns when the last frame has been shown,
+   and `get_refresh_rate` returns the refresh rate of the screen.
+ - With `EGL_ANDROID_presentation_time`, `set_presentation_time` chooses when the next frame is
+   shown.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_int, c_uint, c_ulong, c_void};
+
+use backend::Facade;
+use context::{self, Context};
+
+use ContextExt;
+
+const EGL_DRAW: c_int = 0x3059;
+
+type EglGetCurrentDisplay = extern "system" fn() -> *const c_void;
+type EglGetCurrentSurface = extern "system" fn(c_int) -> *const c_void;
+type EglPresentationTimeAndroid = extern "system" fn(*const c_void, *const c_void, i64) -> c_uint;
+type GlxGetCurrentDisplay = extern "C" fn() -> *mut c_void;
+type GlxGetCurrentDrawable = extern "C" fn() -> c_ulong;
+type GlxGetSyncValuesOml = extern "C" fn(*mut c_void, c_ulong, *mut i64, *mut i64,
+                                         *mut i64) -> c_int;
+type GlxGetMscRateOml = extern "C" fn(*mut c_void, c_ulong, *mut i32, *mut i32) -> c_int;
+
+/// Error that happens when the backend can't choose when a frame is shown.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PresentationTimeNotSupportedError;
+
+impl fmt::Display for PresentationTimeNotSupportedError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for PresentationTimeNotSupportedError {
+    fn description(&self) -> &str {
+        "The backend can't choose when a frame is shown"
+    }
+}
+
+/// When the last frame has been shown, as returned by `glXGetSyncValuesOML`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SwapTiming {
+    /// Time in microseconds at which the last frame started being shown. The origin of this
+    /// clock is unspecified.
+    pub ust: i64,
+
+    /// Number of refreshes of the screen since an unspecified origin.
+    pub msc: i64,
+
+    /// Number of times the buffers have been swapped.
+    pub sbc: i64,
+}
+
+/// Sets the maximum number of frames that can be processed by the GPU after the buffers have been
+/// swapped, or `None` to let the driver decide.
+///
+/// `Some(0)` makes `Frame::finish` wait until the frame has been entirely processed. If the
+/// backend doesn't support fences, `Frame::finish` behaves as with `Some(0)` whatever the number.
+#[inline]
+pub fn set_max_frames_in_flight<F: ?Sized>(facade: &F, max: Option<u32>) where F: Facade {
+    context::set_max_frames_in_flight(facade.get_context(), max)
+}
+
+/// Returns the value passed to `set_max_frames_in_flight`.
+#[inline]
+pub fn get_max_frames_in_flight<F: ?Sized>(facade: &F) -> Option<u32> where F: Facade {
+    context::get_max_frames_in_flight(facade.get_context())
+}
+
+/// Returns the number of frames that may still be processed by the GPU. Always `0` if the number
+/// of frames in flight isn't limited.
+#[inline]
+pub fn get_frames_in_flight<F: ?Sized>(facade: &F) -> usize where F: Facade {
+    context::get_frames_in_flight(facade.get_context())
+}
+
+/// Blocks until the next frame can be submitted without blocking in `Frame::finish`.
+///
+/// Does nothing if the number of frames in flight isn't limited.
+#[inline]
+pub fn wait_for_frame_slot<F: ?Sized>(facade: &F) where F: Facade {
+    context::wait_for_frame_slot(facade.get_context())
+}
+
+/// Chooses when the next frame is shown, as a time in nanoseconds of the `CLOCK_MONOTONIC`
+/// clock. Requires `EGL_ANDROID_presentation_time`.
+///
+/// The time applies to the next swap of the buffers.
+pub fn set_presentation_time<F: ?Sized>(facade: &F, nanoseconds: i64)
+                                        -> Result<(), PresentationTimeNotSupportedError>
+                                        where F: Facade
+{
+    let context = facade.get_context();
+    let _ctxt = context.make_current();
+
+    unsafe {
+        let get_current_display = context::get_proc_address(context, "eglGetCurrentDisplay");
+        let get_current_surface = context::get_proc_address(context, "eglGetCurrentSurface");
+        let presentation_time = context::get_proc_address(context,
+                                                          "eglPresentationTimeANDROID");
+        if get_current_display.is_null() || get_current_surface.is_null() ||
+           presentation_time.is_null()
+        {
+            return Err(PresentationTimeNotSupportedError);
+        }
+
+        let display = mem::transmute::<_, EglGetCurrentDisplay>(get_current_display)();
+        let surface = mem::transmute::<_, EglGetCurrentSurface>(get_current_surface)(EGL_DRAW);
+        if display.is_null() || surface.is_null() {
+            return Err(PresentationTimeNotSupportedError);
+        }
+
+        let presentation_time = mem::transmute::<_, EglPresentationTimeAndroid>(presentation_time);
+        if presentation_time(display, surface, nanoseconds) == 0 {
+            return Err(PresentationTimeNotSupportedError);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns when the last frame has been shown. Requires `GLX_OML_sync_control`.
+pub fn get_swap_timing<F: ?Sized>(facade: &F) -> Option<SwapTiming> where F: Facade {
+    let context = facade.get_context();
+    let _ctxt = context.make_current();
+
+    unsafe {
+        let (display, drawable) = match get_glx_drawable(context) {
+            Some(d) => d,
+            None => return None,
+        };
+
+        let get_sync_values = context::get_proc_address(context, "glXGetSyncValuesOML");
+        if get_sync_values.is_null() {
+            return None;
+        }
+
+        let (mut ust, mut msc, mut sbc) = (0, 0, 0);
+        let get_sync_values = mem::transmute::<_, GlxGetSyncValuesOml>(get_sync_values);
+        if get_sync_values(display, drawable, &mut ust, &mut msc, &mut sbc) == 0 {
+            return None;
+        }
+
+        Some(SwapTiming { ust: ust, msc: msc, sbc: sbc })
+    }
+}
+
+/// Returns the refresh rate of the screen in Hertz. Requires `GLX_OML_sync_control`.
+pub fn get_refresh_rate<F: ?Sized>(facade: &F) -> Option<f64> where F: Facade {
+    let context = facade.get_context();
+    let _ctxt = context.make_current();
+
+    unsafe {
+        let (display, drawable) = match get_glx_drawable(context) {
+            Some(d) => d,
+            None => return None,
+        };
+
+        let get_msc_rate = context::get_proc_address(context, "glXGetMscRateOML");
+        if get_msc_rate.is_null() {
+            return None;
+        }
+
+        let (mut numerator, mut denominator) = (0, 0);
+        let get_msc_rate = mem::transmute::<_, GlxGetMscRateOml>(get_msc_rate);
+        if get_msc_rate(display, drawable, &mut numerator, &mut denominator) == 0 ||
+           denominator == 0
+        {
+            return None;
+        }
+
+        Some(numerator as f64 / denominator as f64)
+    }
+}
+
+/// Returns the X display and the drawable of the current context, if it was created with GLX.
+///
+/// # Unsafety
+///
+/// The context must be current.
+unsafe fn get_glx_drawable(context: &Context) -> Option<(*mut c_void, c_ulong)> {
+    // an EGL implementation may return stubs for the functions of GLX
+    let egl_get_current_display = context::get_proc_address(context, "eglGetCurrentDisplay");
+    if !egl_get_current_display.is_null() &&
+       !mem::transmute::<_, EglGetCurrentDisplay>(egl_get_current_display)().is_null()
+    {
+        return None;
+    }
+
+    let get_current_display = context::get_proc_address(context, "glXGetCurrentDisplay");
+    let get_current_drawable = context::get_proc_address(context, "glXGetCurrentDrawable");
+    if get_current_display.is_null() || get_current_drawable.is_null() {
+        return None;
+    }
+
+    let display = mem::transmute::<_, GlxGetCurrentDisplay>(get_current_display)();
+    let drawable = mem::transmute::<_, GlxGetCurrentDrawable>(get_current_drawable)();
+    if display.is_null() || drawable == 0 {
+        return None;
+    }
+
+    Some((display, drawable))
+}
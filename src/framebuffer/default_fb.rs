@@ -9,10 +9,11 @@ use DrawParameters;
 use FboAttachments;
 use Rect;
 use BlitTarget;
-use ContextExt;
+use CapabilitiesSource;
 use ToGlEnum;
 use ops;
 use uniforms;
+use texture::{ReadError, Texture2dDataSink};
 
 use {Program, Surface};
 use DrawError;
@@ -67,11 +68,11 @@ impl Surface for DefaultFramebuffer {
     }
 
     fn get_depth_buffer_bits(&self) -> Option<u16> {
-        self.context.capabilities().depth_bits
+        self.context.get_capabilities().depth_bits
     }
 
     fn get_stencil_buffer_bits(&self) -> Option<u16> {
-        self.context.capabilities().stencil_bits
+        self.context.get_capabilities().stencil_bits
     }
 
     fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V,
@@ -80,24 +81,7 @@ impl Surface for DefaultFramebuffer {
                          where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
                          V: vertex::MultiVerticesSource<'b>
     {
-        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
-                draw_parameters.depth.write)
-        {
-            return Err(DrawError::NoDepthBuffer);
-        }
-
-        if let Some(viewport) = draw_parameters.viewport {
-            if viewport.width > self.context.capabilities().max_viewport_dims.0
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-            if viewport.height > self.context.capabilities().max_viewport_dims.1
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-        }
+        try!(ops::validate_surface(&self.context, self.has_depth_buffer(), draw_parameters));
 
         // TODO: wrong attachment
         ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
@@ -136,6 +120,16 @@ impl Surface for DefaultFramebuffer {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn read_depth<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<f32> {
+        ::read_surface_depth_stencil(&self.context, self, false)
+    }
+
+    #[inline]
+    fn read_stencil<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<u8> {
+        ::read_surface_depth_stencil(&self.context, self, true)
+    }
 }
 
 impl FboAttachments for DefaultFramebuffer {
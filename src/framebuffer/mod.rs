@@ -61,6 +61,12 @@ Modern OpenGL implementations support empty framebuffers. This is handled by gli
 
 You can check whether they are supported by calling `EmptyFrameBuffer::is_supported(&display)`.
 
+# Temporary render targets
+
+A `RenderTargetPool` lends textures that are only used during a frame, for example the
+intermediate images of post-processing effects, and recycles them instead of creating new
+textures every frame.
+
 # Layered framebuffers
 
 Not yet supported
@@ -69,7 +75,9 @@ Not yet supported
 use std::rc::Rc;
 use smallvec::SmallVec;
 
-use texture::TextureAnyImage;
+use texture::{TextureAnyImage, RawImage2d};
+use texture::pixel_buffer::PendingPixelRead;
+use texture::{ReadError, Texture2dDataSink};
 
 use backend::Facade;
 use context::Context;
@@ -80,7 +88,6 @@ use version::Api;
 use FboAttachments;
 use Rect;
 use BlitTarget;
-use ContextExt;
 use ToGlEnum;
 use ops;
 use uniforms;
@@ -89,15 +96,22 @@ use {Program, Surface};
 use DrawError;
 
 use {fbo, gl};
+use ContextExt;
+use GlObject;
 
 pub use self::default_fb::{DefaultFramebufferAttachment, DefaultFramebuffer};
 pub use self::render_buffer::{RenderBuffer, RenderBufferAny, DepthRenderBuffer};
 pub use self::render_buffer::{StencilRenderBuffer, DepthStencilRenderBuffer};
 pub use self::render_buffer::CreationError as RenderBufferCreationError;
+pub use self::pbuffer::Pbuffer;
+pub use self::pbuffer::CreationError as PbufferCreationError;
+pub use self::pool::{RenderTarget, RenderTargetKey, RenderTargetPool};
 pub use fbo::is_dimensions_mismatch_supported;
 pub use fbo::ValidationError;
 
 mod default_fb;
+mod pbuffer;
+mod pool;
 mod render_buffer;
 
 /// A framebuffer which has only one color attachment.
@@ -292,24 +306,7 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         where I: Into<::index::IndicesSource<'b>>, U: ::uniforms::Uniforms,
         V: ::vertex::MultiVerticesSource<'v>
     {
-        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
-                        draw_parameters.depth.write)
-        {
-            return Err(DrawError::NoDepthBuffer);
-        }
-
-        if let Some(viewport) = draw_parameters.viewport {
-            if viewport.width > self.context.capabilities().max_viewport_dims.0
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-            if viewport.height > self.context.capabilities().max_viewport_dims.1
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-        }
+        try!(ops::validate_surface(&self.context, self.has_depth_buffer(), draw_parameters));
 
         ops::draw(&self.context, Some(&self.attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
@@ -347,6 +344,16 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn read_depth<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<f32> {
+        ::read_surface_depth_stencil(&self.context, self, false)
+    }
+
+    #[inline]
+    fn read_stencil<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<u8> {
+        ::read_surface_depth_stencil(&self.context, self, true)
+    }
 }
 
 impl<'a> FboAttachments for SimpleFrameBuffer<'a> {
@@ -560,24 +567,7 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
         where I: Into<::index::IndicesSource<'i>>,
         U: ::uniforms::Uniforms, V: ::vertex::MultiVerticesSource<'v>
     {
-        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
-                draw_parameters.depth.write)
-        {
-            return Err(DrawError::NoDepthBuffer);
-        }
-
-        if let Some(viewport) = draw_parameters.viewport {
-            if viewport.width > self.context.capabilities().max_viewport_dims.0
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-            if viewport.height > self.context.capabilities().max_viewport_dims.1
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-        }
+        try!(ops::validate_surface(&self.context, self.has_depth_buffer(), draw_parameters));
 
         ops::draw(&self.context, Some(&self.build_attachments(program)), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
@@ -615,6 +605,30 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn read_depth<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<f32> {
+        ::read_surface_depth_stencil(&self.context, self, false)
+    }
+
+    #[inline]
+    fn read_stencil<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<u8> {
+        ::read_surface_depth_stencil(&self.context, self, true)
+    }
+}
+
+impl<'a> GlObject for SimpleFrameBuffer<'a> {
+    type Id = gl::types::GLuint;
+
+    /// Returns the id of the framebuffer object, which is created if necessary.
+    ///
+    /// The framebuffer object is owned by glium and is destroyed with its attachments. Its
+    /// attachments must not be modified.
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        let mut ctxt = self.context.make_current();
+        fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, Some(&self.attachments))
+    }
 }
 
 impl<'a> FboAttachments for MultiOutputFrameBuffer<'a> {
@@ -729,24 +743,7 @@ impl Surface for EmptyFrameBuffer {
         where I: Into<::index::IndicesSource<'b>>, U: ::uniforms::Uniforms,
         V: ::vertex::MultiVerticesSource<'v>
     {
-        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
-                        draw_parameters.depth.write)
-        {
-            return Err(DrawError::NoDepthBuffer);
-        }
-
-        if let Some(viewport) = draw_parameters.viewport {
-            if viewport.width > self.context.capabilities().max_viewport_dims.0
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-            if viewport.height > self.context.capabilities().max_viewport_dims.1
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-        }
+        try!(ops::validate_surface(&self.context, self.has_depth_buffer(), draw_parameters));
 
         ops::draw(&self.context, Some(&self.attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
@@ -784,6 +781,32 @@ impl Surface for EmptyFrameBuffer {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn snapshot<F: ?Sized>(&self, _: &F) -> Result<RawImage2d<'static, u8>, ReadError>
+                           where F: Facade
+    {
+        Err(ReadError::NoColorBuffer)
+    }
+
+    #[inline]
+    fn read_to_pixel_buffer_async<F: ?Sized>(&self, _: &F)
+                                             -> Result<PendingPixelRead<(u8, u8, u8, u8)>,
+                                                       ReadError>
+                                             where F: Facade
+    {
+        Err(ReadError::NoColorBuffer)
+    }
+
+    #[inline]
+    fn read_depth<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<f32> {
+        ::read_surface_depth_stencil(&self.context, self, false)
+    }
+
+    #[inline]
+    fn read_stencil<T>(&self) -> Result<T, ReadError> where T: Texture2dDataSink<u8> {
+        ::read_surface_depth_stencil(&self.context, self, true)
+    }
 }
 
 impl FboAttachments for EmptyFrameBuffer {
@@ -793,6 +816,17 @@ impl FboAttachments for EmptyFrameBuffer {
     }
 }
 
+impl GlObject for EmptyFrameBuffer {
+    type Id = gl::types::GLuint;
+
+    /// Returns the id of the framebuffer object, which is created if necessary.
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        let mut ctxt = self.context.make_current();
+        fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, Some(&self.attachments))
+    }
+}
+
 /// Describes an attachment for a color buffer.
 #[derive(Copy, Clone)]
 pub enum ColorAttachment<'a> {
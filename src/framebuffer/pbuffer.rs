@@ -0,0 +1,161 @@
+/*!
+
+An offscreen surface that uses the context of an existing display.
+
+Contrary to a pbuffer created with the platform's API, a `Pbuffer` doesn't need a context of its
+own: it owns a texture and a depth buffer, and drawing on it binds a framebuffer object. It can
+therefore have any dimensions, independently of the window of the display.
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use framebuffer::{DepthRenderBuffer, RenderBufferCreationError, SimpleFrameBuffer};
+use texture::{DepthFormat, MipmapsOption, Texture2d, Texture2dDataSink};
+use texture::{TextureCreationError, UncompressedFloatFormat};
+
+use TextureExt;
+
+/// Error while creating a `Pbuffer`.
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum CreationError {
+    /// The color texture couldn't be created.
+    TextureCreationError(TextureCreationError),
+
+    /// The depth buffer couldn't be created.
+    DepthBufferCreationError(RenderBufferCreationError),
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for CreationError {
+    fn description(&self) -> &str {
+        use self::CreationError::*;
+        match *self {
+            TextureCreationError(ref err) => err.description(),
+            DepthBufferCreationError(ref err) => err.description(),
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::CreationError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            DepthBufferCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for CreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> CreationError {
+        CreationError::TextureCreationError(err)
+    }
+}
+
+impl From<RenderBufferCreationError> for CreationError {
+    #[inline]
+    fn from(err: RenderBufferCreationError) -> CreationError {
+        CreationError::DepthBufferCreationError(err)
+    }
+}
+
+/// An offscreen surface that is drawn with the context of a display, for example to render
+/// thumbnails without resizing the window.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// let pbuffer = glium::framebuffer::Pbuffer::new(&display, 256, 256).unwrap();
+///
+/// let mut surface = pbuffer.draw();
+/// surface.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+/// // surface.draw(...);
+///
+/// let thumbnail: glium::texture::RawImage2d<u8> = pbuffer.read();
+/// ```
+pub struct Pbuffer {
+    color: Texture2d,
+    depth: Option<DepthRenderBuffer>,
+}
+
+impl Pbuffer {
+    /// Builds a new pbuffer with an RGBA8 color buffer and a 24 bits depth buffer.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, width: u32, height: u32) -> Result<Pbuffer, CreationError>
+                          where F: Facade
+    {
+        Pbuffer::with_formats(facade, UncompressedFloatFormat::U8U8U8U8, Some(DepthFormat::I24),
+                              width, height)
+    }
+
+    /// Builds a new pbuffer with the given formats. Pass `None` for `depth` to create a pbuffer
+    /// without a depth buffer.
+    pub fn with_formats<F: ?Sized>(facade: &F, color: UncompressedFloatFormat,
+                                   depth: Option<DepthFormat>, width: u32, height: u32)
+                                   -> Result<Pbuffer, CreationError> where F: Facade
+    {
+        let color = try!(Texture2d::empty_with_format(facade, color, MipmapsOption::NoMipmap,
+                                                      width, height));
+
+        let depth = match depth {
+            Some(format) => Some(try!(DepthRenderBuffer::new(facade, format, width, height))),
+            None => None,
+        };
+
+        Ok(Pbuffer {
+            color: color,
+            depth: depth,
+        })
+    }
+
+    /// Returns the width and height of the pbuffer.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        (self.color.get_width(), self.color.get_height().unwrap())
+    }
+
+    /// Returns true if the pbuffer has a depth buffer.
+    #[inline]
+    pub fn has_depth_buffer(&self) -> bool {
+        self.depth.is_some()
+    }
+
+    /// Returns a surface that draws on the pbuffer.
+    pub fn draw(&self) -> SimpleFrameBuffer {
+        let context = self.color.get_context();
+
+        match self.depth {
+            Some(ref depth) => {
+                SimpleFrameBuffer::with_depth_buffer(context, &self.color, depth).unwrap()
+            },
+            None => SimpleFrameBuffer::new(context, &self.color).unwrap(),
+        }
+    }
+
+    /// Reads the content of the color buffer.
+    #[inline]
+    pub fn read<T>(&self) -> T where T: Texture2dDataSink<(u8, u8, u8, u8)> {
+        self.color.read()
+    }
+
+    /// Returns the texture that contains the color buffer, so that it can be sampled.
+    #[inline]
+    pub fn texture(&self) -> &Texture2d {
+        &self.color
+    }
+
+    /// Destroys the pbuffer and returns the texture that contains its color buffer.
+    #[inline]
+    pub fn into_texture(self) -> Texture2d {
+        self.color
+    }
+}
@@ -0,0 +1,252 @@
+/*!
+
+A pool of textures that are used as temporary render targets.
+
+Post-processing effects often need textures that only live during a frame, for example the
+intermediate images of a blur. Creating and destroying them every frame is expensive, so a
+`RenderTargetPool` keeps the textures that are returned to it and lends them again when a
+texture with the same dimensions, format and number of samples is requested.
+
+The framebuffer objects are cached by glium for each texture, which means that calling
+`as_surface` on a recycled texture doesn't create a new FBO either.
+
+*/
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use framebuffer::SimpleFrameBuffer;
+use texture::{MipmapsOption, Texture2d, Texture2dMultisample, TextureCreationError};
+use texture::UncompressedFloatFormat;
+
+/// Describes the textures that can be exchanged for one another.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct RenderTargetKey {
+    /// Width of the texture.
+    pub width: u32,
+    /// Height of the texture.
+    pub height: u32,
+    /// Format of the texture.
+    pub format: UncompressedFloatFormat,
+    /// Number of samples of the texture. `0` and `1` mean that the texture isn't multisampled.
+    pub samples: u32,
+}
+
+impl RenderTargetKey {
+    /// Builds a key for a texture that isn't multisampled.
+    #[inline]
+    pub fn new(width: u32, height: u32, format: UncompressedFloatFormat) -> RenderTargetKey {
+        RenderTargetKey {
+            width: width,
+            height: height,
+            format: format,
+            samples: 0,
+        }
+    }
+
+    /// Builds a key for a multisampled texture.
+    #[inline]
+    pub fn multisample(width: u32, height: u32, format: UncompressedFloatFormat, samples: u32)
+                       -> RenderTargetKey
+    {
+        RenderTargetKey {
+            width: width,
+            height: height,
+            format: format,
+            samples: samples,
+        }.normalize()
+    }
+
+    /// Turns a number of samples of `1` into `0`, as both describe the same texture.
+    #[inline]
+    fn normalize(self) -> RenderTargetKey {
+        RenderTargetKey {
+            samples: if self.samples == 1 { 0 } else { self.samples },
+            .. self
+        }
+    }
+}
+
+enum PooledTexture {
+    Regular(Texture2d),
+    Multisample(Texture2dMultisample),
+}
+
+/// Lends textures and recycles them.
+pub struct RenderTargetPool {
+    context: Rc<Context>,
+    // available textures, with the frame number where they have been used for the last time
+    free: RefCell<HashMap<RenderTargetKey, Vec<(PooledTexture, u64)>>>,
+    frame: Cell<u64>,
+    max_unused_frames: u64,
+    loaned: Cell<usize>,
+}
+
+impl RenderTargetPool {
+    /// Builds an empty pool. Textures that haven't been used for two frames are destroyed by
+    /// `end_frame`.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F) -> RenderTargetPool where F: Facade {
+        RenderTargetPool::with_max_unused_frames(facade, 2)
+    }
+
+    /// Builds an empty pool. Textures that haven't been used for `frames` frames are destroyed
+    /// by `end_frame`.
+    #[inline]
+    pub fn with_max_unused_frames<F: ?Sized>(facade: &F, frames: u64) -> RenderTargetPool
+                                             where F: Facade
+    {
+        RenderTargetPool {
+            context: facade.get_context().clone(),
+            free: RefCell::new(HashMap::new()),
+            frame: Cell::new(0),
+            max_unused_frames: frames,
+            loaned: Cell::new(0),
+        }
+    }
+
+    /// Lends a texture. It is returned to the pool when the `RenderTarget` is destroyed.
+    ///
+    /// The content of the texture is undefined, and usually contains what has been drawn the
+    /// last time it was used.
+    pub fn get(&self, key: RenderTargetKey) -> Result<RenderTarget, TextureCreationError> {
+        let key = key.normalize();
+        let recycled = self.free.borrow_mut().get_mut(&key).and_then(|list| list.pop());
+
+        let texture = match recycled {
+            Some((texture, _)) => texture,
+            None if key.samples == 0 => {
+                PooledTexture::Regular(try!(Texture2d::empty_with_format(&self.context,
+                                                                         key.format,
+                                                                         MipmapsOption::NoMipmap,
+                                                                         key.width,
+                                                                         key.height)))
+            },
+            None => {
+                PooledTexture::Multisample(try!(Texture2dMultisample::empty_with_format(
+                                                        &self.context, key.format,
+                                                        MipmapsOption::NoMipmap, key.width,
+                                                        key.height, key.samples)))
+            },
+        };
+
+        self.loaned.set(self.loaned.get() + 1);
+
+        Ok(RenderTarget {
+            pool: self,
+            key: key,
+            texture: Some(texture),
+        })
+    }
+
+    /// Same as `get` with a texture that isn't multisampled.
+    #[inline]
+    pub fn get_texture(&self, width: u32, height: u32, format: UncompressedFloatFormat)
+                       -> Result<RenderTarget, TextureCreationError>
+    {
+        self.get(RenderTargetKey::new(width, height, format))
+    }
+
+    /// Starts a new frame, and destroys the textures that haven't been used for too long.
+    pub fn end_frame(&self) {
+        let frame = self.frame.get() + 1;
+        self.frame.set(frame);
+
+        let max_unused_frames = self.max_unused_frames;
+        let mut free = self.free.borrow_mut();
+
+        for list in free.values_mut() {
+            list.retain(|&(_, last_use)| frame - last_use <= max_unused_frames);
+        }
+
+        free.retain(|_, list| !list.is_empty());
+    }
+
+    /// Returns the number of textures that are in the pool and can be lent.
+    #[inline]
+    pub fn get_free_textures_count(&self) -> usize {
+        self.free.borrow().values().map(|list| list.len()).sum()
+    }
+
+    /// Returns the number of textures that are currently lent.
+    #[inline]
+    pub fn get_loaned_textures_count(&self) -> usize {
+        self.loaned.get()
+    }
+
+    /// Destroys all the textures that are in the pool. The lent textures are not affected.
+    #[inline]
+    pub fn clear(&self) {
+        self.free.borrow_mut().clear();
+    }
+}
+
+/// A texture lent by a `RenderTargetPool`. It is returned to the pool when destroyed.
+pub struct RenderTarget<'p> {
+    pool: &'p RenderTargetPool,
+    key: RenderTargetKey,
+    texture: Option<PooledTexture>,
+}
+
+impl<'p> RenderTarget<'p> {
+    /// Returns the key that describes the texture.
+    #[inline]
+    pub fn get_key(&self) -> RenderTargetKey {
+        self.key
+    }
+
+    /// Returns the texture, or `None` if it is multisampled.
+    #[inline]
+    pub fn texture(&self) -> Option<&Texture2d> {
+        match self.texture {
+            Some(PooledTexture::Regular(ref t)) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// Returns the texture, or `None` if it isn't multisampled.
+    #[inline]
+    pub fn multisample_texture(&self) -> Option<&Texture2dMultisample> {
+        match self.texture {
+            Some(PooledTexture::Multisample(ref t)) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// Starts drawing on the texture.
+    #[inline]
+    pub fn as_surface(&self) -> SimpleFrameBuffer {
+        match self.texture {
+            Some(PooledTexture::Regular(ref t)) => t.as_surface(),
+            Some(PooledTexture::Multisample(ref t)) => t.as_surface(),
+            None => unreachable!(),
+        }
+    }
+}
+
+impl<'p> Drop for RenderTarget<'p> {
+    fn drop(&mut self) {
+        let texture = self.texture.take().unwrap();
+        let frame = self.pool.frame.get();
+
+        self.pool.free.borrow_mut().entry(self.key).or_insert_with(Vec::new)
+                                   .push((texture, frame));
+        self.pool.loaned.set(self.pool.loaned.get() - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use texture::UncompressedFloatFormat;
+    use super::RenderTargetKey;
+
+    #[test]
+    fn single_sample_key() {
+        let format = UncompressedFloatFormat::U8U8U8U8;
+        assert_eq!(RenderTargetKey::multisample(64, 64, format, 1),
+                   RenderTargetKey::new(64, 64, format));
+        assert_eq!(RenderTargetKey::multisample(64, 64, format, 4).samples, 4);
+    }
+}
@@ -31,6 +31,7 @@ use version::Api;
 
 /// Error while creating a render buffer.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum CreationError {
     /// The requested format is not supported.
     FormatNotSupported,
@@ -0,0 +1,393 @@
+/*!
+Preprocessing of the vertices on the CPU, before they are uploaded.
+
+This module is only available if you enable the `geometry` feature of glium.
+
+It contains the steps that most programs need when they load or generate a model:
+
+ - `weld`, which merges identical vertices and generates the list of indices.
+ - `compute_normals` and `compute_tangents`, for lighting and normal mapping.
+ - `BoundingBox`, for culling.
+
+The functions work with your own vertex type. Those that need to read an attribute take a
+closure that returns it, and the indices describe a list of triangles.
+
+```
+# #[macro_use] extern crate glium;
+# fn main() {
+use glium::geometry;
+
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+implement_vertex!(Vertex, position, normal);
+
+// two triangles forming a quad, with duplicated vertices
+let mut vertices = vec![
+    Vertex { position: [0.0, 0.0, 0.0], normal: [0.0; 3] },
+    Vertex { position: [1.0, 0.0, 0.0], normal: [0.0; 3] },
+    Vertex { position: [1.0, 1.0, 0.0], normal: [0.0; 3] },
+    Vertex { position: [0.0, 0.0, 0.0], normal: [0.0; 3] },
+    Vertex { position: [1.0, 1.0, 0.0], normal: [0.0; 3] },
+    Vertex { position: [0.0, 1.0, 0.0], normal: [0.0; 3] },
+];
+
+let (unique, indices) = geometry::weld(&vertices);
+assert_eq!(unique.len(), 4);
+assert_eq!(indices, vec![0, 1, 2, 0, 2, 3]);
+vertices = unique;
+
+let normals = geometry::compute_normals(&vertices, &indices, |v| v.position);
+for (vertex, normal) in vertices.iter_mut().zip(normals) {
+    vertex.normal = normal;
+}
+assert_eq!(vertices[0].normal, [0.0, 0.0, 1.0]);
+
+let bounds = geometry::BoundingBox::from_vertices(&vertices, |v| v.position).unwrap();
+assert_eq!(bounds.max, [1.0, 1.0, 0.0]);
+# }
+```
+
+*/
+use std::collections::HashMap;
+use std::slice;
+
+use vertex::Vertex;
+
+/// Merges the vertices that are identical and returns the list of unique vertices, along with
+/// the indices that rebuild the original list.
+///
+/// Two vertices are identical if all the attributes of their `VertexFormat` have the same
+/// bytes. The bytes that are not part of an attribute, such as padding, are ignored. Note that
+/// `0.0` and `-0.0` are considered different.
+///
+/// The unique vertices keep the order of their first occurrence.
+pub fn weld<V>(vertices: &[V]) -> (Vec<V>, Vec<u32>) where V: Vertex {
+    let format = V::build_bindings();
+    let ranges = format.iter().map(|&(_, offset, ty, _)| (offset, ty.get_size_bytes()))
+                       .collect::<Vec<_>>();
+
+    let mut unique = Vec::new();
+    let mut indices = Vec::with_capacity(vertices.len());
+    let mut known: HashMap<Vec<u8>, u32> = HashMap::with_capacity(vertices.len());
+
+    for vertex in vertices {
+        let bytes = unsafe {
+            slice::from_raw_parts(vertex as *const V as *const u8, ::std::mem::size_of::<V>())
+        };
+
+        let mut key = Vec::with_capacity(bytes.len());
+        for &(offset, size) in &ranges {
+            key.extend_from_slice(&bytes[offset .. offset + size]);
+        }
+
+        let index = *known.entry(key).or_insert_with(|| {
+            unique.push(*vertex);
+            (unique.len() - 1) as u32
+        });
+
+        indices.push(index);
+    }
+
+    (unique, indices)
+}
+
+/// Computes the normal of each vertex, by averaging the normals of the triangles that use it.
+///
+/// The normals of the triangles are weighted by their area. The indices must describe a list of
+/// triangles, and vertices that aren't used by any triangle get a null normal.
+///
+/// Vertices that are shared between faces with different orientations, such as the corners of
+/// a cube, get an averaged normal. Don't weld these vertices if the edges must look sharp.
+///
+/// # Panic
+///
+/// Panics if the number of indices is not a multiple of 3, or if an index is out of range.
+pub fn compute_normals<V, I, F>(vertices: &[V], indices: &[I], position: F) -> Vec<[f32; 3]>
+                                where I: Copy + Into<u32>, F: Fn(&V) -> [f32; 3]
+{
+    assert!(indices.len() % 3 == 0, "The number of indices must be a multiple of 3");
+
+    let mut normals = vec![[0.0f32; 3]; vertices.len()];
+
+    for triangle in indices.chunks(3) {
+        let (a, b, c) = (triangle[0].into() as usize, triangle[1].into() as usize,
+                         triangle[2].into() as usize);
+        let (pa, pb, pc) = (position(&vertices[a]), position(&vertices[b]),
+                            position(&vertices[c]));
+
+        // the length of the cross product is twice the area of the triangle
+        let normal = cross(sub(pb, pa), sub(pc, pa));
+
+        for &index in &[a, b, c] {
+            normals[index] = add(normals[index], normal);
+        }
+    }
+
+    for normal in normals.iter_mut() {
+        *normal = normalize(*normal);
+    }
+
+    normals
+}
+
+/// Computes the tangent of each vertex, for normal mapping.
+///
+/// The first three components are the tangent, which points towards increasing `u` texture
+/// coordinates and is orthogonal to the normal. The fourth component is `1.0` or `-1.0`, and
+/// the bitangent is `cross(normal, tangent.xyz) * tangent.w`.
+///
+/// The indices must describe a list of triangles. Vertices that aren't used by any triangle, or
+/// whose texture coordinates are degenerate, get an arbitrary tangent orthogonal to the normal.
+///
+/// # Panic
+///
+/// Panics if the number of indices is not a multiple of 3, or if an index is out of range.
+pub fn compute_tangents<V, I, P, N, T>(vertices: &[V], indices: &[I], position: P, normal: N,
+                                       tex_coords: T) -> Vec<[f32; 4]>
+                                       where I: Copy + Into<u32>, P: Fn(&V) -> [f32; 3],
+                                             N: Fn(&V) -> [f32; 3], T: Fn(&V) -> [f32; 2]
+{
+    assert!(indices.len() % 3 == 0, "The number of indices must be a multiple of 3");
+
+    let mut tangents = vec![[0.0f32; 3]; vertices.len()];
+    let mut bitangents = vec![[0.0f32; 3]; vertices.len()];
+
+    for triangle in indices.chunks(3) {
+        let (a, b, c) = (triangle[0].into() as usize, triangle[1].into() as usize,
+                         triangle[2].into() as usize);
+
+        let (pa, pb, pc) = (position(&vertices[a]), position(&vertices[b]),
+                            position(&vertices[c]));
+        let (ta, tb, tc) = (tex_coords(&vertices[a]), tex_coords(&vertices[b]),
+                            tex_coords(&vertices[c]));
+
+        let (e1, e2) = (sub(pb, pa), sub(pc, pa));
+        let (du1, dv1) = (tb[0] - ta[0], tb[1] - ta[1]);
+        let (du2, dv2) = (tc[0] - ta[0], tc[1] - ta[1]);
+
+        let det = du1 * dv2 - du2 * dv1;
+        if det == 0.0 {
+            continue;
+        }
+
+        let r = 1.0 / det;
+        let tangent = scale(sub(scale(e1, dv2), scale(e2, dv1)), r);
+        let bitangent = scale(sub(scale(e2, du1), scale(e1, du2)), r);
+
+        for &index in &[a, b, c] {
+            tangents[index] = add(tangents[index], tangent);
+            bitangents[index] = add(bitangents[index], bitangent);
+        }
+    }
+
+    vertices.iter().enumerate().map(|(index, vertex)| {
+        let n = normalize(normal(vertex));
+
+        // Gram-Schmidt orthogonalization
+        let mut t = normalize(sub(tangents[index], scale(n, dot(n, tangents[index]))));
+
+        if t == [0.0; 3] {
+            // any vector orthogonal to the normal
+            let axis = if n[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+            t = normalize(cross(axis, n));
+        }
+
+        let w = if dot(cross(n, t), bitangents[index]) < 0.0 { -1.0 } else { 1.0 };
+        [t[0], t[1], t[2], w]
+    }).collect()
+}
+
+/// An axis-aligned box.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingBox {
+    /// The corner with the lowest coordinates.
+    pub min: [f32; 3],
+    /// The corner with the highest coordinates.
+    pub max: [f32; 3],
+}
+
+impl BoundingBox {
+    /// Computes the smallest box that contains all the points. Returns `None` if there is no
+    /// point.
+    pub fn from_points<I>(points: I) -> Option<BoundingBox> where I: IntoIterator<Item = [f32; 3]> {
+        let mut points = points.into_iter();
+
+        let first = match points.next() {
+            Some(p) => p,
+            None => return None,
+        };
+
+        let mut bounds = BoundingBox { min: first, max: first };
+        for point in points {
+            bounds.add_point(point);
+        }
+
+        Some(bounds)
+    }
+
+    /// Computes the smallest box that contains the positions of all the vertices. Returns `None`
+    /// if there is no vertex.
+    #[inline]
+    pub fn from_vertices<V, F>(vertices: &[V], position: F) -> Option<BoundingBox>
+                               where F: Fn(&V) -> [f32; 3]
+    {
+        BoundingBox::from_points(vertices.iter().map(position))
+    }
+
+    /// Enlarges the box so that it contains a point.
+    #[inline]
+    pub fn add_point(&mut self, point: [f32; 3]) {
+        for i in 0 .. 3 {
+            self.min[i] = self.min[i].min(point[i]);
+            self.max[i] = self.max[i].max(point[i]);
+        }
+    }
+
+    /// Returns the smallest box that contains both boxes.
+    #[inline]
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let mut result = *self;
+        result.add_point(other.min);
+        result.add_point(other.max);
+        result
+    }
+
+    /// Returns the center of the box.
+    #[inline]
+    pub fn center(&self) -> [f32; 3] {
+        scale(add(self.min, self.max), 0.5)
+    }
+
+    /// Returns the size of the box along each axis.
+    #[inline]
+    pub fn size(&self) -> [f32; 3] {
+        sub(self.max, self.min)
+    }
+
+    /// Returns true if the point is inside the box or on its border.
+    #[inline]
+    pub fn contains(&self, point: [f32; 3]) -> bool {
+        (0 .. 3).all(|i| point[i] >= self.min[i] && point[i] <= self.max[i])
+    }
+}
+
+#[inline]
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+#[inline]
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+#[inline]
+fn scale(a: [f32; 3], s: f32) -> [f32; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+#[inline]
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline]
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// Normalizes a vector, or returns a null vector if its length is 0.
+#[inline]
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    let len = dot(a, a).sqrt();
+    if len == 0.0 { [0.0; 3] } else { scale(a, 1.0 / len) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_normals, compute_tangents, weld, BoundingBox};
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Vertex {
+        position: [f32; 3],
+        tex_coords: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position, tex_coords);
+
+    fn quad() -> Vec<Vertex> {
+        vec![
+            Vertex { position: [0.0, 0.0, 0.0], tex_coords: [0.0, 0.0] },
+            Vertex { position: [2.0, 0.0, 0.0], tex_coords: [1.0, 0.0] },
+            Vertex { position: [2.0, 2.0, 0.0], tex_coords: [1.0, 1.0] },
+            Vertex { position: [0.0, 2.0, 0.0], tex_coords: [0.0, 1.0] },
+        ]
+    }
+
+    #[test]
+    fn weld_duplicates() {
+        let quad = quad();
+        let vertices = [quad[0], quad[1], quad[2], quad[0], quad[2], quad[3]];
+
+        let (unique, indices) = weld(&vertices);
+        assert_eq!(unique, quad);
+        assert_eq!(indices, vec![0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn weld_keeps_different_attributes() {
+        let mut other = quad()[0];
+        other.tex_coords = [0.5, 0.5];
+
+        let (unique, indices) = weld(&[quad()[0], other]);
+        assert_eq!(unique.len(), 2);
+        assert_eq!(indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn normals_of_quad() {
+        let normals = compute_normals(&quad(), &[0u16, 1, 2, 0, 2, 3], |v| v.position);
+        assert_eq!(normals, vec![[0.0, 0.0, 1.0]; 4]);
+
+        // unused vertices get a null normal
+        let normals = compute_normals(&quad(), &[0u16, 1, 2], |v| v.position);
+        assert_eq!(normals[3], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn tangents_of_quad() {
+        let tangents = compute_tangents(&quad(), &[0u32, 1, 2, 0, 2, 3], |v| v.position,
+                                        |_| [0.0, 0.0, 1.0], |v| v.tex_coords);
+        assert_eq!(tangents, vec![[1.0, 0.0, 0.0, 1.0]; 4]);
+
+        // mirrored texture coordinates
+        let mut mirrored = quad();
+        for vertex in mirrored.iter_mut() {
+            vertex.tex_coords[1] = 1.0 - vertex.tex_coords[1];
+        }
+
+        let tangents = compute_tangents(&mirrored, &[0u32, 1, 2, 0, 2, 3], |v| v.position,
+                                        |_| [0.0, 0.0, 1.0], |v| v.tex_coords);
+        assert_eq!(tangents, vec![[1.0, 0.0, 0.0, -1.0]; 4]);
+    }
+
+    #[test]
+    fn bounding_box() {
+        assert_eq!(BoundingBox::from_points(vec![]), None);
+
+        let bounds = BoundingBox::from_vertices(&quad(), |v| v.position).unwrap();
+        assert_eq!(bounds, BoundingBox { min: [0.0, 0.0, 0.0], max: [2.0, 2.0, 0.0] });
+        assert_eq!(bounds.center(), [1.0, 1.0, 0.0]);
+        assert_eq!(bounds.size(), [2.0, 2.0, 0.0]);
+        assert!(bounds.contains([1.0, 2.0, 0.0]));
+        assert!(!bounds.contains([1.0, 2.5, 0.0]));
+
+        let other = BoundingBox { min: [-1.0, 0.0, 0.0], max: [0.0, 0.0, 3.0] };
+        assert_eq!(bounds.union(&other),
+                   BoundingBox { min: [-1.0, 0.0, 0.0], max: [2.0, 2.0, 3.0] });
+    }
+}
@@ -0,0 +1,589 @@
+/*!
+Schedules the passes of a frame from the textures that they read and write.
+
+Each pass of a `RenderGraph` declares the resources that it reads and the resources that it
+writes. When the graph is executed, glium:
+
+ - Sorts the passes so that each pass runs after the passes that write the resources it reads.
+   Passes are otherwise kept in the order in which they have been added.
+ - Removes the passes whose result is never used.
+ - Borrows the transient textures from a `RenderTargetPool` just before the first pass that uses
+   them, and gives them back just after the last one. Two transient textures with the same
+   dimensions and format whose lifetimes don't overlap are therefore the same texture.
+ - Synchronizes the passes that depend on each other, and invalidates the content of the
+   transient textures after their last use so that the driver doesn't need to preserve it.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let scene: glium::Program = unsafe { ::std::mem::uninitialized() };
+# let blur: glium::Program = unsafe { ::std::mem::uninitialized() };
+use glium::Surface;
+use glium::framebuffer::{RenderTargetKey, RenderTargetPool};
+use glium::graph::RenderGraph;
+use glium::texture::UncompressedFloatFormat;
+use glium::uniforms::MagnifySamplerFilter;
+
+let pool = RenderTargetPool::new(&display);
+let key = RenderTargetKey::new(1024, 768, UncompressedFloatFormat::F16F16F16F16);
+
+let frame = display.draw();
+
+{
+    let mut graph = RenderGraph::new(&display);
+    let hdr = graph.create_target("hdr", key);
+    let blurred = graph.create_target("blurred", key);
+
+    graph.add_pass("scene", &[], &[hdr], |resources| {
+        let mut surface = resources.surface(hdr);
+        surface.clear_color(0.0, 0.0, 0.0, 1.0);
+        // draw the scene with the `scene` program
+        Ok(())
+    });
+
+    graph.add_pass("blur", &[hdr], &[blurred], |resources| {
+        // draw `resources.texture(hdr)` to `resources.surface(blurred)`
+        Ok(())
+    });
+
+    // writes no resource, so it is never removed
+    graph.add_pass("present", &[blurred], &[], |resources| {
+        resources.texture(blurred).unwrap().as_surface()
+                 .fill(&frame, MagnifySamplerFilter::Linear);
+        Ok(())
+    });
+
+    let report = graph.execute(&pool).unwrap();
+    println!("passes: {:?}", report.order);
+}
+
+frame.finish().unwrap();
+pool.end_frame();
+```
+
+Textures that outlive the graph, like a shadow map that is sampled by the rest of the frame,
+are added with `import`. The passes that write an imported texture are never removed.
+
+The content of a transient texture is undefined when the first pass that uses it starts, and
+must be cleared or entirely overwritten by this pass.
+
+This module is only available if you enable the `graph` feature of glium.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use framebuffer::{RenderTarget, RenderTargetKey, RenderTargetPool, SimpleFrameBuffer};
+use texture::{Texture2d, Texture2dMultisample, TextureCreationError};
+use version::{Api, Version};
+
+use gl;
+use ContextExt;
+use DrawError;
+use TextureExt;
+
+/// Identifies a resource of a `RenderGraph`.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct ResourceId(usize);
+
+enum Resource<'a> {
+    Transient(RenderTargetKey),
+    Imported(&'a Texture2d),
+}
+
+struct Pass<'a> {
+    name: String,
+    reads: Vec<ResourceId>,
+    writes: Vec<ResourceId>,
+    callback: Box<FnMut(&PassResources) -> Result<(), DrawError> + 'a>,
+}
+
+/// A list of passes and of the resources that they use.
+pub struct RenderGraph<'a> {
+    context: Rc<Context>,
+    resources: Vec<(String, Resource<'a>)>,
+    passes: Vec<Pass<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+    /// Builds an empty graph.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F) -> RenderGraph<'a> where F: Facade {
+        RenderGraph {
+            context: facade.get_context().clone(),
+            resources: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    /// Adds a texture that is borrowed from the pool while the passes that use it are running.
+    #[inline]
+    pub fn create_target<S>(&mut self, name: S, key: RenderTargetKey) -> ResourceId
+                            where S: Into<String>
+    {
+        self.resources.push((name.into(), Resource::Transient(key)));
+        ResourceId(self.resources.len() - 1)
+    }
+
+    /// Adds a texture that outlives the graph.
+    #[inline]
+    pub fn import<S>(&mut self, name: S, texture: &'a Texture2d) -> ResourceId
+                     where S: Into<String>
+    {
+        self.resources.push((name.into(), Resource::Imported(texture)));
+        ResourceId(self.resources.len() - 1)
+    }
+
+    /// Adds a pass. `callback` is called when the graph is executed, and can only access the
+    /// resources of `reads` and `writes`.
+    ///
+    /// A pass that both reads and writes a resource, for example to blend with its content,
+    /// must list it in both slices.
+    ///
+    /// # Panic
+    ///
+    /// Panics if one of the resources doesn't belong to this graph.
+    pub fn add_pass<S, F>(&mut self, name: S, reads: &[ResourceId], writes: &[ResourceId],
+                          callback: F)
+                          where S: Into<String>,
+                                F: FnMut(&PassResources) -> Result<(), DrawError> + 'a
+    {
+        for &ResourceId(id) in reads.iter().chain(writes.iter()) {
+            assert!(id < self.resources.len(), "The resource doesn't belong to this graph");
+        }
+
+        self.passes.push(Pass {
+            name: name.into(),
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Returns the number of passes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Returns true if the graph doesn't have any pass.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Returns the name of a resource.
+    #[inline]
+    pub fn resource_name(&self, id: ResourceId) -> &str {
+        &self.resources[id.0].0
+    }
+
+    /// Runs the passes, and borrows the transient textures from `pool`.
+    ///
+    /// Nothing is run if the passes can't be ordered. If a pass returns an error, the next
+    /// passes are not run.
+    pub fn execute(&mut self, pool: &RenderTargetPool) -> Result<ExecutionReport, GraphError> {
+        let schedule = {
+            let observable: Vec<bool> = self.resources.iter().map(|&(_, ref r)| match *r {
+                Resource::Imported(_) => true,
+                Resource::Transient(_) => false,
+            }).collect();
+
+            let accesses: Vec<(&[ResourceId], &[ResourceId])> = self.passes.iter()
+                .map(|p| (&p.reads[..], &p.writes[..])).collect();
+
+            match schedule(&accesses, &observable) {
+                Some(s) => s,
+                None => return Err(GraphError::Cycle),
+            }
+        };
+
+        let mut report = ExecutionReport {
+            order: schedule.order.iter().map(|&p| self.passes[p].name.clone()).collect(),
+            culled: schedule.culled.iter().map(|&p| self.passes[p].name.clone()).collect(),
+            allocations: 0,
+            barriers: 0,
+            invalidations: 0,
+        };
+
+        // index in `schedule.order` of the last pass that uses each resource
+        let mut last_use = vec![None; self.resources.len()];
+        for (position, &pass) in schedule.order.iter().enumerate() {
+            let pass = &self.passes[pass];
+            for &ResourceId(id) in pass.reads.iter().chain(pass.writes.iter()) {
+                last_use[id] = Some(position);
+            }
+        }
+
+        let mut targets: Vec<Option<RenderTarget>> = (0 .. self.resources.len())
+                                                          .map(|_| None).collect();
+        // resources written by a pass that hasn't been followed by a barrier yet
+        let mut pending_writes = vec![false; self.resources.len()];
+
+        for (position, &pass) in schedule.order.iter().enumerate() {
+            let pass = &mut self.passes[pass];
+
+            for &ResourceId(id) in pass.reads.iter().chain(pass.writes.iter()) {
+                if let Resource::Transient(key) = self.resources[id].1 {
+                    if targets[id].is_none() {
+                        targets[id] = Some(try!(pool.get(key)));
+                        report.allocations += 1;
+                    }
+                }
+            }
+
+            if pass.reads.iter().any(|&ResourceId(id)| pending_writes[id]) {
+                if memory_barrier(&self.context) {
+                    report.barriers += 1;
+                }
+
+                for &ResourceId(id) in &pass.reads {
+                    pending_writes[id] = false;
+                }
+            }
+
+            {
+                let mut attachments: Vec<Option<Attachment>> = (0 .. self.resources.len())
+                                                                    .map(|_| None).collect();
+                for &ResourceId(id) in pass.reads.iter().chain(pass.writes.iter()) {
+                    attachments[id] = Some(match self.resources[id].1 {
+                        Resource::Imported(texture) => Attachment::Regular(texture),
+                        Resource::Transient(_) => {
+                            let target = targets[id].as_ref().unwrap();
+                            match target.texture() {
+                                Some(texture) => Attachment::Regular(texture),
+                                None => Attachment::Multisample(target.multisample_texture()
+                                                                      .unwrap()),
+                            }
+                        },
+                    });
+                }
+
+                let resources = PassResources {
+                    attachments: attachments,
+                    writes: &pass.writes,
+                };
+
+                try!((pass.callback)(&resources));
+            }
+
+            for &ResourceId(id) in &pass.writes {
+                pending_writes[id] = true;
+            }
+
+            // returning the textures to the pool lets the next passes reuse them
+            for id in 0 .. self.resources.len() {
+                if last_use[id] != Some(position) {
+                    continue;
+                }
+
+                if let Some(target) = targets[id].take() {
+                    let invalidated = match (target.texture(), target.multisample_texture()) {
+                        (Some(t), _) => invalidate(&self.context, t.get_texture_id()),
+                        (_, Some(t)) => invalidate(&self.context, t.get_texture_id()),
+                        _ => false,
+                    };
+
+                    if invalidated {
+                        report.invalidations += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+enum Attachment<'r> {
+    Regular(&'r Texture2d),
+    Multisample(&'r Texture2dMultisample),
+}
+
+/// Gives a pass access to the resources that it has declared.
+pub struct PassResources<'r> {
+    attachments: Vec<Option<Attachment<'r>>>,
+    writes: &'r [ResourceId],
+}
+
+impl<'r> PassResources<'r> {
+    /// Returns the texture of a resource, or `None` if it is multisampled.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the pass hasn't declared the resource.
+    #[inline]
+    pub fn texture(&self, id: ResourceId) -> Option<&'r Texture2d> {
+        match *self.get(id) {
+            Attachment::Regular(texture) => Some(texture),
+            Attachment::Multisample(_) => None,
+        }
+    }
+
+    /// Returns the texture of a resource, or `None` if it isn't multisampled.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the pass hasn't declared the resource.
+    #[inline]
+    pub fn multisample_texture(&self, id: ResourceId) -> Option<&'r Texture2dMultisample> {
+        match *self.get(id) {
+            Attachment::Regular(_) => None,
+            Attachment::Multisample(texture) => Some(texture),
+        }
+    }
+
+    /// Starts drawing on a resource.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the pass hasn't declared that it writes the resource.
+    pub fn surface(&self, id: ResourceId) -> SimpleFrameBuffer<'r> {
+        assert!(self.writes.contains(&id), "The pass hasn't declared that it writes {:?}", id);
+
+        match *self.get(id) {
+            Attachment::Regular(texture) => texture.as_surface(),
+            Attachment::Multisample(texture) => texture.as_surface(),
+        }
+    }
+
+    fn get(&self, id: ResourceId) -> &Attachment<'r> {
+        match self.attachments.get(id.0) {
+            Some(&Some(ref attachment)) => attachment,
+            _ => panic!("The pass hasn't declared {:?}", id),
+        }
+    }
+}
+
+/// What happened during the execution of a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionReport {
+    /// The names of the passes that have been run, in order.
+    pub order: Vec<String>,
+
+    /// The names of the passes that have been removed because nothing used their result.
+    pub culled: Vec<String>,
+
+    /// The number of textures that have been borrowed from the pool.
+    pub allocations: usize,
+
+    /// The number of memory barriers that have been issued. Drawing to a texture is visible to
+    /// the next passes without a barrier, so they are only needed after a pass that has written
+    /// to an image unit.
+    pub barriers: usize,
+
+    /// The number of transient textures whose content has been invalidated. This is zero if
+    /// the backend doesn't support `glInvalidateTexImage`.
+    pub invalidations: usize,
+}
+
+/// Error that can happen when executing a graph.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum GraphError {
+    /// The passes can't be ordered, because some of them depend on each other.
+    Cycle,
+
+    /// Failed to create a transient texture.
+    TextureCreation(TextureCreationError),
+
+    /// One of the passes failed to draw.
+    Draw(DrawError),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for GraphError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            GraphError::Cycle => "The passes can't be ordered, because some of them depend on \
+                                  each other",
+            GraphError::TextureCreation(_) => "Failed to create a transient texture",
+            GraphError::Draw(_) => "One of the passes failed to draw",
+        }
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            GraphError::Cycle => None,
+            GraphError::TextureCreation(ref err) => Some(err),
+            GraphError::Draw(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for GraphError {
+    #[inline]
+    fn from(err: TextureCreationError) -> GraphError {
+        GraphError::TextureCreation(err)
+    }
+}
+
+impl From<DrawError> for GraphError {
+    #[inline]
+    fn from(err: DrawError) -> GraphError {
+        GraphError::Draw(err)
+    }
+}
+
+/// Indices of the passes to run, and of the passes to skip.
+#[derive(Debug, PartialEq, Eq)]
+struct Schedule {
+    order: Vec<usize>,
+    culled: Vec<usize>,
+}
+
+/// Orders the passes, given the resources that each one reads and writes. `observable`
+/// contains the resources whose content is used after the graph.
+///
+/// Returns `None` if there is a cycle.
+fn schedule(passes: &[(&[ResourceId], &[ResourceId])], observable: &[bool])
+            -> Option<Schedule>
+{
+    // `dependencies[p]` contains the passes that must run before `p`: the passes that have been
+    // added before `p` and write the same resources, and all the writers of the resources that
+    // `p` only reads
+    let dependencies: Vec<Vec<usize>> = passes.iter().enumerate().map(|(p, &(reads, writes))| {
+        passes.iter().enumerate()
+              .filter(|&(q, &(_, other_writes))| {
+                  q != p && other_writes.iter().any(|r| {
+                      if writes.contains(r) { q < p } else { reads.contains(r) }
+                  })
+              })
+              .map(|(q, _)| q)
+              .collect()
+    }).collect();
+
+    // passes that write nothing are run for their side effects
+    let mut needed: Vec<bool> = passes.iter().map(|&(_, writes)| {
+        writes.is_empty() || writes.iter().any(|&ResourceId(id)| observable[id])
+    }).collect();
+
+    let mut stack: Vec<usize> = (0 .. passes.len()).filter(|&p| needed[p]).collect();
+    while let Some(p) = stack.pop() {
+        for &q in &dependencies[p] {
+            if !needed[q] {
+                needed[q] = true;
+                stack.push(q);
+            }
+        }
+    }
+
+    // among the passes whose dependencies have run, the one that has been added first runs next
+    let mut order = Vec::with_capacity(passes.len());
+    let mut done = vec![false; passes.len()];
+
+    loop {
+        let next = (0 .. passes.len()).find(|&p| {
+            needed[p] && !done[p] && dependencies[p].iter().all(|&q| done[q])
+        });
+
+        match next {
+            Some(p) => {
+                done[p] = true;
+                order.push(p);
+            },
+            None => break,
+        }
+    }
+
+    if order.len() != needed.iter().filter(|&&n| n).count() {
+        return None;
+    }
+
+    Some(Schedule {
+        order: order,
+        culled: (0 .. passes.len()).filter(|&p| !needed[p]).collect(),
+    })
+}
+
+/// Makes the writes through image units visible to texture fetches and framebuffers. Returns
+/// true if a barrier has been issued.
+fn memory_barrier(context: &Context) -> bool {
+    let mut ctxt = context.make_current();
+
+    if ctxt.state.latest_image_write < ctxt.state.latest_memory_barrier_texture_fetch &&
+       ctxt.state.latest_image_write < ctxt.state.latest_memory_barrier_framebuffer
+    {
+        return false;
+    }
+
+    unsafe {
+        ctxt.gl.MemoryBarrier(gl::TEXTURE_FETCH_BARRIER_BIT | gl::FRAMEBUFFER_BARRIER_BIT);
+    }
+
+    ctxt.state.latest_memory_barrier_texture_fetch = ctxt.state.next_draw_call_id;
+    ctxt.state.latest_memory_barrier_framebuffer = ctxt.state.next_draw_call_id;
+    true
+}
+
+/// Invalidates the content of the first mipmap of a texture. Returns false if this isn't
+/// supported.
+fn invalidate(context: &Context, texture: gl::types::GLuint) -> bool {
+    let ctxt = context.make_current();
+
+    if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.extensions.gl_arb_invalidate_subdata {
+        unsafe { ctxt.gl.InvalidateTexImage(texture, 0); }
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{schedule, ResourceId, Schedule};
+
+    #[test]
+    fn order_and_culling() {
+        let (a, b, c, imported) = (ResourceId(0), ResourceId(1), ResourceId(2), ResourceId(3));
+        let observable = [false, false, false, true];
+
+        let passes: [(&[ResourceId], &[ResourceId]); 5] = [
+            (&[b], &[]),            // 0: presents `b`
+            (&[a], &[b]),           // 1: blurs `a` into `b`
+            (&[], &[a]),            // 2: draws the scene to `a`
+            (&[a], &[c]),           // 3: nobody reads `c`
+            (&[], &[imported]),     // 4: draws a shadow map
+        ];
+
+        assert_eq!(schedule(&passes, &observable), Some(Schedule {
+            order: vec![2, 1, 0, 4],
+            culled: vec![3],
+        }));
+    }
+
+    #[test]
+    fn writers_keep_their_order() {
+        let a = ResourceId(0);
+        let passes: [(&[ResourceId], &[ResourceId]); 3] = [
+            (&[a], &[]),
+            (&[], &[a]),
+            (&[a], &[a]),
+        ];
+
+        // the pass that blends with `a` runs after the pass that clears it, and both run before
+        // the pass that reads the result
+        assert_eq!(schedule(&passes, &[false]).unwrap().order, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn cycle() {
+        let (a, b) = (ResourceId(0), ResourceId(1));
+        let passes: [(&[ResourceId], &[ResourceId]); 3] = [
+            (&[a], &[b]),
+            (&[b], &[a]),
+            (&[a, b], &[]),
+        ];
+
+        assert_eq!(schedule(&passes, &[false, false]), None);
+    }
+}
@@ -0,0 +1,498 @@
+/*!
+Builds the textures of image-based lighting.
+
+Physically-based renderers light their objects with an environment map, which is usually
+distributed as an HDR image in the equirectangular projection. Before it can be used, it must be
+turned into the following textures, which is done on the GPU by an `IblBaker`:
+
+ - A cubemap, which is easier and faster to sample than the equirectangular image.
+ - An irradiance cubemap, which contains the diffuse light that reaches a surface depending on
+   its normal.
+ - A prefiltered cubemap, whose mipmaps contain the specular reflections of increasingly rough
+   surfaces. The roughness of the mipmap `level` is `level / (levels - 1)`.
+ - A lookup table of the scale and bias to apply to the specular color, depending on the cosine
+   of the view angle and the roughness. It doesn't depend on the environment, so it only needs
+   to be built once.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let equirectangular: glium::Texture2d = unsafe { ::std::mem::uninitialized() };
+use glium::ibl::IblBaker;
+use glium::texture::UncompressedFloatFormat;
+
+let baker = IblBaker::new(&display).unwrap();
+let format = UncompressedFloatFormat::F16F16F16F16;
+
+let environment = baker.equirectangular_to_cubemap(&equirectangular, 512, format).unwrap();
+let irradiance = baker.irradiance(&environment, 32, format).unwrap();
+let specular = baker.prefilter(&environment, 128, 5, format).unwrap();
+let brdf = baker.brdf_lut(256).unwrap();
+```
+
+The irradiance map is divided by π, so that the diffuse light of a surface is its albedo
+multiplied by the value read from the map. The prefiltered map and the lookup table follow the
+split-sum approximation of the GGX distribution.
+
+Each face of the cubemaps is drawn by a separate pass, because the framebuffers of glium can't
+be layered. The programs require OpenGL 3.1.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use effects::{self, FullscreenTriangle};
+use framebuffer::{SimpleFrameBuffer, ValidationError};
+use program::ProgramChooserCreationError;
+use texture::{CubeLayer, Cubemap, MipmapsOption, Texture2d, TextureCreationError};
+use texture::UncompressedFloatFormat;
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerWrapFunction};
+use vertex;
+
+use gl;
+use ContextExt;
+use DrawError;
+use Program;
+use TextureExt;
+
+/// The faces of a cubemap, in the order of the `face` uniform of the programs.
+const FACES: [CubeLayer; 6] = [CubeLayer::PositiveX, CubeLayer::NegativeX,
+                               CubeLayer::PositiveY, CubeLayer::NegativeY,
+                               CubeLayer::PositiveZ, CubeLayer::NegativeZ];
+
+/// Returns the direction that corresponds to the texture coordinates `v_tex_coords` of the face
+/// `face` of a cubemap.
+const DIRECTION_FUNCTION: &'static str = "
+    uniform int face;
+    in vec2 v_tex_coords;
+
+    vec3 direction() {
+        vec2 p = v_tex_coords * 2.0 - 1.0;
+
+        if (face == 0) { return normalize(vec3(1.0, -p.y, -p.x)); }
+        if (face == 1) { return normalize(vec3(-1.0, -p.y, p.x)); }
+        if (face == 2) { return normalize(vec3(p.x, 1.0, p.y)); }
+        if (face == 3) { return normalize(vec3(p.x, -1.0, -p.y)); }
+        if (face == 4) { return normalize(vec3(p.x, -p.y, 1.0)); }
+        return normalize(vec3(-p.x, -p.y, -1.0));
+    }
+";
+
+/// Functions that are shared by the programs that integrate the GGX distribution.
+const GGX_FUNCTIONS: &'static str = "
+    const float PI = 3.14159265359;
+
+    vec2 hammersley(uint i, uint count) {
+        uint bits = i;
+        bits = (bits << 16u) | (bits >> 16u);
+        bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+        bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+        bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+        bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+        return vec2(float(i) / float(count), float(bits) * 2.3283064365386963e-10);
+    }
+
+    vec3 importance_sample_ggx(vec2 xi, vec3 n, float roughness) {
+        float a = roughness * roughness;
+        float phi = 2.0 * PI * xi.x;
+        float cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+        float sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+        vec3 h = vec3(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+        vec3 up = abs(n.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+        vec3 tangent = normalize(cross(up, n));
+        vec3 bitangent = cross(n, tangent);
+        return normalize(tangent * h.x + bitangent * h.y + n * h.z);
+    }
+";
+
+const EQUIRECTANGULAR_SHADER: &'static str = "
+    uniform sampler2D source;
+    out vec4 color;
+
+    void main() {
+        vec3 dir = direction();
+        vec2 uv = vec2(atan(dir.z, dir.x) / 6.28318530718 + 0.5,
+                       asin(dir.y) / 3.14159265359 + 0.5);
+        color = vec4(textureLod(source, uv, 0.0).rgb, 1.0);
+    }
+";
+
+const IRRADIANCE_SHADER: &'static str = "
+    uniform samplerCube source;
+    uniform float source_lod;
+    out vec4 color;
+
+    const float PI = 3.14159265359;
+    const float DELTA = 0.025;
+
+    void main() {
+        vec3 n = direction();
+        vec3 up = abs(n.y) < 0.999 ? vec3(0.0, 1.0, 0.0) : vec3(0.0, 0.0, 1.0);
+        vec3 right = normalize(cross(up, n));
+        up = cross(n, right);
+
+        vec3 sum = vec3(0.0);
+        float count = 0.0;
+
+        for (float phi = 0.0; phi < 2.0 * PI; phi += DELTA) {
+            for (float theta = 0.0; theta < 0.5 * PI; theta += DELTA) {
+                vec3 tangent = vec3(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+                vec3 dir = tangent.x * right + tangent.y * up + tangent.z * n;
+                sum += textureLod(source, dir, source_lod).rgb * cos(theta) * sin(theta);
+                count += 1.0;
+            }
+        }
+
+        color = vec4(PI * sum / count, 1.0);
+    }
+";
+
+const PREFILTER_SHADER: &'static str = "
+    uniform samplerCube source;
+    uniform float source_size;
+    uniform float roughness;
+    out vec4 color;
+
+    const uint SAMPLES = 512u;
+
+    void main() {
+        vec3 n = direction();
+
+        if (roughness == 0.0) {
+            color = vec4(textureLod(source, n, 0.0).rgb, 1.0);
+            return;
+        }
+
+        float a2 = roughness * roughness * roughness * roughness;
+        float texel_solid_angle = 4.0 * PI / (6.0 * source_size * source_size);
+
+        vec3 sum = vec3(0.0);
+        float weight = 0.0;
+
+        for (uint i = 0u; i < SAMPLES; ++i) {
+            vec3 h = importance_sample_ggx(hammersley(i, SAMPLES), n, roughness);
+            vec3 l = normalize(2.0 * dot(n, h) * h - n);
+            float n_dot_l = dot(n, l);
+
+            if (n_dot_l > 0.0) {
+                // reading a lower mipmap when the samples are sparse removes the aliasing
+                float n_dot_h = max(dot(n, h), 0.0);
+                float d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+                float pdf = a2 / (PI * d * d) / 4.0;
+                float sample_solid_angle = 1.0 / (float(SAMPLES) * pdf + 0.0001);
+                float lod = 0.5 * log2(sample_solid_angle / texel_solid_angle);
+
+                sum += textureLod(source, l, max(lod, 0.0)).rgb * n_dot_l;
+                weight += n_dot_l;
+            }
+        }
+
+        color = vec4(sum / weight, 1.0);
+    }
+";
+
+const BRDF_SHADER: &'static str = "
+    in vec2 v_tex_coords;
+    out vec4 color;
+
+    const uint SAMPLES = 1024u;
+
+    void main() {
+        float n_dot_v = max(v_tex_coords.x, 0.0001);
+        float roughness = v_tex_coords.y;
+
+        vec3 v = vec3(sqrt(1.0 - n_dot_v * n_dot_v), 0.0, n_dot_v);
+        vec3 n = vec3(0.0, 0.0, 1.0);
+
+        // geometry term of Schlick-GGX, with the remapping of image-based lighting
+        float k = roughness * roughness / 2.0;
+        float g_v = n_dot_v / (n_dot_v * (1.0 - k) + k);
+
+        float scale = 0.0;
+        float bias = 0.0;
+
+        for (uint i = 0u; i < SAMPLES; ++i) {
+            vec3 h = importance_sample_ggx(hammersley(i, SAMPLES), n, roughness);
+            vec3 l = normalize(2.0 * dot(v, h) * h - v);
+
+            float n_dot_l = max(l.z, 0.0);
+            float n_dot_h = max(h.z, 0.0);
+            float v_dot_h = max(dot(v, h), 0.0);
+
+            if (n_dot_l > 0.0) {
+                float g = g_v * n_dot_l / (n_dot_l * (1.0 - k) + k);
+                float g_vis = g * v_dot_h / (n_dot_h * n_dot_v);
+                float fc = pow(1.0 - v_dot_h, 5.0);
+
+                scale += (1.0 - fc) * g_vis;
+                bias += fc * g_vis;
+            }
+        }
+
+        color = vec4(scale / float(SAMPLES), bias / float(SAMPLES), 0.0, 1.0);
+    }
+";
+
+/// Holds the programs that build the textures of image-based lighting.
+pub struct IblBaker {
+    context: Rc<Context>,
+    fullscreen: FullscreenTriangle,
+    equirectangular: Program,
+    irradiance: Program,
+    prefilter: Program,
+    brdf: Program,
+}
+
+impl IblBaker {
+    /// Compiles the programs.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<IblBaker, IblError> where F: Facade {
+        let build = |body: &[&str]| {
+            let fragment = format!("#version 140\n{}", body.concat());
+            program!(facade, 140 => { vertex: effects::VERTEX_SHADER, fragment: &fragment })
+        };
+
+        Ok(IblBaker {
+            context: facade.get_context().clone(),
+            fullscreen: try!(FullscreenTriangle::new(facade)),
+            equirectangular: try!(build(&[DIRECTION_FUNCTION, EQUIRECTANGULAR_SHADER])),
+            irradiance: try!(build(&[DIRECTION_FUNCTION, IRRADIANCE_SHADER])),
+            prefilter: try!(build(&[DIRECTION_FUNCTION, GGX_FUNCTIONS, PREFILTER_SHADER])),
+            brdf: try!(build(&[GGX_FUNCTIONS, BRDF_SHADER])),
+        })
+    }
+
+    /// Projects an image in the equirectangular projection on a cubemap whose faces are
+    /// `size` pixels wide, and generates its mipmaps.
+    ///
+    /// The first row of `source` must be the bottom of the image, which is the case of the
+    /// textures that are built with `Texture2d::from_image`.
+    pub fn equirectangular_to_cubemap(&self, source: &Texture2d, size: u32,
+                                      format: UncompressedFloatFormat)
+                                      -> Result<Cubemap, IblError>
+    {
+        let cubemap = try!(Cubemap::empty_with_format(&self.context, format,
+                                                      MipmapsOption::EmptyMipmaps, size));
+
+        // the image wraps around horizontally, but not between the poles
+        let mut sampler = Sampler::new(source)
+                            .minify_filter(MinifySamplerFilter::Linear)
+                            .magnify_filter(MagnifySamplerFilter::Linear);
+        sampler.1.wrap_function = (SamplerWrapFunction::Repeat, SamplerWrapFunction::Clamp,
+                                   SamplerWrapFunction::Clamp);
+
+        for (index, &layer) in FACES.iter().enumerate() {
+            let mut framebuffer = try!(SimpleFrameBuffer::new(&self.context,
+                                                              cubemap.main_level().image(layer)));
+            let uniforms = uniform! { source: sampler, face: index as i32 };
+            try!(self.fullscreen.draw(&mut framebuffer, &self.equirectangular, &uniforms,
+                                      &Default::default()));
+        }
+
+        generate_mipmaps(&cubemap);
+        Ok(cubemap)
+    }
+
+    /// Builds a cubemap whose faces are `size` pixels wide, and that contains the irradiance
+    /// of `source` divided by π for each normal.
+    ///
+    /// The irradiance varies slowly, and `32` is usually enough for `size`. If `source` has
+    /// mipmaps, a lower mipmap is read to reduce the noise.
+    pub fn irradiance(&self, source: &Cubemap, size: u32, format: UncompressedFloatFormat)
+                      -> Result<Cubemap, IblError>
+    {
+        let cubemap = try!(Cubemap::empty_with_format(&self.context, format,
+                                                      MipmapsOption::NoMipmap, size));
+
+        // the samples of each pixel are 0.025 radians apart, which is about the size of a texel
+        // of a face that is 64 pixels wide
+        let max_lod = source.get_mipmap_levels().saturating_sub(1) as f32;
+        let source_lod = (source.get_width() as f32 / 64.0).log2().max(0.0).min(max_lod);
+
+        for (index, &layer) in FACES.iter().enumerate() {
+            let mut framebuffer = try!(SimpleFrameBuffer::new(&self.context,
+                                                              cubemap.main_level().image(layer)));
+            let uniforms = uniform! {
+                source: cube_sampler(source),
+                source_lod: source_lod,
+                face: index as i32,
+            };
+            try!(self.fullscreen.draw(&mut framebuffer, &self.irradiance, &uniforms,
+                                      &Default::default()));
+        }
+
+        Ok(cubemap)
+    }
+
+    /// Builds a cubemap whose faces are `size` pixels wide and that has `levels` mipmaps. Each
+    /// mipmap contains the light reflected by a surface whose roughness is `level / (levels - 1)`.
+    ///
+    /// `source` should have mipmaps, as they are read to remove the aliasing of the rough
+    /// reflections.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `levels` is `0`, or if it is greater than the number of mipmaps of a cubemap
+    /// of this size.
+    pub fn prefilter(&self, source: &Cubemap, size: u32, levels: u32,
+                     format: UncompressedFloatFormat) -> Result<Cubemap, IblError>
+    {
+        assert!(levels >= 1);
+        let cubemap = try!(Cubemap::empty_with_format(&self.context, format,
+                                                      MipmapsOption::EmptyMipmapsMax(levels - 1),
+                                                      size));
+        assert!(cubemap.get_mipmap_levels() >= levels);
+
+        for level in 0 .. levels {
+            let roughness = if levels == 1 { 0.0 } else { level as f32 / (levels - 1) as f32 };
+            let mipmap = cubemap.mipmap(level).unwrap();
+
+            for (index, &layer) in FACES.iter().enumerate() {
+                let mut framebuffer = try!(SimpleFrameBuffer::new(&self.context,
+                                                                  mipmap.image(layer)));
+                let uniforms = uniform! {
+                    source: cube_sampler(source),
+                    source_size: source.get_width() as f32,
+                    roughness: roughness,
+                    face: index as i32,
+                };
+                try!(self.fullscreen.draw(&mut framebuffer, &self.prefilter, &uniforms,
+                                          &Default::default()));
+            }
+        }
+
+        Ok(cubemap)
+    }
+
+    /// Builds the lookup table of the split-sum approximation, `size` pixels wide and high.
+    ///
+    /// The red and green components are the scale and the bias to apply to the specular color.
+    /// The horizontal axis is the cosine of the angle between the normal and the view
+    /// direction, and the vertical axis is the roughness.
+    pub fn brdf_lut(&self, size: u32) -> Result<Texture2d, IblError> {
+        let texture = try!(Texture2d::empty_with_format(&self.context,
+                                                        UncompressedFloatFormat::F16F16,
+                                                        MipmapsOption::NoMipmap, size, size));
+
+        {
+            let mut framebuffer = try!(SimpleFrameBuffer::new(&self.context, &texture));
+            try!(self.fullscreen.draw(&mut framebuffer, &self.brdf, &uniform! {},
+                                      &Default::default()));
+        }
+
+        Ok(texture)
+    }
+}
+
+/// Returns a sampler that reads every mipmap of a cubemap.
+fn cube_sampler(cubemap: &Cubemap) -> Sampler<Cubemap> {
+    let minify_filter = if cubemap.get_mipmap_levels() > 1 {
+        MinifySamplerFilter::LinearMipmapLinear
+    } else {
+        MinifySamplerFilter::Linear
+    };
+
+    Sampler::new(cubemap)
+        .wrap_function(SamplerWrapFunction::Clamp)
+        .minify_filter(minify_filter)
+        .magnify_filter(MagnifySamplerFilter::Linear)
+}
+
+/// Generates the mipmaps of a cubemap from its main level.
+fn generate_mipmaps(cubemap: &Cubemap) {
+    let mut ctxt = cubemap.get_context().make_current();
+
+    unsafe {
+        let bind_point = cubemap.bind_to_current(&mut ctxt);
+        debug_assert_eq!(bind_point, gl::TEXTURE_CUBE_MAP);
+        ctxt.gl.GenerateMipmap(bind_point);
+    }
+}
+
+/// Error that can happen when building the textures of image-based lighting.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum IblError {
+    /// Failed to compile one of the programs.
+    ProgramCreation(ProgramChooserCreationError),
+
+    /// Failed to upload the triangle that covers the faces.
+    BufferCreation(vertex::BufferCreationError),
+
+    /// Failed to create a texture.
+    TextureCreation(TextureCreationError),
+
+    /// Failed to build a framebuffer that draws to a texture.
+    FramebufferCreation(ValidationError),
+
+    /// Failed to draw one of the faces.
+    Draw(DrawError),
+}
+
+impl fmt::Display for IblError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for IblError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            IblError::ProgramCreation(_) => "Failed to compile one of the programs",
+            IblError::BufferCreation(_) => "Failed to upload the triangle that covers the faces",
+            IblError::TextureCreation(_) => "Failed to create a texture",
+            IblError::FramebufferCreation(_) =>
+                "Failed to build a framebuffer that draws to a texture",
+            IblError::Draw(_) => "Failed to draw one of the faces",
+        }
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            IblError::ProgramCreation(ref err) => Some(err),
+            IblError::BufferCreation(ref err) => Some(err),
+            IblError::TextureCreation(ref err) => Some(err),
+            IblError::FramebufferCreation(ref err) => Some(err),
+            IblError::Draw(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramChooserCreationError> for IblError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> IblError {
+        IblError::ProgramCreation(err)
+    }
+}
+
+impl From<vertex::BufferCreationError> for IblError {
+    #[inline]
+    fn from(err: vertex::BufferCreationError) -> IblError {
+        IblError::BufferCreation(err)
+    }
+}
+
+impl From<TextureCreationError> for IblError {
+    #[inline]
+    fn from(err: TextureCreationError) -> IblError {
+        IblError::TextureCreation(err)
+    }
+}
+
+impl From<ValidationError> for IblError {
+    #[inline]
+    fn from(err: ValidationError) -> IblError {
+        IblError::FramebufferCreation(err)
+    }
+}
+
+impl From<DrawError> for IblError {
+    #[inline]
+    fn from(err: DrawError) -> IblError {
+        IblError::Draw(err)
+    }
+}
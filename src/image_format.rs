@@ -1098,16 +1098,20 @@ impl CompressedFormat {
                 version >= &Version(Api::Gl, 4, 2) || extensions.gl_arb_texture_compression_bptc
             },
             &CompressedFormat::S3tcDxt1NoAlpha => {
-                extensions.gl_ext_texture_compression_s3tc
+                extensions.gl_ext_texture_compression_s3tc ||
+                extensions.gl_ext_texture_compression_dxt1
             },
             &CompressedFormat::S3tcDxt1Alpha => {
-                extensions.gl_ext_texture_compression_s3tc
+                extensions.gl_ext_texture_compression_s3tc ||
+                extensions.gl_ext_texture_compression_dxt1
             },
             &CompressedFormat::S3tcDxt3Alpha => {
-                extensions.gl_ext_texture_compression_s3tc
+                extensions.gl_ext_texture_compression_s3tc ||
+                extensions.gl_angle_texture_compression_dxt3
             },
             &CompressedFormat::S3tcDxt5Alpha => {
-                extensions.gl_ext_texture_compression_s3tc
+                extensions.gl_ext_texture_compression_s3tc ||
+                extensions.gl_angle_texture_compression_dxt5
             },
         }
     }
@@ -1480,6 +1484,99 @@ impl TextureFormat {
             &TextureFormat::DepthStencilFormat(_) => true,
         }
     }
+
+    /// Returns the number of bits used by each texel of this format.
+    ///
+    /// For compressed formats, this is the average number of bits per texel. The actual format
+    /// chosen by the backend may use more bits, for example for padding.
+    pub fn get_total_bits(&self) -> usize {
+        match *self {
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8) => 8,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8) => 8,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16) => 16,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16) => 16,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8) => 16,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8I8) => 16,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16U16) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16I16) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U3U3U2) => 8,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U4U4U4) => 12,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U5U5U5) => 15,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8) => 24,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8I8I8) => 24,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U10U10U10) => 30,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U12U12U12) => 36,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16U16U16) => 48,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16I16I16) => 48,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U2U2U2U2) => 8,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U4U4U4U4) => 16,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U5U5U5U1) => 16,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8U8) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8I8I8I8) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U10U10U10U2) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U12U12U12U12) => 48,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::U16U16U16U16) => 64,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::I16I16I16I16) => 64,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16) => 16,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16F16) => 48,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16F16F16) => 64,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32F32) => 64,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32F32F32) => 96,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32F32F32F32) => 128,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F11F11F10) => 32,
+            TextureFormat::UncompressedFloat(UncompressedFloatFormat::F9F9F9) => 32,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8) => 8,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16) => 16,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32) => 32,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8I8) => 16,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16I16) => 32,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32I32) => 64,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8I8I8) => 24,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16I16I16) => 48,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32I32I32) => 96,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I8I8I8I8) => 32,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I16I16I16I16) => 64,
+            TextureFormat::UncompressedIntegral(UncompressedIntFormat::I32I32I32I32) => 128,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8) => 8,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16) => 16,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32) => 32,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8U8) => 16,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16U16) => 32,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32U32) => 64,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8U8U8) => 24,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16U16U16) => 48,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32U32U32) => 96,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U8U8U8U8) => 32,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U16U16U16U16) => 64,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U32U32U32U32) => 128,
+            TextureFormat::UncompressedUnsigned(UncompressedUintFormat::U10U10U10U2) => 32,
+            TextureFormat::Srgb(SrgbFormat::U8U8U8) => 24,
+            TextureFormat::Srgb(SrgbFormat::U8U8U8U8) => 32,
+            TextureFormat::DepthFormat(DepthFormat::I16) => 16,
+            TextureFormat::DepthFormat(DepthFormat::I24) => 24,
+            TextureFormat::DepthFormat(DepthFormat::I32) => 32,
+            TextureFormat::DepthFormat(DepthFormat::F32) => 32,
+            TextureFormat::StencilFormat(StencilFormat::I1) => 1,
+            TextureFormat::StencilFormat(StencilFormat::I4) => 4,
+            TextureFormat::StencilFormat(StencilFormat::I8) => 8,
+            TextureFormat::StencilFormat(StencilFormat::I16) => 16,
+            TextureFormat::DepthStencilFormat(DepthStencilFormat::I24I8) => 32,
+            TextureFormat::DepthStencilFormat(DepthStencilFormat::F32I8) => 40,
+
+            // 8 bytes per 4x4 block
+            TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatU) |
+            TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatI) |
+            TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha) |
+            TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt1Alpha) |
+            TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1NoAlpha) |
+            TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1Alpha) => 4,
+
+            // 16 bytes per 4x4 block
+            TextureFormat::CompressedFormat(_) | TextureFormat::CompressedSrgbFormat(_) => 8,
+        }
+    }
 }
 
 impl ToGlEnum for TextureFormat {
@@ -1974,7 +2071,7 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
                 }
 
             } else if version >= &Version(Api::Gl, 1, 4) || extensions.gl_arb_depth_texture ||
-                      extensions.gl_oes_depth_texture
+                      extensions.gl_oes_depth_texture || extensions.gl_angle_depth_texture
             {
                 match rq_ty {
                     RequestType::TexImage(_) => gl::DEPTH_COMPONENT,
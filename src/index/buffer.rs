@@ -17,6 +17,7 @@ use utils::range::RangeArgument;
 
 /// Error that can happen while creating an index buffer.
 #[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
 pub enum CreationError {
     /// The type of index is not supported by the backend.
     IndexTypeNotSupported,
@@ -47,7 +48,7 @@ impl Error for CreationError {
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref err) => Some(err),
@@ -201,6 +202,7 @@ impl<T> IndexBuffer<T> where T: Index {
             IndexBufferSlice {
                 buffer: b,
                 primitives: self.primitives,
+                vertices_range: None,
             }
         })
     }
@@ -255,6 +257,7 @@ impl<'a, T> From<&'a IndexBuffer<T>> for IndicesSource<'a> where T: Index {
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.get_indices_type(),
             primitives: buf.primitives,
+            vertices_range: None,
         }
     }
 }
@@ -264,6 +267,7 @@ impl<'a, T> From<&'a IndexBuffer<T>> for IndicesSource<'a> where T: Index {
 pub struct IndexBufferSlice<'a, T: 'a> where T: Index {
     buffer: BufferSlice<'a, [T]>,
     primitives: PrimitiveType,
+    vertices_range: Option<(u32, u32)>,
 }
 
 impl<'a, T: 'a> IndexBufferSlice<'a, T> where T: Index {
@@ -280,15 +284,41 @@ impl<'a, T: 'a> IndexBufferSlice<'a, T> where T: Index {
     }
 
     /// Returns `None` if out of range.
+    ///
+    /// The slice keeps the range of vertices passed to `vertices_range`, if any.
     #[inline]
     pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<IndexBufferSlice<'a, T>> {
         self.buffer.slice(range).map(|b| {
             IndexBufferSlice {
                 buffer: b,
                 primitives: self.primitives,
+                vertices_range: self.vertices_range,
             }
         })
     }
+
+    /// Indicates that the indices of this slice are between `min` and `max` (inclusive).
+    ///
+    /// When drawing, this range is passed to `glDrawRangeElements`, which allows the driver to
+    /// only process the vertices that are used. This is useful when the indices of several meshes
+    /// are packed in the same buffer. Indices that are outside of this range produce
+    /// implementation-dependent results.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `min` is greater than `max`.
+    #[inline]
+    pub fn vertices_range(mut self, min: u32, max: u32) -> IndexBufferSlice<'a, T> {
+        assert!(min <= max);
+        self.vertices_range = Some((min, max));
+        self
+    }
+
+    /// Returns the range of vertices passed to `vertices_range`, if any.
+    #[inline]
+    pub fn get_vertices_range(&self) -> Option<(u32, u32)> {
+        self.vertices_range
+    }
 }
 
 impl<'a, T> Deref for IndexBufferSlice<'a, T> where T: Index {
@@ -321,6 +351,7 @@ impl<'a, T> From<IndexBufferSlice<'a, T>> for IndicesSource<'a> where T: Index {
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.get_indices_type(),
             primitives: buf.primitives,
+            vertices_range: buf.vertices_range,
         }
     }
 }
@@ -332,6 +363,7 @@ impl<'a, 'r, T> From<&'r IndexBufferSlice<'a, T>> for IndicesSource<'a> where T:
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.get_indices_type(),
             primitives: buf.primitives,
+            vertices_range: buf.vertices_range,
         }
     }
 }
@@ -346,7 +378,54 @@ pub struct IndexBufferAny {
     data_type: IndexType,
 }
 
+impl GlObject for IndexBufferAny {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.buffer.get_id()
+    }
+}
+
 impl IndexBufferAny {
+    /// Builds a new index buffer from a list of `u8` indices and a primitive type.
+    ///
+    /// If the backend doesn't handle `u8` indices efficiently (see `IndexType::is_efficient`),
+    /// the indices are converted to `u16` before being uploaded. Use `get_indices_type` to know
+    /// which type was chosen.
+    ///
+    /// When the indices are converted, the primitive restart index `0xff` becomes `0xffff`.
+    pub fn from_u8<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[u8])
+                              -> Result<IndexBufferAny, CreationError>
+                              where F: Facade
+    {
+        if IndexType::U8.is_efficient(facade) {
+            return Ok(try!(IndexBuffer::new(facade, prim, data)).into());
+        }
+
+        Ok(try!(IndexBuffer::new(facade, prim, &widen_to_u16(data))).into())
+    }
+
+    /// Builds a new index buffer from a list of `u32` indices and a primitive type.
+    ///
+    /// If all the indices fit in 16 bits, they are converted to `u16` before being uploaded,
+    /// which halves the size of the buffer. Use `get_indices_type` to know which type was
+    /// chosen. If you always want `u32` indices, for example because you will write larger
+    /// indices in the buffer later, use `IndexBuffer::<u32>::new` instead.
+    ///
+    /// The primitive restart index `0xffffffff` becomes `0xffff`, and the indices are kept as
+    /// `u32` if one of them is equal to `0xffff`, since this value would then be interpreted as
+    /// a primitive restart.
+    pub fn from_u32<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[u32])
+                               -> Result<IndexBufferAny, CreationError>
+                               where F: Facade
+    {
+        match narrow_to_u16(data) {
+            Some(data) => Ok(try!(IndexBuffer::new(facade, prim, &data)).into()),
+            None => Ok(try!(IndexBuffer::new(facade, prim, data)).into()),
+        }
+    }
+
     /// Returns the type of primitives associated with this index buffer.
     #[inline]
     pub fn get_primitives_type(&self) -> PrimitiveType {
@@ -360,6 +439,26 @@ impl IndexBufferAny {
     }
 }
 
+/// Converts `u8` indices to `u16`.
+fn widen_to_u16(data: &[u8]) -> Vec<u16> {
+    data.iter().map(|&index| if index == 0xff { 0xffff } else { index as u16 }).collect()
+}
+
+/// Converts `u32` indices to `u16`, or returns `None` if one of them doesn't fit.
+fn narrow_to_u16(data: &[u32]) -> Option<Vec<u16>> {
+    let mut result = Vec::with_capacity(data.len());
+
+    for &index in data {
+        result.push(match index {
+            0xffffffff => 0xffff,
+            index if index < 0xffff => index as u16,
+            _ => return None,
+        });
+    }
+
+    Some(result)
+}
+
 impl Deref for IndexBufferAny {
     type Target = BufferAny;
 
@@ -396,6 +495,38 @@ impl<'a> From<&'a IndexBufferAny> for IndicesSource<'a> {
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.data_type,
             primitives: buf.primitives,
+            vertices_range: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use buffer::{BufferCreationError, BufferType};
+    use super::{narrow_to_u16, widen_to_u16, CreationError};
+
+    #[test]
+    fn widening() {
+        assert_eq!(widen_to_u16(&[0, 1, 254]), vec![0, 1, 254]);
+        assert_eq!(widen_to_u16(&[0, 0xff, 2]), vec![0, 0xffff, 2]);
+    }
+
+    #[test]
+    fn narrowing() {
+        assert_eq!(narrow_to_u16(&[0, 1, 65534]), Some(vec![0, 1, 65534]));
+        assert_eq!(narrow_to_u16(&[0, 0xffffffff, 2]), Some(vec![0, 0xffff, 2]));
+        assert_eq!(narrow_to_u16(&[0, 0xffff]), None);
+        assert_eq!(narrow_to_u16(&[0, 70000]), None);
+    }
+
+    #[test]
+    fn error_source() {
+        let err = BufferCreationError::BufferTypeNotSupported(BufferType::ElementArrayBuffer);
+        let err = CreationError::BufferCreationError(err);
+
+        let source = err.source().unwrap().to_string();
+        assert_eq!(source, "This type of buffer is not supported: ElementArrayBuffer (0x8893)");
+        assert!(err.source().unwrap().source().is_none());
+    }
+}
@@ -18,10 +18,31 @@ There are eleven types of primitives, each one with a corresponding struct:
 
 There are two ways to specify the indices that must be used:
 
- - Passing a reference to an `IndexBuffer`, which contains a list of indices.
+ - Passing a reference to an `IndexBuffer`, which contains a list of indices, or a slice of
+   it. Slices make it possible to pack the indices of several meshes in the same buffer.
  - `NoIndices`, in which case the vertices will be used in the order in which they are in the
    vertex buffer.
 
+`u8` indices are handled slowly by some backends. `IndexBufferAny::from_u8` builds a buffer
+from `u8` indices and converts them to `u16` on these backends.
+
+Similarly, `IndexBufferAny::from_u32` builds a buffer from `u32` indices and narrows them to
+`u16` when they all fit, which halves the memory used by the indices of small meshes. Use
+`IndexBuffer::<u32>::new` instead if you want to keep `u32` indices.
+
+## Ranged draws
+
+If you know the lowest and highest indices of a slice, you can pass them with
+`IndexBufferSlice::vertices_range`. They are given to `glDrawRangeElements`, which lets the
+driver only process the vertices that are used.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let indices: glium::IndexBuffer<u16> = unsafe { std::mem::uninitialized() };
+// the second mesh uses the vertices 24 to 47, and its indices start at 36
+let second_mesh = indices.slice(36 .. 72).unwrap().vertices_range(24, 47);
+```
+
 ## Multidraw indirect
 
 In addition to indices, you can also use **multidraw indirect** rendering.
@@ -58,6 +79,9 @@ pub enum IndicesSource<'a> {
         data_type: IndexType,
         /// Type of primitives contained in the vertex source.
         primitives: PrimitiveType,
+        /// The lowest and highest values of the indices, if known. Passed to
+        /// `glDrawRangeElements`.
+        vertices_range: Option<(u32, u32)>,
     },
 
     /// Use a multidraw indirect buffer without indices.
@@ -203,13 +227,16 @@ impl PrimitiveType {
             &PrimitiveType::LinesListAdjacency | &PrimitiveType::LineStripAdjacency |
             &PrimitiveType::TrianglesListAdjacency | &PrimitiveType::TriangleStripAdjacency => {
                 caps.get_version() >= &Version(Api::Gl, 3, 0) ||
+                caps.get_version() >= &Version(Api::GlEs, 3, 2) ||
                 caps.get_extensions().gl_arb_geometry_shader4 ||
                 caps.get_extensions().gl_ext_geometry_shader4 ||
-                caps.get_extensions().gl_ext_geometry_shader
+                caps.get_extensions().gl_ext_geometry_shader ||
+                caps.get_extensions().gl_oes_geometry_shader
             },
 
             &PrimitiveType::Patches { .. } => {
                 caps.get_version() >= &Version(Api::Gl, 4, 0) ||
+                caps.get_version() >= &Version(Api::GlEs, 3, 2) ||
                 caps.get_extensions().gl_arb_tessellation_shader
             },
         }
@@ -297,6 +324,21 @@ impl IndexType {
             },
         }
     }
+
+    /// Returns true if the backend handles this type of index natively.
+    ///
+    /// `u8` indices are always supported, but Direct3D (used by ANGLE) and AMD hardware don't
+    /// handle them, and the driver converts them every time they are used.
+    pub fn is_efficient<C: ?Sized>(&self, caps: &C) -> bool where C: CapabilitiesSource {
+        match self {
+            &IndexType::U8 => {
+                let caps = caps.get_capabilities();
+                !(caps.renderer.contains("ANGLE") || caps.renderer.contains("Direct3D") ||
+                  caps.vendor.contains("ATI") || caps.vendor.contains("AMD"))
+            },
+            _ => self.is_supported(caps),
+        }
+    }
 }
 
 impl ToGlEnum for IndexType {
@@ -0,0 +1,198 @@
+/*!
+Shares buffers and textures with OpenCL or CUDA.
+
+This module is only available if you enable the `interop` feature of glium.
+
+OpenCL (with the `cl_khr_gl_sharing` extension) and CUDA can access OpenGL buffers and textures
+directly, which is useful to post-process a rendered image without copying it through the CPU.
+The functions of this module return what these APIs need to register an object, and perform the
+synchronization that they require around each access.
+
+## Registering objects
+
+Use `buffer_handle` or `texture_handle`, then pass the returned ids to `clCreateFromGLBuffer`,
+`clCreateFromGLTexture`, `cudaGraphicsGLRegisterBuffer` or `cudaGraphicsGLRegisterImage`. The
+OpenCL or CUDA context must have been created to share objects with the OpenGL context of glium.
+
+The object must stay alive for as long as it is registered. Textures must not be resized or
+recreated, which glium never does, and the external API must not delete the OpenGL objects.
+
+## Hand-off
+
+Before the external API acquires the objects (`clEnqueueAcquireGLObjects` or
+`cudaGraphicsMapResources`), the OpenGL commands that use them must have been submitted:
+
+ - With OpenCL, call `release_with_finish`, which blocks until these commands have finished.
+   If the OpenCL implementation supports `cl_khr_gl_event`, call `release_with_fence` instead and
+   pass the sync object returned by `get_raw_sync` to `clCreateEventFromGLsyncKHR`. The OpenCL
+   commands then wait for the fence without blocking the CPU.
+ - With CUDA, call `release_with_flush`. `cudaGraphicsMapResources` waits for the OpenGL
+   commands by itself.
+
+glium must not use the objects while they are acquired. Once the external API has released them
+(`clEnqueueReleaseGLObjects` or `cudaGraphicsUnmapResources`), the OpenGL commands must only be
+executed after the external commands have finished. CUDA takes care of this. With OpenCL, either
+wait for the release with `clFinish`, or call `acquire_after_cl_event` with the event returned by
+`clEnqueueReleaseGLObjects`.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let texture: glium::Texture2d = unsafe { ::std::mem::uninitialized() };
+use glium::interop;
+
+let handle = interop::texture_handle(&texture);
+// clCreateFromGLTexture(context, flags, handle.target, 0, handle.id, &mut error)
+
+// draw on `texture` here
+interop::release_with_finish(&display);
+// clEnqueueAcquireGLObjects, run the kernels, clEnqueueReleaseGLObjects, then clFinish
+```
+
+*/
+use std::os::raw::c_void;
+
+use backend::Facade;
+use buffer::BufferAnySlice;
+use sync::{self, SyncNotSupportedError};
+use texture::{CubeLayer, TextureAny};
+use version::{Api, Version};
+
+use gl;
+use BufferExt;
+use ContextExt;
+use GlObject;
+use SyncFence;
+use TextureExt;
+
+/// What is needed to register a buffer in OpenCL or CUDA.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BufferHandle {
+    /// Id of the OpenGL buffer.
+    pub id: gl::types::GLuint,
+
+    /// Offset in bytes of the slice within the buffer. OpenCL and CUDA always register the
+    /// whole buffer, so this offset must be applied when accessing it.
+    pub offset: usize,
+
+    /// Size in bytes of the slice.
+    pub size: usize,
+}
+
+/// What is needed to register a texture in OpenCL or CUDA.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TextureHandle {
+    /// Id of the OpenGL texture.
+    pub id: gl::types::GLuint,
+
+    /// Target of the texture, for example `GL_TEXTURE_2D`.
+    ///
+    /// `clCreateFromGLTexture` expects the target of a face instead of `GL_TEXTURE_CUBE_MAP`.
+    /// Use `cubemap_face_target` in this situation.
+    pub target: gl::types::GLenum,
+
+    /// Number of mipmap levels of the texture.
+    pub levels: u32,
+}
+
+/// Returns the handle of a buffer or of a slice of a buffer.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let buffer: glium::VertexBuffer<u8> = unsafe { ::std::mem::uninitialized() };
+/// let handle = glium::interop::buffer_handle(buffer.as_slice_any());
+/// ```
+#[inline]
+pub fn buffer_handle(buffer: BufferAnySlice) -> BufferHandle {
+    BufferHandle {
+        id: buffer.get_id(),
+        offset: buffer.get_offset_bytes(),
+        size: buffer.get_size(),
+    }
+}
+
+/// Returns the handle of a texture.
+#[inline]
+pub fn texture_handle(texture: &TextureAny) -> TextureHandle {
+    TextureHandle {
+        id: texture.get_id(),
+        target: texture.get_bind_point(),
+        levels: texture.get_mipmap_levels(),
+    }
+}
+
+/// Returns the target of a face of a cubemap, for example `GL_TEXTURE_CUBE_MAP_POSITIVE_X`.
+#[inline]
+pub fn cubemap_face_target(face: CubeLayer) -> gl::types::GLenum {
+    match face {
+        CubeLayer::PositiveX => gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+        CubeLayer::NegativeX => gl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+        CubeLayer::PositiveY => gl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+        CubeLayer::NegativeY => gl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+        CubeLayer::PositiveZ => gl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+        CubeLayer::NegativeZ => gl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+    }
+}
+
+/// Submits the commands that have been issued, so that CUDA can map the objects.
+#[inline]
+pub fn release_with_flush<F: ?Sized>(facade: &F) where F: Facade {
+    facade.get_context().flush();
+}
+
+/// Blocks until the commands that have been issued have finished, so that OpenCL can acquire
+/// the objects.
+#[inline]
+pub fn release_with_finish<F: ?Sized>(facade: &F) where F: Facade {
+    facade.get_context().finish();
+}
+
+/// Submits the commands that have been issued and returns a fence that is signaled when they
+/// have finished.
+///
+/// Pass the result of `get_raw_sync` to `clCreateEventFromGLsyncKHR`. The fence must be kept
+/// alive until the OpenCL event has completed.
+pub fn release_with_fence<F: ?Sized>(facade: &F) -> Result<SyncFence, SyncNotSupportedError>
+                                     where F: Facade
+{
+    let fence = try!(SyncFence::new(facade));
+    facade.get_context().flush();
+    Ok(fence)
+}
+
+/// Returns the OpenGL sync object of a fence, as expected by `clCreateEventFromGLsyncKHR`.
+#[inline]
+pub fn get_raw_sync(fence: &SyncFence) -> *const c_void {
+    sync::get_raw_sync(fence) as *const c_void
+}
+
+/// Makes the next OpenGL commands wait until an OpenCL event is complete, without blocking the
+/// CPU. Usually called with the event returned by `clEnqueueReleaseGLObjects`.
+///
+/// Returns an error if the backend doesn't support `GL_ARB_cl_event`. In this situation, wait
+/// for the OpenCL commands with `clFinish` instead.
+///
+/// # Unsafety
+///
+/// `cl_context` must be the OpenCL context that `cl_event` belongs to, and must share objects
+/// with the OpenGL context of `facade`.
+pub unsafe fn acquire_after_cl_event<F: ?Sized>(facade: &F, cl_context: *mut c_void,
+                                                cl_event: *mut c_void)
+                                                -> Result<(), SyncNotSupportedError>
+                                                where F: Facade
+{
+    let ctxt = facade.get_context().make_current();
+
+    if !ctxt.extensions.gl_arb_cl_event {
+        return Err(SyncNotSupportedError);
+    }
+
+    // `GL_ARB_cl_event` requires OpenGL 3.2 or `GL_ARB_sync`
+    debug_assert!(ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_sync);
+
+    let sync = ctxt.gl.CreateSyncFromCLeventARB(cl_context as *mut _, cl_event as *mut _, 0);
+    ctxt.gl.WaitSync(sync, 0, gl::TIMEOUT_IGNORED);
+    ctxt.gl.DeleteSync(sync);
+
+    Ok(())
+}
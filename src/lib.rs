@@ -89,6 +89,9 @@ result to the user.
  - **Uniform blocks**: If your program uses uniform blocks, you must pass a reference to a
    uniform buffer for the name of the block when drawing.
  - **Vertex array objects**: VAOs are automatically managed by glium if the backend supports them.
+   When `GL_ARB_vertex_attrib_binding` (or OpenGL 4.3 / OpenGL ES 3.1) is available, glium keeps
+   one VAO per vertex format and program and only rebinds the buffers that changed between two
+   draw calls.
 
 */
 #![warn(missing_docs)]
@@ -104,20 +107,49 @@ extern crate backtrace;
 extern crate smallvec;
 extern crate fnv;
 
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "derive")]
+#[allow(unused_imports)]
+#[macro_use]
+extern crate glium_derive;
+#[cfg(feature = "half")]
+extern crate half;
+#[cfg(feature = "image")]
+extern crate image;
+#[cfg(feature = "mint")]
+extern crate mint;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "web")]
+extern crate js_sys;
+#[cfg(feature = "web")]
+extern crate wasm_bindgen;
+#[cfg(feature = "web")]
+extern crate web_sys;
+
 #[cfg(feature = "glutin")]
 pub use backend::glutin::glutin;
 pub use context::Profile;
 pub use draw_parameters::{Blend, BlendingFunction, LinearBlendingFactor, BackfaceCullingMode};
 pub use draw_parameters::{Depth, DepthTest, PolygonMode, DrawParameters, StencilTest, StencilOperation};
-pub use draw_parameters::{Smooth};
+pub use draw_parameters::{Smooth, PointSpriteCoordOrigin, DrawParametersBuilder};
 pub use index::IndexBuffer;
 pub use vertex::{VertexBuffer, Vertex, VertexFormat};
+#[cfg(feature = "derive")]
+pub use glium_derive::Vertex;
 pub use program::{Program, ProgramCreationError};
 pub use program::ProgramCreationError::{CompilationError, LinkingError, ShaderTypeNotSupported};
-pub use sync::{LinearSyncFence, SyncFence};
+pub use sync::{LinearSyncFence, SyncFence, SyncNotSupportedError, SyncWaitError};
 pub use texture::Texture2d;
 pub use version::{Api, Version, get_supported_glsl_version};
 
+use std::borrow::Cow;
 use std::rc::Rc;
 use std::thread;
 use std::error::Error;
@@ -134,16 +166,43 @@ use context::CommandContext;
 mod macros;
 
 pub mod backend;
+#[cfg(feature = "batch2d")]
+pub mod batch2d;
 pub mod buffer;
+pub mod command_buffer;
 pub mod debug;
+pub mod debug_draw;
+pub mod draw_list;
 pub mod draw_parameters;
+pub mod effects;
+pub mod external;
 pub mod framebuffer;
+pub mod frame_pacing;
+#[cfg(feature = "geometry")]
+pub mod geometry;
+#[cfg(feature = "graph")]
+pub mod graph;
+#[allow(missing_docs)]
+pub mod gl {
+    //! Raw OpenGL bindings used by glium. See `Context::exec_with_raw_gl`.
+    include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
+}
+pub mod ibl;
 pub mod index;
+#[cfg(feature = "interop")]
+pub mod interop;
+pub mod mesh;
 pub mod pixel_buffer;
 pub mod program;
+pub mod recovery;
+pub mod shapes;
 pub mod uniforms;
 pub mod vertex;
 pub mod texture;
+#[cfg(feature = "tokens")]
+pub mod tokens;
+pub mod video_capture;
+pub mod video_stream;
 
 mod context;
 mod fbo;
@@ -155,10 +214,6 @@ mod utils;
 mod version;
 mod vertex_array_object;
 
-mod gl {
-    include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
-}
-
 /// The main object of this library. Controls the whole display.
 ///
 /// This object contains a smart pointer to the real implementation.
@@ -257,7 +312,14 @@ trait BufferExt {
 
     /// Makes sure that the buffer is bound to the indexed `GL_SHARED_STORAGE_BUFFER` point and calls
     /// `glMemoryBarrier(GL_SHADER_STORAGE_BARRIER_BIT)` if necessary.
-    fn prepare_and_bind_for_shared_storage(&self, &mut CommandContext, index: gl::types::GLuint);
+    ///
+    /// `write` must be true if the shader may write to the buffer.
+    fn prepare_and_bind_for_shared_storage(&self, &mut CommandContext, index: gl::types::GLuint,
+                                           write: bool);
+
+    /// Makes sure that the buffer is bound to the indexed `GL_ATOMIC_COUNTER_BUFFER` point and
+    /// calls `glMemoryBarrier(GL_ATOMIC_COUNTER_BARRIER_BIT)` if necessary.
+    fn prepare_and_bind_for_atomic_counter(&self, &mut CommandContext, index: gl::types::GLuint);
 
     /// Binds the buffer to `GL_TRANSFORM_FEEDBACk_BUFFER` regardless of the current transform
     /// feedback object.
@@ -279,9 +341,6 @@ trait ContextExt {
 
     /// Start executing OpenGL commands by checking the current context.
     fn make_current(&self) -> context::CommandContext;
-
-    /// Returns the capabilities of the backend.
-    fn capabilities(&self) -> &context::Capabilities;
 }
 
 /// Internal trait for programs.
@@ -294,6 +353,10 @@ trait ProgramExt {
     fn set_uniform(&self, ctxt: &mut context::CommandContext, uniform_location: gl::types::GLint,
                    value: &RawUniformValue);
 
+    /// Changes the values of the elements of a uniform array of the program.
+    fn set_uniform_array(&self, ctxt: &mut context::CommandContext,
+                         uniform_location: gl::types::GLint, value: &uniforms::UniformArray);
+
     /// Changes the uniform block binding of the program.
     fn set_uniform_block_binding(&self, ctxt: &mut context::CommandContext,
                                  block_location: gl::types::GLuint, value: gl::types::GLuint);
@@ -314,7 +377,38 @@ trait ProgramExt {
 
     fn get_shader_storage_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
+    fn get_atomic_counters(&self)
+                           -> &HashMap<String, program::AtomicCounter, BuildHasherDefault<FnvHasher>>;
+
     fn get_subroutine_data(&self) -> &program::SubroutineData;
+
+    fn get_uniforms(&self) -> &HashMap<String, program::Uniform, BuildHasherDefault<FnvHasher>>;
+
+    /// Returns what to do with the uniforms that have no value.
+    fn get_missing_uniforms_policy(&self) -> uniforms::MissingUniforms;
+
+    /// Returns true if some uniforms have a default value.
+    fn has_default_uniforms(&self) -> bool;
+
+    /// Calls `output` with the name and the default value of each uniform that has one.
+    fn visit_default_uniforms(&self, output: &mut FnMut(&str, uniforms::UniformValue));
+
+    /// Prints a warning about a uniform that has no value, unless it has already been done.
+    fn warn_missing_uniform(&self, name: &str);
+
+    /// Returns the binding point that has been assigned to a uniform block, if any.
+    fn get_fixed_uniform_block_binding(&self, block_id: i32) -> Option<u16>;
+
+    /// Returns the texture unit that has been assigned to a sampler, if any.
+    fn get_fixed_texture_unit(&self, location: gl::types::GLint) -> Option<u16>;
+
+    /// Returns true if a sampler of the program has been assigned this texture unit.
+    fn is_texture_unit_reserved(&self, unit: u16) -> bool;
+
+    /// Marks the texture units and the uniform buffer binding points that have been assigned
+    /// as used.
+    fn reserve_fixed_bindings(&self, texture_units: &mut utils::bitsfield::Bitsfield,
+                              buffer_bind_points: &mut utils::bitsfield::Bitsfield);
 }
 
 /// Internal trait for queries.
@@ -385,7 +479,11 @@ trait UniformsExt {
     /// Binds the uniforms to a given program.
     ///
     /// Will replace texture and buffer bind points.
-    fn bind_uniforms<'a, P>(&'a self, &mut CommandContext, &P, &mut Vec<buffer::Inserter<'a>>)
+    ///
+    /// The `MissingUniforms`, if any, overrides the policy of the program about the uniforms that
+    /// have no value.
+    fn bind_uniforms<'a, P>(&'a self, &mut CommandContext, &P, Option<uniforms::MissingUniforms>,
+                            &mut Vec<buffer::Inserter<'a>>)
                             -> Result<(), DrawError> where P: ProgramExt;
 }
 
@@ -394,7 +492,7 @@ trait UniformsExt {
 /// for example are just passed as integers.
 ///
 /// Blocks and subroutines are not included.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 #[allow(missing_docs)]
 pub enum RawUniformValue {
     SignedInt(gl::types::GLint),
@@ -406,6 +504,18 @@ pub enum RawUniformValue {
     Mat3([[gl::types::GLfloat; 3]; 3]),
     /// 4x4 column-major matrix.
     Mat4([[gl::types::GLfloat; 4]; 4]),
+    /// 2x3 column-major matrix, with 2 columns of 3 rows.
+    Mat2x3([[gl::types::GLfloat; 3]; 2]),
+    /// 2x4 column-major matrix, with 2 columns of 4 rows.
+    Mat2x4([[gl::types::GLfloat; 4]; 2]),
+    /// 3x2 column-major matrix, with 3 columns of 2 rows.
+    Mat3x2([[gl::types::GLfloat; 2]; 3]),
+    /// 3x4 column-major matrix, with 3 columns of 4 rows.
+    Mat3x4([[gl::types::GLfloat; 4]; 3]),
+    /// 4x2 column-major matrix, with 4 columns of 2 rows.
+    Mat4x2([[gl::types::GLfloat; 2]; 4]),
+    /// 4x3 column-major matrix, with 4 columns of 3 rows.
+    Mat4x3([[gl::types::GLfloat; 3]; 4]),
     Vec2([gl::types::GLfloat; 2]),
     Vec3([gl::types::GLfloat; 3]),
     Vec4([gl::types::GLfloat; 4]),
@@ -421,6 +531,12 @@ pub enum RawUniformValue {
     DoubleMat2([[gl::types::GLdouble; 2]; 2]),
     DoubleMat3([[gl::types::GLdouble; 3]; 3]),
     DoubleMat4([[gl::types::GLdouble; 4]; 4]),
+    DoubleMat2x3([[gl::types::GLdouble; 3]; 2]),
+    DoubleMat2x4([[gl::types::GLdouble; 4]; 2]),
+    DoubleMat3x2([[gl::types::GLdouble; 2]; 3]),
+    DoubleMat3x4([[gl::types::GLdouble; 4]; 3]),
+    DoubleMat4x2([[gl::types::GLdouble; 2]; 4]),
+    DoubleMat4x3([[gl::types::GLdouble; 3]; 4]),
     DoubleVec2([gl::types::GLdouble; 2]),
     DoubleVec3([gl::types::GLdouble; 3]),
     DoubleVec4([gl::types::GLdouble; 4]),
@@ -432,6 +548,9 @@ pub enum RawUniformValue {
     UnsignedInt64Vec2([gl::types::GLuint64; 2]),
     UnsignedInt64Vec3([gl::types::GLuint64; 3]),
     UnsignedInt64Vec4([gl::types::GLuint64; 4]),
+
+    /// Handle of a resident texture, for samplers with `GL_ARB_bindless_texture`.
+    TextureHandle(gl::types::GLuint64),
 }
 
 /// Area of a surface in pixels.
@@ -677,7 +796,36 @@ pub struct BlitTarget {
 /// to specify whether each color component (red, green, blue and alpha) is written to the color
 /// buffer.
 ///
-pub trait Surface {
+/// # Trait objects
+///
+/// The functions that are generic, like `draw`, can only be called on a concrete surface type.
+/// Code that receives a `&mut Surface`, for example a user interface library that can draw on
+/// any target, can use `draw_dyn` instead of `draw`:
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+/// # let vertex_buffer: glium::vertex::VertexBufferAny = unsafe { ::std::mem::uninitialized() };
+/// use glium::Surface;
+/// use glium::vertex::IntoVerticesSource;
+///
+/// fn draw_overlay(target: &mut Surface, vertices: &glium::vertex::VertexBufferAny,
+///                 program: &glium::Program)
+/// {
+///     let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+///     target.draw_dyn(&[vertices.into_vertices_source()], indices.into(), program,
+///                     &uniform! { alpha: 0.5f32 }, &Default::default()).unwrap();
+/// }
+///
+/// let mut frame = display.draw();
+/// draw_overlay(&mut frame, &vertex_buffer, &program);
+/// frame.finish().unwrap();
+/// # }
+/// ```
+///
+pub trait Surface: DrawDyn {
     /// Clears some attachments of the target.
     fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
              depth: Option<f32>, stencil: Option<i32>);
@@ -769,7 +917,32 @@ pub trait Surface {
     fn draw<'a, 'b, V, I, U>(&mut self, V, I, program: &Program, uniforms: &U,
         draw_parameters: &DrawParameters) -> Result<(), DrawError> where
         V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
-        U: uniforms::Uniforms;
+        U: uniforms::Uniforms, Self: Sized;
+
+    /// Draws a mesh.
+    ///
+    /// This is the same as calling `draw` with the vertex buffers and the indices of the mesh.
+    #[inline]
+    fn draw_mesh<U>(&mut self, mesh: &mesh::Mesh, program: &Program, uniforms: &U,
+                    draw_parameters: &DrawParameters) -> Result<(), DrawError>
+                    where U: uniforms::Uniforms, Self: Sized
+    {
+        self.draw(mesh.vertices(), mesh.indices(), program, uniforms, draw_parameters)
+    }
+
+    /// Draws a submesh of a mesh.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the mesh doesn't have a submesh with this index.
+    #[inline]
+    fn draw_submesh<U>(&mut self, mesh: &mesh::Mesh, submesh: usize, program: &Program,
+                       uniforms: &U, draw_parameters: &DrawParameters) -> Result<(), DrawError>
+                       where U: uniforms::Uniforms, Self: Sized
+    {
+        let (vertices, indices) = mesh.submesh(submesh).expect("Submesh index out of range");
+        self.draw(vertices, indices, program, uniforms, draw_parameters)
+    }
 
     /// Blits from the default framebuffer.
     fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
@@ -798,12 +971,12 @@ pub trait Surface {
     /// Note that there is no alpha blending, depth/stencil checking, etc. This function just
     /// copies pixels.
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface;
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface, Self: Sized;
 
     /// Copies the entire surface to a target surface. See `blit_color`.
     #[inline]
     fn blit_whole_color_to<S>(&self, target: &S, target_rect: &BlitTarget,
-        filter: uniforms::MagnifySamplerFilter) where S: Surface
+        filter: uniforms::MagnifySamplerFilter) where S: Surface, Self: Sized
     {
         let src_dim = self.get_dimensions();
         let src_rect = Rect { left: 0, bottom: 0, width: src_dim.0 as u32, height: src_dim.1 as u32 };
@@ -812,13 +985,169 @@ pub trait Surface {
 
     /// Copies the entire surface to the entire target. See `blit_color`.
     #[inline]
-    fn fill<S>(&self, target: &S, filter: uniforms::MagnifySamplerFilter) where S: Surface {
+    fn fill<S>(&self, target: &S, filter: uniforms::MagnifySamplerFilter)
+               where S: Surface, Self: Sized
+    {
         let src_dim = self.get_dimensions();
         let src_rect = Rect { left: 0, bottom: 0, width: src_dim.0 as u32, height: src_dim.1 as u32 };
         let target_dim = target.get_dimensions();
         let target_rect = BlitTarget { left: 0, bottom: 0, width: target_dim.0 as i32, height: target_dim.1 as i32 };
         self.blit_color(&src_rect, target, &target_rect, filter)
     }
+
+    /// Reads the content of the color buffer of the surface.
+    ///
+    /// Multisampled surfaces are resolved, and the pixels are converted to RGBA with one `u8`
+    /// per component. The first pixel of the image is the bottom-left corner of the surface.
+    ///
+    /// This is mostly useful for screenshot-based tests, in conjunction with
+    /// `RawImage2d::compare`. The content is copied to a temporary texture created with
+    /// `facade`, which must be the context of the surface.
+    ///
+    /// Returns an error if the surface doesn't have a color buffer or if the temporary texture
+    /// can't be created. An empty image is returned if the surface is empty.
+    fn snapshot<F: ?Sized>(&self, facade: &F)
+                           -> Result<texture::RawImage2d<'static, u8>, texture::ReadError>
+                           where F: backend::Facade, Self: Sized
+    {
+        let (width, height) = self.get_dimensions();
+        if width == 0 || height == 0 {
+            return Ok(texture::RawImage2d {
+                data: Cow::Owned(Vec::new()),
+                width: width,
+                height: height,
+                format: texture::ClientFormat::U8U8U8U8,
+            });
+        }
+
+        let texture = try!(copy_surface_to_texture(facade, self));
+        Ok(texture.read())
+    }
+
+    /// Starts reading the content of the color buffer of the surface into a pixel buffer.
+    ///
+    /// Contrary to `snapshot`, this function doesn't wait for the GPU to finish drawing. The
+    /// returned object can be polled to know when the content is available. This is useful to
+    /// record videos or take screenshots without stalling the rendering.
+    ///
+    /// The format of the pixels and the errors are the same as with `snapshot`.
+    fn read_to_pixel_buffer_async<F: ?Sized>(&self, facade: &F)
+        -> Result<texture::pixel_buffer::PendingPixelRead<(u8, u8, u8, u8)>, texture::ReadError>
+        where F: backend::Facade, Self: Sized
+    {
+        let (width, height) = self.get_dimensions();
+        let buffer = if width == 0 || height == 0 {
+            let buffer = texture::pixel_buffer::PixelBuffer::new_empty(facade, 0);
+            texture::pixel_buffer::store_infos(&buffer, (width, height));
+            buffer
+        } else {
+            try!(copy_surface_to_texture(facade, self)).read_to_pixel_buffer()
+        };
+
+        Ok(texture::pixel_buffer::PendingPixelRead::new(facade, buffer))
+    }
+
+    /// Reads the content of the depth buffer of the surface. The values are between `0.0` and
+    /// `1.0`, and the first value is the bottom-left corner of the surface.
+    ///
+    /// OpenGL ES only supports reading the depth buffer with the `GL_NV_read_depth` extension.
+    /// Use `DepthTexture2d::read_depth` instead if the depth buffer is a texture, as it has a
+    /// fallback for this case.
+    ///
+    /// Returns an error if the surface doesn't have a depth buffer or if the backend doesn't
+    /// support reading it. The default implementation always returns
+    /// `ReadError::AttachmentTypeNotSupported`, and is overridden by the surfaces of glium.
+    fn read_depth<T>(&self) -> Result<T, texture::ReadError>
+                     where T: texture::Texture2dDataSink<f32>, Self: Sized
+    {
+        Err(texture::ReadError::AttachmentTypeNotSupported)
+    }
+
+    /// Reads the content of the stencil buffer of the surface. The first value is the
+    /// bottom-left corner of the surface.
+    ///
+    /// OpenGL ES only supports reading the stencil buffer with the `GL_NV_read_stencil`
+    /// extension.
+    ///
+    /// Returns an error if the surface doesn't have a stencil buffer or if the backend doesn't
+    /// support reading it. The default implementation is the same as for `read_depth`.
+    fn read_stencil<T>(&self) -> Result<T, texture::ReadError>
+                       where T: texture::Texture2dDataSink<u8>, Self: Sized
+    {
+        Err(texture::ReadError::AttachmentTypeNotSupported)
+    }
+}
+
+/// Provides `draw_dyn`, which is the same as `Surface::draw` but can be called on a
+/// `&mut Surface`.
+///
+/// This trait is implemented for all the types that implement `Surface`. It doesn't need to be
+/// imported to call `draw_dyn` on a `&mut Surface` or on a generic type bound by `Surface`.
+pub trait DrawDyn {
+    /// Same as `draw`, but can be called on a `&mut Surface`.
+    ///
+    /// The vertex sources are passed as a slice, and the uniforms as a `&AnyUniforms`, which
+    /// is implemented for all the types that implement `Uniforms`.
+    fn draw_dyn<'a, 'b>(&mut self, vertices: &[vertex::VerticesSource<'b>],
+                        indices: index::IndicesSource<'a>, program: &Program,
+                        uniforms: &uniforms::AnyUniforms, draw_parameters: &DrawParameters)
+                        -> Result<(), DrawError>;
+}
+
+impl<S> DrawDyn for S where S: Surface {
+    #[inline]
+    fn draw_dyn<'a, 'b>(&mut self, vertices: &[vertex::VerticesSource<'b>],
+                        indices: index::IndicesSource<'a>, program: &Program,
+                        uniforms: &uniforms::AnyUniforms, draw_parameters: &DrawParameters)
+                        -> Result<(), DrawError>
+    {
+        self.draw(vertices, indices, program, &uniforms, draw_parameters)
+    }
+}
+
+/// Copies the color buffer of a surface to a new RGBA8 texture.
+fn copy_surface_to_texture<F: ?Sized, S>(facade: &F, surface: &S)
+                                         -> Result<texture::Texture2d, texture::ReadError>
+                                         where F: backend::Facade, S: Surface
+{
+    let (width, height) = surface.get_dimensions();
+    let texture = try!(texture::Texture2d::empty_with_format(facade,
+                                                 texture::UncompressedFloatFormat::U8U8U8U8,
+                                                 texture::MipmapsOption::NoMipmap,
+                                                 width, height));
+    surface.fill(&texture.as_surface(), uniforms::MagnifySamplerFilter::Nearest);
+    Ok(texture)
+}
+
+/// Implementation of `Surface::read_depth` and `Surface::read_stencil`.
+fn read_surface_depth_stencil<S, T, P>(context: &Rc<Context>, surface: &S, stencil: bool)
+                                       -> Result<T, texture::ReadError>
+                                       where S: Surface + FboAttachments,
+                                             T: texture::Texture2dDataSink<P>,
+                                             P: texture::PixelValue
+{
+    if stencil && surface.get_stencil_buffer_bits().is_none() {
+        return Err(texture::ReadError::NoStencilBuffer);
+    }
+
+    if !stencil && surface.get_depth_buffer_bits().is_none() {
+        return Err(texture::ReadError::NoDepthBuffer);
+    }
+
+    let (width, height) = surface.get_dimensions();
+    let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+
+    let source = if stencil {
+        ops::Source::StencilBuffer(surface.get_attachments())
+    } else {
+        ops::Source::DepthBuffer(surface.get_attachments())
+    };
+
+    let mut ctxt = context.make_current();
+    let mut data = Vec::new();
+    try!(ops::read(&mut ctxt, source, &rect, &mut data, false, texture::ChannelOrder::Rgba));
+
+    Ok(T::from_raw(Cow::Owned(data), width, height))
 }
 
 /// Private trait for framebuffer-like objects that provide attachments.
@@ -829,19 +1158,34 @@ trait FboAttachments {
 
 /// Error that can happen while drawing.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum DrawError {
     /// A depth function has been requested but no depth buffer is available.
     NoDepthBuffer,
 
     /// The type of a vertex attribute in the vertices source doesn't match what the
     /// program requires.
-    AttributeTypeMismatch,
+    AttributeTypeMismatch {
+        /// Name of the attribute.
+        name: String,
+        /// Type of the attribute in the program. Use `AttributeType::get_glsl_name` to get
+        /// the name of the GLSL type.
+        program: vertex::AttributeType,
+        /// Type of the attribute in the vertex format.
+        vertex: vertex::AttributeType,
+        /// True if the attribute is normalized in the vertex format.
+        normalize: bool,
+    },
 
     /// One of the attributes required by the program is missing from the vertex format.
     ///
     /// Note that it is perfectly valid to have an attribute in the vertex format that is
-    /// not used by the program.
-    AttributeMissing,
+    /// not used by the program. The attributes of the program that aren't in the vertex format
+    /// can also be ignored with the `ignore_missing_attributes` member of the draw parameters.
+    AttributeMissing {
+        /// Name of the attribute.
+        name: String,
+    },
 
     /// The viewport's dimensions are not supported by the backend.
     ViewportTooLarge,
@@ -877,6 +1221,48 @@ pub enum DrawError {
         err: uniforms::LayoutMismatchError,
     },
 
+    /// An array of samplers contains more textures than a shader can access.
+    SamplerArrayTooLarge {
+        /// Name of the uniform you are trying to bind.
+        name: String,
+        /// The number of textures in the array.
+        len: usize,
+        /// The maximum number of textures, given by `GL_MAX_TEXTURE_IMAGE_UNITS`.
+        max: usize,
+    },
+
+    /// A uniform or a uniform block of the program has no value, and the policy about missing
+    /// uniforms is `MissingUniforms::Error`.
+    UniformMissing {
+        /// Name of the uniform or of the block.
+        name: String,
+    },
+
+    /// A draw command uses more textures than there are texture units. The limit is the
+    /// `max_combined_texture_image_units` field of the capabilities.
+    NotEnoughTextureUnits {
+        /// Name of the uniform that couldn't be bound.
+        name: String,
+        /// Number of texture units.
+        max: usize,
+    },
+
+    /// A texture handle is not the handle of a resident texture.
+    TextureHandleNotResident {
+        /// Name of the uniform.
+        name: String,
+    },
+
+    /// The offset of an atomic counter is outside of the buffer that has been bound to it.
+    AtomicCounterOutOfBuffer {
+        /// Name of the atomic counter.
+        name: String,
+    },
+
+    /// Trying to bind a texture to an image unit, but image load and store is not supported by
+    /// the backend.
+    ImageUnitsNotSupported,
+
     /// Tried to bind a subroutine uniform like a regular uniform value.
     SubroutineUniformToValue {
         /// Name of the uniform you are trying to bind.
@@ -903,7 +1289,12 @@ pub enum DrawError {
     },
 
     /// The number of vertices per patch that has been requested is not supported.
-    UnsupportedVerticesPerPatch,
+    UnsupportedVerticesPerPatch {
+        /// The number of vertices per patch that has been requested.
+        requested: u16,
+        /// The maximum number of vertices per patch, given by `GL_MAX_PATCH_VERTICES`.
+        max: u32,
+    },
 
     /// Trying to use tessellation, but this is not supported by the underlying hardware.
     TessellationNotSupported,
@@ -914,7 +1305,7 @@ pub enum DrawError {
     /// Trying to use a sampler, but they are not supported by the backend.
     SamplersNotSupported,
 
-    /// When you use instancing, all vertices sources must have the same size.
+    /// When you use instancing, the per-instance sources must cover the same number of instances.
     InstancesCountMismatch,
 
     /// If you don't use indices, then all vertices sources must have the same size.
@@ -943,6 +1334,31 @@ pub enum DrawError {
 
     /// Restarting indices (multiple objects per draw call) is not supported by the backend.
     FixedIndexRestartingNotSupported,
+
+    /// The requested point fade threshold or point sprite origin is not supported by the backend.
+    PointParametersNotSupported,
+
+    /// Choosing whether the program determines the size of the points is not supported by the
+    /// backend.
+    ProgramPointSizeNotSupported,
+
+    /// Instancing is required in order to pass attributes per object, but it isn't supported
+    /// by the backend.
+    InstancingNotSupported,
+
+    /// The type of primitives isn't supported by the backend.
+    PrimitiveTypeNotSupported,
+
+    /// Trying to draw patches with a program that doesn't contain a tessellation evaluation
+    /// shader.
+    PatchesWithoutTessellation,
+
+    /// The type of primitives doesn't match the input of the geometry shader. See
+    /// `Program::get_geometry_input_primitives`.
+    GeometryShaderInputMismatch,
+
+    /// The OpenGL context has been lost. See the documentation of the `recovery` module.
+    ContextLost,
 }
 
 impl Error for DrawError {
@@ -951,9 +1367,9 @@ impl Error for DrawError {
         match *self {
             NoDepthBuffer =>
                 "A depth function has been requested but no depth buffer is available",
-            AttributeTypeMismatch =>
+            AttributeTypeMismatch { .. } =>
                 "The type of a vertex attribute in the vertices source doesn't match what the program requires",
-            AttributeMissing =>
+            AttributeMissing { .. } =>
                 "One of the attributes required by the program is missing from the vertex format",
             ViewportTooLarge =>
                 "The viewport's dimensions are not supported by the backend",
@@ -967,13 +1383,25 @@ impl Error for DrawError {
                 "Tried to bind a single uniform value to a uniform block",
             UniformBlockLayoutMismatch { .. } =>
                 "The layout of the content of the uniform buffer does not match the layout of the block",
+            SamplerArrayTooLarge { .. } =>
+                "An array of samplers contains more textures than a shader can access",
+            UniformMissing { .. } =>
+                "A uniform of the program has no value",
+            NotEnoughTextureUnits { .. } =>
+                "The draw command uses more textures than there are texture units",
+            TextureHandleNotResident { .. } =>
+                "A texture handle is not the handle of a resident texture",
+            AtomicCounterOutOfBuffer { .. } =>
+                "The offset of an atomic counter is outside of the buffer bound to it",
+            ImageUnitsNotSupported =>
+                "Trying to bind a texture to an image unit, but this is not supported by the backend",
             SubroutineUniformToValue { .. } =>
                 "Tried to bind a subroutine uniform like a regular uniform value",
             SubroutineUniformMissing { .. } =>
                 "Not all subroutine uniforms of a shader stage were set",
             SubroutineNotFound { .. } =>
                 "A non-existant subroutine was referenced",
-            UnsupportedVerticesPerPatch =>
+            UnsupportedVerticesPerPatch { .. } =>
                 "The number of vertices per patch that has been requested is not supported",
             TessellationNotSupported =>
                 "Trying to use tessellation, but this is not supported by the underlying hardware",
@@ -1001,10 +1429,25 @@ impl Error for DrawError {
                 "One the blending parameters is not supported by the backend",
             FixedIndexRestartingNotSupported =>
                 "Restarting indices (multiple objects per draw call) is not supported by the backend",
+            PointParametersNotSupported =>
+                "The requested point parameters are not supported by the backend",
+            ProgramPointSizeNotSupported =>
+                "Choosing whether the program determines the size of the points is not supported \
+                 by the backend",
+            InstancingNotSupported =>
+                "Instancing is not supported by the backend",
+            PrimitiveTypeNotSupported =>
+                "The type of primitives is not supported by the backend",
+            PatchesWithoutTessellation =>
+                "Trying to draw patches without a tessellation evaluation shader",
+            GeometryShaderInputMismatch =>
+                "The type of primitives doesn't match the input of the geometry shader",
+            ContextLost =>
+                "The OpenGL context has been lost",
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::DrawError::*;
         match *self {
             UniformBlockLayoutMismatch { ref err, .. } => Some(err),
@@ -1018,6 +1461,22 @@ impl fmt::Display for DrawError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         use self::DrawError::*;
         match *self {
+            AttributeTypeMismatch { ref name, program, vertex, normalize } =>
+                match program.get_glsl_name() {
+                    Some(glsl) => write!(fmt, "{}: `{}` is a `{}` in the program and a {:?}{} in \
+                                               the vertex", self.description(), name, glsl,
+                                         vertex, if normalize { " (normalized)" } else { "" }),
+                    None => write!(fmt, "{}: `{}` is a {:?} in the program and a {:?}{} in the \
+                                         vertex", self.description(), name, program, vertex,
+                                   if normalize { " (normalized)" } else { "" }),
+                },
+            AttributeMissing { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
             UniformTypeMismatch { ref name, ref expected } =>
                 write!(
                     fmt,
@@ -1040,6 +1499,35 @@ impl fmt::Display for DrawError {
                     self.description(),
                     name,
                 ),
+            UniformMissing { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
+            NotEnoughTextureUnits { ref name, max } =>
+                write!(
+                    fmt,
+                    "{}: all the {} units are already used when binding {}",
+                    self.description(),
+                    max,
+                    name,
+                ),
+            TextureHandleNotResident { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
+            AtomicCounterOutOfBuffer { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
             UniformBlockLayoutMismatch { ref name, ref err } =>
                 write!(
                     fmt,
@@ -1048,6 +1536,23 @@ impl fmt::Display for DrawError {
                     name,
                     err,
                 ),
+            UnsupportedVerticesPerPatch { requested, max } =>
+                write!(
+                    fmt,
+                    "{}: {} vertices per patch were requested, the maximum is {}",
+                    self.description(),
+                    requested,
+                    max,
+                ),
+            SamplerArrayTooLarge { ref name, len, max } =>
+                write!(
+                    fmt,
+                    "{}: {} contains {} textures, the maximum is {}",
+                    self.description(),
+                    name,
+                    len,
+                    max,
+                ),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -1056,6 +1561,7 @@ impl fmt::Display for DrawError {
 
 /// Error that can happen when swapping buffers.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum SwapBuffersError {
     /// The OpenGL context has been lost and needs to be recreated. The `Display` and all the
     /// objects associated to it (textures, buffers, programs, etc.) need to be recreated from
@@ -1117,6 +1623,9 @@ impl Frame {
 
     /// Stop drawing, swap the buffers, and consume the Frame.
     ///
+    /// If the number of frames in flight has been limited with the `frame_pacing` module, this
+    /// function blocks until the GPU has caught up.
+    ///
     /// See the documentation of `SwapBuffersError` about what is being returned.
     #[inline]
     pub fn finish(mut self) -> Result<(), SwapBuffersError> {
@@ -1151,11 +1660,11 @@ impl Surface for Frame {
     }
 
     fn get_depth_buffer_bits(&self) -> Option<u16> {
-        self.context.capabilities().depth_bits
+        self.context.get_capabilities().depth_bits
     }
 
     fn get_stencil_buffer_bits(&self) -> Option<u16> {
-        self.context.capabilities().stencil_bits
+        self.context.get_capabilities().stencil_bits
     }
 
     fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V,
@@ -1164,24 +1673,7 @@ impl Surface for Frame {
                          where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
                          V: vertex::MultiVerticesSource<'b>
     {
-        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
-                draw_parameters.depth.write)
-        {
-            return Err(DrawError::NoDepthBuffer);
-        }
-
-        if let Some(viewport) = draw_parameters.viewport {
-            if viewport.width > self.context.capabilities().max_viewport_dims.0
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-            if viewport.height > self.context.capabilities().max_viewport_dims.1
-                    as u32
-            {
-                return Err(DrawError::ViewportTooLarge);
-            }
-        }
+        try!(ops::validate_surface(&self.context, self.has_depth_buffer(), draw_parameters));
 
         ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
                   uniforms, draw_parameters, (self.dimensions.0 as u32, self.dimensions.1 as u32))
@@ -1219,6 +1711,20 @@ impl Surface for Frame {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
                   gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
     }
+
+    #[inline]
+    fn read_depth<T>(&self) -> Result<T, texture::ReadError>
+                     where T: texture::Texture2dDataSink<f32>
+    {
+        read_surface_depth_stencil(&self.context, self, false)
+    }
+
+    #[inline]
+    fn read_stencil<T>(&self) -> Result<T, texture::ReadError>
+                       where T: texture::Texture2dDataSink<u8>
+    {
+        read_surface_depth_stencil(&self.context, self, true)
+    }
 }
 
 impl FboAttachments for Frame {
@@ -1244,7 +1750,7 @@ pub struct IncompatibleOpenGl(pub String);
 
 impl fmt::Display for IncompatibleOpenGl {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}", self.description())
+        write!(fmt, "{}: {}", self.description(), self.0)
     }
 }
 
@@ -1255,19 +1761,27 @@ impl Error for IncompatibleOpenGl {
     }
 }
 
+/// Returns the name and the value of the error returned by `glGetError`, if any.
 #[allow(dead_code)]
-#[inline]
-fn get_gl_error(ctxt: &mut context::CommandContext) -> Option<&'static str> {
-    match unsafe { ctxt.gl.GetError() } {
-        gl::NO_ERROR => None,
-        gl::INVALID_ENUM => Some("GL_INVALID_ENUM"),
-        gl::INVALID_VALUE => Some("GL_INVALID_VALUE"),
-        gl::INVALID_OPERATION => Some("GL_INVALID_OPERATION"),
-        gl::INVALID_FRAMEBUFFER_OPERATION => Some("GL_INVALID_FRAMEBUFFER_OPERATION"),
-        gl::OUT_OF_MEMORY => Some("GL_OUT_OF_MEMORY"),
-        gl::STACK_UNDERFLOW => Some("GL_STACK_UNDERFLOW"),
-        gl::STACK_OVERFLOW => Some("GL_STACK_OVERFLOW"),
-        gl::CONTEXT_LOST => Some("GL_CONTEXT_LOST"),
-        _ => Some("Unknown glGetError return value")
-    }
+fn get_gl_error(ctxt: &mut context::CommandContext) -> Option<String> {
+    describe_gl_error(ctxt.get_error())
+}
+
+/// Returns the name and the value of an error returned by `glGetError`, or `None` if it is
+/// `GL_NO_ERROR`.
+fn describe_gl_error(error: gl::types::GLenum) -> Option<String> {
+    let name = match error {
+        gl::NO_ERROR => return None,
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        gl::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+        gl::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+        gl::CONTEXT_LOST => "GL_CONTEXT_LOST",
+        _ => "Unknown glGetError return value",
+    };
+
+    Some(format!("{} (0x{:x})", name, error))
 }
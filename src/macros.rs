@@ -410,6 +410,50 @@ macro_rules! implement_uniform_block {
                         ],
                     }
                 }
+
+                fn matches_strides(layout: &$crate::program::BlockLayout, path: &str,
+                                   strides: &::std::collections::HashMap<String, usize>)
+                                   -> ::std::result::Result<(), $crate::uniforms::LayoutMismatchError>
+                {
+                    use std::mem;
+                    use $crate::program::BlockLayout;
+                    use $crate::uniforms::LayoutMismatchError;
+
+                    fn matches_strides_from_ty<T: $crate::uniforms::UniformBlock + ?Sized>(_: &T,
+                        layout: &BlockLayout, path: &str,
+                        strides: &::std::collections::HashMap<String, usize>)
+                        -> ::std::result::Result<(), LayoutMismatchError>
+                    {
+                        <T as $crate::uniforms::UniformBlock>::matches_strides(layout, path, strides)
+                    }
+
+                    if let &BlockLayout::Struct { ref members } = layout {
+                        let dummy: &$struct_name = unsafe { mem::zeroed() };
+
+                        $(
+                            let reflected_ty = members.iter().find(|&&(ref name, _)| {
+                                                                        name == stringify!($field_name)
+                                                                   });
+
+                            if let Some(&(_, ref reflected_ty)) = reflected_ty {
+                                let path = $crate::uniforms::block_member_path(path,
+                                                                    stringify!($field_name));
+
+                                match matches_strides_from_ty(&dummy.$field_name, reflected_ty,
+                                                              &path, strides)
+                                {
+                                    Ok(_) => (),
+                                    Err(e) => return Err(LayoutMismatchError::MemberMismatch {
+                                        member: stringify!($field_name).to_owned(),
+                                        err: Box::new(e),
+                                    })
+                                };
+                            }
+                        )+
+                    }
+
+                    Ok(())
+                }
             }
         }
     );
@@ -427,6 +471,317 @@ macro_rules! implement_uniform_block {
     );
 }
 
+/// Implements the `glium::uniforms::UniformStruct` and `glium::uniforms::AsUniformValue` traits
+/// for the given struct, so that it can be bound to a uniform whose type is a GLSL struct.
+///
+/// Each field is bound to the member of the same name. A slice or a `Vec` of the struct can be
+/// bound to an array of structs, like `lights[0].position`, `lights[1].position`, etc.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Light {
+///     position: [f32; 3],
+///     intensity: f32,
+/// }
+///
+/// implement_uniform_struct!(Light, position, intensity);
+///
+/// let lights = vec![
+///     Light { position: [0.0, 5.0, 0.0], intensity: 1.0 },
+///     Light { position: [2.0, 1.0, 0.0], intensity: 0.5 },
+/// ];
+///
+/// let uniforms = uniform! {
+///     lights: &lights[..],
+///     main_light: lights[0],
+/// };
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_uniform_struct {
+    ($struct_name:ident, $($field_name:ident),+,) => (
+        implement_uniform_struct!($struct_name, $($field_name),+);
+    );
+
+    ($struct_name:ident, $($field_name:ident),+) => (
+        impl $crate::uniforms::UniformStruct for $struct_name {
+            fn visit_fields<'a>(&'a self,
+                                output: &mut FnMut(&str, $crate::uniforms::UniformValue<'a>))
+            {
+                $(
+                    output(concat!(".", stringify!($field_name)),
+                           $crate::uniforms::AsUniformValue::as_uniform_value(&self.$field_name));
+                )+
+            }
+        }
+
+        impl $crate::uniforms::AsUniformValue for $struct_name {
+            #[inline]
+            fn as_uniform_value(&self) -> $crate::uniforms::UniformValue {
+                $crate::uniforms::UniformValue::Struct(self)
+            }
+        }
+    );
+}
+
+/// Implements the `glium::uniforms::AsStd140` trait for the given struct, so that it can be
+/// wrapped in a `glium::uniforms::Std140` and uploaded in a `UniformBuffer` that is bound to a
+/// block with `layout(std140)`.
+///
+/// The offsets of the members are computed with the std140 rules, so the struct doesn't need
+/// any manual padding. Each member is given with its type, which must implement
+/// `glium::uniforms::Std140Field`, and all the members of the struct must be given. The layout
+/// is checked against the block of the program when the buffer is bound.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Light {
+///     position: [f32; 3],
+///     intensity: f32,
+/// }
+///
+/// implement_std140_block!(Light, position: [f32; 3], intensity: f32);
+///
+/// let light = glium::uniforms::Std140::new(&Light { position: [0.0; 3], intensity: 1.0 });
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_std140_block {
+    (__size $end:expr;) => (
+        ($end + 15) / 16 * 16
+    );
+
+    (__size $end:expr; $ty:ty, $($rest:ty,)*) => (
+        implement_std140_block!(__size
+            ($end + <$ty as $crate::uniforms::Std140Field>::ALIGNMENT - 1)
+                / <$ty as $crate::uniforms::Std140Field>::ALIGNMENT
+                * <$ty as $crate::uniforms::Std140Field>::ALIGNMENT
+                + <$ty as $crate::uniforms::Std140Field>::SIZE;
+            $($rest,)*)
+    );
+
+    ($struct_name:ident, $($field_name:ident: $field_ty:ty),+,) => (
+        implement_std140_block!($struct_name, $($field_name: $field_ty),+);
+    );
+
+    ($struct_name:ident, $($field_name:ident: $field_ty:ty),+) => (
+        unsafe impl $crate::uniforms::AsStd140 for $struct_name {
+            type Storage = [u8; implement_std140_block!(__size 0; $($field_ty,)+)];
+
+            #[allow(unused_assignments)]
+            fn members() -> Vec<$crate::uniforms::BlockMember> {
+                let mut members = Vec::new();
+                let mut offset = 0;
+
+                $(
+                    offset = $crate::uniforms::align_block_offset(offset,
+                                        <$field_ty as $crate::uniforms::Std140Field>::ALIGNMENT);
+                    members.push($crate::uniforms::BlockMember {
+                        name: stringify!($field_name),
+                        offset: offset,
+                        matches: <$field_ty as $crate::uniforms::UniformBlock>::matches,
+                        build_layout: <$field_ty as $crate::uniforms::UniformBlock>::build_layout,
+                        matches_strides:
+                            <$field_ty as $crate::uniforms::UniformBlock>::matches_strides,
+                    });
+                    offset += <$field_ty as $crate::uniforms::Std140Field>::SIZE;
+                )+
+
+                members
+            }
+
+            #[allow(unused_assignments)]
+            fn to_std140(&self) -> Self::Storage {
+                let mut storage: Self::Storage = unsafe { ::std::mem::zeroed() };
+                let mut offset = 0;
+
+                $(
+                    offset = $crate::uniforms::align_block_offset(offset,
+                                        <$field_ty as $crate::uniforms::Std140Field>::ALIGNMENT);
+                    {
+                        let value: $field_ty = self.$field_name;
+                        unsafe {
+                            let dest = (&mut storage as *mut Self::Storage as *mut u8)
+                                            .offset(offset as isize);
+                            ::std::ptr::copy_nonoverlapping(&value as *const $field_ty as *const u8,
+                                dest, <$field_ty as $crate::uniforms::Std140Field>::SIZE);
+                        }
+                    }
+                    offset += <$field_ty as $crate::uniforms::Std140Field>::SIZE;
+                )+
+
+                storage
+            }
+
+            #[allow(unused_assignments)]
+            fn from_std140(storage: &Self::Storage) -> Self {
+                let mut offset = 0;
+
+                $struct_name {
+                    $(
+                        $field_name: {
+                            offset = $crate::uniforms::align_block_offset(offset,
+                                        <$field_ty as $crate::uniforms::Std140Field>::ALIGNMENT);
+                            let value = unsafe {
+                                let src = (storage as *const Self::Storage as *const u8)
+                                                .offset(offset as isize);
+                                ::std::ptr::read_unaligned(src as *const $field_ty)
+                            };
+                            offset += <$field_ty as $crate::uniforms::Std140Field>::SIZE;
+                            value
+                        },
+                    )+
+                }
+            }
+        }
+    );
+}
+
+/// Implements the `glium::uniforms::AsStd430` trait for the given struct, so that it can be
+/// wrapped in a `glium::uniforms::Std430` and uploaded in a `UniformBuffer` that is bound to a
+/// shader storage block with `layout(std430)`.
+///
+/// This works like `implement_std140_block!`, except that the offsets are computed with the
+/// std430 rules. Arrays whose elements are not three-components vectors can be used as members.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Particle {
+///     position: [f32; 3],
+///     weights: [f32; 8],
+/// }
+///
+/// implement_std430_block!(Particle, position: [f32; 3], weights: [f32; 8]);
+///
+/// let particle = glium::uniforms::Std430::new(&Particle {
+///     position: [0.0; 3],
+///     weights: [0.0; 8],
+/// });
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_std430_block {
+    (__align $ty:ty,) => (
+        <$ty as $crate::uniforms::Std430Field>::ALIGNMENT
+    );
+
+    (__align $ty:ty, $($rest:ty,)+) => (
+        $crate::uniforms::max_block_alignment(<$ty as $crate::uniforms::Std430Field>::ALIGNMENT,
+                                              implement_std430_block!(__align $($rest,)+))
+    );
+
+    (__size $align:expr; $end:expr;) => (
+        ($end + $align - 1) / $align * $align
+    );
+
+    (__size $align:expr; $end:expr; $ty:ty, $($rest:ty,)*) => (
+        implement_std430_block!(__size $align;
+            ($end + <$ty as $crate::uniforms::Std430Field>::ALIGNMENT - 1)
+                / <$ty as $crate::uniforms::Std430Field>::ALIGNMENT
+                * <$ty as $crate::uniforms::Std430Field>::ALIGNMENT
+                + <$ty as $crate::uniforms::Std430Field>::SIZE;
+            $($rest,)*)
+    );
+
+    ($struct_name:ident, $($field_name:ident: $field_ty:ty),+,) => (
+        implement_std430_block!($struct_name, $($field_name: $field_ty),+);
+    );
+
+    ($struct_name:ident, $($field_name:ident: $field_ty:ty),+) => (
+        unsafe impl $crate::uniforms::AsStd430 for $struct_name {
+            type Storage = [u8; implement_std430_block!(__size
+                                    implement_std430_block!(__align $($field_ty,)+);
+                                    0; $($field_ty,)+)];
+
+            const ALIGNMENT: usize = implement_std430_block!(__align $($field_ty,)+);
+
+            #[allow(unused_assignments)]
+            fn members() -> Vec<$crate::uniforms::BlockMember> {
+                let mut members = Vec::new();
+                let mut offset = 0;
+
+                $(
+                    offset = $crate::uniforms::align_block_offset(offset,
+                                        <$field_ty as $crate::uniforms::Std430Field>::ALIGNMENT);
+                    members.push($crate::uniforms::BlockMember {
+                        name: stringify!($field_name),
+                        offset: offset,
+                        matches: <$field_ty as $crate::uniforms::UniformBlock>::matches,
+                        build_layout: <$field_ty as $crate::uniforms::UniformBlock>::build_layout,
+                        matches_strides:
+                            <$field_ty as $crate::uniforms::UniformBlock>::matches_strides,
+                    });
+                    offset += <$field_ty as $crate::uniforms::Std430Field>::SIZE;
+                )+
+
+                members
+            }
+
+            #[allow(unused_assignments)]
+            fn to_std430(&self) -> Self::Storage {
+                let mut storage: Self::Storage = unsafe { ::std::mem::zeroed() };
+                let mut offset = 0;
+
+                $(
+                    offset = $crate::uniforms::align_block_offset(offset,
+                                        <$field_ty as $crate::uniforms::Std430Field>::ALIGNMENT);
+                    {
+                        let value: $field_ty = self.$field_name;
+                        unsafe {
+                            let dest = (&mut storage as *mut Self::Storage as *mut u8)
+                                            .offset(offset as isize);
+                            ::std::ptr::copy_nonoverlapping(&value as *const $field_ty as *const u8,
+                                dest, <$field_ty as $crate::uniforms::Std430Field>::SIZE);
+                        }
+                    }
+                    offset += <$field_ty as $crate::uniforms::Std430Field>::SIZE;
+                )+
+
+                storage
+            }
+
+            #[allow(unused_assignments)]
+            fn from_std430(storage: &Self::Storage) -> Self {
+                let mut offset = 0;
+
+                $struct_name {
+                    $(
+                        $field_name: {
+                            offset = $crate::uniforms::align_block_offset(offset,
+                                        <$field_ty as $crate::uniforms::Std430Field>::ALIGNMENT);
+                            let value = unsafe {
+                                let src = (storage as *const Self::Storage as *const u8)
+                                                .offset(offset as isize);
+                                ::std::ptr::read_unaligned(src as *const $field_ty)
+                            };
+                            offset += <$field_ty as $crate::uniforms::Std430Field>::SIZE;
+                            value
+                        },
+                    )+
+                }
+            }
+        }
+    );
+}
+
 /// Builds a program depending on the GLSL version supported by the backend.
 ///
 /// This is implemented with successive calls to `is_glsl_version_supported()`.
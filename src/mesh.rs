@@ -0,0 +1,247 @@
+/*!
+Meshes bundle the vertex buffers, the indices and the parts of a model.
+
+Drawing a model usually requires passing the same vertex buffers and index buffer every time.
+A `Mesh` stores them together, along with a list of submeshes, which are ranges of the indices
+that can be drawn separately (for example because they use a different material).
+
+```no_run
+# #[macro_use] extern crate glium;
+# use glium::Surface;
+# fn main() {
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+# let mut frame = display.draw();
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(Vertex, position);
+
+let vertices = glium::VertexBuffer::new(&display, &[
+    Vertex { position: [-0.5, -0.5] }, Vertex { position: [0.5, -0.5] },
+    Vertex { position: [0.5, 0.5] }, Vertex { position: [-0.5, 0.5] },
+]).unwrap();
+
+let indices = glium::IndexBuffer::new(&display, glium::index::PrimitiveType::TrianglesList,
+                                      &[0u16, 1, 2, 0, 2, 3]).unwrap();
+
+let mesh = glium::mesh::Mesh::new(vertices, indices)
+    .with_submesh(0 .. 3)
+    .with_submesh(3 .. 6);
+
+// drawing the whole mesh
+frame.draw_mesh(&mesh, &program, &uniform! { color: [1.0, 0.0, 0.0f32] },
+                &Default::default()).unwrap();
+
+// drawing each submesh with a different color
+frame.draw_submesh(&mesh, 0, &program, &uniform! { color: [0.0, 1.0, 0.0f32] },
+                   &Default::default()).unwrap();
+frame.draw_submesh(&mesh, 1, &program, &uniform! { color: [0.0, 0.0, 1.0f32] },
+                   &Default::default()).unwrap();
+# }
+```
+
+*/
+use std::ops::Range;
+use std::vec::IntoIter;
+
+use index::{IndexBufferAny, IndicesSource, PrimitiveType};
+use vertex::{MultiVerticesSource, VertexBufferAny, VerticesSource};
+
+/// Vertex buffers, indices and submeshes that are drawn together.
+#[derive(Debug)]
+pub struct Mesh {
+    vertex_buffers: Vec<VertexBufferAny>,
+    indices: Option<IndexBufferAny>,
+    primitives: PrimitiveType,
+    submeshes: Vec<Submesh>,
+}
+
+/// A part of a mesh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submesh {
+    /// The range of indices to draw, or the range of vertices if the mesh doesn't have indices.
+    pub range: Range<usize>,
+
+    /// The lowest and highest vertices used by the indices, if known. Passed to
+    /// `glDrawRangeElements`. Ignored if the mesh doesn't have indices.
+    pub vertices_range: Option<(u32, u32)>,
+}
+
+impl Mesh {
+    /// Builds a new mesh from a vertex buffer and an index buffer.
+    #[inline]
+    pub fn new<V, I>(vertices: V, indices: I) -> Mesh
+                     where V: Into<VertexBufferAny>, I: Into<IndexBufferAny>
+    {
+        let indices = indices.into();
+
+        Mesh {
+            vertex_buffers: vec![vertices.into()],
+            primitives: indices.get_primitives_type(),
+            indices: Some(indices),
+            submeshes: Vec::new(),
+        }
+    }
+
+    /// Builds a new mesh that doesn't use indices. The vertices are assembled in the order in
+    /// which they are in the vertex buffer.
+    #[inline]
+    pub fn without_indices<V>(vertices: V, primitives: PrimitiveType) -> Mesh
+                              where V: Into<VertexBufferAny>
+    {
+        Mesh {
+            vertex_buffers: vec![vertices.into()],
+            indices: None,
+            primitives: primitives,
+            submeshes: Vec::new(),
+        }
+    }
+
+    /// Adds a vertex buffer whose attributes are read in parallel with the other ones.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer doesn't have the same length as the first one.
+    #[inline]
+    pub fn with_vertex_buffer<V>(mut self, vertices: V) -> Mesh where V: Into<VertexBufferAny> {
+        let vertices = vertices.into();
+        assert_eq!(vertices.len(), self.vertex_buffers[0].len());
+        self.vertex_buffers.push(vertices);
+        self
+    }
+
+    /// Adds a submesh that corresponds to a range of indices, or to a range of vertices if the
+    /// mesh doesn't have indices.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of bounds.
+    #[inline]
+    pub fn with_submesh(mut self, range: Range<usize>) -> Mesh {
+        self.add_submesh(Submesh { range: range, vertices_range: None });
+        self
+    }
+
+    /// Adds a submesh and returns its index.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of bounds, or if the vertices range is inverted.
+    pub fn add_submesh(&mut self, submesh: Submesh) -> usize {
+        let len = match self.indices {
+            Some(ref indices) => indices.get_elements_count(),
+            None => self.vertex_buffers[0].len(),
+        };
+
+        assert!(submesh.range.start <= submesh.range.end && submesh.range.end <= len,
+                "The range of the submesh is out of bounds");
+
+        if let Some((min, max)) = submesh.vertices_range {
+            assert!(min <= max);
+        }
+
+        self.submeshes.push(submesh);
+        self.submeshes.len() - 1
+    }
+
+    /// Returns the vertex buffers of the mesh.
+    #[inline]
+    pub fn get_vertex_buffers(&self) -> &[VertexBufferAny] {
+        &self.vertex_buffers
+    }
+
+    /// Returns the index buffer of the mesh, if any.
+    #[inline]
+    pub fn get_index_buffer(&self) -> Option<&IndexBufferAny> {
+        self.indices.as_ref()
+    }
+
+    /// Returns the type of primitives of the mesh.
+    #[inline]
+    pub fn get_primitives_type(&self) -> PrimitiveType {
+        self.primitives
+    }
+
+    /// Returns the list of submeshes.
+    #[inline]
+    pub fn get_submeshes(&self) -> &[Submesh] {
+        &self.submeshes
+    }
+
+    /// Returns the vertex sources to pass to `Surface::draw` to draw the whole mesh.
+    #[inline]
+    pub fn vertices(&self) -> MeshVertices {
+        MeshVertices {
+            buffers: &self.vertex_buffers,
+            range: None,
+        }
+    }
+
+    /// Returns the indices to pass to `Surface::draw` to draw the whole mesh.
+    #[inline]
+    pub fn indices(&self) -> IndicesSource {
+        match self.indices {
+            Some(ref indices) => indices.into(),
+            None => IndicesSource::NoIndices { primitives: self.primitives },
+        }
+    }
+
+    /// Returns the vertex sources and the indices to pass to `Surface::draw` to draw a
+    /// submesh. Returns `None` if there is no submesh with this index.
+    pub fn submesh(&self, index: usize) -> Option<(MeshVertices, IndicesSource)> {
+        let submesh = match self.submeshes.get(index) {
+            Some(s) => s,
+            None => return None,
+        };
+
+        let range = submesh.range.clone();
+
+        Some(match self.indices {
+            Some(ref indices) => {
+                let source = IndicesSource::IndexBuffer {
+                    buffer: indices.as_slice_any().slice(range).unwrap(),
+                    data_type: indices.get_indices_type(),
+                    primitives: self.primitives,
+                    vertices_range: submesh.vertices_range,
+                };
+
+                (self.vertices(), source)
+            },
+            None => {
+                let vertices = MeshVertices {
+                    buffers: &self.vertex_buffers,
+                    range: Some((range.start, range.end)),
+                };
+
+                (vertices, IndicesSource::NoIndices { primitives: self.primitives })
+            },
+        })
+    }
+}
+
+/// Vertex sources of a `Mesh`, or of one of its submeshes.
+#[derive(Copy, Clone)]
+pub struct MeshVertices<'a> {
+    buffers: &'a [VertexBufferAny],
+    range: Option<(usize, usize)>,
+}
+
+impl<'a> MultiVerticesSource<'a> for MeshVertices<'a> {
+    type Iterator = IntoIter<VerticesSource<'a>>;
+
+    fn iter(self) -> IntoIter<VerticesSource<'a>> {
+        let range = self.range;
+
+        self.buffers.iter().map(|buffer| {
+            let slice = match range {
+                Some((start, end)) => buffer.as_slice_any().slice(start .. end).unwrap(),
+                None => buffer.as_slice_any(),
+            };
+
+            VerticesSource::VertexBuffer(slice, buffer.get_bindings(), None)
+        }).collect::<Vec<_>>().into_iter()
+    }
+}
@@ -44,7 +44,10 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
 
         // binding source framebuffer
         if ctxt.state.read_framebuffer != source {
-            if ctxt.version >= &Version(Api::Gl, 3, 0) {
+            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+               ctxt.extensions.gl_angle_framebuffer_blit
+            {
                 ctxt.gl.BindFramebuffer(gl::READ_FRAMEBUFFER, source);
                 ctxt.state.read_framebuffer = source;
 
@@ -56,7 +59,10 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
 
         // binding target framebuffer
         if ctxt.state.draw_framebuffer != target {
-            if ctxt.version >= &Version(Api::Gl, 3, 0) {
+            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+               ctxt.extensions.gl_angle_framebuffer_blit
+            {
                 ctxt.gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, target);
                 ctxt.state.draw_framebuffer = target;
 
@@ -67,7 +73,7 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
         }
 
         // doing the blit
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             ctxt.gl.BlitFramebuffer(src_rect.left as gl::types::GLint,
                 src_rect.bottom as gl::types::GLint,
                 (src_rect.left + src_rect.width) as gl::types::GLint,
@@ -76,6 +82,16 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
                 (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
                 (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
 
+        } else if ctxt.extensions.gl_angle_framebuffer_blit {
+            // note that ANGLE doesn't support scaling or flipping the image
+            ctxt.gl.BlitFramebufferANGLE(src_rect.left as gl::types::GLint,
+                src_rect.bottom as gl::types::GLint,
+                (src_rect.left + src_rect.width) as gl::types::GLint,
+                (src_rect.bottom + src_rect.height) as gl::types::GLint,
+                target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
+                (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
+                (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
+
         } else {
             ctxt.gl.BlitFramebufferEXT(src_rect.left as gl::types::GLint,
                 src_rect.bottom as gl::types::GLint,
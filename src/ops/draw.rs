@@ -1,12 +1,15 @@
 use std::ptr;
 
+use smallvec::SmallVec;
+
 use BufferExt;
 use BufferSliceExt;
 use ProgramExt;
 use DrawError;
 use UniformsExt;
 
-use context::Context;
+use context::{Context, ValidationLevel};
+use CapabilitiesSource;
 use ContextExt;
 use TransformFeedbackSessionExt;
 
@@ -24,6 +27,31 @@ use {gl, context, draw_parameters};
 use version::Version;
 use version::Api;
 
+/// Checks the draw parameters against a surface, unless the validation level of the context is
+/// `ValidationLevel::None`.
+pub fn validate_surface(context: &Context, has_depth_buffer: bool,
+                        draw_parameters: &DrawParameters) -> Result<(), DrawError>
+{
+    if context.get_validation_level() == ValidationLevel::None {
+        return Ok(());
+    }
+
+    if !has_depth_buffer && (draw_parameters.depth.test.requires_depth_buffer() ||
+                             draw_parameters.depth.write)
+    {
+        return Err(DrawError::NoDepthBuffer);
+    }
+
+    if let Some(viewport) = draw_parameters.viewport {
+        let max = context.get_capabilities().max_viewport_dims;
+        if viewport.width > max.0 as u32 || viewport.height > max.1 as u32 {
+            return Err(DrawError::ViewportTooLarge);
+        }
+    }
+
+    Ok(())
+}
+
 /// Draws everything.
 pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachments>,
                       vertex_buffers: V, indices: IndicesSource,
@@ -35,31 +63,43 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
     // has started
     let mut fences = Vec::with_capacity(0);
 
+    let primitives = indices.get_primitives_type();
+    if !primitives.is_supported(context) {
+        return Err(DrawError::PrimitiveTypeNotSupported);
+    }
+
     // handling tessellation
-    let vertices_per_patch = match indices.get_primitives_type() {
+    let vertices_per_patch = match primitives {
         index::PrimitiveType::Patches { vertices_per_patch } => {
-            if let Some(max) = context.capabilities().max_patch_vertices {
+            if let Some(max) = context.get_capabilities().max_patch_vertices {
                 if vertices_per_patch == 0 || vertices_per_patch as gl::types::GLint > max {
-                    return Err(DrawError::UnsupportedVerticesPerPatch);
+                    return Err(DrawError::UnsupportedVerticesPerPatch {
+                        requested: vertices_per_patch,
+                        max: max as u32,
+                    });
                 }
             } else {
                 return Err(DrawError::TessellationNotSupported);
             }
 
-            // TODO: programs created from binaries have the wrong value
-            // for `has_tessellation_shaders`
-            /*if !program.has_tessellation_shaders() {    // TODO:
-                panic!("Default tessellation level is not supported yet");
-            }*/
+            if !program.has_tessellation_evaluation_shader() {
+                return Err(DrawError::PatchesWithoutTessellation);
+            }
 
             Some(vertices_per_patch)
         },
         _ => {
-            // TODO: programs created from binaries have the wrong value
-            // for `has_tessellation_shaders`
-            /*if program.has_tessellation_shaders() {
+            if program.has_tessellation_shaders() {
                 return Err(DrawError::TessellationWithoutPatches);
-            }*/
+            }
+
+            // with tessellation, the geometry shader receives the output of the tessellation
+            // evaluation shader instead
+            if let Some(input) = program.get_geometry_input_primitives() {
+                if !input.accepts(primitives) {
+                    return Err(DrawError::GeometryShaderInputMismatch);
+                }
+            }
 
             None
         },
@@ -68,6 +108,10 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
     // starting the state changes
     let mut ctxt = context.make_current();
 
+    if context::check_context_lost(&mut ctxt) {
+        return Err(DrawError::ContextLost);
+    }
+
     // handling vertices source
     let (vertices_count, instances_count, base_vertex) = {
         let index_buffer = match indices {
@@ -93,25 +137,25 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                                                        use_base_vertex);
         // number of vertices in the vertices sources, or `None` if there is a mismatch
         let mut vertices_count: Option<usize> = None;
-        // number of instances to draw
-        let mut instances_count: Option<usize> = None;
+        // number of instances covered by each per-instance source, and its divisor
+        let mut instance_sources: SmallVec<[(usize, usize); 4]> = SmallVec::new();
 
         for src in vertex_buffers.iter() {
             match src {
-                VerticesSource::VertexBuffer(buffer, format, per_instance) => {
+                VerticesSource::VertexBuffer(buffer, format, divisor) => {
                     // TODO: assert!(buffer.get_elements_size() == total_size(format));
 
                     if let Some(fence) = buffer.add_fence() {
                         fences.push(fence);
                     }
 
-                    binder = binder.add(&buffer, format, if per_instance { Some(1) } else { None });
+                    binder = binder.add(&buffer, format, divisor);
                 },
                 _ => {}
             }
 
             match src {
-                VerticesSource::VertexBuffer(ref buffer, _, false) => {
+                VerticesSource::VertexBuffer(ref buffer, _, None) => {
                     if let Some(curr) = vertices_count {
                         if curr != buffer.get_elements_count() {
                             vertices_count = None;
@@ -121,14 +165,9 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                         vertices_count = Some(buffer.get_elements_count());
                     }
                 },
-                VerticesSource::VertexBuffer(ref buffer, _, true) => {
-                    if let Some(curr) = instances_count {
-                        if curr != buffer.get_elements_count() {
-                            return Err(DrawError::InstancesCountMismatch);
-                        }
-                    } else {
-                        instances_count = Some(buffer.get_elements_count());
-                    }
+                VerticesSource::VertexBuffer(ref buffer, _, Some(divisor)) => {
+                    let count = buffer.get_elements_count() * divisor as usize;
+                    instance_sources.push((count, divisor as usize));
                 },
                 VerticesSource::Marker { len, per_instance } if !per_instance => {
                     if let Some(curr) = vertices_count {
@@ -141,19 +180,22 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                     }
                 },
                 VerticesSource::Marker { len, per_instance } if per_instance => {
-                    if let Some(curr) = instances_count {
-                        if curr != len {
-                            return Err(DrawError::InstancesCountMismatch);
-                        }
-                    } else {
-                        instances_count = Some(len);
-                    }
+                    instance_sources.push((len, 1));
                 },
                 _ => ()
             }
         }
 
-        (vertices_count, instances_count, binder.bind().unwrap_or(0))
+        // the number of instances is the smallest number covered by a source ; the other
+        // sources can only cover more instances if their last element is partially used
+        let instances_count = instance_sources.iter().map(|s| s.0).min();
+        if let Some(count) = instances_count {
+            if instance_sources.iter().any(|&(covered, divisor)| covered - count >= divisor) {
+                return Err(DrawError::InstancesCountMismatch);
+            }
+        }
+
+        (vertices_count, instances_count, try!(binder.bind(draw_parameters.ignore_missing_attributes)).unwrap_or(0))
     };
 
     // binding the FBO to draw upon
@@ -164,7 +206,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
     // binding the program and uniforms
     program.use_program(&mut ctxt);
-    try!(uniforms.bind_uniforms(&mut ctxt, program, &mut fences));
+    try!(uniforms.bind_uniforms(&mut ctxt, program, draw_parameters.missing_uniforms,
+                                &mut fences));
 
     // sync-ing draw_parameters
     unsafe {
@@ -184,7 +227,7 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
     // TODO: make this code more readable
     {
         match &indices {
-            &IndicesSource::IndexBuffer { ref buffer, data_type, primitives } => {
+            &IndicesSource::IndexBuffer { ref buffer, data_type, primitives, vertices_range } => {
                 let ptr: *const u8 = ptr::null_mut();
                 let ptr = unsafe { ptr.offset(buffer.get_offset_bytes() as isize) };
 
@@ -221,6 +264,14 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                                 unreachable!();
                             }
 
+                        } else if uses_angle_instancing(&ctxt) {
+                            ctxt.gl.DrawElementsInstancedANGLE(primitives.to_glenum(),
+                                                               buffer.get_elements_count() as
+                                                               gl::types::GLsizei,
+                                                               data_type.to_glenum(),
+                                                               ptr as *const _,
+                                                               instances_count as
+                                                               gl::types::GLsizei);
                         } else {
                             ctxt.gl.DrawElementsInstanced(primitives.to_glenum(),
                                                           buffer.get_elements_count() as
@@ -230,6 +281,42 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                                                           instances_count as gl::types::GLsizei);
                         }
 
+                    } else if let Some((min, max)) = vertices_range.and_then(|r| {
+                        if supports_draw_range_elements(&ctxt) { Some(r) } else { None }
+                    }) {
+                        if base_vertex != 0 {
+                            if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                               ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                               ctxt.extensions.gl_arb_draw_elements_base_vertex
+                            {
+                                ctxt.gl.DrawRangeElementsBaseVertex(primitives.to_glenum(),
+                                                                    min, max,
+                                                                    buffer.get_elements_count() as
+                                                                    gl::types::GLsizei,
+                                                                    data_type.to_glenum(),
+                                                                    ptr as *const _,
+                                                                    base_vertex);
+
+                            } else if ctxt.extensions.gl_oes_draw_elements_base_vertex {
+                                ctxt.gl.DrawRangeElementsBaseVertexOES(primitives.to_glenum(),
+                                                                       min, max,
+                                                                       buffer.get_elements_count()
+                                                                       as gl::types::GLsizei,
+                                                                       data_type.to_glenum(),
+                                                                       ptr as *const _,
+                                                                       base_vertex);
+                            } else {
+                                unreachable!();
+                            }
+
+                        } else {
+                            ctxt.gl.DrawRangeElements(primitives.to_glenum(), min, max,
+                                                      buffer.get_elements_count() as
+                                                      gl::types::GLsizei,
+                                                      data_type.to_glenum(),
+                                                      ptr as *const _);
+                        }
+
                     } else {
                         if base_vertex != 0 {
                             if ctxt.version >= &Version(Api::Gl, 3, 2) ||
@@ -312,9 +399,15 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
                 unsafe {
                     if let Some(instances_count) = instances_count {
-                        ctxt.gl.DrawArraysInstanced(primitives.to_glenum(), base_vertex,
-                                                    vertices_count as gl::types::GLsizei,
-                                                    instances_count as gl::types::GLsizei);
+                        if uses_angle_instancing(&ctxt) {
+                            ctxt.gl.DrawArraysInstancedANGLE(primitives.to_glenum(), base_vertex,
+                                                             vertices_count as gl::types::GLsizei,
+                                                             instances_count as gl::types::GLsizei);
+                        } else {
+                            ctxt.gl.DrawArraysInstanced(primitives.to_glenum(), base_vertex,
+                                                        vertices_count as gl::types::GLsizei,
+                                                        instances_count as gl::types::GLsizei);
+                        }
                     } else {
                         ctxt.gl.DrawArrays(primitives.to_glenum(), base_vertex,
                                            vertices_count as gl::types::GLsizei);
@@ -343,3 +436,16 @@ unsafe fn sync_vertices_per_patch(ctxt: &mut context::CommandContext, vertices_p
         }
     }
 }
+
+/// Returns true if instanced draw commands must use the functions of `GL_ANGLE_instanced_arrays`,
+/// which is how ANGLE exposes instancing with OpenGL ES 2.
+#[inline]
+fn uses_angle_instancing(ctxt: &context::CommandContext) -> bool {
+    ctxt.extensions.gl_angle_instanced_arrays && !(ctxt.version >= &Version(Api::GlEs, 3, 0))
+}
+
+/// Returns true if `glDrawRangeElements` is available.
+#[inline]
+fn supports_draw_range_elements(ctxt: &context::CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 1, 2) || ctxt.version >= &Version(Api::GlEs, 3, 0)
+}
@@ -1,10 +1,16 @@
 use std::ptr;
 use std::fmt;
+use std::mem;
+use std::slice;
 use std::error::Error;
 
 use pixel_buffer::PixelBuffer;
 use texture::ClientFormat;
 use texture::PixelValue;
+use texture::PixelLayout;
+use texture::ChannelOrder;
+use texture::TextureCreationError;
+use vertex::F16;
 use image_format::{TextureFormatRequest, TextureFormat};
 
 use fbo;
@@ -20,11 +26,16 @@ use version::Version;
 use version::Api;
 
 /// A source for reading pixels.
+#[derive(Copy, Clone)]
 pub enum Source<'a> {
     /// A regular framebuffer attachment.
     Attachment(&'a fbo::RegularAttachment<'a>),
     // TODO: use a Rust enum
     DefaultFramebuffer(gl::types::GLenum),
+    /// The depth buffer of a framebuffer, or of the default framebuffer if `None`.
+    DepthBuffer(Option<&'a fbo::ValidatedAttachments<'a>>),
+    /// The stencil buffer of a framebuffer, or of the default framebuffer if `None`.
+    StencilBuffer(Option<&'a fbo::ValidatedAttachments<'a>>),
 }
 
 impl<'a> From<&'a fbo::RegularAttachment<'a>> for Source<'a> {
@@ -37,6 +48,9 @@ impl<'a> From<&'a fbo::RegularAttachment<'a>> for Source<'a> {
 /// A destination for reading pixels.
 pub enum Destination<'a, P> where P: PixelValue {
     Memory(&'a mut Vec<P>),
+    /// Memory whose layout is described by a `PixelLayout`. Pixels outside of the rectangle are
+    /// left untouched.
+    MemoryWithLayout(&'a mut [P], &'a PixelLayout),
     PixelBuffer(&'a PixelBuffer<P>),
     // TODO: texture with glCopyTexSubImage2D
 }
@@ -57,6 +71,7 @@ impl<'a, P> From<&'a PixelBuffer<P>> for Destination<'a, P> where P: PixelValue
 
 /// Error that can happen while reading.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ReadError {
     /// The implementation doesn't support converting to the requested output format.
     ///
@@ -73,6 +88,18 @@ pub enum ReadError {
     /// Clamping the values is not supported by the implementation.
     ClampingNotSupported,
 
+    /// The surface doesn't have a depth buffer.
+    NoDepthBuffer,
+
+    /// The surface doesn't have a stencil buffer.
+    NoStencilBuffer,
+
+    /// The surface doesn't have a color buffer.
+    NoColorBuffer,
+
+    /// Failed to create the intermediate texture that the content is copied to.
+    TextureCreation(TextureCreationError),
+
     // TODO: context lost
 }
 
@@ -92,37 +119,59 @@ impl Error for ReadError {
                 "The implementation doesn't support reading a depth, depth-stencil or stencil attachment",
             ClampingNotSupported =>
                 "Clamping the values is not supported by the implementation",
+            NoDepthBuffer =>
+                "The surface doesn't have a depth buffer",
+            NoStencilBuffer =>
+                "The surface doesn't have a stencil buffer",
+            NoColorBuffer =>
+                "The surface doesn't have a color buffer",
+            TextureCreation(_) =>
+                "Failed to create the intermediate texture that the content is copied to",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::ReadError::*;
+        match *self {
+            TextureCreation(ref err) => Some(err),
+            _ => None,
         }
     }
 }
 
+impl From<TextureCreationError> for ReadError {
+    #[inline]
+    fn from(err: TextureCreationError) -> ReadError {
+        ReadError::TextureCreation(err)
+    }
+}
+
 /// Reads pixels from the source into the destination.
 ///
 /// Panics if the destination is not large enough.
 ///
-/// The `(u8, u8, u8, u8)` format is guaranteed to be supported.
+/// The `(u8, u8, u8, u8)` format is guaranteed to be supported. With OpenGL ES, other formats
+/// are read directly if they match the implementation-defined format, and are otherwise
+/// converted in software from `(u8, u8, u8, u8)` when the destination is `Destination::Memory`.
 // TODO: differentiate between GL_* and GL_*_INTEGER
 #[inline]
-pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect, dest: D,
-                         clamp: bool) -> Result<(), ReadError>
-                         where S: Into<Source<'a>>, D: Into<Destination<'a, T>>,
-                               T: PixelValue
+pub fn read<'a, 'b, S, D, T>(ctxt: &mut CommandContext, source: S, rect: &Rect, dest: D,
+                             clamp: bool, order: ChannelOrder) -> Result<(), ReadError>
+                             where S: Into<Source<'a>>, D: Into<Destination<'b, T>>,
+                                   T: PixelValue
+{
+    read_impl(ctxt, source.into(), rect, dest.into(), clamp, order)
+}
+
+fn read_impl<'a, 'b, T>(mut ctxt: &mut CommandContext, source: Source<'a>, rect: &Rect,
+                        dest: Destination<'b, T>, clamp: bool, order: ChannelOrder)
+                        -> Result<(), ReadError>
+                        where T: PixelValue
 {
-    let source = source.into();
-    let dest = dest.into();
     let output_pixel_format = <T as PixelValue>::get_format();
 
     let pixels_to_read = rect.width * rect.height;
 
-    // checking that the output format is supported
-    // OpenGL supported everything, while OpenGL ES only supports U8U8U8U8 plus an additional
-    // implementation-defined format
-    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format != ClientFormat::U8U8U8U8 {
-        // TODO: GLES is guaranteed to support GL_RGBA and an implementation-defined format
-        //       queried with GL_IMPLEMENTATION_COLOR_READ_FORMAT. We only handle GL_RGBA.
-        return Err(ReadError::OutputFormatNotSupported);
-    }
-
     // handling clamping
     if ctxt.version >= &Version(Api::Gl, 3, 0) {
         unsafe {
@@ -151,6 +200,12 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
         Source::DefaultFramebuffer(read_buffer) => {
             FramebuffersContainer::bind_default_framebuffer_for_reading(&mut ctxt, read_buffer);
         },
+        Source::DepthBuffer(attachments) | Source::StencilBuffer(attachments) => {
+            unsafe {
+                FramebuffersContainer::bind_framebuffer_for_reading_depth_stencil(&mut ctxt,
+                                                                                  attachments)
+            };
+        },
     };
 
     // determining what kind of data we are reading
@@ -177,6 +232,8 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
         Source::DefaultFramebuffer(read_buffer) => {
             (false, ReadSourceType::Color)       // FIXME: wrong
         },
+        Source::DepthBuffer(_) => (false, ReadSourceType::Depth),
+        Source::StencilBuffer(_) => (false, ReadSourceType::Stencil),
     };
 
     // OpenGL ES doesn't support reading from depth, stencil or depth-stencil attachments by default
@@ -201,17 +258,60 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
             client_format_to_gl_enum(&output_pixel_format, integer)
         },
         ReadSourceType::Depth => {
-            unimplemented!()        // TODO:
             // TODO: NV_depth_buffer_float2
-            //(gl::DEPTH_COMPONENT, )
+            match output_pixel_format {
+                ClientFormat::U16 => (gl::DEPTH_COMPONENT, gl::UNSIGNED_SHORT),
+                ClientFormat::U32 => (gl::DEPTH_COMPONENT, gl::UNSIGNED_INT),
+                ClientFormat::F32 => (gl::DEPTH_COMPONENT, gl::FLOAT),
+                _ => return Err(ReadError::OutputFormatNotSupported),
+            }
         },
         ReadSourceType::DepthStencil => unimplemented!(),        // FIXME: only 24_8 is possible and there's no client format in the enum that corresponds to 24_8
         ReadSourceType::Stencil => {
-            unimplemented!()        // TODO:
-            //(gl::STENCIL_INDEX, )
+            match output_pixel_format {
+                ClientFormat::U8 => (gl::STENCIL_INDEX, gl::UNSIGNED_BYTE),
+                ClientFormat::U16 => (gl::STENCIL_INDEX, gl::UNSIGNED_SHORT),
+                ClientFormat::U32 => (gl::STENCIL_INDEX, gl::UNSIGNED_INT),
+                _ => return Err(ReadError::OutputFormatNotSupported),
+            }
         },
     };
 
+    // applying the order of the components
+    let format = match (order, format) {
+        (ChannelOrder::Bgra, gl::RGB) => gl::BGR,
+        (ChannelOrder::Bgra, gl::RGBA) => gl::BGRA,
+        (ChannelOrder::Bgra, gl::RGB_INTEGER) => gl::BGR_INTEGER,
+        (ChannelOrder::Bgra, gl::RGBA_INTEGER) => gl::BGRA_INTEGER,
+        (_, format) => format,
+    };
+
+    // checking that the output format is supported
+    // OpenGL supports everything, while OpenGL ES only supports `GL_RGBA` with
+    // `GL_UNSIGNED_BYTE` plus an implementation-defined format, and `GL_BGRA` with
+    // `GL_UNSIGNED_BYTE` if `GL_EXT_read_format_bgra` is supported (like with ANGLE, whose
+    // default framebuffer is in BGRA)
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) && format != gl::DEPTH_COMPONENT &&
+       format != gl::STENCIL_INDEX && (format, gltype) != (gl::RGBA, gl::UNSIGNED_BYTE) &&
+       !(ctxt.extensions.gl_ext_read_format_bgra && (format, gltype) == (gl::BGRA, gl::UNSIGNED_BYTE))
+    {
+        let (implementation_format, implementation_type) = unsafe {
+            let mut format = 0;
+            let mut ty = 0;
+            ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_FORMAT, &mut format);
+            ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_TYPE, &mut ty);
+            (format as gl::types::GLenum, ty as gl::types::GLenum)
+        };
+
+        if (format, gltype) != (implementation_format, implementation_type) {
+            if integer {
+                return Err(ReadError::OutputFormatNotSupported);
+            }
+
+            return read_with_conversion(ctxt, source, rect, dest, clamp, order);
+        }
+    }
+
     // reading
     unsafe {
         match dest {
@@ -221,7 +321,7 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
                 BufferAny::unbind_pixel_pack(ctxt);
 
                 // adjusting data alignement
-                let ptr = buf.as_mut_ptr() as *mut D;
+                let ptr = buf.as_mut_ptr() as *mut T;
                 let ptr = ptr as usize;
                 if (ptr % 8) == 0 {
                 } else if (ptr % 4) == 0 && ctxt.state.pixel_store_pack_alignment != 4 {
@@ -244,6 +344,73 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
                 *dest = buf;
             },
 
+            Destination::MemoryWithLayout(dest, layout) => {
+                let pixel_size = mem::size_of::<T>();
+                assert!(dest.len() * pixel_size >=
+                        layout.required_bytes(rect.width, rect.height, pixel_size),
+                        "The destination is too small for the rectangle and the layout");
+
+                if pixels_to_read == 0 {
+                    return Ok(());
+                }
+
+                BufferAny::unbind_pixel_pack(ctxt);
+
+                let supported = ctxt.version >= &Version(Api::Gl, 1, 0) ||
+                                ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                                ctxt.extensions.gl_nv_pack_subimage;
+
+                if supported {
+                    if ctxt.state.pixel_store_pack_alignment != layout.alignment as gl::types::GLint {
+                        ctxt.state.pixel_store_pack_alignment = layout.alignment as gl::types::GLint;
+                        ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT,
+                                            layout.alignment as gl::types::GLint);
+                    }
+
+                    ctxt.gl.PixelStorei(gl::PACK_ROW_LENGTH,
+                                        layout.row_length.unwrap_or(0) as gl::types::GLint);
+                    ctxt.gl.PixelStorei(gl::PACK_SKIP_PIXELS,
+                                        layout.skip_pixels as gl::types::GLint);
+                    ctxt.gl.PixelStorei(gl::PACK_SKIP_ROWS, layout.skip_rows as gl::types::GLint);
+
+                    ctxt.gl.ReadPixels(rect.left as gl::types::GLint,
+                                       rect.bottom as gl::types::GLint,
+                                       rect.width as gl::types::GLsizei,
+                                       rect.height as gl::types::GLsizei, format, gltype,
+                                       dest.as_mut_ptr() as *mut _);
+
+                    // the rest of glium assumes that these parameters are 0
+                    ctxt.gl.PixelStorei(gl::PACK_ROW_LENGTH, 0);
+                    ctxt.gl.PixelStorei(gl::PACK_SKIP_PIXELS, 0);
+                    ctxt.gl.PixelStorei(gl::PACK_SKIP_ROWS, 0);
+
+                } else {
+                    // reading tightly packed rows and copying them to their destination
+                    if ctxt.state.pixel_store_pack_alignment != 1 {
+                        ctxt.state.pixel_store_pack_alignment = 1;
+                        ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
+                    }
+
+                    let row_bytes = rect.width as usize * pixel_size;
+                    let mut buf: Vec<u8> = Vec::with_capacity(row_bytes * rect.height as usize);
+                    ctxt.gl.ReadPixels(rect.left as gl::types::GLint,
+                                       rect.bottom as gl::types::GLint,
+                                       rect.width as gl::types::GLsizei,
+                                       rect.height as gl::types::GLsizei, format, gltype,
+                                       buf.as_mut_ptr() as *mut _);
+                    buf.set_len(row_bytes * rect.height as usize);
+
+                    let stride = layout.row_stride(rect.width, pixel_size);
+                    let offset = layout.offset(rect.width, pixel_size);
+                    let dest = slice::from_raw_parts_mut(dest.as_mut_ptr() as *mut u8,
+                                                         dest.len() * pixel_size);
+                    for (row, data) in buf.chunks(row_bytes).enumerate() {
+                        let start = offset + row * stride;
+                        dest[start .. start + row_bytes].copy_from_slice(data);
+                    }
+                }
+            },
+
             Destination::PixelBuffer(pixel_buffer) => {
                 assert!(pixel_buffer.len() >= pixels_to_read as usize);
 
@@ -261,6 +428,105 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     Ok(())
 }
 
+/// Reads `(u8, u8, u8, u8)` pixels and converts them to the format of the destination.
+///
+/// Only unsigned normalized and floating-point destinations with 8, 16 or 32 bits per component
+/// are supported, and only `Destination::Memory`.
+fn read_with_conversion<'a, 'b, T>(ctxt: &mut CommandContext, source: Source<'a>, rect: &Rect,
+                                   dest: Destination<'b, T>, clamp: bool, order: ChannelOrder)
+                                   -> Result<(), ReadError>
+                                   where T: PixelValue
+{
+    let dest = match dest {
+        Destination::Memory(dest) => dest,
+        _ => return Err(ReadError::OutputFormatNotSupported),
+    };
+
+    let (components, ty) = match <T as PixelValue>::get_format() {
+        ClientFormat::U8 => (1, ConversionType::U8),
+        ClientFormat::U8U8 => (2, ConversionType::U8),
+        ClientFormat::U8U8U8 => (3, ConversionType::U8),
+        ClientFormat::U8U8U8U8 => (4, ConversionType::U8),
+        ClientFormat::U16 => (1, ConversionType::U16),
+        ClientFormat::U16U16 => (2, ConversionType::U16),
+        ClientFormat::U16U16U16 => (3, ConversionType::U16),
+        ClientFormat::U16U16U16U16 => (4, ConversionType::U16),
+        ClientFormat::F16 => (1, ConversionType::F16),
+        ClientFormat::F16F16 => (2, ConversionType::F16),
+        ClientFormat::F16F16F16 => (3, ConversionType::F16),
+        ClientFormat::F16F16F16F16 => (4, ConversionType::F16),
+        ClientFormat::F32 => (1, ConversionType::F32),
+        ClientFormat::F32F32 => (2, ConversionType::F32),
+        ClientFormat::F32F32F32 => (3, ConversionType::F32),
+        ClientFormat::F32F32F32F32 => (4, ConversionType::F32),
+        _ => return Err(ReadError::OutputFormatNotSupported),
+    };
+
+    let mut rgba: Vec<(u8, u8, u8, u8)> = Vec::new();
+    try!(read_impl(ctxt, source, rect, Destination::Memory(&mut rgba), clamp,
+                   ChannelOrder::Rgba));
+
+    match ty {
+        ConversionType::U8 => {
+            let data = convert_pixels(&rgba, components, order, |v| v);
+            *dest = cast_pixels(&data, rgba.len());
+        },
+        ConversionType::U16 => {
+            let data = convert_pixels(&rgba, components, order, |v| v as u16 * 257);
+            *dest = cast_pixels(&data, rgba.len());
+        },
+        ConversionType::F16 => {
+            let data = convert_pixels(&rgba, components, order,
+                                      |v| F16::from_f32(v as f32 / 255.0));
+            *dest = cast_pixels(&data, rgba.len());
+        },
+        ConversionType::F32 => {
+            let data = convert_pixels(&rgba, components, order, |v| v as f32 / 255.0);
+            *dest = cast_pixels(&data, rgba.len());
+        },
+    }
+
+    Ok(())
+}
+
+/// Type of the components when converting pixels in software.
+enum ConversionType { U8, U16, F16, F32 }
+
+/// Converts each component of `(u8, u8, u8, u8)` pixels and keeps the `components` first ones.
+fn convert_pixels<C, F>(pixels: &[(u8, u8, u8, u8)], components: usize, order: ChannelOrder,
+                        convert: F) -> Vec<C>
+                        where F: Fn(u8) -> C
+{
+    let mut data = Vec::with_capacity(pixels.len() * components);
+
+    for &(r, g, b, a) in pixels {
+        let values = match order {
+            ChannelOrder::Bgra if components >= 3 => [b, g, r, a],
+            _ => [r, g, b, a],
+        };
+
+        for &value in &values[.. components] {
+            data.push(convert(value));
+        }
+    }
+
+    data
+}
+
+/// Reinterprets a list of components as a list of pixels.
+fn cast_pixels<C, T>(data: &[C], num_pixels: usize) -> Vec<T> where C: Copy, T: PixelValue {
+    let bytes = data.len() * mem::size_of::<C>();
+    assert_eq!(bytes, num_pixels * mem::size_of::<T>());
+
+    let mut pixels = Vec::with_capacity(num_pixels);
+    unsafe {
+        ptr::copy_nonoverlapping(data.as_ptr() as *const u8, pixels.as_mut_ptr() as *mut u8,
+                                 bytes);
+        pixels.set_len(num_pixels);
+    }
+    pixels
+}
+
 fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
                             -> (gl::types::GLenum, gl::types::GLenum)
 {
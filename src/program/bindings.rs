@@ -0,0 +1,193 @@
+//! Binding points that are assigned once to the uniform blocks and the samplers of a program.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::BuildHasherDefault;
+
+use fnv::FnvHasher;
+
+use utils::bitsfield::Bitsfield;
+
+/// Error that can happen when assigning a binding point to a uniform block or a sampler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BindingError {
+    /// The program doesn't have an active uniform block or sampler with this name.
+    NotFound {
+        /// Name of the uniform block or of the sampler.
+        name: String,
+    },
+
+    /// The binding point is not lower than the number of binding points of the backend.
+    OutOfRange {
+        /// The binding point that has been requested.
+        binding: u32,
+        /// The number of binding points.
+        max: u32,
+    },
+
+    /// The binding point has already been assigned to another uniform block or sampler of the
+    /// program.
+    AlreadyUsed {
+        /// The binding point that has been requested.
+        binding: u32,
+    },
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BindingError::NotFound { ref name } => {
+                write!(fmt, "{}: {}", self.description(), name)
+            },
+            BindingError::OutOfRange { binding, max } => {
+                write!(fmt, "{}: {} (the backend supports {} binding points)",
+                       self.description(), binding, max)
+            },
+            BindingError::AlreadyUsed { binding } => {
+                write!(fmt, "{}: {}", self.description(), binding)
+            },
+        }
+    }
+}
+
+impl Error for BindingError {
+    fn description(&self) -> &str {
+        match *self {
+            BindingError::NotFound { .. } => {
+                "The program has no active uniform block or sampler with this name"
+            },
+            BindingError::OutOfRange { .. } => "The binding point is not supported by the backend",
+            BindingError::AlreadyUsed { .. } => "The binding point is already used by the program",
+        }
+    }
+}
+
+/// The binding point of a uniform block or the texture unit of a sampler.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Binding {
+    /// The binding point or the texture unit that has been assigned, or the one that was used by
+    /// the last draw command if nothing was assigned. `None` if nothing has been bound yet.
+    pub point: Option<u32>,
+
+    /// True if the binding point has been assigned with `set_uniform_block_binding` or
+    /// `set_sampler_binding`. Otherwise glium picks one at each draw command.
+    pub fixed: bool,
+}
+
+/// The binding points of the uniform blocks and of the samplers of a program.
+///
+/// Can be printed with `{}` for debugging purposes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingTable {
+    /// The uniform blocks, sorted by name.
+    pub uniform_blocks: Vec<(String, Binding)>,
+
+    /// The samplers, sorted by name.
+    pub samplers: Vec<(String, Binding)>,
+}
+
+impl fmt::Display for BindingTable {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self.uniform_blocks.iter().map(|e| ("uniform block", e))
+                          .chain(self.samplers.iter().map(|e| ("sampler", e)));
+
+        for (kind, &(ref name, binding)) in entries {
+            try!(write!(fmt, "{} {}: ", kind, name));
+
+            match binding.point {
+                Some(point) => try!(write!(fmt, "{}", point)),
+                None => try!(write!(fmt, "unbound")),
+            }
+
+            if binding.fixed {
+                try!(write!(fmt, " (fixed)"));
+            }
+
+            try!(writeln!(fmt, ""));
+        }
+
+        Ok(())
+    }
+}
+
+/// Stores the binding points that have been assigned to a program.
+pub struct FixedBindings {
+    // binding point of each uniform block, indexed by block id
+    uniform_blocks: RefCell<HashMap<i32, u16, BuildHasherDefault<FnvHasher>>>,
+    // texture unit of each sampler, indexed by location
+    samplers: RefCell<HashMap<i32, u16, BuildHasherDefault<FnvHasher>>>,
+}
+
+impl FixedBindings {
+    /// Builds an empty table.
+    #[inline]
+    pub fn new() -> FixedBindings {
+        FixedBindings {
+            uniform_blocks: RefCell::new(HashMap::with_hasher(Default::default())),
+            samplers: RefCell::new(HashMap::with_hasher(Default::default())),
+        }
+    }
+
+    /// Assigns a binding point to the uniform block with the given id.
+    pub fn set_uniform_block(&self, id: i32, binding: u32, max: u32)
+                             -> Result<(), BindingError>
+    {
+        set_binding(&mut self.uniform_blocks.borrow_mut(), id, binding, max)
+    }
+
+    /// Assigns a texture unit to the sampler at the given location.
+    pub fn set_sampler(&self, location: i32, texture_unit: u32, max: u32)
+                       -> Result<(), BindingError>
+    {
+        set_binding(&mut self.samplers.borrow_mut(), location, texture_unit, max)
+    }
+
+    /// Returns the binding point assigned to a uniform block.
+    #[inline]
+    pub fn get_uniform_block(&self, id: i32) -> Option<u16> {
+        self.uniform_blocks.borrow().get(&id).cloned()
+    }
+
+    /// Returns the texture unit assigned to a sampler.
+    #[inline]
+    pub fn get_sampler(&self, location: i32) -> Option<u16> {
+        self.samplers.borrow().get(&location).cloned()
+    }
+
+    /// Returns true if a sampler has been assigned this texture unit.
+    #[inline]
+    pub fn is_texture_unit_reserved(&self, unit: u16) -> bool {
+        self.samplers.borrow().values().any(|&u| u == unit)
+    }
+
+    /// Marks the binding points and the texture units that have been assigned as used, so that
+    /// they are not picked for the other uniform blocks and samplers.
+    pub fn reserve(&self, texture_units: &mut Bitsfield, buffer_bind_points: &mut Bitsfield) {
+        for &unit in self.samplers.borrow().values() {
+            texture_units.set_used(unit);
+        }
+
+        for &binding in self.uniform_blocks.borrow().values() {
+            buffer_bind_points.set_used(binding);
+        }
+    }
+}
+
+fn set_binding(bindings: &mut HashMap<i32, u16, BuildHasherDefault<FnvHasher>>, key: i32,
+               binding: u32, max: u32) -> Result<(), BindingError>
+{
+    // bitsfields can't contain more than 256 bind points
+    if binding >= max || binding >= 256 {
+        return Err(BindingError::OutOfRange { binding: binding, max: max.min(256) });
+    }
+
+    if bindings.iter().any(|(&k, &b)| k != key && b as u32 == binding) {
+        return Err(BindingError::AlreadyUsed { binding: binding });
+    }
+
+    bindings.insert(key, binding as u16);
+    Ok(())
+}
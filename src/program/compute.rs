@@ -18,14 +18,16 @@ use RawUniformValue;
 
 use program::{COMPILER_GLOBAL_LOCK, ProgramCreationError, Binary, GetBinaryError};
 
-use program::reflection::{Uniform, UniformBlock};
+use program::reflection::{Uniform, UniformBlock, AtomicCounter};
 use program::reflection::{ShaderStage, SubroutineData};
 use program::shader::{build_shader, check_shader_type_compatibility};
 
 use program::raw::RawProgram;
 
 use buffer::BufferSlice;
-use uniforms::Uniforms;
+use uniforms::{MissingUniforms, UniformArray, UniformValue, Uniforms};
+
+use utils::bitsfield::Bitsfield;
 
 /// A combination of compute shaders linked together.
 pub struct ComputeShader {
@@ -35,6 +37,7 @@ pub struct ComputeShader {
 impl ComputeShader {
     /// Returns true if the backend supports compute shaders.
     #[inline]
+    #[cfg_attr(feature = "tokens", deprecated(note = "Use `tokens::ComputeSupport::new` instead"))]
     pub fn is_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
         check_shader_type_compatibility(ctxt, gl::COMPUTE_SHADER)
     }
@@ -146,6 +149,22 @@ impl ComputeShader {
             -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_shader_storage_blocks()
     }
+
+    /// Returns the list of atomic counters.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (name, counter) in program.get_atomic_counters() {
+    ///     println!("Name: {} binding: {} offset: {}", name, counter.binding, counter.offset);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_atomic_counters(&self)
+            -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
 }
 
 impl fmt::Debug for ComputeShader {
@@ -177,11 +196,18 @@ impl ProgramExt for ComputeShader {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext, uniform_location: gl::types::GLint,
+                         value: &UniformArray)
+    {
+        self.raw.set_uniform_array(ctxt, uniform_location, value)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
     {
-        self.raw.set_uniform_block_binding(ctxt, block_location, value)
+        ProgramExt::set_uniform_block_binding(&self.raw, ctxt, block_location, value)
     }
 
     #[inline]
@@ -216,10 +242,63 @@ impl ProgramExt for ComputeShader {
         self.raw.get_shader_storage_blocks()
     }
 
+    #[inline]
+    fn get_atomic_counters(&self)
+                           -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
+
     #[inline]
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
     }
+
+    #[inline]
+    fn get_uniforms(&self) -> &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_uniforms()
+    }
+
+    #[inline]
+    fn get_missing_uniforms_policy(&self) -> MissingUniforms {
+        self.raw.get_missing_uniforms_policy()
+    }
+
+    #[inline]
+    fn has_default_uniforms(&self) -> bool {
+        self.raw.has_default_uniforms()
+    }
+
+    #[inline]
+    fn visit_default_uniforms(&self, output: &mut FnMut(&str, UniformValue)) {
+        self.raw.visit_default_uniforms(output)
+    }
+
+    #[inline]
+    fn warn_missing_uniform(&self, name: &str) {
+        self.raw.warn_missing_uniform(name)
+    }
+
+    #[inline]
+    fn get_fixed_uniform_block_binding(&self, block_id: i32) -> Option<u16> {
+        self.raw.get_fixed_uniform_block_binding(block_id)
+    }
+
+    #[inline]
+    fn get_fixed_texture_unit(&self, location: gl::types::GLint) -> Option<u16> {
+        self.raw.get_fixed_texture_unit(location)
+    }
+
+    #[inline]
+    fn is_texture_unit_reserved(&self, unit: u16) -> bool {
+        self.raw.is_texture_unit_reserved(unit)
+    }
+
+    #[inline]
+    fn reserve_fixed_bindings(&self, texture_units: &mut Bitsfield,
+                              buffer_bind_points: &mut Bitsfield)
+    {
+        self.raw.reserve_fixed_bindings(texture_units, buffer_bind_points)
+    }
 }
 
 /// Represents a compute shader command waiting to be dispatched.
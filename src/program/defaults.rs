@@ -0,0 +1,73 @@
+//! Default values of the uniforms of a program, and what to do with the uniforms that have none.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasherDefault;
+
+use fnv::FnvHasher;
+
+use uniforms::{AsUniformValue, MissingUniforms, UniformValue};
+
+/// Stores the default values of the uniforms of a program.
+pub struct UniformDefaults {
+    policy: Cell<MissingUniforms>,
+    values: RefCell<HashMap<String, Box<AsUniformValue>, BuildHasherDefault<FnvHasher>>>,
+    // uniforms for which a warning has already been printed
+    warned: RefCell<HashSet<String, BuildHasherDefault<FnvHasher>>>,
+}
+
+impl UniformDefaults {
+    /// Builds an empty list with the `Ignore` policy.
+    #[inline]
+    pub fn new() -> UniformDefaults {
+        UniformDefaults {
+            policy: Cell::new(MissingUniforms::Ignore),
+            values: RefCell::new(HashMap::with_hasher(Default::default())),
+            warned: RefCell::new(HashSet::with_hasher(Default::default())),
+        }
+    }
+
+    /// Returns what to do with the uniforms that have no value.
+    #[inline]
+    pub fn get_policy(&self) -> MissingUniforms {
+        self.policy.get()
+    }
+
+    /// Changes what to do with the uniforms that have no value.
+    #[inline]
+    pub fn set_policy(&self, policy: MissingUniforms) {
+        self.policy.set(policy);
+    }
+
+    /// Sets the default value of a uniform, replacing the previous one.
+    #[inline]
+    pub fn set(&self, name: &str, value: Box<AsUniformValue>) {
+        self.values.borrow_mut().insert(name.to_owned(), value);
+    }
+
+    /// Removes the default value of a uniform. Returns false if there was none.
+    #[inline]
+    pub fn remove(&self, name: &str) -> bool {
+        self.values.borrow_mut().remove(name).is_some()
+    }
+
+    /// Returns true if no uniform has a default value.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.borrow().is_empty()
+    }
+
+    /// Calls `output` with the name and the default value of each uniform.
+    pub fn visit_values(&self, output: &mut FnMut(&str, UniformValue)) {
+        for (name, value) in self.values.borrow().iter() {
+            output(name, value.as_uniform_value());
+        }
+    }
+
+    /// Prints a warning about a uniform that has no value, unless it has already been done.
+    pub fn warn_once(&self, name: &str) {
+        if self.warned.borrow_mut().insert(name.to_owned()) {
+            eprintln!("glium: the uniform `{}` has no value, it keeps its previous value", name);
+        }
+    }
+}
@@ -9,13 +9,18 @@ use gl;
 use version::Api;
 use version::Version;
 
+pub use self::bindings::{Binding, BindingError, BindingTable};
 pub use self::compute::{ComputeShader, ComputeCommand};
 pub use self::program::Program;
 pub use self::reflection::{Uniform, UniformBlock, BlockLayout, OutputPrimitives};
+pub use self::reflection::InputPrimitives;
+pub use self::reflection::AtomicCounter;
 pub use self::reflection::{Attribute, TransformFeedbackVarying, TransformFeedbackBuffer, TransformFeedbackMode};
 pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform};
 
+mod bindings;
 mod compute;
+mod defaults;
 mod program;
 mod raw;
 mod reflection;
@@ -64,6 +69,7 @@ lazy_static! {
 
 /// Error that can be triggered when creating a `Program`.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum ProgramCreationError {
     /// Error while compiling one of the shaders.
     CompilationError(String),
@@ -129,6 +135,7 @@ impl Error for ProgramCreationError {
 
 /// Error type that is returned by the `program!` macro.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum ProgramChooserCreationError {
     /// No available version has been found.
     NoVersion,
@@ -155,7 +162,7 @@ impl Error for ProgramChooserCreationError {
     }
 
     #[inline]
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::ProgramChooserCreationError::*;
         match *self {
             ProgramCreationError(ref err) => Some(err),
@@ -172,6 +179,7 @@ impl From<ProgramCreationError> for ProgramChooserCreationError {
 
 /// Error while retreiving the binary representation of a program.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum GetBinaryError {
     /// The backend doesn't support binary.
     NotSupported,
@@ -20,15 +20,21 @@ use RawUniformValue;
 
 use program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, Binary};
 use program::GetBinaryError;
+use program::bindings::{BindingError, BindingTable};
 
-use program::reflection::{Uniform, UniformBlock, OutputPrimitives};
+use program::reflection::{Uniform, UniformBlock, OutputPrimitives, AtomicCounter};
+use program::reflection::InputPrimitives;
 use program::reflection::{Attribute, TransformFeedbackBuffer};
 use program::reflection::{SubroutineData, ShaderStage, SubroutineUniform};
 use program::shader::build_shader;
 
 use program::raw::RawProgram;
 
-use vertex::VertexFormat;
+use uniforms::{AsUniformValue, MissingUniforms, UniformArray, UniformValue};
+
+use vertex::{TransformFeedbackSessionCreationError, VertexFormat};
+
+use utils::bitsfield::Bitsfield;
 
 /// A combination of shaders linked together.
 pub struct Program {
@@ -178,6 +184,15 @@ impl Program {
         self.raw.get_frag_data_location(name)
     }
 
+    /// Sets the label of this program. If you use an OpenGL debugger, the program will be shown
+    /// with this name.
+    ///
+    /// Returns `Err` if the backend doesn't support labels.
+    #[inline]
+    pub fn set_object_label(&self, label: &str) -> Result<(), ()> {
+        self.raw.set_object_label(label)
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
@@ -230,6 +245,18 @@ impl Program {
         self.raw.transform_feedback_matches(format, stride)
     }
 
+    /// Checks that the transform feedback output of this program matches the specified
+    /// `VertexFormat` and `stride`, and returns what doesn't match.
+    ///
+    /// A varying named `Block.name`, which is a member of an output block, corresponds to the
+    /// attribute `name`.
+    #[inline]
+    pub fn check_transform_feedback_format(&self, format: &VertexFormat, stride: usize)
+                                           -> Result<(), TransformFeedbackSessionCreationError>
+    {
+        self.raw.check_transform_feedback_format(format, stride)
+    }
+
     /// Returns the type of geometry that transform feedback would generate, or `None` if it
     /// depends on the vertex/index data passed when drawing.
     ///
@@ -240,6 +267,16 @@ impl Program {
         self.raw.get_output_primitives()
     }
 
+    /// Returns the type of primitives that the geometry shader receives, or `None` if the
+    /// program doesn't contain a geometry shader.
+    ///
+    /// This corresponds to `GL_GEOMETRY_INPUT_TYPE`. Drawing primitives of another type
+    /// returns a `DrawError::GeometryShaderInputMismatch`.
+    #[inline]
+    pub fn get_geometry_input_primitives(&self) -> Option<InputPrimitives> {
+        self.raw.get_geometry_input_primitives()
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -307,6 +344,106 @@ impl Program {
         self.raw.get_shader_storage_blocks()
     }
 
+    /// Returns the list of atomic counters.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (name, counter) in program.get_atomic_counters() {
+    ///     println!("Name: {} binding: {} offset: {}", name, counter.binding, counter.offset);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_atomic_counters(&self)
+            -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
+
+    /// Assigns a binding point to a uniform block of the program.
+    ///
+    /// By default glium chooses a binding point for each uniform block at each draw command.
+    /// After calling this method, the block always uses `binding`, which makes it possible to
+    /// share a uniform buffer between multiple programs without rebinding it.
+    ///
+    /// Returns an error if the program has no active uniform block named `name`, if `binding`
+    /// is not supported by the backend, or if another block already uses `binding`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// program.set_uniform_block_binding("Lights", 3).unwrap();
+    /// ```
+    #[inline]
+    pub fn set_uniform_block_binding(&self, name: &str, binding: u32)
+                                     -> Result<(), BindingError>
+    {
+        self.raw.set_uniform_block_binding(name, binding)
+    }
+
+    /// Assigns a texture unit to a sampler of the program.
+    ///
+    /// By default glium chooses a texture unit for each sampler at each draw command. After
+    /// calling this method, the sampler always uses `texture_unit`.
+    ///
+    /// Returns an error if the program has no active sampler named `name`, if `texture_unit`
+    /// is not supported by the backend, or if another sampler already uses `texture_unit`.
+    #[inline]
+    pub fn set_sampler_binding(&self, name: &str, texture_unit: u32)
+                               -> Result<(), BindingError>
+    {
+        self.raw.set_sampler_binding(name, texture_unit)
+    }
+
+    /// Changes what to do when drawing with this program while some of its uniforms or uniform
+    /// blocks have no value. The default is `MissingUniforms::Ignore`.
+    ///
+    /// The `missing_uniforms` member of the draw parameters overrides this policy.
+    #[inline]
+    pub fn set_missing_uniforms(&self, policy: MissingUniforms) {
+        self.raw.set_missing_uniforms(policy)
+    }
+
+    /// Sets the value that a uniform takes when drawing without giving it a value, replacing
+    /// any previous default value.
+    ///
+    /// The value can be a struct or an array, in which case `name` is the name of the struct or
+    /// of the array.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// program.set_default_uniform("tint", [1.0f32, 1.0, 1.0, 1.0]);
+    /// ```
+    #[inline]
+    pub fn set_default_uniform<T>(&self, name: &str, value: T)
+                                  where T: AsUniformValue + 'static
+    {
+        self.raw.set_default_uniform(name, value)
+    }
+
+    /// Removes the default value of a uniform. Returns false if it had none.
+    #[inline]
+    pub fn remove_default_uniform(&self, name: &str) -> bool {
+        self.raw.remove_default_uniform(name)
+    }
+
+    /// Returns the binding points of the uniform blocks and the texture units of the samplers
+    /// of the program.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// println!("{}", program.get_bindings());
+    /// ```
+    #[inline]
+    pub fn get_bindings(&self) -> BindingTable {
+        self.raw.get_bindings()
+    }
+
     /// Returns the subroutine uniforms of this program.
     ///
     /// Since subroutine uniforms are unique per shader and *not* per program,
@@ -356,8 +493,10 @@ impl ProgramExt for Program {
         // compatibility was checked at program creation
         if self.uses_point_size && !ctxt.state.enabled_program_point_size {
             unsafe { ctxt.gl.Enable(gl::PROGRAM_POINT_SIZE); }
+            ctxt.state.enabled_program_point_size = true;
         } else if !self.uses_point_size && ctxt.state.enabled_program_point_size {
             unsafe { ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE); }
+            ctxt.state.enabled_program_point_size = false;
         }
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
@@ -384,11 +523,18 @@ impl ProgramExt for Program {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext, uniform_location: gl::types::GLint,
+                         value: &UniformArray)
+    {
+        self.raw.set_uniform_array(ctxt, uniform_location, value)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
     {
-        self.raw.set_uniform_block_binding(ctxt, block_location, value)
+        ProgramExt::set_uniform_block_binding(&self.raw, ctxt, block_location, value)
     }
 
     #[inline]
@@ -423,8 +569,61 @@ impl ProgramExt for Program {
         self.raw.get_shader_storage_blocks()
     }
 
+    #[inline]
+    fn get_atomic_counters(&self)
+                           -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
+
     #[inline]
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
     }
+
+    #[inline]
+    fn get_uniforms(&self) -> &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_uniforms()
+    }
+
+    #[inline]
+    fn get_missing_uniforms_policy(&self) -> MissingUniforms {
+        self.raw.get_missing_uniforms_policy()
+    }
+
+    #[inline]
+    fn has_default_uniforms(&self) -> bool {
+        self.raw.has_default_uniforms()
+    }
+
+    #[inline]
+    fn visit_default_uniforms(&self, output: &mut FnMut(&str, UniformValue)) {
+        self.raw.visit_default_uniforms(output)
+    }
+
+    #[inline]
+    fn warn_missing_uniform(&self, name: &str) {
+        self.raw.warn_missing_uniform(name)
+    }
+
+    #[inline]
+    fn get_fixed_uniform_block_binding(&self, block_id: i32) -> Option<u16> {
+        self.raw.get_fixed_uniform_block_binding(block_id)
+    }
+
+    #[inline]
+    fn get_fixed_texture_unit(&self, location: gl::types::GLint) -> Option<u16> {
+        self.raw.get_fixed_texture_unit(location)
+    }
+
+    #[inline]
+    fn is_texture_unit_reserved(&self, unit: u16) -> bool {
+        self.raw.is_texture_unit_reserved(unit)
+    }
+
+    #[inline]
+    fn reserve_fixed_bindings(&self, texture_units: &mut Bitsfield,
+                              buffer_bind_points: &mut Bitsfield)
+    {
+        self.raw.reserve_fixed_bindings(texture_units, buffer_bind_points)
+    }
 }
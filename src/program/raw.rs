@@ -5,7 +5,8 @@ use version::Version;
 use version::Api;
 
 use backend::Facade;
-use context::Context;
+use context::{self, Context};
+use CapabilitiesSource;
 use ContextExt;
 use UniformsExt;
 
@@ -32,22 +33,28 @@ use BufferExt;
 use BufferSliceExt;
 
 use program::{ProgramCreationError, Binary, GetBinaryError};
+use program::bindings::{Binding, BindingError, BindingTable, FixedBindings};
+use program::defaults::UniformDefaults;
 use program::uniforms_storage::UniformsStorage;
 
 use program::compute::ComputeCommand;
-use program::reflection::{Uniform, UniformBlock, OutputPrimitives};
+use program::reflection::{Uniform, UniformBlock, InputPrimitives, OutputPrimitives};
 use program::reflection::{Attribute, TransformFeedbackMode, TransformFeedbackBuffer};
 use program::reflection::{SubroutineData, ShaderStage};
 use program::reflection::{reflect_uniforms, reflect_attributes, reflect_uniform_blocks};
 use program::reflection::{reflect_transform_feedback, reflect_geometry_output_type};
+use program::reflection::reflect_geometry_input_type;
 use program::reflection::{reflect_tess_eval_output_type, reflect_shader_storage_blocks};
+use program::reflection::{reflect_atomic_counters, AtomicCounter};
 use program::reflection::{reflect_subroutine_data};
 use program::shader::Shader;
 use program::binary_header::{attach_glium_header, process_glium_header};
 
-use uniforms::Uniforms;
+use uniforms::{AsUniformValue, MissingUniforms, UniformArray, UniformValue, Uniforms};
 
-use vertex::VertexFormat;
+use utils::bitsfield::Bitsfield;
+
+use vertex::{TransformFeedbackSessionCreationError, VertexFormat};
 use vertex_array_object::VertexAttributesSystem;
 
 /// A combination of shaders linked together.
@@ -55,6 +62,8 @@ pub struct RawProgram {
     context: Rc<Context>,
     id: Handle,
     uniform_values: UniformsStorage,
+    fixed_bindings: FixedBindings,
+    uniform_defaults: UniformDefaults,
     uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
     uniform_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     subroutine_data: SubroutineData,
@@ -62,7 +71,9 @@ pub struct RawProgram {
     frag_data_locations: RefCell<HashMap<String, Option<u32>, BuildHasherDefault<FnvHasher>>>,
     tf_buffers: Vec<TransformFeedbackBuffer>,
     ssbos: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
+    atomic_counters: HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>>,
     output_primitives: Option<OutputPrimitives>,
+    geometry_input_primitives: Option<InputPrimitives>,
     has_geometry_shader: bool,
     has_tessellation_control_shader: bool,
     has_tessellation_evaluation_shader: bool,
@@ -168,12 +179,19 @@ impl RawProgram {
         let blocks = unsafe { reflect_uniform_blocks(&mut ctxt, id) };
         let tf_buffers = unsafe { reflect_transform_feedback(&mut ctxt, id) };
         let ssbos = unsafe { reflect_shader_storage_blocks(&mut ctxt, id) };
+        let atomic_counters = unsafe { reflect_atomic_counters(&mut ctxt, id) };
         let subroutine_data = unsafe {
             reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
                                     has_tessellation_control_shader,
                                     has_tessellation_evaluation_shader)
             };
 
+        let geometry_input_primitives = if has_geometry_shader {
+            Some(unsafe { reflect_geometry_input_type(&mut ctxt, id) })
+        } else {
+            None
+        };
+
         let output_primitives = if has_geometry_shader {
             Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
         } else if has_tessellation_evaluation_shader {
@@ -187,13 +205,17 @@ impl RawProgram {
             id: id,
             uniforms: uniforms,
             uniform_values: UniformsStorage::new(),
+            fixed_bindings: FixedBindings::new(),
+            uniform_defaults: UniformDefaults::new(),
             uniform_blocks: blocks,
             subroutine_data: subroutine_data,
             attributes: attributes,
             frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
             tf_buffers: tf_buffers,
             ssbos: ssbos,
+            atomic_counters: atomic_counters,
             output_primitives: output_primitives,
+            geometry_input_primitives: geometry_input_primitives,
             has_geometry_shader: has_geometry_shader,
             has_tessellation_control_shader: has_tessellation_control_shader,
             has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
@@ -232,19 +254,27 @@ impl RawProgram {
             id
         };
 
-        let (uniforms, attributes, blocks, tf_buffers, ssbos, subroutine_data) = unsafe {
+        let (uniforms, attributes, blocks, tf_buffers, ssbos, atomic_counters,
+             subroutine_data) = unsafe {
             (
                 reflect_uniforms(&mut ctxt, id),
                 reflect_attributes(&mut ctxt, id),
                 reflect_uniform_blocks(&mut ctxt, id),
                 reflect_transform_feedback(&mut ctxt, id),
                 reflect_shader_storage_blocks(&mut ctxt, id),
+                reflect_atomic_counters(&mut ctxt, id),
                 reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
                                         has_tessellation_control_shader,
                                         has_tessellation_evaluation_shader),
             )
         };
 
+        let geometry_input_primitives = if has_geometry_shader {
+            Some(unsafe { reflect_geometry_input_type(&mut ctxt, id) })
+        } else {
+            None
+        };
+
         let output_primitives = if has_geometry_shader {
             Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
         } else if has_tessellation_evaluation_shader {
@@ -258,13 +288,17 @@ impl RawProgram {
             id: id,
             uniforms: uniforms,
             uniform_values: UniformsStorage::new(),
+            fixed_bindings: FixedBindings::new(),
+            uniform_defaults: UniformDefaults::new(),
             uniform_blocks: blocks,
             subroutine_data: subroutine_data,
             attributes: attributes,
             frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
             tf_buffers: tf_buffers,
             ssbos: ssbos,
+            atomic_counters: atomic_counters,
             output_primitives: output_primitives,
+            geometry_input_primitives: geometry_input_primitives,
             has_geometry_shader: has_geometry_shader,
             has_tessellation_control_shader: has_tessellation_control_shader,
             has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
@@ -358,6 +392,18 @@ impl RawProgram {
         location
     }
 
+    /// Sets the label of this program, for debugging purposes.
+    pub fn set_object_label(&self, label: &str) -> Result<(), ()> {
+        let id = match self.id {
+            Handle::Id(id) => id,
+            // labels can't be used with `GL_ARB_shader_objects`
+            Handle::Handle(_) => return Err(()),
+        };
+
+        let ctxt = self.context.make_current();
+        context::set_object_label(&ctxt, gl::PROGRAM, id, label)
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
@@ -405,28 +451,68 @@ impl RawProgram {
     /// and `stride`.
     ///
     /// The `stride` is the number of bytes between two vertices.
+    #[inline]
     pub fn transform_feedback_matches(&self, format: &VertexFormat, stride: usize) -> bool {
+        self.check_transform_feedback_format(format, stride).is_ok()
+    }
+
+    /// Checks that the transform feedback output of this program matches the specified
+    /// `VertexFormat` and `stride`, and returns what doesn't match.
+    ///
+    /// A varying named `Block.name`, which is a member of an output block, corresponds to the
+    /// attribute `name`.
+    pub fn check_transform_feedback_format(&self, format: &VertexFormat, stride: usize)
+                                           -> Result<(), TransformFeedbackSessionCreationError>
+    {
         // TODO: doesn't support multiple buffers
 
         if self.get_transform_feedback_buffers().len() != 1 {
-            return false;
+            return Err(TransformFeedbackSessionCreationError::WrongVertexFormat);
         }
 
         let buf = &self.get_transform_feedback_buffers()[0];
 
-        if buf.stride != stride {
-            return false;
+        for elem in buf.elements.iter() {
+            let attribute = match format.iter().find(|e| varying_matches(&elem.name, &e.0)) {
+                Some(a) => a,
+                None => return Err(TransformFeedbackSessionCreationError::MissingAttribute {
+                    name: elem.name.clone(),
+                }),
+            };
+
+            if attribute.2 != elem.ty {
+                return Err(TransformFeedbackSessionCreationError::AttributeTypeMismatch {
+                    name: elem.name.clone(),
+                    program: elem.ty,
+                    vertex: attribute.2,
+                });
+            }
+
+            if attribute.1 != elem.offset {
+                return Err(TransformFeedbackSessionCreationError::AttributeOffsetMismatch {
+                    name: elem.name.clone(),
+                    program: elem.offset,
+                    vertex: attribute.1,
+                });
+            }
         }
 
-        for elem in buf.elements.iter() {
-            if format.iter().find(|e| &e.0 == &*elem.name && e.1 == elem.offset && e.2 == elem.ty)
-                            .is_none()
-            {
-                return false;
+        for attribute in format.iter() {
+            if !buf.elements.iter().any(|elem| varying_matches(&elem.name, &attribute.0)) {
+                return Err(TransformFeedbackSessionCreationError::UncapturedAttribute {
+                    name: attribute.0.clone().into_owned(),
+                });
             }
         }
 
-        true
+        if buf.stride != stride {
+            return Err(TransformFeedbackSessionCreationError::StrideMismatch {
+                program: buf.stride,
+                vertex: stride,
+            });
+        }
+
+        Ok(())
     }
 
     /// Returns the type of geometry that transform feedback would generate, or `None` if it
@@ -439,6 +525,15 @@ impl RawProgram {
         self.output_primitives
     }
 
+    /// Returns the type of primitives that the geometry shader receives, or `None` if the
+    /// program doesn't contain a geometry shader.
+    ///
+    /// This corresponds to `GL_GEOMETRY_INPUT_TYPE`.
+    #[inline]
+    pub fn get_geometry_input_primitives(&self) -> Option<InputPrimitives> {
+        self.geometry_input_primitives
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -500,12 +595,111 @@ impl RawProgram {
         &self.ssbos
     }
 
+    /// Returns the list of atomic counters.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (name, counter) in program.get_atomic_counters() {
+    ///     println!("Name: {} binding: {} offset: {}", name, counter.binding, counter.offset);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_atomic_counters(&self)
+            -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        &self.atomic_counters
+    }
+
     /// Returns data associated with the programs subroutines.
     #[inline]
     pub fn get_subroutine_data(&self) -> &SubroutineData {
         &self.subroutine_data
     }
 
+    /// Assigns a binding point to a uniform block of the program.
+    ///
+    /// The binding point is kept for the whole lifetime of the program and is used by all the
+    /// following draw commands, instead of a binding point chosen at each draw.
+    pub fn set_uniform_block_binding(&self, name: &str, binding: u32)
+                                     -> Result<(), BindingError>
+    {
+        let block = match self.uniform_blocks.get(name) {
+            Some(block) => block,
+            None => return Err(BindingError::NotFound { name: name.to_owned() }),
+        };
+
+        let max = self.context.get_capabilities().max_indexed_uniform_buffer as u32;
+        self.fixed_bindings.set_uniform_block(block.id, binding, max)
+    }
+
+    /// Assigns a texture unit to a sampler of the program.
+    ///
+    /// The texture unit is kept for the whole lifetime of the program and is used by all the
+    /// following draw commands, instead of a texture unit chosen at each draw.
+    pub fn set_sampler_binding(&self, name: &str, texture_unit: u32)
+                               -> Result<(), BindingError>
+    {
+        let uniform = match self.uniforms.get(name) {
+            Some(uniform) if uniform.ty.is_sampler() => uniform,
+            _ => return Err(BindingError::NotFound { name: name.to_owned() }),
+        };
+
+        let max = self.context.get_capabilities().max_combined_texture_image_units as u32;
+        self.fixed_bindings.set_sampler(uniform.location, texture_unit, max)
+    }
+
+    /// Returns the binding points of the uniform blocks and of the samplers of the program.
+    pub fn get_bindings(&self) -> BindingTable {
+        let mut uniform_blocks = self.uniform_blocks.iter().map(|(name, block)| {
+            let fixed = self.fixed_bindings.get_uniform_block(block.id);
+            let point = fixed.map(|b| b as u32).or_else(|| {
+                self.uniform_values.get_uniform_block_binding(block.id as gl::types::GLuint)
+            });
+
+            (name.clone(), Binding { point: point, fixed: fixed.is_some() })
+        }).collect::<Vec<_>>();
+
+        let mut samplers = self.uniforms.iter().filter(|&(_, u)| u.ty.is_sampler())
+                               .map(|(name, uniform)|
+        {
+            let fixed = self.fixed_bindings.get_sampler(uniform.location);
+            let point = fixed.map(|u| u as u32).or_else(|| {
+                self.uniform_values.get_signed_int(uniform.location).map(|u| u as u32)
+            });
+
+            (name.clone(), Binding { point: point, fixed: fixed.is_some() })
+        }).collect::<Vec<_>>();
+
+        uniform_blocks.sort_by(|a, b| a.0.cmp(&b.0));
+        samplers.sort_by(|a, b| a.0.cmp(&b.0));
+
+        BindingTable {
+            uniform_blocks: uniform_blocks,
+            samplers: samplers,
+        }
+    }
+
+    /// Changes what to do with the uniforms that have no value when drawing.
+    #[inline]
+    pub fn set_missing_uniforms(&self, policy: MissingUniforms) {
+        self.uniform_defaults.set_policy(policy);
+    }
+
+    /// Sets the value that a uniform takes when drawing without giving it a value.
+    #[inline]
+    pub fn set_default_uniform<T>(&self, name: &str, value: T)
+                                  where T: AsUniformValue + 'static
+    {
+        self.uniform_defaults.set(name, Box::new(value));
+    }
+
+    /// Removes the default value of a uniform. Returns false if it had none.
+    #[inline]
+    pub fn remove_default_uniform(&self, name: &str) -> bool {
+        self.uniform_defaults.remove(name)
+    }
+
     /// Assumes that the program contains a compute shader and executes it.
     ///
     /// # Safety
@@ -532,7 +726,7 @@ impl RawProgram {
         let mut fences = Vec::with_capacity(0);
 
         self.use_program(&mut ctxt);
-        try!(uniforms.bind_uniforms(&mut ctxt, self, &mut fences));
+        try!(uniforms.bind_uniforms(&mut ctxt, self, None, &mut fences));
         ctxt.gl.DispatchCompute(x, y, z);
 
         for fence in fences {
@@ -574,7 +768,7 @@ impl RawProgram {
         self.use_program(&mut ctxt);
 
         let mut fences = Vec::with_capacity(0);
-        try!(uniforms.bind_uniforms(&mut ctxt, self, &mut fences));
+        try!(uniforms.bind_uniforms(&mut ctxt, self, None, &mut fences));
 
         ctxt.gl.DispatchComputeIndirect(offset as gl::types::GLintptr);
 
@@ -624,6 +818,13 @@ impl ProgramExt for RawProgram {
         self.uniform_values.set_uniform_value(ctxt, self.id, uniform_location, value);
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext, uniform_location: gl::types::GLint,
+                         value: &UniformArray)
+    {
+        self.uniform_values.set_uniform_array(ctxt, self.id, uniform_location, value);
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -663,10 +864,63 @@ impl ProgramExt for RawProgram {
         &self.ssbos
     }
 
+    #[inline]
+    fn get_atomic_counters(&self)
+                           -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        &self.atomic_counters
+    }
+
     #[inline]
     fn get_subroutine_data(&self) -> &SubroutineData {
         &self.subroutine_data
     }
+
+    #[inline]
+    fn get_uniforms(&self) -> &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>> {
+        &self.uniforms
+    }
+
+    #[inline]
+    fn get_missing_uniforms_policy(&self) -> MissingUniforms {
+        self.uniform_defaults.get_policy()
+    }
+
+    #[inline]
+    fn has_default_uniforms(&self) -> bool {
+        !self.uniform_defaults.is_empty()
+    }
+
+    #[inline]
+    fn visit_default_uniforms(&self, output: &mut FnMut(&str, UniformValue)) {
+        self.uniform_defaults.visit_values(output)
+    }
+
+    #[inline]
+    fn warn_missing_uniform(&self, name: &str) {
+        self.uniform_defaults.warn_once(name)
+    }
+
+    #[inline]
+    fn get_fixed_uniform_block_binding(&self, block_id: i32) -> Option<u16> {
+        self.fixed_bindings.get_uniform_block(block_id)
+    }
+
+    #[inline]
+    fn get_fixed_texture_unit(&self, location: gl::types::GLint) -> Option<u16> {
+        self.fixed_bindings.get_sampler(location)
+    }
+
+    #[inline]
+    fn is_texture_unit_reserved(&self, unit: u16) -> bool {
+        self.fixed_bindings.is_texture_unit_reserved(unit)
+    }
+
+    #[inline]
+    fn reserve_fixed_bindings(&self, texture_units: &mut Bitsfield,
+                              buffer_bind_points: &mut Bitsfield)
+    {
+        self.fixed_bindings.reserve(texture_units, buffer_bind_points)
+    }
 }
 
 impl Drop for RawProgram {
@@ -798,3 +1052,16 @@ unsafe fn check_program_link_errors(ctxt: &mut CommandContext, id: Handle)
 
     Ok(())
 }
+
+/// True if the transform feedback varying corresponds to the vertex attribute, either because
+/// they have the same name or because the varying is the member `name` of an output block.
+fn varying_matches(varying: &str, attribute: &str) -> bool {
+    if varying == attribute {
+        return true;
+    }
+
+    match varying.rfind('.') {
+        Some(pos) => &varying[pos + 1 ..] == attribute,
+        None => false,
+    }
+}
@@ -13,6 +13,8 @@ use context::CommandContext;
 use version::Version;
 use version::Api;
 
+use index::PrimitiveType;
+use uniforms;
 use uniforms::UniformType;
 use vertex::AttributeType;
 use program;
@@ -31,9 +33,22 @@ pub struct Uniform {
     pub ty: UniformType,
 
     /// If it is an array, the number of elements.
+    ///
+    /// Each element of an array is a separate uniform whose name ends with its index, and only
+    /// the first one contains the size.
     pub size: Option<usize>,
 }
 
+/// Information about an atomic counter (except its name).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AtomicCounter {
+    /// The buffer bind point of the counter, given with `layout(binding = ...)` in the shader.
+    pub binding: u32,
+
+    /// Offset in bytes of the counter from the start of the buffer.
+    pub offset: usize,
+}
+
 /// Information about a uniform block (except its name).
 #[derive(Debug, Clone)]
 pub struct UniformBlock {
@@ -52,6 +67,12 @@ pub struct UniformBlock {
 
     /// Layout of the block.
     pub layout: BlockLayout,
+
+    /// Number of bytes between the start of two consecutive elements of the arrays of the block.
+    ///
+    /// The arrays are indexed by their path in `layout`, without the indices. For example the
+    /// stride of `foo[].bar[]` is stored as `foo.bar`. Arrays whose stride is unknown are absent.
+    pub array_strides: HashMap<String, usize>,
 }
 
 /// Layout of a shader storage buffer or a uniform buffer.
@@ -163,6 +184,41 @@ pub enum TransformFeedbackMode {
     Separate,
 }
 
+/// Type of primitives that a geometry shader receives.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InputPrimitives {
+    /// Points.
+    Points,
+    /// Lines.
+    Lines,
+    /// Lines with adjacency information.
+    LinesAdjacency,
+    /// Triangles.
+    Triangles,
+    /// Triangles with adjacency information.
+    TrianglesAdjacency,
+}
+
+impl InputPrimitives {
+    /// Returns true if drawing primitives of the given type produces this type of input.
+    pub fn accepts(&self, primitives: PrimitiveType) -> bool {
+        match (*self, primitives) {
+            (InputPrimitives::Points, PrimitiveType::Points) => true,
+            (InputPrimitives::Lines, PrimitiveType::LinesList) => true,
+            (InputPrimitives::Lines, PrimitiveType::LineStrip) => true,
+            (InputPrimitives::Lines, PrimitiveType::LineLoop) => true,
+            (InputPrimitives::LinesAdjacency, PrimitiveType::LinesListAdjacency) => true,
+            (InputPrimitives::LinesAdjacency, PrimitiveType::LineStripAdjacency) => true,
+            (InputPrimitives::Triangles, PrimitiveType::TrianglesList) => true,
+            (InputPrimitives::Triangles, PrimitiveType::TriangleStrip) => true,
+            (InputPrimitives::Triangles, PrimitiveType::TriangleFan) => true,
+            (InputPrimitives::TrianglesAdjacency, PrimitiveType::TrianglesListAdjacency) => true,
+            (InputPrimitives::TrianglesAdjacency, PrimitiveType::TriangleStripAdjacency) => true,
+            _ => false,
+        }
+    }
+}
+
 /// Type of primitives that is being output by transform feedback.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum OutputPrimitives {
@@ -263,14 +319,15 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
             continue;
         }
 
-        // We've got an array, first get the base of the name
-        let name_base = uniform.0.split('[').next().unwrap();
+        // We've got an array, first get the base of the name, which can itself be the member of
+        // an array of structs
+        let name_base = &uniform.0[.. uniform.0.len() - 3];
         let uniform_base = uniform.1;
 
-        // Go over all the elements in the array
-        for i in 0..uniform_base.size.unwrap() {
+        // Go over all the elements in the array, the first one keeping the size of the array
+        for i in 0..uniform_base.size.unwrap_or(1) {
             let uniform = Uniform {
-                size: None,
+                size: if i == 0 { uniform_base.size } else { None },
                 location: uniform_base.location + (i as i32),
                 .. uniform_base
             };
@@ -448,6 +505,12 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
         ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
                                     gl::UNIFORM_SIZE, member_size.as_mut_ptr());
 
+        // getting the array strides of the members
+        let mut member_strides = ::std::iter::repeat(0).take(num_members as usize)
+                                                       .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_ARRAY_STRIDE, member_strides.as_mut_ptr());
+
         // getting the length of the names of the members
         let mut member_name_len = ::std::iter::repeat(0).take(num_members as usize)
                                                          .collect::<Vec<gl::types::GLint>>();
@@ -471,15 +534,18 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
         let members = member_names.into_iter().enumerate().map(|(index, name)| {
             (name, member_offsets[index] as usize,
              glenum_to_uniform_type(member_types[index] as gl::types::GLenum),
-             member_size[index] as usize, None)
+             member_size[index] as usize, member_strides[index] as usize, None)
         });
 
         // finally inserting into the blocks list
+        let (layout, array_strides) = introspection_output_to_layout(members);
+
         blocks.insert(name, UniformBlock {
             id: block_id as i32,
             initial_binding: binding as i32,
             size: block_size as usize,
-            layout: introspection_output_to_layout(members),
+            layout: layout,
+            array_strides: array_strides,
         });
     }
 
@@ -647,6 +713,39 @@ pub unsafe fn reflect_geometry_output_type(ctxt: &mut CommandContext, program: H
     }
 }
 
+/// Obtains the type of data that the geometry shader stage receives.
+///
+/// # Unsafety
+///
+/// - `program` must be a valid handle to a program.
+/// - The program **must** contain a geometry shader.
+pub unsafe fn reflect_geometry_input_type(ctxt: &mut CommandContext, program: Handle)
+                                          -> InputPrimitives
+{
+    let mut value = mem::uninitialized();
+
+    match program {
+        Handle::Id(program) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetProgramiv(program, gl::GEOMETRY_INPUT_TYPE, &mut value);
+        },
+        Handle::Handle(program) => {
+            assert!(ctxt.extensions.gl_arb_vertex_shader);
+            ctxt.gl.GetObjectParameterivARB(program, gl::GEOMETRY_INPUT_TYPE, &mut value);
+        }
+    };
+
+    match value as gl::types::GLenum {
+        gl::POINTS => InputPrimitives::Points,
+        gl::LINES => InputPrimitives::Lines,
+        gl::LINES_ADJACENCY => InputPrimitives::LinesAdjacency,
+        gl::TRIANGLES => InputPrimitives::Triangles,
+        gl::TRIANGLES_ADJACENCY => InputPrimitives::TrianglesAdjacency,
+        _ => unreachable!()
+    }
+}
+
 /// Obtains the type of data that the tessellation evaluation shader stage outputs.
 ///
 /// # Unsafety
@@ -678,6 +777,81 @@ pub unsafe fn reflect_tess_eval_output_type(ctxt: &mut CommandContext, program:
     }
 }
 
+/// Returns the list of atomic counters of a program.
+///
+/// Each element of an array of counters is a separate entry whose name ends with its index.
+pub unsafe fn reflect_atomic_counters(ctxt: &mut CommandContext, program: Handle)
+    -> HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>>
+{
+    let mut counters = HashMap::with_hasher(Default::default());
+
+    if !(ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+         ctxt.extensions.gl_arb_shader_atomic_counters)
+    {
+        // not supported
+        return counters;
+    }
+
+    let program = match program {
+        Handle::Id(program) => program,
+        Handle::Handle(_) => return counters,
+    };
+
+    let mut active_uniforms = 0;
+    ctxt.gl.GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut active_uniforms);
+
+    for uniform_id in 0 .. active_uniforms as gl::types::GLuint {
+        let mut buffer_index = -1;
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id,
+                                    gl::UNIFORM_ATOMIC_COUNTER_BUFFER_INDEX, &mut buffer_index);
+
+        // the uniform is not an atomic counter
+        if buffer_index < 0 {
+            continue;
+        }
+
+        let mut name_tmp: Vec<u8> = Vec::with_capacity(64);
+        let mut name_tmp_len = 63;
+        let mut data_type = 0;
+        let mut data_size = 0;
+        ctxt.gl.GetActiveUniform(program, uniform_id, name_tmp_len, &mut name_tmp_len,
+                                 &mut data_size, &mut data_type,
+                                 name_tmp.as_mut_ptr() as *mut gl::types::GLchar);
+        name_tmp.set_len(name_tmp_len as usize);
+        let name = String::from_utf8(name_tmp).unwrap();
+
+        let mut offset = 0;
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id, gl::UNIFORM_OFFSET, &mut offset);
+
+        let mut binding = 0;
+        ctxt.gl.GetActiveAtomicCounterBufferiv(program, buffer_index as gl::types::GLuint,
+                                               gl::ATOMIC_COUNTER_BUFFER_BINDING, &mut binding);
+
+        if !name.ends_with("[0]") {
+            counters.insert(name, AtomicCounter {
+                binding: binding as u32,
+                offset: offset as usize,
+            });
+            continue;
+        }
+
+        // flattening arrays
+        let mut stride = 0;
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id, gl::UNIFORM_ARRAY_STRIDE,
+                                    &mut stride);
+
+        let name_base = &name[.. name.len() - 3];
+        for i in 0 .. data_size as usize {
+            counters.insert(format!("{}[{}]", name_base, i), AtomicCounter {
+                binding: binding as u32,
+                offset: offset as usize + i * stride as usize,
+            });
+        }
+    }
+
+    counters
+}
+
 /// Returns the list of shader storage blocks of a program.
 pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program: Handle)
     -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
@@ -742,16 +916,18 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
 
         // iterator over variables
         let members = active_variables.into_iter().map(|variable| {
-            let (ty, array_size, offset, _array_stride, name_len, top_level_array_size) = {
-                let mut output: [gl::types::GLint; 6] = mem::uninitialized();
+            let (ty, array_size, offset, array_stride, name_len, top_level_array) = {
+                let mut output: [gl::types::GLint; 7] = mem::uninitialized();
                 ctxt.gl.GetProgramResourceiv(program, gl::BUFFER_VARIABLE,
-                                             variable as gl::types::GLuint, 6,
+                                             variable as gl::types::GLuint, 7,
                                              [gl::TYPE, gl::ARRAY_SIZE, gl::OFFSET,
                                               gl::ARRAY_STRIDE, gl::NAME_LENGTH,
-                                              gl::TOP_LEVEL_ARRAY_SIZE].as_ptr(), 6,
+                                              gl::TOP_LEVEL_ARRAY_SIZE,
+                                              gl::TOP_LEVEL_ARRAY_STRIDE].as_ptr(), 7,
                                              ptr::null_mut(), output.as_mut_ptr() as *mut _);
                 (glenum_to_uniform_type(output[0] as gl::types::GLenum), output[1] as usize,
-                 output[2] as usize, output[3] as usize, output[4] as usize, output[5] as usize)
+                 output[2] as usize, output[3] as usize, output[4] as usize,
+                 (output[5] as usize, output[6] as usize))
             };
 
             let name = {
@@ -766,15 +942,18 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
                 String::from_utf8(name_tmp).unwrap()
             };
 
-            (name, offset, ty, array_size, Some(top_level_array_size))
+            (name, offset, ty, array_size, array_stride, Some(top_level_array))
         });
 
         // finally inserting into the blocks list
+        let (layout, array_strides) = introspection_output_to_layout(members);
+
         blocks.insert(name, UniformBlock {
             id: block_id as i32,
             initial_binding: binding as i32,
             size: total_size,
-            layout: introspection_output_to_layout(members),
+            layout: layout,
+            array_strides: array_strides,
         });
     }
 
@@ -782,29 +961,56 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
 }
 
 /// Takes a list of elements produced by OpenGL's introspection API and turns them into
-/// a `BlockLayout` object.
+/// a `BlockLayout` object and the strides of its arrays.
 ///
-/// The iterator must produce a list of `(name, offset, ty, array_size, top_level_array_size)`.
-/// The `top_level_array_size` can be `None` if unknown.
+/// The iterator must produce a list of
+/// `(name, offset, ty, array_size, array_stride, top_level_array)`, where `top_level_array`
+/// contains the size and the stride of the top-level array and can be `None` if unknown.
+/// The strides are zero for elements that aren't arrays.
 ///
 /// # Panic
 ///
 /// Panic if the input doesn't conform to the OpenGL specs.
 ///
-fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
+fn introspection_output_to_layout<I>(elements: I) -> (BlockLayout, HashMap<String, usize>)
                                      where I: Iterator<Item = (String, usize, UniformType,
-                                                               usize, Option<usize>)>
+                                                               usize, usize,
+                                                               Option<(usize, usize)>)>
 {
+    // returns the offset of the first element with the given name in `layout`
+    fn find_offset(layout: &BlockLayout, name: &str) -> Option<usize> {
+        match layout {
+            &BlockLayout::BasicType { offset_in_buffer, .. } => Some(offset_in_buffer),
+            &BlockLayout::Array { ref content, .. } => find_offset(content, name),
+            &BlockLayout::DynamicSizedArray { ref content } => find_offset(content, name),
+            &BlockLayout::Struct { ref members } => {
+                let mut components = name.splitn(2, '.');
+                let current_component = components.next().unwrap();
+                let current_component = match current_component.rfind('[') {
+                    Some(open_bracket_pos) => &current_component[.. open_bracket_pos],
+                    None => current_component,
+                };
+
+                match members.iter().find(|m| m.0 == current_component) {
+                    Some(member) => find_offset(&member.1, components.next().unwrap_or("")),
+                    None => None,
+                }
+            },
+        }
+    }
+
     // `output` must be a BlockLayout::Struct, otherwise this function will panic
-    fn process(output: &mut BlockLayout, name: &str, offset: usize, ty: UniformType,
-               array_size: usize, top_level_array_size: Option<usize>)
+    // `path` is the path of `output` in the block, used to index `strides`
+    fn process(output: &mut BlockLayout, strides: &mut HashMap<String, usize>, path: &str,
+               name: &str, offset: usize, ty: UniformType, array_size: usize,
+               array_stride: usize, top_level_array: Option<(usize, usize)>)
     {
         let mut components = name.splitn(2, '.');
         let current_component = components.next().unwrap();
         let name_rest = components.next();
 
         // finding the appropriate place in `output` to write the element
-        let member = if let &mut BlockLayout::Struct { ref mut members } = output {
+        let (member, member_path) = if let &mut BlockLayout::Struct { ref mut members } = output {
             // splitting the name and array size
             let (current_component, array) = if current_component.ends_with(']') {
                 let open_bracket_pos = current_component.rfind('[').unwrap();
@@ -815,6 +1021,8 @@ fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
                 (&current_component[..], None)
             };
 
+            let member_path = uniforms::block_member_path(path, current_component);
+
             // because of a bug in Rust's borrow checker, we have to loop twice instead of just
             // call `if let Some() { } else { }`
             let existing = members.iter_mut().find(|m| m.0 == current_component).is_some();
@@ -826,21 +1034,46 @@ fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
                     match member {
                         &mut BlockLayout::Array { ref mut content, ref mut length } => {
                             if *length <= array { *length = array + 1; }
-                            &mut **content
+
+                            // the stride of an array of structs is deduced from the offsets of
+                            // the same member in the first element and in this one
+                            if !strides.contains_key(&member_path) && array != 0 {
+                                if let Some(name_rest) = name_rest {
+                                    match find_offset(content, name_rest) {
+                                        Some(first) if offset > first => {
+                                            strides.insert(member_path.clone(),
+                                                           (offset - first) / array);
+                                        },
+                                        _ => ()
+                                    }
+                                }
+                            }
+
+                            (&mut **content, member_path)
                         },
                         &mut BlockLayout::DynamicSizedArray { ref mut content } => {
-                            &mut **content
+                            (&mut **content, member_path)
                         },
                         _ => unreachable!()
                     }
                 } else {
-                    member
+                    (member, member_path)
                 }
 
             } else {
                 // member doesn't exist yet in the output, adding it
                 if let Some(array) = array {
-                    if top_level_array_size == Some(0) {
+                    match top_level_array {
+                        Some((_, stride)) if stride != 0 => {
+                            strides.insert(member_path.clone(), stride);
+                        },
+                        _ if name_rest.is_none() && array_stride != 0 => {
+                            strides.insert(member_path.clone(), array_stride);
+                        },
+                        _ => (),
+                    };
+
+                    if top_level_array.map(|(size, _)| size) == Some(0) {
                         members.push((current_component.to_owned(), BlockLayout::DynamicSizedArray {
                             content: Box::new(BlockLayout::Struct { members: Vec::new() }),
                         }));
@@ -852,8 +1085,12 @@ fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
                     }
 
                     match &mut members.last_mut().unwrap().1 {
-                        &mut BlockLayout::Array { ref mut content, .. } => &mut **content,
-                        &mut BlockLayout::DynamicSizedArray { ref mut content } => &mut **content,
+                        &mut BlockLayout::Array { ref mut content, .. } => {
+                            (&mut **content, member_path)
+                        },
+                        &mut BlockLayout::DynamicSizedArray { ref mut content } => {
+                            (&mut **content, member_path)
+                        },
                         _ => unreachable!()
                     }
 
@@ -861,7 +1098,7 @@ fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
                     members.push((current_component.to_owned(), BlockLayout::Struct {
                         members: Vec::new()
                     }));
-                    &mut members.last_mut().unwrap().1
+                    (&mut members.last_mut().unwrap().1, member_path)
                 }
             }
 
@@ -871,7 +1108,8 @@ fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
 
         // now adding either the other elements or the final element itself
         if let Some(name_rest) = name_rest {
-            process(member, name_rest, offset, ty, array_size, None);
+            process(member, strides, &member_path, name_rest, offset, ty, array_size,
+                    array_stride, None);
 
         } else {
             // don't write over the offset in buffer
@@ -889,10 +1127,12 @@ fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
 
     // ↓ actual body of `introspection_output_to_layout` starts here ↓
     let mut layout = BlockLayout::Struct { members: Vec::new() };
-    for (name, offset, ty, array_size, top_level_array_size) in elements {
-        process(&mut layout, &name, offset, ty, array_size, top_level_array_size);
+    let mut strides = HashMap::new();
+    for (name, offset, ty, array_size, array_stride, top_level_array) in elements {
+        process(&mut layout, &mut strides, "", &name, offset, ty, array_size, array_stride,
+                top_level_array);
     }
-    layout
+    (layout, strides)
 }
 
 #[inline]
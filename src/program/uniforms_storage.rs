@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use RawUniformValue;
@@ -12,8 +12,27 @@ use context::CommandContext;
 use version::Version;
 use version::Api;
 use program::reflection::ShaderStage;
+use uniforms::UniformArray;
+
+macro_rules! uniform(
+    ($ctxt:expr, $uniform:ident, $uniform_arb:ident, $($params:expr),+) => (
+        unsafe {
+            if $ctxt.version >= &Version(Api::Gl, 1, 5) ||
+               $ctxt.version >= &Version(Api::GlEs, 2, 0)
+            {
+                $ctxt.gl.$uniform($($params),+)
+            } else {
+                assert!($ctxt.extensions.gl_arb_shader_objects);
+                $ctxt.gl.$uniform_arb($($params),+)
+            }
+        }
+    )
+);
 
 pub struct UniformsStorage {
+    // value of `uniform_values_generation` in the state of the context when the values below
+    // were recorded
+    generation: Cell<u64>,
     values: RefCell<HashMap<gl::types::GLint, Option<RawUniformValue>,
                             BuildHasherDefault<FnvHasher>>>,
     uniform_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
@@ -27,6 +46,7 @@ impl UniformsStorage {
     #[inline]
     pub fn new() -> UniformsStorage {
         UniformsStorage {
+            generation: Cell::new(0),
             values: RefCell::new(HashMap::with_hasher(Default::default())),
             uniform_blocks: RefCell::new(SmallVec::new()),
             shader_storage_blocks: RefCell::new(SmallVec::new()),
@@ -34,38 +54,57 @@ impl UniformsStorage {
         }
     }
 
+    /// Forgets all the values stored in this object if the state of the context has been reset
+    /// since they were recorded, because code outside of glium may have modified them.
+    fn check_generation(&self, ctxt: &CommandContext) {
+        let generation = ctxt.state.uniform_values_generation;
+        if self.generation.get() == generation {
+            return;
+        }
+
+        self.generation.set(generation);
+        self.values.borrow_mut().clear();
+        self.uniform_blocks.borrow_mut().clear();
+        self.shader_storage_blocks.borrow_mut().clear();
+        self.subroutine_uniforms.borrow_mut().clear();
+    }
+
     /// Compares `value` with the value stored in this object. If the values differ, updates
     /// the storage and calls `glUniform`.
+    ///
+    /// Since the same values are often given to many draw commands in a row, for example the
+    /// constants of a material, this avoids most of the `glUniform` calls.
     pub fn set_uniform_value(&self, ctxt: &mut CommandContext, program: Handle,
                              location: gl::types::GLint, value: &RawUniformValue)
     {
+        self.check_generation(ctxt);
         let mut values = self.values.borrow_mut();
 
         // TODO: don't assume that, instead use DSA if the program is not current
         assert!(ctxt.state.program == program);
 
-        macro_rules! uniform(
-            ($ctxt:expr, $uniform:ident, $uniform_arb:ident, $($params:expr),+) => (
+        macro_rules! uniform_f64(
+            ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
                 unsafe {
-                    if $ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                       $ctxt.version >= &Version(Api::GlEs, 2, 0)
-                    {
+                    if $ctxt.extensions.gl_arb_gpu_shader_fp64 {
                         $ctxt.gl.$uniform($($params),+)
                     } else {
-                        assert!($ctxt.extensions.gl_arb_shader_objects);
-                        $ctxt.gl.$uniform_arb($($params),+)
+                        panic!("Double precision floats are not supported on this system.")
                     }
                 }
             )
         );
 
-        macro_rules! uniform_f64(
+        // there is no ARB version of the functions for non-square matrices
+        macro_rules! uniform_non_square(
             ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
                 unsafe {
-                    if $ctxt.extensions.gl_arb_gpu_shader_fp64 {
+                    if $ctxt.version >= &Version(Api::Gl, 2, 1) ||
+                       $ctxt.version >= &Version(Api::GlEs, 3, 0)
+                    {
                         $ctxt.gl.$uniform($($params),+)
                     } else {
-                        panic!("Double precision floats are not supported on this system.")
+                        panic!("Non-square matrices are not supported on this system.")
                     }
                 }
             )
@@ -90,6 +129,12 @@ impl UniformsStorage {
             (&RawUniformValue::Mat2(a), &mut Some(RawUniformValue::Mat2(b))) if a == b => (),
             (&RawUniformValue::Mat3(a), &mut Some(RawUniformValue::Mat3(b))) if a == b => (),
             (&RawUniformValue::Mat4(a), &mut Some(RawUniformValue::Mat4(b))) if a == b => (),
+            (&RawUniformValue::Mat2x3(a), &mut Some(RawUniformValue::Mat2x3(b))) if a == b => (),
+            (&RawUniformValue::Mat2x4(a), &mut Some(RawUniformValue::Mat2x4(b))) if a == b => (),
+            (&RawUniformValue::Mat3x2(a), &mut Some(RawUniformValue::Mat3x2(b))) if a == b => (),
+            (&RawUniformValue::Mat3x4(a), &mut Some(RawUniformValue::Mat3x4(b))) if a == b => (),
+            (&RawUniformValue::Mat4x2(a), &mut Some(RawUniformValue::Mat4x2(b))) if a == b => (),
+            (&RawUniformValue::Mat4x3(a), &mut Some(RawUniformValue::Mat4x3(b))) if a == b => (),
             (&RawUniformValue::Vec2(a), &mut Some(RawUniformValue::Vec2(b))) if a == b => (),
             (&RawUniformValue::Vec3(a), &mut Some(RawUniformValue::Vec3(b))) if a == b => (),
             (&RawUniformValue::Vec4(a), &mut Some(RawUniformValue::Vec4(b))) if a == b => (),
@@ -103,6 +148,12 @@ impl UniformsStorage {
             (&RawUniformValue::DoubleMat2(a), &mut Some(RawUniformValue::DoubleMat2(b))) if a == b => (),
             (&RawUniformValue::DoubleMat3(a), &mut Some(RawUniformValue::DoubleMat3(b))) if a == b => (),
             (&RawUniformValue::DoubleMat4(a), &mut Some(RawUniformValue::DoubleMat4(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat2x3(a), &mut Some(RawUniformValue::DoubleMat2x3(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat2x4(a), &mut Some(RawUniformValue::DoubleMat2x4(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat3x2(a), &mut Some(RawUniformValue::DoubleMat3x2(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat3x4(a), &mut Some(RawUniformValue::DoubleMat3x4(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat4x2(a), &mut Some(RawUniformValue::DoubleMat4x2(b))) if a == b => (),
+            (&RawUniformValue::DoubleMat4x3(a), &mut Some(RawUniformValue::DoubleMat4x3(b))) if a == b => (),
             (&RawUniformValue::DoubleVec2(a), &mut Some(RawUniformValue::DoubleVec2(b))) if a == b => (),
             (&RawUniformValue::DoubleVec3(a), &mut Some(RawUniformValue::DoubleVec3(b))) if a == b => (),
             (&RawUniformValue::DoubleVec4(a), &mut Some(RawUniformValue::DoubleVec4(b))) if a == b => (),
@@ -114,6 +165,7 @@ impl UniformsStorage {
             (&RawUniformValue::UnsignedInt64Vec2(a), &mut Some(RawUniformValue::UnsignedInt64Vec2(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt64Vec3(a), &mut Some(RawUniformValue::UnsignedInt64Vec3(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt64Vec4(a), &mut Some(RawUniformValue::UnsignedInt64Vec4(b))) if a == b => (),
+            (&RawUniformValue::TextureHandle(a), &mut Some(RawUniformValue::TextureHandle(b))) if a == b => (),
 
             (&RawUniformValue::SignedInt(v), target) => {
                 *target = Some(RawUniformValue::SignedInt(v));
@@ -159,6 +211,42 @@ impl UniformsStorage {
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
+            (&RawUniformValue::Mat2x3(v), target) => {
+                *target = Some(RawUniformValue::Mat2x3(v));
+                uniform_non_square!(ctxt, UniformMatrix2x3fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat2x4(v), target) => {
+                *target = Some(RawUniformValue::Mat2x4(v));
+                uniform_non_square!(ctxt, UniformMatrix2x4fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat3x2(v), target) => {
+                *target = Some(RawUniformValue::Mat3x2(v));
+                uniform_non_square!(ctxt, UniformMatrix3x2fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat3x4(v), target) => {
+                *target = Some(RawUniformValue::Mat3x4(v));
+                uniform_non_square!(ctxt, UniformMatrix3x4fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat4x2(v), target) => {
+                *target = Some(RawUniformValue::Mat4x2(v));
+                uniform_non_square!(ctxt, UniformMatrix4x2fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat4x3(v), target) => {
+                *target = Some(RawUniformValue::Mat4x3(v));
+                uniform_non_square!(ctxt, UniformMatrix4x3fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
             (&RawUniformValue::Vec2(v), target) => {
                 *target = Some(RawUniformValue::Vec2(v));
                 uniform!(ctxt, Uniform2fv, Uniform2fvARB, location, 1, v.as_ptr() as *const f32);
@@ -259,6 +347,42 @@ impl UniformsStorage {
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
+            (&RawUniformValue::DoubleMat2x3(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat2x3(v));
+                uniform_f64!(ctxt, UniformMatrix2x3dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat2x4(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat2x4(v));
+                uniform_f64!(ctxt, UniformMatrix2x4dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat3x2(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat3x2(v));
+                uniform_f64!(ctxt, UniformMatrix3x2dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat3x4(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat3x4(v));
+                uniform_f64!(ctxt, UniformMatrix3x4dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat4x2(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat4x2(v));
+                uniform_f64!(ctxt, UniformMatrix4x2dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
+            (&RawUniformValue::DoubleMat4x3(v), target) => {
+                *target = Some(RawUniformValue::DoubleMat4x3(v));
+                uniform_f64!(ctxt, UniformMatrix4x3dv,
+                         location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
+            },
+
             (&RawUniformValue::DoubleVec2(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec2(v));
                 uniform_f64!(ctxt, Uniform2dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
@@ -309,14 +433,138 @@ impl UniformsStorage {
                 *target = Some(RawUniformValue::UnsignedInt64Vec4(v));
                 uniform_i64!(ctxt, Uniform4ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
+
+            (&RawUniformValue::TextureHandle(v), target) => {
+                *target = Some(RawUniformValue::TextureHandle(v));
+                assert!(ctxt.extensions.gl_arb_bindless_texture);
+                unsafe { ctxt.gl.UniformHandleui64ARB(location, v) };
+            },
+        }
+    }
+
+    /// Compares the elements of `value` with the values stored in this object. If any of them
+    /// differs, updates the storage and sets the whole array with a single `glUniform*v` call.
+    pub fn set_uniform_array(&self, ctxt: &mut CommandContext, program: Handle,
+                             location: gl::types::GLint, value: &UniformArray)
+    {
+        self.check_generation(ctxt);
+        let mut values = self.values.borrow_mut();
+
+        // TODO: don't assume that, instead use DSA if the program is not current
+        assert!(ctxt.state.program == program);
+
+        // the elements of an array have consecutive locations
+        let unchanged = (0 .. value.len()).all(|index| {
+            match values.get(&(location + index as gl::types::GLint)) {
+                Some(&Some(ref stored)) => *stored == value.get(index),
+                _ => false,
+            }
+        });
+
+        if unchanged {
+            return;
+        }
+
+        for index in 0 .. value.len() {
+            values.insert(location + index as gl::types::GLint, Some(value.get(index)));
+        }
+
+        let count = value.len() as gl::types::GLsizei;
+
+        // the unsigned functions don't have an `ARB` version
+        macro_rules! uniform_unsigned(
+            ($uniform:ident, $uniform_arb:ident, $v:expr) => (
+                unsafe {
+                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                       ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    {
+                        ctxt.gl.$uniform(location, count, $v.as_ptr() as *const gl::types::GLuint)
+                    } else {
+                        assert!(ctxt.extensions.gl_arb_shader_objects);
+                        ctxt.gl.$uniform_arb(location, count,
+                                             $v.as_ptr() as *const gl::types::GLint)
+                    }
+                }
+            )
+        );
+
+        match *value {
+            UniformArray::Float(v) => {
+                uniform!(ctxt, Uniform1fv, Uniform1fvARB, location, count, v.as_ptr());
+            },
+            UniformArray::Vec2(v) => {
+                uniform!(ctxt, Uniform2fv, Uniform2fvARB, location, count,
+                         v.as_ptr() as *const f32);
+            },
+            UniformArray::Vec3(v) => {
+                uniform!(ctxt, Uniform3fv, Uniform3fvARB, location, count,
+                         v.as_ptr() as *const f32);
+            },
+            UniformArray::Vec4(v) => {
+                uniform!(ctxt, Uniform4fv, Uniform4fvARB, location, count,
+                         v.as_ptr() as *const f32);
+            },
+            UniformArray::SignedInt(v) => {
+                uniform!(ctxt, Uniform1iv, Uniform1ivARB, location, count, v.as_ptr());
+            },
+            UniformArray::IntVec2(v) => {
+                uniform!(ctxt, Uniform2iv, Uniform2ivARB, location, count,
+                         v.as_ptr() as *const gl::types::GLint);
+            },
+            UniformArray::IntVec3(v) => {
+                uniform!(ctxt, Uniform3iv, Uniform3ivARB, location, count,
+                         v.as_ptr() as *const gl::types::GLint);
+            },
+            UniformArray::IntVec4(v) => {
+                uniform!(ctxt, Uniform4iv, Uniform4ivARB, location, count,
+                         v.as_ptr() as *const gl::types::GLint);
+            },
+            UniformArray::UnsignedInt(v) => uniform_unsigned!(Uniform1uiv, Uniform1ivARB, v),
+            UniformArray::UnsignedIntVec2(v) => uniform_unsigned!(Uniform2uiv, Uniform2ivARB, v),
+            UniformArray::UnsignedIntVec3(v) => uniform_unsigned!(Uniform3uiv, Uniform3ivARB, v),
+            UniformArray::UnsignedIntVec4(v) => uniform_unsigned!(Uniform4uiv, Uniform4ivARB, v),
+            UniformArray::Mat2(v) => {
+                uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB, location, count, gl::FALSE,
+                         v.as_ptr() as *const f32);
+            },
+            UniformArray::Mat3(v) => {
+                uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB, location, count, gl::FALSE,
+                         v.as_ptr() as *const f32);
+            },
+            UniformArray::Mat4(v) => {
+                uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB, location, count, gl::FALSE,
+                         v.as_ptr() as *const f32);
+            },
+            UniformArray::TextureHandle(v) => {
+                assert!(ctxt.extensions.gl_arb_bindless_texture);
+                unsafe { ctxt.gl.UniformHandleui64vARB(location, count, v.as_ptr()) };
+            },
+        }
+    }
+
+    /// Returns the value of an integer uniform that was last set, if any.
+    #[inline]
+    pub fn get_signed_int(&self, location: gl::types::GLint) -> Option<gl::types::GLint> {
+        match self.values.borrow().get(&location) {
+            Some(&Some(RawUniformValue::SignedInt(v))) => Some(v),
+            _ => None,
         }
     }
 
+    /// Returns the binding of a uniform block that was last set, if any.
+    #[inline]
+    pub fn get_uniform_block_binding(&self, location: gl::types::GLuint)
+                                     -> Option<gl::types::GLuint>
+    {
+        self.uniform_blocks.borrow().get(location as usize).and_then(|b| *b)
+    }
+
     /// Compares `value` with the value stored in this object. If the values differ, updates
     /// the storage and calls `glUniformBlockBinding`.
     pub fn set_uniform_block_binding(&self, ctxt: &mut CommandContext, program: Handle,
                                      location: gl::types::GLuint, value: gl::types::GLuint)
     {
+        self.check_generation(ctxt);
         let mut blocks = self.uniform_blocks.borrow_mut();
 
         if blocks.len() <= location as usize {
@@ -348,6 +596,7 @@ impl UniformsStorage {
     pub fn set_shader_storage_block_binding(&self, ctxt: &mut CommandContext, program: Handle,
                                             location: gl::types::GLuint, value: gl::types::GLuint)
     {
+        self.check_generation(ctxt);
         let mut blocks = self.shader_storage_blocks.borrow_mut();
 
         if blocks.len() <= location as usize {
@@ -381,6 +630,7 @@ impl UniformsStorage {
                                          stage: ShaderStage,
                                          indices: &[gl::types::GLuint])
     {
+        self.check_generation(ctxt);
         let mut subroutine_uniforms = self.subroutine_uniforms.borrow_mut();
         if let Some(stored_indices) = subroutine_uniforms.get(&stage) {
             if &stored_indices[..] == indices {
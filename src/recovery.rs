@@ -0,0 +1,122 @@
+/*!
+Recovering from the loss of the OpenGL context.
+
+The OpenGL context can be lost, for example when an Android application goes to the background
+or when the GPU is reset after a driver crash on Windows. All the objects that belong to the
+context (buffers, textures, programs, etc.) are then destroyed, and the context must be
+recreated from scratch.
+
+A context loss can only be detected if the context has been created with the
+`LOSE_CONTEXT_ON_RESET` notification strategy (see `Context::is_context_loss_possible`). Once
+glium has detected it:
+
+ - `Frame::finish` returns `SwapBuffersError::ContextLost`.
+ - Drawing returns `DrawError::ContextLost`.
+ - Creating a buffer returns `BufferCreationError::ContextLost`, and writing a buffer does
+   nothing.
+ - `Context::is_context_lost` returns true.
+
+To recover, build a new `Display` and recreate all the objects. A `ResourceRegistry` makes this
+easier by remembering how each object was created.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# fn build_display() -> glium::Display { unimplemented!() }
+use std::cell::RefCell;
+use std::rc::Rc;
+use glium::recovery::ResourceRegistry;
+
+let mut registry = ResourceRegistry::new();
+
+let program = Rc::new(RefCell::new(None));
+{
+    let program = program.clone();
+    registry.register(&display, move |facade| {
+        *program.borrow_mut() = Some(program!(facade,
+            140 => { vertex: "...", fragment: "..." }
+        ).unwrap());
+    });
+}
+
+// later, when the context has been lost
+if display.get_context().is_context_lost() {
+    let display = build_display();
+    registry.recreate(&display);
+}
+# }
+```
+
+*/
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+
+/// Identifies a callback registered in a `ResourceRegistry`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceId(usize);
+
+/// Remembers how to create objects, so that they can be recreated after a context loss.
+///
+/// The objects that were created with the lost context must not be used with the new one. The
+/// callbacks usually replace them, which destroys them. Destroying an object of a lost context
+/// is harmless.
+pub struct ResourceRegistry {
+    callbacks: Vec<Option<Box<FnMut(&Rc<Context>)>>>,
+}
+
+impl ResourceRegistry {
+    /// Builds an empty registry.
+    #[inline]
+    pub fn new() -> ResourceRegistry {
+        ResourceRegistry {
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Registers a callback that creates objects, and calls it immediately with `facade`.
+    ///
+    /// The callback is called again by `recreate`, with the new context.
+    pub fn register<F: ?Sized, C>(&mut self, facade: &F, mut callback: C) -> ResourceId
+                                  where F: Facade, C: FnMut(&Rc<Context>) + 'static
+    {
+        callback(facade.get_context());
+        self.callbacks.push(Some(Box::new(callback)));
+        ResourceId(self.callbacks.len() - 1)
+    }
+
+    /// Removes a callback from the registry. Does nothing if it has already been removed.
+    #[inline]
+    pub fn unregister(&mut self, id: ResourceId) {
+        if let Some(callback) = self.callbacks.get_mut(id.0) {
+            *callback = None;
+        }
+    }
+
+    /// Calls all the callbacks with a new context, in the order in which they were registered.
+    pub fn recreate<F: ?Sized>(&mut self, facade: &F) where F: Facade {
+        for callback in self.callbacks.iter_mut() {
+            if let Some(ref mut callback) = *callback {
+                callback(facade.get_context());
+            }
+        }
+    }
+}
+
+impl Default for ResourceRegistry {
+    #[inline]
+    fn default() -> ResourceRegistry {
+        ResourceRegistry::new()
+    }
+}
+
+impl fmt::Debug for ResourceRegistry {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let count = self.callbacks.iter().filter(|c| c.is_some()).count();
+        write!(fmt, "ResourceRegistry ({} callbacks)", count)
+    }
+}
+
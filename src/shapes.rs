@@ -0,0 +1,275 @@
+/*!
+Generators for simple shapes, ready to be uploaded.
+
+These are useful for examples, tests and prototypes. Each function returns a `Shape`, which
+contains a list of `Vertex` and a list of indices that describe triangles. The front faces are
+counter-clockwise, and the cube, the plane and the sphere are centered on the origin, from
+`-1.0` to `1.0` along each axis.
+
+```no_run
+# #[macro_use] extern crate glium;
+# use glium::Surface;
+# fn main() {
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+# let mut frame = display.draw();
+let sphere = glium::shapes::sphere(32, 16);
+let vertex_buffer = sphere.vertex_buffer(&display).unwrap();
+let index_buffer = sphere.index_buffer(&display).unwrap();
+
+frame.draw(&vertex_buffer, &index_buffer, &program, &uniform! { scale: 0.5f32 },
+           &Default::default()).unwrap();
+# }
+```
+
+If your program uses other attribute names or types, convert the vertices before uploading
+them, for example with `shape.vertices.iter().map(|v| MyVertex { pos: v.position })`.
+
+*/
+use std::f32::consts::PI;
+
+use backend::Facade;
+use index::{self, IndexBuffer, PrimitiveType};
+use vertex::{self, VertexBuffer};
+
+/// A vertex of a generated shape.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vertex {
+    /// The position of the vertex.
+    pub position: [f32; 3],
+    /// The normal of the surface, which has a length of 1.
+    pub normal: [f32; 3],
+    /// The texture coordinates, between `0.0` and `1.0` on the visible parts of the shape.
+    pub tex_coords: [f32; 2],
+}
+
+implement_vertex!(Vertex, position, normal, tex_coords);
+
+/// Vertices and indices of a generated shape.
+#[derive(Clone, Debug)]
+pub struct Shape {
+    /// The list of vertices.
+    pub vertices: Vec<Vertex>,
+    /// The indices of the vertices that form each primitive.
+    pub indices: Vec<u16>,
+    /// The type of primitives described by the indices.
+    pub primitives: PrimitiveType,
+}
+
+impl Shape {
+    /// Uploads the vertices in a vertex buffer.
+    #[inline]
+    pub fn vertex_buffer<F: ?Sized>(&self, facade: &F)
+                                    -> Result<VertexBuffer<Vertex>, vertex::BufferCreationError>
+                                    where F: Facade
+    {
+        VertexBuffer::new(facade, &self.vertices)
+    }
+
+    /// Uploads the indices in an index buffer.
+    #[inline]
+    pub fn index_buffer<F: ?Sized>(&self, facade: &F)
+                                   -> Result<IndexBuffer<u16>, index::BufferCreationError>
+                                   where F: Facade
+    {
+        IndexBuffer::new(facade, self.primitives, &self.indices)
+    }
+}
+
+/// Builds a cube. Each face has its own four vertices, so that the normals are sharp.
+pub fn cube() -> Shape {
+    // the normal of each face, followed by two axes whose cross product is the normal
+    let faces = [
+        ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+        ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ];
+
+    let mut shape = Shape {
+        vertices: Vec::with_capacity(24),
+        indices: Vec::with_capacity(36),
+        primitives: PrimitiveType::TrianglesList,
+    };
+
+    for &(normal, u, v) in faces.iter() {
+        add_quad(&mut shape, normal, u, v);
+    }
+
+    shape
+}
+
+/// Builds a square in the XZ plane, facing towards positive Y.
+pub fn plane() -> Shape {
+    let mut shape = Shape {
+        vertices: Vec::with_capacity(4),
+        indices: Vec::with_capacity(6),
+        primitives: PrimitiveType::TrianglesList,
+    };
+
+    add_quad(&mut shape, [0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]);
+
+    // the quad is centered on its normal
+    for vertex in shape.vertices.iter_mut() {
+        vertex.position[1] = 0.0;
+    }
+
+    shape
+}
+
+/// Builds a sphere of radius 1, divided in `slices` around the Y axis and in `stacks` from the
+/// top to the bottom.
+///
+/// The texture coordinates wrap around the sphere, so the vertices of the first and last
+/// slices are duplicated.
+///
+/// # Panic
+///
+/// Panics if `slices` is lower than 3, if `stacks` is lower than 2, or if the sphere would
+/// have more than 65536 vertices.
+pub fn sphere(slices: u32, stacks: u32) -> Shape {
+    assert!(slices >= 3 && stacks >= 2);
+    assert!((slices + 1) * (stacks + 1) <= 65536, "Too many vertices for 16 bits indices");
+
+    let mut vertices = Vec::with_capacity(((slices + 1) * (stacks + 1)) as usize);
+
+    for stack in 0 .. stacks + 1 {
+        let phi = PI * stack as f32 / stacks as f32;
+        let (radius, y) = (phi.sin(), phi.cos());
+
+        for slice in 0 .. slices + 1 {
+            let theta = 2.0 * PI * slice as f32 / slices as f32;
+            let position = [radius * theta.sin(), y, radius * theta.cos()];
+
+            vertices.push(Vertex {
+                position: position,
+                normal: position,
+                tex_coords: [slice as f32 / slices as f32, 1.0 - stack as f32 / stacks as f32],
+            });
+        }
+    }
+
+    let mut indices = Vec::with_capacity((slices * stacks * 6) as usize);
+
+    for stack in 0 .. stacks {
+        for slice in 0 .. slices {
+            let top = (stack * (slices + 1) + slice) as u16;
+            let bottom = top + slices as u16 + 1;
+            indices.extend_from_slice(&[top, bottom, top + 1, top + 1, bottom, bottom + 1]);
+        }
+    }
+
+    Shape {
+        vertices: vertices,
+        indices: indices,
+        primitives: PrimitiveType::TrianglesList,
+    }
+}
+
+/// Builds a triangle that covers the whole viewport when its positions are used as clip-space
+/// coordinates. The texture coordinates are between `0.0` and `1.0` inside the viewport.
+///
+/// This is the usual way to run a fragment shader on every pixel, for example for
+/// post-processing.
+pub fn fullscreen_triangle() -> Shape {
+    let vertex = |x: f32, y: f32| Vertex {
+        position: [x, y, 0.0],
+        normal: [0.0, 0.0, 1.0],
+        tex_coords: [(x + 1.0) / 2.0, (y + 1.0) / 2.0],
+    };
+
+    Shape {
+        vertices: vec![vertex(-1.0, -1.0), vertex(3.0, -1.0), vertex(-1.0, 3.0)],
+        indices: vec![0, 1, 2],
+        primitives: PrimitiveType::TrianglesList,
+    }
+}
+
+/// Adds a square centered on `normal`, whose sides are along `u` and `v`. The cross product of
+/// `u` and `v` must be the normal.
+fn add_quad(shape: &mut Shape, normal: [f32; 3], u: [f32; 3], v: [f32; 3]) {
+    let first = shape.vertices.len() as u16;
+
+    for &(su, sv) in &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+        let position = [normal[0] + u[0] * su + v[0] * sv,
+                        normal[1] + u[1] * su + v[1] * sv,
+                        normal[2] + u[2] * su + v[2] * sv];
+
+        shape.vertices.push(Vertex {
+            position: position,
+            normal: normal,
+            tex_coords: [(su + 1.0) / 2.0, (sv + 1.0) / 2.0],
+        });
+    }
+
+    shape.indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cube, fullscreen_triangle, plane, sphere, Shape};
+
+    /// Checks that the indices are in range and that the triangles are counter-clockwise when
+    /// seen from the side of their normals.
+    fn check_shape(shape: &Shape) {
+        assert_eq!(shape.indices.len() % 3, 0);
+
+        for triangle in shape.indices.chunks(3) {
+            let v: Vec<_> = triangle.iter().map(|&i| shape.vertices[i as usize]).collect();
+
+            let e1 = [v[1].position[0] - v[0].position[0], v[1].position[1] - v[0].position[1],
+                      v[1].position[2] - v[0].position[2]];
+            let e2 = [v[2].position[0] - v[0].position[0], v[2].position[1] - v[0].position[1],
+                      v[2].position[2] - v[0].position[2]];
+            let cross = [e1[1] * e2[2] - e1[2] * e2[1], e1[2] * e2[0] - e1[0] * e2[2],
+                         e1[0] * e2[1] - e1[1] * e2[0]];
+
+            let normal = v[0].normal;
+            let dot = cross[0] * normal[0] + cross[1] * normal[1] + cross[2] * normal[2];
+
+            // triangles at the poles of the sphere are degenerate
+            assert!(dot >= -1.0e-6);
+        }
+    }
+
+    #[test]
+    fn cube_shape() {
+        let shape = cube();
+        assert_eq!(shape.vertices.len(), 24);
+        assert_eq!(shape.indices.len(), 36);
+        assert!(shape.vertices.iter().all(|v| v.position.iter().all(|c| c.abs() == 1.0)));
+        check_shape(&shape);
+    }
+
+    #[test]
+    fn plane_shape() {
+        let shape = plane();
+        assert_eq!(shape.vertices.len(), 4);
+        assert!(shape.vertices.iter().all(|v| v.position[1] == 0.0));
+        check_shape(&shape);
+    }
+
+    #[test]
+    fn sphere_shape() {
+        let shape = sphere(8, 4);
+        assert_eq!(shape.vertices.len(), 9 * 5);
+        assert_eq!(shape.indices.len(), 8 * 4 * 6);
+
+        for vertex in &shape.vertices {
+            let len = vertex.position.iter().map(|c| c * c).sum::<f32>().sqrt();
+            assert!((len - 1.0).abs() < 1.0e-5);
+        }
+
+        check_shape(&shape);
+    }
+
+    #[test]
+    fn fullscreen_triangle_shape() {
+        let shape = fullscreen_triangle();
+        assert_eq!(shape.vertices[0].tex_coords, [0.0, 0.0]);
+        assert_eq!(shape.vertices[1].tex_coords, [2.0, 0.0]);
+        check_shape(&shape);
+    }
+}
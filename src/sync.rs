@@ -1,11 +1,14 @@
-use context::CommandContext;
+use context::{self, CommandContext};
 use version::Api;
 use version::Version;
 use gl;
 
 use backend::Facade;
 use context::Context;
+use CapabilitiesSource;
 use ContextExt;
+use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
 
 use std::thread;
@@ -14,6 +17,44 @@ use std::thread;
 #[derive(Copy, Clone, Debug)]
 pub struct SyncNotSupportedError;
 
+impl fmt::Display for SyncNotSupportedError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for SyncNotSupportedError {
+    fn description(&self) -> &str {
+        "Sync objects are not supported by the backend"
+    }
+}
+
+/// Error that can happen when waiting for a fence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SyncWaitError {
+    /// `glClientWaitSync` returned `GL_WAIT_FAILED`.
+    WaitFailed,
+
+    /// The OpenGL context has been lost. See the documentation of the `recovery` module.
+    ContextLost,
+}
+
+impl fmt::Display for SyncWaitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for SyncWaitError {
+    fn description(&self) -> &str {
+        match *self {
+            SyncWaitError::WaitFailed => "Could not wait for the fence",
+            SyncWaitError::ContextLost => "The OpenGL context has been lost",
+        }
+    }
+}
+
 /// Provides a way to wait for a server-side operation to be finished.
 ///
 /// Creating a `SyncFence` injects an element in the commands queue of the backend.
@@ -42,7 +83,20 @@ impl SyncFence {
     }
 
     /// Blocks until the operation has finished on the server.
-    pub fn wait(mut self) {
+    ///
+    /// # Panic
+    ///
+    /// Panics if the wait fails. See `try_wait`.
+    #[inline]
+    pub fn wait(self) {
+        if let Err(err) = self.try_wait() {
+            panic!("{}", err);
+        }
+    }
+
+    /// Blocks until the operation has finished on the server. Returns an error instead of
+    /// panicking if the wait fails, which usually means that the context has been lost.
+    pub fn try_wait(mut self) -> Result<(), SyncWaitError> {
         let sync = self.id.take().unwrap();
 
         let mut ctxt = self.context.make_current();
@@ -50,9 +104,23 @@ impl SyncFence {
         unsafe { delete_fence(&mut ctxt, sync) };
 
         match result {
-            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => (),
-            _ => panic!("Could not wait for the fence")
-        };
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => Ok(()),
+            _ if context::check_context_lost(&mut ctxt) => Err(SyncWaitError::ContextLost),
+            _ => Err(SyncWaitError::WaitFailed),
+        }
+    }
+
+    /// Returns true if the operations that were submitted before the fence have finished.
+    ///
+    /// Contrary to `wait`, this function never blocks.
+    pub fn is_signaled(&self) -> bool {
+        let sync = self.id.unwrap();
+
+        let mut ctxt = self.context.make_current();
+        match unsafe { poll(&mut ctxt, sync) } {
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+            _ => false
+        }
     }
 }
 
@@ -69,6 +137,12 @@ impl Drop for SyncFence {
     }
 }
 
+/// Returns the OpenGL sync object of a fence.
+#[inline]
+pub fn get_raw_sync(fence: &SyncFence) -> gl::types::GLsync {
+    fence.id.unwrap()
+}
+
 /// Prototype for a `SyncFence`.
 ///
 /// The fence must be consumed with either `into_sync_fence`, otherwise
@@ -100,6 +174,13 @@ impl Drop for LinearSyncFence {
     }
 }
 
+/// Returns true if the backend supports sync objects.
+pub fn is_sync_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+    caps.get_version() >= &Version(Api::Gl, 3, 2) ||
+    caps.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    caps.get_extensions().gl_arb_sync || caps.get_extensions().gl_apple_sync
+}
+
 pub unsafe fn new_linear_sync_fence(ctxt: &mut CommandContext)
                                     -> Result<LinearSyncFence, SyncNotSupportedError>
 {
@@ -130,6 +211,40 @@ pub unsafe fn wait_linear_sync_fence_and_drop(mut fence: LinearSyncFence,
     delete_fence(ctxt, fence);
 }
 
+/// Makes the next commands wait for this fence and destroys it, from within the commands context.
+///
+/// Contrary to `wait_linear_sync_fence_and_drop`, this function doesn't block. The fence can have
+/// been created by another context of the same share group.
+#[inline]
+pub unsafe fn server_wait_linear_sync_fence_and_drop(mut fence: LinearSyncFence,
+                                                     ctxt: &mut CommandContext)
+{
+    let fence = fence.id.take().unwrap();
+
+    if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.WaitSync(fence, 0, gl::TIMEOUT_IGNORED);
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.WaitSyncAPPLE(fence, 0, gl::TIMEOUT_IGNORED_APPLE);
+    } else {
+        unreachable!();
+    }
+
+    delete_fence(ctxt, fence);
+}
+
+/// Returns true if the commands submitted before this fence have finished. Never blocks.
+#[inline]
+pub unsafe fn is_linear_sync_fence_signaled(ctxt: &mut CommandContext, fence: &LinearSyncFence)
+                                            -> bool
+{
+    match poll(ctxt, fence.id.unwrap()) {
+        gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+        _ => false
+    }
+}
+
 /// Destroys a fence, from within the commands context.
 #[inline]
 pub unsafe fn destroy_linear_sync_fence(ctxt: &mut CommandContext, mut fence: LinearSyncFence) {
@@ -180,6 +295,26 @@ unsafe fn client_wait(ctxt: &mut CommandContext, fence: gl::types::GLsync) -> gl
     }
 }
 
+/// Calls `glClientWaitSync` with a timeout of zero and returns the result.
+///
+/// The commands queue is flushed so that the fence eventually gets signaled.
+///
+/// # Unsafety
+///
+/// The fence object must exist.
+///
+unsafe fn poll(ctxt: &mut CommandContext, fence: gl::types::GLsync) -> gl::types::GLenum {
+    if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, 0)
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.ClientWaitSyncAPPLE(fence, gl::SYNC_FLUSH_COMMANDS_BIT_APPLE, 0)
+    } else {
+        unreachable!();
+    }
+}
+
 /// Deletes a fence.
 ///
 /// # Unsafety
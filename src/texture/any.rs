@@ -3,8 +3,8 @@ use GlObject;
 
 use backend::Facade;
 use version::Version;
-use context::Context;
-use context::CommandContext;
+use context::{self, Context};
+use context::{CommandContext, Fallback, PendingDestruction, ShareGroup};
 use CapabilitiesSource;
 use ContextExt;
 use TextureExt;
@@ -18,10 +18,13 @@ use texture::TextureKind;
 use texture::{MipmapsOption, TextureFormat, TextureCreationError, CubeLayer};
 use texture::{get_format, InternalFormat, GetFormatError};
 use texture::pixel::PixelValue;
+use texture::{PixelLayout, ChannelOrder, ReadError};
 use texture::pixel_buffer::PixelBuffer;
 
 use fbo::ClearBufferData;
 
+use smallvec::SmallVec;
+
 use buffer::BufferSlice;
 use buffer::BufferAny;
 use BufferExt;
@@ -31,13 +34,17 @@ use std::cmp;
 use std::fmt;
 use std::mem;
 use std::ptr;
+use std::slice;
 use std::borrow::Cow;
 use std::cell::Cell;
+use std::marker::PhantomData;
 use std::rc::Rc;
 use std::ops::Range;
+use std::sync::Arc;
 
 use ops;
 use fbo;
+use sync::{self, LinearSyncFence};
 
 /// Type of a texture.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -107,6 +114,14 @@ fn get_bind_point(ty: Dimensions) -> gl::types::GLenum {
     }
 }
 
+/// Returns the description of a format shown by `Context::dump_resources`.
+fn describe_format(format: &TextureFormatRequest) -> String {
+    match *format {
+        TextureFormatRequest::Specific(ref format) => format!("{:?}", format),
+        ref request => format!("{:?}", request),
+    }
+}
+
 unsafe fn generate_mipmaps(ctxt: &CommandContext,
                            bind_point: gl::types::GLenum) {
     if ctxt.version >= &Version(Api::Gl, 3, 0) ||
@@ -343,6 +358,13 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
                 if is_client_compressed && !data_raw.is_null() {
                     ctxt.gl.CompressedTexImage2D(bind_point, 0, teximg_internal_format as u32,
                                        width, height, 0, data_bufsize as i32, data_raw);
+                } else if bind_point == gl::TEXTURE_CUBE_MAP {
+                    // each face of a cubemap must be allocated separately
+                    for face in 0 .. 6 {
+                        ctxt.gl.TexImage2D(gl::TEXTURE_CUBE_MAP_POSITIVE_X + face, 0,
+                                           teximg_internal_format as i32, width, height, 0,
+                                           client_format as u32, client_type, data_raw);
+                    }
                 } else {
                     ctxt.gl.TexImage2D(bind_point, 0, teximg_internal_format as i32, width,
                                        height, 0, client_format as u32, client_type, data_raw);
@@ -455,7 +477,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         id
     };
 
-    Ok(TextureAny {
+    let texture = TextureAny {
         context: facade.get_context().clone(),
         id: id,
         requested_format: format,
@@ -464,7 +486,13 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         levels: texture_levels as u32,
         generate_mipmaps: should_generate_mipmaps,
         owned: true
-    })
+    };
+
+    context::register_allocation(facade.get_context(), context::AllocationKind::Texture, id,
+                                 texture.estimate_memory_size(),
+                                 Some(describe_format(&texture.requested_format)));
+
+    Ok(texture)
 }
 
 /// Builds a new texture reference from an existing, externally created OpenGL texture.
@@ -498,6 +526,20 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
 }
 
 impl TextureAny {
+    /// Sets the label of this texture. If you use an OpenGL debugger, the texture will be shown
+    /// with this name.
+    ///
+    /// The label is also shown by `Context::dump_resources`.
+    ///
+    /// Returns `Err` if the backend doesn't support labels.
+    pub fn set_object_label(&self, label: &str) -> Result<(), ()> {
+        context::set_allocation_name(&self.context, context::AllocationKind::Texture, self.id,
+                                     label);
+
+        let ctxt = self.context.make_current();
+        context::set_object_label(&ctxt, gl::TEXTURE, self.id, label)
+    }
+
     /// Returns the width of the texture.
     #[inline]
     pub fn get_width(&self) -> u32 {
@@ -654,6 +696,35 @@ impl TextureAny {
         self.levels
     }
 
+    /// Returns an estimate of the number of bytes of video memory used by this texture.
+    ///
+    /// The size is computed from the requested format when it is specific, so that the backend
+    /// is only queried for the other requests. If the internal format can't be determined, four
+    /// bytes per texel are assumed.
+    fn estimate_memory_size(&self) -> usize {
+        let bits_per_texel = match self.requested_format {
+            TextureFormatRequest::Specific(format) => format.get_total_bits(),
+            _ => self.get_internal_format().map(|f| f.get_total_bits()).unwrap_or(32),
+        };
+
+        let (width, height, depth, array_size, samples) = extract_dimensions(self.ty);
+        let faces = match self.ty {
+            Dimensions::Cubemap { .. } => 6,
+            _ => 1,
+        };
+        let layers = array_size.unwrap_or(1) as usize * faces * samples.unwrap_or(1) as usize;
+
+        let mut texels = 0;
+        for level in 0 .. self.levels {
+            let w = cmp::max(width >> level, 1) as usize;
+            let h = cmp::max(height.unwrap_or(1) >> level, 1) as usize;
+            let d = cmp::max(depth.unwrap_or(1) >> level, 1) as usize;
+            texels += w * h * d;
+        }
+
+        texels * layers * bits_per_texel / 8
+    }
+
     /// Returns a structure that represents the main mipmap level of the texture.
     #[inline]
     pub fn main_level(&self) -> TextureAnyMipmap {
@@ -686,6 +757,65 @@ impl TextureAny {
         self.bind_to_current(&mut ctxt);
         generate_mipmaps(&ctxt, self.get_bind_point());
     }
+
+    /// Sets all the texels of a mipmap level to the same value, including all the layers of
+    /// array textures and all the faces of cubemaps.
+    ///
+    /// This uses `glClearTexImage` if it is supported, and otherwise clears each layer by
+    /// attaching it to a framebuffer.
+    ///
+    /// Returns an error if the fallback is used and a layer can't be attached to a framebuffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the level doesn't exist. Panics if `data` does not match the kind of texture.
+    /// For example passing a `[i32; 4]` when using a regular (float) texture.
+    pub fn clear<D>(&self, level: u32, data: D) -> Result<(), fbo::ValidationError>
+                    where D: Into<ClearBufferData>
+    {
+        let data = data.into();
+        let mipmap = self.mipmap(level).expect("The mipmap level doesn't exist");
+
+        match (self.kind(), data) {
+            (TextureKind::Float, ClearBufferData::Float(_)) => (),
+            (TextureKind::Integral, ClearBufferData::Integral(_)) => (),
+            (TextureKind::Unsigned, ClearBufferData::Unsigned(_)) => (),
+            (TextureKind::Depth, ClearBufferData::Depth(_)) => (),
+            (TextureKind::Stencil, ClearBufferData::Stencil(_)) => (),
+            (TextureKind::DepthStencil, ClearBufferData::DepthStencil(_, _)) => (),
+            _ => panic!("The data passed to `clear` does not match the kind of texture")
+        };
+
+        {
+            let mut ctxt = self.context.make_current();
+
+            if ctxt.version >= &Version(Api::Gl, 4, 4) || ctxt.extensions.gl_arb_clear_texture {
+                unsafe { clear_tex_image(&mut ctxt, self.id, level, data) };
+                return Ok(());
+            }
+        }
+
+        // the depth of 3D textures is divided at each mipmap level
+        let layers = self.get_array_size().or(mipmap.get_depth()).unwrap_or(1);
+        let cube_layers = match self.ty {
+            Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } => vec![
+                Some(CubeLayer::PositiveX), Some(CubeLayer::NegativeX),
+                Some(CubeLayer::PositiveY), Some(CubeLayer::NegativeY),
+                Some(CubeLayer::PositiveZ), Some(CubeLayer::NegativeZ),
+            ],
+            _ => vec![None],
+        };
+
+        for layer in (0 .. layers).filter_map(|layer| mipmap.layer(layer)) {
+            for &cube_layer in &cube_layers {
+                if let Some(image) = layer.into_image(cube_layer) {
+                    try!(clear_image(&self.context, image, data));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl TextureExt for TextureAny {
@@ -707,6 +837,13 @@ impl TextureExt for TextureAny {
     fn bind_to_current(&self, ctxt: &mut CommandContext) -> gl::types::GLenum {
         let bind_point = self.get_bind_point();
 
+        // the texture is bound to be uploaded to or read from, and may have been written through
+        // an image unit by a previous command
+        if ctxt.state.latest_image_write >= ctxt.state.latest_memory_barrier_texture_update {
+            unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_UPDATE_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_texture_update = ctxt.state.next_draw_call_id;
+        }
+
         let texture_unit = ctxt.state.active_texture;
         if ctxt.state.texture_units[texture_unit as usize].texture != self.id {
             unsafe { ctxt.gl.BindTexture(bind_point, self.id) };
@@ -737,22 +874,180 @@ impl fmt::Debug for TextureAny {
 
 impl Drop for TextureAny {
     fn drop(&mut self) {
+        if context::is_destruction_deferred(&self.context) {
+            context::defer_destruction(&self.context, context::PendingDestruction::Texture {
+                id: self.id,
+                owned: self.owned,
+            });
+            return;
+        }
+
         let mut ctxt = self.context.make_current();
+        unsafe { destroy_dropped_texture(&mut ctxt, self.id, self.owned); }
 
-        // removing FBOs which contain this texture
-        fbo::FramebuffersContainer::purge_texture(&mut ctxt, self.id);
+        if self.owned {
+            context::unregister_allocation(&self.context, context::AllocationKind::Texture,
+                                           self.id);
+        }
+    }
+}
 
-        // resetting the bindings
-        for tex_unit in ctxt.state.texture_units.iter_mut() {
-            if tex_unit.texture == self.id {
-                tex_unit.texture = 0;
-            }
+/// Removes a texture that has been dropped from the caches of the context, and deletes it if
+/// it is owned by glium.
+#[doc(hidden)]
+pub unsafe fn destroy_dropped_texture(ctxt: &mut CommandContext, id: gl::types::GLuint,
+                                      owned: bool)
+{
+    // removing FBOs which contain this texture
+    fbo::FramebuffersContainer::purge_texture(ctxt, id);
+
+    // resetting the bindings
+    for tex_unit in ctxt.state.texture_units.iter_mut() {
+        if tex_unit.texture == id {
+            tex_unit.texture = 0;
         }
+    }
 
-        if self.owned {
-            unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
+    for image_unit in ctxt.state.image_units.iter_mut() {
+        if image_unit.texture == id {
+            image_unit.texture = 0;
         }
     }
+
+    if owned {
+        ctxt.gl.DeleteTextures(1, [ id ].as_ptr());
+    }
+}
+
+/// A texture that has been detached from its context, so that it can be sent to another thread.
+///
+/// Obtained with the `into_shared` method of a texture, and turned back into a texture with its
+/// `from_shared` function. The context that receives the texture must share its objects with the
+/// context that detached it, which is the case of the contexts created with
+/// `Display::new_shared_context`.
+///
+/// The commands that modify the texture are submitted when it is detached, and the context that
+/// receives it waits for them to be finished before executing its own commands.
+///
+/// If this object is destroyed without being turned back into a texture, the texture is destroyed
+/// by the next context of the share group that calls `Context::collect`, which happens
+/// automatically when a frame is finished.
+pub struct SharedTexture<T> {
+    id: gl::types::GLuint,
+    requested_format: TextureFormatRequest,
+    ty: Dimensions,
+    levels: u32,
+    generate_mipmaps: bool,
+    owned: bool,
+    share_group: Arc<ShareGroup>,
+
+    /// Signaled when the commands that modify the texture are finished. `None` if the backend
+    /// doesn't support fences, in which case these commands have already finished.
+    fence: Option<LinearSyncFence>,
+
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for SharedTexture<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "Shared texture #{} ({:?})", self.id, self.ty)
+    }
+}
+
+impl<T> Drop for SharedTexture<T> {
+    fn drop(&mut self) {
+        // there is no context here, so the texture is destroyed by the next context of the share
+        // group that collects its objects
+        if let Some(fence) = self.fence.take() {
+            context::orphan_shared_object(&self.share_group, PendingDestruction::Fence(fence));
+        }
+
+        context::orphan_shared_object(&self.share_group, PendingDestruction::Texture {
+            id: self.id,
+            owned: self.owned,
+        });
+    }
+}
+
+/// Detaches a texture from its context.
+pub fn into_shared<T>(mut texture: TextureAny) -> SharedTexture<T> {
+    let fence = {
+        let mut ctxt = texture.context.make_current();
+
+        match unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+            Ok(fence) => {
+                unsafe { ctxt.gl.Flush(); }
+                Some(fence)
+            },
+            Err(_) => {
+                unsafe { ctxt.gl.Finish(); }
+                ctxt.fallbacks.record(Fallback::FinishInsteadOfFence);
+                None
+            },
+        }
+    };
+
+    if texture.owned {
+        context::unregister_allocation(&texture.context, context::AllocationKind::Texture,
+                                       texture.id);
+    }
+
+    let shared = SharedTexture {
+        id: texture.id,
+        requested_format: texture.requested_format,
+        ty: texture.ty,
+        levels: texture.levels,
+        generate_mipmaps: texture.generate_mipmaps,
+        owned: texture.owned,
+        share_group: context::get_share_group(&texture.context),
+        fence: fence,
+        marker: PhantomData,
+    };
+
+    // the OpenGL texture now belongs to the `SharedTexture`
+    texture.owned = false;
+    shared
+}
+
+/// Attaches a texture that has been detached with `into_shared` to the context of `facade`.
+///
+/// # Panic
+///
+/// Panics if the context doesn't share its objects with the context that detached the texture.
+pub fn from_shared<F: ?Sized, T>(facade: &F, mut shared: SharedTexture<T>) -> TextureAny
+                                 where F: Facade
+{
+    assert!(Arc::ptr_eq(&shared.share_group, &context::get_share_group(facade.get_context())),
+            "The context doesn't share its objects with the context of the texture");
+
+    if let Some(fence) = shared.fence.take() {
+        let mut ctxt = facade.get_context().make_current();
+        unsafe { sync::server_wait_linear_sync_fence_and_drop(fence, &mut ctxt); }
+    }
+
+    let texture = TextureAny {
+        context: facade.get_context().clone(),
+        id: shared.id,
+        requested_format: shared.requested_format,
+        actual_format: Cell::new(None),
+        ty: shared.ty,
+        levels: shared.levels,
+        generate_mipmaps: shared.generate_mipmaps,
+        owned: shared.owned,
+    };
+
+    // the OpenGL texture now belongs to the `TextureAny`, so only the share group is released
+    let share_group = unsafe { ptr::read(&shared.share_group) };
+    mem::forget(shared);
+    drop(share_group);
+
+    if texture.owned {
+        context::register_allocation(facade.get_context(), context::AllocationKind::Texture,
+                                     texture.id, texture.estimate_memory_size(),
+                                     Some(describe_format(&texture.requested_format)));
+    }
+
+    texture
 }
 
 /// Represents a specific layer of an array texture and 3D textures.
@@ -956,6 +1251,105 @@ impl<'a> TextureAnyMipmap<'a> {
         self.raw_upload_from_pixel_buffer_impl(source, x, y, z, true);
     }
 
+    /// Uploads a rectangle of pixels whose layout in `data` is described by `layout`.
+    ///
+    /// This allows uploading a sub-rectangle of a larger image, or rows that are padded, without
+    /// repacking the data. If the backend doesn't support `GL_UNPACK_ROW_LENGTH` (OpenGL ES 2
+    /// without `GL_EXT_unpack_subimage`), the rows are copied to a temporary buffer first.
+    ///
+    /// Mipmaps are not regenerated.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the rect is out of range.
+    /// - Panics if `data` is too small for the rectangle and the layout.
+    /// - Panics if the texture is not a 2D texture or a 1D texture array.
+    /// - Panics if the format of the texture can't be written with this type of pixels.
+    ///
+    pub fn raw_upload_with_layout<P>(&self, rect: &Rect, data: &[P], layout: &PixelLayout)
+                                     where P: PixelValue
+    {
+        assert!(rect.left + rect.width <= self.width);
+        assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
+
+        let bind_point = self.texture.get_bind_point();
+        assert!(bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY,
+                "Uploading with a layout is only supported for 2D textures and 1D texture \
+                 arrays");
+
+        let pixel_size = mem::size_of::<P>();
+        assert!(data.len() * pixel_size >=
+                layout.required_bytes(rect.width, rect.height, pixel_size),
+                "The data is too small for the rectangle and the layout");
+
+        if rect.width == 0 || rect.height == 0 {
+            return;
+        }
+
+        let client_format = ClientFormatAny::ClientFormat(P::get_format());
+
+        let mut ctxt = self.texture.context.make_current();
+
+        let supported = ctxt.version >= &Version(Api::Gl, 1, 0) ||
+                        ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                        ctxt.extensions.gl_ext_unpack_subimage;
+
+        if !supported {
+            // copying the rows into a tightly packed buffer
+            let stride = layout.row_stride(rect.width, pixel_size);
+            let row_bytes = rect.width as usize * pixel_size;
+            let bytes = unsafe {
+                slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * pixel_size)
+            };
+
+            let mut packed = Vec::with_capacity(row_bytes * rect.height as usize);
+            for row in 0 .. rect.height as usize {
+                let start = layout.offset(rect.width, pixel_size) + row * stride;
+                packed.extend_from_slice(&bytes[start .. start + row_bytes]);
+            }
+
+            drop(ctxt);
+            self.upload_texture(rect.left, rect.bottom, 0, (client_format, Cow::Owned(packed)),
+                                rect.width, Some(rect.height), None, false)
+                .expect("The texture can't be written with this type of pixels");
+            return;
+        }
+
+        let (format, ty) = image_format::client_format_to_glenum(&self.texture.context,
+                                                                 client_format,
+                                                                 self.texture.requested_format,
+                                                                 false)
+                            .expect("The texture can't be written with this type of pixels");
+
+        unsafe {
+            BufferAny::unbind_pixel_unpack(&mut ctxt);
+
+            if ctxt.state.pixel_store_unpack_alignment != layout.alignment as gl::types::GLint {
+                ctxt.state.pixel_store_unpack_alignment = layout.alignment as gl::types::GLint;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, layout.alignment as gl::types::GLint);
+            }
+
+            ctxt.gl.PixelStorei(gl::UNPACK_ROW_LENGTH,
+                                layout.row_length.unwrap_or(0) as gl::types::GLint);
+            ctxt.gl.PixelStorei(gl::UNPACK_SKIP_PIXELS, layout.skip_pixels as gl::types::GLint);
+            ctxt.gl.PixelStorei(gl::UNPACK_SKIP_ROWS, layout.skip_rows as gl::types::GLint);
+
+            self.texture.bind_to_current(&mut ctxt);
+
+            ctxt.gl.TexSubImage2D(bind_point, self.level as gl::types::GLint,
+                                  rect.left as gl::types::GLint,
+                                  rect.bottom as gl::types::GLint,
+                                  rect.width as gl::types::GLsizei,
+                                  rect.height as gl::types::GLsizei,
+                                  format, ty, data.as_ptr() as *const _);
+
+            // the rest of glium assumes that these parameters are 0
+            ctxt.gl.PixelStorei(gl::UNPACK_ROW_LENGTH, 0);
+            ctxt.gl.PixelStorei(gl::UNPACK_SKIP_PIXELS, 0);
+            ctxt.gl.PixelStorei(gl::UNPACK_SKIP_ROWS, 0);
+        }
+    }
+
     fn raw_upload_from_pixel_buffer_impl<P>(&self, source: BufferSlice<[P]>, x: Range<u32>,
                                             y: Range<u32>, z: Range<u32>, inverted: bool)
                                             where P: PixelValue
@@ -986,6 +1380,13 @@ impl<'a> TextureAnyMipmap<'a> {
         // binds the pixel buffer
         source.prepare_and_bind_for_pixel_unpack(&mut ctxt);
 
+        unsafe {
+            if ctxt.state.pixel_store_unpack_alignment != 1 {
+                ctxt.state.pixel_store_unpack_alignment = 1;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            }
+        }
+
         match self.texture.ty {
             Dimensions::Texture1d { .. } => {
                 if ctxt.version >= &Version(Api::Gl, 4, 5) ||
@@ -1223,6 +1624,11 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
 
         let mut ctxt = texture.context.make_current();
 
+        // OpenGL ES doesn't have `glGetCompressedTexImage`
+        if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+            return None;
+        }
+
         unsafe {
             let bind_point = texture.bind_to_current(&mut ctxt);
 
@@ -1351,6 +1757,94 @@ impl<'a> TextureAnyLayerMipmap<'a> {
     }
 }
 
+/// Clears a mipmap level of a texture with `glClearTexImage`.
+///
+/// `data` must match the kind of the texture.
+unsafe fn clear_tex_image(ctxt: &mut CommandContext, id: gl::types::GLuint, level: u32,
+                          data: ClearBufferData)
+{
+    let level = level as gl::types::GLint;
+
+    match data {
+        ClearBufferData::Float(data) => {
+            ctxt.gl.ClearTexImage(id, level, gl::RGBA, gl::FLOAT, data.as_ptr() as *const _);
+        },
+        ClearBufferData::Integral(data) => {
+            ctxt.gl.ClearTexImage(id, level, gl::RGBA_INTEGER, gl::INT,
+                                  data.as_ptr() as *const _);
+        },
+        ClearBufferData::Unsigned(data) => {
+            ctxt.gl.ClearTexImage(id, level, gl::RGBA_INTEGER, gl::UNSIGNED_INT,
+                                  data.as_ptr() as *const _);
+        },
+        ClearBufferData::Depth(depth) => {
+            ctxt.gl.ClearTexImage(id, level, gl::DEPTH_COMPONENT, gl::FLOAT,
+                                  &depth as *const f32 as *const _);
+        },
+        ClearBufferData::Stencil(stencil) => {
+            ctxt.gl.ClearTexImage(id, level, gl::STENCIL_INDEX, gl::INT,
+                                  &stencil as *const i32 as *const _);
+        },
+        ClearBufferData::DepthStencil(depth, stencil) => {
+            // the depth is stored in the 24 most significant bits
+            let depth = (depth.max(0.0).min(1.0) as f64 * 16777215.0).round() as u32;
+            let value: u32 = (depth << 8) | (stencil as u32 & 0xff);
+            ctxt.gl.ClearTexImage(id, level, gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8,
+                                  &value as *const u32 as *const _);
+        },
+    }
+}
+
+/// Clears an image of a texture by attaching it to a framebuffer.
+///
+/// `data` must match the kind of the texture.
+fn clear_image(context: &Rc<Context>, image: TextureAnyImage, data: ClearBufferData)
+               -> Result<(), fbo::ValidationError>
+{
+    let attachment = fbo::RegularAttachment::Texture(image);
+
+    let (colors, depth_stencil) = match data {
+        ClearBufferData::Integral(_) | ClearBufferData::Unsigned(_) => {
+            // `glClear` can't be used with integral textures
+            image.raw_clear_buffer(data);
+            return Ok(());
+        },
+        ClearBufferData::Float(_) => {
+            let mut colors = SmallVec::new();
+            colors.push((0, attachment));
+            (colors, fbo::DepthStencilAttachments::None)
+        },
+        ClearBufferData::Depth(_) => {
+            (SmallVec::new(), fbo::DepthStencilAttachments::DepthAttachment(attachment))
+        },
+        ClearBufferData::Stencil(_) => {
+            (SmallVec::new(), fbo::DepthStencilAttachments::StencilAttachment(attachment))
+        },
+        ClearBufferData::DepthStencil(_, _) => {
+            (SmallVec::new(), fbo::DepthStencilAttachments::DepthStencilAttachment(attachment))
+        },
+    };
+
+    let attachments = fbo::FramebufferAttachments::Regular(fbo::FramebufferSpecificAttachments {
+        colors: colors,
+        depth_stencil: depth_stencil,
+    });
+
+    let attachments = try!(attachments.validate(context));
+
+    let (color, depth, stencil) = match data {
+        ClearBufferData::Float(c) => (Some((c[0], c[1], c[2], c[3])), None, None),
+        ClearBufferData::Depth(d) => (None, Some(d), None),
+        ClearBufferData::Stencil(s) => (None, None, Some(s)),
+        ClearBufferData::DepthStencil(d, s) => (None, Some(d), Some(s)),
+        _ => unreachable!()
+    };
+
+    // the values are written as they are, like `glClearTexImage` does
+    ops::clear(context, Some(&attachments), None, color, true, depth, stencil);
+    Ok(())
+}
+
 /// Represents a specific 2D image of a texture. 1D textures are considered as having a height of 1.
 #[derive(Copy, Clone)]
 pub struct TextureAnyImage<'a> {
@@ -1427,12 +1921,67 @@ impl<'a> TextureAnyImage<'a> {
         let mut ctxt = self.texture.context.make_current();
 
         let mut data = Vec::new();
-        ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, &mut data, false)
+        ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, &mut data, false,
+                  ChannelOrder::Rgba)
             .unwrap();
 
         T::from_raw(Cow::Owned(data), self.width, self.height.unwrap_or(1))
     }
 
+    /// Reads the content of the image in the client format of `P`, with the components in the
+    /// given order.
+    ///
+    /// With OpenGL, the conversion is always done by the driver. With OpenGL ES, the driver only
+    /// supports `(u8, u8, u8, u8)` and an implementation-defined format. Other formats are read
+    /// as `(u8, u8, u8, u8)` then converted in software, which only supports unsigned normalized
+    /// and floating-point formats with 8, 16 or 32 bits per component. Note that in this case
+    /// the precision is limited to 8 bits per component.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rect is out of range.
+    ///
+    pub fn raw_read_with_format<T, P>(&self, rect: &Rect, order: ChannelOrder)
+                                      -> Result<T, ReadError>
+                                      where T: Texture2dDataSink<P>, P: PixelValue
+    {
+        assert!(rect.left + rect.width <= self.width);
+        assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
+
+        let mut ctxt = self.texture.context.make_current();
+
+        let mut data = Vec::new();
+        try!(ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, &mut data,
+                       false, order));
+
+        Ok(T::from_raw(Cow::Owned(data), rect.width, rect.height))
+    }
+
+    /// Reads a rectangle of the image into `dest`, with the layout described by `layout`.
+    ///
+    /// This allows reading into a sub-rectangle of a larger image, or into rows that are padded,
+    /// without repacking the data afterwards. The pixels of `dest` that are outside of the
+    /// rectangle are left untouched. If the backend doesn't support `GL_PACK_ROW_LENGTH`
+    /// (OpenGL ES 2 without `GL_NV_pack_subimage`), the rows are copied from a temporary buffer.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the rect is out of range.
+    /// - Panics if `dest` is too small for the rectangle and the layout.
+    /// - Panics if it fails to read the texture.
+    ///
+    pub fn raw_read_with_layout<P>(&self, rect: &Rect, dest: &mut [P], layout: &PixelLayout)
+        where P: PixelValue
+    {
+        assert!(rect.left + rect.width <= self.width);
+        assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
+
+        let mut ctxt = self.texture.context.make_current();
+        ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect,
+                  ops::Destination::MemoryWithLayout(dest, layout), false,
+                  ChannelOrder::Rgba).unwrap();
+    }
+
     /// Reads the content of the image to a pixel buffer.
     ///
     /// # Panic
@@ -1450,7 +1999,8 @@ impl<'a> TextureAnyImage<'a> {
 
         let size = rect.width as usize * rect.height as usize * 4;
         let mut ctxt = self.texture.context.make_current();
-        ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, dest, false)
+        ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, dest, false,
+                  ChannelOrder::Rgba)
             .unwrap();
     }
 
@@ -0,0 +1,312 @@
+/*!
+Atlases pack many small images, like the glyphs of a font, in a single texture.
+
+A `GlyphAtlas` owns a `Texture2d` and remembers where each image has been uploaded, along
+with its texture coordinates. It doesn't know anything about fonts: a font rasterizer produces
+images in any format supported by `Texture2dDataSource`, and the text library draws quads
+with the texture coordinates of the `AtlasEntry` of each glyph.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# fn rasterize(c: char) -> glium::texture::RawImage2d<'static, u8> { unimplemented!() }
+use glium::texture::UncompressedFloatFormat;
+use glium::texture::atlas::GlyphAtlas;
+
+let mut atlas = GlyphAtlas::new(&display, UncompressedFloatFormat::U8, 512, 512, 1).unwrap();
+
+for c in "Hello".chars() {
+    let entry = atlas.get_or_insert_with(c, || rasterize(c)).unwrap();
+    println!("{:?} is at {:?}", c, entry.uv_min);
+}
+```
+
+The packing itself is done by an `AtlasAllocator`, which can be used on its own if you manage
+the texture yourself.
+
+*/
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+use backend::Facade;
+use texture::{MipmapsOption, PixelValue, Texture2d, Texture2dDataSource};
+use texture::{TextureCreationError, UncompressedFloatFormat};
+
+use Rect;
+use Surface;
+
+/// Finds free rectangles in an area, by placing them on rows called shelves.
+///
+/// Shelf packing works well when the rectangles have similar heights, which is the case of the
+/// glyphs of a font.
+#[derive(Debug, Clone)]
+pub struct AtlasAllocator {
+    width: u32,
+    height: u32,
+    padding: u32,
+    // `(bottom, height, used width)` of each shelf
+    shelves: Vec<(u32, u32, u32)>,
+}
+
+impl AtlasAllocator {
+    /// Builds an allocator for an area of `width` by `height` pixels. Each rectangle is
+    /// surrounded by `padding` free pixels, so that linear filtering doesn't mix the borders of
+    /// neighbouring rectangles.
+    #[inline]
+    pub fn new(width: u32, height: u32, padding: u32) -> AtlasAllocator {
+        AtlasAllocator {
+            width: width,
+            height: height,
+            padding: padding,
+            shelves: Vec::new(),
+        }
+    }
+
+    /// Returns the dimensions of the area.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the number of free pixels around each rectangle.
+    #[inline]
+    pub fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    /// Finds a free rectangle of `width` by `height` pixels. Returns `None` if there is not
+    /// enough space left.
+    pub fn allocate(&mut self, width: u32, height: u32) -> Option<Rect> {
+        let cell_width = width + 2 * self.padding;
+        let cell_height = height + 2 * self.padding;
+
+        if cell_width > self.width {
+            return None;
+        }
+
+        // the lowest shelf that is high enough wastes the least space
+        let best = self.shelves.iter().enumerate()
+                       .filter(|&(_, &(_, h, used))| h >= cell_height &&
+                                                     used + cell_width <= self.width)
+                       .min_by_key(|&(_, &(_, h, _))| h)
+                       .map(|(i, _)| i);
+
+        let shelf = match best {
+            Some(i) => i,
+            None => {
+                let bottom = self.shelves.last().map(|&(b, h, _)| b + h).unwrap_or(0);
+                if bottom + cell_height > self.height {
+                    return None;
+                }
+
+                self.shelves.push((bottom, cell_height, 0));
+                self.shelves.len() - 1
+            },
+        };
+
+        let (bottom, _, ref mut used) = self.shelves[shelf];
+        let rect = Rect {
+            left: *used + self.padding,
+            bottom: bottom + self.padding,
+            width: width,
+            height: height,
+        };
+
+        *used += cell_width;
+        Some(rect)
+    }
+
+    /// Forgets all the rectangles.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+    }
+}
+
+/// Location of an image in an atlas.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AtlasEntry {
+    /// The pixels of the texture that contain the image.
+    pub rect: Rect,
+
+    /// The texture coordinates of the bottom-left corner of the image.
+    pub uv_min: [f32; 2],
+
+    /// The texture coordinates of the top-right corner of the image.
+    pub uv_max: [f32; 2],
+}
+
+impl AtlasEntry {
+    fn new(rect: Rect, (width, height): (u32, u32)) -> AtlasEntry {
+        AtlasEntry {
+            rect: rect,
+            uv_min: [rect.left as f32 / width as f32, rect.bottom as f32 / height as f32],
+            uv_max: [(rect.left + rect.width) as f32 / width as f32,
+                     (rect.bottom + rect.height) as f32 / height as f32],
+        }
+    }
+}
+
+/// A texture that contains many images, each one identified by a key of type `K`.
+pub struct GlyphAtlas<K> {
+    texture: Texture2d,
+    allocator: AtlasAllocator,
+    entries: HashMap<K, AtlasEntry>,
+}
+
+impl<K> GlyphAtlas<K> where K: Hash + Eq {
+    /// Builds an empty atlas. The texture is cleared to zero so that the padding around the
+    /// images is transparent.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32,
+                          padding: u32) -> Result<GlyphAtlas<K>, TextureCreationError>
+                          where F: Facade
+    {
+        let texture = try!(Texture2d::empty_with_format(facade, format, MipmapsOption::NoMipmap,
+                                                        width, height));
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+        Ok(GlyphAtlas {
+            texture: texture,
+            allocator: AtlasAllocator::new(width, height, padding),
+            entries: HashMap::new(),
+        })
+    }
+
+    /// Returns the texture that contains the images.
+    #[inline]
+    pub fn texture(&self) -> &Texture2d {
+        &self.texture
+    }
+
+    /// Returns the location of an image.
+    #[inline]
+    pub fn get(&self, key: &K) -> Option<&AtlasEntry> {
+        self.entries.get(key)
+    }
+
+    /// Uploads an image and returns its location. If the key was already in the atlas, the
+    /// new image is uploaded to a new location and replaces the previous one.
+    ///
+    /// Images with a width or a height of zero, like the glyph of a space, don't use any space
+    /// and have an empty `rect`.
+    pub fn insert<'a, T>(&mut self, key: K, image: T) -> Result<AtlasEntry, AtlasError>
+                         where T: Texture2dDataSource<'a>, T::Data: PixelValue
+    {
+        let image = image.into_raw();
+
+        let rect = if image.width == 0 || image.height == 0 {
+            Rect { left: 0, bottom: 0, width: 0, height: 0 }
+        } else {
+            let rect = match self.allocator.allocate(image.width, image.height) {
+                Some(r) => r,
+                None => return Err(AtlasError::Full),
+            };
+
+            self.texture.write(rect, image);
+            rect
+        };
+
+        let entry = AtlasEntry::new(rect, self.allocator.dimensions());
+        self.entries.insert(key, entry);
+        Ok(entry)
+    }
+
+    /// Returns the location of an image, or uploads the image returned by `image` if the key
+    /// isn't in the atlas yet.
+    pub fn get_or_insert_with<'a, T, F>(&mut self, key: K, image: F)
+                                        -> Result<AtlasEntry, AtlasError>
+                                        where T: Texture2dDataSource<'a>, T::Data: PixelValue,
+                                              F: FnOnce() -> T
+    {
+        if let Some(entry) = self.entries.get(&key) {
+            return Ok(*entry);
+        }
+
+        self.insert(key, image())
+    }
+
+    /// Returns the number of images in the atlas.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the atlas doesn't contain any image.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all the images, for example when the atlas is full. The content of the texture
+    /// is cleared as well.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.allocator.clear();
+        self.texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    }
+}
+
+/// Error that can happen when uploading an image to an atlas.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AtlasError {
+    /// There is not enough space left in the texture. You can clear the atlas, or create a
+    /// bigger one.
+    Full,
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for AtlasError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            AtlasError::Full => "There is not enough space left in the atlas",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtlasAllocator, AtlasEntry};
+    use Rect;
+
+    #[test]
+    fn shelves() {
+        let mut allocator = AtlasAllocator::new(16, 16, 1);
+
+        let a = allocator.allocate(6, 4).unwrap();
+        assert_eq!(a, Rect { left: 1, bottom: 1, width: 6, height: 4 });
+
+        let b = allocator.allocate(6, 3).unwrap();
+        assert_eq!(b, Rect { left: 9, bottom: 1, width: 6, height: 3 });
+
+        // doesn't fit on the first shelf anymore
+        let c = allocator.allocate(4, 4).unwrap();
+        assert_eq!(c, Rect { left: 1, bottom: 7, width: 4, height: 4 });
+
+        // lower than the first shelf, but the second one is the lowest that fits
+        let d = allocator.allocate(2, 2).unwrap();
+        assert_eq!(d, Rect { left: 7, bottom: 7, width: 2, height: 2 });
+
+        assert!(allocator.allocate(15, 1).is_none());
+        assert!(allocator.allocate(4, 5).is_none());
+
+        allocator.clear();
+        assert_eq!(allocator.allocate(14, 14).unwrap(),
+                   Rect { left: 1, bottom: 1, width: 14, height: 14 });
+    }
+
+    #[test]
+    fn texture_coordinates() {
+        let entry = AtlasEntry::new(Rect { left: 64, bottom: 32, width: 64, height: 32 },
+                                    (256, 128));
+        assert_eq!(entry.uv_min, [0.25, 0.25]);
+        assert_eq!(entry.uv_max, [0.5, 0.5]);
+    }
+}
@@ -56,6 +56,47 @@ currently doesn't check whether the type of your texture matches the expected ty
 do in the future). Binding the wrong type of texture may lead to undefined values when sampling
 the texture.
 
+A `TextureHandle`, or a slice or a `Vec` of handles, can also be passed directly as the value of
+a sampler uniform or of an array of samplers. With the default validation level, drawing returns
+`DrawError::TextureHandleNotResident` if one of the handles doesn't belong to a texture that is
+still resident.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let texture: glium::texture::bindless::ResidentTexture = unsafe { std::mem::uninitialized() };
+let handles = vec![glium::texture::TextureHandle::new(&texture, &Default::default()); 4];
+let uniforms = uniform! { textures: &handles[..] };
+# }
+```
+
+In a `std140` uniform block, the elements of an array of samplers are 16 bytes apart. Use an
+array of `PaddedTextureHandle` to upload them.
+
+```no_run
+#[macro_use]
+extern crate glium;
+
+# fn main() {
+use glium::texture::bindless::PaddedTextureHandle;
+
+#[derive(Copy, Clone)]
+struct Textures<'a> {
+    textures: [PaddedTextureHandle<'a>; 2],
+}
+
+implement_uniform_block!(Textures<'a>, textures);
+
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let texture: glium::texture::bindless::ResidentTexture = unsafe { std::mem::uninitialized() };
+let handle = glium::texture::TextureHandle::new(&texture, &Default::default());
+let uniform_buffer = glium::uniforms::UniformBuffer::new(&display, Textures {
+    textures: [PaddedTextureHandle::new(handle); 2],
+});
+# }
+```
+
 */
 use texture::any::TextureAny;
 use TextureExt;
@@ -64,13 +105,17 @@ use GlObject;
 use ContextExt;
 use gl;
 
+use std::error::Error;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::slice;
 
 use program::BlockLayout;
 use uniforms::AsUniformValue;
 use uniforms::LayoutMismatchError;
 use uniforms::UniformBlock;
+use uniforms::UniformArray;
 use uniforms::UniformValue;
 use uniforms::UniformType;
 use uniforms::SamplerBehavior;
@@ -151,6 +196,7 @@ impl Drop for ResidentTexture {
 
 /// Represents a handle to a texture. Contains a raw pointer to a texture that is hidden from you.
 #[derive(Copy, Clone)]
+#[repr(C)]
 pub struct TextureHandle<'a> {
     value: gl::types::GLuint64,
     marker: PhantomData<&'a ResidentTexture>,
@@ -178,8 +224,30 @@ impl<'a> TextureHandle<'a> {
 impl<'a> AsUniformValue for TextureHandle<'a> {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
-        // TODO: u64
-        unimplemented!();
+        UniformValue::TextureHandle(self.value)
+    }
+}
+
+impl<'a, 'b> AsUniformValue for &'a [TextureHandle<'b>] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        // `TextureHandle` is `repr(C)` and only contains the handle
+        let handles = unsafe {
+            slice::from_raw_parts(self.as_ptr() as *const gl::types::GLuint64, self.len())
+        };
+
+        UniformValue::Array(UniformArray::TextureHandle(handles))
+    }
+}
+
+impl<'a> AsUniformValue for Vec<TextureHandle<'a>> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        let handles = unsafe {
+            slice::from_raw_parts(self.as_ptr() as *const gl::types::GLuint64, self.len())
+        };
+
+        UniformValue::Array(UniformArray::TextureHandle(handles))
     }
 }
 
@@ -284,19 +352,78 @@ impl<'a> UniformBlock for TextureHandle<'a> {
     }
 }
 
+/// A `TextureHandle` followed by 8 bytes of padding.
+///
+/// The elements of an array of samplers in a `std140` uniform block are 16 bytes apart, while
+/// the elements of an array of `TextureHandle`s are 8 bytes apart.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct PaddedTextureHandle<'a> {
+    handle: TextureHandle<'a>,
+    padding: u64,
+}
+
+impl<'a> PaddedTextureHandle<'a> {
+    /// Builds a new padded handle.
+    #[inline]
+    pub fn new(handle: TextureHandle<'a>) -> PaddedTextureHandle<'a> {
+        PaddedTextureHandle {
+            handle: handle,
+            padding: 0,
+        }
+    }
+
+    /// Sets the value to the given handle.
+    #[inline]
+    pub fn set(&mut self, handle: TextureHandle<'a>) {
+        self.handle = handle;
+    }
+}
+
+impl<'a> UniformBlock for PaddedTextureHandle<'a> {
+    #[inline]
+    fn matches(layout: &BlockLayout, base_offset: usize)
+               -> Result<(), LayoutMismatchError>
+    {
+        <TextureHandle as UniformBlock>::matches(layout, base_offset)
+    }
+
+    #[inline]
+    fn build_layout(base_offset: usize) -> BlockLayout {
+        <TextureHandle as UniformBlock>::build_layout(base_offset)
+    }
+}
+
 // TODO: implement `vertex::Attribute` on `TextureHandle`
 
 /// Bindless textures are not supported.
 #[derive(Debug, Copy, Clone)]
 pub struct BindlessTexturesNotSupportedError;
 
+impl fmt::Display for BindlessTexturesNotSupportedError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for BindlessTexturesNotSupportedError {
+    fn description(&self) -> &str {
+        "Bindless textures are not supported by the backend"
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::mem;
-    use super::TextureHandle;
+    use super::{PaddedTextureHandle, TextureHandle};
 
     #[test]
     fn texture_handle_size() {
         assert_eq!(mem::size_of::<TextureHandle>(), 8);
     }
+
+    #[test]
+    fn padded_texture_handle_size() {
+        assert_eq!(mem::size_of::<PaddedTextureHandle>(), 16);
+    }
 }
@@ -81,6 +81,7 @@ use uniforms::UniformValue;
 
 /// Error that can happen while building the texture part of a buffer texture.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum TextureCreationError {
     /// Buffer textures are not supported at all.
     NotSupported,
@@ -89,12 +90,22 @@ pub enum TextureCreationError {
     FormatNotSupported,
 
     /// The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`.
-    TooLarge,
+    TooLarge {
+        /// Number of texels in the buffer.
+        size: usize,
+        /// Value of `GL_MAX_TEXTURE_BUFFER_SIZE`.
+        max: usize,
+    },
 }
 
 impl fmt::Display for TextureCreationError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}", self.description())
+        match *self {
+            TextureCreationError::TooLarge { size, max } =>
+                write!(fmt, "{}: the buffer contains {} texels, the maximum is {}",
+                       self.description(), size, max),
+            _ => write!(fmt, "{}", self.description()),
+        }
     }
 }
 
@@ -106,7 +117,7 @@ impl Error for TextureCreationError {
                 "Buffer textures are not supported at all",
             FormatNotSupported =>
                 "The requested format is not supported in combination with the given texture buffer type",
-            TooLarge =>
+            TooLarge { .. } =>
                 "The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`",
         }
     }
@@ -114,6 +125,7 @@ impl Error for TextureCreationError {
 
 /// Error that can happen while building a buffer texture.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum CreationError {
     /// Failed to create the buffer.
     BufferCreationError(BufferCreationError),
@@ -139,7 +151,7 @@ impl Error for CreationError {
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref err) => Some(err),
@@ -289,10 +301,10 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
         let mut ctxt = context.make_current();
 
         // checking capabilities
-        if buffer.get_size() / mem::size_of::<T>() > ctxt.capabilities
-                                                         .max_texture_buffer_size.unwrap() as usize
-        {
-            return Err((TextureCreationError::TooLarge, buffer));
+        let size = buffer.get_size() / mem::size_of::<T>();
+        let max = ctxt.capabilities.max_texture_buffer_size.unwrap() as usize;
+        if size > max {
+            return Err((TextureCreationError::TooLarge { size: size, max: max }, buffer));
         }
 
         // before starting, we determine the internal format and check that buffer textures are
@@ -492,6 +504,12 @@ impl<T> Drop for BufferTexture<T> where [T]: BufferContent {
 }
 
 impl<T> BufferTexture<T> where [T]: BufferContent {
+    /// Returns the id of the OpenGL texture object. `get_id` returns the id of the buffer.
+    #[inline]
+    pub fn get_texture_id(&self) -> gl::types::GLuint {
+        self.texture
+    }
+
     /// Builds a `BufferTextureRef`.
     #[inline]
     pub fn as_buffer_texture_ref(&self) -> BufferTextureRef {
@@ -0,0 +1,1074 @@
+/*!
+Loads textures from DDS, KTX and KTX2 files.
+
+These containers store images that are ready to be uploaded: their pixels are already in the
+format of the texture, usually compressed, along with all their mipmaps and, for cubemaps, their
+six faces. `load` reads the file, checks that the backend supports its format, and creates the
+texture without converting anything.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+use glium::texture::container::{self, ContainerTexture};
+
+let bytes = std::fs::read("skybox.dds").unwrap();
+
+match container::load(&display, &bytes).unwrap() {
+    ContainerTexture::CompressedSrgbCubemap(skybox) => { /* ... */ },
+    other => panic!("Unexpected texture: {:?}", other.as_texture_any()),
+}
+```
+
+If you need to check the content of a file before creating the texture, call `Container::parse`
+and then `Container::create_texture`.
+
+The following are supported:
+
+ - 2D textures and cubemaps, with or without mipmaps.
+ - S3TC (BC1 to BC3), RGTC (BC4 and BC5) and BPTC (BC6H and BC7) compressed formats, in their
+   linear and sRGB variants when they exist.
+ - Uncompressed R8, RG8, RGB8, RGBA8, RGBA16F and RGBA32F images, and sRGB RGB8 and RGBA8.
+
+Texture arrays, 3D textures and supercompressed KTX2 files are not supported.
+
+*/
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use buffer::BufferAny;
+use image_format::{self, ClientFormatAny, RequestType};
+use texture::{ClientFormat, CompressedFormat, CompressedMipmapsOption, CompressedSrgbFormat};
+use texture::{CompressedCubemap, CompressedSrgbCubemap, CompressedSrgbTexture2d};
+use texture::{CompressedTexture2d, Cubemap, MipmapsOption, SrgbCubemap, SrgbFormat};
+use texture::{SrgbTexture2d, Texture2d, TextureAny, TextureCreationError};
+use texture::{UncompressedFloatFormat, is_cubemaps_supported};
+use version::{Api, Version};
+
+use gl;
+use BufferExt;
+use CapabilitiesSource;
+use ContextExt;
+use TextureExt;
+
+const DDS_MAGIC: &'static [u8] = b"DDS ";
+const KTX_IDENTIFIER: &'static [u8] = &[0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB,
+                                         0x0D, 0x0A, 0x1A, 0x0A];
+const KTX2_IDENTIFIER: &'static [u8] = &[0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB,
+                                          0x0D, 0x0A, 0x1A, 0x0A];
+
+/// The kind of file that a `Container` has been parsed from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// DirectDraw Surface, with or without the DX10 header.
+    Dds,
+    /// Khronos Texture, version 1.
+    Ktx,
+    /// Khronos Texture, version 2.
+    Ktx2,
+}
+
+/// The format of the images of a container.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContainerFormat {
+    /// Uncompressed pixels, which are uploaded to a texture with a floating-point format.
+    Uncompressed(ClientFormat, UncompressedFloatFormat),
+    /// Uncompressed pixels in the sRGB color space.
+    Srgb(ClientFormat, SrgbFormat),
+    /// Compressed blocks.
+    Compressed(CompressedFormat),
+    /// Compressed blocks in the sRGB color space.
+    CompressedSrgb(CompressedSrgbFormat),
+}
+
+impl ContainerFormat {
+    /// Returns true if textures of this format can be created by the backend.
+    pub fn is_supported<C: ?Sized>(&self, context: &C) -> bool where C: CapabilitiesSource {
+        match *self {
+            ContainerFormat::Uncompressed(_, format) => format.is_supported(context),
+            ContainerFormat::Srgb(_, format) => format.is_supported(context),
+            ContainerFormat::Compressed(format) => format.is_supported(context),
+            ContainerFormat::CompressedSrgb(format) => format.is_supported(context),
+        }
+    }
+
+    fn client_format(&self) -> ClientFormatAny {
+        match *self {
+            ContainerFormat::Uncompressed(client, _) => ClientFormatAny::ClientFormat(client),
+            ContainerFormat::Srgb(client, _) => ClientFormatAny::ClientFormat(client),
+            ContainerFormat::Compressed(format) => ClientFormatAny::CompressedFormat(format),
+            ContainerFormat::CompressedSrgb(format) => {
+                ClientFormatAny::CompressedSrgbFormat(format)
+            },
+        }
+    }
+
+    fn is_compressed(&self) -> bool {
+        self.client_format().is_compressed()
+    }
+
+    /// Returns the size in bytes of an image of this format, or `None` if it overflows.
+    fn image_size(&self, width: u32, height: u32) -> Option<usize> {
+        match self.client_format() {
+            ClientFormatAny::ClientFormat(client) => {
+                client.get_size().checked_mul(width as usize)
+                                 .and_then(|size| size.checked_mul(height as usize))
+            },
+            compressed => {
+                let block_size = compressed.get_buffer_size(4, Some(4), None, None);
+                let blocks_x = (width as usize + 3) / 4;
+                let blocks_y = (height as usize + 3) / 4;
+                block_size.checked_mul(blocks_x).and_then(|size| size.checked_mul(blocks_y))
+            },
+        }
+    }
+
+    fn from_compressed(format: ClientFormatAny) -> Option<ContainerFormat> {
+        match format {
+            ClientFormatAny::CompressedFormat(f) => Some(ContainerFormat::Compressed(f)),
+            ClientFormatAny::CompressedSrgbFormat(f) => Some(ContainerFormat::CompressedSrgb(f)),
+            ClientFormatAny::ClientFormat(_) => None,
+        }
+    }
+}
+
+/// The content of a DDS, KTX or KTX2 file.
+pub struct Container<'d> {
+    kind: ContainerKind,
+    format: ContainerFormat,
+    width: u32,
+    height: u32,
+    cubemap: bool,
+    // `images[level][face]`
+    images: Vec<Vec<Cow<'d, [u8]>>>,
+}
+
+impl<'d> Container<'d> {
+    /// Parses a file. The kind of file is detected from its first bytes.
+    ///
+    /// The images are not copied, except for KTX files whose endianness doesn't match the
+    /// endianness of the machine.
+    pub fn parse(data: &'d [u8]) -> Result<Container<'d>, ContainerError> {
+        if data.starts_with(DDS_MAGIC) {
+            parse_dds(data)
+        } else if data.starts_with(KTX_IDENTIFIER) {
+            parse_ktx(data)
+        } else if data.starts_with(KTX2_IDENTIFIER) {
+            parse_ktx2(data)
+        } else {
+            Err(ContainerError::UnknownContainer)
+        }
+    }
+
+    /// Returns the kind of file.
+    #[inline]
+    pub fn kind(&self) -> ContainerKind {
+        self.kind
+    }
+
+    /// Returns the format of the images.
+    #[inline]
+    pub fn format(&self) -> ContainerFormat {
+        self.format
+    }
+
+    /// Returns the width and height of the main level.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns true if the file contains the six faces of a cubemap.
+    #[inline]
+    pub fn is_cubemap(&self) -> bool {
+        self.cubemap
+    }
+
+    /// Returns the number of mipmap levels, including the main level.
+    #[inline]
+    pub fn get_mipmap_levels(&self) -> u32 {
+        self.images.len() as u32
+    }
+
+    /// Returns the data of a mipmap level. `face` is `0` for 2D textures, and follows the order
+    /// of `CubeLayer` for cubemaps.
+    #[inline]
+    pub fn image(&self, level: u32, face: u32) -> Option<&[u8]> {
+        self.images.get(level as usize).and_then(|faces| faces.get(face as usize))
+                   .map(|image| &image[..])
+    }
+
+    /// Checks that the backend can create a texture from this container.
+    pub fn check_support<C: ?Sized>(&self, context: &C) -> Result<(), ContainerError>
+                                    where C: CapabilitiesSource
+    {
+        if !self.format.is_supported(context) {
+            return Err(ContainerError::FormatNotSupported(self.format));
+        }
+
+        if self.cubemap && !is_cubemaps_supported(context) {
+            return Err(ContainerError::CubemapsNotSupported);
+        }
+
+        Ok(())
+    }
+
+    /// Creates a texture and uploads all the images of the container.
+    pub fn create_texture<F: ?Sized>(&self, facade: &F)
+                                     -> Result<ContainerTexture, ContainerError>
+                                     where F: Facade
+    {
+        try!(self.check_support(&**facade.get_context()));
+
+        let (width, height) = (self.width, self.height);
+        let mipmaps = match self.images.len() as u32 {
+            1 => MipmapsOption::NoMipmap,
+            levels => MipmapsOption::EmptyMipmapsMax(levels - 1),
+        };
+        let compressed_mipmaps = match self.images.len() as u32 {
+            1 => CompressedMipmapsOption::NoMipmap,
+            levels => CompressedMipmapsOption::EmptyMipmapsMax(levels - 1),
+        };
+
+        let texture = match (self.format, self.cubemap) {
+            (ContainerFormat::Uncompressed(_, f), false) => ContainerTexture::Texture2d(
+                try!(Texture2d::empty_with_format(facade, f, mipmaps, width, height))),
+            (ContainerFormat::Srgb(_, f), false) => ContainerTexture::SrgbTexture2d(
+                try!(SrgbTexture2d::empty_with_format(facade, f, mipmaps, width, height))),
+            (ContainerFormat::Compressed(f), false) => ContainerTexture::CompressedTexture2d(
+                try!(CompressedTexture2d::empty_with_format(facade, f, compressed_mipmaps,
+                                                            width, height))),
+            (ContainerFormat::CompressedSrgb(f), false) => {
+                ContainerTexture::CompressedSrgbTexture2d(try!(
+                    CompressedSrgbTexture2d::empty_with_format(facade, f, compressed_mipmaps,
+                                                               width, height)))
+            },
+            (ContainerFormat::Uncompressed(_, f), true) => ContainerTexture::Cubemap(
+                try!(Cubemap::empty_with_format(facade, f, mipmaps, width))),
+            (ContainerFormat::Srgb(_, f), true) => ContainerTexture::SrgbCubemap(
+                try!(SrgbCubemap::empty_with_format(facade, f, mipmaps, width))),
+            (ContainerFormat::Compressed(f), true) => ContainerTexture::CompressedCubemap(
+                try!(CompressedCubemap::empty_with_format(facade, f, compressed_mipmaps,
+                                                          width))),
+            (ContainerFormat::CompressedSrgb(f), true) => ContainerTexture::CompressedSrgbCubemap(
+                try!(CompressedSrgbCubemap::empty_with_format(facade, f, compressed_mipmaps,
+                                                              width))),
+        };
+
+        try!(self.upload(texture.as_texture_any()));
+        Ok(texture)
+    }
+
+    fn upload(&self, texture: &TextureAny) -> Result<(), ContainerError> {
+        let context = texture.get_context();
+        let client = self.format.client_format();
+        let requested = texture.get_requested_format();
+
+        let (client_format, client_type) =
+            try!(image_format::client_format_to_glenum(context, client, requested, false)
+                        .map_err(|_| ContainerError::FormatNotSupported(self.format)));
+        let internal_format =
+            try!(image_format::format_request_to_glenum(context, requested,
+                                                        RequestType::TexImage(Some(client)))
+                        .map_err(|_| ContainerError::FormatNotSupported(self.format)));
+
+        let mut ctxt = context.make_current();
+
+        unsafe {
+            if ctxt.state.pixel_store_unpack_alignment != 1 {
+                ctxt.state.pixel_store_unpack_alignment = 1;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            }
+
+            BufferAny::unbind_pixel_unpack(&mut ctxt);
+            let bind_point = texture.bind_to_current(&mut ctxt);
+
+            // textures created with `glTexStorage` can't be redefined, while the levels of the
+            // other textures may not have been allocated
+            let immutable = if ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                               ctxt.extensions.gl_arb_texture_storage
+            {
+                let mut value = 0;
+                ctxt.gl.GetTexParameteriv(bind_point, gl::TEXTURE_IMMUTABLE_FORMAT, &mut value);
+                value != 0
+            } else {
+                false
+            };
+
+            for (level, faces) in self.images.iter().enumerate() {
+                let width = mipmap_dimension(self.width, level);
+                let height = mipmap_dimension(self.height, level);
+
+                for (face, data) in faces.iter().enumerate() {
+                    let target = if self.cubemap {
+                        gl::TEXTURE_CUBE_MAP_POSITIVE_X + face as gl::types::GLenum
+                    } else {
+                        bind_point
+                    };
+
+                    let level = level as gl::types::GLint;
+                    let (width, height) = (width as gl::types::GLsizei,
+                                           height as gl::types::GLsizei);
+                    let size = data.len() as gl::types::GLsizei;
+                    let ptr = data.as_ptr() as *const _;
+
+                    match (self.format.is_compressed(), immutable) {
+                        (true, true) => {
+                            ctxt.gl.CompressedTexSubImage2D(target, level, 0, 0, width, height,
+                                                            client_format, size, ptr);
+                        },
+                        (true, false) => {
+                            ctxt.gl.CompressedTexImage2D(target, level, internal_format, width,
+                                                         height, 0, size, ptr);
+                        },
+                        (false, true) => {
+                            ctxt.gl.TexSubImage2D(target, level, 0, 0, width, height,
+                                                  client_format, client_type, ptr);
+                        },
+                        (false, false) => {
+                            ctxt.gl.TexImage2D(target, level, internal_format as gl::types::GLint,
+                                               width, height, 0, client_format, client_type,
+                                               ptr);
+                        },
+                    }
+                }
+            }
+
+            if ctxt.version >= &Version(Api::Gl, 1, 2) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 0)
+            {
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAX_LEVEL,
+                                      self.images.len() as gl::types::GLint - 1);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a file and creates a texture with its content. See `Container::parse` and
+/// `Container::create_texture`.
+#[inline]
+pub fn load<F: ?Sized>(facade: &F, data: &[u8]) -> Result<ContainerTexture, ContainerError>
+                       where F: Facade
+{
+    try!(Container::parse(data)).create_texture(facade)
+}
+
+/// A texture created from a container. The type of the texture depends on the format of the
+/// file and on whether it contains a cubemap.
+#[derive(Debug)]
+pub enum ContainerTexture {
+    /// An uncompressed 2D texture.
+    Texture2d(Texture2d),
+    /// An uncompressed 2D texture in the sRGB color space.
+    SrgbTexture2d(SrgbTexture2d),
+    /// A compressed 2D texture.
+    CompressedTexture2d(CompressedTexture2d),
+    /// A compressed 2D texture in the sRGB color space.
+    CompressedSrgbTexture2d(CompressedSrgbTexture2d),
+    /// An uncompressed cubemap.
+    Cubemap(Cubemap),
+    /// An uncompressed cubemap in the sRGB color space.
+    SrgbCubemap(SrgbCubemap),
+    /// A compressed cubemap.
+    CompressedCubemap(CompressedCubemap),
+    /// A compressed cubemap in the sRGB color space.
+    CompressedSrgbCubemap(CompressedSrgbCubemap),
+}
+
+impl ContainerTexture {
+    /// Returns the texture, whatever its type.
+    #[inline]
+    pub fn as_texture_any(&self) -> &TextureAny {
+        match *self {
+            ContainerTexture::Texture2d(ref t) => t,
+            ContainerTexture::SrgbTexture2d(ref t) => t,
+            ContainerTexture::CompressedTexture2d(ref t) => t,
+            ContainerTexture::CompressedSrgbTexture2d(ref t) => t,
+            ContainerTexture::Cubemap(ref t) => t,
+            ContainerTexture::SrgbCubemap(ref t) => t,
+            ContainerTexture::CompressedCubemap(ref t) => t,
+            ContainerTexture::CompressedSrgbCubemap(ref t) => t,
+        }
+    }
+}
+
+/// Error that can happen when loading a container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContainerError {
+    /// The data doesn't start with the signature of a DDS, KTX or KTX2 file.
+    UnknownContainer,
+
+    /// The file is shorter than what its header describes.
+    Truncated,
+
+    /// The header contains invalid values.
+    InvalidHeader,
+
+    /// The format of the images is not one of the formats supported by this module. Contains
+    /// the FourCC code or the DXGI format of DDS files, the internal format of KTX files, and
+    /// the Vulkan format of KTX2 files.
+    UnsupportedFormat(u32),
+
+    /// The file contains a texture array, a 1D or 3D texture, or is supercompressed.
+    UnsupportedTextureType,
+
+    /// The backend doesn't support the format of the images.
+    FormatNotSupported(ContainerFormat),
+
+    /// The file contains a cubemap, and the backend doesn't support cubemaps.
+    CubemapsNotSupported,
+
+    /// Failed to create the texture.
+    TextureCreation(TextureCreationError),
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ContainerError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ContainerError::UnknownContainer =>
+                "The data doesn't start with the signature of a DDS, KTX or KTX2 file",
+            ContainerError::Truncated => "The file is shorter than what its header describes",
+            ContainerError::InvalidHeader => "The header contains invalid values",
+            ContainerError::UnsupportedFormat(_) =>
+                "The format of the images is not supported by glium",
+            ContainerError::UnsupportedTextureType =>
+                "Texture arrays, 1D and 3D textures and supercompressed files are not supported",
+            ContainerError::FormatNotSupported(_) =>
+                "The backend doesn't support the format of the images",
+            ContainerError::CubemapsNotSupported => "The backend doesn't support cubemaps",
+            ContainerError::TextureCreation(_) => "Failed to create the texture",
+        }
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            ContainerError::TextureCreation(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for ContainerError {
+    #[inline]
+    fn from(err: TextureCreationError) -> ContainerError {
+        ContainerError::TextureCreation(err)
+    }
+}
+
+#[inline]
+fn mipmap_dimension(dimension: u32, level: usize) -> u32 {
+    ::std::cmp::max(1, dimension.checked_shr(level as u32).unwrap_or(0))
+}
+
+/// Checks the number of faces and of mipmap levels read from a header.
+fn check_faces_and_levels((width, height): (u32, u32), faces: u32, levels: u32)
+                          -> Result<(), ContainerError>
+{
+    // a full mipmap chain has `floor(log2(max(width, height))) + 1` levels
+    let max_levels = 32 - ::std::cmp::max(width, height).leading_zeros();
+
+    if (faces != 1 && faces != 6) || levels > max_levels {
+        return Err(ContainerError::InvalidHeader);
+    }
+
+    Ok(())
+}
+
+/// Returns the size of the image of a mipmap level, or an error if it overflows.
+#[inline]
+fn level_image_size(format: ContainerFormat, (width, height): (u32, u32), level: usize)
+                    -> Result<usize, ContainerError>
+{
+    format.image_size(mipmap_dimension(width, level), mipmap_dimension(height, level))
+          .ok_or(ContainerError::InvalidHeader)
+}
+
+#[inline]
+fn add_offset(offset: usize, len: usize) -> Result<usize, ContainerError> {
+    offset.checked_add(len).ok_or(ContainerError::InvalidHeader)
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, ContainerError> {
+    let b = try!(read_slice(data, offset, 4));
+    Ok(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24)
+}
+
+#[inline]
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, ContainerError> {
+    let low = try!(read_u32(data, offset)) as u64;
+    let high = try!(read_u32(data, try!(add_offset(offset, 4)))) as u64;
+    Ok(low | high << 32)
+}
+
+#[inline]
+fn read_slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], ContainerError> {
+    match offset.checked_add(len) {
+        Some(end) if end <= data.len() => Ok(&data[offset .. end]),
+        _ => Err(ContainerError::Truncated),
+    }
+}
+
+/// Reads the images of a file that stores the mipmaps of each face one after the other.
+fn read_faces_then_levels<'d>(data: &'d [u8], mut offset: usize, format: ContainerFormat,
+                              (width, height): (u32, u32), faces: u32, levels: u32)
+                              -> Result<Vec<Vec<Cow<'d, [u8]>>>, ContainerError>
+{
+    let mut images: Vec<Vec<Cow<[u8]>>> = (0 .. levels).map(|_| Vec::new()).collect();
+
+    for _ in 0 .. faces {
+        for (level, faces) in images.iter_mut().enumerate() {
+            let size = try!(level_image_size(format, (width, height), level));
+            faces.push(Cow::Borrowed(try!(read_slice(data, offset, size))));
+            offset = try!(add_offset(offset, size));
+        }
+    }
+
+    Ok(images)
+}
+
+fn parse_dds(data: &[u8]) -> Result<Container, ContainerError> {
+    const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+    const DDPF_ALPHAPIXELS: u32 = 0x1;
+    const DDPF_FOURCC: u32 = 0x4;
+    const DDPF_RGB: u32 = 0x40;
+    const DDPF_LUMINANCE: u32 = 0x20000;
+    const DDSCAPS2_CUBEMAP: u32 = 0x200;
+    const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xFC00;
+    const DDSCAPS2_VOLUME: u32 = 0x200000;
+    const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+    const DDS_DIMENSION_TEXTURE2D: u32 = 3;
+
+    if try!(read_u32(data, 4)) != 124 {
+        return Err(ContainerError::InvalidHeader);
+    }
+
+    let flags = try!(read_u32(data, 8));
+    let height = try!(read_u32(data, 12));
+    let width = try!(read_u32(data, 16));
+    let levels = if flags & DDSD_MIPMAPCOUNT != 0 {
+        ::std::cmp::max(1, try!(read_u32(data, 28)))
+    } else {
+        1
+    };
+
+    let pf_flags = try!(read_u32(data, 80));
+    let four_cc = try!(read_slice(data, 84, 4));
+    let bit_count = try!(read_u32(data, 88));
+    let masks = (try!(read_u32(data, 92)), try!(read_u32(data, 96)),
+                 try!(read_u32(data, 100)), try!(read_u32(data, 104)));
+    let caps2 = try!(read_u32(data, 112));
+
+    if caps2 & DDSCAPS2_VOLUME != 0 {
+        return Err(ContainerError::UnsupportedTextureType);
+    }
+
+    let mut cubemap = caps2 & DDSCAPS2_CUBEMAP != 0;
+    if cubemap && caps2 & DDSCAPS2_CUBEMAP_ALLFACES != DDSCAPS2_CUBEMAP_ALLFACES {
+        return Err(ContainerError::UnsupportedTextureType);
+    }
+
+    let mut offset = 128;
+
+    let format = if pf_flags & DDPF_FOURCC != 0 {
+        match four_cc {
+            b"DXT1" if pf_flags & DDPF_ALPHAPIXELS != 0 => {
+                ContainerFormat::Compressed(CompressedFormat::S3tcDxt1Alpha)
+            },
+            b"DXT1" => ContainerFormat::Compressed(CompressedFormat::S3tcDxt1NoAlpha),
+            b"DXT2" | b"DXT3" => ContainerFormat::Compressed(CompressedFormat::S3tcDxt3Alpha),
+            b"DXT4" | b"DXT5" => ContainerFormat::Compressed(CompressedFormat::S3tcDxt5Alpha),
+            b"ATI1" | b"BC4U" => ContainerFormat::Compressed(CompressedFormat::RgtcFormatU),
+            b"BC4S" => ContainerFormat::Compressed(CompressedFormat::RgtcFormatI),
+            b"ATI2" | b"BC5U" => ContainerFormat::Compressed(CompressedFormat::RgtcFormatUU),
+            b"BC5S" => ContainerFormat::Compressed(CompressedFormat::RgtcFormatII),
+            b"DX10" => {
+                let dxgi_format = try!(read_u32(data, 128));
+                let dimension = try!(read_u32(data, 132));
+                let misc_flags = try!(read_u32(data, 136));
+                let array_size = try!(read_u32(data, 140));
+
+                if dimension != DDS_DIMENSION_TEXTURE2D || array_size > 1 {
+                    return Err(ContainerError::UnsupportedTextureType);
+                }
+
+                cubemap = misc_flags & DDS_RESOURCE_MISC_TEXTURECUBE != 0;
+                offset += 20;
+
+                match dxgi_format_to_format(dxgi_format) {
+                    Some(f) => f,
+                    None => return Err(ContainerError::UnsupportedFormat(dxgi_format)),
+                }
+            },
+            _ => {
+                // older writers store a `D3DFORMAT` instead of a FourCC
+                let code = try!(read_u32(data, 84));
+                match code {
+                    113 => ContainerFormat::Uncompressed(ClientFormat::F16F16F16F16,
+                                                         UncompressedFloatFormat::F16F16F16F16),
+                    116 => ContainerFormat::Uncompressed(ClientFormat::F32F32F32F32,
+                                                         UncompressedFloatFormat::F32F32F32F32),
+                    _ => return Err(ContainerError::UnsupportedFormat(code)),
+                }
+            },
+        }
+
+    } else if pf_flags & DDPF_RGB != 0 {
+        match (bit_count, masks) {
+            (32, (0xff, 0xff00, 0xff0000, 0xff000000)) => {
+                ContainerFormat::Uncompressed(ClientFormat::U8U8U8U8,
+                                              UncompressedFloatFormat::U8U8U8U8)
+            },
+            (24, (0xff, 0xff00, 0xff0000, 0)) => {
+                ContainerFormat::Uncompressed(ClientFormat::U8U8U8,
+                                              UncompressedFloatFormat::U8U8U8)
+            },
+            _ => return Err(ContainerError::UnsupportedFormat(pf_flags)),
+        }
+
+    } else if pf_flags & DDPF_LUMINANCE != 0 {
+        match (bit_count, masks.3) {
+            (8, 0) => ContainerFormat::Uncompressed(ClientFormat::U8, UncompressedFloatFormat::U8),
+            (16, 0xff00) => {
+                ContainerFormat::Uncompressed(ClientFormat::U8U8, UncompressedFloatFormat::U8U8)
+            },
+            _ => return Err(ContainerError::UnsupportedFormat(pf_flags)),
+        }
+
+    } else {
+        return Err(ContainerError::UnsupportedFormat(pf_flags));
+    };
+
+    if width == 0 || height == 0 || (cubemap && width != height) {
+        return Err(ContainerError::InvalidHeader);
+    }
+
+    let faces = if cubemap { 6 } else { 1 };
+    try!(check_faces_and_levels((width, height), faces, levels));
+
+    let images = try!(read_faces_then_levels(data, offset, format, (width, height), faces,
+                                             levels));
+
+    Ok(Container {
+        kind: ContainerKind::Dds,
+        format: format,
+        width: width,
+        height: height,
+        cubemap: cubemap,
+        images: images,
+    })
+}
+
+fn dxgi_format_to_format(format: u32) -> Option<ContainerFormat> {
+    Some(match format {
+        2 => ContainerFormat::Uncompressed(ClientFormat::F32F32F32F32,
+                                           UncompressedFloatFormat::F32F32F32F32),
+        10 => ContainerFormat::Uncompressed(ClientFormat::F16F16F16F16,
+                                            UncompressedFloatFormat::F16F16F16F16),
+        28 => ContainerFormat::Uncompressed(ClientFormat::U8U8U8U8,
+                                            UncompressedFloatFormat::U8U8U8U8),
+        29 => ContainerFormat::Srgb(ClientFormat::U8U8U8U8, SrgbFormat::U8U8U8U8),
+        49 => ContainerFormat::Uncompressed(ClientFormat::U8U8, UncompressedFloatFormat::U8U8),
+        61 => ContainerFormat::Uncompressed(ClientFormat::U8, UncompressedFloatFormat::U8),
+        71 => ContainerFormat::Compressed(CompressedFormat::S3tcDxt1Alpha),
+        72 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt1Alpha),
+        74 => ContainerFormat::Compressed(CompressedFormat::S3tcDxt3Alpha),
+        75 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt3Alpha),
+        77 => ContainerFormat::Compressed(CompressedFormat::S3tcDxt5Alpha),
+        78 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt5Alpha),
+        80 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatU),
+        81 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatI),
+        83 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatUU),
+        84 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatII),
+        95 => ContainerFormat::Compressed(CompressedFormat::BptcUnsignedFloat3),
+        96 => ContainerFormat::Compressed(CompressedFormat::BptcSignedFloat3),
+        98 => ContainerFormat::Compressed(CompressedFormat::BptcUnorm4),
+        99 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::Bptc),
+        _ => return None,
+    })
+}
+
+fn parse_ktx(data: &[u8]) -> Result<Container, ContainerError> {
+    let swap = match try!(read_u32(data, 12)) {
+        0x04030201 => false,
+        0x01020304 => true,
+        _ => return Err(ContainerError::InvalidHeader),
+    };
+
+    let field = |index: usize| -> Result<u32, ContainerError> {
+        let value = try!(read_u32(data, 16 + index * 4));
+        Ok(if swap { value.swap_bytes() } else { value })
+    };
+
+    let gl_type = try!(field(0));
+    let type_size = try!(field(1));
+    let internal_format = try!(field(3));
+    let width = try!(field(5));
+    let height = try!(field(6));
+    let depth = try!(field(7));
+    let array_elements = try!(field(8));
+    let faces = try!(field(9));
+    let levels = ::std::cmp::max(1, try!(field(10)));
+    let key_value_bytes = try!(field(11)) as usize;
+
+    if height == 0 || depth != 0 || array_elements != 0 {
+        return Err(ContainerError::UnsupportedTextureType);
+    }
+
+    if width == 0 || (faces == 6 && width != height) {
+        return Err(ContainerError::InvalidHeader);
+    }
+
+    try!(check_faces_and_levels((width, height), faces, levels));
+
+    let format = if gl_type == 0 {
+        ClientFormatAny::from_internal_compressed_format(internal_format)
+                        .and_then(ContainerFormat::from_compressed)
+    } else {
+        gl_format_to_format(internal_format, gl_type)
+    };
+
+    let format = match format {
+        Some(f) => f,
+        None => return Err(ContainerError::UnsupportedFormat(internal_format)),
+    };
+
+    let mut offset = try!(add_offset(64, key_value_bytes));
+    let mut images = Vec::with_capacity(levels as usize);
+
+    for level in 0 .. levels as usize {
+        let image_size = try!(read_u32(data, offset));
+        let image_size = if swap { image_size.swap_bytes() } else { image_size } as usize;
+        offset = try!(add_offset(offset, 4));
+
+        if image_size != try!(level_image_size(format, (width, height), level)) {
+            return Err(ContainerError::InvalidHeader);
+        }
+
+        let mut level_faces = Vec::with_capacity(faces as usize);
+        for _ in 0 .. faces {
+            let image = try!(read_slice(data, offset, image_size));
+
+            level_faces.push(if swap && type_size > 1 {
+                Cow::Owned(swap_endianness(image, type_size as usize))
+            } else {
+                Cow::Borrowed(image)
+            });
+
+            // each face and each level is padded to a multiple of four bytes
+            offset = try!(add_offset(offset, try!(add_offset(image_size, 3)) & !3));
+        }
+
+        images.push(level_faces);
+    }
+
+    Ok(Container {
+        kind: ContainerKind::Ktx,
+        format: format,
+        width: width,
+        height: height,
+        cubemap: faces == 6,
+        images: images,
+    })
+}
+
+fn gl_format_to_format(internal_format: u32, gl_type: u32) -> Option<ContainerFormat> {
+    Some(match (internal_format, gl_type) {
+        (gl::R8, gl::UNSIGNED_BYTE) => {
+            ContainerFormat::Uncompressed(ClientFormat::U8, UncompressedFloatFormat::U8)
+        },
+        (gl::RG8, gl::UNSIGNED_BYTE) => {
+            ContainerFormat::Uncompressed(ClientFormat::U8U8, UncompressedFloatFormat::U8U8)
+        },
+        (gl::RGB8, gl::UNSIGNED_BYTE) => {
+            ContainerFormat::Uncompressed(ClientFormat::U8U8U8, UncompressedFloatFormat::U8U8U8)
+        },
+        (gl::RGBA8, gl::UNSIGNED_BYTE) => {
+            ContainerFormat::Uncompressed(ClientFormat::U8U8U8U8,
+                                          UncompressedFloatFormat::U8U8U8U8)
+        },
+        (gl::SRGB8, gl::UNSIGNED_BYTE) => {
+            ContainerFormat::Srgb(ClientFormat::U8U8U8, SrgbFormat::U8U8U8)
+        },
+        (gl::SRGB8_ALPHA8, gl::UNSIGNED_BYTE) => {
+            ContainerFormat::Srgb(ClientFormat::U8U8U8U8, SrgbFormat::U8U8U8U8)
+        },
+        (gl::RGBA16F, gl::HALF_FLOAT) => {
+            ContainerFormat::Uncompressed(ClientFormat::F16F16F16F16,
+                                          UncompressedFloatFormat::F16F16F16F16)
+        },
+        (gl::RGBA32F, gl::FLOAT) => {
+            ContainerFormat::Uncompressed(ClientFormat::F32F32F32F32,
+                                          UncompressedFloatFormat::F32F32F32F32)
+        },
+        _ => return None,
+    })
+}
+
+fn swap_endianness(data: &[u8], type_size: usize) -> Vec<u8> {
+    data.chunks(type_size).flat_map(|value| value.iter().rev().cloned()).collect()
+}
+
+fn parse_ktx2(data: &[u8]) -> Result<Container, ContainerError> {
+    let vk_format = try!(read_u32(data, 12));
+    let width = try!(read_u32(data, 20));
+    let height = try!(read_u32(data, 24));
+    let depth = try!(read_u32(data, 28));
+    let layers = try!(read_u32(data, 32));
+    let faces = try!(read_u32(data, 36));
+    let levels = ::std::cmp::max(1, try!(read_u32(data, 40)));
+    let supercompression = try!(read_u32(data, 44));
+
+    if height == 0 || depth != 0 || layers != 0 || supercompression != 0 {
+        return Err(ContainerError::UnsupportedTextureType);
+    }
+
+    if width == 0 || (faces == 6 && width != height) {
+        return Err(ContainerError::InvalidHeader);
+    }
+
+    try!(check_faces_and_levels((width, height), faces, levels));
+
+    let format = match vk_format_to_format(vk_format) {
+        Some(f) => f,
+        None => return Err(ContainerError::UnsupportedFormat(vk_format)),
+    };
+
+    let mut images = Vec::with_capacity(levels as usize);
+
+    for level in 0 .. levels as usize {
+        let index = 80 + level * 24;
+        let offset = try!(read_u64(data, index));
+        let length = try!(read_u64(data, index + 8));
+
+        let image_size = try!(level_image_size(format, (width, height), level));
+        let expected = try!(image_size.checked_mul(faces as usize)
+                                      .ok_or(ContainerError::InvalidHeader));
+        if length != expected as u64 || offset > usize::max_value() as u64 {
+            return Err(ContainerError::InvalidHeader);
+        }
+
+        let (offset, length) = (offset as usize, length as usize);
+
+        let level_data = try!(read_slice(data, offset, length));
+        images.push(level_data.chunks(image_size).map(Cow::Borrowed).collect());
+    }
+
+    Ok(Container {
+        kind: ContainerKind::Ktx2,
+        format: format,
+        width: width,
+        height: height,
+        cubemap: faces == 6,
+        images: images,
+    })
+}
+
+fn vk_format_to_format(format: u32) -> Option<ContainerFormat> {
+    Some(match format {
+        9 => ContainerFormat::Uncompressed(ClientFormat::U8, UncompressedFloatFormat::U8),
+        16 => ContainerFormat::Uncompressed(ClientFormat::U8U8, UncompressedFloatFormat::U8U8),
+        23 => ContainerFormat::Uncompressed(ClientFormat::U8U8U8,
+                                            UncompressedFloatFormat::U8U8U8),
+        29 => ContainerFormat::Srgb(ClientFormat::U8U8U8, SrgbFormat::U8U8U8),
+        37 => ContainerFormat::Uncompressed(ClientFormat::U8U8U8U8,
+                                            UncompressedFloatFormat::U8U8U8U8),
+        43 => ContainerFormat::Srgb(ClientFormat::U8U8U8U8, SrgbFormat::U8U8U8U8),
+        97 => ContainerFormat::Uncompressed(ClientFormat::F16F16F16F16,
+                                            UncompressedFloatFormat::F16F16F16F16),
+        109 => ContainerFormat::Uncompressed(ClientFormat::F32F32F32F32,
+                                             UncompressedFloatFormat::F32F32F32F32),
+        131 => ContainerFormat::Compressed(CompressedFormat::S3tcDxt1NoAlpha),
+        132 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt1NoAlpha),
+        133 => ContainerFormat::Compressed(CompressedFormat::S3tcDxt1Alpha),
+        134 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt1Alpha),
+        135 => ContainerFormat::Compressed(CompressedFormat::S3tcDxt3Alpha),
+        136 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt3Alpha),
+        137 => ContainerFormat::Compressed(CompressedFormat::S3tcDxt5Alpha),
+        138 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt5Alpha),
+        139 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatU),
+        140 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatI),
+        141 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatUU),
+        142 => ContainerFormat::Compressed(CompressedFormat::RgtcFormatII),
+        143 => ContainerFormat::Compressed(CompressedFormat::BptcUnsignedFloat3),
+        144 => ContainerFormat::Compressed(CompressedFormat::BptcSignedFloat3),
+        145 => ContainerFormat::Compressed(CompressedFormat::BptcUnorm4),
+        146 => ContainerFormat::CompressedSrgb(CompressedSrgbFormat::Bptc),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Container, ContainerError, ContainerFormat, ContainerKind};
+    use super::{DDS_MAGIC, KTX_IDENTIFIER, KTX2_IDENTIFIER};
+    use texture::{ClientFormat, CompressedFormat, CompressedSrgbFormat, UncompressedFloatFormat};
+
+    fn push_u32(data: &mut Vec<u8>, value: u32) {
+        data.extend_from_slice(&[value as u8, (value >> 8) as u8, (value >> 16) as u8,
+                                 (value >> 24) as u8]);
+    }
+
+    fn dds_header(width: u32, height: u32, levels: u32, four_cc: &[u8], caps2: u32) -> Vec<u8> {
+        let mut data = DDS_MAGIC.to_vec();
+        push_u32(&mut data, 124);
+        push_u32(&mut data, 0x20000);       // DDSD_MIPMAPCOUNT
+        push_u32(&mut data, height);
+        push_u32(&mut data, width);
+        push_u32(&mut data, 0);
+        push_u32(&mut data, 0);
+        push_u32(&mut data, levels);
+        data.extend_from_slice(&[0; 44]);
+        push_u32(&mut data, 32);
+        push_u32(&mut data, 0x4);           // DDPF_FOURCC
+        data.extend_from_slice(four_cc);
+        data.extend_from_slice(&[0; 20]);
+        push_u32(&mut data, 0);
+        push_u32(&mut data, caps2);
+        data.extend_from_slice(&[0; 12]);
+        assert_eq!(data.len(), 128);
+        data
+    }
+
+    #[test]
+    fn dds_dxt5_with_mipmaps() {
+        let mut data = dds_header(8, 8, 4, b"DXT5", 0);
+        // 8x8 is 4 blocks, then 4x4, 2x2 and 1x1 are one block each
+        data.extend((0 .. 7 * 16).map(|i| i as u8));
+
+        let container = Container::parse(&data).unwrap();
+        assert_eq!(container.kind(), ContainerKind::Dds);
+        assert_eq!(container.format(),
+                   ContainerFormat::Compressed(CompressedFormat::S3tcDxt5Alpha));
+        assert_eq!(container.dimensions(), (8, 8));
+        assert!(!container.is_cubemap());
+        assert_eq!(container.get_mipmap_levels(), 4);
+        assert_eq!(container.image(0, 0).unwrap().len(), 64);
+        assert_eq!(container.image(3, 0).unwrap()[0], 6 * 16);
+        assert!(container.image(4, 0).is_none());
+
+        data.pop();
+        assert_eq!(Container::parse(&data).err(), Some(ContainerError::Truncated));
+    }
+
+    #[test]
+    fn dds_dx10_srgb_cubemap() {
+        let mut data = dds_header(4, 4, 1, b"DX10", 0);
+        push_u32(&mut data, 72);            // DXGI_FORMAT_BC1_UNORM_SRGB
+        push_u32(&mut data, 3);             // DDS_DIMENSION_TEXTURE2D
+        push_u32(&mut data, 0x4);           // DDS_RESOURCE_MISC_TEXTURECUBE
+        push_u32(&mut data, 1);
+        push_u32(&mut data, 0);
+        data.extend((0 .. 6 * 8).map(|i| (i / 8) as u8));
+
+        let container = Container::parse(&data).unwrap();
+        assert_eq!(container.format(),
+                   ContainerFormat::CompressedSrgb(CompressedSrgbFormat::S3tcDxt1Alpha));
+        assert!(container.is_cubemap());
+        assert_eq!(container.image(0, 5).unwrap(), &[5; 8][..]);
+    }
+
+    #[test]
+    fn ktx_rgba8_with_padding() {
+        let mut data = KTX_IDENTIFIER.to_vec();
+        for &field in &[0x04030201, 0x1401, 1, 0x1908, 0x8058, 0x1908, 3, 1, 0, 0, 1, 2, 4] {
+            push_u32(&mut data, field);
+        }
+        data.extend_from_slice(&[0xaa; 4]);     // key/value data
+        push_u32(&mut data, 12);
+        data.extend_from_slice(&[1; 12]);
+        push_u32(&mut data, 4);
+        data.extend_from_slice(&[2; 4]);
+
+        let container = Container::parse(&data).unwrap();
+        assert_eq!(container.kind(), ContainerKind::Ktx);
+        assert_eq!(container.format(),
+                   ContainerFormat::Uncompressed(ClientFormat::U8U8U8U8,
+                                                 UncompressedFloatFormat::U8U8U8U8));
+        assert_eq!(container.dimensions(), (3, 1));
+        assert_eq!(container.image(0, 0).unwrap(), &[1; 12][..]);
+        assert_eq!(container.image(1, 0).unwrap(), &[2; 4][..]);
+    }
+
+    #[test]
+    fn ktx2_bc4() {
+        let mut data = KTX2_IDENTIFIER.to_vec();
+        for &field in &[139, 1, 4, 4, 0, 0, 1, 1, 0] {
+            push_u32(&mut data, field);
+        }
+        data.extend_from_slice(&[0; 32]);
+        for &field in &[104, 0, 8, 0, 8, 0] {
+            push_u32(&mut data, field);
+        }
+        data.extend_from_slice(&[7; 8]);
+
+        let container = Container::parse(&data).unwrap();
+        assert_eq!(container.kind(), ContainerKind::Ktx2);
+        assert_eq!(container.format(), ContainerFormat::Compressed(CompressedFormat::RgtcFormatU));
+        assert_eq!(container.image(0, 0).unwrap(), &[7; 8][..]);
+
+        // supercompression
+        data[44] = 2;
+        assert_eq!(Container::parse(&data).err(), Some(ContainerError::UnsupportedTextureType));
+    }
+
+    #[test]
+    fn too_many_levels() {
+        // an 8x8 image has at most 4 levels
+        let mut data = dds_header(8, 8, 5, b"DXT5", 0);
+        data.extend((0 .. 8 * 16).map(|i| i as u8));
+        assert_eq!(Container::parse(&data).err(), Some(ContainerError::InvalidHeader));
+
+        // a shift by 32 or more used to overflow
+        let data = dds_header(8, 8, 40, b"DXT5", 0);
+        assert_eq!(Container::parse(&data).err(), Some(ContainerError::InvalidHeader));
+    }
+
+    #[test]
+    fn ktx_invalid_faces() {
+        let mut data = KTX_IDENTIFIER.to_vec();
+        for &field in &[0x04030201, 0x1401, 1, 0x1908, 0x8058, 0x1908, 1, 1, 0, 0, 2, 1, 0] {
+            push_u32(&mut data, field);
+        }
+        assert_eq!(Container::parse(&data).err(), Some(ContainerError::InvalidHeader));
+    }
+
+    #[test]
+    fn ktx_huge_key_value_data() {
+        let mut data = KTX_IDENTIFIER.to_vec();
+        for &field in &[0x04030201, 0x1401, 1, 0x1908, 0x8058, 0x1908, 1, 1, 0, 0, 1, 1,
+                        0xffffffff] {
+            push_u32(&mut data, field);
+        }
+        assert!(Container::parse(&data).is_err());
+    }
+
+    #[test]
+    fn ktx2_overflowing_image_size() {
+        let mut data = KTX2_IDENTIFIER.to_vec();
+        for &field in &[109, 16, 0xffffffff, 0xffffffff, 0, 0, 6, 1, 0] {
+            push_u32(&mut data, field);
+        }
+        data.extend_from_slice(&[0; 32]);
+        for &field in &[104, 0, 0xffffffff, 0xffffffff, 0, 0] {
+            push_u32(&mut data, field);
+        }
+        assert_eq!(Container::parse(&data).err(), Some(ContainerError::InvalidHeader));
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(Container::parse(b"PNG").err(), Some(ContainerError::UnknownContainer));
+    }
+}
@@ -0,0 +1,184 @@
+//! Conversions between textures and the images of the `image` crate.
+//!
+//! The `image` crate stores the top row of an image first, while OpenGL expects the bottom row
+//! first. `from_image` reverses the rows by default so that the texture coordinates `(0, 0)`
+//! are the bottom-left corner of the picture, and `to_image` reverses them back.
+//!
+//! Colors that have been painted or photographed are usually sRGB-encoded and must be loaded
+//! in a `SrgbTexture2d`, so that sampling them returns linear values. Images that contain data,
+//! like normal maps or height maps, must be loaded in a `Texture2d`.
+
+use std::borrow::Cow;
+
+use image::{DynamicImage, GenericImage, ImageBuffer};
+
+use backend::Facade;
+use texture::{ClientFormat, MipmapsOption, RawImage2d, ReadError, SrgbFormat, SrgbTexture2d};
+use texture::{Texture2d, TextureCreationError, UncompressedFloatFormat};
+
+/// Describes how an image is uploaded by `from_image_with_options`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    /// If true, the rows of the image are reversed so that the first row of the image is the
+    /// top of the texture. The default is `true`.
+    pub flip_vertically: bool,
+
+    /// The mipmaps of the texture. The default is `AutoGeneratedMipmaps`.
+    pub mipmaps: MipmapsOption,
+}
+
+impl Default for ImageOptions {
+    #[inline]
+    fn default() -> ImageOptions {
+        ImageOptions {
+            flip_vertically: true,
+            mipmaps: MipmapsOption::AutoGeneratedMipmaps,
+        }
+    }
+}
+
+impl Texture2d {
+    /// Uploads an image, and generates its mipmaps. The color values are not converted.
+    ///
+    /// Grayscale images are uploaded to the red component, and grayscale images with alpha to
+    /// the red and green components. Images with more than 8 bits per component are not
+    /// supported by the `image` crate that glium depends on.
+    ///
+    /// This method is only available if you enable the `image` feature of glium.
+    #[inline]
+    pub fn from_image<F: ?Sized>(facade: &F, image: &DynamicImage)
+                                 -> Result<Texture2d, TextureCreationError> where F: Facade
+    {
+        Texture2d::from_image_with_options(facade, image, Default::default())
+    }
+
+    /// Same as `from_image`, but with the given options.
+    pub fn from_image_with_options<F: ?Sized>(facade: &F, image: &DynamicImage,
+                                              options: ImageOptions)
+                                              -> Result<Texture2d, TextureCreationError>
+                                              where F: Facade
+    {
+        let (data, client_format, format) = match *image {
+            DynamicImage::ImageLuma8(ref b) => {
+                (b.clone().into_raw(), ClientFormat::U8, UncompressedFloatFormat::U8)
+            },
+            DynamicImage::ImageLumaA8(ref b) => {
+                (b.clone().into_raw(), ClientFormat::U8U8, UncompressedFloatFormat::U8U8)
+            },
+            DynamicImage::ImageRgb8(ref b) => {
+                (b.clone().into_raw(), ClientFormat::U8U8U8, UncompressedFloatFormat::U8U8U8)
+            },
+            DynamicImage::ImageRgba8(ref b) => {
+                (b.clone().into_raw(), ClientFormat::U8U8U8U8,
+                 UncompressedFloatFormat::U8U8U8U8)
+            },
+        };
+
+        let raw = raw_image(data, image.dimensions(), client_format, options.flip_vertically);
+        Texture2d::with_format(facade, raw, format, options.mipmaps)
+    }
+
+    /// Reads the content of the texture into an RGBA image whose first row is the top of the
+    /// texture.
+    ///
+    /// This method is only available if you enable the `image` feature of glium.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture can't be read. See `read`.
+    #[inline]
+    pub fn to_image(&self) -> DynamicImage {
+        self.try_to_image().expect("Failed to read the texture")
+    }
+
+    /// Same as `to_image`, but returns an error instead of panicking if the backend can't read
+    /// the texture.
+    #[inline]
+    pub fn try_to_image(&self) -> Result<DynamicImage, ReadError> {
+        Ok(into_image(try!(self.try_read())))
+    }
+}
+
+impl SrgbTexture2d {
+    /// Uploads an image whose colors are sRGB-encoded, and generates its mipmaps.
+    ///
+    /// OpenGL doesn't have sRGB formats with one or two components, so grayscale images are
+    /// converted to RGBA.
+    ///
+    /// This method is only available if you enable the `image` feature of glium.
+    #[inline]
+    pub fn from_image<F: ?Sized>(facade: &F, image: &DynamicImage)
+                                 -> Result<SrgbTexture2d, TextureCreationError> where F: Facade
+    {
+        SrgbTexture2d::from_image_with_options(facade, image, Default::default())
+    }
+
+    /// Same as `from_image`, but with the given options.
+    pub fn from_image_with_options<F: ?Sized>(facade: &F, image: &DynamicImage,
+                                              options: ImageOptions)
+                                              -> Result<SrgbTexture2d, TextureCreationError>
+                                              where F: Facade
+    {
+        let (data, client_format, format) = match *image {
+            DynamicImage::ImageRgb8(ref b) => {
+                (b.clone().into_raw(), ClientFormat::U8U8U8, SrgbFormat::U8U8U8)
+            },
+            _ => (image.to_rgba().into_raw(), ClientFormat::U8U8U8U8, SrgbFormat::U8U8U8U8),
+        };
+
+        let raw = raw_image(data, image.dimensions(), client_format, options.flip_vertically);
+        SrgbTexture2d::with_format(facade, raw, format, options.mipmaps)
+    }
+
+    /// Reads the content of the texture into an RGBA image whose first row is the top of the
+    /// texture. The colors are not converted, and are therefore still sRGB-encoded.
+    ///
+    /// This method is only available if you enable the `image` feature of glium.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture can't be read. See `read`.
+    #[inline]
+    pub fn to_image(&self) -> DynamicImage {
+        self.try_to_image().expect("Failed to read the texture")
+    }
+
+    /// Same as `to_image`, but returns an error instead of panicking if the backend can't read
+    /// the texture.
+    #[inline]
+    pub fn try_to_image(&self) -> Result<DynamicImage, ReadError> {
+        Ok(into_image(try!(self.try_read())))
+    }
+}
+
+fn raw_image(data: Vec<u8>, (width, height): (u32, u32), format: ClientFormat, flip: bool)
+             -> RawImage2d<'static, u8>
+{
+    let data = if flip {
+        flip_rows(data, width as usize * format.get_size())
+    } else {
+        data
+    };
+
+    RawImage2d {
+        data: Cow::Owned(data),
+        width: width,
+        height: height,
+        format: format,
+    }
+}
+
+fn into_image(raw: RawImage2d<u8>) -> DynamicImage {
+    let (width, height) = (raw.width, raw.height);
+    let data = flip_rows(raw.data.into_owned(), width as usize * 4);
+    DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, data).unwrap())
+}
+
+/// Reverses the order of the rows of an image.
+fn flip_rows(data: Vec<u8>, row_size: usize) -> Vec<u8> {
+    if row_size == 0 {
+        return data;
+    }
+
+    data.chunks(row_size).rev().flat_map(|row| row.iter().cloned()).collect()
+}
@@ -0,0 +1,244 @@
+/*!
+Creates textures from EGL images, without copying their content.
+
+An `EGLImage` is an image that can be shared between APIs that use EGL. Video decoders such as
+VA-API and Wayland compositors usually give access to their images as Linux DMA-BUF file
+descriptors, which can be turned into an `EGLImage` with the `EGL_EXT_image_dma_buf_import`
+extension. The functions of this module create a `Texture2d` that uses the memory of such an
+image, which makes it possible to display or post-process video frames and screen captures
+without copying them.
+
+This requires the context to have been created with EGL, and the `GL_OES_EGL_image` extension.
+glutin uses EGL for example on Wayland and Android.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let (egl_display, fd, stride) = (::std::ptr::null(), 0, 0);
+use glium::texture::UncompressedFloatFormat;
+use glium::texture::egl_image::{self, DmaBuf, DmaBufPlane};
+
+// DRM_FORMAT_ABGR8888, in other words RGBA in memory
+let dmabuf = DmaBuf {
+    width: 1920,
+    height: 1080,
+    fourcc: 0x34324241,
+    modifier: None,
+    planes: &[DmaBufPlane { fd: fd, offset: 0, pitch: stride }],
+};
+
+let texture = unsafe {
+    egl_image::texture_from_dmabuf(&display, egl_display, &dmabuf,
+                                   UncompressedFloatFormat::U8U8U8U8)
+}.unwrap();
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+use backend::Facade;
+use context;
+use texture::{Dimensions, MipmapsOption, Texture2d, UncompressedFloatFormat};
+
+use gl;
+use ContextExt;
+
+const EGL_NONE: c_int = 0x3038;
+const EGL_HEIGHT: c_int = 0x3056;
+const EGL_WIDTH: c_int = 0x3057;
+const EGL_LINUX_DMA_BUF_EXT: u32 = 0x3270;
+const EGL_LINUX_DRM_FOURCC_EXT: c_int = 0x3271;
+
+// for each plane: fd, offset, pitch, modifier low, modifier high
+const EGL_DMA_BUF_PLANE_ATTRIBUTES: [[c_int; 5]; 4] = [
+    [0x3272, 0x3273, 0x3274, 0x3443, 0x3444],
+    [0x3275, 0x3276, 0x3277, 0x3445, 0x3446],
+    [0x3278, 0x3279, 0x327A, 0x3447, 0x3448],
+    [0x3440, 0x3441, 0x3442, 0x3449, 0x344A],
+];
+
+type EglCreateImageKhr = extern "system" fn(*const c_void, *const c_void, u32, *const c_void,
+                                            *const c_int) -> *const c_void;
+type EglDestroyImageKhr = extern "system" fn(*const c_void, *const c_void) -> u32;
+
+/// Error that can happen when creating a texture from an EGL image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EglImageError {
+    /// The context wasn't created with EGL, or doesn't support the required extensions.
+    NotSupported,
+
+    /// EGL failed to create an image from the DMA-BUF. The format, the modifier or the layout
+    /// of the planes may not be supported by the driver.
+    ImageCreationFailed,
+}
+
+impl fmt::Display for EglImageError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for EglImageError {
+    fn description(&self) -> &str {
+        match self {
+            &EglImageError::NotSupported => "EGL images are not supported by the backend",
+            &EglImageError::ImageCreationFailed => "Failed to create an EGL image",
+        }
+    }
+}
+
+/// A plane of a DMA-BUF.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DmaBufPlane {
+    /// File descriptor of the plane. It is not closed by glium, and can be closed once the
+    /// texture has been created.
+    pub fd: c_int,
+
+    /// Offset in bytes of the plane within the file.
+    pub offset: u32,
+
+    /// Number of bytes between the start of two consecutive rows.
+    pub pitch: u32,
+}
+
+/// Describes an image stored in one or more DMA-BUFs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DmaBuf<'a> {
+    /// Width of the image in pixels.
+    pub width: u32,
+
+    /// Height of the image in pixels.
+    pub height: u32,
+
+    /// Format of the image, as one of the `DRM_FORMAT_*` codes of `drm_fourcc.h`.
+    pub fourcc: u32,
+
+    /// Format modifier of the planes, as one of the `DRM_FORMAT_MOD_*` codes. Requires the
+    /// `EGL_EXT_image_dma_buf_import_modifiers` extension if it is not `None`.
+    pub modifier: Option<u64>,
+
+    /// The planes of the image. Between one and four.
+    pub planes: &'a [DmaBufPlane],
+}
+
+/// Creates a texture that shares its content with an `EGLImage`.
+///
+/// `format` must correspond to the format of the image. The image can be destroyed once the
+/// texture has been created, as the texture keeps the content alive.
+///
+/// Images in a YUV format can usually only be used as external textures, which glium doesn't
+/// support. To sample a video frame in a format such as NV12, create one image per plane with
+/// a single-component format instead.
+///
+/// # Unsafety
+///
+/// `image` must be a valid `EGLImage` of the display of the context.
+pub unsafe fn texture_from_egl_image<F: ?Sized>(facade: &F, image: *const c_void,
+                                                format: UncompressedFloatFormat, width: u32,
+                                                height: u32)
+                                                -> Result<Texture2d, EglImageError>
+                                                where F: Facade
+{
+    let id = {
+        let mut ctxt = facade.get_context().make_current();
+
+        if !ctxt.extensions.gl_oes_egl_image {
+            return Err(EglImageError::NotSupported);
+        }
+
+        let mut id = 0;
+        ctxt.gl.GenTextures(1, &mut id);
+
+        {
+            ctxt.gl.BindTexture(gl::TEXTURE_2D, id);
+            let act = ctxt.state.active_texture as usize;
+            ctxt.state.texture_units[act].texture = id;
+        }
+
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        ctxt.gl.EGLImageTargetTexture2DOES(gl::TEXTURE_2D, image);
+
+        id
+    };
+
+    Ok(Texture2d::from_id(facade, format, id, true, MipmapsOption::NoMipmap,
+                          Dimensions::Texture2d { width: width, height: height }))
+}
+
+/// Creates a texture that shares its content with a DMA-BUF.
+///
+/// An `EGLImage` is created from the DMA-BUF with `eglCreateImageKHR`, then passed to
+/// `texture_from_egl_image`. See the documentation of this function.
+///
+/// # Panic
+///
+/// Panics if the number of planes is not between one and four.
+///
+/// # Unsafety
+///
+/// `egl_display` must be the `EGLDisplay` of the context, and the file descriptors must be
+/// valid DMA-BUFs that contain an image of the given dimensions and format.
+pub unsafe fn texture_from_dmabuf<F: ?Sized>(facade: &F, egl_display: *const c_void,
+                                             dmabuf: &DmaBuf, format: UncompressedFloatFormat)
+                                             -> Result<Texture2d, EglImageError>
+                                             where F: Facade
+{
+    assert!(dmabuf.planes.len() >= 1 && dmabuf.planes.len() <= 4,
+            "A DMA-BUF must have between one and four planes");
+
+    let (create_image, destroy_image) = {
+        let ctxt = facade.get_context().make_current();
+
+        if !ctxt.extensions.gl_oes_egl_image {
+            return Err(EglImageError::NotSupported);
+        }
+
+        let create = context::get_proc_address(facade.get_context(), "eglCreateImageKHR");
+        let destroy = context::get_proc_address(facade.get_context(), "eglDestroyImageKHR");
+        if create.is_null() || destroy.is_null() {
+            return Err(EglImageError::NotSupported);
+        }
+
+        (mem::transmute::<_, EglCreateImageKhr>(create),
+         mem::transmute::<_, EglDestroyImageKhr>(destroy))
+    };
+
+    let mut attributes = vec![
+        EGL_WIDTH, dmabuf.width as c_int,
+        EGL_HEIGHT, dmabuf.height as c_int,
+        EGL_LINUX_DRM_FOURCC_EXT, dmabuf.fourcc as c_int,
+    ];
+
+    for (plane, names) in dmabuf.planes.iter().zip(EGL_DMA_BUF_PLANE_ATTRIBUTES.iter()) {
+        attributes.extend_from_slice(&[
+            names[0], plane.fd,
+            names[1], plane.offset as c_int,
+            names[2], plane.pitch as c_int,
+        ]);
+
+        if let Some(modifier) = dmabuf.modifier {
+            attributes.extend_from_slice(&[
+                names[3], modifier as u32 as c_int,
+                names[4], (modifier >> 32) as u32 as c_int,
+            ]);
+        }
+    }
+
+    attributes.push(EGL_NONE);
+
+    // the context parameter must be `EGL_NO_CONTEXT` for this target
+    let image = create_image(egl_display, ptr::null(), EGL_LINUX_DMA_BUF_EXT, ptr::null(),
+                             attributes.as_ptr());
+    if image.is_null() {
+        return Err(EglImageError::ImageCreationFailed);
+    }
+
+    let texture = texture_from_egl_image(facade, image, format, dmabuf.width, dmabuf.height);
+    destroy_image(egl_display, image);
+    texture
+}
@@ -12,6 +12,7 @@ use GlObject;
 
 /// Error that can happen when retrieving the internal format of a texture.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum GetFormatError {
     /// The backend doesn't support retrieving the internal format.
     NotSupported,
@@ -0,0 +1,97 @@
+/// Describes how the pixels of a rectangle are laid out in client memory.
+///
+/// This makes it possible to transfer a sub-rectangle of a larger image, or rows that are padded,
+/// without repacking the data first. The fields correspond to the `GL_UNPACK_*` and
+/// `GL_PACK_*` pixel store parameters.
+///
+/// The default value describes tightly packed rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PixelLayout {
+    /// Number of pixels in a row of the client image. If `None`, the rows are as long as the
+    /// rectangle that is transferred.
+    pub row_length: Option<u32>,
+
+    /// Number of pixels to skip at the start of each row.
+    pub skip_pixels: u32,
+
+    /// Number of rows to skip at the start of the image.
+    pub skip_rows: u32,
+
+    /// Alignment in bytes of the start of each row. Must be 1, 2, 4 or 8.
+    pub alignment: u32,
+}
+
+impl Default for PixelLayout {
+    #[inline]
+    fn default() -> PixelLayout {
+        PixelLayout {
+            row_length: None,
+            skip_pixels: 0,
+            skip_rows: 0,
+            alignment: 1,
+        }
+    }
+}
+
+impl PixelLayout {
+    /// Returns the number of bytes between the start of two consecutive rows, when transferring
+    /// a rectangle of the given width with pixels of `pixel_size` bytes.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the alignment is not 1, 2, 4 or 8.
+    pub fn row_stride(&self, width: u32, pixel_size: usize) -> usize {
+        assert!(self.alignment == 1 || self.alignment == 2 || self.alignment == 4 ||
+                self.alignment == 8, "The alignment must be 1, 2, 4 or 8");
+
+        let alignment = self.alignment as usize;
+        let row_bytes = self.row_length.unwrap_or(width) as usize * pixel_size;
+        (row_bytes + alignment - 1) / alignment * alignment
+    }
+
+    /// Returns the offset in bytes of the first pixel of the rectangle.
+    #[inline]
+    pub fn offset(&self, width: u32, pixel_size: usize) -> usize {
+        self.skip_rows as usize * self.row_stride(width, pixel_size) +
+            self.skip_pixels as usize * pixel_size
+    }
+
+    /// Returns the minimum number of bytes that the client memory must contain to hold a
+    /// rectangle of the given dimensions.
+    pub fn required_bytes(&self, width: u32, height: u32, pixel_size: usize) -> usize {
+        if width == 0 || height == 0 {
+            return 0;
+        }
+
+        self.offset(width, pixel_size) +
+            (height as usize - 1) * self.row_stride(width, pixel_size) +
+            width as usize * pixel_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PixelLayout;
+
+    #[test]
+    fn tightly_packed() {
+        let layout = PixelLayout::default();
+        assert_eq!(layout.row_stride(3, 3), 9);
+        assert_eq!(layout.offset(3, 3), 0);
+        assert_eq!(layout.required_bytes(3, 2, 3), 18);
+    }
+
+    #[test]
+    fn sub_rectangle() {
+        let layout = PixelLayout {
+            row_length: Some(10),
+            skip_pixels: 2,
+            skip_rows: 1,
+            alignment: 4,
+        };
+
+        assert_eq!(layout.row_stride(4, 3), 32);
+        assert_eq!(layout.offset(4, 3), 38);
+        assert_eq!(layout.required_bytes(4, 2, 3), 38 + 32 + 12);
+    }
+}
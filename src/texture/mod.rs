@@ -119,27 +119,40 @@ use std::error::Error;
 use image_format::FormatNotSupportedError;
 
 pub use image_format::{ClientFormat, TextureFormat};
+pub use ops::ReadError;
 pub use image_format::{UncompressedFloatFormat, UncompressedIntFormat, UncompressedUintFormat};
 pub use image_format::{CompressedFormat, DepthFormat, DepthStencilFormat, StencilFormat};
 pub use image_format::{CompressedSrgbFormat, SrgbFormat};
 pub use self::any::{TextureAny, TextureAnyMipmap, TextureAnyLayer, TextureAnyLayerMipmap};
-pub use self::any::{TextureAnyImage, Dimensions};
+pub use self::any::{TextureAnyImage, Dimensions, SharedTexture};
+#[doc(hidden)]
+pub use self::any::destroy_dropped_texture;
 pub use self::bindless::{ResidentTexture, TextureHandle, BindlessTexturesNotSupportedError};
 pub use self::get_format::{InternalFormat, InternalFormatType, GetFormatError};
+pub use self::layout::PixelLayout;
 pub use self::pixel::PixelValue;
+#[cfg(feature = "image")]
+pub use self::dynamic_image::ImageOptions;
 pub use self::ty_support::{is_texture_1d_supported, is_texture_2d_supported};
 pub use self::ty_support::{is_texture_3d_supported, is_texture_1d_array_supported};
 pub use self::ty_support::{is_texture_2d_array_supported, is_texture_2d_multisample_supported};
 pub use self::ty_support::{is_texture_2d_multisample_array_supported, is_cubemaps_supported};
 pub use self::ty_support::is_cubemap_arrays_supported;
 
+pub mod atlas;
 pub mod bindless;
 pub mod buffer_texture;
+pub mod container;
+pub mod egl_image;
 pub mod pixel_buffer;
 
 mod any;
+#[cfg(feature = "image")]
+mod dynamic_image;
 mod get_format;
+mod layout;
 mod pixel;
+mod readback;
 mod ty_support;
 
 include!(concat!(env!("OUT_DIR"), "/textures.rs"));
@@ -186,6 +199,24 @@ pub enum TextureKind {
     DepthStencil,
 }
 
+/// Order of the color components in the client memory when reading a texture.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChannelOrder {
+    /// Red, green, blue, alpha. This is the order used by all the other reading functions.
+    Rgba,
+
+    /// Blue, green, red, alpha. The red and blue components are swapped. This has no effect
+    /// for pixel formats with less than three components.
+    Bgra,
+}
+
+impl Default for ChannelOrder {
+    #[inline]
+    fn default() -> ChannelOrder {
+        ChannelOrder::Rgba
+    }
+}
+
 /// Describes what to do about mipmaps during texture creation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum MipmapsOption {
@@ -525,6 +556,74 @@ impl<'a, T: Clone + 'a> RawImage2d<'a, T> {
     }
 }
 
+impl<'a> RawImage2d<'a, u8> {
+    /// Compares this image with another one, for example an image produced by
+    /// `Surface::snapshot` with a reference image.
+    ///
+    /// Two components are considered equal if their difference is lower than or equal to
+    /// `tolerance`. Each component of each pixel is compared separately.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use glium::Surface;
+    /// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+    /// # let expected: glium::texture::RawImage2d<u8> = unsafe { ::std::mem::uninitialized() };
+    /// let frame = display.draw();
+    /// // ... draw here ...
+    /// let snapshot = frame.snapshot(&display).unwrap();
+    /// frame.finish().unwrap();
+    ///
+    /// snapshot.compare(&expected, 2).unwrap();
+    /// ```
+    pub fn compare(&self, other: &RawImage2d<u8>, tolerance: u8) -> Result<(), ImageMismatch> {
+        if self.width != other.width || self.height != other.height {
+            return Err(ImageMismatch::DimensionsMismatch {
+                left: (self.width, self.height),
+                right: (other.width, other.height),
+            });
+        }
+
+        if self.format != other.format {
+            return Err(ImageMismatch::FormatMismatch);
+        }
+
+        let components = self.format.get_size();
+        let mut mismatched_pixels = 0;
+        let mut max_difference = 0;
+        let mut first_mismatch = None;
+
+        for (index, (a, b)) in self.data.chunks(components)
+                                        .zip(other.data.chunks(components))
+                                        .enumerate()
+        {
+            let difference = a.iter().zip(b.iter())
+                              .map(|(&a, &b)| if a > b { a - b } else { b - a })
+                              .max().unwrap_or(0);
+
+            if difference > tolerance {
+                mismatched_pixels += 1;
+                if difference > max_difference {
+                    max_difference = difference;
+                }
+                if first_mismatch.is_none() {
+                    first_mismatch = Some((index as u32 % self.width,
+                                           index as u32 / self.width));
+                }
+            }
+        }
+
+        match first_mismatch {
+            None => Ok(()),
+            Some(first_mismatch) => Err(ImageMismatch::PixelsMismatch {
+                mismatched_pixels: mismatched_pixels,
+                max_difference: max_difference,
+                first_mismatch: first_mismatch,
+            }),
+        }
+    }
+}
+
 impl<'a, P: PixelValue + Clone> Texture2dDataSource<'a> for Vec<Vec<P>> {
     type Data = P;
 
@@ -769,6 +868,7 @@ impl<P> Texture3dDataSink<P> for Vec<Vec<Vec<P>>> where P: Copy + Clone {
 
 /// Error that can happen when creating a texture.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TextureCreationError {
     /// The requested format is not supported by the backend.
     FormatNotSupported,
@@ -800,6 +900,59 @@ impl Error for TextureCreationError {
     }
 }
 
+/// Error returned by `RawImage2d::compare`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ImageMismatch {
+    /// The images don't have the same dimensions.
+    DimensionsMismatch {
+        /// Dimensions of the compared image.
+        left: (u32, u32),
+        /// Dimensions of the image it was compared to.
+        right: (u32, u32),
+    },
+
+    /// The images don't have the same pixel format.
+    FormatMismatch,
+
+    /// Some pixels differ by more than the tolerance.
+    PixelsMismatch {
+        /// Number of pixels that differ.
+        mismatched_pixels: usize,
+        /// Largest difference between two components.
+        max_difference: u8,
+        /// Coordinates of the first pixel that differs, starting from the bottom-left corner.
+        first_mismatch: (u32, u32),
+    },
+}
+
+impl fmt::Display for ImageMismatch {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImageMismatch::DimensionsMismatch { left, right } =>
+                write!(fmt, "{} ({:?} and {:?})", self.description(), left, right),
+            ImageMismatch::FormatMismatch => write!(fmt, "{}", self.description()),
+            ImageMismatch::PixelsMismatch { mismatched_pixels, max_difference, first_mismatch } =>
+                write!(fmt, "{} ({} pixels differ by up to {}, the first one is at {:?})",
+                       self.description(), mismatched_pixels, max_difference, first_mismatch),
+        }
+    }
+}
+
+impl Error for ImageMismatch {
+    fn description(&self) -> &str {
+        use self::ImageMismatch::*;
+        match *self {
+            DimensionsMismatch { .. } =>
+                "The images don't have the same dimensions",
+            FormatMismatch =>
+                "The images don't have the same pixel format",
+            PixelsMismatch { .. } =>
+                "Some pixels of the images are different",
+        }
+    }
+}
+
 impl From<FormatNotSupportedError> for TextureCreationError {
     #[inline]
     fn from(_: FormatNotSupportedError) -> TextureCreationError {
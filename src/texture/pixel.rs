@@ -1,6 +1,10 @@
+#[cfg(feature = "half")]
+use half;
 #[cfg(feature = "image")]
 use image;
 
+use vertex::F16;
+
 /// A trait that must be implemented for any type that can represent the value of a pixel.
 pub unsafe trait PixelValue: Copy + Clone + Send + 'static {
     /// Returns corresponding client format.
@@ -203,6 +207,34 @@ unsafe impl PixelValue for (f32, f32, f32, f32) {
     }
 }
 
+unsafe impl PixelValue for F16 {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16
+    }
+}
+
+unsafe impl PixelValue for (F16, F16) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16
+    }
+}
+
+unsafe impl PixelValue for (F16, F16, F16) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16F16
+    }
+}
+
+unsafe impl PixelValue for (F16, F16, F16, F16) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16F16F16
+    }
+}
+
 #[cfg(feature = "image")]
 unsafe impl PixelValue for image::Rgb<u8> {
     #[inline]
@@ -266,3 +298,35 @@ unsafe impl PixelValue for image::LumaA<f32> {
         super::ClientFormat::F32F32
     }
 }
+
+#[cfg(feature = "half")]
+unsafe impl PixelValue for half::f16 {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16
+    }
+}
+
+#[cfg(feature = "half")]
+unsafe impl PixelValue for (half::f16, half::f16) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16
+    }
+}
+
+#[cfg(feature = "half")]
+unsafe impl PixelValue for (half::f16, half::f16, half::f16) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16F16
+    }
+}
+
+#[cfg(feature = "half")]
+unsafe impl PixelValue for (half::f16, half::f16, half::f16, half::f16) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16F16F16
+    }
+}
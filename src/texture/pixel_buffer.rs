@@ -11,6 +11,7 @@ use std::ops::{Deref, DerefMut};
 use backend::Facade;
 
 use GlObject;
+use SyncFence;
 use buffer::{ReadError, Buffer, BufferType, BufferMode};
 use gl;
 
@@ -61,6 +62,81 @@ impl<T> DerefMut for PixelBuffer<T> where T: PixelValue {
     }
 }
 
+/// A pixel buffer that is the destination of a read operation that may not be finished yet.
+///
+/// This is returned by `Surface::read_to_pixel_buffer_async`. Instead of reading the content
+/// immediately, which would block until the GPU has finished drawing, you can check with
+/// `is_ready` whether the read has finished, for example once per frame.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// let frame = display.draw();
+/// // ... draw here ...
+/// let pending = frame.read_to_pixel_buffer_async(&display).unwrap();
+/// frame.finish().unwrap();
+///
+/// // later
+/// if pending.is_ready() {
+///     let image: glium::texture::RawImage2d<u8> = pending.read().unwrap();
+/// }
+/// ```
+pub struct PendingPixelRead<T> where T: PixelValue {
+    buffer: PixelBuffer<T>,
+    fence: Option<SyncFence>,
+}
+
+impl<T> PendingPixelRead<T> where T: PixelValue {
+    /// Wraps around a pixel buffer that is the destination of a read operation that has just
+    /// been submitted, for example with `Texture2d::read_to_pixel_buffer`.
+    ///
+    /// This inserts a fence in the commands queue.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, buffer: PixelBuffer<T>) -> PendingPixelRead<T>
+                          where F: Facade
+    {
+        PendingPixelRead {
+            buffer: buffer,
+            fence: SyncFence::new(facade).ok(),
+        }
+    }
+
+    /// Returns true if the read operation has finished and the content of the buffer can be
+    /// read without blocking.
+    ///
+    /// If the backend doesn't support fences, this always returns `true` and reading may block.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.fence.as_ref().map(|f| f.is_signaled()).unwrap_or(true)
+    }
+
+    /// Reads the content of the buffer. Blocks if the read operation hasn't finished yet.
+    pub fn read<S>(mut self) -> Result<S, ReadError> where S: Texture2dDataSink<T> {
+        if let Some(fence) = self.fence.take() {
+            fence.wait();
+        }
+
+        self.buffer.read_as_texture_2d()
+    }
+
+    /// Blocks until the read operation has finished, then returns the underlying pixel buffer.
+    pub fn wait(mut self) -> PixelBuffer<T> {
+        if let Some(fence) = self.fence.take() {
+            fence.wait();
+        }
+
+        self.buffer
+    }
+
+    /// Returns the underlying pixel buffer, without waiting for the read operation.
+    #[inline]
+    pub fn into_pixel_buffer(self) -> PixelBuffer<T> {
+        self.buffer
+    }
+}
+
 // TODO: rework this
 impl<T> GlObject for PixelBuffer<T> where T: PixelValue {
     type Id = gl::types::GLuint;
@@ -0,0 +1,234 @@
+//! Reading the content of textures that can't be attached to a framebuffer.
+//!
+//! Textures are normally read by attaching them to a framebuffer and calling `glReadPixels`.
+//! This isn't possible for compressed textures and for formats that are not color-renderable.
+//! OpenGL provides `glGetTexImage` for these textures, but OpenGL ES doesn't, so the texture is
+//! instead drawn on a color-renderable texture with a shader, which is then read.
+//!
+//! The same problem exists for depth textures, which OpenGL ES can only read with the
+//! `GL_NV_read_depth` extension. Without it, the depth values are drawn with a shader that packs
+//! each value into the four components of an RGBA8 pixel.
+
+use std::borrow::Cow;
+use std::rc::Rc;
+
+use buffer::BufferAny;
+use context::{Context, Fallback};
+use framebuffer::SimpleFrameBuffer;
+use image_format::{TextureFormat, TextureFormatRequest};
+use index::{NoIndices, PrimitiveType};
+use texture::{DepthTexture2d, Texture2d, SrgbTexture2d, Texture2dDataSink, RawImage2d};
+use texture::{ReadError, TextureAny, UncompressedFloatFormat, SrgbFormat, MipmapsOption};
+use uniforms::{AsUniformValue, Sampler, MagnifySamplerFilter, MinifySamplerFilter};
+use version::{Api, Version};
+use vertex::VertexBuffer;
+
+use gl;
+use BufferExt;
+use CapabilitiesSource;
+use ContextExt;
+use Program;
+use Rect;
+use Surface;
+use TextureExt;
+
+#[derive(Copy, Clone)]
+struct SpriteVertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(SpriteVertex, position);
+
+const VERTEX_SHADER: &'static str = "
+    #version 100
+
+    attribute vec2 position;
+    varying vec2 v_tex_coords;
+
+    void main() {
+        v_tex_coords = position * 0.5 + 0.5;
+        gl_Position = vec4(position, 0.0, 1.0);
+    }
+";
+
+const COPY_FRAGMENT_SHADER: &'static str = "
+    #version 100
+
+    precision mediump float;
+
+    uniform sampler2D source;
+    varying vec2 v_tex_coords;
+
+    void main() {
+        gl_FragColor = texture2D(source, v_tex_coords);
+    }
+";
+
+const PACK_DEPTH_FRAGMENT_SHADER: &'static str = "
+    #version 100
+
+    #ifdef GL_FRAGMENT_PRECISION_HIGH
+        precision highp float;
+    #else
+        precision mediump float;
+    #endif
+
+    uniform sampler2D source;
+    varying vec2 v_tex_coords;
+
+    void main() {
+        // 1.0 would be packed as 0.0
+        float value = min(texture2D(source, v_tex_coords).r, 0.99999994);
+        vec4 packed = fract(value * vec4(1.0, 255.0, 65025.0, 16581375.0));
+        packed -= packed.yzww * vec4(1.0 / 255.0, 1.0 / 255.0, 1.0 / 255.0, 0.0);
+        gl_FragColor = packed;
+    }
+";
+
+/// Implementation of `read` and `try_read` for two-dimensional color textures.
+///
+/// `texture` and `any` must be the same texture.
+pub fn read_texture_2d<'t, T, S>(context: &Rc<Context>, texture: &'t T, any: &TextureAny)
+                                 -> Result<S, ReadError>
+                                 where Sampler<'t, T>: AsUniformValue,
+                                       S: Texture2dDataSink<(u8, u8, u8, u8)>
+{
+    let (width, height) = (any.get_width(), any.get_height().unwrap_or(1));
+    let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+
+    if is_color_renderable(context, any) {
+        return Ok(any.main_level().first_layer().into_image(None).unwrap().raw_read(&rect));
+    }
+
+    if !(context.get_version() >= &Version(Api::GlEs, 2, 0)) {
+        return Ok(get_tex_image(context, any));
+    }
+
+    context.make_current().fallbacks.record(Fallback::TextureReadThroughFramebuffer);
+
+    // sRGB textures are drawn on an sRGB texture, so that the values are not converted
+    if is_srgb(any) {
+        let target = try!(SrgbTexture2d::empty_with_format(context, SrgbFormat::U8U8U8U8,
+                                                           MipmapsOption::NoMipmap, width, height)
+                              .map_err(|_| ReadError::OutputFormatNotSupported));
+        {
+            let mut framebuffer = try!(SimpleFrameBuffer::new(context, &target)
+                                           .map_err(|_| ReadError::OutputFormatNotSupported));
+            try!(draw_texture(context, texture, &mut framebuffer, COPY_FRAGMENT_SHADER));
+        }
+        Ok(target.main_level().first_layer().into_image(None).unwrap().raw_read(&rect))
+
+    } else {
+        let target = try!(Texture2d::empty_with_format(context, UncompressedFloatFormat::U8U8U8U8,
+                                                       MipmapsOption::NoMipmap, width, height)
+                              .map_err(|_| ReadError::OutputFormatNotSupported));
+        try!(draw_texture(context, texture, &mut target.as_surface(), COPY_FRAGMENT_SHADER));
+        Ok(target.main_level().first_layer().into_image(None).unwrap().raw_read(&rect))
+    }
+}
+
+/// Implementation of `DepthTexture2d::read_depth`.
+pub fn read_depth_texture<T>(context: &Rc<Context>, texture: &DepthTexture2d)
+                             -> Result<T, ReadError>
+                             where T: Texture2dDataSink<f32>
+{
+    let direct = !(context.get_version() >= &Version(Api::GlEs, 2, 0)) ||
+                 context.get_extensions().gl_nv_read_depth;
+
+    if direct {
+        let framebuffer = try!(SimpleFrameBuffer::depth_only(context, texture)
+                                   .map_err(|_| ReadError::AttachmentTypeNotSupported));
+        return framebuffer.read_depth();
+    }
+
+    // drawing the depth values packed in an RGBA8 texture, then unpacking them
+    // the precision of the values depends on the precision of the floats in the fragment shader
+    let (width, height) = (texture.get_width(), texture.get_height().unwrap_or(1));
+    let target = Texture2d::empty_with_format(context, UncompressedFloatFormat::U8U8U8U8,
+                                              MipmapsOption::NoMipmap, width, height).unwrap();
+    try!(draw_texture(context, texture, &mut target.as_surface(), PACK_DEPTH_FRAGMENT_SHADER));
+
+    let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+    let packed: RawImage2d<u8> = target.main_level().first_layer().into_image(None).unwrap()
+                                       .raw_read::<_, (u8, u8, u8, u8)>(&rect);
+    let values = packed.data.chunks(4).map(|p| {
+        (p[0] as f64 / 255.0 + p[1] as f64 / 65025.0 + p[2] as f64 / 16581375.0 +
+         p[3] as f64 / 4228250625.0) as f32
+    }).collect::<Vec<_>>();
+
+    Ok(T::from_raw(Cow::Owned(values), width, height))
+}
+
+/// Returns true if the texture can be attached to a framebuffer as a color attachment.
+fn is_color_renderable(context: &Rc<Context>, texture: &TextureAny) -> bool {
+    match texture.get_requested_format() {
+        TextureFormatRequest::Specific(format) => format.is_renderable(context),
+        TextureFormatRequest::AnyCompressed => false,
+        TextureFormatRequest::AnyCompressedSrgb => false,
+        _ => true,
+    }
+}
+
+/// Returns true if the texture has an sRGB format.
+fn is_srgb(texture: &TextureAny) -> bool {
+    match texture.get_requested_format() {
+        TextureFormatRequest::Specific(TextureFormat::Srgb(_)) => true,
+        TextureFormatRequest::Specific(TextureFormat::CompressedSrgbFormat(_)) => true,
+        TextureFormatRequest::AnySrgb => true,
+        TextureFormatRequest::AnyCompressedSrgb => true,
+        _ => false,
+    }
+}
+
+/// Reads the main level of a two-dimensional texture with `glGetTexImage`. Only available with
+/// OpenGL.
+fn get_tex_image<S>(context: &Rc<Context>, texture: &TextureAny) -> S
+                    where S: Texture2dDataSink<(u8, u8, u8, u8)>
+{
+    let (width, height) = (texture.get_width(), texture.get_height().unwrap_or(1));
+    let pixels = width as usize * height as usize;
+
+    let mut data: Vec<(u8, u8, u8, u8)> = Vec::with_capacity(pixels);
+
+    let mut ctxt = context.make_current();
+
+    unsafe {
+        BufferAny::unbind_pixel_pack(&mut ctxt);
+
+        if ctxt.state.pixel_store_pack_alignment != 1 {
+            ctxt.state.pixel_store_pack_alignment = 1;
+            ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
+        }
+
+        let bind_point = texture.bind_to_current(&mut ctxt);
+        ctxt.gl.GetTexImage(bind_point, 0, gl::RGBA, gl::UNSIGNED_BYTE,
+                            data.as_mut_ptr() as *mut _);
+        data.set_len(pixels);
+    }
+
+    S::from_raw(Cow::Owned(data), width, height)
+}
+
+/// Draws a texture on the whole surface with the given fragment shader, which receives the
+/// texture as the `source` uniform.
+fn draw_texture<'t, T, S>(context: &Rc<Context>, texture: &'t T, surface: &mut S,
+                          fragment_shader: &str) -> Result<(), ReadError>
+                          where Sampler<'t, T>: AsUniformValue, S: Surface
+{
+    let program = try!(Program::from_source(context, VERTEX_SHADER, fragment_shader, None)
+                           .map_err(|_| ReadError::AttachmentTypeNotSupported));
+
+    let vertex_buffer = VertexBuffer::new(context, &[
+        SpriteVertex { position: [-1.0, -1.0] }, SpriteVertex { position: [-1.0, 1.0] },
+        SpriteVertex { position: [1.0, -1.0] }, SpriteVertex { position: [1.0, 1.0] },
+    ]).unwrap();
+
+    let uniforms = uniform! {
+        source: Sampler::new(texture).magnify_filter(MagnifySamplerFilter::Nearest)
+                                     .minify_filter(MinifySamplerFilter::Nearest),
+    };
+
+    surface.draw(&vertex_buffer, &NoIndices(PrimitiveType::TriangleStrip), &program,
+                 &uniforms, &Default::default())
+           .map_err(|_| ReadError::AttachmentTypeNotSupported)
+}
@@ -0,0 +1,152 @@
+/*!
+Capability tokens, which prove that the backend supports a feature.
+
+This module is only available if you enable the `tokens` feature of glium. Enabling it also
+deprecates the functions that the tokens replace, like `ComputeShader::is_supported`.
+
+Many functions of glium check whether the backend supports a feature every time they are
+called, and return an error if it doesn't. A token is obtained once from a context, and only if
+the feature is supported. Its methods then use the feature without returning an error for this
+reason, which means that code that requires a feature can take the token as a parameter instead
+of handling the same error at every call.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+use glium::tokens::SyncSupport;
+
+let sync = match SyncSupport::new(&display) {
+    Some(sync) => sync,
+    None => return,         // use another way to synchronize
+};
+
+// `fence` can't fail
+let fence = sync.fence();
+fence.wait();
+```
+
+A token can only be used with the objects of the context it was obtained from. The methods that
+take an object panic if this isn't the case.
+
+*/
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use program::{ComputeShader, ProgramCreationError};
+use sync::{self, SyncFence};
+use texture::TextureAny;
+use texture::bindless::ResidentTexture;
+use vertex::{self, PerInstance, VertexBuffer};
+
+use CapabilitiesSource;
+use TextureExt;
+
+/// Proves that the backend supports sync objects.
+#[derive(Clone)]
+pub struct SyncSupport {
+    context: Rc<Context>,
+}
+
+impl SyncSupport {
+    /// Returns a token if the backend supports sync objects.
+    pub fn new<F: ?Sized>(facade: &F) -> Option<SyncSupport> where F: Facade {
+        if !sync::is_sync_supported(&**facade.get_context()) {
+            return None;
+        }
+
+        Some(SyncSupport { context: facade.get_context().clone() })
+    }
+
+    /// Builds a new `SyncFence` that is injected in the commands queue.
+    #[inline]
+    pub fn fence(&self) -> SyncFence {
+        SyncFence::new(&self.context).unwrap()
+    }
+}
+
+/// Proves that the backend supports compute shaders.
+#[derive(Clone)]
+pub struct ComputeSupport {
+    context: Rc<Context>,
+}
+
+impl ComputeSupport {
+    /// Returns a token if the backend supports compute shaders.
+    pub fn new<F: ?Sized>(facade: &F) -> Option<ComputeSupport> where F: Facade {
+        #[allow(deprecated)]
+        let supported = ComputeShader::is_supported(&**facade.get_context());
+
+        if !supported {
+            return None;
+        }
+
+        Some(ComputeSupport { context: facade.get_context().clone() })
+    }
+
+    /// Builds a compute shader from some source code.
+    ///
+    /// Contrary to `ComputeShader::from_source`, this never returns `ShaderTypeNotSupported`.
+    /// Compilation and linking errors are still returned.
+    #[inline]
+    pub fn compute_shader(&self, src: &str) -> Result<ComputeShader, ProgramCreationError> {
+        ComputeShader::from_source(&self.context, src)
+    }
+}
+
+/// Proves that the backend supports per-instance attributes.
+#[derive(Clone)]
+pub struct InstancingSupport {
+    context: Rc<Context>,
+}
+
+impl InstancingSupport {
+    /// Returns a token if the backend supports per-instance attributes.
+    pub fn new<F: ?Sized>(facade: &F) -> Option<InstancingSupport> where F: Facade {
+        if !vertex::is_instancing_supported(&**facade.get_context()) {
+            return None;
+        }
+
+        Some(InstancingSupport { context: facade.get_context().clone() })
+    }
+
+    /// Same as `VertexBuffer::per_instance`, but can't fail.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer doesn't belong to the context of the token.
+    pub fn per_instance<'a, T>(&self, buffer: &'a VertexBuffer<T>) -> PerInstance<'a>
+                               where T: Copy
+    {
+        assert!(Rc::ptr_eq(buffer.get_context(), &self.context),
+                "The buffer doesn't belong to the context of the token");
+        buffer.per_instance().unwrap()
+    }
+}
+
+/// Proves that the backend supports bindless textures.
+#[derive(Clone)]
+pub struct BindlessSupport {
+    context: Rc<Context>,
+}
+
+impl BindlessSupport {
+    /// Returns a token if the backend supports bindless textures.
+    pub fn new<F: ?Sized>(facade: &F) -> Option<BindlessSupport> where F: Facade {
+        if !facade.get_context().get_extensions().gl_arb_bindless_texture {
+            return None;
+        }
+
+        Some(BindlessSupport { context: facade.get_context().clone() })
+    }
+
+    /// Same as `ResidentTexture::new`, but can't fail.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture doesn't belong to the context of the token.
+    pub fn make_resident(&self, texture: TextureAny) -> ResidentTexture {
+        assert!(Rc::ptr_eq(texture.get_context(), &self.context),
+                "The texture doesn't belong to the context of the token");
+        ResidentTexture::new(texture).unwrap()
+    }
+}
@@ -19,43 +19,94 @@ use RawUniformValue;
 use TextureExt;
 
 use uniforms::Uniforms;
+use uniforms::MissingUniforms;
+use uniforms::UniformArray;
+use uniforms::UniformStruct;
+use uniforms::UniformSamplerArray;
 use uniforms::UniformValue;
 use uniforms::SamplerBehavior;
+use uniforms::ImageUnitBehavior;
+use uniforms::ShaderAccess;
 
-use context::CommandContext;
+use context::{CommandContext, ValidationLevel};
+use context::ImageUnitState;
 use buffer::Inserter;
 
 use utils::bitsfield::Bitsfield;
 
 use program;
 use context;
+use texture;
+use texture::Dimensions;
+use ToGlEnum;
 use version::Version;
 use version::Api;
 
 impl<U> UniformsExt for U where U: Uniforms {
     fn bind_uniforms<'a, P>(&'a self, mut ctxt: &mut CommandContext, program: &P,
+                            missing_uniforms: Option<MissingUniforms>,
                             fences: &mut Vec<Inserter<'a>>)
                             -> Result<(), DrawError>
                             where P: ProgramExt
     {
         let mut texture_bind_points = Bitsfield::new();
+        let mut image_bind_points = Bitsfield::new();
         let mut uniform_buffer_bind_points = Bitsfield::new();
         let mut shared_storage_buffer_bind_points = Bitsfield::new();
 
+        // the texture units and binding points that have been assigned to the program can't be
+        // picked for the other samplers and blocks
+        program.reserve_fixed_bindings(&mut texture_bind_points, &mut uniform_buffer_bind_points);
+
         // Subroutine uniforms must be bound all at once, so we collect them first and process them at the end.
         // The vec contains the uniform we want to set and the value we want to set it to.
         let mut subroutine_bindings: HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, _>
             = HashMap::with_hasher(Default::default());
 
+        // the names of the uniforms that have a value are only needed to find the ones that don't
+        let policy = missing_uniforms.unwrap_or_else(|| program.get_missing_uniforms_policy());
+        let track_names = policy != MissingUniforms::Ignore || program.has_default_uniforms();
+        let mut provided_names = Vec::new();
+
         let mut visiting_result = Ok(());
         self.visit_values(|name, value| {
             if visiting_result.is_err() { return; }
 
-            if let Some(uniform) = program.get_uniform(name) {
-                // TODO: remove the size member
-                debug_assert!(uniform.size.is_none());
+            if track_names {
+                provided_names.push(name.to_owned());
+            }
+
+            match value {
+                UniformValue::Array(array) => {
+                    visiting_result = bind_uniform_array(&mut ctxt, array, program, name);
+                    return;
+                },
+                UniformValue::Struct(value) => {
+                    visiting_result = bind_uniform_struct(&mut ctxt, value, program,
+                                                          &mut texture_bind_points,
+                                                          &mut image_bind_points, name);
+                    return;
+                },
+                UniformValue::Samplers(array) => {
+                    visiting_result = bind_sampler_array(&mut ctxt, array, program,
+                                                         &mut texture_bind_points,
+                                                         &mut image_bind_points, name);
+                    return;
+                },
+                _ => ()
+            }
+
+            if let Some(counter) = program.get_atomic_counters().get(name) {
+                match bind_atomic_counter(&mut ctxt, &value, counter, name) {
+                    Ok(Some(fence)) => fences.push(fence),
+                    Ok(None) => (),
+                    Err(e) => visiting_result = Err(e),
+                }
 
-                if !value.is_usable_with(&uniform.ty) {
+            } else if let Some(uniform) = program.get_uniform(name) {
+                if ctxt.validation_level == ValidationLevel::Full &&
+                   !value.is_usable_with(&uniform.ty)
+                {
                     visiting_result = Err(DrawError::UniformTypeMismatch {
                         name: name.to_owned(),
                         expected: uniform.ty,
@@ -64,7 +115,7 @@ impl<U> UniformsExt for U where U: Uniforms {
                 }
 
                 match bind_uniform(&mut ctxt, &value, program, uniform.location,
-                                   &mut texture_bind_points, name)
+                                   &mut texture_bind_points, &mut image_bind_points, name)
                 {
                     Ok(_) => (),
                     Err(e) => {
@@ -122,10 +173,101 @@ impl<U> UniformsExt for U where U: Uniforms {
             }
         }
 
-        visiting_result
+        try!(visiting_result);
+
+        if track_names {
+            try!(bind_missing_uniforms(ctxt, program, policy, &provided_names,
+                                       &mut texture_bind_points, &mut image_bind_points));
+        }
+
+        Ok(())
     }
 }
 
+/// Binds the default values of the uniforms that haven't been given a value, then applies
+/// `policy` to the ones that still have none.
+fn bind_missing_uniforms<P>(ctxt: &mut context::CommandContext, program: &P,
+                            policy: MissingUniforms, provided_names: &[String],
+                            texture_bind_points: &mut Bitsfield,
+                            image_bind_points: &mut Bitsfield)
+                            -> Result<(), DrawError> where P: ProgramExt
+{
+    let mut defaults_names = Vec::new();
+    let mut result = Ok(());
+
+    program.visit_default_uniforms(&mut |name, value| {
+        if result.is_err() || is_provided(name, provided_names) { return; }
+        defaults_names.push(name.to_owned());
+
+        result = match value {
+            UniformValue::Array(array) => bind_uniform_array(ctxt, array, program, name),
+            UniformValue::Struct(value) => {
+                bind_uniform_struct(ctxt, value, program, texture_bind_points, image_bind_points,
+                                    name)
+            },
+            UniformValue::Samplers(array) => {
+                bind_sampler_array(ctxt, array, program, texture_bind_points, image_bind_points,
+                                   name)
+            },
+            value => match program.get_uniform(name) {
+                // atomic counters don't have a location and can't have a default value
+                Some(uniform) if uniform.location < 0 => Ok(()),
+                Some(uniform) => {
+                    if ctxt.validation_level == ValidationLevel::Full &&
+                       !value.is_usable_with(&uniform.ty)
+                    {
+                        Err(DrawError::UniformTypeMismatch {
+                            name: name.to_owned(),
+                            expected: uniform.ty,
+                        })
+                    } else {
+                        bind_uniform(ctxt, &value, program, uniform.location,
+                                     texture_bind_points, image_bind_points, name)
+                    }
+                },
+                None => Ok(()),
+            },
+        };
+    });
+
+    try!(result);
+
+    if policy == MissingUniforms::Ignore {
+        return Ok(());
+    }
+
+    let names = program.get_uniforms().keys().chain(program.get_uniform_blocks().keys());
+    for name in names {
+        // built-in uniforms can't be set
+        if name.starts_with("gl_") || is_provided(name, provided_names) ||
+           is_provided(name, &defaults_names)
+        {
+            continue;
+        }
+
+        match policy {
+            MissingUniforms::Ignore => (),
+            MissingUniforms::WarnOnce => program.warn_missing_uniform(name),
+            MissingUniforms::Error => {
+                return Err(DrawError::UniformMissing { name: name.clone() });
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true if `name` is one of `provided_names`, or an element or a member of one of them.
+fn is_provided(name: &str, provided_names: &[String]) -> bool {
+    provided_names.iter().any(|provided| {
+        name.starts_with(&provided[..]) &&
+        match name.as_bytes().get(provided.len()) {
+            None | Some(&b'[') | Some(&b'.') => true,
+            _ => false,
+        }
+    })
+}
+
 fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext, program: &P,
                             subroutine_bindings: &HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, BuildHasherDefault<FnvHasher>>)
                             -> Result<(), DrawError>
@@ -175,20 +317,27 @@ fn bind_uniform_block<'a, P>(ctxt: &mut context::CommandContext, value: &Uniform
 {
     match value {
         &UniformValue::Block(buffer, ref layout) => {
-            match layout(block) {
-                Ok(_) => (),
-                Err(e) => {
-                    return Err(DrawError::UniformBlockLayoutMismatch {
-                        name: name.to_owned(),
-                        err: e,
-                    });
+            if ctxt.validation_level == ValidationLevel::Full {
+                match layout(block) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        return Err(DrawError::UniformBlockLayoutMismatch {
+                            name: name.to_owned(),
+                            err: e,
+                        });
+                    }
                 }
             }
 
-            let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
+            let bind_point = match program.get_fixed_uniform_block_binding(block.id) {
+                Some(bind_point) => bind_point,
+                None => buffer_bind_points.get_unused().expect("Not enough buffer units"),
+            };
             buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
+            // only the range of the slice is bound, and its offset must be properly aligned
+            assert!(buffer.get_offset_bytes() %
+                    ctxt.capabilities.uniform_buffer_offset_alignment as usize == 0);
             let fence = buffer.add_fence();
             let block_id = block.id as gl::types::GLuint;
 
@@ -209,54 +358,91 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext, value: &
                                     -> Result<Option<Inserter<'a>>, DrawError>
                                     where P: ProgramExt
 {
-    match value {
-        &UniformValue::Block(buffer, ref layout) => {
-            match layout(block) {
-                Ok(_) => (),
-                Err(e) => {
-                    return Err(DrawError::UniformBlockLayoutMismatch {
-                        name: name.to_owned(),
-                        err: e,
-                    });
-                }
+    let (buffer, layout, access) = match value {
+        &UniformValue::Block(buffer, layout) => (buffer, layout, ShaderAccess::ReadWrite),
+        &UniformValue::ShaderStorage(buffer, layout, access) => (buffer, layout, access),
+        _ => {
+            return Err(DrawError::UniformValueToBlock { name: name.to_owned() });
+        }
+    };
+
+    if ctxt.validation_level == ValidationLevel::Full {
+        match layout(block) {
+            Ok(_) => (),
+            Err(e) => {
+                return Err(DrawError::UniformBlockLayoutMismatch {
+                    name: name.to_owned(),
+                    err: e,
+                });
             }
+        }
+    }
 
-            let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
-            buffer_bind_points.set_used(bind_point);
+    let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
+    buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
-            let fence = buffer.add_fence();
-            let block_id = block.id as gl::types::GLuint;
+    // only the range of the slice is bound, and its offset must be properly aligned
+    assert!(buffer.get_offset_bytes() %
+            ctxt.capabilities.shader_storage_buffer_offset_alignment as usize == 0);
+    let fence = buffer.add_fence();
+    let block_id = block.id as gl::types::GLuint;
 
-            buffer.prepare_and_bind_for_shared_storage(ctxt, bind_point as gl::types::GLuint);
-            program.set_shader_storage_block_binding(ctxt, block_id, bind_point as gl::types::GLuint);
+    buffer.prepare_and_bind_for_shared_storage(ctxt, bind_point as gl::types::GLuint,
+                                               access.is_write());
+    program.set_shader_storage_block_binding(ctxt, block_id, bind_point as gl::types::GLuint);
 
-            Ok(fence)
-        },
+    Ok(fence)
+}
+
+fn bind_atomic_counter<'a>(ctxt: &mut context::CommandContext, value: &UniformValue<'a>,
+                           counter: &program::AtomicCounter, name: &str)
+                           -> Result<Option<Inserter<'a>>, DrawError>
+{
+    let buffer = match value {
+        &UniformValue::Block(buffer, _) | &UniformValue::ShaderStorage(buffer, _, _) => buffer,
         _ => {
-            Err(DrawError::UniformValueToBlock { name: name.to_owned() })
+            return Err(DrawError::UniformValueToBlock { name: name.to_owned() });
         }
+    };
+
+    // the counters are 32 bits unsigned integers
+    if counter.offset + 4 > buffer.get_size() {
+        return Err(DrawError::AtomicCounterOutOfBuffer { name: name.to_owned() });
     }
+
+    // the binding point is chosen by the shader, so several counters may use the same buffer
+    let fence = buffer.add_fence();
+    buffer.prepare_and_bind_for_atomic_counter(ctxt, counter.binding);
+
+    Ok(fence)
 }
 
 fn bind_uniform<P>(ctxt: &mut context::CommandContext,
                    value: &UniformValue, program: &P, location: gl::types::GLint,
-                   texture_bind_points: &mut Bitsfield, name: &str)
+                   texture_bind_points: &mut Bitsfield, image_bind_points: &mut Bitsfield,
+                   name: &str)
                    -> Result<(), DrawError> where P: ProgramExt
 {
     assert!(location >= 0);
 
     match *value {
-        UniformValue::Block(_, _) => {
+        UniformValue::Block(_, _) | UniformValue::ShaderStorage(_, _, _) => {
             Err(DrawError::UniformBufferToValue {
                 name: name.to_owned(),
             })
         },
+        UniformValue::Image(texture, ref behavior) => {
+            bind_image_uniform(ctxt, texture, behavior, location, program, image_bind_points)
+        },
         UniformValue::Subroutine(_, _) => {
             Err(DrawError::SubroutineUniformToValue {
                 name: name.to_owned(),
             })
         },
+        UniformValue::Array(_) | UniformValue::Struct(_) | UniformValue::Samplers(_) => {
+            // bound by `bind_uniform_array`, `bind_uniform_struct` and `bind_sampler_array`
+            unreachable!()
+        },
         UniformValue::Bool(val) => {
             // Booleans get passed as integers.
             program.set_uniform(ctxt, location, &RawUniformValue::SignedInt(val as i32));
@@ -286,6 +472,30 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::Mat4(val));
             Ok(())
         },
+        UniformValue::Mat2x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat2x3(val));
+            Ok(())
+        },
+        UniformValue::Mat2x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat2x4(val));
+            Ok(())
+        },
+        UniformValue::Mat3x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat3x2(val));
+            Ok(())
+        },
+        UniformValue::Mat3x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat3x4(val));
+            Ok(())
+        },
+        UniformValue::Mat4x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat4x2(val));
+            Ok(())
+        },
+        UniformValue::Mat4x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat4x3(val));
+            Ok(())
+        },
         UniformValue::Vec2(val) => {
             program.set_uniform(ctxt, location, &RawUniformValue::Vec2(val));
             Ok(())
@@ -353,6 +563,30 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat4(val));
             Ok(())
         },
+        UniformValue::DoubleMat2x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat2x3(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat2x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat2x4(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat3x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat3x2(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat3x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat3x4(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat4x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat4x2(val));
+            Ok(())
+        },
+        UniformValue::DoubleMat4x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::DoubleMat4x3(val));
+            Ok(())
+        },
         UniformValue::DoubleVec2(val) => {
             program.set_uniform(ctxt, location, &RawUniformValue::DoubleVec2(val));
             Ok(())
@@ -397,195 +631,392 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::UnsignedInt64Vec4(val));
             Ok(())
         },
+        UniformValue::TextureHandle(handle) => {
+            if ctxt.validation_level == ValidationLevel::Full &&
+               !ctxt.resident_texture_handles.contains(&handle)
+            {
+                return Err(DrawError::TextureHandleNotResident { name: name.to_owned() });
+            }
+
+            program.set_uniform(ctxt, location, &RawUniformValue::TextureHandle(handle));
+            Ok(())
+        },
         UniformValue::Texture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthTexture1d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::Texture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthTexture2d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::Texture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthTexture2dMultisample(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::Texture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthTexture3d(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::Texture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthTexture1dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::Texture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedSrgbTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthTexture2dArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::Texture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthTexture2dMultisampleArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::Cubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedSrgbCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthCubemap(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::SrgbCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::CompressedSrgbCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::IntegralCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::UnsignedCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::DepthCubemapArray(texture, sampler) => {
-            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program,
+                                 texture_bind_points, name)
         },
         UniformValue::BufferTexture(texture) => {
-            bind_texture_uniform(ctxt, &texture, None, location, program, texture_bind_points)
+            bind_texture_uniform(ctxt, &texture, None, location, program,
+                                 texture_bind_points, name)
         },
     }
 }
 
+fn bind_uniform_array<P>(ctxt: &mut context::CommandContext, array: UniformArray,
+                         program: &P, name: &str)
+                         -> Result<(), DrawError> where P: ProgramExt
+{
+    // the reflection stores each element of the array separately, and the first one has the
+    // size of the array
+    let uniform = match program.get_uniform(&format!("{}[0]", name)) {
+        Some(uniform) => uniform,
+        None => return Ok(()),
+    };
+
+    if ctxt.validation_level == ValidationLevel::Full && !array.is_usable_with(&uniform.ty) {
+        return Err(DrawError::UniformTypeMismatch {
+            name: name.to_owned(),
+            expected: uniform.ty,
+        });
+    }
+
+    if let UniformArray::TextureHandle(handles) = array {
+        if ctxt.validation_level == ValidationLevel::Full &&
+           !handles.iter().all(|h| ctxt.resident_texture_handles.contains(h))
+        {
+            return Err(DrawError::TextureHandleNotResident { name: name.to_owned() });
+        }
+    }
+
+    // the elements that don't exist in the program are ignored
+    let array = array.truncate(uniform.size.unwrap_or(1));
+    if !array.is_empty() {
+        program.set_uniform_array(ctxt, uniform.location, &array);
+    }
+
+    Ok(())
+}
+
+fn bind_sampler_array<P>(ctxt: &mut context::CommandContext, array: &UniformSamplerArray,
+                         program: &P, texture_bind_points: &mut Bitsfield,
+                         image_bind_points: &mut Bitsfield, name: &str)
+                         -> Result<(), DrawError> where P: ProgramExt
+{
+    let mut textures = Vec::new();
+    array.visit_textures(&mut |value| textures.push(value));
+
+    let max = ctxt.capabilities.max_texture_image_units as usize;
+    if textures.len() > max {
+        return Err(DrawError::SamplerArrayTooLarge {
+            name: name.to_owned(),
+            len: textures.len(),
+            max: max,
+        });
+    }
+
+    for (index, value) in textures.into_iter().enumerate() {
+        let name = format!("{}[{}]", name, index);
+
+        // the elements that don't exist in the program are ignored
+        let uniform = match program.get_uniform(&name) {
+            Some(uniform) => uniform,
+            None => continue,
+        };
+
+        if ctxt.validation_level == ValidationLevel::Full && !value.is_usable_with(&uniform.ty) {
+            return Err(DrawError::UniformTypeMismatch {
+                name: name,
+                expected: uniform.ty,
+            });
+        }
+
+        try!(bind_uniform(ctxt, &value, program, uniform.location, texture_bind_points,
+                          image_bind_points, &name));
+    }
+
+    Ok(())
+}
+
+fn bind_uniform_struct<P>(ctxt: &mut context::CommandContext, value: &UniformStruct,
+                          program: &P, texture_bind_points: &mut Bitsfield,
+                          image_bind_points: &mut Bitsfield, name: &str)
+                          -> Result<(), DrawError> where P: ProgramExt
+{
+    let mut result = Ok(());
+
+    value.visit_fields(&mut |field, value| {
+        if result.is_err() { return; }
+
+        let name = format!("{}{}", name, field);
+
+        result = match value {
+            UniformValue::Array(array) => bind_uniform_array(ctxt, array, program, &name),
+            UniformValue::Struct(value) => {
+                bind_uniform_struct(ctxt, value, program, texture_bind_points, image_bind_points,
+                                    &name)
+            },
+            UniformValue::Samplers(array) => {
+                bind_sampler_array(ctxt, array, program, texture_bind_points, image_bind_points,
+                                   &name)
+            },
+            value => match program.get_uniform(&name) {
+                Some(uniform) => {
+                    if ctxt.validation_level == ValidationLevel::Full &&
+                       !value.is_usable_with(&uniform.ty)
+                    {
+                        Err(DrawError::UniformTypeMismatch {
+                            name: name.clone(),
+                            expected: uniform.ty,
+                        })
+                    } else {
+                        bind_uniform(ctxt, &value, program, uniform.location,
+                                     texture_bind_points, image_bind_points, &name)
+                    }
+                },
+
+                // members that are not used by the program are not active
+                None => Ok(()),
+            },
+        };
+    });
+
+    result
+}
+
 fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
                               texture: &T, sampler: Option<SamplerBehavior>,
                               location: gl::types::GLint, program: &P,
-                              texture_bind_points: &mut Bitsfield)
+                              texture_bind_points: &mut Bitsfield, name: &str)
                               -> Result<(), DrawError> where P: ProgramExt, T: TextureExt
 {
+    // the texture may have been written through an image unit by a previous command
+    if ctxt.state.latest_image_write >= ctxt.state.latest_memory_barrier_texture_fetch {
+        unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_FETCH_BARRIER_BIT); }
+        ctxt.state.latest_memory_barrier_texture_fetch = ctxt.state.next_draw_call_id;
+    }
+
     let sampler = if let Some(sampler) = sampler {
         Some(try!(::sampler_object::get_sampler(ctxt, &sampler)))
     } else {
@@ -594,27 +1025,50 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
 
     let sampler = sampler.unwrap_or(0);
 
-    // finding an appropriate texture unit
-    let texture_unit =
-        ctxt.state.texture_units
-            .iter().enumerate()
-            .find(|&(unit, content)| {
-                content.texture == texture.get_texture_id() && (content.sampler == sampler ||
-                                                        !texture_bind_points.is_used(unit as u16))
-            })
-            .map(|(unit, _)| unit as u16)
-            .or_else(|| {
-                if ctxt.state.texture_units.len() <
-                    ctxt.capabilities.max_combined_texture_image_units as usize
-                {
-                    Some(ctxt.state.texture_units.len() as u16)
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| {
-                texture_bind_points.get_unused().expect("Not enough texture units available")
-            });
+    // finding an appropriate texture unit, skipping the ones that have been assigned to other
+    // samplers of the program
+    let texture_unit = match program.get_fixed_texture_unit(location) {
+        Some(unit) => unit,
+        None => {
+            let max = ctxt.capabilities.max_combined_texture_image_units as usize;
+
+            let unit = ctxt.state.texture_units
+                .iter().enumerate()
+                .find(|&(unit, content)| {
+                    content.texture == texture.get_texture_id() &&
+                    !program.is_texture_unit_reserved(unit as u16) &&
+                    (content.sampler == sampler || !texture_bind_points.is_used(unit as u16))
+                })
+                .map(|(unit, _)| unit as u16)
+                .or_else(|| {
+                    let unit = ctxt.state.texture_units.len();
+                    if unit < max && !texture_bind_points.is_used(unit as u16) {
+                        Some(unit as u16)
+                    } else {
+                        None
+                    }
+                })
+                .or_else(|| {
+                    // all the units contain a texture, so the one that has been used the least
+                    // recently is replaced
+                    ctxt.state.texture_units
+                        .iter().enumerate().take(max)
+                        .filter(|&(unit, _)| !texture_bind_points.is_used(unit as u16))
+                        .min_by_key(|&(_, content)| content.latest_use)
+                        .map(|(unit, _)| unit as u16)
+                });
+
+            match unit {
+                Some(unit) => unit,
+                None => {
+                    return Err(DrawError::NotEnoughTextureUnits {
+                        name: name.to_owned(),
+                        max: max,
+                    });
+                },
+            }
+        },
+    };
     assert!((texture_unit as gl::types::GLint) <
             ctxt.capabilities.max_combined_texture_image_units);
     texture_bind_points.set_used(texture_unit);
@@ -630,10 +1084,9 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
         }
     }
 
-    // TODO: do better
-    if ctxt.state.texture_units[texture_unit as usize].texture != texture.get_texture_id() ||
-       ctxt.state.texture_units[texture_unit as usize].sampler != sampler
-    {
+    ctxt.state.texture_units[texture_unit as usize].latest_use = ctxt.state.next_draw_call_id;
+
+    if ctxt.state.texture_units[texture_unit as usize].texture != texture.get_texture_id() {
         // TODO: what if it's not supported?
         if ctxt.state.active_texture != texture_unit as gl::types::GLenum {
             unsafe { ctxt.gl.ActiveTexture(texture_unit as gl::types::GLenum + gl::TEXTURE0) };
@@ -641,15 +1094,87 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
         }
 
         texture.bind_to_current(ctxt);
+    }
+
+    // `glBindSampler` takes the texture unit as parameter, so there's no need to change the
+    // active texture unit if only the sampler differs
+    if ctxt.state.texture_units[texture_unit as usize].sampler != sampler {
+        assert!(ctxt.version >= &Version(Api::Gl, 3, 3) ||
+                ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                ctxt.extensions.gl_arb_sampler_objects);
+
+        unsafe { ctxt.gl.BindSampler(texture_unit as gl::types::GLenum, sampler); }
+        ctxt.state.texture_units[texture_unit as usize].sampler = sampler;
+    }
+
+    Ok(())
+}
+
+fn bind_image_uniform<P>(ctxt: &mut context::CommandContext,
+                         texture: &texture::TextureAny, behavior: &ImageUnitBehavior,
+                         location: gl::types::GLint, program: &P,
+                         image_bind_points: &mut Bitsfield)
+                         -> Result<(), DrawError> where P: ProgramExt
+{
+    if ctxt.capabilities.max_image_units == 0 {
+        return Err(DrawError::ImageUnitsNotSupported);
+    }
+
+    let image_unit = image_bind_points.get_unused().expect("Not enough image units available");
+    assert!((image_unit as gl::types::GLint) < ctxt.capabilities.max_image_units);
+    image_bind_points.set_used(image_unit);
+
+    // updating the program to use the right unit
+    program.set_uniform(ctxt, location,
+                        &RawUniformValue::SignedInt(image_unit as gl::types::GLint));
 
-        if ctxt.state.texture_units[texture_unit as usize].sampler != sampler {
-            assert!(ctxt.version >= &Version(Api::Gl, 3, 3) ||
-                    ctxt.version >= &Version(Api::GlEs, 3, 0) ||
-                    ctxt.extensions.gl_arb_sampler_objects);
+    // the texture may have been written through an image unit by a previous command
+    if ctxt.state.latest_image_write >= ctxt.state.latest_memory_barrier_shader_image_access {
+        unsafe { ctxt.gl.MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT); }
+        ctxt.state.latest_memory_barrier_shader_image_access = ctxt.state.next_draw_call_id;
+    }
+
+    let layered = behavior.layer.is_none() && match texture.get_texture_type() {
+        Dimensions::Texture1dArray { .. } | Dimensions::Texture2dArray { .. } |
+        Dimensions::Texture2dMultisampleArray { .. } | Dimensions::Texture3d { .. } |
+        Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } => true,
+        _ => false,
+    };
 
-            unsafe { ctxt.gl.BindSampler(texture_unit as gl::types::GLenum, sampler); }
-            ctxt.state.texture_units[texture_unit as usize].sampler = sampler;
+    let new_state = ImageUnitState {
+        texture: texture.get_texture_id(),
+        level: behavior.level as gl::types::GLint,
+        layered: if layered { gl::TRUE } else { gl::FALSE },
+        layer: behavior.layer.unwrap_or(0) as gl::types::GLint,
+        access: behavior.access.to_glenum(),
+        format: behavior.format.to_glenum(),
+    };
+
+    // updating the state of the image unit ; the units that are not in the list are unknown
+    if ctxt.state.image_units.get(image_unit as usize) != Some(&new_state) {
+        unsafe {
+            ctxt.gl.BindImageTexture(image_unit as gl::types::GLuint, new_state.texture,
+                                     new_state.level, new_state.layered, new_state.layer,
+                                     new_state.access, new_state.format);
         }
+
+        while ctxt.state.image_units.len() <= image_unit as usize {
+            // an entry that can't match any binding, so that the unit is bound when used
+            ctxt.state.image_units.push(ImageUnitState {
+                texture: 0,
+                level: -1,
+                layered: gl::FALSE,
+                layer: 0,
+                access: 0,
+                format: 0,
+            });
+        }
+
+        ctxt.state.image_units[image_unit as usize] = new_state;
+    }
+
+    if behavior.access.is_write() {
+        ctxt.state.latest_image_write = ctxt.state.next_draw_call_id;
     }
 
     Ok(())
@@ -1,6 +1,7 @@
 use buffer::{Content, Buffer, BufferAny, BufferType, BufferMode, BufferCreationError};
 use buffer::{BufferSlice, BufferMutSlice};
 use uniforms::{AsUniformValue, UniformBlock, UniformValue, LayoutMismatchError};
+use uniforms::matches_block;
 use program;
 
 use gl;
@@ -238,7 +239,7 @@ impl<'a, T: ?Sized> AsUniformValue for &'a UniformBuffer<T> where T: UniformBloc
                         -> Result<(), LayoutMismatchError> where T: UniformBlock + Content
         {
             // TODO: more checks?
-            T::matches(&block.layout, 0)
+            matches_block::<T>(block)
         }
 
         UniformValue::Block(self.buffer.as_slice_any(), f::<T>)
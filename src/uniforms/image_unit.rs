@@ -0,0 +1,286 @@
+use texture::{Dimensions, TextureAny};
+use uniforms::{AsUniformValue, ShaderAccess, UniformType, UniformValue};
+
+use ToGlEnum;
+use gl;
+
+/// Format used to interpret the texels of a texture bound to an image unit.
+///
+/// Corresponds to the format qualifier of the image in GLSL, for example `layout(rgba8)`. It
+/// must be compatible with the internal format of the texture.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ImageUnitFormat {
+    RGBA32F,
+    RGBA16F,
+    RG32F,
+    RG16F,
+    R11FG11FB10F,
+    R32F,
+    R16F,
+    RGBA32UI,
+    RGBA16UI,
+    RGB10A2UI,
+    RGBA8UI,
+    RG32UI,
+    RG16UI,
+    RG8UI,
+    R32UI,
+    R16UI,
+    R8UI,
+    RGBA32I,
+    RGBA16I,
+    RGBA8I,
+    RG32I,
+    RG16I,
+    RG8I,
+    R32I,
+    R16I,
+    R8I,
+    RGBA16,
+    RGB10A2,
+    RGBA8,
+    RG16,
+    RG8,
+    R16,
+    R8,
+    RGBA16snorm,
+    RGBA8snorm,
+    RG16snorm,
+    RG8snorm,
+    R16snorm,
+    R8snorm,
+}
+
+impl ImageUnitFormat {
+    /// Returns true if the texels are signed integers, which must be accessed with an `iimage`.
+    pub fn is_signed_integral(&self) -> bool {
+        match *self {
+            ImageUnitFormat::RGBA32I | ImageUnitFormat::RGBA16I | ImageUnitFormat::RGBA8I |
+            ImageUnitFormat::RG32I | ImageUnitFormat::RG16I | ImageUnitFormat::RG8I |
+            ImageUnitFormat::R32I | ImageUnitFormat::R16I | ImageUnitFormat::R8I => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the texels are unsigned integers, which must be accessed with a `uimage`.
+    pub fn is_unsigned_integral(&self) -> bool {
+        match *self {
+            ImageUnitFormat::RGBA32UI | ImageUnitFormat::RGBA16UI | ImageUnitFormat::RGB10A2UI |
+            ImageUnitFormat::RGBA8UI | ImageUnitFormat::RG32UI | ImageUnitFormat::RG16UI |
+            ImageUnitFormat::RG8UI | ImageUnitFormat::R32UI | ImageUnitFormat::R16UI |
+            ImageUnitFormat::R8UI => true,
+            _ => false,
+        }
+    }
+}
+
+impl ToGlEnum for ImageUnitFormat {
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageUnitFormat::RGBA32F => gl::RGBA32F,
+            ImageUnitFormat::RGBA16F => gl::RGBA16F,
+            ImageUnitFormat::RG32F => gl::RG32F,
+            ImageUnitFormat::RG16F => gl::RG16F,
+            ImageUnitFormat::R11FG11FB10F => gl::R11F_G11F_B10F,
+            ImageUnitFormat::R32F => gl::R32F,
+            ImageUnitFormat::R16F => gl::R16F,
+            ImageUnitFormat::RGBA32UI => gl::RGBA32UI,
+            ImageUnitFormat::RGBA16UI => gl::RGBA16UI,
+            ImageUnitFormat::RGB10A2UI => gl::RGB10_A2UI,
+            ImageUnitFormat::RGBA8UI => gl::RGBA8UI,
+            ImageUnitFormat::RG32UI => gl::RG32UI,
+            ImageUnitFormat::RG16UI => gl::RG16UI,
+            ImageUnitFormat::RG8UI => gl::RG8UI,
+            ImageUnitFormat::R32UI => gl::R32UI,
+            ImageUnitFormat::R16UI => gl::R16UI,
+            ImageUnitFormat::R8UI => gl::R8UI,
+            ImageUnitFormat::RGBA32I => gl::RGBA32I,
+            ImageUnitFormat::RGBA16I => gl::RGBA16I,
+            ImageUnitFormat::RGBA8I => gl::RGBA8I,
+            ImageUnitFormat::RG32I => gl::RG32I,
+            ImageUnitFormat::RG16I => gl::RG16I,
+            ImageUnitFormat::RG8I => gl::RG8I,
+            ImageUnitFormat::R32I => gl::R32I,
+            ImageUnitFormat::R16I => gl::R16I,
+            ImageUnitFormat::R8I => gl::R8I,
+            ImageUnitFormat::RGBA16 => gl::RGBA16,
+            ImageUnitFormat::RGB10A2 => gl::RGB10_A2,
+            ImageUnitFormat::RGBA8 => gl::RGBA8,
+            ImageUnitFormat::RG16 => gl::RG16,
+            ImageUnitFormat::RG8 => gl::RG8,
+            ImageUnitFormat::R16 => gl::R16,
+            ImageUnitFormat::R8 => gl::R8,
+            ImageUnitFormat::RGBA16snorm => gl::RGBA16_SNORM,
+            ImageUnitFormat::RGBA8snorm => gl::RGBA8_SNORM,
+            ImageUnitFormat::RG16snorm => gl::RG16_SNORM,
+            ImageUnitFormat::RG8snorm => gl::RG8_SNORM,
+            ImageUnitFormat::R16snorm => gl::R16_SNORM,
+            ImageUnitFormat::R8snorm => gl::R8_SNORM,
+        }
+    }
+}
+
+/// Describes how a texture is bound to an image unit.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ImageUnitBehavior {
+    /// The mipmap level to bind.
+    pub level: u32,
+
+    /// The layer to bind, or `None` to bind all the layers of an array, cubemap or 3D texture.
+    pub layer: Option<u32>,
+
+    /// How the shader accesses the texture.
+    pub access: ShaderAccess,
+
+    /// The format used to interpret the texels.
+    pub format: ImageUnitFormat,
+}
+
+/// A texture to bind to an image uniform (`image2D`, `uimage3D`, etc.).
+///
+/// Textures written by a shader through an image unit can then be sampled, bound to an image
+/// unit or used as a framebuffer attachment by the next draw commands, and glium calls
+/// `glMemoryBarrier` when needed.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{ImageUnit, ImageUnitFormat, ShaderAccess};
+///
+/// let uniforms = uniform! {
+///     output: ImageUnit::new(&texture, ImageUnitFormat::RGBA8)
+///                 .access(ShaderAccess::WriteOnly),
+/// };
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ImageUnit<'t>(pub &'t TextureAny, pub ImageUnitBehavior);
+
+impl<'t> ImageUnit<'t> {
+    /// Builds a new `ImageUnit` that binds the main level of the texture, with all its layers
+    /// and read and write access.
+    #[inline]
+    pub fn new(texture: &'t TextureAny, format: ImageUnitFormat) -> ImageUnit<'t> {
+        ImageUnit(texture, ImageUnitBehavior {
+            level: 0,
+            layer: None,
+            access: ShaderAccess::ReadWrite,
+            format: format,
+        })
+    }
+
+    /// Changes the mipmap level to bind.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture doesn't have this level.
+    pub fn level(mut self, level: u32) -> ImageUnit<'t> {
+        assert!(level < self.0.get_mipmap_levels());
+        self.1.level = level;
+        self
+    }
+
+    /// Binds a single layer of an array, cubemap or 3D texture, or all of them with `None`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture doesn't have this layer.
+    pub fn layer(mut self, layer: Option<u32>) -> ImageUnit<'t> {
+        if let Some(layer) = layer {
+            assert!(layer < layers_count(self.0.get_texture_type()));
+        }
+
+        self.1.layer = layer;
+        self
+    }
+
+    /// Changes how the shader accesses the texture.
+    #[inline]
+    pub fn access(mut self, access: ShaderAccess) -> ImageUnit<'t> {
+        self.1.access = access;
+        self
+    }
+}
+
+impl<'t> AsUniformValue for ImageUnit<'t> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Image(self.0, self.1)
+    }
+}
+
+/// Returns the number of layers that can be bound separately.
+fn layers_count(ty: Dimensions) -> u32 {
+    match ty {
+        Dimensions::Texture1dArray { array_size, .. } |
+        Dimensions::Texture2dArray { array_size, .. } |
+        Dimensions::Texture2dMultisampleArray { array_size, .. } => array_size,
+        Dimensions::Texture3d { depth, .. } => depth,
+        Dimensions::Cubemap { .. } => 6,
+        Dimensions::CubemapArray { array_size, .. } => array_size * 6,
+        _ => 1,
+    }
+}
+
+/// Returns true if a texture bound with `behavior` can be used with an image uniform of type
+/// `ty`.
+pub fn is_usable_with(texture: &TextureAny, behavior: &ImageUnitBehavior, ty: UniformType)
+                      -> bool
+{
+    let layered = behavior.layer.is_none();
+
+    // the type of image for floating-point formats, a single layer being a 2D image
+    let float_ty = match texture.get_texture_type() {
+        Dimensions::Texture1d { .. } => UniformType::Image1d,
+        Dimensions::Texture1dArray { .. } if layered => UniformType::Image1dArray,
+        Dimensions::Texture1dArray { .. } => UniformType::Image1d,
+        Dimensions::Texture2d { .. } => UniformType::Image2d,
+        Dimensions::Texture2dArray { .. } if layered => UniformType::Image2dArray,
+        Dimensions::Texture2dMultisample { .. } => UniformType::Image2dMultisample,
+        Dimensions::Texture2dMultisampleArray { .. } if layered => {
+            UniformType::Image2dMultisampleArray
+        },
+        Dimensions::Texture2dMultisampleArray { .. } => UniformType::Image2dMultisample,
+        Dimensions::Texture3d { .. } if layered => UniformType::Image3d,
+        Dimensions::Cubemap { .. } if layered => UniformType::ImageCube,
+        // there is no `imageCubeArray` uniform type
+        Dimensions::CubemapArray { .. } if layered => return false,
+        Dimensions::Texture2dArray { .. } | Dimensions::Texture3d { .. } |
+        Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } => UniformType::Image2d,
+    };
+
+    let expected = if behavior.format.is_signed_integral() {
+        match float_ty {
+            UniformType::Image1d => UniformType::IImage1d,
+            UniformType::Image1dArray => UniformType::IImage1dArray,
+            UniformType::Image2d => UniformType::IImage2d,
+            UniformType::Image2dArray => UniformType::IImage2dArray,
+            UniformType::Image2dMultisample => UniformType::IImage2dMultisample,
+            UniformType::Image2dMultisampleArray => UniformType::IImage2dMultisampleArray,
+            UniformType::Image3d => UniformType::IImage3d,
+            UniformType::ImageCube => UniformType::IImageCube,
+            _ => unreachable!(),
+        }
+    } else if behavior.format.is_unsigned_integral() {
+        match float_ty {
+            UniformType::Image1d => UniformType::UImage1d,
+            UniformType::Image1dArray => UniformType::UImage1dArray,
+            UniformType::Image2d => UniformType::UImage2d,
+            UniformType::Image2dArray => UniformType::UImage2dArray,
+            UniformType::Image2dMultisample => UniformType::UImage2dMultisample,
+            UniformType::Image2dMultisampleArray => UniformType::UImage2dMultisampleArray,
+            UniformType::Image3d => UniformType::UImage3d,
+            UniformType::ImageCube => UniformType::UImageCube,
+            _ => unreachable!(),
+        }
+    } else {
+        float_ty
+    };
+
+    ty == expected
+}
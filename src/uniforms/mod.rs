@@ -23,7 +23,12 @@ let uniforms = uniform! {
 # }
 ```
 
-In both situations, each field must implement the `UniformValue` trait.
+In both situations, each field must implement the `UniformValue` trait. This trait is also
+implemented on the vectors and matrices of `cgmath`, `glam`, `mint` and `nalgebra` when the
+feature with the same name is enabled.
+
+If the names of the uniforms are only known at runtime, you can instead add the values one by
+one to a `DynamicUniforms`.
 
 ## Samplers
 
@@ -43,6 +48,57 @@ let uniforms = uniform! {
 # }
 ```
 
+## Arrays and structs
+
+A slice or a `Vec` of numbers, vectors or matrices can be bound to a uniform array. All the
+elements are then set at once, and the elements that don't exist in the program are ignored.
+
+A struct on which you call the `implement_uniform_struct!` macro can be bound to a uniform whose
+type is a GLSL struct, and a slice or a `Vec` of them to an array of structs.
+
+```no_run
+#[macro_use]
+extern crate glium;
+# fn main() {
+#[derive(Copy, Clone)]
+struct Light {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+
+implement_uniform_struct!(Light, position, color);
+
+let weights = [0.25f32, 0.5, 0.25];
+let lights = vec![
+    Light { position: [0.0, 5.0, 0.0], color: [1.0, 1.0, 1.0] },
+    Light { position: [3.0, 1.0, 0.0], color: [1.0, 0.5, 0.0] },
+];
+
+// binds `weights[0]` to `weights[2]`, and `lights[0].position` to `lights[1].color`
+let uniforms = uniform! {
+    weights: &weights[..],
+    lights: &lights[..],
+};
+# }
+```
+
+Similarly, a slice or a `Vec` of textures or of `Sampler`s can be bound to an array of samplers,
+for example `uniform sampler2D textures[8];`. Each texture is then bound to its own texture unit.
+
+```no_run
+#[macro_use]
+extern crate glium;
+# fn main() {
+# let grass: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+# let stone: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+let textures = [&grass, &stone];
+
+let uniforms = uniform! {
+    textures: &textures[..],
+};
+# }
+```
+
 ## Blocks
 
 In GLSL, you can choose to use a uniform *block*. When you use a block, you first need to
@@ -88,6 +144,47 @@ let uniforms = uniform! {
 # }
 ```
 
+The members of a struct stored in a buffer must have the same offsets as in the block. For
+blocks declared with `layout(std140)`, the `implement_std140_block!` macro computes these
+offsets for you, and a `Std140<T>` can then be stored in the `UniformBuffer`. Shader storage
+blocks declared with `layout(std430)` work the same way with `implement_std430_block!` and
+`Std430<T>`.
+
+For small values that change between each draw command, a `PushConstants` chooses between
+setting the members of a struct uniform and writing in a different range of a uniform buffer
+each time, depending on what is the fastest with the current backend.
+
+## Shader storage, atomic counters and images
+
+A buffer is bound to a shader storage block in the same way as to a uniform block. Wrap it in a
+`ShaderStorage` to indicate that the shader only reads it, or to bind a slice of it.
+
+A buffer can also be bound to an atomic counter, by using the name of the counter. The binding
+point of the counter is the one given by `layout(binding = ...)` in the shader, and the counter
+is at its `offset` from the start of the buffer or of the slice. The counters that share the
+same binding point must be given the same buffer.
+
+Textures are bound to image uniforms (`image2D`, `uimage3D`, etc.) with an `ImageUnit`, which
+also indicates the format of the image and how the shader accesses it.
+
+```no_run
+#[macro_use]
+extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+# let particles: glium::buffer::Buffer<[[f32; 4]]> = unsafe { std::mem::uninitialized() };
+# let counter: glium::buffer::Buffer<u32> = unsafe { std::mem::uninitialized() };
+use glium::uniforms::{ImageUnit, ImageUnitFormat, ShaderAccess, ShaderStorage};
+
+let uniforms = uniform! {
+    Particles: ShaderStorage::new(&particles, ShaderAccess::ReadOnly),
+    visible_count: &counter,
+    output: ImageUnit::new(&texture, ImageUnitFormat::RGBA8).access(ShaderAccess::WriteOnly),
+};
+# }
+```
+
 ## Subroutines
 OpenGL allows the use of subroutines, which are like function pointers. Subroutines can be used
 to change the functionality of a shader program at runtime. This method is usually a lot faster
@@ -141,13 +238,24 @@ let program = glium::Program::from_source(&display,
 ```
 */
 pub use self::buffer::UniformBuffer;
+pub use self::image_unit::{ImageUnit, ImageUnitBehavior, ImageUnitFormat};
+pub use self::push_constants::{PushConstants, PushConstantsPath};
 pub use self::sampler::{SamplerWrapFunction, MagnifySamplerFilter, MinifySamplerFilter};
 pub use self::sampler::{Sampler, SamplerBehavior};
-pub use self::uniforms::{EmptyUniforms, UniformsStorage};
-pub use self::value::{UniformValue, UniformType};
-
+pub use self::std140::{AsStd140, BlockMember, Std140, Std140Field};
+pub use self::std430::{AsStd430, Std430, Std430ArrayElement, Std430Field};
+pub use self::storage::{ShaderAccess, ShaderStorage};
+#[doc(hidden)]
+pub use self::std140::align_block_offset;
+#[doc(hidden)]
+pub use self::std430::max_block_alignment;
+pub use self::uniforms::{ChainedUniforms, DynamicUniforms, EmptyUniforms, UniformsStorage};
+pub use self::value::{Transposed, UniformArray, UniformValue, UniformType};
+
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
+use std::mem;
 
 use buffer::Content as BufferContent;
 use buffer::Buffer;
@@ -156,7 +264,12 @@ use program::BlockLayout;
 
 mod bind;
 mod buffer;
+mod image_unit;
+mod push_constants;
 mod sampler;
+mod std140;
+mod std430;
+mod storage;
 mod uniforms;
 mod value;
 
@@ -166,10 +279,80 @@ mod value;
 pub trait Uniforms {
     /// Calls the parameter once with the name and value of each uniform.
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, F);
+
+    /// Builds an object that contains the uniforms of both `self` and `other`.
+    ///
+    /// The uniforms of `other` are bound after the ones of `self`, so if both contain a uniform
+    /// with the same name, the value of `other` is used.
+    ///
+    /// Since `Uniforms` is implemented on references, this lets you keep the uniforms that are
+    /// the same for a whole frame in one object and merge them with the uniforms of each draw
+    /// call, for example `(&globals).chain(uniform! { model: model })`.
+    #[inline]
+    fn chain<U>(self, other: U) -> ChainedUniforms<Self, U> where Self: Sized, U: Uniforms {
+        ChainedUniforms::new(self, other)
+    }
+}
+
+impl<'r, U: ?Sized> Uniforms for &'r U where U: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, output: F) {
+        (**self).visit_values(output)
+    }
+}
+
+/// Version of `Uniforms` that can be used as a trait object, for example with
+/// `DrawDyn::draw_dyn`.
+///
+/// This trait is implemented for all the types that implement `Uniforms`, and `&AnyUniforms`
+/// implements `Uniforms`.
+pub trait AnyUniforms {
+    /// Calls the parameter once with the name and value of each uniform.
+    fn visit_values_dyn<'a>(&'a self, output: &mut FnMut(&str, UniformValue<'a>));
+}
+
+impl<U> AnyUniforms for U where U: Uniforms {
+    #[inline]
+    fn visit_values_dyn<'a>(&'a self, output: &mut FnMut(&str, UniformValue<'a>)) {
+        self.visit_values(|name, value| output(name, value))
+    }
+}
+
+impl<'u> Uniforms for AnyUniforms + 'u {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        self.visit_values_dyn(&mut output)
+    }
+}
+
+/// What to do when a uniform or a uniform block of the program has no value.
+///
+/// The policy can be chosen for each program with `Program::set_missing_uniforms`, and
+/// overridden for a single draw command with the `missing_uniforms` member of the draw
+/// parameters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MissingUniforms {
+    /// The uniform keeps its previous value. This is the default.
+    Ignore,
+
+    /// A warning is printed on the standard error the first time a uniform of the program has
+    /// no value, and the uniform keeps its previous value.
+    WarnOnce,
+
+    /// The draw command returns `DrawError::UniformMissing`.
+    Error,
+}
+
+impl Default for MissingUniforms {
+    #[inline]
+    fn default() -> MissingUniforms {
+        MissingUniforms::Ignore
+    }
 }
 
 /// Error about a block layout mismatch.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum LayoutMismatchError {
     /// There is a mismatch in the type of one element.
     TypeMismatch {
@@ -210,6 +393,14 @@ pub enum LayoutMismatchError {
         /// Name of the field.
         name: String,
     },
+
+    /// The elements of an array are not spaced by the same number of bytes.
+    StrideMismatch {
+        /// Stride of the array in the shader.
+        expected: usize,
+        /// Stride of the array in the input.
+        obtained: usize,
+    },
 }
 
 impl Error for LayoutMismatchError {
@@ -226,10 +417,12 @@ impl Error for LayoutMismatchError {
                 "There is a mismatch in a submember of this layout",
             MissingField { .. } =>
                 "A field is missing in either the expected of the input data layout",
+            StrideMismatch { .. } =>
+                "The elements of an array are not spaced by the same number of bytes",
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::LayoutMismatchError::*;
         match *self {
             MemberMismatch{ ref err, .. } => Some(err.as_ref()),
@@ -282,10 +475,80 @@ impl fmt::Display for LayoutMismatchError {
                     self.description(),
                     name,
                 ),
+            StrideMismatch { ref expected, ref obtained } =>
+                write!(
+                    fmt,
+                    "{}, got: {}, expected: {}",
+                    self.description(),
+                    obtained,
+                    expected,
+                ),
         }
     }
 }
 
+/// A struct whose fields can be bound to the members of a GLSL struct.
+///
+/// You should use the `implement_uniform_struct!` macro instead of implementing this trait
+/// manually.
+pub trait UniformStruct {
+    /// Calls `output` with the name and the value of each field. The names are appended to the
+    /// name of the uniform, and must therefore start with `.` or with `[`.
+    fn visit_fields<'a>(&'a self, output: &mut FnMut(&str, UniformValue<'a>));
+}
+
+impl<'s, T> UniformStruct for &'s [T] where T: UniformStruct {
+    #[inline]
+    fn visit_fields<'a>(&'a self, output: &mut FnMut(&str, UniformValue<'a>)) {
+        visit_elements(self, output)
+    }
+}
+
+impl<T> UniformStruct for Vec<T> where T: UniformStruct {
+    #[inline]
+    fn visit_fields<'a>(&'a self, output: &mut FnMut(&str, UniformValue<'a>)) {
+        visit_elements(self, output)
+    }
+}
+
+/// A list of textures that can be bound to an array of samplers.
+///
+/// This trait is implemented on slices and `Vec`s of textures and of `Sampler`s, which can
+/// directly be used as the value of a uniform.
+pub trait UniformSamplerArray {
+    /// Calls `output` with the value of each texture, in order.
+    fn visit_textures<'a>(&'a self, output: &mut FnMut(UniformValue<'a>));
+}
+
+impl<'s, T> UniformSamplerArray for &'s [T] where T: AsUniformValue {
+    #[inline]
+    fn visit_textures<'a>(&'a self, output: &mut FnMut(UniformValue<'a>)) {
+        for texture in self.iter() {
+            output(texture.as_uniform_value());
+        }
+    }
+}
+
+impl<T> UniformSamplerArray for Vec<T> where T: AsUniformValue {
+    #[inline]
+    fn visit_textures<'a>(&'a self, output: &mut FnMut(UniformValue<'a>)) {
+        for texture in self.iter() {
+            output(texture.as_uniform_value());
+        }
+    }
+}
+
+/// Visits the fields of each element of an array of structs, with the index of the element
+/// in front of their names.
+fn visit_elements<'a, T>(elements: &'a [T], output: &mut FnMut(&str, UniformValue<'a>))
+                         where T: UniformStruct
+{
+    for (index, element) in elements.iter().enumerate() {
+        let prefix = format!("[{}]", index);
+        element.visit_fields(&mut |name, value| output(&format!("{}{}", prefix, name), value));
+    }
+}
+
 /// Value that can be used as the value of a uniform.
 ///
 /// This includes buffers and textures for example.
@@ -303,7 +566,7 @@ impl<'a, T: ?Sized> AsUniformValue for &'a Buffer<T> where T: UniformBlock + Buf
                         -> Result<(), LayoutMismatchError> where T: UniformBlock + BufferContent
         {
             // TODO: more checks?
-            T::matches(&block.layout, 0)
+            matches_block::<T>(block)
         }
 
         UniformValue::Block(self.as_slice_any(), f::<T>)
@@ -318,6 +581,18 @@ pub trait UniformBlock {        // TODO: `: Copy`, but unsized structs don't imp
 
     /// Builds the `BlockLayout` corresponding to the current object.
     fn build_layout(base_offset: usize) -> BlockLayout;
+
+    /// Checks whether the arrays of `Self` have the same strides as the arrays of the block.
+    ///
+    /// This is called after `matches` succeeded with the same layout. The second parameter is
+    /// the path of the layout in the block, and the third one is the `array_strides` of the
+    /// block. The default implementation doesn't check anything.
+    #[inline]
+    fn matches_strides(_: &BlockLayout, _: &str, _: &HashMap<String, usize>)
+                       -> Result<(), LayoutMismatchError>
+    {
+        Ok(())
+    }
 }
 
 impl<T> UniformBlock for [T] where T: UniformBlock {
@@ -362,6 +637,65 @@ impl<T> UniformBlock for [T] where T: UniformBlock {
             content: Box::new(<T as UniformBlock>::build_layout(base_offset)),
         }
     }
+
+    fn matches_strides(layout: &BlockLayout, path: &str, strides: &HashMap<String, usize>)
+                       -> Result<(), LayoutMismatchError>
+    {
+        if let &BlockLayout::Struct { ref members } = layout {
+            if members.len() == 1 {
+                let path = block_member_path(path, &members[0].0);
+                return Self::matches_strides(&members[0].1, &path, strides);
+            }
+        }
+
+        let content = match *layout {
+            BlockLayout::DynamicSizedArray { ref content } => content,
+            BlockLayout::Array { ref content, .. } => content,
+            _ => return Ok(()),
+        };
+
+        try!(check_array_stride::<T>(strides.get(path).cloned()));
+        <T as UniformBlock>::matches_strides(content, path, strides)
+            .map_err(|err| {
+                LayoutMismatchError::MemberMismatch {
+                    member: "<dynamic array content>".to_owned(),
+                    err: Box::new(err),
+                }
+            })
+    }
+}
+
+/// Checks that the elements of an array in the shader are spaced like the elements of an array
+/// of `T`.
+fn check_array_stride<T>(stride: Option<usize>) -> Result<(), LayoutMismatchError> {
+    match stride {
+        Some(stride) if stride != mem::size_of::<T>() => {
+            Err(LayoutMismatchError::StrideMismatch {
+                expected: stride,
+                obtained: mem::size_of::<T>(),
+            })
+        },
+        _ => Ok(())
+    }
+}
+
+/// Returns the path of the member `name` of the struct at `path`, in the format of the keys of
+/// `program::UniformBlock::array_strides`.
+#[doc(hidden)]
+pub fn block_member_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Checks that a block has the layout of `T`, including the strides of its arrays.
+fn matches_block<T: ?Sized>(block: &program::UniformBlock) -> Result<(), LayoutMismatchError>
+    where T: UniformBlock
+{
+    try!(T::matches(&block.layout, 0));
+    T::matches_strides(&block.layout, "", &block.array_strides)
 }
 
 macro_rules! impl_uniform_block_array {
@@ -408,6 +742,31 @@ macro_rules! impl_uniform_block_array {
                     length: $len,
                 }
             }
+
+            fn matches_strides(layout: &program::BlockLayout, path: &str,
+                               strides: &HashMap<String, usize>)
+                               -> Result<(), LayoutMismatchError>
+            {
+                if let &BlockLayout::Struct { ref members } = layout {
+                    if members.len() == 1 {
+                        let path = block_member_path(path, &members[0].0);
+                        return Self::matches_strides(&members[0].1, &path, strides);
+                    }
+                }
+
+                if let &BlockLayout::Array { ref content, .. } = layout {
+                    try!(check_array_stride::<T>(strides.get(path).cloned()));
+
+                    if let Err(err) = T::matches_strides(content, path, strides) {
+                        return Err(LayoutMismatchError::MemberMismatch {
+                            member: "<array content>".to_owned(),
+                            err: Box::new(err),
+                        });
+                    }
+                }
+
+                Ok(())
+            }
         }
     );
 }
@@ -0,0 +1,216 @@
+use std::mem;
+use std::slice;
+
+use backend::Facade;
+use buffer::{Buffer, BufferCreationError, BufferMode, BufferType};
+use uniforms::{AsUniformValue, LayoutMismatchError, UniformBlock, UniformStruct, UniformValue};
+use uniforms::matches_block;
+use program;
+use version::{Api, Version};
+use CapabilitiesSource;
+
+/// Number of values that are written in the uniform buffer before the first range is reused.
+const RING_LENGTH: usize = 64;
+
+/// Size in bytes up to which `glUniform` is faster than writing in a uniform buffer, if the
+/// buffer can be persistent-mapped.
+const MAX_UNIFORMS_SIZE_PERSISTENT: usize = 64;
+
+/// Size in bytes up to which `glUniform` is faster than writing in a uniform buffer, if each
+/// write goes through `glBufferSubData`.
+const MAX_UNIFORMS_SIZE: usize = 256;
+
+/// The way the content of a `PushConstants` is sent to the program.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PushConstantsPath {
+    /// The members are set one by one with `glUniform`.
+    ///
+    /// The program must declare a uniform whose type is a struct, for example
+    /// `uniform Constants constants;`.
+    Uniforms,
+
+    /// The value is written in a range of a uniform buffer that is different at each update,
+    /// so that updating the value never waits for the previous draw commands to finish.
+    ///
+    /// The program must declare a uniform block, for example `uniform constants { ... };`.
+    UniformBuffer,
+}
+
+/// Small amount of data that changes between draw commands.
+///
+/// `PushConstants` chooses the fastest way to send the data depending on the backend and on the
+/// size of `T`: either with `glUniform`, or by writing it in a range of a uniform buffer. Use
+/// `path()` to know which one was chosen, and declare the uniform accordingly in your shader.
+///
+/// `T` must implement both `UniformStruct` and `UniformBlock`, which you can do with the
+/// `implement_uniform_struct!` and `implement_uniform_block!` macros.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{PushConstants, PushConstantsPath};
+///
+/// #[derive(Copy, Clone)]
+/// struct Constants {
+///     color: [f32; 4],
+/// }
+///
+/// implement_uniform_struct!(Constants, color);
+/// implement_uniform_block!(Constants, color);
+///
+/// let mut constants = PushConstants::new(&display, Constants { color: [1.0; 4] }).unwrap();
+///
+/// let declaration = match constants.path() {
+///     PushConstantsPath::Uniforms => "struct Constants { vec4 color; };
+///                                     uniform Constants constants;",
+///     PushConstantsPath::UniformBuffer => "uniform constants { vec4 color; };",
+/// };
+///
+/// // before each draw command
+/// constants.set(Constants { color: [0.0, 1.0, 0.0, 1.0] });
+/// let uniforms = uniform! { constants: &constants };
+/// # }
+/// ```
+pub struct PushConstants<T> where T: Copy {
+    value: T,
+    ring: Option<Ring>,
+}
+
+/// Uniform buffer that contains `RING_LENGTH` ranges, one of which is used at a time.
+struct Ring {
+    buffer: Buffer<[u8]>,
+    stride: usize,
+    current: usize,
+}
+
+impl<T> PushConstants<T> where T: Copy {
+    /// Builds a new `PushConstants`, choosing the fastest path for the backend.
+    pub fn new<F: ?Sized>(facade: &F, value: T) -> Result<PushConstants<T>, BufferCreationError>
+                          where F: Facade
+    {
+        let path = preferred_path::<T, _>(facade.get_context());
+        PushConstants::with_path(facade, value, path)
+    }
+
+    /// Builds a new `PushConstants` that uses the given path.
+    ///
+    /// Returns an error if the path is `UniformBuffer` but uniform buffers are not supported.
+    pub fn with_path<F: ?Sized>(facade: &F, value: T, path: PushConstantsPath)
+                                -> Result<PushConstants<T>, BufferCreationError>
+                                where F: Facade
+    {
+        let ring = match path {
+            PushConstantsPath::Uniforms => None,
+            PushConstantsPath::UniformBuffer => {
+                let alignment = facade.get_context().get_capabilities()
+                                      .uniform_buffer_offset_alignment as usize;
+                let alignment = if alignment == 0 { 1 } else { alignment };
+                let stride = (mem::size_of::<T>() + alignment - 1) / alignment * alignment;
+
+                let buffer = try!(Buffer::empty_array(facade, BufferType::UniformBuffer,
+                                                      stride * RING_LENGTH,
+                                                      BufferMode::Persistent));
+
+                let ring = Ring {
+                    buffer: buffer,
+                    stride: stride,
+                    current: 0,
+                };
+
+                ring.write(&value);
+                Some(ring)
+            },
+        };
+
+        Ok(PushConstants {
+            value: value,
+            ring: ring,
+        })
+    }
+
+    /// Returns the way the data is sent to the program.
+    #[inline]
+    pub fn path(&self) -> PushConstantsPath {
+        if self.ring.is_some() {
+            PushConstantsPath::UniformBuffer
+        } else {
+            PushConstantsPath::Uniforms
+        }
+    }
+
+    /// Returns the current value.
+    #[inline]
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Changes the value. The draw commands that have already been submitted are not affected.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+
+        if let Some(ref mut ring) = self.ring {
+            ring.current = (ring.current + 1) % RING_LENGTH;
+            ring.write(&value);
+        }
+    }
+}
+
+impl Ring {
+    /// Writes a value in the current range.
+    fn write<T>(&self, value: &T) where T: Copy {
+        let start = self.current * self.stride;
+        let data = unsafe {
+            slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>())
+        };
+
+        self.buffer.slice(start .. start + data.len()).unwrap().write(data);
+    }
+}
+
+impl<'a, T> AsUniformValue for &'a PushConstants<T>
+    where T: UniformBlock + UniformStruct + Copy
+{
+    fn as_uniform_value(&self) -> UniformValue {
+        #[inline]
+        fn f<T>(block: &program::UniformBlock) -> Result<(), LayoutMismatchError>
+                where T: UniformBlock
+        {
+            matches_block::<T>(block)
+        }
+
+        match self.ring {
+            Some(ref ring) => {
+                let start = ring.current * ring.stride;
+                let slice = ring.buffer.slice(start .. start + mem::size_of::<T>()).unwrap();
+                UniformValue::Block(slice.as_slice_any(), f::<T>)
+            },
+            None => UniformValue::Struct(&self.value),
+        }
+    }
+}
+
+/// Returns the path that is the fastest for values of type `T`.
+fn preferred_path<T, C: ?Sized>(ctxt: &C) -> PushConstantsPath where C: CapabilitiesSource {
+    if ctxt.get_capabilities().max_indexed_uniform_buffer == 0 {
+        return PushConstantsPath::Uniforms;
+    }
+
+    // without persistent mapping, writing in the buffer costs a `glBufferSubData` call, which is
+    // only worth it for larger values
+    let max_uniforms_size = if ctxt.get_version() >= &Version(Api::Gl, 4, 4) ||
+                               ctxt.get_extensions().gl_arb_buffer_storage
+    {
+        MAX_UNIFORMS_SIZE_PERSISTENT
+    } else {
+        MAX_UNIFORMS_SIZE
+    };
+
+    if mem::size_of::<T>() <= max_uniforms_size {
+        PushConstantsPath::Uniforms
+    } else {
+        PushConstantsPath::UniformBuffer
+    }
+}
@@ -0,0 +1,360 @@
+//! Structs laid out with the std140 rules of uniform blocks.
+//!
+//! A GLSL block with `layout(std140)` has a layout that is known in advance, but which differs
+//! from the layout of a Rust struct: for example a `vec3` is aligned to 16 bytes, and a struct
+//! always ends on a multiple of 16 bytes. The `implement_std140_block!` macro computes these
+//! offsets, and `Std140<T>` stores a value with them so that it can be uploaded in a
+//! `UniformBuffer`.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem;
+
+use program::BlockLayout;
+use uniforms::{block_member_path, LayoutMismatchError, UniformBlock};
+
+/// A type that can be a member of a block with the std140 layout, and whose size is the same
+/// in Rust and in GLSL.
+///
+/// Arrays, matrices with less than four rows and booleans are not included, because std140
+/// pads each of their elements or columns.
+pub unsafe trait Std140Field: UniformBlock + Copy {
+    /// Alignment of the type in the std140 layout, in bytes.
+    const ALIGNMENT: usize;
+
+    /// Size of the type in bytes. Must be equal to its size in Rust.
+    const SIZE: usize;
+}
+
+macro_rules! impl_std140_field {
+    ($ty:ty, $alignment:expr, $size:expr) => {
+        unsafe impl Std140Field for $ty {
+            const ALIGNMENT: usize = $alignment;
+            const SIZE: usize = $size;
+        }
+    };
+}
+
+impl_std140_field!(f32, 4, 4);
+impl_std140_field!([f32; 2], 8, 8);
+impl_std140_field!([f32; 3], 16, 12);
+impl_std140_field!([f32; 4], 16, 16);
+impl_std140_field!(i32, 4, 4);
+impl_std140_field!([i32; 2], 8, 8);
+impl_std140_field!([i32; 3], 16, 12);
+impl_std140_field!([i32; 4], 16, 16);
+impl_std140_field!(u32, 4, 4);
+impl_std140_field!([u32; 2], 8, 8);
+impl_std140_field!([u32; 3], 16, 12);
+impl_std140_field!([u32; 4], 16, 16);
+impl_std140_field!([[f32; 4]; 4], 16, 64);
+
+/// Describes a member of a struct that implements `AsStd140` or `AsStd430`.
+#[derive(Copy, Clone)]
+pub struct BlockMember {
+    /// Name of the member, which must be the same as in the GLSL block.
+    pub name: &'static str,
+
+    /// Offset of the member from the start of the struct, in bytes.
+    pub offset: usize,
+
+    /// The `UniformBlock::matches` function of the type of the member.
+    pub matches: fn(&BlockLayout, usize) -> Result<(), LayoutMismatchError>,
+
+    /// The `UniformBlock::build_layout` function of the type of the member.
+    pub build_layout: fn(usize) -> BlockLayout,
+
+    /// The `UniformBlock::matches_strides` function of the type of the member.
+    pub matches_strides: fn(&BlockLayout, &str, &HashMap<String, usize>)
+                            -> Result<(), LayoutMismatchError>,
+}
+
+/// A struct that can be converted to the std140 layout.
+///
+/// You should use the `implement_std140_block!` macro instead of implementing this trait
+/// manually.
+pub unsafe trait AsStd140: Copy {
+    /// The bytes of a value laid out with the std140 rules. Must be an array of `u8` whose
+    /// length is a multiple of 16.
+    type Storage: Copy;
+
+    /// Returns the members of the struct, with their std140 offsets.
+    fn members() -> Vec<BlockMember>;
+
+    /// Lays out the value with the std140 rules.
+    fn to_std140(&self) -> Self::Storage;
+
+    /// Reads a value laid out with the std140 rules.
+    fn from_std140(storage: &Self::Storage) -> Self;
+}
+
+/// A value of `T` laid out with the std140 rules, that can be stored in a `UniformBuffer` and
+/// bound to a block with `layout(std140)`.
+///
+/// ## Example
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{Std140, UniformBuffer};
+///
+/// #[derive(Copy, Clone)]
+/// struct Light {
+///     position: [f32; 3],
+///     intensity: f32,
+///     color: [f32; 3],
+/// }
+///
+/// implement_std140_block!(Light, position: [f32; 3], intensity: f32, color: [f32; 3]);
+///
+/// let light = Light { position: [0.0, 5.0, 0.0], intensity: 2.0, color: [1.0, 1.0, 1.0] };
+/// let buffer = UniformBuffer::new(&display, Std140::new(&light)).unwrap();
+/// # }
+/// ```
+#[repr(C)]
+pub struct Std140<T> where T: AsStd140 {
+    storage: T::Storage,
+    marker: PhantomData<T>,
+}
+
+impl<T> Std140<T> where T: AsStd140 {
+    /// Lays out a value with the std140 rules.
+    #[inline]
+    pub fn new(value: &T) -> Std140<T> {
+        Std140 {
+            storage: value.to_std140(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the value.
+    #[inline]
+    pub fn get(&self) -> T {
+        T::from_std140(&self.storage)
+    }
+}
+
+impl<T> Copy for Std140<T> where T: AsStd140 {}
+
+impl<T> Clone for Std140<T> where T: AsStd140 {
+    #[inline]
+    fn clone(&self) -> Std140<T> {
+        *self
+    }
+}
+
+impl<T> From<T> for Std140<T> where T: AsStd140 {
+    #[inline]
+    fn from(value: T) -> Std140<T> {
+        Std140::new(&value)
+    }
+}
+
+// a struct is aligned to the size of a `vec4`, and its size is rounded up to it
+unsafe impl<T> Std140Field for Std140<T> where T: AsStd140 {
+    const ALIGNMENT: usize = 16;
+    const SIZE: usize = mem::size_of::<T::Storage>();
+}
+
+impl<T> UniformBlock for Std140<T> where T: AsStd140 {
+    #[inline]
+    fn matches(layout: &BlockLayout, base_offset: usize) -> Result<(), LayoutMismatchError> {
+        matches_members(T::members(), layout, base_offset)
+    }
+
+    #[inline]
+    fn build_layout(base_offset: usize) -> BlockLayout {
+        build_members_layout(T::members(), base_offset)
+    }
+
+    #[inline]
+    fn matches_strides(layout: &BlockLayout, path: &str, strides: &HashMap<String, usize>)
+                       -> Result<(), LayoutMismatchError>
+    {
+        matches_members_strides(T::members(), layout, path, strides)
+    }
+}
+
+/// Checks that the members of a struct are at the same offsets as in `layout`.
+pub fn matches_members(own_members: Vec<BlockMember>, layout: &BlockLayout, base_offset: usize)
+                       -> Result<(), LayoutMismatchError>
+{
+    let members = match *layout {
+        BlockLayout::Struct { ref members } => members,
+        _ => return Err(LayoutMismatchError::LayoutMismatch {
+            expected: layout.clone(),
+            obtained: build_members_layout(own_members, base_offset),
+        }),
+    };
+
+    // checking that each member of the block exists in the struct
+    for &(ref name, _) in members {
+        if !own_members.iter().any(|m| m.name == name) {
+            return Err(LayoutMismatchError::MissingField {
+                name: name.clone(),
+            });
+        }
+    }
+
+    // checking that each member of the struct is correct in the block
+    for member in own_members {
+        let reflected = match members.iter().find(|&&(ref name, _)| name == member.name) {
+            Some(&(_, ref reflected)) => reflected,
+            None => return Err(LayoutMismatchError::MissingField {
+                name: member.name.to_owned(),
+            }),
+        };
+
+        if let Err(err) = (member.matches)(reflected, base_offset + member.offset) {
+            return Err(LayoutMismatchError::MemberMismatch {
+                member: member.name.to_owned(),
+                err: Box::new(err),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the arrays in the members of a struct have the same strides as in the block.
+///
+/// `layout` must already have been checked with `matches_members`.
+pub fn matches_members_strides(own_members: Vec<BlockMember>, layout: &BlockLayout, path: &str,
+                               strides: &HashMap<String, usize>)
+                               -> Result<(), LayoutMismatchError>
+{
+    let members = match *layout {
+        BlockLayout::Struct { ref members } => members,
+        _ => return Ok(()),
+    };
+
+    for member in own_members {
+        let reflected = match members.iter().find(|&&(ref name, _)| name == member.name) {
+            Some(&(_, ref reflected)) => reflected,
+            None => continue,
+        };
+
+        let member_path = block_member_path(path, member.name);
+        if let Err(err) = (member.matches_strides)(reflected, &member_path, strides) {
+            return Err(LayoutMismatchError::MemberMismatch {
+                member: member.name.to_owned(),
+                err: Box::new(err),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the layout of a struct from its members.
+pub fn build_members_layout(members: Vec<BlockMember>, base_offset: usize) -> BlockLayout {
+    BlockLayout::Struct {
+        members: members.into_iter().map(|member| {
+            (member.name.to_owned(), (member.build_layout)(base_offset + member.offset))
+        }).collect(),
+    }
+}
+
+/// Returns the offset of a member with the given alignment that follows a member ending at
+/// `offset`.
+#[doc(hidden)]
+#[inline]
+pub fn align_block_offset(offset: usize, alignment: usize) -> usize {
+    (offset + alignment - 1) / alignment * alignment
+}
+
+#[cfg(test)]
+mod tests {
+    use program::BlockLayout;
+    use uniforms::{UniformBlock, UniformType};
+    use super::Std140;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Light {
+        position: [f32; 3],
+        intensity: f32,
+        color: [f32; 3],
+        direction: [f32; 2],
+    }
+
+    implement_std140_block!(Light, position: [f32; 3], intensity: f32, color: [f32; 3],
+                            direction: [f32; 2]);
+
+    #[derive(Copy, Clone)]
+    struct Scene {
+        count: u32,
+        light: Std140<Light>,
+    }
+
+    implement_std140_block!(Scene, count: u32, light: Std140<Light>);
+
+    fn offset_of(layout: &BlockLayout, member: &str) -> usize {
+        match *layout {
+            BlockLayout::Struct { ref members } => {
+                first_offset(&members.iter().find(|&&(ref name, _)| name == member).unwrap().1)
+            },
+            _ => panic!(),
+        }
+    }
+
+    fn first_offset(layout: &BlockLayout) -> usize {
+        match *layout {
+            BlockLayout::BasicType { offset_in_buffer, .. } => offset_in_buffer,
+            BlockLayout::Struct { ref members } => first_offset(&members[0].1),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn offsets() {
+        let layout = <Std140<Light> as UniformBlock>::build_layout(0);
+        assert_eq!(offset_of(&layout, "position"), 0);
+        assert_eq!(offset_of(&layout, "intensity"), 12);
+        assert_eq!(offset_of(&layout, "color"), 16);
+        assert_eq!(offset_of(&layout, "direction"), 32);
+        assert_eq!(::std::mem::size_of::<Std140<Light>>(), 48);
+    }
+
+    #[test]
+    fn nested_struct() {
+        let layout = <Std140<Scene> as UniformBlock>::build_layout(0);
+        assert_eq!(offset_of(&layout, "count"), 0);
+        assert_eq!(offset_of(&layout, "light"), 16);
+        assert_eq!(::std::mem::size_of::<Std140<Scene>>(), 64);
+    }
+
+    #[test]
+    fn round_trip() {
+        let light = Light {
+            position: [1.0, 2.0, 3.0],
+            intensity: 4.0,
+            color: [5.0, 6.0, 7.0],
+            direction: [8.0, 9.0],
+        };
+
+        assert_eq!(Std140::new(&light).get(), light);
+    }
+
+    #[test]
+    fn matches_own_layout() {
+        let layout = <Std140<Light> as UniformBlock>::build_layout(0);
+        assert!(<Std140<Light> as UniformBlock>::matches(&layout, 0).is_ok());
+
+        let member = |name: &str, ty, offset| {
+            (name.to_owned(), BlockLayout::BasicType { ty: ty, offset_in_buffer: offset })
+        };
+
+        // `intensity` after a manually padded `position`
+        let shifted = BlockLayout::Struct {
+            members: vec![
+                member("position", UniformType::FloatVec3, 0),
+                member("intensity", UniformType::Float, 16),
+                member("color", UniformType::FloatVec3, 32),
+                member("direction", UniformType::FloatVec2, 48),
+            ],
+        };
+        assert!(<Std140<Light> as UniformBlock>::matches(&shifted, 0).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+//! Structs laid out with the std430 rules of shader storage blocks.
+//!
+//! The std430 layout is the same as the std140 layout, except that arrays and structs are not
+//! padded to the size of a `vec4`. For example an array of `float`s has a stride of 4 bytes
+//! instead of 16, and a struct of two `float`s is 8 bytes long. It can only be used for shader
+//! storage blocks.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem;
+
+use program::BlockLayout;
+use uniforms::{LayoutMismatchError, UniformBlock};
+use uniforms::std140::{self, BlockMember};
+
+/// A type that can be a member of a block with the std430 layout, and whose size is the same
+/// in Rust and in GLSL.
+///
+/// Matrices with less than four rows and booleans are not included, because std430 pads each of
+/// their columns.
+pub unsafe trait Std430Field: UniformBlock + Copy {
+    /// Alignment of the type in the std430 layout, in bytes.
+    const ALIGNMENT: usize;
+
+    /// Size of the type in bytes. Must be equal to its size in Rust.
+    const SIZE: usize;
+}
+
+/// A type whose size is a multiple of its std430 alignment, and that can therefore be the
+/// element of an array without any padding.
+///
+/// This excludes the three-components vectors, whose elements are 16 bytes apart in an array.
+pub unsafe trait Std430ArrayElement: Std430Field {}
+
+macro_rules! impl_std430_field {
+    ($ty:ty, $alignment:expr, $size:expr) => {
+        unsafe impl Std430Field for $ty {
+            const ALIGNMENT: usize = $alignment;
+            const SIZE: usize = $size;
+        }
+    };
+
+    ($ty:ty, $alignment:expr, $size:expr, array_element) => {
+        impl_std430_field!($ty, $alignment, $size);
+        unsafe impl Std430ArrayElement for $ty {}
+    };
+}
+
+impl_std430_field!(f32, 4, 4, array_element);
+impl_std430_field!([f32; 2], 8, 8, array_element);
+impl_std430_field!([f32; 3], 16, 12);
+impl_std430_field!([f32; 4], 16, 16, array_element);
+impl_std430_field!(i32, 4, 4, array_element);
+impl_std430_field!([i32; 2], 8, 8, array_element);
+impl_std430_field!([i32; 3], 16, 12);
+impl_std430_field!([i32; 4], 16, 16, array_element);
+impl_std430_field!(u32, 4, 4, array_element);
+impl_std430_field!([u32; 2], 8, 8, array_element);
+impl_std430_field!([u32; 3], 16, 12);
+impl_std430_field!([u32; 4], 16, 16, array_element);
+impl_std430_field!([[f32; 4]; 4], 16, 64, array_element);
+
+// the lengths are the ones for which `[T; N]` implements `UniformBlock`
+macro_rules! impl_std430_array {
+    ($len:expr) => {
+        unsafe impl<T> Std430Field for [T; $len] where T: Std430ArrayElement {
+            const ALIGNMENT: usize = T::ALIGNMENT;
+            const SIZE: usize = T::SIZE * $len;
+        }
+    };
+}
+
+impl_std430_array!(5);
+impl_std430_array!(6);
+impl_std430_array!(7);
+impl_std430_array!(8);
+impl_std430_array!(9);
+impl_std430_array!(10);
+impl_std430_array!(11);
+impl_std430_array!(12);
+impl_std430_array!(13);
+impl_std430_array!(14);
+impl_std430_array!(15);
+impl_std430_array!(16);
+impl_std430_array!(17);
+impl_std430_array!(18);
+impl_std430_array!(19);
+impl_std430_array!(20);
+impl_std430_array!(21);
+impl_std430_array!(22);
+impl_std430_array!(23);
+impl_std430_array!(24);
+impl_std430_array!(25);
+impl_std430_array!(26);
+impl_std430_array!(27);
+impl_std430_array!(28);
+impl_std430_array!(29);
+impl_std430_array!(30);
+impl_std430_array!(31);
+impl_std430_array!(32);
+impl_std430_array!(64);
+impl_std430_array!(128);
+impl_std430_array!(256);
+impl_std430_array!(512);
+impl_std430_array!(1024);
+impl_std430_array!(2048);
+
+/// A struct that can be converted to the std430 layout.
+///
+/// You should use the `implement_std430_block!` macro instead of implementing this trait
+/// manually.
+pub unsafe trait AsStd430: Copy {
+    /// The bytes of a value laid out with the std430 rules. Must be an array of `u8` whose
+    /// length is a multiple of `ALIGNMENT`.
+    type Storage: Copy;
+
+    /// Alignment of the struct, which is the largest alignment of its members.
+    const ALIGNMENT: usize;
+
+    /// Returns the members of the struct, with their std430 offsets.
+    fn members() -> Vec<BlockMember>;
+
+    /// Lays out the value with the std430 rules.
+    fn to_std430(&self) -> Self::Storage;
+
+    /// Reads a value laid out with the std430 rules.
+    fn from_std430(storage: &Self::Storage) -> Self;
+}
+
+/// A value of `T` laid out with the std430 rules, that can be stored in a `UniformBuffer` and
+/// bound to a shader storage block with `layout(std430)`.
+///
+/// The layout and the stride of the arrays are checked when the buffer is bound, and a mismatch
+/// is reported with the name of the member. A buffer of `[Std430<T>]` can be bound to a block
+/// that contains an array of structs whose size isn't known at compile-time.
+///
+/// ## Example
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{Std430, UniformBuffer};
+///
+/// #[derive(Copy, Clone)]
+/// struct Particle {
+///     position: [f32; 3],
+///     life: f32,
+///     weights: [f32; 8],
+/// }
+///
+/// implement_std430_block!(Particle, position: [f32; 3], life: f32, weights: [f32; 8]);
+///
+/// let particle = Particle { position: [0.0, 0.0, 0.0], life: 1.0, weights: [0.125; 8] };
+/// let buffer = UniformBuffer::new(&display, Std430::new(&particle)).unwrap();
+/// # }
+/// ```
+#[repr(C)]
+pub struct Std430<T> where T: AsStd430 {
+    storage: T::Storage,
+    marker: PhantomData<T>,
+}
+
+impl<T> Std430<T> where T: AsStd430 {
+    /// Lays out a value with the std430 rules.
+    #[inline]
+    pub fn new(value: &T) -> Std430<T> {
+        Std430 {
+            storage: value.to_std430(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the value.
+    #[inline]
+    pub fn get(&self) -> T {
+        T::from_std430(&self.storage)
+    }
+}
+
+impl<T> Copy for Std430<T> where T: AsStd430 {}
+
+impl<T> Clone for Std430<T> where T: AsStd430 {
+    #[inline]
+    fn clone(&self) -> Std430<T> {
+        *self
+    }
+}
+
+impl<T> From<T> for Std430<T> where T: AsStd430 {
+    #[inline]
+    fn from(value: T) -> Std430<T> {
+        Std430::new(&value)
+    }
+}
+
+unsafe impl<T> Std430Field for Std430<T> where T: AsStd430 {
+    const ALIGNMENT: usize = T::ALIGNMENT;
+    const SIZE: usize = mem::size_of::<T::Storage>();
+}
+
+// the size of the storage is a multiple of the alignment
+unsafe impl<T> Std430ArrayElement for Std430<T> where T: AsStd430 {}
+
+impl<T> UniformBlock for Std430<T> where T: AsStd430 {
+    #[inline]
+    fn matches(layout: &BlockLayout, base_offset: usize) -> Result<(), LayoutMismatchError> {
+        std140::matches_members(T::members(), layout, base_offset)
+    }
+
+    #[inline]
+    fn build_layout(base_offset: usize) -> BlockLayout {
+        std140::build_members_layout(T::members(), base_offset)
+    }
+
+    #[inline]
+    fn matches_strides(layout: &BlockLayout, path: &str, strides: &HashMap<String, usize>)
+                       -> Result<(), LayoutMismatchError>
+    {
+        std140::matches_members_strides(T::members(), layout, path, strides)
+    }
+}
+
+/// Returns the largest of two alignments.
+#[doc(hidden)]
+#[inline]
+pub const fn max_block_alignment(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use program::BlockLayout;
+    use uniforms::{LayoutMismatchError, UniformBlock};
+    use super::Std430;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Particle {
+        position: [f32; 3],
+        life: f32,
+        weights: [f32; 6],
+        velocity: [f32; 2],
+    }
+
+    implement_std430_block!(Particle, position: [f32; 3], life: f32, weights: [f32; 6],
+                            velocity: [f32; 2]);
+
+    #[derive(Copy, Clone)]
+    struct Pair {
+        a: f32,
+        b: f32,
+    }
+
+    implement_std430_block!(Pair, a: f32, b: f32);
+
+    #[derive(Copy, Clone)]
+    struct System {
+        count: u32,
+        pairs: [Std430<Pair>; 5],
+    }
+
+    implement_std430_block!(System, count: u32, pairs: [Std430<Pair>; 5]);
+
+    fn member<'a>(layout: &'a BlockLayout, name: &str) -> &'a BlockLayout {
+        match *layout {
+            BlockLayout::Struct { ref members } => {
+                &members.iter().find(|&&(ref n, _)| n == name).unwrap().1
+            },
+            _ => panic!(),
+        }
+    }
+
+    fn offset(layout: &BlockLayout) -> usize {
+        match *layout {
+            BlockLayout::BasicType { offset_in_buffer, .. } => offset_in_buffer,
+            BlockLayout::Array { ref content, .. } => offset(content),
+            BlockLayout::Struct { ref members } => offset(&members[0].1),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn offsets() {
+        let layout = <Std430<Particle> as UniformBlock>::build_layout(0);
+        assert_eq!(offset(member(&layout, "position")), 0);
+        assert_eq!(offset(member(&layout, "life")), 12);
+        assert_eq!(offset(member(&layout, "weights")), 16);
+        assert_eq!(offset(member(&layout, "velocity")), 40);
+        assert_eq!(::std::mem::size_of::<Std430<Particle>>(), 48);
+    }
+
+    #[test]
+    fn structs_are_not_padded() {
+        assert_eq!(::std::mem::size_of::<Std430<Pair>>(), 8);
+
+        let layout = <Std430<System> as UniformBlock>::build_layout(0);
+        assert_eq!(offset(member(&layout, "pairs")), 4);
+        assert_eq!(::std::mem::size_of::<Std430<System>>(), 44);
+    }
+
+    #[test]
+    fn round_trip() {
+        let particle = Particle {
+            position: [1.0, 2.0, 3.0],
+            life: 4.0,
+            weights: [5.0, 6.0, 7.0, 8.0, 9.0, 10.0],
+            velocity: [11.0, 12.0],
+        };
+
+        assert_eq!(Std430::new(&particle).get(), particle);
+    }
+
+    #[test]
+    fn stride_mismatch() {
+        let layout = <Std430<Particle> as UniformBlock>::build_layout(0);
+        assert!(<Std430<Particle> as UniformBlock>::matches(&layout, 0).is_ok());
+
+        let mut strides = HashMap::new();
+        strides.insert("weights".to_owned(), 4);
+        assert!(<Std430<Particle> as UniformBlock>::matches_strides(&layout, "", &strides)
+                    .is_ok());
+
+        // `weights` laid out with the std140 rules
+        strides.insert("weights".to_owned(), 16);
+        match <Std430<Particle> as UniformBlock>::matches_strides(&layout, "", &strides) {
+            Err(LayoutMismatchError::MemberMismatch { ref member, ref err }) => {
+                assert_eq!(member, "weights");
+                match **err {
+                    LayoutMismatchError::StrideMismatch { expected: 16, obtained: 4 } => (),
+                    ref err => panic!("{:?}", err),
+                }
+            },
+            res => panic!("{:?}", res),
+        }
+    }
+}
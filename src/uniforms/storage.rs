@@ -0,0 +1,114 @@
+use buffer::{BufferAnySlice, BufferSlice, Content};
+use uniforms::{AsUniformValue, LayoutMismatchError, UniformBlock, UniformValue};
+use uniforms::matches_block;
+use program;
+
+use ToGlEnum;
+use gl;
+
+/// How a shader accesses a shader storage buffer or an image.
+///
+/// Corresponds to the `readonly` and `writeonly` qualifiers of GLSL.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ShaderAccess {
+    /// The shader only reads the content.
+    ReadOnly,
+
+    /// The shader only writes the content.
+    WriteOnly,
+
+    /// The shader both reads and writes the content.
+    ReadWrite,
+}
+
+impl ShaderAccess {
+    /// Returns true if the shader may modify the content.
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        *self != ShaderAccess::ReadOnly
+    }
+}
+
+impl Default for ShaderAccess {
+    #[inline]
+    fn default() -> ShaderAccess {
+        ShaderAccess::ReadWrite
+    }
+}
+
+impl ToGlEnum for ShaderAccess {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ShaderAccess::ReadOnly => gl::READ_ONLY,
+            ShaderAccess::WriteOnly => gl::WRITE_ONLY,
+            ShaderAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
+/// A buffer, or a slice of a buffer, to bind to a shader storage block.
+///
+/// Binding a buffer directly to a shader storage block assumes that the shader modifies it.
+/// Wrapping it in a `ShaderStorage` with `ShaderAccess::ReadOnly` tells glium that the content
+/// doesn't change, which avoids a memory barrier the next time the buffer is used.
+///
+/// The offset of a slice must be a multiple of the `shader_storage_buffer_offset_alignment`
+/// capability.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{ShaderAccess, ShaderStorage};
+///
+/// let input = glium::buffer::Buffer::new(&display, &[1.0f32, 2.0, 3.0, 4.0][..],
+///                                        glium::buffer::BufferType::ShaderStorageBuffer,
+///                                        glium::buffer::BufferMode::Default).unwrap();
+///
+/// let uniforms = uniform! {
+///     Input: ShaderStorage::new(&input, ShaderAccess::ReadOnly),
+/// };
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ShaderStorage<'a> {
+    buffer: BufferAnySlice<'a>,
+    layout: fn(&program::UniformBlock) -> Result<(), LayoutMismatchError>,
+    access: ShaderAccess,
+}
+
+impl<'a> ShaderStorage<'a> {
+    /// Builds a new `ShaderStorage` from a buffer or a slice of a buffer.
+    pub fn new<T: ?Sized, S>(buffer: S, access: ShaderAccess) -> ShaderStorage<'a>
+                             where S: Into<BufferSlice<'a, T>>, T: UniformBlock + Content + 'a
+    {
+        #[inline]
+        fn f<T: ?Sized>(block: &program::UniformBlock) -> Result<(), LayoutMismatchError>
+                        where T: UniformBlock
+        {
+            matches_block::<T>(block)
+        }
+
+        ShaderStorage {
+            buffer: buffer.into().as_slice_any(),
+            layout: f::<T>,
+            access: access,
+        }
+    }
+
+    /// Returns how the shader accesses the buffer.
+    #[inline]
+    pub fn get_access(&self) -> ShaderAccess {
+        self.access
+    }
+}
+
+impl<'a> AsUniformValue for ShaderStorage<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::ShaderStorage(self.buffer, self.layout, self.access)
+    }
+}
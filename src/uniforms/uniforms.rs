@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use uniforms::{Uniforms, UniformValue, AsUniformValue};
 
 /// Object that can be used when you don't have any uniforms.
@@ -53,3 +55,115 @@ impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: AsUniformValue, R
         self.rest.visit_values(output);
     }
 }
+
+/// Contains the uniforms of two objects. Built with `Uniforms::chain`.
+#[derive(Debug, Copy, Clone)]
+pub struct ChainedUniforms<A, B> where A: Uniforms, B: Uniforms {
+    first: A,
+    second: B,
+}
+
+impl<A, B> ChainedUniforms<A, B> where A: Uniforms, B: Uniforms {
+    /// Builds an object that contains the uniforms of `first` followed by the ones of `second`.
+    #[inline]
+    pub fn new(first: A, second: B) -> ChainedUniforms<A, B> {
+        ChainedUniforms {
+            first: first,
+            second: second,
+        }
+    }
+}
+
+impl<A, B> Uniforms for ChainedUniforms<A, B> where A: Uniforms, B: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        self.first.visit_values(|name, value| output(name, value));
+        self.second.visit_values(output);
+    }
+}
+
+/// Stores uniforms whose names are only known at runtime.
+///
+/// Contrary to the `uniform!` macro, the uniforms can be added and removed one by one, which is
+/// useful for example when the list of parameters of a material is loaded from a file. Any value
+/// that implements `AsUniformValue` can be added, including textures, samplers and buffers.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{DynamicUniforms, Sampler};
+///
+/// let mut uniforms = DynamicUniforms::new();
+/// uniforms.add("color", [1.0, 0.0, 0.0, 1.0f32]);
+/// uniforms.add("diffuse", Sampler::new(&texture));
+///
+/// for name in &["roughness", "metalness"] {
+///     uniforms.add(*name, 0.5f32);
+/// }
+/// ```
+pub struct DynamicUniforms<'a> {
+    values: HashMap<String, Box<AsUniformValue + 'a>>,
+}
+
+impl<'a> DynamicUniforms<'a> {
+    /// Builds an empty storage.
+    #[inline]
+    pub fn new() -> DynamicUniforms<'a> {
+        DynamicUniforms {
+            values: HashMap::new(),
+        }
+    }
+
+    /// Adds a value to the storage. Replaces the previous value that had the same name, if any.
+    #[inline]
+    pub fn add<S, T>(&mut self, name: S, value: T) where S: Into<String>, T: AsUniformValue + 'a {
+        self.values.insert(name.into(), Box::new(value));
+    }
+
+    /// Removes a value from the storage. Returns false if there was no value with this name.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.values.remove(name).is_some()
+    }
+
+    /// Returns true if the storage contains a value with this name.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Returns the number of values in the storage.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if the storage doesn't contain any value.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Removes all the values from the storage.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl<'a> Default for DynamicUniforms<'a> {
+    #[inline]
+    fn default() -> DynamicUniforms<'a> {
+        DynamicUniforms::new()
+    }
+}
+
+impl<'a> Uniforms for DynamicUniforms<'a> {
+    #[inline]
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        for (name, value) in self.values.iter() {
+            output(name, value.as_uniform_value());
+        }
+    }
+}
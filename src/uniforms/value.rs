@@ -2,11 +2,19 @@ use program;
 use program::BlockLayout;
 use program::ShaderStage;
 use texture;
+use vertex::F16;
 
 use uniforms::AsUniformValue;
 use uniforms::LayoutMismatchError;
 use uniforms::UniformBlock;
+use uniforms::UniformStruct;
+use uniforms::UniformSamplerArray;
 use uniforms::SamplerBehavior;
+use uniforms::ImageUnitBehavior;
+use uniforms::ShaderAccess;
+use uniforms::image_unit;
+
+use RawUniformValue;
 
 use buffer::BufferAnySlice;
 
@@ -133,6 +141,33 @@ pub enum UniformType {
     AtomicCounterUint,
 }
 
+impl UniformType {
+    /// Returns true if this is the type of a sampler, that is bound to a texture unit.
+    pub fn is_sampler(&self) -> bool {
+        match *self {
+            UniformType::Sampler1d | UniformType::ISampler1d | UniformType::USampler1d |
+            UniformType::Sampler2d | UniformType::ISampler2d | UniformType::USampler2d |
+            UniformType::Sampler3d | UniformType::ISampler3d | UniformType::USampler3d |
+            UniformType::Sampler1dArray | UniformType::ISampler1dArray |
+            UniformType::USampler1dArray | UniformType::Sampler2dArray |
+            UniformType::ISampler2dArray | UniformType::USampler2dArray |
+            UniformType::SamplerCube | UniformType::ISamplerCube | UniformType::USamplerCube |
+            UniformType::Sampler2dRect | UniformType::ISampler2dRect |
+            UniformType::USampler2dRect | UniformType::Sampler2dRectShadow |
+            UniformType::SamplerCubeArray | UniformType::ISamplerCubeArray |
+            UniformType::USamplerCubeArray | UniformType::SamplerBuffer |
+            UniformType::ISamplerBuffer | UniformType::USamplerBuffer |
+            UniformType::Sampler2dMultisample | UniformType::ISampler2dMultisample |
+            UniformType::USampler2dMultisample | UniformType::Sampler2dMultisampleArray |
+            UniformType::ISampler2dMultisampleArray | UniformType::USampler2dMultisampleArray |
+            UniformType::Sampler1dShadow | UniformType::Sampler2dShadow |
+            UniformType::SamplerCubeShadow | UniformType::Sampler1dArrayShadow |
+            UniformType::Sampler2dArrayShadow | UniformType::SamplerCubeArrayShadow => true,
+            _ => false,
+        }
+    }
+}
+
 /// Represents a value to bind to a uniform.
 #[allow(missing_docs)]
 #[derive(Copy)]
@@ -143,6 +178,19 @@ pub enum UniformValue<'a> {
     /// the buffer has finished being used.
     Block(BufferAnySlice<'a>, fn(&program::UniformBlock) -> Result<(), LayoutMismatchError>),
     Subroutine(ShaderStage, &'a str),
+    /// The elements of a uniform array, which are all set with a single call.
+    Array(UniformArray<'a>),
+    /// The members of a struct, or the elements of an array of structs.
+    Struct(&'a UniformStruct),
+    /// The textures of an array of samplers.
+    Samplers(&'a UniformSamplerArray),
+    /// A buffer to bind to a shader storage block, with the way the shader accesses it.
+    ShaderStorage(BufferAnySlice<'a>,
+                  fn(&program::UniformBlock) -> Result<(), LayoutMismatchError>, ShaderAccess),
+    /// A texture to bind to an image unit.
+    Image(&'a texture::TextureAny, ImageUnitBehavior),
+    /// The handle of a resident texture, for a sampler with `GL_ARB_bindless_texture`.
+    TextureHandle(u64),
     SignedInt(i32),
     UnsignedInt(u32),
     Float(f32),
@@ -152,6 +200,18 @@ pub enum UniformValue<'a> {
     Mat3([[f32; 3]; 3]),
     /// 4x4 column-major matrix.
     Mat4([[f32; 4]; 4]),
+    /// 2x3 column-major matrix, with 2 columns of 3 rows.
+    Mat2x3([[f32; 3]; 2]),
+    /// 2x4 column-major matrix, with 2 columns of 4 rows.
+    Mat2x4([[f32; 4]; 2]),
+    /// 3x2 column-major matrix, with 3 columns of 2 rows.
+    Mat3x2([[f32; 2]; 3]),
+    /// 3x4 column-major matrix, with 3 columns of 4 rows.
+    Mat3x4([[f32; 4]; 3]),
+    /// 4x2 column-major matrix, with 4 columns of 2 rows.
+    Mat4x2([[f32; 2]; 4]),
+    /// 4x3 column-major matrix, with 4 columns of 3 rows.
+    Mat4x3([[f32; 3]; 4]),
     Vec2([f32; 2]),
     Vec3([f32; 3]),
     Vec4([f32; 4]),
@@ -172,6 +232,12 @@ pub enum UniformValue<'a> {
     DoubleMat2([[f64;2]; 2]),
     DoubleMat3([[f64;3]; 3]),
     DoubleMat4([[f64;4]; 4]),
+    DoubleMat2x3([[f64; 3]; 2]),
+    DoubleMat2x4([[f64; 4]; 2]),
+    DoubleMat3x2([[f64; 2]; 3]),
+    DoubleMat3x4([[f64; 4]; 3]),
+    DoubleMat4x2([[f64; 2]; 4]),
+    DoubleMat4x3([[f64; 3]; 4]),
     Int64(i64),
     Int64Vec2([i64; 2]),
     Int64Vec3([i64; 3]),
@@ -253,6 +319,12 @@ impl<'a> UniformValue<'a> {
     /// Returns true if this value can be used with a uniform of the given type.
     pub fn is_usable_with(&self, ty: &UniformType) -> bool {
         match (self, *ty) {
+            (&UniformValue::Array(ref array), ty) => array.is_usable_with(&ty),
+            (&UniformValue::Image(texture, ref behavior), ty) => {
+                image_unit::is_usable_with(texture, behavior, ty)
+            },
+            // the type of the texture is unknown, see the `bindless` module
+            (&UniformValue::TextureHandle(_), ty) => ty.is_sampler(),
             (&UniformValue::Bool(_), UniformType::Bool) => true,
             (&UniformValue::SignedInt(_), UniformType::Int) => true,
             (&UniformValue::UnsignedInt(_), UniformType::UnsignedInt) => true,
@@ -260,6 +332,12 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::Mat2(_), UniformType::FloatMat2) => true,
             (&UniformValue::Mat3(_), UniformType::FloatMat3) => true,
             (&UniformValue::Mat4(_), UniformType::FloatMat4) => true,
+            (&UniformValue::Mat2x3(_), UniformType::FloatMat2x3) => true,
+            (&UniformValue::Mat2x4(_), UniformType::FloatMat2x4) => true,
+            (&UniformValue::Mat3x2(_), UniformType::FloatMat3x2) => true,
+            (&UniformValue::Mat3x4(_), UniformType::FloatMat3x4) => true,
+            (&UniformValue::Mat4x2(_), UniformType::FloatMat4x2) => true,
+            (&UniformValue::Mat4x3(_), UniformType::FloatMat4x3) => true,
             (&UniformValue::Vec2(_), UniformType::FloatVec2) => true,
             (&UniformValue::Vec3(_), UniformType::FloatVec3) => true,
             (&UniformValue::Vec4(_), UniformType::FloatVec4) => true,
@@ -276,9 +354,23 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::DoubleMat2(_), UniformType::DoubleMat2) => true,
             (&UniformValue::DoubleMat3(_), UniformType::DoubleMat3) => true,
             (&UniformValue::DoubleMat4(_), UniformType::DoubleMat4) => true,
+            (&UniformValue::DoubleMat2x3(_), UniformType::DoubleMat2x3) => true,
+            (&UniformValue::DoubleMat2x4(_), UniformType::DoubleMat2x4) => true,
+            (&UniformValue::DoubleMat3x2(_), UniformType::DoubleMat3x2) => true,
+            (&UniformValue::DoubleMat3x4(_), UniformType::DoubleMat3x4) => true,
+            (&UniformValue::DoubleMat4x2(_), UniformType::DoubleMat4x2) => true,
+            (&UniformValue::DoubleMat4x3(_), UniformType::DoubleMat4x3) => true,
             (&UniformValue::DoubleVec2(_), UniformType::DoubleVec2) => true,
             (&UniformValue::DoubleVec3(_), UniformType::DoubleVec3) => true,
             (&UniformValue::DoubleVec4(_), UniformType::DoubleVec4) => true,
+            (&UniformValue::Int64(_), UniformType::Int64) => true,
+            (&UniformValue::Int64Vec2(_), UniformType::Int64Vec2) => true,
+            (&UniformValue::Int64Vec3(_), UniformType::Int64Vec3) => true,
+            (&UniformValue::Int64Vec4(_), UniformType::Int64Vec4) => true,
+            (&UniformValue::UnsignedInt64(_), UniformType::UnsignedInt64) => true,
+            (&UniformValue::UnsignedInt64Vec2(_), UniformType::UnsignedInt64Vec2) => true,
+            (&UniformValue::UnsignedInt64Vec3(_), UniformType::UnsignedInt64Vec3) => true,
+            (&UniformValue::UnsignedInt64Vec4(_), UniformType::UnsignedInt64Vec4) => true,
             (&UniformValue::Texture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::CompressedTexture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::SrgbTexture1d(_, _), UniformType::Sampler1d) => true,
@@ -353,6 +445,141 @@ impl<'a> UniformValue<'a> {
     }
 }
 
+/// The elements of a uniform array.
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Debug)]
+pub enum UniformArray<'a> {
+    Float(&'a [f32]),
+    Vec2(&'a [[f32; 2]]),
+    Vec3(&'a [[f32; 3]]),
+    Vec4(&'a [[f32; 4]]),
+    SignedInt(&'a [i32]),
+    IntVec2(&'a [[i32; 2]]),
+    IntVec3(&'a [[i32; 3]]),
+    IntVec4(&'a [[i32; 4]]),
+    UnsignedInt(&'a [u32]),
+    UnsignedIntVec2(&'a [[u32; 2]]),
+    UnsignedIntVec3(&'a [[u32; 3]]),
+    UnsignedIntVec4(&'a [[u32; 4]]),
+    /// 2x2 column-major matrices.
+    Mat2(&'a [[[f32; 2]; 2]]),
+    /// 3x3 column-major matrices.
+    Mat3(&'a [[[f32; 3]; 3]]),
+    /// 4x4 column-major matrices.
+    Mat4(&'a [[[f32; 4]; 4]]),
+    /// Handles of resident textures, for an array of samplers.
+    TextureHandle(&'a [u64]),
+}
+
+impl<'a> UniformArray<'a> {
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        match *self {
+            UniformArray::Float(v) => v.len(),
+            UniformArray::Vec2(v) => v.len(),
+            UniformArray::Vec3(v) => v.len(),
+            UniformArray::Vec4(v) => v.len(),
+            UniformArray::SignedInt(v) => v.len(),
+            UniformArray::IntVec2(v) => v.len(),
+            UniformArray::IntVec3(v) => v.len(),
+            UniformArray::IntVec4(v) => v.len(),
+            UniformArray::UnsignedInt(v) => v.len(),
+            UniformArray::UnsignedIntVec2(v) => v.len(),
+            UniformArray::UnsignedIntVec3(v) => v.len(),
+            UniformArray::UnsignedIntVec4(v) => v.len(),
+            UniformArray::Mat2(v) => v.len(),
+            UniformArray::Mat3(v) => v.len(),
+            UniformArray::Mat4(v) => v.len(),
+            UniformArray::TextureHandle(v) => v.len(),
+        }
+    }
+
+    /// Returns true if there is no element.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at the given index.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the index is out of range.
+    pub fn get(&self, index: usize) -> RawUniformValue {
+        match *self {
+            UniformArray::Float(v) => RawUniformValue::Float(v[index]),
+            UniformArray::Vec2(v) => RawUniformValue::Vec2(v[index]),
+            UniformArray::Vec3(v) => RawUniformValue::Vec3(v[index]),
+            UniformArray::Vec4(v) => RawUniformValue::Vec4(v[index]),
+            UniformArray::SignedInt(v) => RawUniformValue::SignedInt(v[index]),
+            UniformArray::IntVec2(v) => RawUniformValue::IntVec2(v[index]),
+            UniformArray::IntVec3(v) => RawUniformValue::IntVec3(v[index]),
+            UniformArray::IntVec4(v) => RawUniformValue::IntVec4(v[index]),
+            UniformArray::UnsignedInt(v) => RawUniformValue::UnsignedInt(v[index]),
+            UniformArray::UnsignedIntVec2(v) => RawUniformValue::UnsignedIntVec2(v[index]),
+            UniformArray::UnsignedIntVec3(v) => RawUniformValue::UnsignedIntVec3(v[index]),
+            UniformArray::UnsignedIntVec4(v) => RawUniformValue::UnsignedIntVec4(v[index]),
+            UniformArray::Mat2(v) => RawUniformValue::Mat2(v[index]),
+            UniformArray::Mat3(v) => RawUniformValue::Mat3(v[index]),
+            UniformArray::Mat4(v) => RawUniformValue::Mat4(v[index]),
+            UniformArray::TextureHandle(v) => RawUniformValue::TextureHandle(v[index]),
+        }
+    }
+
+    /// Returns the first `len` elements, or all of them if there are less.
+    pub fn truncate(&self, len: usize) -> UniformArray<'a> {
+        match *self {
+            UniformArray::Float(v) => UniformArray::Float(&v[.. len.min(v.len())]),
+            UniformArray::Vec2(v) => UniformArray::Vec2(&v[.. len.min(v.len())]),
+            UniformArray::Vec3(v) => UniformArray::Vec3(&v[.. len.min(v.len())]),
+            UniformArray::Vec4(v) => UniformArray::Vec4(&v[.. len.min(v.len())]),
+            UniformArray::SignedInt(v) => UniformArray::SignedInt(&v[.. len.min(v.len())]),
+            UniformArray::IntVec2(v) => UniformArray::IntVec2(&v[.. len.min(v.len())]),
+            UniformArray::IntVec3(v) => UniformArray::IntVec3(&v[.. len.min(v.len())]),
+            UniformArray::IntVec4(v) => UniformArray::IntVec4(&v[.. len.min(v.len())]),
+            UniformArray::UnsignedInt(v) => UniformArray::UnsignedInt(&v[.. len.min(v.len())]),
+            UniformArray::UnsignedIntVec2(v) => {
+                UniformArray::UnsignedIntVec2(&v[.. len.min(v.len())])
+            },
+            UniformArray::UnsignedIntVec3(v) => {
+                UniformArray::UnsignedIntVec3(&v[.. len.min(v.len())])
+            },
+            UniformArray::UnsignedIntVec4(v) => {
+                UniformArray::UnsignedIntVec4(&v[.. len.min(v.len())])
+            },
+            UniformArray::Mat2(v) => UniformArray::Mat2(&v[.. len.min(v.len())]),
+            UniformArray::Mat3(v) => UniformArray::Mat3(&v[.. len.min(v.len())]),
+            UniformArray::Mat4(v) => UniformArray::Mat4(&v[.. len.min(v.len())]),
+            UniformArray::TextureHandle(v) => {
+                UniformArray::TextureHandle(&v[.. len.min(v.len())])
+            },
+        }
+    }
+
+    /// Returns true if the elements can be used with a uniform array of the given type.
+    pub fn is_usable_with(&self, ty: &UniformType) -> bool {
+        match (self, *ty) {
+            (&UniformArray::Float(_), UniformType::Float) => true,
+            (&UniformArray::Vec2(_), UniformType::FloatVec2) => true,
+            (&UniformArray::Vec3(_), UniformType::FloatVec3) => true,
+            (&UniformArray::Vec4(_), UniformType::FloatVec4) => true,
+            (&UniformArray::SignedInt(_), UniformType::Int) => true,
+            (&UniformArray::IntVec2(_), UniformType::IntVec2) => true,
+            (&UniformArray::IntVec3(_), UniformType::IntVec3) => true,
+            (&UniformArray::IntVec4(_), UniformType::IntVec4) => true,
+            (&UniformArray::UnsignedInt(_), UniformType::UnsignedInt) => true,
+            (&UniformArray::UnsignedIntVec2(_), UniformType::UnsignedIntVec2) => true,
+            (&UniformArray::UnsignedIntVec3(_), UniformType::UnsignedIntVec3) => true,
+            (&UniformArray::UnsignedIntVec4(_), UniformType::UnsignedIntVec4) => true,
+            (&UniformArray::Mat2(_), UniformType::FloatMat2) => true,
+            (&UniformArray::Mat3(_), UniformType::FloatMat3) => true,
+            (&UniformArray::Mat4(_), UniformType::FloatMat4) => true,
+            (&UniformArray::TextureHandle(_), ty) => ty.is_sampler(),
+            _ => false,
+        }
+    }
+}
+
 macro_rules! impl_uniform_block_basic {
     ($ty:ty, $uniform_ty:expr) => (
         impl UniformBlock for $ty {
@@ -704,6 +931,60 @@ impl AsUniformValue for [[f32; 4]; 4] {
 
 impl_uniform_block_basic!([[f32; 4]; 4], UniformType::FloatMat4);
 
+impl AsUniformValue for [[f32; 3]; 2] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat2x3(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 3]; 2], UniformType::FloatMat2x3);
+
+impl AsUniformValue for [[f32; 4]; 2] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat2x4(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 4]; 2], UniformType::FloatMat2x4);
+
+impl AsUniformValue for [[f32; 2]; 3] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat3x2(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 2]; 3], UniformType::FloatMat3x2);
+
+impl AsUniformValue for [[f32; 4]; 3] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat3x4(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 4]; 3], UniformType::FloatMat3x4);
+
+impl AsUniformValue for [[f32; 2]; 4] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat4x2(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 2]; 4], UniformType::FloatMat4x2);
+
+impl AsUniformValue for [[f32; 3]; 4] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat4x3(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 3]; 4], UniformType::FloatMat4x3);
+
 impl AsUniformValue for (f32, f32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
@@ -758,7 +1039,6 @@ impl AsUniformValue for [f32; 4] {
 
 impl_uniform_block_basic!([f32; 4], UniformType::FloatVec4);
 
-//TODO bool, i32, u32 and f64 should also be implemented as cgmath and nalgebra variants (i.e. nalgebra::Vec3<f64>).
 // Start of double type variants
 impl AsUniformValue for f64 {
     #[inline]
@@ -850,6 +1130,60 @@ impl AsUniformValue for [[f64; 4]; 4] {
 
 impl_uniform_block_basic!([[f64; 4]; 4], UniformType::DoubleMat4);
 
+impl AsUniformValue for [[f64; 3]; 2] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::DoubleMat2x3(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f64; 3]; 2], UniformType::DoubleMat2x3);
+
+impl AsUniformValue for [[f64; 4]; 2] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::DoubleMat2x4(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f64; 4]; 2], UniformType::DoubleMat2x4);
+
+impl AsUniformValue for [[f64; 2]; 3] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::DoubleMat3x2(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f64; 2]; 3], UniformType::DoubleMat3x2);
+
+impl AsUniformValue for [[f64; 4]; 3] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::DoubleMat3x4(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f64; 4]; 3], UniformType::DoubleMat3x4);
+
+impl AsUniformValue for [[f64; 2]; 4] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::DoubleMat4x2(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f64; 2]; 4], UniformType::DoubleMat4x2);
+
+impl AsUniformValue for [[f64; 3]; 4] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::DoubleMat4x3(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f64; 3]; 4], UniformType::DoubleMat4x3);
+
 impl AsUniformValue for i64 {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
@@ -857,7 +1191,7 @@ impl AsUniformValue for i64 {
     }
 }
 
-impl_uniform_block_basic!(i64, UniformType::Int);
+impl_uniform_block_basic!(i64, UniformType::Int64);
 
 impl AsUniformValue for [i64; 2] {
     #[inline]
@@ -976,6 +1310,102 @@ impl AsUniformValue for (u64, u64, u64, u64) {
 
 impl_uniform_block_basic!((u64, u64, u64, u64), UniformType::UnsignedInt64Vec4);
 
+// Arrays
+macro_rules! impl_uniform_array {
+    ($ty:ty, $variant:ident) => (
+        impl<'a> AsUniformValue for &'a [$ty] {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue {
+                UniformValue::Array(UniformArray::$variant(self))
+            }
+        }
+
+        impl AsUniformValue for Vec<$ty> {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue {
+                UniformValue::Array(UniformArray::$variant(self))
+            }
+        }
+    );
+}
+
+impl_uniform_array!(f32, Float);
+impl_uniform_array!([f32; 2], Vec2);
+impl_uniform_array!([f32; 3], Vec3);
+impl_uniform_array!([f32; 4], Vec4);
+impl_uniform_array!(i32, SignedInt);
+impl_uniform_array!([i32; 2], IntVec2);
+impl_uniform_array!([i32; 3], IntVec3);
+impl_uniform_array!([i32; 4], IntVec4);
+impl_uniform_array!(u32, UnsignedInt);
+impl_uniform_array!([u32; 2], UnsignedIntVec2);
+impl_uniform_array!([u32; 3], UnsignedIntVec3);
+impl_uniform_array!([u32; 4], UnsignedIntVec4);
+impl_uniform_array!([[f32; 2]; 2], Mat2);
+impl_uniform_array!([[f32; 3]; 3], Mat3);
+impl_uniform_array!([[f32; 4]; 4], Mat4);
+
+/// A row-major matrix, which is transposed before being uploaded.
+///
+/// The matrices of glium are column-major, like in GLSL. Wrapping a matrix whose inner arrays
+/// are rows in a `Transposed` lets you use it without converting it yourself. For example a
+/// `Transposed<[[f32; 3]; 2]>` has two rows of three elements and corresponds to a `mat3x2`.
+///
+/// The matrix is transposed on the CPU, because OpenGL ES 2 doesn't support uploading
+/// transposed matrices.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transposed<T>(pub T);
+
+macro_rules! impl_transposed {
+    ($ty:ty, $column_major:ty, $variant:ident) => (
+        impl AsUniformValue for Transposed<$ty> {
+            fn as_uniform_value(&self) -> UniformValue {
+                let mut matrix: $column_major = Default::default();
+                for (row, values) in self.0.iter().enumerate() {
+                    for (column, &value) in values.iter().enumerate() {
+                        matrix[column][row] = value;
+                    }
+                }
+                UniformValue::$variant(matrix)
+            }
+        }
+    );
+}
+
+impl_transposed!([[f32; 2]; 2], [[f32; 2]; 2], Mat2);
+impl_transposed!([[f32; 2]; 3], [[f32; 3]; 2], Mat2x3);
+impl_transposed!([[f32; 2]; 4], [[f32; 4]; 2], Mat2x4);
+impl_transposed!([[f32; 3]; 2], [[f32; 2]; 3], Mat3x2);
+impl_transposed!([[f32; 3]; 3], [[f32; 3]; 3], Mat3);
+impl_transposed!([[f32; 3]; 4], [[f32; 4]; 3], Mat3x4);
+impl_transposed!([[f32; 4]; 2], [[f32; 2]; 4], Mat4x2);
+impl_transposed!([[f32; 4]; 3], [[f32; 3]; 4], Mat4x3);
+impl_transposed!([[f32; 4]; 4], [[f32; 4]; 4], Mat4);
+impl_transposed!([[f64; 2]; 2], [[f64; 2]; 2], DoubleMat2);
+impl_transposed!([[f64; 2]; 3], [[f64; 3]; 2], DoubleMat2x3);
+impl_transposed!([[f64; 2]; 4], [[f64; 4]; 2], DoubleMat2x4);
+impl_transposed!([[f64; 3]; 2], [[f64; 2]; 3], DoubleMat3x2);
+impl_transposed!([[f64; 3]; 3], [[f64; 3]; 3], DoubleMat3);
+impl_transposed!([[f64; 3]; 4], [[f64; 4]; 3], DoubleMat3x4);
+impl_transposed!([[f64; 4]; 2], [[f64; 2]; 4], DoubleMat4x2);
+impl_transposed!([[f64; 4]; 3], [[f64; 3]; 4], DoubleMat4x3);
+impl_transposed!([[f64; 4]; 4], [[f64; 4]; 4], DoubleMat4);
+
+// Structs
+impl<'a, T> AsUniformValue for &'a [T] where T: UniformStruct {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Struct(self)
+    }
+}
+
+impl<T> AsUniformValue for Vec<T> where T: UniformStruct {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Struct(self)
+    }
+}
+
 // Subroutines
 impl<'a> AsUniformValue for (&'a str, ShaderStage) {
     #[inline]
@@ -983,3 +1413,181 @@ impl<'a> AsUniformValue for (&'a str, ShaderStage) {
         UniformValue::Subroutine(self.1, self.0)
     }
 }
+
+// Math libraries
+macro_rules! impl_uniform_value_from {
+    ($ty:ty, $variant:ident, |$value:ident| $conversion:expr) => (
+        impl AsUniformValue for $ty {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue {
+                let $value = *self;
+                UniformValue::$variant($conversion)
+            }
+        }
+    );
+}
+
+// GLSL doesn't have half-precision uniforms, so they are converted to `float`s
+impl_uniform_value_from!(F16, Float, |value| value.to_f32());
+impl_uniform_value_from!([F16; 2], Vec2, |value| [value[0].to_f32(), value[1].to_f32()]);
+impl_uniform_value_from!([F16; 3], Vec3, |value| {
+    [value[0].to_f32(), value[1].to_f32(), value[2].to_f32()]
+});
+impl_uniform_value_from!([F16; 4], Vec4, |value| {
+    [value[0].to_f32(), value[1].to_f32(), value[2].to_f32(), value[3].to_f32()]
+});
+
+#[cfg(feature = "cgmath")]
+mod cgmath_values {
+    use cgmath;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value_from!(cgmath::Vector2<f32>, Vec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector3<f32>, Vec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector4<f32>, Vec4, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point2<f32>, Vec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point3<f32>, Vec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector2<f64>, DoubleVec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector3<f64>, DoubleVec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector4<f64>, DoubleVec4, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point2<f64>, DoubleVec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point3<f64>, DoubleVec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector2<i32>, IntVec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector3<i32>, IntVec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector4<i32>, IntVec4, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point2<i32>, IntVec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point3<i32>, IntVec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector2<u32>, UnsignedIntVec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector3<u32>, UnsignedIntVec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Vector4<u32>, UnsignedIntVec4, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point2<u32>, UnsignedIntVec2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Point3<u32>, UnsignedIntVec3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Matrix2<f32>, Mat2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Matrix3<f32>, Mat3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Matrix4<f32>, Mat4, |value| value.into());
+    impl_uniform_value_from!(cgmath::Matrix2<f64>, DoubleMat2, |value| value.into());
+    impl_uniform_value_from!(cgmath::Matrix3<f64>, DoubleMat3, |value| value.into());
+    impl_uniform_value_from!(cgmath::Matrix4<f64>, DoubleMat4, |value| value.into());
+}
+
+#[cfg(feature = "half")]
+mod half_values {
+    use half;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value_from!(half::f16, Float, |value| value.to_f32());
+    impl_uniform_value_from!([half::f16; 2], Vec2, |value| {
+        [value[0].to_f32(), value[1].to_f32()]
+    });
+    impl_uniform_value_from!([half::f16; 3], Vec3, |value| {
+        [value[0].to_f32(), value[1].to_f32(), value[2].to_f32()]
+    });
+    impl_uniform_value_from!([half::f16; 4], Vec4, |value| {
+        [value[0].to_f32(), value[1].to_f32(), value[2].to_f32(), value[3].to_f32()]
+    });
+}
+
+#[cfg(feature = "mint")]
+mod mint_values {
+    use mint;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value_from!(mint::Vector2<f32>, Vec2, |value| value.into());
+    impl_uniform_value_from!(mint::Vector3<f32>, Vec3, |value| value.into());
+    impl_uniform_value_from!(mint::Vector4<f32>, Vec4, |value| value.into());
+    impl_uniform_value_from!(mint::Point2<f32>, Vec2, |value| value.into());
+    impl_uniform_value_from!(mint::Point3<f32>, Vec3, |value| value.into());
+    impl_uniform_value_from!(mint::Vector2<f64>, DoubleVec2, |value| value.into());
+    impl_uniform_value_from!(mint::Vector3<f64>, DoubleVec3, |value| value.into());
+    impl_uniform_value_from!(mint::Vector4<f64>, DoubleVec4, |value| value.into());
+    impl_uniform_value_from!(mint::Point2<f64>, DoubleVec2, |value| value.into());
+    impl_uniform_value_from!(mint::Point3<f64>, DoubleVec3, |value| value.into());
+    impl_uniform_value_from!(mint::Vector2<i32>, IntVec2, |value| value.into());
+    impl_uniform_value_from!(mint::Vector3<i32>, IntVec3, |value| value.into());
+    impl_uniform_value_from!(mint::Vector4<i32>, IntVec4, |value| value.into());
+    impl_uniform_value_from!(mint::Point2<i32>, IntVec2, |value| value.into());
+    impl_uniform_value_from!(mint::Point3<i32>, IntVec3, |value| value.into());
+    impl_uniform_value_from!(mint::Vector2<u32>, UnsignedIntVec2, |value| value.into());
+    impl_uniform_value_from!(mint::Vector3<u32>, UnsignedIntVec3, |value| value.into());
+    impl_uniform_value_from!(mint::Vector4<u32>, UnsignedIntVec4, |value| value.into());
+    impl_uniform_value_from!(mint::Point2<u32>, UnsignedIntVec2, |value| value.into());
+    impl_uniform_value_from!(mint::Point3<u32>, UnsignedIntVec3, |value| value.into());
+    impl_uniform_value_from!(mint::ColumnMatrix2<f32>, Mat2, |value| value.into());
+    impl_uniform_value_from!(mint::ColumnMatrix3<f32>, Mat3, |value| value.into());
+    impl_uniform_value_from!(mint::ColumnMatrix4<f32>, Mat4, |value| value.into());
+    impl_uniform_value_from!(mint::ColumnMatrix2<f64>, DoubleMat2, |value| value.into());
+    impl_uniform_value_from!(mint::ColumnMatrix3<f64>, DoubleMat3, |value| value.into());
+    impl_uniform_value_from!(mint::ColumnMatrix4<f64>, DoubleMat4, |value| value.into());
+}
+
+// the layout of the nalgebra types is checked by the tests of `vertex::format`
+#[cfg(feature = "nalgebra")]
+mod nalgebra_values {
+    use std::mem;
+    use nalgebra;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value_from!(nalgebra::Vector2<f32>, Vec2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector3<f32>, Vec3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector4<f32>, Vec4,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point2<f32>, Vec2, |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point3<f32>, Vec3, |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector2<f64>, DoubleVec2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector3<f64>, DoubleVec3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector4<f64>, DoubleVec4,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point2<f64>, DoubleVec2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point3<f64>, DoubleVec3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector2<i32>, IntVec2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector3<i32>, IntVec3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector4<i32>, IntVec4,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point2<i32>, IntVec2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point3<i32>, IntVec3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector2<u32>, UnsignedIntVec2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector3<u32>, UnsignedIntVec3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Vector4<u32>, UnsignedIntVec4,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point2<u32>, UnsignedIntVec2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Point3<u32>, UnsignedIntVec3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Matrix2<f32>, Mat2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Matrix3<f32>, Mat3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Matrix4<f32>, Mat4,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Matrix2<f64>, DoubleMat2,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Matrix3<f64>, DoubleMat3,
+                             |value| unsafe { mem::transmute(value) });
+    impl_uniform_value_from!(nalgebra::Matrix4<f64>, DoubleMat4,
+                             |value| unsafe { mem::transmute(value) });
+}
+
+#[cfg(feature = "glam")]
+mod glam_values {
+    use glam;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value_from!(glam::Vec2, Vec2, |value| value.into());
+    impl_uniform_value_from!(glam::Vec3, Vec3, |value| value.into());
+    impl_uniform_value_from!(glam::Vec4, Vec4, |value| value.into());
+    impl_uniform_value_from!(glam::Mat2, Mat2, |value| value.to_cols_array_2d());
+    impl_uniform_value_from!(glam::Mat3, Mat3, |value| value.to_cols_array_2d());
+    impl_uniform_value_from!(glam::Mat4, Mat4, |value| value.to_cols_array_2d());
+}
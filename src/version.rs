@@ -8,10 +8,12 @@ use gl;
 /// For example, both `Version(Gl, 3, 0) >= Version(GlEs, 3, 0)` and `Version(GlEs, 3, 0) >= 
 /// Version(Gl, 3, 0)` return `false`.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Version(pub Api, pub u8, pub u8);
 
 /// Describes an OpenGL-related API.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Api {
     /// Regular OpenGL.
     Gl,
@@ -43,8 +45,12 @@ pub unsafe fn get_gl_version(gl: &gl::Gl) -> Version {
     let version = gl.GetString(gl::VERSION);
     let version = String::from_utf8(CStr::from_ptr(version as *const _).to_bytes().to_vec()).unwrap();
 
-    // for the moment we mock WebGL as OpenGL ES 2.0
+    // WebGL 2.0 is based on OpenGL ES 3.0, and WebGL 1.0 on OpenGL ES 2.0
     // TODO: handle the differences between WebGL and OpenGL ES
+    if version.starts_with("WebGL 2") {
+        return Version(Api::GlEs, 3, 0);
+    }
+
     if version.starts_with("WebGL ") {
         return Version(Api::GlEs, 2, 0);
     }
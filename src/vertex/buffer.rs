@@ -4,8 +4,9 @@ use std::ops::{Deref, DerefMut};
 use utils::range::RangeArgument;
 
 use buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferType, BufferMode, BufferCreationError, Content};
+use buffer::BufferAnySlice;
 use vertex::{Vertex, VerticesSource, IntoVerticesSource, PerInstance};
-use vertex::format::VertexFormat;
+use vertex::format::{self, VertexFormat};
 
 use gl;
 use GlObject;
@@ -16,12 +17,16 @@ use CapabilitiesSource;
 
 /// Error that can happen when creating a vertex buffer.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum CreationError {
     /// The vertex format is not supported by the backend.
     ///
     /// Anything 64bits-related may not be supported.
     FormatNotSupported,
 
+    /// An attribute of the vertex format ends after the end of the vertex.
+    AttributeOutOfVertex,
+
     /// Error while creating the vertex buffer.
     BufferCreationError(BufferCreationError),
 }
@@ -44,15 +49,17 @@ impl Error for CreationError {
         use self::CreationError::*;
         match *self {
             FormatNotSupported => "The vertex format is not supported by the backend",
+            AttributeOutOfVertex => "An attribute of the vertex format ends after the vertex",
             BufferCreationError(_) => "Error while creating the vertex buffer",
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref error) => Some(error),
             FormatNotSupported => None,
+            AttributeOutOfVertex => None,
         }
     }
 }
@@ -71,12 +78,15 @@ impl<T: Copy> GlObject for VertexBuffer<T> {
 pub struct VertexBuffer<T> where T: Copy {
     buffer: Buffer<[T]>,
     bindings: VertexFormat,
+    // number of consecutive instances that use each element when used per instance
+    divisor: u32,
 }
 
 /// Represents a slice of a `VertexBuffer`.
 pub struct VertexBufferSlice<'b, T: 'b> where T: Copy {
     buffer: BufferSlice<'b, [T]>,
     bindings: &'b VertexFormat,
+    divisor: u32,
 }
 
 impl<'b, T: 'b> VertexBufferSlice<'b, T> where T: Copy + Content {
@@ -90,13 +100,11 @@ impl<'b, T: 'b> VertexBufferSlice<'b, T> where T: Copy + Content {
     #[inline]
     pub fn per_instance(&'b self) -> Result<PerInstance, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.get_context().get_extensions().gl_arb_instanced_arrays
-        {
+        if !is_instancing_supported(&**self.get_context()) {
             return Err(InstancingNotSupported);
         }
 
-        Ok(PerInstance(self.buffer.as_slice_any(), &self.bindings))
+        Ok(PerInstance(self.buffer.as_slice_any(), &self.bindings, self.divisor))
     }
 }
 
@@ -274,6 +282,7 @@ impl<T> VertexBuffer<T> where T: Copy {
             buffer: try!(Buffer::new(facade, data, BufferType::ArrayBuffer,
                                          BufferMode::Default)),
             bindings: bindings,
+            divisor: 1,
         })
     }
 
@@ -290,6 +299,7 @@ impl<T> VertexBuffer<T> where T: Copy {
             buffer: try!(Buffer::new(facade, data, BufferType::ArrayBuffer,
                                          BufferMode::Dynamic)),
             bindings: bindings,
+            divisor: 1,
         })
     }
 
@@ -306,6 +316,7 @@ impl<T> VertexBuffer<T> where T: Copy {
         Some(VertexBufferSlice {
             buffer: slice,
             bindings: &self.bindings,
+            divisor: self.divisor,
         })
     }
 
@@ -321,16 +332,17 @@ impl<T> VertexBuffer<T> where T: Copy {
     /// `surface.draw(vertex_buffer.per_instance(), ...)`. This will draw one instance of the
     /// geometry for each element in this buffer. The attributes are still passed to the
     /// vertex shader, but each entry is passed for each different instance.
+    ///
+    /// Each element is used for `Vertex::instance_divisor()` consecutive instances, which is 1
+    /// unless the vertex type says otherwise or `PerInstance::divisor` is called.
     #[inline]
     pub fn per_instance(&self) -> Result<PerInstance, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.buffer.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.buffer.get_context().get_extensions().gl_arb_instanced_arrays
-        {
+        if !is_instancing_supported(&**self.buffer.get_context()) {
             return Err(InstancingNotSupported);
         }
 
-        Ok(PerInstance(self.buffer.as_slice_any(), &self.bindings))
+        Ok(PerInstance(self.buffer.as_slice_any(), &self.bindings, self.divisor))
     }
 }
 
@@ -342,6 +354,7 @@ impl<T> VertexBuffer<T> where T: Copy + Send + 'static {
         VertexBufferAny {
             buffer: self.buffer.into(),
             bindings: self.bindings,
+            divisor: self.divisor,
         }
     }
 }
@@ -356,6 +369,7 @@ impl<T> From<Buffer<[T]>> for VertexBuffer<T> where T: Vertex + Copy {
         VertexBuffer {
             buffer: buffer,
             bindings: bindings,
+            divisor: <T as Vertex>::instance_divisor(),
         }
     }
 }
@@ -395,7 +409,7 @@ impl<'a, T> From<&'a mut VertexBuffer<T>> for BufferMutSlice<'a, [T]> where T: C
 impl<'a, T> IntoVerticesSource<'a> for &'a VertexBuffer<T> where T: Copy {
     #[inline]
     fn into_vertices_source(self) -> VerticesSource<'a> {
-        VerticesSource::VertexBuffer(self.buffer.as_slice_any(), &self.bindings, false)
+        VerticesSource::VertexBuffer(self.buffer.as_slice_any(), &self.bindings, None)
     }
 }
 
@@ -425,7 +439,7 @@ impl<'a, T> From<VertexBufferSlice<'a, T>> for BufferSlice<'a, [T]> where T: Cop
 impl<'a, T> IntoVerticesSource<'a> for VertexBufferSlice<'a, T> where T: Copy {
     #[inline]
     fn into_vertices_source(self) -> VerticesSource<'a> {
-        VerticesSource::VertexBuffer(self.buffer.as_slice_any(), &self.bindings, false)
+        VerticesSource::VertexBuffer(self.buffer.as_slice_any(), &self.bindings, None)
     }
 }
 
@@ -440,9 +454,69 @@ impl<'a, T> IntoVerticesSource<'a> for VertexBufferSlice<'a, T> where T: Copy {
 pub struct VertexBufferAny {
     buffer: BufferAny,
     bindings: VertexFormat,
+    divisor: u32,
+}
+
+impl GlObject for VertexBufferAny {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.buffer.get_id()
+    }
 }
 
 impl VertexBufferAny {
+    /// Builds a new vertex buffer from raw bytes and a format that is only known at runtime,
+    /// for example when loading a mesh from a file.
+    ///
+    /// `vertex_size` is the number of bytes between the start of two consecutive vertices.
+    /// Returns an error if one of the attributes ends after the end of the vertex, or if one of
+    /// them is not supported by the backend.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `data` is not a multiple of `vertex_size`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+    /// use glium::vertex::{AttributeType, VertexBufferAny, VertexFormatBuilder};
+    ///
+    /// let format = VertexFormatBuilder::new()
+    ///     .attribute("position", 0, AttributeType::F32F32)
+    ///     .normalized_attribute("color", 8, AttributeType::U8U8U8U8)
+    ///     .build();
+    ///
+    /// // read from a file
+    /// let data: Vec<u8> = vec![0; 3 * 12];
+    ///
+    /// let vertex_buffer = VertexBufferAny::new_raw(&display, &data, format, 12).unwrap();
+    /// assert_eq!(vertex_buffer.len(), 3);
+    /// ```
+    pub fn new_raw<F: ?Sized>(facade: &F, data: &[u8], bindings: VertexFormat,
+                              vertex_size: usize) -> Result<VertexBufferAny, CreationError>
+                              where F: Facade
+    {
+        if format::format_size(&bindings) > vertex_size {
+            return Err(CreationError::AttributeOutOfVertex);
+        }
+
+        if !bindings.iter().all(|&(_, _, ty, _)| ty.is_supported(facade.get_context())) {
+            return Err(CreationError::FormatNotSupported);
+        }
+
+        let buffer = try!(BufferAny::from_bytes(facade, data, vertex_size,
+                                                BufferType::ArrayBuffer, BufferMode::Default));
+
+        Ok(VertexBufferAny {
+            buffer: buffer,
+            bindings: bindings,
+            divisor: 1,
+        })
+    }
+
     /// Returns the number of bytes between two consecutive elements in the buffer.
     #[inline]
     pub fn get_elements_size(&self) -> usize {
@@ -461,6 +535,20 @@ impl VertexBufferAny {
         &self.bindings
     }
 
+    /// Accesses a slice of the buffer, in number of vertices. The slice can be used per vertex
+    /// or per instance.
+    ///
+    /// Returns `None` if the slice is out of range.
+    #[inline]
+    pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<BufferVertices> {
+        self.buffer.as_slice_any().slice(range).map(|buffer| {
+            BufferVertices {
+                buffer: buffer,
+                bindings: &self.bindings,
+            }
+        })
+    }
+
     /// Turns the vertex buffer into a `VertexBuffer` without checking the type.
     #[inline]
     pub unsafe fn into_vertex_buffer<T: Copy>(self) -> VertexBuffer<T> {
@@ -476,13 +564,11 @@ impl VertexBufferAny {
     #[inline]
     pub fn per_instance(&self) -> Result<PerInstance, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.buffer.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.buffer.get_context().get_extensions().gl_arb_instanced_arrays
-        {
+        if !is_instancing_supported(&**self.buffer.get_context()) {
             return Err(InstancingNotSupported);
         }
 
-        Ok(PerInstance(self.buffer.as_slice_any(), &self.bindings))
+        Ok(PerInstance(self.buffer.as_slice_any(), &self.bindings, self.divisor))
     }
 }
 
@@ -520,10 +606,136 @@ impl DerefMut for VertexBufferAny {
 impl<'a> IntoVerticesSource<'a> for &'a VertexBufferAny {
     #[inline]
     fn into_vertices_source(self) -> VerticesSource<'a> {
-        VerticesSource::VertexBuffer(self.buffer.as_slice_any(), &self.bindings, false)
+        VerticesSource::VertexBuffer(self.buffer.as_slice_any(), &self.bindings, None)
     }
 }
 
+/// Vertices read from any range of any buffer, with a format and a stride given separately.
+///
+/// This corresponds to `glBindVertexBuffer`: the format describes the attributes relatively to
+/// the start of each vertex, while the buffer, the offset of the first vertex and the number of
+/// bytes between two vertices are chosen when drawing. This allows for example storing the
+/// vertices of many meshes in one big buffer, or drawing with attributes that come from several
+/// buffers with different layouts.
+///
+/// The offset of the first vertex is the start of the slice.
+///
+/// # Example
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+/// # let mut frame = display.draw();
+/// use glium::buffer::{Buffer, BufferMode, BufferType};
+/// use glium::vertex::{AttributeType, BufferVertices, VertexFormatBuilder};
+///
+/// let arena: Buffer<[u8]> = Buffer::empty_array(&display, BufferType::ArrayBuffer, 65536,
+///                                               BufferMode::Default).unwrap();
+///
+/// let positions = VertexFormatBuilder::new()
+///     .attribute("position", 0, AttributeType::F32F32F32)
+///     .build();
+/// let tex_coords = VertexFormatBuilder::new()
+///     .attribute("tex_coords", 0, AttributeType::F32F32)
+///     .build();
+///
+/// // 100 positions at the start of the arena, and 100 texture coordinates after them
+/// let positions = BufferVertices::new(arena.slice(0 .. 1200).unwrap().as_slice_any(),
+///                                     &positions, 12).unwrap();
+/// let tex_coords = BufferVertices::new(arena.slice(1200 .. 2000).unwrap().as_slice_any(),
+///                                      &tex_coords, 8).unwrap();
+///
+/// frame.draw((positions, tex_coords),
+///            &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+///            &program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct BufferVertices<'a> {
+    buffer: BufferAnySlice<'a>,
+    bindings: &'a VertexFormat,
+}
+
+impl<'a> BufferVertices<'a> {
+    /// Builds a new source of vertices. `stride` is the number of bytes between the start of two
+    /// consecutive vertices.
+    ///
+    /// Returns an error if one of the attributes ends after the end of the vertex, or if one of
+    /// them is not supported by the backend. `BufferCreationError` is never returned.
+    pub fn new(buffer: BufferAnySlice<'a>, bindings: &'a VertexFormat, stride: usize)
+               -> Result<BufferVertices<'a>, CreationError>
+    {
+        if stride == 0 || format::format_size(bindings) > stride {
+            return Err(CreationError::AttributeOutOfVertex);
+        }
+
+        if !bindings.iter().all(|&(_, _, ty, _)| ty.is_supported(buffer.get_context())) {
+            return Err(CreationError::FormatNotSupported);
+        }
+
+        Ok(BufferVertices {
+            buffer: buffer.with_elements_size(stride),
+            bindings: bindings,
+        })
+    }
+
+    /// Returns the number of vertices, which is the number of whole strides in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffer.get_elements_count()
+    }
+
+    /// Returns the number of bytes between the start of two consecutive vertices.
+    #[inline]
+    pub fn get_stride(&self) -> usize {
+        self.buffer.get_elements_size()
+    }
+
+    /// Returns the associated `VertexFormat`.
+    #[inline]
+    pub fn get_bindings(&self) -> &'a VertexFormat {
+        self.bindings
+    }
+
+    /// Creates a marker that instructs glium to use each vertex for one instance.
+    #[inline]
+    pub fn per_instance(&self) -> Result<PerInstance<'a>, InstancingNotSupported> {
+        // TODO: don't check this here
+        if !is_instancing_supported(&**self.buffer.get_context()) {
+            return Err(InstancingNotSupported);
+        }
+
+        Ok(PerInstance(self.buffer, self.bindings, 1))
+    }
+}
+
+impl<'a> IntoVerticesSource<'a> for BufferVertices<'a> {
+    #[inline]
+    fn into_vertices_source(self) -> VerticesSource<'a> {
+        VerticesSource::VertexBuffer(self.buffer, self.bindings, None)
+    }
+}
+
+/// Returns true if the backend supports per-instance attributes.
+pub fn is_instancing_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
+    caps.get_version() >= &Version(Api::Gl, 3, 3) ||
+    caps.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    caps.get_extensions().gl_arb_instanced_arrays ||
+    caps.get_extensions().gl_angle_instanced_arrays
+}
+
 /// Instancing is not supported by the backend.
 #[derive(Debug, Copy, Clone)]
 pub struct InstancingNotSupported;
+
+impl fmt::Display for InstancingNotSupported {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for InstancingNotSupported {
+    fn description(&self) -> &str {
+        "Instancing is not supported by the backend"
+    }
+}
@@ -10,9 +10,15 @@ use CapabilitiesSource;
 use cgmath;
 #[cfg(feature = "nalgebra")]
 use nalgebra;
+#[cfg(feature = "mint")]
+use mint;
+#[cfg(feature = "glam")]
+use glam;
+#[cfg(feature = "half")]
+use half;
 
 #[allow(missing_docs)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum AttributeType {
     I8,
     I8I8,
@@ -401,6 +407,48 @@ impl AttributeType {
             AttributeType::FixedFloatI16U16 => 1,
         }
     }
+
+    /// Returns the name of the GLSL type that corresponds to this type, for example `vec3` for
+    /// `F32F32F32`. Returns `None` for the types that can only be used as vertex data.
+    pub fn get_glsl_name(&self) -> Option<&'static str> {
+        Some(match *self {
+            AttributeType::I32 => "int",
+            AttributeType::I32I32 => "ivec2",
+            AttributeType::I32I32I32 => "ivec3",
+            AttributeType::I32I32I32I32 => "ivec4",
+            AttributeType::U32 => "uint",
+            AttributeType::U32U32 => "uvec2",
+            AttributeType::U32U32U32 => "uvec3",
+            AttributeType::U32U32U32U32 => "uvec4",
+            AttributeType::F32 => "float",
+            AttributeType::F32F32 => "vec2",
+            AttributeType::F32F32F32 => "vec3",
+            AttributeType::F32F32F32F32 => "vec4",
+            AttributeType::F32x2x2 => "mat2",
+            AttributeType::F32x2x3 => "mat2x3",
+            AttributeType::F32x2x4 => "mat2x4",
+            AttributeType::F32x3x2 => "mat3x2",
+            AttributeType::F32x3x3 => "mat3",
+            AttributeType::F32x3x4 => "mat3x4",
+            AttributeType::F32x4x2 => "mat4x2",
+            AttributeType::F32x4x3 => "mat4x3",
+            AttributeType::F32x4x4 => "mat4",
+            AttributeType::F64 => "double",
+            AttributeType::F64F64 => "dvec2",
+            AttributeType::F64F64F64 => "dvec3",
+            AttributeType::F64F64F64F64 => "dvec4",
+            AttributeType::F64x2x2 => "dmat2",
+            AttributeType::F64x2x3 => "dmat2x3",
+            AttributeType::F64x2x4 => "dmat2x4",
+            AttributeType::F64x3x2 => "dmat3x2",
+            AttributeType::F64x3x3 => "dmat3",
+            AttributeType::F64x3x4 => "dmat3x4",
+            AttributeType::F64x4x2 => "dmat4x2",
+            AttributeType::F64x4x3 => "dmat4x3",
+            AttributeType::F64x4x4 => "dmat4",
+            _ => return None,
+        })
+    }
 }
 
 /// Describes the layout of each vertex in a vertex buffer.
@@ -412,6 +460,80 @@ impl AttributeType {
 /// binding in a VAO.
 pub type VertexFormat = Cow<'static, [(Cow<'static, str>, usize, AttributeType, bool)]>;
 
+/// Builds a `VertexFormat` at runtime, for example from the description of a mesh read from a
+/// file, without going through the `implement_vertex!` macro.
+///
+/// # Example
+///
+/// ```
+/// use glium::vertex::{AttributeType, VertexFormatBuilder};
+///
+/// let builder = VertexFormatBuilder::new()
+///     .attribute("position", 0, AttributeType::F32F32F32)
+///     .normalized_attribute("color", 12, AttributeType::U8U8U8U8);
+///
+/// assert_eq!(builder.vertex_size(), 16);
+/// let format = builder.build();
+/// assert_eq!(format.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VertexFormatBuilder {
+    attributes: Vec<(Cow<'static, str>, usize, AttributeType, bool)>,
+}
+
+impl VertexFormatBuilder {
+    /// Builds a new builder without any attribute.
+    #[inline]
+    pub fn new() -> VertexFormatBuilder {
+        VertexFormatBuilder {
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Adds an attribute that starts `offset` bytes after the start of each vertex.
+    #[inline]
+    pub fn attribute<S>(self, name: S, offset: usize, ty: AttributeType) -> VertexFormatBuilder
+                        where S: Into<Cow<'static, str>>
+    {
+        self.add(name.into(), offset, ty, false)
+    }
+
+    /// Adds an attribute whose integer values are turned into floating-point values between
+    /// `0.0` and `1.0` (or `-1.0` and `1.0` for signed types) when the shader reads them.
+    #[inline]
+    pub fn normalized_attribute<S>(self, name: S, offset: usize, ty: AttributeType)
+                                   -> VertexFormatBuilder
+                                   where S: Into<Cow<'static, str>>
+    {
+        self.add(name.into(), offset, ty, true)
+    }
+
+    fn add(mut self, name: Cow<'static, str>, offset: usize, ty: AttributeType, normalize: bool)
+           -> VertexFormatBuilder
+    {
+        self.attributes.push((name, offset, ty, normalize));
+        self
+    }
+
+    /// Returns the minimal size in bytes of a vertex, which is the end of the attribute that
+    /// ends the last.
+    #[inline]
+    pub fn vertex_size(&self) -> usize {
+        format_size(&self.attributes)
+    }
+
+    /// Builds the `VertexFormat`.
+    #[inline]
+    pub fn build(self) -> VertexFormat {
+        Cow::Owned(self.attributes)
+    }
+}
+
+/// Returns the end of the attribute that ends the last.
+pub fn format_size(format: &[(Cow<'static, str>, usize, AttributeType, bool)]) -> usize {
+    format.iter().map(|&(_, offset, ty, _)| offset + ty.get_size_bytes()).max().unwrap_or(0)
+}
+
 unsafe impl Attribute for i8 {
     #[inline]
     fn get_type() -> AttributeType {
@@ -1395,14 +1517,14 @@ unsafe impl Attribute for cgmath::Matrix4<f64> {
 
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i8> {
+unsafe impl Attribute for nalgebra::Point1<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8
     }
 }
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i8> {
+unsafe impl Attribute for nalgebra::Point2<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8
@@ -1410,7 +1532,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i8> {
+unsafe impl Attribute for nalgebra::Point3<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8
@@ -1418,7 +1540,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i8> {
+unsafe impl Attribute for nalgebra::Point4<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8I8
@@ -1426,7 +1548,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i8> {
+unsafe impl Attribute for nalgebra::Vector1<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8
@@ -1434,7 +1556,7 @@ unsafe impl Attribute for nalgebra::Vec1<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i8> {
+unsafe impl Attribute for nalgebra::Vector2<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8
@@ -1442,7 +1564,7 @@ unsafe impl Attribute for nalgebra::Vec2<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i8> {
+unsafe impl Attribute for nalgebra::Vector3<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8
@@ -1450,7 +1572,7 @@ unsafe impl Attribute for nalgebra::Vec3<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i8> {
+unsafe impl Attribute for nalgebra::Vector4<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8I8
@@ -1458,7 +1580,7 @@ unsafe impl Attribute for nalgebra::Vec4<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u8> {
+unsafe impl Attribute for nalgebra::Point1<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8
@@ -1466,7 +1588,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u8> {
+unsafe impl Attribute for nalgebra::Point2<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8
@@ -1474,7 +1596,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u8> {
+unsafe impl Attribute for nalgebra::Point3<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8
@@ -1482,7 +1604,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u8> {
+unsafe impl Attribute for nalgebra::Point4<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8U8
@@ -1490,7 +1612,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u8> {
+unsafe impl Attribute for nalgebra::Vector1<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8
@@ -1498,7 +1620,7 @@ unsafe impl Attribute for nalgebra::Vec1<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u8> {
+unsafe impl Attribute for nalgebra::Vector2<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8
@@ -1506,7 +1628,7 @@ unsafe impl Attribute for nalgebra::Vec2<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u8> {
+unsafe impl Attribute for nalgebra::Vector3<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8
@@ -1514,7 +1636,7 @@ unsafe impl Attribute for nalgebra::Vec3<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u8> {
+unsafe impl Attribute for nalgebra::Vector4<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8U8
@@ -1522,7 +1644,7 @@ unsafe impl Attribute for nalgebra::Vec4<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i16> {
+unsafe impl Attribute for nalgebra::Point1<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16
@@ -1530,7 +1652,7 @@ unsafe impl Attribute for nalgebra::Pnt1<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i16> {
+unsafe impl Attribute for nalgebra::Point2<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16
@@ -1538,7 +1660,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i16> {
+unsafe impl Attribute for nalgebra::Point3<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16
@@ -1546,7 +1668,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i16> {
+unsafe impl Attribute for nalgebra::Point4<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16I16
@@ -1554,7 +1676,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i16> {
+unsafe impl Attribute for nalgebra::Vector1<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16
@@ -1562,7 +1684,7 @@ unsafe impl Attribute for nalgebra::Vec1<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i16> {
+unsafe impl Attribute for nalgebra::Vector2<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16
@@ -1570,7 +1692,7 @@ unsafe impl Attribute for nalgebra::Vec2<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i16> {
+unsafe impl Attribute for nalgebra::Vector3<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16
@@ -1578,7 +1700,7 @@ unsafe impl Attribute for nalgebra::Vec3<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i16> {
+unsafe impl Attribute for nalgebra::Vector4<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16I16
@@ -1586,7 +1708,7 @@ unsafe impl Attribute for nalgebra::Vec4<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u16> {
+unsafe impl Attribute for nalgebra::Point1<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16
@@ -1594,7 +1716,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u16> {
+unsafe impl Attribute for nalgebra::Point2<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16
@@ -1602,7 +1724,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u16> {
+unsafe impl Attribute for nalgebra::Point3<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16
@@ -1610,7 +1732,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u16> {
+unsafe impl Attribute for nalgebra::Point4<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16U16
@@ -1618,7 +1740,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u16> {
+unsafe impl Attribute for nalgebra::Vector1<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16
@@ -1626,7 +1748,7 @@ unsafe impl Attribute for nalgebra::Vec1<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u16> {
+unsafe impl Attribute for nalgebra::Vector2<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16
@@ -1634,7 +1756,7 @@ unsafe impl Attribute for nalgebra::Vec2<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u16> {
+unsafe impl Attribute for nalgebra::Vector3<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16
@@ -1642,7 +1764,7 @@ unsafe impl Attribute for nalgebra::Vec3<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u16> {
+unsafe impl Attribute for nalgebra::Vector4<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16U16
@@ -1650,7 +1772,7 @@ unsafe impl Attribute for nalgebra::Vec4<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i32> {
+unsafe impl Attribute for nalgebra::Point1<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32
@@ -1658,7 +1780,7 @@ unsafe impl Attribute for nalgebra::Pnt1<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i32> {
+unsafe impl Attribute for nalgebra::Point2<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32
@@ -1666,7 +1788,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i32> {
+unsafe impl Attribute for nalgebra::Point3<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32
@@ -1674,7 +1796,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i32> {
+unsafe impl Attribute for nalgebra::Point4<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32I32
@@ -1682,7 +1804,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i32> {
+unsafe impl Attribute for nalgebra::Vector1<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32
@@ -1690,7 +1812,7 @@ unsafe impl Attribute for nalgebra::Vec1<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i32> {
+unsafe impl Attribute for nalgebra::Vector2<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32
@@ -1698,7 +1820,7 @@ unsafe impl Attribute for nalgebra::Vec2<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i32> {
+unsafe impl Attribute for nalgebra::Vector3<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32
@@ -1706,7 +1828,7 @@ unsafe impl Attribute for nalgebra::Vec3<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i32> {
+unsafe impl Attribute for nalgebra::Vector4<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32I32
@@ -1714,7 +1836,7 @@ unsafe impl Attribute for nalgebra::Vec4<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u32> {
+unsafe impl Attribute for nalgebra::Point1<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32
@@ -1722,7 +1844,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u32> {
+unsafe impl Attribute for nalgebra::Point2<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32
@@ -1730,7 +1852,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u32> {
+unsafe impl Attribute for nalgebra::Point3<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32
@@ -1738,7 +1860,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u32> {
+unsafe impl Attribute for nalgebra::Point4<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32U32
@@ -1746,7 +1868,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u32> {
+unsafe impl Attribute for nalgebra::Vector1<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32
@@ -1754,7 +1876,7 @@ unsafe impl Attribute for nalgebra::Vec1<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u32> {
+unsafe impl Attribute for nalgebra::Vector2<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32
@@ -1762,7 +1884,7 @@ unsafe impl Attribute for nalgebra::Vec2<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u32> {
+unsafe impl Attribute for nalgebra::Vector3<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32
@@ -1770,7 +1892,7 @@ unsafe impl Attribute for nalgebra::Vec3<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u32> {
+unsafe impl Attribute for nalgebra::Vector4<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32U32
@@ -1778,7 +1900,7 @@ unsafe impl Attribute for nalgebra::Vec4<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i64> {
+unsafe impl Attribute for nalgebra::Point1<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64
@@ -1786,7 +1908,7 @@ unsafe impl Attribute for nalgebra::Pnt1<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i64> {
+unsafe impl Attribute for nalgebra::Point2<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64
@@ -1794,7 +1916,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i64> {
+unsafe impl Attribute for nalgebra::Point3<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64
@@ -1802,7 +1924,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i64> {
+unsafe impl Attribute for nalgebra::Point4<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64I64
@@ -1810,7 +1932,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i64> {
+unsafe impl Attribute for nalgebra::Vector1<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64
@@ -1818,7 +1940,7 @@ unsafe impl Attribute for nalgebra::Vec1<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i64> {
+unsafe impl Attribute for nalgebra::Vector2<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64
@@ -1826,7 +1948,7 @@ unsafe impl Attribute for nalgebra::Vec2<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i64> {
+unsafe impl Attribute for nalgebra::Vector3<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64
@@ -1834,7 +1956,7 @@ unsafe impl Attribute for nalgebra::Vec3<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i64> {
+unsafe impl Attribute for nalgebra::Vector4<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64I64
@@ -1842,7 +1964,7 @@ unsafe impl Attribute for nalgebra::Vec4<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u64> {
+unsafe impl Attribute for nalgebra::Point1<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64
@@ -1850,7 +1972,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u64> {
+unsafe impl Attribute for nalgebra::Point2<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64
@@ -1858,7 +1980,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u64> {
+unsafe impl Attribute for nalgebra::Point3<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64
@@ -1866,7 +1988,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u64> {
+unsafe impl Attribute for nalgebra::Point4<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64U64
@@ -1874,7 +1996,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u64> {
+unsafe impl Attribute for nalgebra::Vector1<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64
@@ -1882,7 +2004,7 @@ unsafe impl Attribute for nalgebra::Vec1<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u64> {
+unsafe impl Attribute for nalgebra::Vector2<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64
@@ -1890,7 +2012,7 @@ unsafe impl Attribute for nalgebra::Vec2<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u64> {
+unsafe impl Attribute for nalgebra::Vector3<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64
@@ -1898,7 +2020,7 @@ unsafe impl Attribute for nalgebra::Vec3<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u64> {
+unsafe impl Attribute for nalgebra::Vector4<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64U64
@@ -1906,7 +2028,7 @@ unsafe impl Attribute for nalgebra::Vec4<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<f32> {
+unsafe impl Attribute for nalgebra::Point1<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32
@@ -1914,7 +2036,7 @@ unsafe impl Attribute for nalgebra::Pnt1<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<f32> {
+unsafe impl Attribute for nalgebra::Point2<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32
@@ -1922,7 +2044,7 @@ unsafe impl Attribute for nalgebra::Pnt2<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<f32> {
+unsafe impl Attribute for nalgebra::Point3<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32
@@ -1930,7 +2052,7 @@ unsafe impl Attribute for nalgebra::Pnt3<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<f32> {
+unsafe impl Attribute for nalgebra::Point4<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32F32
@@ -1938,7 +2060,7 @@ unsafe impl Attribute for nalgebra::Pnt4<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<f32> {
+unsafe impl Attribute for nalgebra::Vector1<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32
@@ -1946,7 +2068,7 @@ unsafe impl Attribute for nalgebra::Vec1<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<f32> {
+unsafe impl Attribute for nalgebra::Vector2<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32
@@ -1954,7 +2076,7 @@ unsafe impl Attribute for nalgebra::Vec2<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<f32> {
+unsafe impl Attribute for nalgebra::Vector3<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32
@@ -1962,7 +2084,7 @@ unsafe impl Attribute for nalgebra::Vec3<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<f32> {
+unsafe impl Attribute for nalgebra::Vector4<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32F32
@@ -1970,7 +2092,7 @@ unsafe impl Attribute for nalgebra::Vec4<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat1<f32> {
+unsafe impl Attribute for nalgebra::Matrix1<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32
@@ -1978,7 +2100,7 @@ unsafe impl Attribute for nalgebra::Mat1<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat2<f32> {
+unsafe impl Attribute for nalgebra::Matrix2<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32x2x2
@@ -1986,7 +2108,7 @@ unsafe impl Attribute for nalgebra::Mat2<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat3<f32> {
+unsafe impl Attribute for nalgebra::Matrix3<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32x3x3
@@ -1994,7 +2116,7 @@ unsafe impl Attribute for nalgebra::Mat3<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat4<f32> {
+unsafe impl Attribute for nalgebra::Matrix4<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32x4x4
@@ -2002,7 +2124,7 @@ unsafe impl Attribute for nalgebra::Mat4<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<f64> {
+unsafe impl Attribute for nalgebra::Point1<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64
@@ -2010,7 +2132,7 @@ unsafe impl Attribute for nalgebra::Pnt1<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<f64> {
+unsafe impl Attribute for nalgebra::Point2<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64
@@ -2018,7 +2140,7 @@ unsafe impl Attribute for nalgebra::Pnt2<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<f64> {
+unsafe impl Attribute for nalgebra::Point3<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64
@@ -2026,7 +2148,7 @@ unsafe impl Attribute for nalgebra::Pnt3<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<f64> {
+unsafe impl Attribute for nalgebra::Point4<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64F64
@@ -2034,7 +2156,7 @@ unsafe impl Attribute for nalgebra::Pnt4<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<f64> {
+unsafe impl Attribute for nalgebra::Vector1<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64
@@ -2042,7 +2164,7 @@ unsafe impl Attribute for nalgebra::Vec1<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<f64> {
+unsafe impl Attribute for nalgebra::Vector2<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64
@@ -2050,7 +2172,7 @@ unsafe impl Attribute for nalgebra::Vec2<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<f64> {
+unsafe impl Attribute for nalgebra::Vector3<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64
@@ -2058,7 +2180,7 @@ unsafe impl Attribute for nalgebra::Vec3<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<f64> {
+unsafe impl Attribute for nalgebra::Vector4<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64F64
@@ -2066,7 +2188,7 @@ unsafe impl Attribute for nalgebra::Vec4<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat1<f64> {
+unsafe impl Attribute for nalgebra::Matrix1<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64
@@ -2074,7 +2196,7 @@ unsafe impl Attribute for nalgebra::Mat1<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat2<f64> {
+unsafe impl Attribute for nalgebra::Matrix2<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64x2x2
@@ -2082,7 +2204,7 @@ unsafe impl Attribute for nalgebra::Mat2<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat3<f64> {
+unsafe impl Attribute for nalgebra::Matrix3<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64x3x3
@@ -2090,7 +2212,7 @@ unsafe impl Attribute for nalgebra::Mat3<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat4<f64> {
+unsafe impl Attribute for nalgebra::Matrix4<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64x4x4
@@ -2098,6 +2220,481 @@ unsafe impl Attribute for nalgebra::Mat4<f64> {
 }
 
 
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix2<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x2x2
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix3<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x3x3
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix4<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x4x4
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix2<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64x2x2
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix3<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64x3x3
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix4<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64x4x4
+    }
+}
+
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec2 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec3 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec4 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat2 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x2x2
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat3 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x3x3
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat4 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x4x4
+    }
+}
+
+
+#[cfg(feature="half")]
+unsafe impl Attribute for half::f16 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16
+    }
+}
+
+#[cfg(feature="half")]
+unsafe impl Attribute for (half::f16, half::f16) {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16
+    }
+}
+
+#[cfg(feature="half")]
+unsafe impl Attribute for [half::f16; 2] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16
+    }
+}
+
+#[cfg(feature="half")]
+unsafe impl Attribute for (half::f16, half::f16, half::f16) {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16
+    }
+}
+
+#[cfg(feature="half")]
+unsafe impl Attribute for [half::f16; 3] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16
+    }
+}
+
+#[cfg(feature="half")]
+unsafe impl Attribute for (half::f16, half::f16, half::f16, half::f16) {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16F16
+    }
+}
+
+#[cfg(feature="half")]
+unsafe impl Attribute for [half::f16; 4] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16F16
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -2111,11 +2708,11 @@ mod tests {
         }}
     }
 
-    #[cfg(feature = "nalgebra")]
-    macro_rules! test_layout_ref {
-        ($from_ref:path, $ety:ty, $ncomps:expr, $literal:expr) => {{
-            let arr: &[$ety; $ncomps] = unsafe { mem::transmute($from_ref(&$literal)) };
-            assert_eq!(*arr, $literal);
+    #[cfg(any(feature = "nalgebra", feature = "mint", feature = "glam"))]
+    macro_rules! test_layout_new {
+        ($value:expr, $ety:ty, $ncomps:expr, $literal:expr) => {{
+            let arr: [$ety; $ncomps] = unsafe { mem::transmute($value) };
+            assert_eq!(arr, $literal);
         }}
     }
 
@@ -2194,94 +2791,146 @@ mod tests {
     fn test_nalgebra_layout() {
         use nalgebra;
 
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u8, 1, [0u8]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u8, 2, [0u8, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u8, 3, [0u8, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u8, 4, [0u8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i8, 1, [0i8]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i8, 2, [0i8, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i8, 3, [0i8, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i8, 4, [0i8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u8, 1, [0u8]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u8, 2, [0u8, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u8, 3, [0u8, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u8, 4, [0u8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i8, 1, [0i8]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i8, 2, [0i8, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i8, 3, [0i8, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i8, 4, [0i8, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u16, 1, [0u16]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u16, 2, [0u16, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u16, 3, [0u16, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u16, 4, [0u16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i16, 1, [0i16]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i16, 2, [0i16, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i16, 3, [0i16, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i16, 4, [0i16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u16, 1, [0u16]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u16, 2, [0u16, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u16, 3, [0u16, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u16, 4, [0u16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i16, 1, [0i16]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i16, 2, [0i16, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i16, 3, [0i16, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i16, 4, [0i16, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u32, 1, [0u32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u32, 2, [0u32, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u32, 3, [0u32, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u32, 4, [0u32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i32, 1, [0i32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i32, 2, [0i32, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i32, 3, [0i32, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i32, 4, [0i32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u32, 1, [0u32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u32, 2, [0u32, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u32, 3, [0u32, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u32, 4, [0u32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i32, 1, [0i32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i32, 2, [0i32, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i32, 3, [0i32, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i32, 4, [0i32, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, f32, 1, [0.0f32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, f32, 2, [0.0f32, 1.0]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, f32, 3, [0.0f32, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, f64, 1, [0.0f64]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, f64, 2, [0.0f64, 1.0]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, f64, 3, [0.0f64, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, f32, 1, [0.0f32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, f32, 2, [0.0f32, 1.0]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, f32, 3, [0.0f32, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, f64, 1, [0.0f64]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, f64, 2, [0.0f64, 1.0]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, f64, 3, [0.0f64, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
-
-        test_layout_ref!(nalgebra::Mat1::from_array_ref, [f32; 1], 1, [[0.0f32]]);
-        test_layout_ref!(nalgebra::Mat2::from_array_ref, [f32; 2], 2, [[0.0f32, 1.0],
-                                                                       [2.0f32, 3.0]]);
-        test_layout_ref!(nalgebra::Mat3::from_array_ref, [f32; 3], 3, [[0.0f32, 1.0, 2.0],
-                                                                       [3.0f32, 4.0, 5.0],
-                                                                       [6.0f32, 7.0, 8.0]]);
-        test_layout_ref!(nalgebra::Mat4::from_array_ref, [f32; 4], 4, [[0.0f32, 1.0, 2.0, 3.0],
-                                                                       [4.0f32, 5.0, 6.0, 7.0],
-                                                                       [8.0f32, 9.0, 10.0, 11.0],
-                                                                       [12.0f32, 13.0, 14.0, 15.0]]);
-
-        test_layout_ref!(nalgebra::Mat1::from_array_ref, [f64; 1], 1, [[0.0f64]]);
-        test_layout_ref!(nalgebra::Mat2::from_array_ref, [f64; 2], 2, [[0.0f64, 1.0],
-                                                                       [2.0f64, 3.0]]);
-        test_layout_ref!(nalgebra::Mat3::from_array_ref, [f64; 3], 3, [[0.0f64, 1.0, 2.0],
-                                                                       [3.0f64, 4.0, 5.0],
-                                                                       [6.0f64, 7.0, 8.0]]);
-        test_layout_ref!(nalgebra::Mat4::from_array_ref, [f64; 4], 4, [[0.0f64, 1.0, 2.0, 3.0],
-                                                                       [4.0f64, 5.0, 6.0, 7.0],
-                                                                       [8.0f64, 9.0, 10.0, 11.0],
-                                                                       [12.0f64, 13.0, 14.0, 15.0]]);
+        test_layout_new!(nalgebra::Vector1::new(0u8), u8, 1, [0u8]);
+        test_layout_new!(nalgebra::Vector2::new(0u8, 1), u8, 2, [0u8, 1]);
+        test_layout_new!(nalgebra::Vector3::new(0u8, 1, 2), u8, 3, [0u8, 1, 2]);
+        test_layout_new!(nalgebra::Vector4::new(0u8, 1, 2, 3), u8, 4, [0u8, 1, 2, 3]);
+        test_layout_new!(nalgebra::Vector1::new(0i8), i8, 1, [0i8]);
+        test_layout_new!(nalgebra::Vector2::new(0i8, 1), i8, 2, [0i8, 1]);
+        test_layout_new!(nalgebra::Vector3::new(0i8, 1, 2), i8, 3, [0i8, 1, 2]);
+        test_layout_new!(nalgebra::Vector4::new(0i8, 1, 2, 3), i8, 4, [0i8, 1, 2, 3]);
+        test_layout_new!(nalgebra::Point1::new(0u8), u8, 1, [0u8]);
+        test_layout_new!(nalgebra::Point2::new(0u8, 1), u8, 2, [0u8, 1]);
+        test_layout_new!(nalgebra::Point3::new(0u8, 1, 2), u8, 3, [0u8, 1, 2]);
+        test_layout_new!(nalgebra::Point4::new(0u8, 1, 2, 3), u8, 4, [0u8, 1, 2, 3]);
+        test_layout_new!(nalgebra::Point1::new(0i8), i8, 1, [0i8]);
+        test_layout_new!(nalgebra::Point2::new(0i8, 1), i8, 2, [0i8, 1]);
+        test_layout_new!(nalgebra::Point3::new(0i8, 1, 2), i8, 3, [0i8, 1, 2]);
+        test_layout_new!(nalgebra::Point4::new(0i8, 1, 2, 3), i8, 4, [0i8, 1, 2, 3]);
+
+        test_layout_new!(nalgebra::Vector1::new(0u16), u16, 1, [0u16]);
+        test_layout_new!(nalgebra::Vector2::new(0u16, 1), u16, 2, [0u16, 1]);
+        test_layout_new!(nalgebra::Vector3::new(0u16, 1, 2), u16, 3, [0u16, 1, 2]);
+        test_layout_new!(nalgebra::Vector4::new(0u16, 1, 2, 3), u16, 4, [0u16, 1, 2, 3]);
+        test_layout_new!(nalgebra::Vector1::new(0i16), i16, 1, [0i16]);
+        test_layout_new!(nalgebra::Vector2::new(0i16, 1), i16, 2, [0i16, 1]);
+        test_layout_new!(nalgebra::Vector3::new(0i16, 1, 2), i16, 3, [0i16, 1, 2]);
+        test_layout_new!(nalgebra::Vector4::new(0i16, 1, 2, 3), i16, 4, [0i16, 1, 2, 3]);
+        test_layout_new!(nalgebra::Point1::new(0u16), u16, 1, [0u16]);
+        test_layout_new!(nalgebra::Point2::new(0u16, 1), u16, 2, [0u16, 1]);
+        test_layout_new!(nalgebra::Point3::new(0u16, 1, 2), u16, 3, [0u16, 1, 2]);
+        test_layout_new!(nalgebra::Point4::new(0u16, 1, 2, 3), u16, 4, [0u16, 1, 2, 3]);
+        test_layout_new!(nalgebra::Point1::new(0i16), i16, 1, [0i16]);
+        test_layout_new!(nalgebra::Point2::new(0i16, 1), i16, 2, [0i16, 1]);
+        test_layout_new!(nalgebra::Point3::new(0i16, 1, 2), i16, 3, [0i16, 1, 2]);
+        test_layout_new!(nalgebra::Point4::new(0i16, 1, 2, 3), i16, 4, [0i16, 1, 2, 3]);
+
+        test_layout_new!(nalgebra::Vector1::new(0u32), u32, 1, [0u32]);
+        test_layout_new!(nalgebra::Vector2::new(0u32, 1), u32, 2, [0u32, 1]);
+        test_layout_new!(nalgebra::Vector3::new(0u32, 1, 2), u32, 3, [0u32, 1, 2]);
+        test_layout_new!(nalgebra::Vector4::new(0u32, 1, 2, 3), u32, 4, [0u32, 1, 2, 3]);
+        test_layout_new!(nalgebra::Vector1::new(0i32), i32, 1, [0i32]);
+        test_layout_new!(nalgebra::Vector2::new(0i32, 1), i32, 2, [0i32, 1]);
+        test_layout_new!(nalgebra::Vector3::new(0i32, 1, 2), i32, 3, [0i32, 1, 2]);
+        test_layout_new!(nalgebra::Vector4::new(0i32, 1, 2, 3), i32, 4, [0i32, 1, 2, 3]);
+        test_layout_new!(nalgebra::Point1::new(0u32), u32, 1, [0u32]);
+        test_layout_new!(nalgebra::Point2::new(0u32, 1), u32, 2, [0u32, 1]);
+        test_layout_new!(nalgebra::Point3::new(0u32, 1, 2), u32, 3, [0u32, 1, 2]);
+        test_layout_new!(nalgebra::Point4::new(0u32, 1, 2, 3), u32, 4, [0u32, 1, 2, 3]);
+        test_layout_new!(nalgebra::Point1::new(0i32), i32, 1, [0i32]);
+        test_layout_new!(nalgebra::Point2::new(0i32, 1), i32, 2, [0i32, 1]);
+        test_layout_new!(nalgebra::Point3::new(0i32, 1, 2), i32, 3, [0i32, 1, 2]);
+        test_layout_new!(nalgebra::Point4::new(0i32, 1, 2, 3), i32, 4, [0i32, 1, 2, 3]);
+
+        test_layout_new!(nalgebra::Vector1::new(0.0f32), f32, 1, [0.0f32]);
+        test_layout_new!(nalgebra::Vector2::new(0.0f32, 1.0), f32, 2, [0.0f32, 1.0]);
+        test_layout_new!(nalgebra::Vector3::new(0.0f32, 1.0, 2.0), f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_new!(nalgebra::Vector4::new(0.0f32, 1.0, 2.0, 3.0),
+                         f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_new!(nalgebra::Vector1::new(0.0f64), f64, 1, [0.0f64]);
+        test_layout_new!(nalgebra::Vector2::new(0.0f64, 1.0), f64, 2, [0.0f64, 1.0]);
+        test_layout_new!(nalgebra::Vector3::new(0.0f64, 1.0, 2.0), f64, 3, [0.0f64, 1.0, 2.0]);
+        test_layout_new!(nalgebra::Vector4::new(0.0f64, 1.0, 2.0, 3.0),
+                         f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
+        test_layout_new!(nalgebra::Point1::new(0.0f32), f32, 1, [0.0f32]);
+        test_layout_new!(nalgebra::Point2::new(0.0f32, 1.0), f32, 2, [0.0f32, 1.0]);
+        test_layout_new!(nalgebra::Point3::new(0.0f32, 1.0, 2.0), f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_new!(nalgebra::Point4::new(0.0f32, 1.0, 2.0, 3.0),
+                         f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_new!(nalgebra::Point1::new(0.0f64), f64, 1, [0.0f64]);
+        test_layout_new!(nalgebra::Point2::new(0.0f64, 1.0), f64, 2, [0.0f64, 1.0]);
+        test_layout_new!(nalgebra::Point3::new(0.0f64, 1.0, 2.0), f64, 3, [0.0f64, 1.0, 2.0]);
+        test_layout_new!(nalgebra::Point4::new(0.0f64, 1.0, 2.0, 3.0),
+                         f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
+
+        test_layout_new!(nalgebra::Matrix1::new(0.0f32),
+                         [f32; 1], 1, [[0.0f32]]);
+        test_layout_new!(nalgebra::Matrix2::new(0.0f32, 2.0,
+                                                1.0, 3.0),
+                         [f32; 2], 2, [[0.0f32, 1.0],
+                                       [2.0f32, 3.0]]);
+        test_layout_new!(nalgebra::Matrix3::new(0.0f32, 3.0, 6.0,
+                                                1.0, 4.0, 7.0,
+                                                2.0, 5.0, 8.0),
+                         [f32; 3], 3, [[0.0f32, 1.0, 2.0],
+                                       [3.0f32, 4.0, 5.0],
+                                       [6.0f32, 7.0, 8.0]]);
+        test_layout_new!(nalgebra::Matrix4::new(0.0f32, 4.0, 8.0, 12.0,
+                                                1.0, 5.0, 9.0, 13.0,
+                                                2.0, 6.0, 10.0, 14.0,
+                                                3.0, 7.0, 11.0, 15.0),
+                         [f32; 4], 4, [[0.0f32, 1.0, 2.0, 3.0],
+                                       [4.0f32, 5.0, 6.0, 7.0],
+                                       [8.0f32, 9.0, 10.0, 11.0],
+                                       [12.0f32, 13.0, 14.0, 15.0]]);
+
+        test_layout_new!(nalgebra::Matrix1::new(0.0f64),
+                         [f64; 1], 1, [[0.0f64]]);
+        test_layout_new!(nalgebra::Matrix2::new(0.0f64, 2.0,
+                                                1.0, 3.0),
+                         [f64; 2], 2, [[0.0f64, 1.0],
+                                       [2.0f64, 3.0]]);
+        test_layout_new!(nalgebra::Matrix3::new(0.0f64, 3.0, 6.0,
+                                                1.0, 4.0, 7.0,
+                                                2.0, 5.0, 8.0),
+                         [f64; 3], 3, [[0.0f64, 1.0, 2.0],
+                                       [3.0f64, 4.0, 5.0],
+                                       [6.0f64, 7.0, 8.0]]);
+        test_layout_new!(nalgebra::Matrix4::new(0.0f64, 4.0, 8.0, 12.0,
+                                                1.0, 5.0, 9.0, 13.0,
+                                                2.0, 6.0, 10.0, 14.0,
+                                                3.0, 7.0, 11.0, 15.0),
+                         [f64; 4], 4, [[0.0f64, 1.0, 2.0, 3.0],
+                                       [4.0f64, 5.0, 6.0, 7.0],
+                                       [8.0f64, 9.0, 10.0, 11.0],
+                                       [12.0f64, 13.0, 14.0, 15.0]]);
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn test_mint_layout() {
+        use mint;
+
+        test_layout_new!(mint::Vector2 { x: 0u8, y: 1 }, u8, 2, [0u8, 1]);
+        test_layout_new!(mint::Vector3 { x: 0i16, y: 1, z: 2 }, i16, 3, [0i16, 1, 2]);
+        test_layout_new!(mint::Vector4 { x: 0u32, y: 1, z: 2, w: 3 }, u32, 4, [0u32, 1, 2, 3]);
+        test_layout_new!(mint::Point2 { x: 0.0f32, y: 1.0 }, f32, 2, [0.0f32, 1.0]);
+        test_layout_new!(mint::Point3 { x: 0.0f64, y: 1.0, z: 2.0 }, f64, 3, [0.0f64, 1.0, 2.0]);
+
+        let matrix: mint::ColumnMatrix2<f32> = [[0.0f32, 1.0], [2.0, 3.0]].into();
+        test_layout_new!(matrix, [f32; 2], 2, [[0.0f32, 1.0], [2.0f32, 3.0]]);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_glam_layout() {
+        use glam;
+
+        test_layout_new!(glam::Vec2::new(0.0, 1.0), f32, 2, [0.0f32, 1.0]);
+        test_layout_new!(glam::Vec3::new(0.0, 1.0, 2.0), f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_new!(glam::Vec4::new(0.0, 1.0, 2.0, 3.0), f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+
+        let columns = [[0.0f32, 1.0, 2.0], [3.0f32, 4.0, 5.0], [6.0f32, 7.0, 8.0]];
+        test_layout_new!(glam::Mat3::from_cols_array_2d(&columns), [f32; 3], 3, columns);
     }
 }
@@ -26,6 +26,61 @@ implement_vertex!(MyVertex, position, texcoords);
 # }
 ```
 
+If you enable the `derive` feature of glium, you can also use `#[derive(Vertex)]`, which lets you
+rename the attributes, normalize them or give a divisor to the instances. See the documentation
+of the `glium_derive` crate for the list of options.
+
+```ignore
+#[derive(Copy, Clone, Vertex)]
+struct MyVertex {
+    position: [f32; 3],
+    #[glium(name = "tex_coords")]
+    texcoords: [f32; 2],
+}
+```
+
+The shader decides how each attribute is read:
+
+ - `float`, `vec` and `mat` attributes accept any type of data except 64-bits integers. Integers
+   are converted to floating-point, and normalized if you ask for it.
+ - `int`, `ivec`, `uint` and `uvec` attributes require integer data that isn't normalized, and
+   receive it without any conversion.
+ - `double`, `dvec` and `dmat` attributes require `f64` data.
+
+Drawing with a vertex format that doesn't match the attributes of the program panics.
+
+## Packed attributes
+
+Vertex attributes are often the biggest part of the data that the GPU reads when drawing.
+Storing positions and texture coordinates as half-precision floats, and normals and tangents
+as packed 10 bits values, halves the size of a typical vertex compared to `f32`s.
+
+The packed types must be read as `vec4`s by the shader. Enable `normalize` in
+`implement_vertex!` to receive values in the `[-1.0, 1.0]` or `[0.0, 1.0]` ranges.
+
+```
+# #[macro_use] extern crate glium;
+# fn main() {
+use glium::vertex::{F16, I2I10I10I10Reversed};
+
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [F16; 3],
+    tex_coords: [F16; 2],
+    normal: I2I10I10I10Reversed,
+}
+
+implement_vertex!(Vertex, position normalize(false), tex_coords normalize(false),
+                  normal normalize(true));
+
+let vertex = Vertex {
+    position: F16::array([0.5, 2.0, -1.0]),
+    tex_coords: F16::array([0.0, 1.0]),
+    normal: I2I10I10I10Reversed::from_normalized([0.0, 1.0, 0.0, 0.0]),
+};
+# }
+```
+
 ## Vertex buffer
 
 Once you have a struct that implements the `Vertex` trait, you can build an array of vertices and
@@ -59,6 +114,31 @@ let data = &[
 let vertex_buffer = glium::vertex::VertexBuffer::new(&display, data);
 ```
 
+Buffers whose content changes often should be created with `VertexBuffer::dynamic`. You can
+then update only a part of them, either with a slice of vertices or with an iterator.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# #[derive(Copy, Clone)]
+# struct MyVertex {
+#     position: [f32; 3],
+# }
+# impl glium::vertex::Vertex for MyVertex {
+#     fn build_bindings() -> glium::vertex::VertexFormat { unimplemented!() }
+# }
+# let mut vertex_buffer: glium::vertex::VertexBuffer<MyVertex> = unsafe { ::std::mem::uninitialized() };
+// replaces the vertices 4 and 5
+vertex_buffer.slice_mut(4 .. 6).unwrap().write(&[
+    MyVertex { position: [0.0, 1.0, 0.0] },
+    MyVertex { position: [1.0, 1.0, 0.0] },
+]);
+
+// moves the first 100 vertices without building a `Vec`
+vertex_buffer.slice_mut(0 .. 100).unwrap().update_from_iter((0 .. 100).map(|i| {
+    MyVertex { position: [i as f32, 0.0, 0.0] }
+}));
+```
+
 ## Drawing
 
 When you draw, you can pass either a single vertex source or a tuple of multiple sources.
@@ -69,8 +149,13 @@ Each source can be:
  - A vertex buffer where each element corresponds to an instance, by
    caling `vertex_buffer.per_instance()`.
  - The same with a slice, by calling `vertex_buffer.slice(start .. end).unwrap().per_instance()`.
+ - Any range of any buffer, with a format and a stride given separately, with a
+   `glium::vertex::BufferVertices`.
  - A marker indicating a number of vertex sources, with `glium::vertex::EmptyVertexAttributes`.
  - A marker indicating a number of instances, with `glium::vertex::EmptyInstanceAttributes`.
+ - A number of vertices and a type of primitives, with `glium::vertex::NoVertices`, which is
+   also passed as the indices. This is the simplest way to draw when the shaders compute the
+   vertices themselves.
 
 ```no_run
 # use glium::Surface;
@@ -85,6 +170,7 @@ Each source can be:
 # let uniforms = glium::uniforms::EmptyUniforms;
 # let vertex_buffer: glium::vertex::VertexBuffer<MyVertex> = unsafe { ::std::mem::uninitialized() };
 # let vertex_buffer2: glium::vertex::VertexBuffer<MyVertex> = unsafe { ::std::mem::uninitialized() };
+# let vertex_buffer3: glium::vertex::VertexBufferAny = unsafe { ::std::mem::uninitialized() };
 # let mut frame = display.draw();
 // drawing with a single vertex buffer
 frame.draw(&vertex_buffer, &indices, &program, &uniforms, &Default::default()).unwrap();
@@ -109,6 +195,16 @@ frame.draw((vertex_buffer.slice(6 .. 24).unwrap(), vertex_buffer2.slice(128 .. 1
 frame.draw((&vertex_buffer, vertex_buffer2.per_instance().unwrap()), &indices,
            &program, &uniforms, &Default::default()).unwrap();
 
+// using each element of `vertex_buffer2` for 4 consecutive instances
+frame.draw((&vertex_buffer, vertex_buffer2.per_instance().unwrap().divisor(4)), &indices,
+           &program, &uniforms, &Default::default()).unwrap();
+
+// 32 instances, with one element of `vertex_buffer2` per instance and one element of
+// `vertex_buffer3` every 8 instances, both read from slices
+frame.draw((&vertex_buffer, vertex_buffer2.slice(0 .. 32).unwrap().per_instance().unwrap(),
+            vertex_buffer3.slice(4 .. 8).unwrap().per_instance().unwrap().divisor(8)),
+           &indices, &program, &uniforms, &Default::default()).unwrap();
+
 // instancing without any per-instance attribute
 frame.draw((&vertex_buffer, glium::vertex::EmptyInstanceAttributes { len: 36 }), &indices,
            &program, &uniforms, &Default::default()).unwrap();
@@ -117,8 +213,20 @@ frame.draw((&vertex_buffer, glium::vertex::EmptyInstanceAttributes { len: 36 }),
 Note that if you use `index::EmptyIndices` as indices the length of all vertex sources must
 be the same, or a `DrawError::VerticesSourcesLengthMismatch` will be produced.
 
-In all situation, the length of all per-instance sources must match, or
-`DrawError::InstancesCountMismatch` will be retured.
+The per-instance sources can have different divisors, and can be slices of `VertexBuffer`s,
+`VertexBufferAny`s or `BufferVertices`. The number of instances that are drawn is the smallest
+number of instances covered by a source, which is its length multiplied by its divisor. The
+other sources can only cover more instances if the last element is partially used, for example
+a source of 13 elements with a divisor of 8 for 100 instances. Otherwise a
+`DrawError::InstancesCountMismatch` is returned.
+
+## Runtime vertex formats
+
+When the layout of the vertices is only known at runtime, for example when loading a mesh from a
+file, build the format with a `VertexFormatBuilder` and the buffer with
+`VertexBufferAny::new_raw`. If the vertices are in a buffer that you manage yourself, for
+example a big buffer shared between many meshes, use a `BufferVertices` to draw from any range
+of it.
 
 # Transform feedback
 
@@ -132,20 +240,27 @@ The program you use when drawing must be the same as you the one you created the
 with, or else you will get an error.
 
 */
-use std::iter::Chain;
+use std::iter::{Chain, Cloned};
 use std::option::IntoIter;
+use std::slice::Iter as SliceIter;
 
-pub use self::buffer::{VertexBuffer, VertexBufferAny};
+pub use self::buffer::{VertexBuffer, VertexBufferAny, BufferVertices};
 pub use self::buffer::VertexBufferSlice;
 pub use self::buffer::CreationError as BufferCreationError;
-pub use self::format::{AttributeType, VertexFormat};
+#[doc(hidden)]
+pub use self::buffer::is_instancing_supported;
+pub use self::format::{AttributeType, VertexFormat, VertexFormatBuilder};
+pub use self::packed::{F16, F16Array, I2I10I10I10Reversed, U2U10U10U10Reversed};
 pub use self::transform_feedback::{is_transform_feedback_supported, TransformFeedbackSession};
+pub use self::transform_feedback::TransformFeedbackSessionCreationError;
 
 use buffer::BufferAnySlice;
+use index::{IndicesSource, PrimitiveType};
 use CapabilitiesSource;
 
 mod buffer;
 mod format;
+mod packed;
 mod transform_feedback;
 
 /// Describes the source to use for the vertices when drawing.
@@ -155,9 +270,9 @@ pub enum VerticesSource<'a> {
     ///
     /// The second parameter is the number of vertices in the buffer.
     ///
-    /// The third parameter tells whether or not this buffer is "per instance" (true) or
-    /// "per vertex" (false).
-    VertexBuffer(BufferAnySlice<'a>, &'a VertexFormat, bool),
+    /// The third parameter is `None` if this buffer is "per vertex", or the number of
+    /// consecutive instances that use each element if it is "per instance".
+    VertexBuffer(BufferAnySlice<'a>, &'a VertexFormat, Option<u32>),
 
     /// A marker indicating a "phantom list of attributes".
     Marker {
@@ -183,6 +298,7 @@ impl<'a> IntoVerticesSource<'a> for VerticesSource<'a> {
 }
 
 /// Marker that can be passed instead of a buffer to indicate an empty list of buffers.
+#[derive(Copy, Clone, Debug)]
 pub struct EmptyVertexAttributes {
     /// Number of phantom vertices.
     pub len: usize,
@@ -196,6 +312,7 @@ impl<'a> IntoVerticesSource<'a> for EmptyVertexAttributes {
 }
 
 /// Marker that can be passed instead of a buffer to indicate an empty list of buffers.
+#[derive(Copy, Clone, Debug)]
 pub struct EmptyInstanceAttributes {
     /// Number of phantom vertices.
     pub len: usize,
@@ -208,13 +325,75 @@ impl<'a> IntoVerticesSource<'a> for EmptyInstanceAttributes {
     }
 }
 
+/// Draws a number of vertices without any vertex attribute, assembled into primitives of the
+/// given type.
+///
+/// It must be passed both as the vertex source and as the indices. The shaders compute the
+/// vertices themselves, usually from `gl_VertexID`, for example to draw a fullscreen triangle or
+/// to read the vertices from a shader storage buffer. The program must not have any attribute,
+/// except for per-instance sources passed alongside.
+///
+/// ```no_run
+/// # use glium::Surface;
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+/// # let mut frame = display.draw();
+/// use glium::index::PrimitiveType;
+/// use glium::vertex::NoVertices;
+///
+/// let fullscreen = NoVertices(PrimitiveType::TrianglesList, 3);
+/// frame.draw(fullscreen, fullscreen, &program, &glium::uniforms::EmptyUniforms,
+///            &Default::default()).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct NoVertices(pub PrimitiveType, pub usize);
+
+impl<'a> IntoVerticesSource<'a> for NoVertices {
+    #[inline]
+    fn into_vertices_source(self) -> VerticesSource<'a> {
+        VerticesSource::Marker { len: self.1, per_instance: false }
+    }
+}
+
+impl<'a> From<NoVertices> for IndicesSource<'a> {
+    #[inline]
+    fn from(marker: NoVertices) -> IndicesSource<'a> {
+        IndicesSource::NoIndices {
+            primitives: marker.0
+        }
+    }
+}
+
+impl<'a, 'b> From<&'b NoVertices> for IndicesSource<'a> {
+    #[inline]
+    fn from(marker: &'b NoVertices) -> IndicesSource<'a> {
+        IndicesSource::NoIndices {
+            primitives: marker.0
+        }
+    }
+}
+
 /// Marker that instructs glium that the buffer is to be used per instance.
-pub struct PerInstance<'a>(BufferAnySlice<'a>, &'a VertexFormat);
+pub struct PerInstance<'a>(BufferAnySlice<'a>, &'a VertexFormat, u32);
+
+impl<'a> PerInstance<'a> {
+    /// Uses each element of the buffer for `divisor` consecutive instances instead of one.
+    /// The number of instances drawn is the number of elements multiplied by the divisor.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `divisor` is 0.
+    #[inline]
+    pub fn divisor(self, divisor: u32) -> PerInstance<'a> {
+        assert!(divisor != 0);
+        PerInstance(self.0, self.1, divisor)
+    }
+}
 
 impl<'a> IntoVerticesSource<'a> for PerInstance<'a> {
     #[inline]
     fn into_vertices_source(self) -> VerticesSource<'a> {
-        VerticesSource::VertexBuffer(self.0, self.1, true)
+        VerticesSource::VertexBuffer(self.0, self.1, Some(self.2))
     }
 }
 
@@ -238,6 +417,15 @@ impl<'a, T> MultiVerticesSource<'a> for T
     }
 }
 
+impl<'a, 'b> MultiVerticesSource<'a> for &'b [VerticesSource<'a>] {
+    type Iterator = Cloned<SliceIter<'b, VerticesSource<'a>>>;
+
+    #[inline]
+    fn iter(self) -> Cloned<SliceIter<'b, VerticesSource<'a>>> {
+        <[VerticesSource<'a>]>::iter(self).cloned()
+    }
+}
+
 macro_rules! impl_for_tuple {
     ($t:ident) => (
         impl<'a, $t> MultiVerticesSource<'a> for ($t,)
@@ -298,12 +486,19 @@ impl_for_tuple!(A, B, C, D, E, F, G);
 /// Trait for structures that represent a vertex.
 ///
 /// Instead of implementing this trait yourself, it is recommended to use the `implement_vertex!`
-/// macro instead.
+/// macro or `#[derive(Vertex)]` instead.
 // TODO: this should be `unsafe`, but that would break the syntax extension
 pub trait Vertex: Copy + Sized {
     /// Builds the `VertexFormat` representing the layout of this element.
     fn build_bindings() -> VertexFormat;
 
+    /// Returns the number of consecutive instances that use each element of a buffer of this
+    /// type when it is used per instance. The default is 1.
+    #[inline]
+    fn instance_divisor() -> u32 {
+        1
+    }
+
     /// Returns true if the backend supports this vertex format.
     fn is_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
         let format = Self::build_bindings();
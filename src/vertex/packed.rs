@@ -0,0 +1,378 @@
+use std::fmt;
+
+#[cfg(feature = "half")]
+use half;
+
+use vertex::{Attribute, AttributeType};
+
+/// A half-precision floating-point value.
+///
+/// Corresponds to `GL_HALF_FLOAT`. Besides vertex attributes, it can be used as the components
+/// of the pixels of a texture, for example `(F16, F16, F16, F16)`, and as a uniform value, which
+/// is converted to a `float`.
+///
+/// If the `half` feature of glium is enabled, it converts to and from `half::f16`, which can be
+/// used in the same places.
+#[repr(C)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct F16(pub u16);
+
+impl F16 {
+    /// Converts a `f32`, rounding to the nearest value. Values that are too large become
+    /// infinite.
+    pub fn from_f32(value: f32) -> F16 {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let mantissa = bits & 0x7fffff;
+
+        // infinity and NaN
+        if exponent == 0xff {
+            return F16(sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 });
+        }
+
+        let exponent = exponent - 127 + 15;
+
+        if exponent >= 0x1f {
+            return F16(sign | 0x7c00);
+        }
+
+        // the value is too small for a normal half, the implicit bit becomes explicit
+        let (mantissa, shift, exponent) = if exponent <= 0 {
+            (mantissa | 0x800000, (14 - exponent) as u32, 0)
+        } else {
+            (mantissa, 13, exponent as u32)
+        };
+
+        if shift > 24 {
+            return F16(sign);
+        }
+
+        // rounding to nearest, ties to even ; a carry into the exponent gives the right result
+        let round_bit = 1 << (shift - 1);
+        let mut result = (exponent << 10) + (mantissa >> shift);
+        if (mantissa & round_bit) != 0 && (mantissa & ((round_bit - 1) | (round_bit << 1))) != 0 {
+            result += 1;
+        }
+
+        F16(sign | result as u16)
+    }
+
+    /// Converts to a `f32`. The conversion is exact.
+    pub fn to_f32(self) -> f32 {
+        let sign = ((self.0 & 0x8000) as u32) << 16;
+        let exponent = ((self.0 >> 10) & 0x1f) as u32;
+        let mantissa = (self.0 & 0x3ff) as u32;
+
+        match exponent {
+            0 => {
+                let value = mantissa as f32 / (1 << 24) as f32;
+                if sign != 0 { -value } else { value }
+            },
+            0x1f => f32::from_bits(sign | 0x7f800000 | (mantissa << 13)),
+            _ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)),
+        }
+    }
+
+    /// Converts an array of `f32`s, for example `F16::array([0.0, 1.0])`.
+    #[inline]
+    pub fn array<A>(values: A) -> A::Output where A: F16Array {
+        values.to_f16()
+    }
+}
+
+impl From<f32> for F16 {
+    #[inline]
+    fn from(value: f32) -> F16 {
+        F16::from_f32(value)
+    }
+}
+
+impl From<F16> for f32 {
+    #[inline]
+    fn from(value: F16) -> f32 {
+        value.to_f32()
+    }
+}
+
+#[cfg(feature = "half")]
+impl From<half::f16> for F16 {
+    #[inline]
+    fn from(value: half::f16) -> F16 {
+        F16(value.to_bits())
+    }
+}
+
+#[cfg(feature = "half")]
+impl From<F16> for half::f16 {
+    #[inline]
+    fn from(value: F16) -> half::f16 {
+        half::f16::from_bits(value.0)
+    }
+}
+
+impl fmt::Debug for F16 {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "F16({})", self.to_f32())
+    }
+}
+
+/// Array of `f32`s that can be converted with `F16::array`.
+pub trait F16Array {
+    /// The corresponding array of `F16`s.
+    type Output;
+
+    /// Converts each value.
+    fn to_f16(self) -> Self::Output;
+}
+
+macro_rules! impl_f16_array {
+    ($($n:expr),+) => (
+        $(
+            impl F16Array for [f32; $n] {
+                type Output = [F16; $n];
+
+                #[inline]
+                fn to_f16(self) -> [F16; $n] {
+                    let mut result = [F16(0); $n];
+                    for (out, value) in result.iter_mut().zip(self.iter()) {
+                        *out = F16::from_f32(*value);
+                    }
+                    result
+                }
+            }
+        )+
+    );
+}
+
+impl_f16_array!(1, 2, 3, 4);
+
+/// Four signed values packed in 32 bits: ten bits for each of the first three components and
+/// two bits for the fourth one. The first component is in the least significant bits.
+///
+/// This is usually used for normals and tangents, with `normalize` enabled.
+///
+/// Corresponds to `GL_INT_2_10_10_10_REV`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct I2I10I10I10Reversed(pub u32);
+
+impl I2I10I10I10Reversed {
+    /// Packs the components. The first three must be between -512 and 511, and the last one
+    /// between -2 and 1. The bits that don't fit are dropped.
+    #[inline]
+    pub fn new(x: i16, y: i16, z: i16, w: i8) -> I2I10I10I10Reversed {
+        I2I10I10I10Reversed((x as u32 & 0x3ff) | ((y as u32 & 0x3ff) << 10) |
+                            ((z as u32 & 0x3ff) << 20) | ((w as u32 & 0x3) << 30))
+    }
+
+    /// Packs values between -1.0 and 1.0, which is what the shader receives when `normalize`
+    /// is enabled. The values outside of this range are clamped.
+    pub fn from_normalized(values: [f32; 4]) -> I2I10I10I10Reversed {
+        fn pack(value: f32, max: f32) -> i16 {
+            (value.max(-1.0).min(1.0) * max).round() as i16
+        }
+
+        I2I10I10I10Reversed::new(pack(values[0], 511.0), pack(values[1], 511.0),
+                                 pack(values[2], 511.0), pack(values[3], 1.0) as i8)
+    }
+
+    /// Returns the components.
+    pub fn components(&self) -> (i16, i16, i16, i8) {
+        // the shifts propagate the sign bit
+        let value = self.0 as i32;
+        (((value << 22) >> 22) as i16, ((value << 12) >> 22) as i16,
+         ((value << 2) >> 22) as i16, (value >> 30) as i8)
+    }
+}
+
+/// Four unsigned values packed in 32 bits: ten bits for each of the first three components and
+/// two bits for the fourth one. The first component is in the least significant bits.
+///
+/// Corresponds to `GL_UNSIGNED_INT_2_10_10_10_REV`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct U2U10U10U10Reversed(pub u32);
+
+impl U2U10U10U10Reversed {
+    /// Packs the components. The first three must be lower than 1024, and the last one lower
+    /// than 4. The bits that don't fit are dropped.
+    #[inline]
+    pub fn new(x: u16, y: u16, z: u16, w: u8) -> U2U10U10U10Reversed {
+        U2U10U10U10Reversed((x as u32 & 0x3ff) | ((y as u32 & 0x3ff) << 10) |
+                            ((z as u32 & 0x3ff) << 20) | ((w as u32 & 0x3) << 30))
+    }
+
+    /// Packs values between 0.0 and 1.0, which is what the shader receives when `normalize`
+    /// is enabled. The values outside of this range are clamped.
+    pub fn from_normalized(values: [f32; 4]) -> U2U10U10U10Reversed {
+        fn pack(value: f32, max: f32) -> u16 {
+            (value.max(0.0).min(1.0) * max).round() as u16
+        }
+
+        U2U10U10U10Reversed::new(pack(values[0], 1023.0), pack(values[1], 1023.0),
+                                 pack(values[2], 1023.0), pack(values[3], 3.0) as u8)
+    }
+
+    /// Returns the components.
+    #[inline]
+    pub fn components(&self) -> (u16, u16, u16, u8) {
+        ((self.0 & 0x3ff) as u16, ((self.0 >> 10) & 0x3ff) as u16,
+         ((self.0 >> 20) & 0x3ff) as u16, (self.0 >> 30) as u8)
+    }
+}
+
+unsafe impl Attribute for I2I10I10I10Reversed {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I2I10I10I10Reversed
+    }
+}
+
+unsafe impl Attribute for U2U10U10U10Reversed {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U2U10U10U10Reversed
+    }
+}
+
+unsafe impl Attribute for F16 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16
+    }
+}
+
+unsafe impl Attribute for (F16, F16) {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16
+    }
+}
+
+unsafe impl Attribute for [F16; 2] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16
+    }
+}
+
+unsafe impl Attribute for (F16, F16, F16) {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16
+    }
+}
+
+unsafe impl Attribute for [F16; 3] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16
+    }
+}
+
+unsafe impl Attribute for (F16, F16, F16, F16) {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16F16
+    }
+}
+
+unsafe impl Attribute for [F16; 4] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16F16F16F16
+    }
+}
+
+unsafe impl Attribute for [[F16; 2]; 2] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16x2x2
+    }
+}
+
+unsafe impl Attribute for [[F16; 3]; 3] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16x3x3
+    }
+}
+
+unsafe impl Attribute for [[F16; 4]; 4] {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F16x4x4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32;
+    use super::{F16, I2I10I10I10Reversed, U2U10U10U10Reversed};
+
+    #[test]
+    fn f16_exact_values() {
+        for &(value, bits) in &[(0.0, 0x0000), (-0.0, 0x8000), (1.0, 0x3c00), (-2.0, 0xc000),
+                                (0.5, 0x3800), (65504.0, 0x7bff), (6.103515625e-5, 0x0400),
+                                (5.960464477539063e-8, 0x0001)]
+        {
+            assert_eq!(F16::from_f32(value), F16(bits));
+            assert_eq!(F16(bits).to_f32(), value);
+        }
+    }
+
+    #[test]
+    fn f16_rounding() {
+        // 1 + 2^-11 is exactly between 1.0 and the next half, and rounds to the even one
+        assert_eq!(F16::from_f32(1.0 + 1.0 / 2048.0), F16(0x3c00));
+        assert_eq!(F16::from_f32(1.0 + 3.0 / 2048.0), F16(0x3c02));
+        assert_eq!(F16::from_f32(1.0 + 1.5 / 2048.0), F16(0x3c01));
+        assert_eq!(F16::from_f32(2.0e-8), F16(0x0000));
+        assert_eq!(F16::from_f32(4.0e-8), F16(0x0001));
+    }
+
+    #[test]
+    fn f16_special_values() {
+        assert_eq!(F16::from_f32(f32::INFINITY), F16(0x7c00));
+        assert_eq!(F16::from_f32(-1.0e6), F16(0xfc00));
+        assert_eq!(F16::from_f32(65520.0), F16(0x7c00));
+        assert!(F16::from_f32(f32::NAN).to_f32().is_nan());
+    }
+
+    #[test]
+    fn f16_round_trip() {
+        for bits in 0 .. 0x7c00 {
+            assert_eq!(F16::from_f32(F16(bits).to_f32()), F16(bits));
+            assert_eq!(F16::from_f32(F16(bits | 0x8000).to_f32()), F16(bits | 0x8000));
+        }
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn f16_half_conversions() {
+        use half;
+
+        assert_eq!(F16::from(half::f16::from_f32(1.5)), F16::from_f32(1.5));
+        assert_eq!(half::f16::from(F16::from_f32(-2.0)).to_f32(), -2.0);
+    }
+
+    #[test]
+    fn signed_packing() {
+        let value = I2I10I10I10Reversed::new(-512, 511, -1, -2);
+        assert_eq!(value.components(), (-512, 511, -1, -2));
+
+        let value = I2I10I10I10Reversed::from_normalized([1.0, -1.0, 0.0, 2.0]);
+        assert_eq!(value.components(), (511, -511, 0, 1));
+    }
+
+    #[test]
+    fn unsigned_packing() {
+        let value = U2U10U10U10Reversed::new(1023, 0, 512, 3);
+        assert_eq!(value.0, 1023 | (512 << 20) | (3 << 30));
+        assert_eq!(value.components(), (1023, 0, 512, 3));
+
+        let value = U2U10U10U10Reversed::from_normalized([1.0, 0.5, -1.0, 1.0]);
+        assert_eq!(value.components(), (1023, 512, 0, 3));
+    }
+}
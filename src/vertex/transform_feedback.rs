@@ -14,7 +14,7 @@ use buffer::{Buffer, BufferAnySlice};
 use index::PrimitiveType;
 use program::OutputPrimitives;
 use program::Program;
-use vertex::Vertex;
+use vertex::{AttributeType, Vertex};
 
 use gl;
 
@@ -90,6 +90,86 @@ use gl;
 /// println!("List of generated vertices: {:?}", result);
 /// # }
 /// ```
+///
+/// # Feeding the output back
+///
+/// The buffer is a regular vertex buffer, and can be used as the source of a draw command once
+/// the session is destroyed. For example a particle simulation can keep the particles in two
+/// buffers, and alternately read from one of them and write to the other one.
+///
+/// Since the shader can't have an input and an output with the same name, the outputs are
+/// members of an output block. A varying named `Block.name` corresponds to the attribute `name`
+/// of the vertex. Output blocks require GLSL 1.50 or GLSL ES 3.20.
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # use glium::Surface;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// #[derive(Copy, Clone)]
+/// struct Particle {
+///     position: [f32; 2],
+///     velocity: [f32; 2],
+/// }
+///
+/// implement_vertex!(Particle, position, velocity);
+///
+/// let program = glium::Program::new(&display, glium::program::ProgramCreationInput::SourceCode {
+///     vertex_shader: "
+///         #version 150
+///
+///         in vec2 position;
+///         in vec2 velocity;
+///
+///         out Particle {
+///             vec2 position;
+///             vec2 velocity;
+///         } particle;
+///
+///         void main() {
+///             particle.position = position + velocity;
+///             particle.velocity = velocity;
+///         }
+///     ",
+///     fragment_shader: "#version 150\nvoid main() {}",
+///     transform_feedback_varyings: Some((
+///         vec!["Particle.position".to_string(), "Particle.velocity".to_string()],
+///         glium::program::TransformFeedbackMode::Interleaved
+///     )),
+///     tessellation_control_shader: None, tessellation_evaluation_shader: None,
+///     geometry_shader: None, outputs_srgb: false, uses_point_size: false,
+/// }).unwrap();
+///
+/// let mut buffers = [
+///     glium::VertexBuffer::new(&display, &[Particle { position: [0.0, 0.0],
+///                                                     velocity: [0.1, 0.0] }]).unwrap(),
+///     glium::VertexBuffer::empty(&display, 1).unwrap(),
+/// ];
+///
+/// for _ in 0 .. 60 {
+///     {
+///         let (source, destination) = buffers.split_at_mut(1);
+///         let session = glium::vertex::TransformFeedbackSession::new(&display, &program,
+///                                                                    &mut destination[0])
+///                                                                    .unwrap();
+///
+///         let params = glium::DrawParameters {
+///             transform_feedback: Some(&session),
+///             draw_primitives: false,
+///             .. Default::default()
+///         };
+///
+///         let indices = glium::index::NoIndices(glium::index::PrimitiveType::Points);
+///         let mut target = display.draw();
+///         target.draw(&source[0], &indices, &program, &uniform!{}, &params).unwrap();
+///         target.finish().unwrap();
+///     }
+///
+///     buffers.swap(0, 1);
+/// }
+/// # }
+/// ```
 #[derive(Debug)]
 pub struct TransformFeedbackSession<'a> {
     buffer: BufferAnySlice<'a>,
@@ -98,17 +178,75 @@ pub struct TransformFeedbackSession<'a> {
 
 /// Error that can happen when creating a `TransformFeedbackSession`.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum TransformFeedbackSessionCreationError {
     /// Transform feedback is not supported by the OpenGL implementation.
     NotSupported,
 
-    /// The format of the output doesn't match what the program is expected to output.
+    /// The program doesn't output its varyings in exactly one buffer, which is the only
+    /// configuration that is supported.
     WrongVertexFormat,
+
+    /// The program outputs a varying that doesn't correspond to any attribute of the vertex.
+    MissingAttribute {
+        /// Name of the varying.
+        name: String,
+    },
+
+    /// An attribute of the vertex isn't written by the program.
+    UncapturedAttribute {
+        /// Name of the attribute.
+        name: String,
+    },
+
+    /// The type of a varying doesn't match the type of the corresponding attribute.
+    AttributeTypeMismatch {
+        /// Name of the varying.
+        name: String,
+        /// Type of the varying.
+        program: AttributeType,
+        /// Type of the attribute.
+        vertex: AttributeType,
+    },
+
+    /// A varying isn't written at the offset of the corresponding attribute. This usually
+    /// means that the varyings aren't in the same order as the fields of the vertex.
+    AttributeOffsetMismatch {
+        /// Name of the varying.
+        name: String,
+        /// Offset where the program writes the varying.
+        program: usize,
+        /// Offset of the attribute.
+        vertex: usize,
+    },
+
+    /// The size of the data written by the program for each vertex isn't the size of the
+    /// vertex.
+    StrideMismatch {
+        /// Number of bytes written for each vertex.
+        program: usize,
+        /// Size of the vertex.
+        vertex: usize,
+    },
 }
 
 impl fmt::Display for TransformFeedbackSessionCreationError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}", self.description())
+        use self::TransformFeedbackSessionCreationError::*;
+        match *self {
+            MissingAttribute { ref name } | UncapturedAttribute { ref name } =>
+                write!(fmt, "{}: `{}`", self.description(), name),
+            AttributeTypeMismatch { ref name, ref program, ref vertex } =>
+                write!(fmt, "{}: `{}` is a {:?} in the program and a {:?} in the vertex",
+                       self.description(), name, program, vertex),
+            AttributeOffsetMismatch { ref name, program, vertex } =>
+                write!(fmt, "{}: `{}` is at offset {} in the program and {} in the vertex",
+                       self.description(), name, program, vertex),
+            StrideMismatch { program, vertex } =>
+                write!(fmt, "{}: the program writes {} bytes and the vertex has {} bytes",
+                       self.description(), program, vertex),
+            _ => write!(fmt, "{}", self.description()),
+        }
     }
 }
 
@@ -120,6 +258,16 @@ impl Error for TransformFeedbackSessionCreationError {
                 "Transform feedback is not supported by the OpenGL implementation",
             WrongVertexFormat =>
                 "The format of the output doesn't match what the program is expected to output",
+            MissingAttribute { .. } =>
+                "The program outputs a varying that isn't an attribute of the vertex",
+            UncapturedAttribute { .. } =>
+                "An attribute of the vertex isn't written by the program",
+            AttributeTypeMismatch { .. } =>
+                "The type of a varying doesn't match the type of the attribute",
+            AttributeOffsetMismatch { .. } =>
+                "A varying isn't at the same position as the attribute",
+            StrideMismatch { .. } =>
+                "The size of the output doesn't match the size of the vertex",
         }
     }
 }
@@ -137,6 +285,11 @@ pub fn is_transform_feedback_supported<F: ?Sized>(facade: &F) -> bool where F: F
 impl<'a> TransformFeedbackSession<'a> {
     /// Builds a new transform feedback session.
     ///
+    /// The varyings written by the program are checked against the attributes of `V`: they must
+    /// have the same names, the same types and the same offsets, which means that they must be
+    /// in the same order as the fields, and the program must write exactly `size_of::<V>()`
+    /// bytes for each vertex.
+    ///
     /// TODO: this constructor should ultimately support passing multiple buffers of different
     ///       types
     pub fn new<F: ?Sized, V>(facade: &F, program: &'a Program, buffer: &'a mut Buffer<[V]>)
@@ -147,11 +300,8 @@ impl<'a> TransformFeedbackSession<'a> {
             return Err(TransformFeedbackSessionCreationError::NotSupported);
         }
 
-        if !program.transform_feedback_matches(&<V as Vertex>::build_bindings(),
-                                               mem::size_of::<V>())
-        {
-            return Err(TransformFeedbackSessionCreationError::WrongVertexFormat);
-        }
+        try!(program.check_transform_feedback_format(&<V as Vertex>::build_bindings(),
+                                                     mem::size_of::<V>()));
 
         Ok(TransformFeedbackSession {
             buffer: buffer.as_slice_any(),
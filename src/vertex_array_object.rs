@@ -1,10 +1,12 @@
 use std::borrow::Borrow;
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::mem;
 
 use smallvec::SmallVec;
 
+use DrawError;
 use Handle;
 use buffer::BufferAnySlice;
 use program::Program;
@@ -14,15 +16,24 @@ use GlObject;
 use BufferExt;
 
 use gl;
-use context::CommandContext;
+use context::{CommandContext, Fallback};
 use version::Api;
 use version::Version;
 
+/// Value of `GL_HALF_FLOAT_OES`, which is different from `GL_HALF_FLOAT`.
+const HALF_FLOAT_OES: gl::types::GLenum = 0x8D61;
+
 /// Stores and handles vertex attributes.
 pub struct VertexAttributesSystem {
     // we maintain a list of VAOs for each vertexbuffer-indexbuffer-program association
-    // the key is a (buffers-list-with-offset, program) ; the buffers list must be sorted
-    vaos: RefCell<HashMap<(Vec<(gl::types::GLuint, usize)>, Handle), VertexArrayObject>>,
+    // the key is a (buffers-list-with-offset-and-divisor, program) ; the buffers list must be
+    // sorted
+    vaos: RefCell<HashMap<(Vec<(gl::types::GLuint, usize, Option<u32>)>, Handle),
+                         VertexArrayObject>>,
+
+    // VAOs that only store the format of the attributes (`GL_ARB_vertex_attrib_binding`), for
+    // each list of formats and divisors and each program ; the buffers are bound when drawing
+    layouts: RefCell<HashMap<(Vec<(VertexFormat, Option<u32>)>, Handle), VertexArrayObject>>,
 }
 
 /// Object allowing one to bind vertex attributes to the current context.
@@ -40,6 +51,7 @@ impl VertexAttributesSystem {
     pub fn new() -> VertexAttributesSystem {
         VertexAttributesSystem {
             vaos: RefCell::new(HashMap::with_hasher(Default::default())),
+            layouts: RefCell::new(HashMap::with_hasher(Default::default())),
         }
     }
 
@@ -70,15 +82,27 @@ impl VertexAttributesSystem {
     #[inline]
     pub fn purge_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint) {
         VertexAttributesSystem::purge_if(ctxt, |&(ref buffers, _)| {
-            buffers.iter().find(|&&(b, _)| b == id).is_some()
-        })
+            buffers.iter().find(|&&(b, _, _)| b == id).is_some()
+        });
+
+        // the name of the buffer may be reused, so the VAOs that only store the format must
+        // forget that it is bound
+        for (_, vao) in ctxt.vertex_array_objects.layouts.borrow().iter() {
+            vao.forget_buffer(id);
+        }
     }
 
     /// This function *must* be called whenever you destroy a program so that the system can
     /// purge its VAOs cache.
     #[inline]
     pub fn purge_program(ctxt: &mut CommandContext, program: Handle) {
-        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program)
+        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program);
+
+        let mut layouts = ctxt.vertex_array_objects.layouts.borrow_mut();
+        let keys: Vec<_> = layouts.keys().filter(|&&(_, p)| p == program).cloned().collect();
+        for key in keys {
+            layouts.remove(&key).unwrap().destroy(ctxt);
+        }
     }
 
     /// Purges the VAOs cache.
@@ -89,6 +113,13 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let layouts = mem::replace(&mut *ctxt.vertex_array_objects.layouts.borrow_mut(),
+                                   HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in layouts {
+            vao.destroy(ctxt);
+        }
     }
 
     /// Purges the VAOs cache. Contrary to `purge_all`, this function expects the system to be
@@ -100,13 +131,34 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let layouts = mem::replace(&mut *ctxt.vertex_array_objects.layouts.borrow_mut(),
+                                   HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in layouts {
+            vao.destroy(ctxt);
+        }
+    }
+
+    /// Binds the default vertex array object, so that the next operations can't modify the
+    /// VAOs of glium. Does nothing if the backend doesn't support VAOs.
+    pub fn unbind(ctxt: &mut CommandContext) {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+            ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+            ctxt.extensions.gl_arb_vertex_array_object ||
+            ctxt.extensions.gl_oes_vertex_array_object ||
+            ctxt.extensions.gl_apple_vertex_array_object
+        {
+            bind_vao(ctxt, 0);
+        }
     }
 
     /// Tells the VAOs system that the currently bound element array buffer will change.
     pub fn hijack_current_element_array_buffer(ctxt: &mut CommandContext) {
         let vaos = ctxt.vertex_array_objects.vaos.borrow_mut();
+        let layouts = ctxt.vertex_array_objects.layouts.borrow_mut();
 
-        for (_, vao) in vaos.iter() {
+        for vao in vaos.values().chain(layouts.values()) {
             if vao.id == ctxt.state.vertex_array {
                 vao.element_array_buffer_hijacked.set(true);
                 return;
@@ -116,7 +168,7 @@ impl VertexAttributesSystem {
 
     /// Purges VAOs that match a certain condition.
     fn purge_if<F>(ctxt: &mut CommandContext, mut condition: F)
-                   where F: FnMut(&(Vec<(gl::types::GLuint, usize)>, Handle)) -> bool
+                   where F: FnMut(&(Vec<(gl::types::GLuint, usize, Option<u32>)>, Handle)) -> bool
     {
         let mut vaos = ctxt.vertex_array_objects.vaos.borrow_mut();
 
@@ -159,7 +211,13 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
     /// Finish binding the vertex attributes.
     ///
     /// If `base_vertex` was set to true, returns the base vertex to use when drawing.
-    pub fn bind(mut self) -> Option<gl::types::GLint> {
+    ///
+    /// Returns an error if the vertex sources don't match the attributes of the program. If
+    /// `ignore_missing` is true, the attributes of the program that aren't in the vertex
+    /// sources are left unbound instead.
+    pub fn bind(mut self, ignore_missing: bool)
+                -> Result<Option<gl::types::GLint>, DrawError>
+    {
         let ctxt = self.context;
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
@@ -168,6 +226,20 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
         {
             // VAOs are supported
 
+            if is_separate_format_supported(ctxt) &&
+               self.vertex_buffers.iter().all(|&(_, ref bindings, _, stride, _)| {
+                   fits_separate_format(bindings, stride)
+               })
+            {
+                unsafe {
+                    try!(bind_with_separate_format(ctxt, self.program, self.element_array_buffer,
+                                                   &self.vertex_buffers, ignore_missing));
+                }
+
+                // the offsets are not part of the VAO, so there is no need for a base vertex
+                return Ok(if self.base_vertex { Some(0) } else { None });
+            }
+
             // finding the base vertex
             let base_vertex = if self.base_vertex {
                 Some(self.vertex_buffers.iter()
@@ -186,9 +258,9 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
             }
 
             let mut buffers_list: Vec<_> = self.vertex_buffers.iter()
-                                                              .map(|&(v, _, o, s, _)| (v, o))
+                                                              .map(|&(v, _, o, _, d)| (v, o, d))
                                                               .collect();
-            buffers_list.push((self.element_array_buffer.map(|b| b.get_id()).unwrap_or(0), 0));
+            buffers_list.push((self.element_array_buffer.map(|b| b.get_id()).unwrap_or(0), 0, None));
             buffers_list.sort();
 
             let program_id = self.program.get_id();
@@ -197,23 +269,31 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
             if let Some(value) = ctxt.vertex_array_objects.vaos.borrow_mut()
                                      .get(&(buffers_list.clone(), program_id))
             {
+                try!(check_missing_attribute(&value.missing_attribute, ignore_missing));
                 value.bind(ctxt);
-                return base_vertex.map(|v| v as gl::types::GLint);
+                return Ok(base_vertex.map(|v| v as gl::types::GLint));
             }
 
             // if not found, building a new one
+            let missing = try!(check_attributes(&self.vertex_buffers, self.program));
+            try!(check_missing_attribute(&missing, ignore_missing));
+
             let new_vao = unsafe {
                 VertexArrayObject::new(ctxt, &self.vertex_buffers,
-                                       self.element_array_buffer, self.program)
+                                       self.element_array_buffer, self.program, missing)
             };
 
             new_vao.bind(ctxt);
             ctxt.vertex_array_objects.vaos.borrow_mut().insert((buffers_list, program_id), new_vao);
 
-            base_vertex.map(|v| v as gl::types::GLint)
+            Ok(base_vertex.map(|v| v as gl::types::GLint))
 
         } else {
             // VAOs are not supported
+            ctxt.fallbacks.record(Fallback::NoVertexArrayObjects);
+
+            let missing = try!(check_attributes(&self.vertex_buffers, self.program));
+            try!(check_missing_attribute(&missing, ignore_missing));
 
             // just in case
             bind_vao(ctxt, 0);
@@ -232,9 +312,9 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
             // TODO: it is unlikely that a backend supports base vertex but not VAOs, so we just
             //       ignore this case ; however it would ideally be better to handle it
             if self.base_vertex {
-                Some(0)
+                Ok(Some(0))
             } else {
-                None
+                Ok(None)
             }
         }
     }
@@ -244,50 +324,26 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
 struct VertexArrayObject {
     id: gl::types::GLuint,
     destroyed: bool,
-    element_array_buffer: gl::types::GLuint,
+    element_array_buffer: Cell<gl::types::GLuint>,
     element_array_buffer_hijacked: Cell<bool>,
+    // for VAOs that only store the format of the attributes, the buffer, offset and stride that
+    // are bound to each binding index
+    bound_buffers: RefCell<SmallVec<[(gl::types::GLuint, usize, usize); 2]>>,
+    // the first attribute of the program that isn't provided by the vertex sources, if any
+    missing_attribute: Option<String>,
 }
 
 impl VertexArrayObject {
     /// Builds a new `VertexArrayObject`.
     ///
     /// The vertex buffer, index buffer and program must not outlive the
-    /// VAO, and the VB & program attributes must not change.
+    /// VAO, and the VB & program attributes must not change. The attributes must have been
+    /// checked with `check_attributes`, whose result is passed as `missing_attribute`.
     unsafe fn new(mut ctxt: &mut CommandContext,
                   vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
-                  index_buffer: Option<BufferAnySlice>, program: &Program) -> VertexArrayObject
+                  index_buffer: Option<BufferAnySlice>, program: &Program,
+                  missing_attribute: Option<String>) -> VertexArrayObject
     {
-        // checking the attributes types
-        for &(_, ref bindings, _, _, _) in vertex_buffers {
-            for &(ref name, _, ty, _) in bindings.iter() {
-                let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
-                    Some(a) => a,
-                    None => continue
-                };
-
-                if ty.get_num_components() != attribute.ty.get_num_components() ||
-                    attribute.size != 1
-                {
-                    panic!("The program attribute `{}` does not match the vertex format. \
-                            Program expected {:?}, got {:?}.", name, attribute.ty, ty);
-                }
-            }
-        }
-
-        // checking for missing attributes
-        for (&ref name, _) in program.attributes() {
-            let mut found = false;
-            for &(_, ref bindings, _, _, _) in vertex_buffers {
-                if bindings.iter().find(|&&(ref n, _, _, _)| n == name).is_some() {
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                panic!("The program attribute `{}` is missing in the vertex bindings", name);
-            }
-        };
-
         // TODO: check for collisions between the vertices sources
 
         // building the VAO
@@ -324,8 +380,88 @@ impl VertexArrayObject {
         VertexArrayObject {
             id: id,
             destroyed: false,
-            element_array_buffer: index_buffer.map(|b| b.get_id()).unwrap_or(0),
+            element_array_buffer: Cell::new(index_buffer.map(|b| b.get_id()).unwrap_or(0)),
+            element_array_buffer_hijacked: Cell::new(false),
+            bound_buffers: RefCell::new(SmallVec::new()),
+            missing_attribute: missing_attribute,
+        }
+    }
+
+    /// Builds a new `VertexArrayObject` that only stores the format of the attributes, with one
+    /// binding index for each vertex source. The buffers must then be bound with `bind_buffers`.
+    ///
+    /// The program attributes must not change. The attributes must have been checked with
+    /// `check_attributes`, whose result is passed as `missing_attribute`.
+    unsafe fn new_separate_format(ctxt: &mut CommandContext,
+                                  vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize,
+                                                     usize, Option<u32>)],
+                                  program: &Program, missing_attribute: Option<String>)
+                                  -> VertexArrayObject
+    {
+        let id = {
+            let mut id = mem::uninitialized();
+            ctxt.gl.GenVertexArrays(1, &mut id);
+            id
+        };
+
+        bind_vao(ctxt, id);
+
+        for (index, &(_, ref bindings, _, _, divisor)) in vertex_buffers.iter().enumerate() {
+            set_attributes_format(ctxt, program, index as gl::types::GLuint, bindings);
+            ctxt.gl.VertexBindingDivisor(index as gl::types::GLuint, divisor.unwrap_or(0));
+        }
+
+        VertexArrayObject {
+            id: id,
+            destroyed: false,
+            element_array_buffer: Cell::new(0),
             element_array_buffer_hijacked: Cell::new(false),
+            bound_buffers: RefCell::new(SmallVec::new()),
+            missing_attribute: missing_attribute,
+        }
+    }
+
+    /// Binds the buffers of a VAO built with `new_separate_format`. The VAO must be the current
+    /// one.
+    unsafe fn bind_buffers(&self, ctxt: &mut CommandContext,
+                           index_buffer: Option<BufferAnySlice>,
+                           vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize,
+                                              Option<u32>)])
+    {
+        if let Some(index_buffer) = index_buffer {
+            if self.element_array_buffer.get() != index_buffer.get_id() {
+                index_buffer.bind_to_element_array(ctxt);
+                self.element_array_buffer.set(index_buffer.get_id());
+            }
+        }
+
+        let mut bound_buffers = self.bound_buffers.borrow_mut();
+
+        for (index, &(buffer, _, offset, stride, _)) in vertex_buffers.iter().enumerate() {
+            if bound_buffers.len() <= index {
+                bound_buffers.push((0, 0, 0));
+            }
+
+            if bound_buffers[index] != (buffer, offset, stride) {
+                ctxt.gl.BindVertexBuffer(index as gl::types::GLuint, buffer,
+                                         offset as gl::types::GLintptr,
+                                         stride as gl::types::GLsizei);
+                bound_buffers[index] = (buffer, offset, stride);
+            }
+        }
+    }
+
+    /// Forgets that a buffer is bound to this VAO, so that it is bound again the next time.
+    /// Called when the buffer is destroyed.
+    fn forget_buffer(&self, id: gl::types::GLuint) {
+        if self.element_array_buffer.get() == id {
+            self.element_array_buffer.set(0);
+        }
+
+        for bound in self.bound_buffers.borrow_mut().iter_mut() {
+            if bound.0 == id {
+                *bound = (0, 0, 0);
+            }
         }
     }
 
@@ -339,9 +475,10 @@ impl VertexArrayObject {
                 if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                     ctxt.version >= &Version(Api::GlEs, 2, 0)
                 {
-                    ctxt.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.element_array_buffer);
+                    ctxt.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.element_array_buffer.get());
                 } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-                    ctxt.gl.BindBufferARB(gl::ELEMENT_ARRAY_BUFFER_ARB, self.element_array_buffer);
+                    ctxt.gl.BindBufferARB(gl::ELEMENT_ARRAY_BUFFER_ARB,
+                                          self.element_array_buffer.get());
                 } else {
                     unreachable!();
                 }
@@ -466,15 +603,199 @@ fn vertex_binding_type_to_gl(ty: AttributeType) -> (gl::types::GLenum, gl::types
         AttributeType::F64x4x2 => (gl::DOUBLE, 4, 2),
         AttributeType::F64x4x3 => (gl::DOUBLE, 4, 3),
         AttributeType::F64x4x4 => (gl::DOUBLE, 4, 4),
-        AttributeType::I2I10I10I10Reversed => (gl::INT_2_10_10_10_REV, 1, 1),
-        AttributeType::U2U10U10U10Reversed => (gl::UNSIGNED_INT_2_10_10_10_REV, 1, 1),
-        AttributeType::I10I10I10I2 => (gl::INT_10_10_10_2_OES, 1, 1),
-        AttributeType::U10U10U10U2 => (gl::UNSIGNED_INT_10_10_10_2_OES, 1, 1),
-        AttributeType::F10F11F11UnsignedIntReversed => (gl::UNSIGNED_INT_10F_11F_11F_REV, 1, 1),
+        AttributeType::I2I10I10I10Reversed => (gl::INT_2_10_10_10_REV, 4, 1),
+        AttributeType::U2U10U10U10Reversed => (gl::UNSIGNED_INT_2_10_10_10_REV, 4, 1),
+        AttributeType::I10I10I10I2 => (gl::INT_10_10_10_2_OES, 4, 1),
+        AttributeType::U10U10U10U2 => (gl::UNSIGNED_INT_10_10_10_2_OES, 4, 1),
+        AttributeType::F10F11F11UnsignedIntReversed => (gl::UNSIGNED_INT_10F_11F_11F_REV, 3, 1),
         AttributeType::FixedFloatI16U16 => (gl::FIXED, 1, 1),
     }
 }
 
+/// The way the shader reads an attribute, which determines the function used to bind it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum AttributeKind {
+    /// `float`, `vec` and `mat` attributes, bound with `glVertexAttribPointer`. The data is
+    /// converted to floating-point.
+    Float,
+    /// `int`, `ivec`, `uint` and `uvec` attributes, bound with `glVertexAttribIPointer`.
+    Integer,
+    /// `double`, `dvec` and `dmat` attributes and 64-bits integers, bound with
+    /// `glVertexAttribLPointer`.
+    Long,
+}
+
+/// Returns the way a shader reads an attribute of the given type.
+fn attribute_kind(ty: AttributeType) -> AttributeKind {
+    match vertex_binding_type_to_gl(ty).0 {
+        gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
+        gl::INT | gl::UNSIGNED_INT => AttributeKind::Integer,
+        gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV => AttributeKind::Long,
+        _ => AttributeKind::Float,
+    }
+}
+
+/// Returns the size in bytes of a component of an attribute whose data has the given type.
+fn component_size(data_type: gl::types::GLenum) -> usize {
+    match data_type {
+        gl::BYTE | gl::UNSIGNED_BYTE => 1,
+        gl::SHORT | gl::UNSIGNED_SHORT | gl::HALF_FLOAT | HALF_FLOAT_OES => 2,
+        gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV => 8,
+        _ => 4,
+    }
+}
+
+/// Returns true if a shader attribute of type `attribute` can read data of type `data`.
+fn is_data_compatible(attribute: AttributeType, data: AttributeType, normalize: bool) -> bool {
+    let data_type = vertex_binding_type_to_gl(data).0;
+
+    match attribute_kind(attribute) {
+        // integers are passed as they are, so they can't be floating-point or normalized
+        AttributeKind::Integer => attribute_kind(data) == AttributeKind::Integer && !normalize,
+        // the data must have exactly the type of the attribute, as there is no conversion
+        AttributeKind::Long => data_type == vertex_binding_type_to_gl(attribute).0,
+        // everything can be converted to floating-point except 64-bits integers
+        AttributeKind::Float => data_type != gl::INT64_NV && data_type != gl::UNSIGNED_INT64_NV,
+    }
+}
+
+/// Checks that the vertex sources provide the attributes of the program with the right types.
+///
+/// Returns an error if the type of an attribute doesn't match, and the name of the first
+/// attribute of the program that is missing from the vertex sources, if any.
+fn check_attributes(vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
+                    program: &Program) -> Result<Option<String>, DrawError>
+{
+    // checking the attributes types
+    for &(_, ref bindings, _, _, _) in vertex_buffers {
+        for &(ref name, _, ty, normalize) in bindings.iter() {
+            let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
+                Some(a) => a,
+                None => continue
+            };
+
+            if ty.get_num_components() != attribute.ty.get_num_components() ||
+                attribute.size != 1 || !is_data_compatible(attribute.ty, ty, normalize)
+            {
+                return Err(DrawError::AttributeTypeMismatch {
+                    name: name.to_string(),
+                    program: attribute.ty,
+                    vertex: ty,
+                    normalize: normalize,
+                });
+            }
+        }
+    }
+
+    // checking for missing attributes
+    for (&ref name, _) in program.attributes() {
+        let found = vertex_buffers.iter().any(|&(_, ref bindings, _, _, _)| {
+            bindings.iter().any(|&(ref n, _, _, _)| n == name)
+        });
+
+        if !found {
+            return Ok(Some(name.clone()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Returns an error if an attribute is missing and missing attributes aren't ignored.
+#[inline]
+fn check_missing_attribute(missing: &Option<String>, ignore_missing: bool)
+                           -> Result<(), DrawError>
+{
+    match *missing {
+        Some(ref name) if !ignore_missing => {
+            Err(DrawError::AttributeMissing { name: name.clone() })
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Returns true if the format of the attributes can be stored separately from the buffers.
+fn is_separate_format_supported(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+    ctxt.extensions.gl_arb_vertex_attrib_binding
+}
+
+/// Returns true if a vertex source can be bound with `glBindVertexBuffer`, whose stride and
+/// offsets are limited. The values are the minimums required by the specification.
+fn fits_separate_format(bindings: &VertexFormat, stride: usize) -> bool {
+    stride <= 2048 && bindings.iter().all(|&(_, offset, _, _)| offset <= 2047)
+}
+
+/// Binds the attributes with a VAO that only stores their format, which is reused whatever the
+/// buffers are. Only the buffers that changed since the last time are bound.
+unsafe fn bind_with_separate_format(ctxt: &mut CommandContext, program: &Program,
+                                    index_buffer: Option<BufferAnySlice>,
+                                    vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize,
+                                                       usize, Option<u32>)],
+                                    ignore_missing: bool) -> Result<(), DrawError>
+{
+    let formats = vertex_buffers.iter().map(|&(_, ref bindings, _, _, divisor)| {
+        (bindings.clone(), divisor)
+    }).collect();
+
+    let mut layouts = ctxt.vertex_array_objects.layouts.borrow_mut();
+
+    let vao = match layouts.entry((formats, program.get_id())) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            let missing = try!(check_attributes(vertex_buffers, program));
+            entry.insert(VertexArrayObject::new_separate_format(ctxt, vertex_buffers, program,
+                                                                missing))
+        },
+    };
+
+    try!(check_missing_attribute(&vao.missing_attribute, ignore_missing));
+    vao.bind(ctxt);
+    vao.bind_buffers(ctxt, index_buffer, vertex_buffers);
+    Ok(())
+}
+
+/// Sets the format of the attributes of a vertex source and associates them with a binding
+/// index of the current VAO.
+unsafe fn set_attributes_format(ctxt: &mut CommandContext, program: &Program,
+                                binding_index: gl::types::GLuint, bindings: &VertexFormat)
+{
+    for &(ref name, offset, ty, normalize) in bindings.iter() {
+        let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
+            Some(a) => a,
+            None => continue
+        };
+
+        if attribute.location == -1 {
+            continue;
+        }
+
+        let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
+        let column_size = elements_count as usize * component_size(data_type);
+
+        for i in 0 .. instances_count {
+            let location = (attribute.location + i) as gl::types::GLuint;
+            let offset = (offset + i as usize * column_size) as gl::types::GLuint;
+
+            match attribute_kind(attribute.ty) {
+                AttributeKind::Float => {
+                    ctxt.gl.VertexAttribFormat(location, elements_count, data_type,
+                                               if normalize { 1 } else { 0 }, offset);
+                },
+                AttributeKind::Integer => {
+                    ctxt.gl.VertexAttribIFormat(location, elements_count, data_type, offset);
+                },
+                AttributeKind::Long => {
+                    ctxt.gl.VertexAttribLFormat(location, elements_count, data_type, offset);
+                },
+            }
+
+            ctxt.gl.VertexAttribBinding(location, binding_index);
+            ctxt.gl.EnableVertexAttribArray(location);
+        }
+    }
+}
+
 /// Binds the vertex array object as the current one. Unbinds if `0` is passed.
 ///
 /// ## Panic
@@ -523,54 +844,67 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext, program: &Program,
     for &(ref name, offset, ty, normalize) in bindings.iter() {
         let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
 
+        // OpenGL ES 2 only supports half floats through `GL_OES_vertex_half_float`, which uses
+        // a different value for the enum
+        let data_type = if data_type == gl::HALF_FLOAT && ctxt.version.0 == Api::GlEs &&
+                           ctxt.version < &Version(Api::GlEs, 3, 0)
+        {
+            HALF_FLOAT_OES
+        } else {
+            data_type
+        };
+
         let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
             Some(a) => a,
             None => continue
         };
 
         if attribute.location != -1 {
-            let (attribute_ty, _, _) = vertex_binding_type_to_gl(attribute.ty);
-            if normalize {
-                for i in 0..instances_count {
-                    ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
-                                                elements_count as gl::types::GLint, data_type, 1,
-                                                stride as i32,
-                                                (buffer_offset + offset + (i * elements_count * 4) as usize) as *const _)
-                }
-            } else {
-                match attribute_ty {
-                    gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
-                    gl::INT | gl::UNSIGNED_INT =>
-                        ctxt.gl.VertexAttribIPointer(attribute.location as u32,
+            // offset of each column of a matrix
+            let column_size = elements_count as usize * component_size(data_type);
+
+            match attribute_kind(attribute.ty) {
+                AttributeKind::Float => {
+                    for i in 0..instances_count {
+                        ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
+                                                    elements_count as gl::types::GLint, data_type,
+                                                    if normalize { 1 } else { 0 },
+                                                    stride as i32,
+                                                    (buffer_offset + offset +
+                                                     i as usize * column_size) as *const _)
+                    }
+                },
+
+                AttributeKind::Integer => {
+                    ctxt.gl.VertexAttribIPointer(attribute.location as u32,
+                                                 elements_count as gl::types::GLint, data_type,
+                                                 stride as i32,
+                                                 (buffer_offset + offset) as *const _)
+                },
+
+                AttributeKind::Long => {
+                    for i in 0..instances_count {
+                        ctxt.gl.VertexAttribLPointer((attribute.location + i) as u32,
                                                      elements_count as gl::types::GLint, data_type,
                                                      stride as i32,
-                                                     (buffer_offset + offset) as *const _),
-
-                    gl::FLOAT => {
-                        for i in 0..instances_count {
-                            ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
-                                                        elements_count as gl::types::GLint, data_type, 0,
-                                                        stride as i32,
-                                                        (buffer_offset + offset + (i * elements_count * 4) as usize) as *const _)
-                        }
-                    },
-
-                    gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV => {
-                        for i in 0..instances_count {
-                            ctxt.gl.VertexAttribLPointer((attribute.location + i) as u32,
-                                                         elements_count as gl::types::GLint, data_type,
-                                                         stride as i32,
-                                                         (buffer_offset + offset + (i * elements_count * 8) as usize) as *const _)
-                        }
-                    },
-
-                    _ => unreachable!()
-                }
+                                                     (buffer_offset + offset +
+                                                      i as usize * column_size) as *const _)
+                    }
+                },
             }
 
             for i in 0..instances_count {
                 if let Some(divisor) = divisor {
-                    ctxt.gl.VertexAttribDivisor((attribute.location + i) as u32, divisor);
+                    if ctxt.version >= &Version(Api::Gl, 3, 3) ||
+                       ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                       ctxt.extensions.gl_arb_instanced_arrays
+                    {
+                        ctxt.gl.VertexAttribDivisor((attribute.location + i) as u32, divisor);
+                    } else if ctxt.extensions.gl_angle_instanced_arrays {
+                        ctxt.gl.VertexAttribDivisorANGLE((attribute.location + i) as u32, divisor);
+                    } else {
+                        unreachable!();
+                    }
                 }
                 ctxt.gl.EnableVertexAttribArray((attribute.location + i) as u32);
             }
@@ -0,0 +1,336 @@
+/*!
+Captures the content of surfaces frame after frame, for example to record a video.
+
+Reading a surface with `Texture2d::read` stalls the CPU until the GPU has finished drawing, which
+is too slow to record at 60 frames per second. A `VideoCapture` instead copies each frame into a
+pixel buffer taken from a pool, and only reads the pixel buffers once the GPU has finished
+writing them, usually one or two frames later.
+
+The captured frames are flipped so that the first row is the top of the image, and are tightly
+packed, which is what most video encoders expect.
+
+```no_run
+# use glium::Surface;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# fn write_to_encoder(_: &[u8]) {}
+use glium::video_capture::{VideoCapture, CaptureFormat};
+
+let mut capture = VideoCapture::new(&display, CaptureFormat::I420, 3);
+
+loop {
+    let mut target = display.draw();
+    // draw here
+    capture.capture(&target);
+    target.finish().unwrap();
+
+    while let Some(frame) = capture.poll() {
+        write_to_encoder(&frame.data);
+        capture.recycle(frame);
+    }
+}
+```
+
+*/
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use texture::{Texture2d, UncompressedFloatFormat, MipmapsOption};
+use texture::pixel_buffer::{PixelBuffer, PendingPixelRead};
+use uniforms::MagnifySamplerFilter;
+
+use Rect;
+use Surface;
+
+/// Format of the captured frames.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CaptureFormat {
+    /// Four bytes per pixel, in the red, green, blue, alpha order.
+    Rgba,
+
+    /// Planar YUV 4:2:0 (also known as YUV420p), with the BT.601 coefficients and a limited
+    /// range.
+    ///
+    /// The data contains the Y plane with one byte per pixel, then the U plane and the V plane
+    /// with one byte per block of 2x2 pixels. If a dimension is odd, it is rounded up for the
+    /// U and V planes.
+    I420,
+}
+
+/// A frame that has been captured.
+#[derive(Debug, Clone)]
+pub struct CapturedFrame {
+    /// Width of the frame in pixels.
+    pub width: u32,
+
+    /// Height of the frame in pixels.
+    pub height: u32,
+
+    /// Format of `data`.
+    pub format: CaptureFormat,
+
+    /// The content of the frame, tightly packed. The first row is the top of the image.
+    pub data: Vec<u8>,
+}
+
+/// Captures the content of surfaces without stalling the rendering.
+pub struct VideoCapture {
+    context: Rc<Context>,
+    format: CaptureFormat,
+    max_pending: usize,
+
+    // texture in which the surfaces are copied before being read
+    staging: Option<Texture2d>,
+
+    // pixel buffers that are not in use, all with the dimensions of `staging`
+    pool: Vec<PixelBuffer<(u8, u8, u8, u8)>>,
+
+    // reads that have been submitted, from the oldest to the newest
+    pending: VecDeque<(PendingPixelRead<(u8, u8, u8, u8)>, (u32, u32))>,
+
+    // frames that have been read but not returned yet
+    ready: VecDeque<CapturedFrame>,
+
+    // memory of frames that were passed to `recycle`
+    free_data: Vec<Vec<u8>>,
+}
+
+impl VideoCapture {
+    /// Builds a new `VideoCapture`.
+    ///
+    /// `max_pending` is the number of frames that can be in flight before `capture` blocks.
+    /// Each of them uses a pixel buffer of the size of the surface. A value of 2 or 3 is usually
+    /// enough.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `max_pending` is 0.
+    pub fn new<F: ?Sized>(facade: &F, format: CaptureFormat, max_pending: usize) -> VideoCapture
+                          where F: Facade
+    {
+        assert!(max_pending >= 1);
+
+        VideoCapture {
+            context: facade.get_context().clone(),
+            format: format,
+            max_pending: max_pending,
+            staging: None,
+            pool: Vec::with_capacity(max_pending),
+            pending: VecDeque::with_capacity(max_pending),
+            ready: VecDeque::new(),
+            free_data: Vec::new(),
+        }
+    }
+
+    /// Returns the format of the captured frames.
+    #[inline]
+    pub fn get_format(&self) -> CaptureFormat {
+        self.format
+    }
+
+    /// Returns the number of frames that have been captured and are not finished yet.
+    #[inline]
+    pub fn get_pending_frames(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Starts capturing the color buffer of a surface.
+    ///
+    /// If `max_pending` frames are already in flight, this function first blocks until the
+    /// oldest one is finished. The frame is then returned by the next call to `poll`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the surface doesn't have a color buffer.
+    pub fn capture<S>(&mut self, surface: &S) where S: Surface {
+        if self.pending.len() >= self.max_pending {
+            let frame = self.finish_oldest();
+            self.ready.push_back(frame);
+        }
+
+        let (width, height) = surface.get_dimensions();
+
+        // the pool is emptied when the dimensions change
+        let dimensions_changed = self.staging.as_ref()
+                                     .map(|t| (t.get_width(), t.get_height().unwrap()) !=
+                                              (width, height))
+                                     .unwrap_or(true);
+        if dimensions_changed {
+            self.staging = Some(Texture2d::empty_with_format(&self.context,
+                                                             UncompressedFloatFormat::U8U8U8U8,
+                                                             MipmapsOption::NoMipmap,
+                                                             width, height).unwrap());
+            self.pool.clear();
+        }
+
+        let staging = self.staging.as_ref().unwrap();
+        surface.fill(&staging.as_surface(), MagnifySamplerFilter::Nearest);
+
+        let buffer = match self.pool.pop() {
+            Some(buffer) => buffer,
+            None => PixelBuffer::new_empty(&self.context, width as usize * height as usize),
+        };
+
+        let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+        staging.main_level().first_layer().into_image(None).unwrap()
+               .raw_read_to_pixel_buffer(&rect, &buffer);
+
+        let pending = PendingPixelRead::new(&self.context, buffer);
+        self.pending.push_back((pending, (width, height)));
+    }
+
+    /// Returns the oldest captured frame if it is finished. Never blocks.
+    pub fn poll(&mut self) -> Option<CapturedFrame> {
+        if let Some(frame) = self.ready.pop_front() {
+            return Some(frame);
+        }
+
+        let oldest_ready = self.pending.front().map(|p| p.0.is_ready()).unwrap_or(false);
+        if oldest_ready {
+            Some(self.finish_oldest())
+        } else {
+            None
+        }
+    }
+
+    /// Blocks until all the captured frames are finished and returns them, from the oldest to
+    /// the newest.
+    pub fn flush(&mut self) -> Vec<CapturedFrame> {
+        while !self.pending.is_empty() {
+            let frame = self.finish_oldest();
+            self.ready.push_back(frame);
+        }
+
+        self.ready.drain(..).collect()
+    }
+
+    /// Gives back a frame returned by `poll` or `flush`, so that its memory is reused for the
+    /// next frames.
+    #[inline]
+    pub fn recycle(&mut self, frame: CapturedFrame) {
+        self.free_data.push(frame.data);
+    }
+
+    /// Waits for the oldest pending read and converts its content.
+    fn finish_oldest(&mut self) -> CapturedFrame {
+        let (pending, (width, height)) = self.pending.pop_front().unwrap();
+        let mut buffer = pending.wait();
+
+        let mut data = self.free_data.pop().unwrap_or_else(Vec::new);
+        data.clear();
+
+        {
+            let pixels = buffer.map_read();
+            match self.format {
+                CaptureFormat::Rgba => flip_rgba(&pixels, width, height, &mut data),
+                CaptureFormat::I420 => convert_to_i420(&pixels, width, height, &mut data),
+            }
+        }
+
+        // the buffer goes back to the pool only if the dimensions haven't changed since
+        let same_dimensions = self.staging.as_ref()
+                                  .map(|t| (t.get_width(), t.get_height().unwrap()) ==
+                                           (width, height))
+                                  .unwrap_or(false);
+        if same_dimensions {
+            self.pool.push(buffer);
+        }
+
+        CapturedFrame {
+            width: width,
+            height: height,
+            format: self.format,
+            data: data,
+        }
+    }
+}
+
+/// Copies RGBA pixels whose first row is the bottom of the image into `out`, with the first row
+/// at the top.
+fn flip_rgba(pixels: &[(u8, u8, u8, u8)], width: u32, height: u32, out: &mut Vec<u8>) {
+    let width = width as usize;
+    out.reserve(pixels.len() * 4);
+
+    for row in (0 .. height as usize).rev() {
+        for &(r, g, b, a) in &pixels[row * width .. (row + 1) * width] {
+            out.push(r);
+            out.push(g);
+            out.push(b);
+            out.push(a);
+        }
+    }
+}
+
+/// Converts RGBA pixels whose first row is the bottom of the image to planar YUV 4:2:0, with the
+/// first row at the top.
+fn convert_to_i420(pixels: &[(u8, u8, u8, u8)], width: u32, height: u32, out: &mut Vec<u8>) {
+    let (width, height) = (width as usize, height as usize);
+    let (chroma_width, chroma_height) = ((width + 1) / 2, (height + 1) / 2);
+    out.reserve(width * height + 2 * chroma_width * chroma_height);
+
+    // returns the pixel at the given coordinates, with `y = 0` being the top of the image
+    let pixel = |x: usize, y: usize| {
+        let (r, g, b, _) = pixels[(height - 1 - y) * width + x];
+        (r as i32, g as i32, b as i32)
+    };
+
+    for y in 0 .. height {
+        for x in 0 .. width {
+            let (r, g, b) = pixel(x, y);
+            out.push((((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8);
+        }
+    }
+
+    let u_start = out.len();
+    out.resize(u_start + 2 * chroma_width * chroma_height, 0);
+    let (u_plane, v_plane) = out[u_start ..].split_at_mut(chroma_width * chroma_height);
+
+    for cy in 0 .. chroma_height {
+        for cx in 0 .. chroma_width {
+            // averaging the block of 2x2 pixels, which can be smaller at the edges
+            let (mut r, mut g, mut b, mut count) = (0, 0, 0, 0);
+            for y in cy * 2 .. ::std::cmp::min(cy * 2 + 2, height) {
+                for x in cx * 2 .. ::std::cmp::min(cx * 2 + 2, width) {
+                    let p = pixel(x, y);
+                    r += p.0;
+                    g += p.1;
+                    b += p.2;
+                    count += 1;
+                }
+            }
+            let (r, g, b) = (r / count, g / count, b / count);
+
+            let u = ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128;
+            let v = ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128;
+            u_plane[cy * chroma_width + cx] = u as u8;
+            v_plane[cy * chroma_width + cx] = v as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flip_rgba, convert_to_i420};
+
+    #[test]
+    fn flip() {
+        let pixels = [(1, 1, 1, 1), (2, 2, 2, 2), (3, 3, 3, 3), (4, 4, 4, 4)];
+        let mut out = Vec::new();
+        flip_rgba(&pixels, 2, 2, &mut out);
+        assert_eq!(out, vec![3, 3, 3, 3, 4, 4, 4, 4, 1, 1, 1, 1, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn i420_sizes() {
+        let pixels = vec![(255, 255, 255, 255); 3 * 3];
+        let mut out = Vec::new();
+        convert_to_i420(&pixels, 3, 3, &mut out);
+        assert_eq!(out.len(), 9 + 4 + 4);
+
+        // white is Y = 235, U = V = 128
+        assert_eq!(out[0], 235);
+        assert_eq!(out[9], 128);
+        assert_eq!(out[13], 128);
+    }
+}
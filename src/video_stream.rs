@@ -0,0 +1,151 @@
+/*!
+Streams frames into a texture, for example to display a video or the output of a webcam.
+
+Uploading a frame with `Texture2d::write` copies the data synchronously, and the driver may stall
+the CPU until the GPU has finished using the texture. A `VideoStreamTexture` instead copies each
+frame into one of several pixel buffers, then asks the GPU to transfer the content of the buffer
+to the texture. The pixel buffers are used in turn, and a fence is inserted after each transfer
+so that a buffer is only written again once the GPU has finished reading it.
+
+```no_run
+# use glium::Surface;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# fn decode_next_frame() -> Vec<u8> { unimplemented!() }
+use glium::video_stream::VideoStreamTexture;
+
+let mut stream: VideoStreamTexture = VideoStreamTexture::new(&display, 640, 480, 3).unwrap();
+
+loop {
+    if stream.is_ready() {
+        stream.push_frame(&decode_next_frame());
+    }
+
+    let mut target = display.draw();
+    // draw `stream.get_texture()` here
+    target.finish().unwrap();
+}
+```
+
+*/
+use std::mem;
+use std::slice;
+
+use backend::Facade;
+use buffer::{Buffer, BufferType, BufferMode};
+use texture::{Texture2d, UncompressedFloatFormat, MipmapsOption, PixelValue};
+use texture::TextureCreationError;
+
+use SyncFence;
+use TextureExt;
+
+/// A texture whose content is streamed from the CPU frame after frame.
+///
+/// The generic type is the type of the pixels passed to `push_frame`. The texture always has
+/// four 8-bit components.
+pub struct VideoStreamTexture<P = (u8, u8, u8, u8)> where P: PixelValue {
+    texture: Texture2d,
+
+    // the pixel buffers, and the fence inserted after their last transfer
+    buffers: Vec<(Buffer<[P]>, Option<SyncFence>)>,
+
+    // index within `buffers` of the buffer that receives the next frame
+    next: usize,
+}
+
+impl<P> VideoStreamTexture<P> where P: PixelValue {
+    /// Builds a new `VideoStreamTexture` with the given dimensions.
+    ///
+    /// `buffers` is the number of pixel buffers that are used in turn. Each of them has the
+    /// size of a frame. A value of 2 or 3 is usually enough.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `buffers` is 0.
+    pub fn new<F: ?Sized>(facade: &F, width: u32, height: u32, buffers: usize)
+                          -> Result<VideoStreamTexture<P>, TextureCreationError>
+                          where F: Facade
+    {
+        assert!(buffers >= 1);
+
+        let texture = try!(Texture2d::empty_with_format(facade, UncompressedFloatFormat::U8U8U8U8,
+                                                        MipmapsOption::NoMipmap, width, height));
+
+        let pixels = width as usize * height as usize;
+        let buffers = (0 .. buffers).map(|_| {
+            let buffer = Buffer::empty_array(facade, BufferType::PixelUnpackBuffer, pixels,
+                                             BufferMode::Dynamic).unwrap();
+            (buffer, None)
+        }).collect();
+
+        Ok(VideoStreamTexture {
+            texture: texture,
+            buffers: buffers,
+            next: 0,
+        })
+    }
+
+    /// Returns the texture that contains the last frame.
+    #[inline]
+    pub fn get_texture(&self) -> &Texture2d {
+        &self.texture
+    }
+
+    /// Returns the width of the frames.
+    #[inline]
+    pub fn get_width(&self) -> u32 {
+        self.texture.get_width()
+    }
+
+    /// Returns the height of the frames.
+    #[inline]
+    pub fn get_height(&self) -> u32 {
+        self.texture.get_height().unwrap()
+    }
+
+    /// Returns true if `push_frame` can be called without blocking.
+    ///
+    /// If the backend doesn't support fences, this always returns `true` and `push_frame`
+    /// may block.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.buffers[self.next].1.as_ref().map(|f| f.is_signaled()).unwrap_or(true)
+    }
+
+    /// Uploads a frame to the texture.
+    ///
+    /// `data` must contain the pixels of the whole frame, tightly packed. As with
+    /// `RawImage2d::from_raw_rgba`, the first row is the bottom of the texture.
+    ///
+    /// The texture is updated once the GPU executes the transfer, and the commands that use the
+    /// texture are executed after it. If the pixel buffer that receives this frame is still
+    /// being transferred, this function blocks until the transfer has finished.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `data` doesn't match the dimensions of the texture, or if `data`
+    /// is not correctly aligned for the type of the pixels.
+    pub fn push_frame(&mut self, data: &[u8]) {
+        let (width, height) = (self.get_width(), self.get_height());
+        let pixels = width as usize * height as usize;
+
+        assert_eq!(data.len(), pixels * mem::size_of::<P>());
+        assert!(data.as_ptr() as usize % mem::align_of::<P>() == 0,
+                "The data is not aligned for the type of the pixels");
+
+        let data = unsafe { slice::from_raw_parts(data.as_ptr() as *const P, pixels) };
+
+        let context = self.texture.get_context().clone();
+        let &mut (ref buffer, ref mut fence) = &mut self.buffers[self.next];
+
+        if let Some(fence) = fence.take() {
+            fence.wait();
+        }
+
+        buffer.write(data);
+        self.texture.main_level().raw_upload_from_pixel_buffer(buffer.as_slice(), 0 .. width,
+                                                               0 .. height, 0 .. 1);
+        *fence = SyncFence::new(&context).ok();
+
+        self.next = (self.next + 1) % self.buffers.len();
+    }
+}
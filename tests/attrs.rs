@@ -7,7 +7,6 @@ use glium::index::PrimitiveType;
 mod support;
 
 #[test]
-#[should_panic(expected = "The program attribute `field1` does not match the vertex format")]
 fn attribute_types_mismatch() {
     let display = support::build_display();
 
@@ -79,15 +78,21 @@ fn attribute_types_mismatch() {
 
     // drawing a frame
     let mut target = display.draw();
-    target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
-                &Default::default()).unwrap();
+    match target.draw(&vertex_buffer, &index_buffer, &program,
+                      &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(e @ glium::DrawError::AttributeTypeMismatch { .. }) => {
+            let message = e.to_string();
+            assert!(message.contains("`field1` is a `vec2` in the program"), "{}", message);
+        },
+        e => panic!("{:?}", e)
+    }
     target.finish().unwrap();
 
     display.assert_no_error(None);
 }
 
 #[test]
-#[should_panic(expected = "The program attribute `field2` is missing in the vertex bindings")]
 fn missing_attribute() {
     let display = support::build_display();
 
@@ -159,8 +164,20 @@ fn missing_attribute() {
 
     // drawing a frame
     let mut target = display.draw();
+    match target.draw(&vertex_buffer, &index_buffer, &program,
+                      &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::AttributeMissing { ref name }) if name == "field2" => (),
+        e => panic!("{:?}", e)
+    }
+
+    let params = glium::DrawParameters {
+        ignore_missing_attributes: true,
+        .. Default::default()
+    };
+
     target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
-                &Default::default()).unwrap();
+                &params).unwrap();
     target.finish().unwrap();
 
     display.assert_no_error(None);
@@ -307,3 +324,236 @@ attribute_test!(attribute_vec3_i32, [i32; 3], "vec3", [0, 0, 0], "vec4(field1, 1
 attribute_test!(attribute_vec3_tuple_i32, (i32, i32, i32), "vec3", (0, 0, 0), "vec4(field1, 1.0)");
 attribute_test!(attribute_vec4_i32, [i32; 4], "vec4", [0, 0, 0, 0], "field1");
 attribute_test!(attribute_vec4_tuple_i32, (i32, i32, i32, i32), "vec4", (0, 0, 0, 0), "field1");
+
+attribute_test!(attribute_float_f16, glium::vertex::F16, "float", glium::vertex::F16::from_f32(0.0), "vec4(field1, 0.0, 0.0, 1.0)");
+attribute_test!(attribute_vec2_f16, [glium::vertex::F16; 2], "vec2", glium::vertex::F16::array([0.0, 0.0]), "vec4(field1, 0.0, 1.0)");
+attribute_test!(attribute_vec3_f16, [glium::vertex::F16; 3], "vec3", glium::vertex::F16::array([0.0, 0.0, 0.0]), "vec4(field1, 1.0)");
+attribute_test!(attribute_vec4_f16, [glium::vertex::F16; 4], "vec4", glium::vertex::F16::array([0.0, 0.0, 0.0, 0.0]), "field1");
+
+attribute_test!(attribute_vec4_i2i10i10i10, glium::vertex::I2I10I10I10Reversed, "vec4", glium::vertex::I2I10I10I10Reversed::new(0, 0, 0, 0), "field1");
+attribute_test!(attribute_vec4_u2u10u10u10, glium::vertex::U2U10U10U10Reversed, "vec4", glium::vertex::U2U10U10U10Reversed::new(0, 0, 0, 0), "field1");
+
+#[test]
+fn integer_attributes() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        ints: [i32; 2],
+        uints: u32,
+    }
+
+    implement_vertex!(Vertex, position, ints, uints);
+
+    // 16777217 can't be represented by a `f32`, so the test fails if the values are converted
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0, 1.0], ints: [-3, 16777217], uints: 4294967295 },
+        Vertex { position: [1.0, 1.0], ints: [-3, 16777217], uints: 4294967295 },
+        Vertex { position: [-1.0, -1.0], ints: [-3, 16777217], uints: 4294967295 },
+        Vertex { position: [1.0, -1.0], ints: [-3, 16777217], uints: 4294967295 },
+    ]).unwrap();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+            in ivec2 ints;
+            in uint uints;
+            out vec4 v_color;
+
+            void main() {
+                bool ok = ints == ivec2(-3, 16777217) && uints == 4294967295u;
+                v_color = ok ? vec4(0.0, 1.0, 0.0, 1.0) : vec4(1.0, 0.0, 0.0, 1.0);
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            in vec4 v_color;
+            out vec4 color;
+
+            void main() {
+                color = v_color;
+            }
+        ", None)
+    {
+        Ok(p) => p,
+        Err(glium::CompilationError(_)) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer,
+                              &glium::index::NoIndices(PrimitiveType::TriangleStrip), &program,
+                              &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[512][512], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn double_attributes() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        value: f64,
+    }
+
+    implement_vertex!(Vertex, position, value);
+
+    // the difference with 1.0 is lost if the value is converted to a `f32`
+    let value = 1.0 + 1.0 / (1u64 << 40) as f64;
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0, 1.0], value: value },
+        Vertex { position: [1.0, 1.0], value: value },
+        Vertex { position: [-1.0, -1.0], value: value },
+        Vertex { position: [1.0, -1.0], value: value },
+    ]).unwrap();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 410
+
+            in vec2 position;
+            in double value;
+            out vec4 v_color;
+
+            void main() {
+                v_color = value > 1.0lf ? vec4(0.0, 1.0, 0.0, 1.0) : vec4(1.0, 0.0, 0.0, 1.0);
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 410
+
+            in vec4 v_color;
+            out vec4 color;
+
+            void main() {
+                color = v_color;
+            }
+        ", None)
+    {
+        Ok(p) => p,
+        Err(glium::CompilationError(_)) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer,
+                              &glium::index::NoIndices(PrimitiveType::TriangleStrip), &program,
+                              &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn integer_attribute_with_float_data() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, field1);
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &Vec::<Vertex>::new()).unwrap();
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::Points,
+                                               &Vec::<u16>::new()).unwrap();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in ivec2 field1;
+
+                void main() {
+                    gl_Position = vec4(vec2(field1), 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+                out vec4 color;
+                void main() {
+                    color = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            "
+        }
+    ).unwrap();
+
+    let mut target = display.draw();
+    match target.draw(&vertex_buffer, &index_buffer, &program,
+                      &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::AttributeTypeMismatch { ref name, .. }) if name == "field1" => (),
+        e => panic!("{:?}", e)
+    }
+    target.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn integer_attribute_normalized() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1 normalize(true));
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &Vec::<Vertex>::new()).unwrap();
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::Points,
+                                               &Vec::<u16>::new()).unwrap();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in uvec2 field1;
+
+                void main() {
+                    gl_Position = vec4(vec2(field1), 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+                out vec4 color;
+                void main() {
+                    color = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            "
+        }
+    ).unwrap();
+
+    let mut target = display.draw();
+    match target.draw(&vertex_buffer, &index_buffer, &program,
+                      &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::AttributeTypeMismatch { ref name, normalize: true, .. })
+            if name == "field1" => (),
+        e => panic!("{:?}", e)
+    }
+    target.finish().unwrap();
+
+    display.assert_no_error(None);
+}
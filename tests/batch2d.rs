@@ -0,0 +1,65 @@
+#![cfg(feature = "batch2d")]
+
+extern crate glium;
+
+use glium::Surface;
+use glium::batch2d::{self, Batch, Batcher, Sprite};
+
+mod support;
+
+#[test]
+fn draw_sprites() {
+    let display = support::build_display();
+    let mut batcher = Batcher::new(&display).unwrap();
+
+    let red = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let blue = support::build_unicolor_texture2d(&display, 0.0, 0.0, 1.0);
+    let target = support::build_renderable_texture(&display);
+
+    let mut batch = Batch::new();
+    batch.push(&red, Sprite::new([0.0, 0.0], [512.0, 1024.0]));
+    batch.push(&blue, Sprite::new([512.0, 0.0], [512.0, 512.0]));
+    batch.push(&blue, Sprite::new([512.0, 512.0], [512.0, 512.0]).with_color([0.0, 0.0, 0.0, 1.0]));
+
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let draw_calls = batcher.draw(&mut target.as_surface(), &batch,
+                                  batch2d::pixel_projection(1024, 1024),
+                                  &Default::default()).unwrap();
+    assert_eq!(draw_calls, 2);
+    assert!(batcher.capacity() >= 3);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[100][100], (255, 0, 0, 255));
+    assert_eq!(data[100][900], (0, 0, 255, 255));
+    assert_eq!(data[900][900], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sort_by_texture() {
+    let display = support::build_display();
+    let mut batcher = Batcher::new(&display).unwrap();
+
+    let red = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let blue = support::build_unicolor_texture2d(&display, 0.0, 0.0, 1.0);
+    let target = support::build_renderable_texture(&display);
+
+    let mut batch = Batch::new();
+    for i in 0 .. 100 {
+        let texture = if i % 2 == 0 { &red } else { &blue };
+        batch.push(texture, Sprite::new([i as f32 * 10.0, 0.0], [10.0, 10.0]));
+    }
+
+    let projection = batch2d::pixel_projection(1024, 1024);
+    let draw_calls = batcher.draw(&mut target.as_surface(), &batch, projection,
+                                  &Default::default()).unwrap();
+    assert_eq!(draw_calls, 100);
+
+    batch.sort_by_texture();
+    let draw_calls = batcher.draw(&mut target.as_surface(), &batch, projection,
+                                  &Default::default()).unwrap();
+    assert_eq!(draw_calls, 2);
+
+    display.assert_no_error(None);
+}
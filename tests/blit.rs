@@ -56,3 +56,31 @@ fn blit_texture_to_window() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn blit_window_to_texture() {
+    let display = support::build_display();
+
+    let rect = Rect {
+        left: 0,
+        bottom: 0,
+        width: 2,
+        height: 2,
+    };
+
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // the default framebuffer can be in BGRA, for example with ANGLE
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 1.0, 0.0, 1.0);
+    frame.blit_color(&rect, &target.as_surface(), &BlitTarget {
+        left: 0,
+        bottom: 0,
+        width: 2,
+        height: 2,
+    }, glium::uniforms::MagnifySamplerFilter::Nearest);
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
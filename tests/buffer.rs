@@ -115,6 +115,31 @@ fn buffer_immutable_mapping_read() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn buffer_try_map() {
+    let display = support::build_display();
+
+    let mut buffer: glium::buffer::Buffer<[u32]> =
+        glium::buffer::Buffer::new(&display, &[1u32, 2, 3, 4][..],
+                                   glium::buffer::BufferType::ArrayBuffer,
+                                   BufferMode::Dynamic).unwrap();
+
+    match buffer.try_map_write() {
+        Ok(mut mapping) => mapping[1] = 5,
+        Err(glium::buffer::MapError::NotSupported) => return,
+        Err(e) => panic!("{}", e),
+    };
+
+    match buffer.slice_mut(2 .. 4).unwrap().try_map_read() {
+        Ok(mapping) => assert_eq!(&*mapping, &[3, 4]),
+        Err(e) => panic!("{}", e),
+    };
+
+    assert_eq!(buffer.read().unwrap(), vec![1, 5, 3, 4]);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn buffer_dynamic_mapping_read() {
     let display = support::build_display();
@@ -976,3 +1001,116 @@ fn copy_to_slice() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn from_id() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::<[u8]>::new(&display, &[1, 2, 3, 4],
+                                                    glium::buffer::BufferType::ArrayBuffer,
+                                                    BufferMode::Default).unwrap();
+    let id = buffer.get_id();
+    mem::forget(buffer);
+
+    let buffer = unsafe {
+        glium::buffer::Buffer::<[u8]>::from_id(&display, id,
+                                               glium::buffer::BufferType::ArrayBuffer)
+    };
+    assert_eq!(buffer.get_id(), id);
+    assert_eq!(buffer.len(), 4);
+    assert_eq!(buffer.read().unwrap(), [1, 2, 3, 4]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_dynamic_update_from_iter() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1);
+
+    let mut vb = glium::VertexBuffer::dynamic(&display,
+        &[
+            Vertex { field1: [ 2,  3] },
+            Vertex { field1: [ 5,  7] },
+            Vertex { field1: [11, 13] },
+        ]
+    ).unwrap();
+
+    vb.slice_mut(1 .. 3).unwrap().update_from_iter((0 .. 2).map(|i| {
+        Vertex { field1: [20 + i, 30 + i] }
+    }));
+
+    let data = match vb.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data[0].field1, [2, 3]);
+    assert_eq!(data[1].field1, [20, 30]);
+    assert_eq!(data[2].field1, [21, 31]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_immutable_update_from_iter() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1);
+
+    let mut vb = glium::VertexBuffer::immutable(&display,
+        &[
+            Vertex { field1: [ 2,  3] },
+            Vertex { field1: [ 5,  7] },
+        ]
+    ).unwrap();
+
+    vb.update_from_iter(vec![Vertex { field1: [8, 9] }, Vertex { field1: [10, 11] }]);
+
+    let data = match vb.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data[0].field1, [8, 9]);
+    assert_eq!(data[1].field1, [10, 11]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn buffer_update_from_iter_too_short() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1);
+
+    let mut vb = glium::VertexBuffer::dynamic(&display,
+        &[
+            Vertex { field1: [ 2,  3] },
+            Vertex { field1: [ 5,  7] },
+        ]
+    ).unwrap();
+
+    vb.update_from_iter(Some(Vertex { field1: [8, 9] }));
+}
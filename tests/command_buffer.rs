@@ -0,0 +1,116 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::command_buffer::{CommandBuffer, CommandIndices, RecordError, ReplayError};
+use glium::command_buffer::Resources;
+
+use std::thread;
+
+mod support;
+
+#[test]
+fn record_on_other_thread() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut resources = Resources::new();
+    let vb_id = resources.add_vertex_buffer(&vb);
+    let ib_id = resources.add_index_buffer(&ib);
+    let program_id = resources.add_program(&program);
+
+    let commands = thread::spawn(move || {
+        let mut commands = CommandBuffer::new();
+        commands.draw(vb_id, CommandIndices::Buffer(ib_id), program_id, &[],
+                      &Default::default()).unwrap();
+        commands
+    }).join().unwrap();
+
+    assert_eq!(commands.len(), 1);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    commands.replay(&mut texture.as_surface(), &resources).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[512][512], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn append() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut a = CommandBuffer::new();
+    let mut b = CommandBuffer::new();
+    assert!(a.is_empty());
+
+    let mut resources = Resources::new();
+    let vb_id = resources.add_vertex_buffer(&vb);
+    let program_id = resources.add_program(&program);
+
+    let indices = CommandIndices::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+    a.draw(vb_id, indices, program_id, &[], &Default::default()).unwrap();
+    b.draw(vb_id, indices, program_id, &[("unused", 1.0f32.into())],
+           &Default::default()).unwrap();
+    a.append(&mut b);
+
+    assert_eq!(a.len(), 2);
+    assert!(b.is_empty());
+}
+
+#[test]
+fn record_errors() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut resources = Resources::new();
+    let vb_id = resources.add_vertex_buffer(&vb);
+    let program_id = resources.add_program(&program);
+
+    let mut commands = CommandBuffer::new();
+    let indices = CommandIndices::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+
+    let params = glium::DrawParameters {
+        depth: glium::Depth { range: (0.0, 2.0), .. Default::default() },
+        .. Default::default()
+    };
+    assert_eq!(commands.draw(vb_id, indices, program_id, &[], &params),
+               Err(RecordError::InvalidDepthRange));
+
+    let uniforms = [("a", 1.0f32.into()), ("a", 2.0f32.into())];
+    assert_eq!(commands.draw(vb_id, indices, program_id, &uniforms, &Default::default()),
+               Err(RecordError::DuplicateUniform("a".to_owned())));
+
+    assert!(commands.is_empty());
+}
+
+#[test]
+fn replay_unknown_resource() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut resources = Resources::new();
+    let vb_id = resources.add_vertex_buffer(&vb);
+    let program_id = resources.add_program(&program);
+    let uniform_id = resources.add_uniform(1.0f32);
+
+    let mut commands = CommandBuffer::new();
+    let indices = CommandIndices::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+    commands.draw(vb_id, indices, program_id, &[("unused", uniform_id.into())],
+                  &Default::default()).unwrap();
+
+    // the IDs of another table don't exist in this one
+    let other_resources = Resources::new();
+
+    let texture = support::build_renderable_texture(&display);
+    match commands.replay(&mut texture.as_surface(), &other_resources) {
+        Err(ReplayError::UnknownResource) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
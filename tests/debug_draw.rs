@@ -0,0 +1,55 @@
+extern crate glium;
+
+use glium::Surface;
+use glium::debug_draw::DebugDraw;
+
+mod support;
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+#[test]
+fn shapes_lines_count() {
+    let display = support::build_display();
+    let mut debug = DebugDraw::new(&display).unwrap();
+    assert!(debug.is_empty());
+
+    debug.aabb([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0], [1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(debug.len(), 12);
+
+    debug.axis([0.0, 0.0, 0.0], 1.0);
+    assert_eq!(debug.len(), 15);
+
+    debug.sphere([0.0, 0.0, 0.0], 1.0, [1.0, 1.0, 1.0, 1.0]);
+    assert!(debug.len() > 15);
+
+    debug.clear();
+    assert!(debug.is_empty());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn flush_draws_lines() {
+    let display = support::build_display();
+    let mut debug = DebugDraw::new(&display).unwrap();
+    let texture = support::build_renderable_texture(&display);
+
+    // the line goes through the centers of the pixels of row 512
+    let y = 512.5 / 1024.0 * 2.0 - 1.0;
+    debug.line([-1.0, y, 0.0], [1.0, y, 0.0], [1.0, 0.0, 0.0, 1.0]);
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    debug.flush(&mut texture.as_surface(), IDENTITY, &Default::default()).unwrap();
+    assert!(debug.is_empty());
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[512][300], (255, 0, 0, 255));
+    assert_eq!(data[100][300], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
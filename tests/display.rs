@@ -82,6 +82,53 @@ fn timestamp_query() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn timestamp_query_try_get() {
+    let display = support::build_display();
+
+    let query = match glium::debug::TimestampQuery::new(&display) {
+        Some(q) => q,
+        None => return
+    };
+
+    display.finish();
+    assert!(query.try_get().is_some());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn profiler_report() {
+    let display = support::build_display();
+
+    let profiler = match glium::debug::profiler::Profiler::new(&display) {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // the queries of the first frames are reused by the next ones
+    let mut reports = Vec::new();
+    for _ in 0 .. 8 {
+        {
+            let _frame = profiler.scope("frame");
+            let _a = profiler.scope("a");
+        }
+
+        reports.extend(profiler.end_frame());
+        display.finish();
+    }
+
+    assert!(!reports.is_empty());
+    for report in reports {
+        assert_eq!(report.scopes.len(), 1);
+        assert_eq!(report.scopes[0].name, "frame");
+        assert_eq!(report.scopes[0].children.len(), 1);
+        assert_eq!(report.scopes[0].children[0].name, "a");
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn sync() {
     let display = support::build_display();
@@ -94,6 +141,17 @@ fn sync() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn sync_try_wait() {
+    let display = support::build_display();
+
+    if let Ok(fence) = glium::SyncFence::new(&display) {
+        assert_eq!(fence.try_wait(), Ok(()));
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn multiple_displays() {
     let display1 = support::build_display();
@@ -141,6 +199,256 @@ fn debug_string() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn debug_group() {
+    // tests that debug groups don't trigger an OpenGL error
+    let display = support::build_display();
+    let value = display.debug_group("outer", || {
+        display.debug_group("inner", || 5)
+    });
+    assert_eq!(value, 5);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn object_labels() {
+    // tests that setting labels doesn't trigger an OpenGL error
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    vb.set_object_label("vertices").ok();
+    ib.set_object_label("indices").ok();
+    program.set_object_label("program").ok();
+    texture.set_object_label("texture").ok();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn set_debug_callback() {
+    let display = support::build_display();
+    display.set_debug_callback(glium::debug::DebugCallbackBehavior::Custom {
+        callback: Box::new(|_, _, _, _, _, _| ()),
+        synchronous: true,
+    });
+    display.set_debug_callback(glium::debug::DebugCallbackBehavior::Ignore);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn check_errors() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    // the errors are only checked in debug mode
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let display = support::build_display();
+    display.set_debug_callback(glium::debug::DebugCallbackBehavior::CheckErrors);
+
+    // triggers a `GL_INVALID_ENUM`, which is consumed with `glGetError` at the end of the call
+    unsafe { display.exec_with_raw_gl(|gl| gl.Enable(0xffff)); }
+    let error = unsafe { display.exec_with_raw_gl(|gl| gl.GetError()) };
+    assert_eq!(error, glium::gl::NO_ERROR);
+
+    // the error is still reported
+    let result = panic::catch_unwind(AssertUnwindSafe(|| display.assert_no_error(None)));
+    assert!(result.is_err());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn capabilities_report() {
+    let display = support::build_display();
+    let report = display.capabilities();
+
+    assert_eq!(&report.opengl_version, display.get_opengl_version());
+    assert!(report.max_texture_size >= 64);
+    assert!(report.max_vertex_attribs >= 8);
+    assert!(!format!("{}", report).is_empty());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn extension_names() {
+    let display = support::build_display();
+    let names = display.get_extension_names();
+
+    for name in names {
+        assert!(display.has_extension(name));
+    }
+    assert!(!display.has_extension("GL_GLIUM_nonexistent_extension"));
+    assert_eq!(display.capabilities().extensions, names);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(not(feature = "test_headless"))]
+fn disabled_extensions() {
+    let display = support::build_display();
+    let name = match display.get_extension_names().first() {
+        Some(name) => name.clone(),
+        None => return,
+    };
+
+    let events_loop = glium::glutin::EventsLoop::new();
+    let window = glium::glutin::WindowBuilder::new().with_visibility(false);
+    let context = glium::glutin::ContextBuilder::new();
+    let gl_window = glium::glutin::GlWindow::new(window, context, &events_loop).unwrap();
+    let display = glium::Display::with_disabled_extensions(gl_window, &[&name[..]]).unwrap();
+
+    assert!(!display.has_extension(&name));
+    assert!(display.get_extension_names().iter().all(|e| e != &name));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn robust_buffer_access_behavior() {
+    let display = support::build_display();
+
+    // the behavior is only defined with robust contexts
+    if display.has_robust_buffer_access_behavior() {
+        assert!(display.is_robust());
+    }
+    assert_eq!(display.capabilities().robust_buffer_access_behavior,
+               display.has_robust_buffer_access_behavior());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn memory_info() {
+    let display = support::build_display();
+    let before = display.memory_info();
+
+    let buffer = glium::VertexBuffer::new(&display, &[[0.0f32; 4]; 16]).unwrap();
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            64, 64).unwrap();
+
+    let info = display.memory_info();
+    assert_eq!(info.buffers_count, before.buffers_count + 1);
+    assert_eq!(info.buffers_memory, before.buffers_memory + 256);
+    assert_eq!(info.textures_count, before.textures_count + 1);
+    assert_eq!(info.textures_memory, before.textures_memory + 64 * 64 * 4);
+    assert!(display.get_allocations().iter().any(|a| {
+        a.kind == glium::backend::AllocationKind::Buffer && a.size == 256
+    }));
+
+    drop(buffer);
+    drop(texture);
+    assert_eq!(display.memory_info().buffers_count, before.buffers_count);
+    assert_eq!(display.memory_info().textures_count, before.textures_count);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn memory_info_specific_format() {
+    let display = support::build_display();
+    let before = display.memory_info();
+
+    // the size is computed from the requested format, without querying the backend
+    let texture = match glium::Texture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedFloatFormat::F32F32,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            32, 32)
+    {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    assert_eq!(display.memory_info().textures_memory, before.textures_memory + 32 * 32 * 8);
+
+    drop(texture);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn named_resources() {
+    let display = support::build_display();
+
+    let format = glium::texture::UncompressedFloatFormat::U8U8U8U8;
+    let texture = glium::Texture2d::empty_with_format(&display, format,
+                                                      glium::texture::MipmapsOption::NoMipmap,
+                                                      16, 16).unwrap();
+    let _ = texture.set_object_label("shadow map");
+
+    let allocation = display.get_allocations().into_iter()
+                            .find(|a| a.kind == glium::backend::AllocationKind::Texture &&
+                                      a.name.as_ref().map(|n| &n[..]) == Some("shadow map"))
+                            .unwrap();
+    assert_eq!(allocation.size, 16 * 16 * 4);
+    assert!(allocation.format.unwrap().contains("U8U8U8U8"));
+
+    let dump = display.dump_resources();
+    assert!(dump.lines().any(|l| l.contains("\"shadow map\": 1024 bytes")), "{}", dump);
+
+    drop(texture);
+    assert!(!display.dump_resources().contains("shadow map"));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn deferred_destruction() {
+    let display = support::build_display();
+    let before = display.memory_info();
+
+    display.set_deferred_destruction(true);
+    assert!(display.is_destruction_deferred());
+
+    let buffer = glium::VertexBuffer::new(&display, &[[0.0f32; 4]; 16]).unwrap();
+    let texture = glium::Texture2d::empty(&display, 64, 64).unwrap();
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    drop(framebuffer);
+
+    drop(buffer);
+    drop(texture);
+    assert_eq!(display.memory_info().buffers_count, before.buffers_count + 1);
+    assert_eq!(display.memory_info().textures_count, before.textures_count + 1);
+
+    // the objects are destroyed by the first call to `collect` after the GPU is done
+    display.collect();
+    display.finish();
+    display.collect();
+    assert_eq!(display.memory_info().buffers_count, before.buffers_count);
+    assert_eq!(display.memory_info().textures_count, before.textures_count);
+
+    // `collect` is also called when the buffers are swapped
+    let texture = glium::Texture2d::empty(&display, 64, 64).unwrap();
+    drop(texture);
+    display.draw().finish().unwrap();
+    display.finish();
+    display.draw().finish().unwrap();
+    assert_eq!(display.memory_info().textures_count, before.textures_count);
+
+    // and when the deferred destruction is disabled
+    let texture = glium::Texture2d::empty(&display, 64, 64).unwrap();
+    drop(texture);
+    display.set_deferred_destruction(false);
+    assert_eq!(display.memory_info().textures_count, before.textures_count);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn fallback_report() {
+    let display = support::build_display();
+    assert!(!format!("{}", display.fallback_report()).is_empty());
+
+    display.clear_fallback_report();
+    assert!(display.fallback_report().is_empty());
+
+    display.assert_no_error(None);
+}
 
 #[test]
 fn is_context_lost() {
@@ -149,3 +457,254 @@ fn is_context_lost() {
     display.is_context_lost();
     display.assert_no_error(None);
 }
+
+#[test]
+fn swap_interval() {
+    use glium::backend::{SwapInterval, SwapIntervalError};
+
+    let display = support::build_display();
+
+    match display.set_swap_interval(SwapInterval::Wait(1)) {
+        Ok(()) | Err(SwapIntervalError::NotSupported) => (),
+        Err(e) => panic!("{:?}", e),
+    }
+
+    assert!(display.set_swap_interval(SwapInterval::Mailbox).is_err());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_pacing() {
+    use glium::frame_pacing;
+
+    let display = support::build_display();
+    frame_pacing::set_max_frames_in_flight(&display, Some(1));
+    assert_eq!(frame_pacing::get_max_frames_in_flight(&display), Some(1));
+
+    for _ in 0 .. 3 {
+        frame_pacing::wait_for_frame_slot(&display);
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 0.0);
+        frame.finish().unwrap();
+        assert!(frame_pacing::get_frames_in_flight(&display) <= 1);
+    }
+
+    frame_pacing::set_max_frames_in_flight(&display, None);
+    assert_eq!(frame_pacing::get_frames_in_flight(&display), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn reset_state() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    // after a reset, glium must set again everything that the draw call needs
+    display.reset_state();
+
+    texture.as_surface().clear_color(0.0, 0.0, 1.0, 1.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[1023][1023], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn exec_with_raw_gl() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    unsafe {
+        display.exec_with_raw_gl(|gl| {
+            gl.Enable(glium::gl::SCISSOR_TEST);
+            gl.Scissor(0, 0, 1, 1);
+            gl.ColorMask(0, 0, 0, 0);
+        });
+    }
+
+    // glium must not be affected by the state left by the raw OpenGL calls
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[1023][1023], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn resource_registry() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use glium::recovery::ResourceRegistry;
+
+    let display = support::build_display();
+    let mut registry = ResourceRegistry::new();
+
+    let calls = Rc::new(Cell::new(0));
+    let id = {
+        let calls = calls.clone();
+        registry.register(&display, move |facade| {
+            glium::VertexBuffer::new(facade, &[1u8, 2, 3]).unwrap();
+            calls.set(calls.get() + 1);
+        })
+    };
+    assert_eq!(calls.get(), 1);
+
+    registry.recreate(&display);
+    assert_eq!(calls.get(), 2);
+
+    registry.unregister(id);
+    registry.recreate(&display);
+    assert_eq!(calls.get(), 2);
+
+    assert!(!display.get_context().is_context_lost());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(not(feature = "test_headless"))]
+fn shared_context() {
+    use std::sync::mpsc;
+    use std::thread;
+    use glium::buffer::{Buffer, BufferMode, BufferType};
+
+    let display = support::build_display();
+    let shared = support::build_shared_context(&display);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let worker = shared.build().unwrap();
+
+        let texture = glium::Texture2d::empty(&worker, 2, 2).unwrap();
+        texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+        let buffer = Buffer::new(&worker, &[1u32, 2, 3][..], BufferType::ArrayBuffer,
+                                 BufferMode::Default).unwrap();
+
+        worker.assert_no_error(None);
+        sender.send((texture.into_shared(), buffer.into_shared())).unwrap();
+    }).join().unwrap();
+
+    let (texture, buffer) = receiver.recv().unwrap();
+    let texture = glium::Texture2d::from_shared(&display, texture);
+    let buffer = Buffer::from_shared(&display, buffer);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[1][1], (0, 255, 0, 255));
+    assert_eq!(buffer.read().unwrap(), vec![1, 2, 3]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(not(feature = "test_headless"))]
+fn shared_context_dropped_handles() {
+    use std::sync::mpsc;
+    use std::thread;
+    use glium::buffer::{Buffer, BufferMode, BufferType};
+
+    let display = support::build_display();
+    let shared = support::build_shared_context(&display);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let worker = shared.build().unwrap();
+
+        let texture = glium::Texture2d::empty(&worker, 2, 2).unwrap();
+        let buffer = Buffer::new(&worker, &[1u32, 2, 3][..], BufferType::ArrayBuffer,
+                                 BufferMode::Default).unwrap();
+
+        // this handle is dropped by the worker thread
+        drop(glium::Texture2d::empty(&worker, 2, 2).unwrap().into_shared());
+
+        worker.assert_no_error(None);
+        sender.send((texture.into_shared(), buffer.into_shared())).unwrap();
+    }).join().unwrap();
+
+    // the handles are dropped without being attached to the display, which destroys the objects
+    // when the frame is finished
+    drop(receiver.recv().unwrap());
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(not(feature = "test_headless"))]
+fn multiple_windows() {
+    let display = support::build_display();
+    let window = support::build_window(&display);
+
+    // the resources of the display are used to draw on the window
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    let mut frame = window.draw().unwrap();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+               &Default::default()).unwrap();
+    frame.finish().unwrap();
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+               &Default::default()).unwrap();
+    frame.finish().unwrap();
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+
+    drop(window);
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(not(feature = "test_headless"))]
+fn multiple_windows_switch() {
+    let display = support::build_display();
+    let window = support::build_window(&display);
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    for _ in 0 .. 3 {
+        let mut frame = window.draw().unwrap();
+        frame.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                   &Default::default()).unwrap();
+        frame.finish().unwrap();
+
+        let mut frame = display.draw();
+        frame.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                   &Default::default()).unwrap();
+        frame.finish().unwrap();
+    }
+
+    // the window is dropped while the context is drawing on it
+    let mut frame = window.draw().unwrap();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.finish().unwrap();
+    drop(window);
+
+    let mut frame = display.draw();
+    frame.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+               &Default::default()).unwrap();
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::draw_list::DrawList;
+
+mod support;
+
+#[test]
+fn draw_list_submit() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let uniforms = uniform!{};
+    let params = Default::default();
+
+    let mut list = DrawList::new();
+    assert!(list.is_empty());
+    list.draw(&vb, &ib, &program, &uniforms, &params);
+    list.draw(&vb, &ib, &program, &uniforms, &params);
+    list.sort();
+    assert_eq!(list.len(), 2);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    list.submit(&mut texture.as_surface()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[512][512], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_list_submit_instanced() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+            in vec2 offset;
+
+            void main() {
+                gl_Position = vec4(position * vec2(0.5, 1.0) + offset, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            uniform vec4 color;
+            out vec4 f_color;
+
+            void main() {
+                f_color = color;
+            }
+        ",
+        None) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let left = uniform! { offset: [-0.5, 0.0f32], color: [1.0, 0.0, 0.0, 1.0f32] };
+    let right = uniform! { offset: [0.5, 0.0f32], color: [1.0, 0.0, 0.0, 1.0f32] };
+    let green = uniform! { offset: [0.5, 0.0f32], color: [0.0, 1.0, 0.0, 1.0f32] };
+    let params = Default::default();
+
+    let mut list = DrawList::new();
+    list.draw(&vb, &ib, &program, &left, &params);
+    list.draw(&vb, &ib, &program, &right, &params);
+    list.draw(&vb, &ib, &program, &green, &params);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let report = match list.submit_instanced(&display, &mut texture.as_surface()) {
+        Ok(r) => r,
+        Err(glium::DrawError::InstancingNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(report.batch_sizes, vec![2, 1]);
+    assert_eq!(report.draw_calls(), 2);
+    assert_eq!(report.commands(), 3);
+    assert_eq!(report.largest_batch(), 2);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[512][256], (255, 0, 0, 255));
+    assert_eq!(data[512][768], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
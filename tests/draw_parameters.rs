@@ -1267,7 +1267,7 @@ fn primitive_bounding_box() {
     let display = support::build_display();
 
     let params = glium::DrawParameters {
-        primitive_bounding_box: (0.0 .. 1.0, -0.2 .. 0.3, 0.0 .. 1.0, -1.0 .. 1.0),
+        primitive_bounding_box: Some((0.0 .. 1.0, -0.2 .. 0.3, 0.0 .. 1.0, -1.0 .. 1.0)),
         .. Default::default()
     };
 
@@ -1407,4 +1407,174 @@ fn primitive_restart_index() {
     }
 
     display.assert_no_error(None);
-}
\ No newline at end of file
+}
+
+#[test]
+fn primitive_restart_index_reenabled() {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: (f32, f32, f32),
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display, &[
+            Vertex { position: (-0.5, -0.5, 0.0) },
+            Vertex { position: ( 0.5, -0.5, 0.0) },
+            Vertex { position: (-0.5,  0.5, 0.0) },
+            Vertex { position: ( 0.5,  0.5, 0.0) },
+        ]).unwrap()
+    };
+
+    let restarted = glium::IndexBuffer::<u8>::new(&display,
+                                                  glium::index::PrimitiveType::LineStrip,
+                                                  &[0, 1, 255, 2, 3]).unwrap();
+    let continuous = glium::IndexBuffer::<u8>::new(&display,
+                                                   glium::index::PrimitiveType::LineStrip,
+                                                   &[0, 1, 2, 3]).unwrap();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec3 position;
+
+                void main() {
+                    gl_Position = vec4(position, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    let restart = glium::DrawParameters {
+        primitive_restart_index: true,
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the state cache must enable primitive restarting again after it has been disabled
+    texture.as_surface().clear_color(1.0, 1.0, 1.0, 1.0);
+    match texture.as_surface().draw(&vertex_buffer, &restarted, &program,
+                                    &glium::uniforms::EmptyUniforms, &restart)
+    {
+        Ok(_) => (),
+        Err(glium::DrawError::FixedIndexRestartingNotSupported) => return,
+        e => e.unwrap(),
+    }
+    texture.as_surface().draw(&vertex_buffer, &continuous, &program,
+                              &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    texture.as_surface().clear_color(1.0, 1.0, 1.0, 1.0);
+    texture.as_surface().draw(&vertex_buffer, &restarted, &program,
+                              &glium::uniforms::EmptyUniforms, &restart).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    let mid_x = texture.get_width() as usize / 2;
+    let mid_y = texture.get_height().unwrap() as usize / 2;
+
+    for row in (mid_y - 2)..(mid_y + 2) {
+        for pixel in (mid_x - 2)..(mid_x + 2) {
+            assert_eq!(data[row][pixel], (255, 255, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn point_parameters() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        point_fade_threshold_size: Some(2.0),
+        point_sprite_coord_origin: glium::draw_parameters::PointSpriteCoordOrigin::LowerLeft,
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Ok(_) => (),
+        Err(glium::DrawError::PointParametersNotSupported) => (),
+        e => e.unwrap(),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_point_size() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        program_point_size: Some(true),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Ok(_) => (),
+        Err(glium::DrawError::ProgramPointSizeNotSupported) => (),
+        e => e.unwrap(),
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,85 @@
+extern crate glium;
+
+use glium::Surface;
+use glium::backend::Facade;
+use glium::effects::{self, FullscreenTriangle, Pass, PassList, PingPong};
+use glium::texture::UncompressedFloatFormat;
+
+mod support;
+
+fn build_program<F: ?Sized>(facade: &F, fragment_shader: &str) -> glium::Program
+                            where F: Facade
+{
+    glium::Program::from_source(facade, effects::VERTEX_SHADER, fragment_shader, None).unwrap()
+}
+
+#[test]
+fn pass_list() {
+    let display = support::build_display();
+
+    // moves the blue component to the red component
+    let swap = build_program(&display, "
+        #version 140
+
+        uniform sampler2D input;
+        in vec2 v_tex_coords;
+        out vec4 color;
+
+        void main() {
+            color = vec4(texture(input, v_tex_coords).b, 0.0, 0.0, 1.0);
+        }
+    ");
+
+    let scale = build_program(&display, "
+        #version 140
+
+        uniform sampler2D image;
+        uniform float factor;
+        in vec2 v_tex_coords;
+        out vec4 color;
+
+        void main() {
+            color = vec4(texture(image, v_tex_coords).rgb * factor, 1.0);
+        }
+    ");
+
+    let fullscreen = FullscreenTriangle::new(&display).unwrap();
+    let mut buffers = PingPong::new(&display, UncompressedFloatFormat::U8U8U8U8, 64, 64).unwrap();
+    let source = support::build_unicolor_texture2d(&display, 0.0, 0.0, 1.0);
+    let target = support::build_renderable_texture(&display);
+
+    let passes = PassList::new()
+        .with_pass(Pass::new(&swap))
+        .with_pass(Pass::new(&scale).with_input_name("image").with_uniform("factor", 1.0f32))
+        .with_pass(Pass::new(&scale).with_input_name("image").with_uniform("factor", 0.5f32));
+
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    passes.apply(&fullscreen, &source, &mut buffers, &mut target.as_surface()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert!(pixel.0 >= 126 && pixel.0 <= 129);
+            assert_eq!((pixel.1, pixel.2, pixel.3), (0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn empty_pass_list() {
+    let display = support::build_display();
+
+    let fullscreen = FullscreenTriangle::new(&display).unwrap();
+    let mut buffers = PingPong::new(&display, UncompressedFloatFormat::U8U8U8U8, 64, 64).unwrap();
+    let target = support::build_renderable_texture(&display);
+
+    target.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+    PassList::new().apply_in_place(&fullscreen, &mut buffers, &mut target.as_surface()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
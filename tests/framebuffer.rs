@@ -2,6 +2,7 @@
 extern crate glium;
 
 use glium::Surface;
+use glium::vertex::IntoVerticesSource;
 
 mod support;
 
@@ -61,6 +62,34 @@ fn no_depth_buffer_depth_write() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn no_depth_buffer_validation_level_none() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                            glium::texture::MipmapsOption::NoMipmap, 128, 128).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    let parameters = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    // the program doesn't have any uniform, so skipping the checks is safe
+    unsafe { display.set_validation_level(glium::debug::ValidationLevel::None); }
+    assert_eq!(display.get_validation_level(), glium::debug::ValidationLevel::None);
+
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program,
+                     &glium::uniforms::EmptyUniforms, &parameters).unwrap();
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn simple_dimensions() {
     let display = support::build_display();
@@ -76,6 +105,20 @@ fn simple_dimensions() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn simple_get_id() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    assert!(framebuffer.get_id() != 0);
+    assert_eq!(framebuffer.get_id(), framebuffer.get_id());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn simple_render_to_texture() {
     let display = support::build_display();
@@ -181,6 +224,64 @@ fn depth_texture2d() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn read_depth() {
+    use std::iter;
+
+    let display = support::build_display();
+
+    let color = glium::Texture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            128, 128).unwrap();
+
+    let depth_data = iter::repeat(iter::repeat(0.5f32).take(128).collect::<Vec<_>>())
+                                  .take(128).collect::<Vec<_>>();
+    let depth = match glium::texture::DepthTexture2d::new(&display, depth_data) {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    let texture_values: Vec<Vec<f32>> = match depth.read_depth() {
+        Ok(v) => v,
+        Err(glium::texture::ReadError::AttachmentTypeNotSupported) => return,
+        Err(e) => panic!("{}", e)
+    };
+    assert!((texture_values[0][0] - 0.5).abs() < 0.01);
+    assert!((texture_values[127][127] - 0.5).abs() < 0.01);
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                                   &color, &depth).unwrap();
+    framebuffer.clear_depth(0.25);
+
+    let surface_values: Vec<Vec<f32>> = match framebuffer.read_depth() {
+        Ok(v) => v,
+        Err(glium::texture::ReadError::AttachmentTypeNotSupported) => return,
+        Err(e) => panic!("{}", e)
+    };
+    assert!((surface_values[64][64] - 0.25).abs() < 0.01);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_depth_no_depth_buffer() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    match texture.as_surface().read_depth::<Vec<Vec<f32>>>() {
+        Err(glium::texture::ReadError::NoDepthBuffer) => (),
+        _ => panic!()
+    }
+
+    match texture.as_surface().read_stencil::<Vec<Vec<u8>>>() {
+        Err(glium::texture::ReadError::NoStencilBuffer) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn multioutput() {
     let display = support::build_display();
@@ -447,3 +548,104 @@ fn empty_framebuffer_simple_draw() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn pbuffer_draw_and_read() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let pbuffer = glium::framebuffer::Pbuffer::new(&display, 300, 200).unwrap();
+    assert_eq!(pbuffer.get_dimensions(), (300, 200));
+    assert!(pbuffer.has_depth_buffer());
+
+    let parameters = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    let mut surface = pbuffer.draw();
+    surface.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 1.0);
+    surface.draw(&vertex_buffer, &index_buffer, &program,
+                 &glium::uniforms::EmptyUniforms, &parameters).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = pbuffer.read();
+    assert_eq!(data.len(), 200);
+    for row in data.iter() {
+        assert_eq!(row.len(), 300);
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_dyn_on_trait_object() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    fn draw_on(target: &mut Surface, vertex_buffer: &glium::vertex::VertexBufferAny,
+               index_buffer: &glium::index::IndexBufferAny, program: &glium::Program)
+    {
+        target.clear_color(0.0, 0.0, 0.0, 0.0);
+        target.draw_dyn(&[vertex_buffer.into_vertices_source()], index_buffer.into(), program,
+                        &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+    }
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    draw_on(&mut framebuffer, &vertex_buffer, &index_buffer, &program);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn render_target_pool_recycles() {
+    use glium::GlObject;
+    use glium::framebuffer::RenderTargetPool;
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+    let pool = RenderTargetPool::new(&display);
+
+    let first_id = {
+        let target = pool.get_texture(64, 64, UncompressedFloatFormat::U8U8U8U8).unwrap();
+        target.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+        assert_eq!(pool.get_loaned_textures_count(), 1);
+        target.texture().unwrap().get_id()
+    };
+
+    assert_eq!(pool.get_loaned_textures_count(), 0);
+    assert_eq!(pool.get_free_textures_count(), 1);
+
+    {
+        let a = pool.get_texture(64, 64, UncompressedFloatFormat::U8U8U8U8).unwrap();
+        let b = pool.get_texture(64, 64, UncompressedFloatFormat::U8U8U8U8).unwrap();
+        let c = pool.get_texture(32, 32, UncompressedFloatFormat::U8U8U8U8).unwrap();
+        assert_eq!(a.texture().unwrap().get_id(), first_id);
+        assert!(b.texture().unwrap().get_id() != first_id);
+        assert_eq!(c.texture().unwrap().dimensions(), (32, 32));
+    }
+
+    assert_eq!(pool.get_free_textures_count(), 3);
+
+    pool.end_frame();
+    pool.end_frame();
+    assert_eq!(pool.get_free_textures_count(), 3);
+    pool.end_frame();
+    assert_eq!(pool.get_free_textures_count(), 0);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "graph")]
+
+extern crate glium;
+
+use glium::Surface;
+use glium::framebuffer::{RenderTargetKey, RenderTargetPool};
+use glium::graph::{GraphError, RenderGraph};
+use glium::texture::UncompressedFloatFormat;
+use glium::uniforms::MagnifySamplerFilter;
+
+mod support;
+
+#[test]
+fn execute_graph() {
+    let display = support::build_display();
+    let pool = RenderTargetPool::new(&display);
+    let key = RenderTargetKey::new(64, 64, UncompressedFloatFormat::U8U8U8U8);
+
+    let red = glium::Texture2d::empty(&display, 64, 64).unwrap();
+    let green = glium::Texture2d::empty(&display, 64, 64).unwrap();
+
+    let report = {
+        let mut graph = RenderGraph::new(&display);
+        let x = graph.create_target("x", key);
+        let y = graph.create_target("y", key);
+        let unused = graph.create_target("unused", key);
+        let red_output = graph.import("red", &red);
+        let green_output = graph.import("green", &green);
+
+        graph.add_pass("copy y", &[y], &[green_output], |resources| {
+            resources.texture(y).unwrap().as_surface()
+                     .fill(&resources.surface(green_output), MagnifySamplerFilter::Nearest);
+            Ok(())
+        });
+
+        graph.add_pass("clear x", &[], &[x], |resources| {
+            resources.surface(x).clear_color(1.0, 0.0, 0.0, 1.0);
+            Ok(())
+        });
+
+        graph.add_pass("copy x", &[x], &[red_output], |resources| {
+            resources.texture(x).unwrap().as_surface()
+                     .fill(&resources.surface(red_output), MagnifySamplerFilter::Nearest);
+            Ok(())
+        });
+
+        graph.add_pass("clear unused", &[], &[unused], |resources| {
+            resources.surface(unused).clear_color(0.0, 0.0, 1.0, 1.0);
+            Ok(())
+        });
+
+        graph.add_pass("clear y", &[], &[y], |resources| {
+            resources.surface(y).clear_color(0.0, 1.0, 0.0, 1.0);
+            Ok(())
+        });
+
+        graph.execute(&pool).unwrap()
+    };
+
+    assert_eq!(report.order, vec!["clear x", "copy x", "clear y", "copy y"]);
+    assert_eq!(report.culled, vec!["clear unused"]);
+
+    // `y` reuses the texture of `x`
+    assert_eq!(report.allocations, 2);
+    assert_eq!(pool.get_loaned_textures_count(), 0);
+    assert_eq!(pool.get_free_textures_count(), 1);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = red.read();
+    assert_eq!(data[10][10], (255, 0, 0, 255));
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = green.read();
+    assert_eq!(data[10][10], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn cycle() {
+    let display = support::build_display();
+    let pool = RenderTargetPool::new(&display);
+    let key = RenderTargetKey::new(64, 64, UncompressedFloatFormat::U8U8U8U8);
+
+    let mut graph = RenderGraph::new(&display);
+    let a = graph.create_target("a", key);
+    let b = graph.create_target("b", key);
+
+    graph.add_pass("a to b", &[a], &[b], |_| Ok(()));
+    graph.add_pass("b to a", &[b], &[a], |_| Ok(()));
+    graph.add_pass("present", &[a, b], &[], |_| panic!());
+
+    match graph.execute(&pool) {
+        Err(GraphError::Cycle) => (),
+        _ => panic!(),
+    }
+
+    assert_eq!(pool.get_free_textures_count(), 0);
+    display.assert_no_error(None);
+}
@@ -0,0 +1,106 @@
+extern crate glium;
+
+use glium::Surface;
+use glium::backend::Facade;
+use glium::framebuffer::SimpleFrameBuffer;
+use glium::ibl::IblBaker;
+use glium::texture::{CubeLayer, Cubemap, UncompressedFloatFormat};
+use glium::uniforms::MagnifySamplerFilter;
+
+mod support;
+
+const FACES: [CubeLayer; 6] = [CubeLayer::PositiveX, CubeLayer::NegativeX,
+                               CubeLayer::PositiveY, CubeLayer::NegativeY,
+                               CubeLayer::PositiveZ, CubeLayer::NegativeZ];
+
+/// Copies a face of a mipmap of a cubemap to a texture and reads it.
+fn read_face<F: ?Sized>(display: &F, cubemap: &Cubemap, level: u32, layer: CubeLayer)
+                        -> Vec<Vec<(u8, u8, u8, u8)>> where F: Facade
+{
+    let mipmap = cubemap.mipmap(level).unwrap();
+    let size = mipmap.width();
+
+    let texture = glium::Texture2d::empty(display, size, size).unwrap();
+    let face = SimpleFrameBuffer::new(display, mipmap.image(layer)).unwrap();
+    face.fill(&texture.as_surface(), MagnifySamplerFilter::Nearest);
+    texture.read()
+}
+
+fn assert_color(data: &[Vec<(u8, u8, u8, u8)>], expected: (u8, u8, u8)) {
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert!((pixel.0 as i32 - expected.0 as i32).abs() <= 3, "{:?}", pixel);
+            assert!((pixel.1 as i32 - expected.1 as i32).abs() <= 3, "{:?}", pixel);
+            assert!((pixel.2 as i32 - expected.2 as i32).abs() <= 3, "{:?}", pixel);
+        }
+    }
+}
+
+#[test]
+fn uniform_environment() {
+    let display = support::build_display();
+    let baker = IblBaker::new(&display).unwrap();
+    let format = UncompressedFloatFormat::F16F16F16F16;
+
+    // the light is the same in every direction, so every texture must contain this color
+    let source = support::build_unicolor_texture2d(&display, 1.0, 0.0, 1.0);
+
+    let environment = baker.equirectangular_to_cubemap(&source, 32, format).unwrap();
+    assert!(environment.get_mipmap_levels() > 1);
+
+    let irradiance = baker.irradiance(&environment, 8, format).unwrap();
+    let specular = baker.prefilter(&environment, 16, 3, format).unwrap();
+    assert_eq!(specular.get_mipmap_levels(), 3);
+
+    for &layer in FACES.iter() {
+        assert_color(&read_face(&display, &environment, 0, layer), (255, 0, 255));
+        assert_color(&read_face(&display, &irradiance, 0, layer), (255, 0, 255));
+
+        for level in 0 .. 3 {
+            assert_color(&read_face(&display, &specular, level, layer), (255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn equirectangular_directions() {
+    let display = support::build_display();
+    let baker = IblBaker::new(&display).unwrap();
+
+    // the bottom row of the image is the floor, and the top row is the sky
+    let source = glium::Texture2d::new(&display, vec![
+        vec![(0u8, 255u8, 0u8), (0, 255, 0)],
+        vec![(0u8, 0u8, 255u8), (0, 0, 255)],
+    ]).unwrap();
+
+    let environment = baker.equirectangular_to_cubemap(&source, 16,
+                                                       UncompressedFloatFormat::U8U8U8U8)
+                           .unwrap();
+
+    let floor = read_face(&display, &environment, 0, CubeLayer::NegativeY);
+    let sky = read_face(&display, &environment, 0, CubeLayer::PositiveY);
+
+    assert_eq!(floor[8][8], (0, 255, 0, 255));
+    assert_eq!(sky[8][8], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn brdf_lut() {
+    let display = support::build_display();
+    let baker = IblBaker::new(&display).unwrap();
+
+    let lut = baker.brdf_lut(32).unwrap();
+    assert_eq!(lut.dimensions(), (32, 32));
+
+    // a smooth surface seen from the front reflects the specular color without bias
+    let data: Vec<Vec<(f32, f32, f32, f32)>> = lut.read();
+    let (scale, bias, _, _) = data[0][31];
+    assert!(scale > 0.9 && scale < 1.05, "{}", scale);
+    assert!(bias < 0.05, "{}", bias);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "image")]
+
+extern crate glium;
+extern crate image;
+
+use glium::texture::{ImageOptions, MipmapsOption, SrgbTexture2d};
+use glium::Texture2d;
+use image::{DynamicImage, ImageBuffer, Rgba};
+
+mod support;
+
+fn build_image() -> DynamicImage {
+    // red on the top row, blue on the bottom row
+    DynamicImage::ImageRgba8(ImageBuffer::from_fn(2, 2, |_, y| {
+        if y == 0 { Rgba([255, 0, 0, 255]) } else { Rgba([0, 0, 255, 255]) }
+    }))
+}
+
+#[test]
+fn texture_from_image() {
+    let display = support::build_display();
+    let image = build_image();
+
+    let texture = Texture2d::from_image(&display, &image).unwrap();
+    assert_eq!(texture.dimensions(), (2, 2));
+    assert!(texture.get_mipmap_levels() > 1);
+
+    // the first row of `read` is the bottom of the texture
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+    assert_eq!(data[1][0], (255, 0, 0, 255));
+
+    assert_eq!(texture.to_image().raw_pixels(), image.raw_pixels());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_from_image_without_flip() {
+    let display = support::build_display();
+    let options = ImageOptions {
+        flip_vertically: false,
+        mipmaps: MipmapsOption::NoMipmap,
+    };
+
+    let texture = Texture2d::from_image_with_options(&display, &build_image(), options).unwrap();
+    assert_eq!(texture.get_mipmap_levels(), 1);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn srgb_texture_from_grayscale_image() {
+    let display = support::build_display();
+    let image = DynamicImage::ImageLuma8(ImageBuffer::from_pixel(4, 4, image::Luma([128])));
+
+    let texture = SrgbTexture2d::from_image(&display, &image).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (128, 128, 128, 255));
+
+    display.assert_no_error(None);
+}
@@ -189,6 +189,37 @@ fn get_indices_type_u32() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn u8_indices_promotion() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::index::IndexBufferAny::from_u8(&display, PrimitiveType::TriangleStrip,
+                                                        &[0, 1, 2, 3]).unwrap();
+
+    if glium::index::IndexType::U8.is_efficient(&display) {
+        assert_eq!(indices.get_indices_type(), glium::index::IndexType::U8);
+    } else {
+        assert_eq!(indices.get_indices_type(), glium::index::IndexType::U16);
+    }
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn triangles_list_noindices() {
     let display = support::build_display();
@@ -331,6 +362,48 @@ fn indexbuffer_slice_draw() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn indexbuffer_slice_vertices_range_draw() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    // two meshes packed in the same buffers
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [1.0, -1.0] },
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 3, 4, 5]).unwrap();
+
+    let second_mesh = indices.slice(3 .. 6).unwrap().vertices_range(3, 5);
+    assert_eq!(second_mesh.get_vertices_range(), Some((3, 5)));
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &second_mesh, &program,
+                &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn indexbuffer_slice_vertices_range_inverted() {
+    let display = support::build_display();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2]).unwrap();
+
+    indices.slice(0 .. 3).unwrap().vertices_range(2, 0);
+}
+
 #[test]
 fn multidraw_array() {
     let display = support::build_display();
@@ -414,3 +487,135 @@ fn multidraw_elements() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn patches_without_tessellation() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = index::NoIndices(PrimitiveType::Patches { vertices_per_patch: 3 });
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                                    &Default::default())
+    {
+        Err(glium::DrawError::PatchesWithoutTessellation) => (),
+        Err(glium::DrawError::PrimitiveTypeNotSupported) => (),
+        Err(glium::DrawError::TessellationNotSupported) => (),
+        e => panic!("{:?}", e)
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn geometry_shader_input_mismatch() {
+    let display = support::build_display();
+
+    let program = glium::Program::new(&display,
+        glium::program::ProgramCreationInput::SourceCode {
+            vertex_shader: "
+                #version 150
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            geometry_shader: Some("
+                #version 150
+
+                layout(triangles) in;
+                layout(triangle_strip, max_vertices = 3) out;
+
+                void main() {
+                    for (int i = 0; i < 3; i++) {
+                        gl_Position = gl_in[i].gl_Position;
+                        EmitVertex();
+                    }
+                    EndPrimitive();
+                }
+            "),
+            fragment_shader: "
+                #version 150
+
+                out vec4 color;
+
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            transform_feedback_varyings: None,
+            outputs_srgb: false,
+            uses_point_size: false,
+        });
+
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    assert_eq!(program.get_geometry_input_primitives(),
+               Some(glium::program::InputPrimitives::Triangles));
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] },
+    ]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    match texture.as_surface().draw(&vb, &index::NoIndices(PrimitiveType::Points), &program,
+                                    &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::GeometryShaderInputMismatch) => (),
+        e => panic!("{:?}", e)
+    }
+
+    texture.as_surface().draw(&vb, &index::NoIndices(PrimitiveType::TriangleStrip), &program,
+                              &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn u32_indices_narrowing() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::index::IndexBufferAny::from_u32(&display, PrimitiveType::TriangleStrip,
+                                                         &[0, 1, 2, 3]).unwrap();
+    assert_eq!(indices.get_indices_type(), glium::index::IndexType::U16);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    match glium::index::IndexBufferAny::from_u32(&display, PrimitiveType::Points, &[0, 70000]) {
+        Ok(indices) => assert_eq!(indices.get_indices_type(), glium::index::IndexType::U32),
+        Err(glium::index::BufferCreationError::IndexTypeNotSupported) => (),
+        Err(e) => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
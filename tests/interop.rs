@@ -0,0 +1,50 @@
+#![cfg(feature = "interop")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::GlObject;
+use glium::interop;
+
+mod support;
+
+#[test]
+fn buffer_handle() {
+    let display = support::build_display();
+
+    let buffer = glium::VertexBuffer::new(&display, &[1u8, 2, 3, 4]).unwrap();
+
+    let handle = interop::buffer_handle(buffer.slice(1 .. 3).unwrap().as_slice_any());
+    assert_eq!(handle.id, buffer.get_id());
+    assert_eq!(handle.offset, 1);
+    assert_eq!(handle.size, 2);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_handle() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    let handle = interop::texture_handle(&texture);
+    assert_eq!(handle.id, texture.get_id());
+    assert_eq!(handle.target, glium::gl::TEXTURE_2D);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn release_with_fence() {
+    let display = support::build_display();
+
+    let fence = match interop::release_with_fence(&display) {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    assert!(!interop::get_raw_sync(&fence).is_null());
+    fence.wait();
+
+    display.assert_no_error(None);
+}
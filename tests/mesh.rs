@@ -0,0 +1,142 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::index::PrimitiveType;
+use glium::mesh::Mesh;
+
+mod support;
+
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(Vertex, position);
+
+fn build_program(display: &glium::Display) -> glium::Program {
+    program!(display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        }
+    ).unwrap()
+}
+
+fn build_vertex_buffer(display: &glium::Display) -> glium::VertexBuffer<Vertex> {
+    glium::VertexBuffer::new(display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap()
+}
+
+#[test]
+fn draw_mesh() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+    let mesh = Mesh::new(build_vertex_buffer(&display), indices);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw_mesh(&mesh, &program, &glium::uniforms::EmptyUniforms,
+                                   &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_submesh() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 3, 2, 0, 1, 3]).unwrap();
+    let mesh = Mesh::new(build_vertex_buffer(&display), indices)
+        .with_submesh(0 .. 3)
+        .with_submesh(3 .. 6);
+
+    assert_eq!(mesh.get_submeshes().len(), 2);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw_submesh(&mesh, 1, &program, &glium::uniforms::EmptyUniforms,
+                                      &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_submesh_without_indices() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let mesh = Mesh::without_indices(build_vertex_buffer(&display), PrimitiveType::TrianglesList)
+        .with_submesh(1 .. 4);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw_submesh(&mesh, 0, &program, &glium::uniforms::EmptyUniforms,
+                                      &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data.last().unwrap()[0], (0, 0, 0, 0));
+    assert_eq!(data[0].last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic(expected = "The range of the submesh is out of bounds")]
+fn submesh_out_of_range() {
+    let display = support::build_display();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2]).unwrap();
+    Mesh::new(build_vertex_buffer(&display), indices).with_submesh(0 .. 6);
+}
@@ -65,6 +65,39 @@ fn samples_passed() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn occlusion_query_try_get() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::OcclusionQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    assert_eq!(query.try_get(), None);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            samples_passed_query: Some((&query).into()),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    display.finish();
+
+    assert_eq!(query.try_get(), Some(1024 * 1024));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn any_samples_passed() {
     let display = support::build_display();
@@ -159,6 +192,13 @@ fn time_elapsed() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn is_gpu_disjoint() {
+    let display = support::build_display();
+    display.is_gpu_disjoint();
+    display.assert_no_error(None);
+}
+
 #[test]
 #[ignore]       // not sure about the interaction between pritmives_generated and no geometry shader
 fn primitives_generated() {
@@ -309,3 +349,33 @@ fn query_to_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn pipeline_statistics_fragment_invocations() {
+    use glium::draw_parameters::{PipelineStatistic, PipelineStatisticsQuery};
+
+    let display = support::build_display();
+
+    let query = match PipelineStatisticsQuery::new(&display,
+                                                   PipelineStatistic::FragmentShaderInvocations)
+    {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let _scope = query.begin();
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                  &Default::default()).unwrap();
+    }
+
+    // implementations are allowed to invoke the fragment shader more than once per sample
+    assert!(query.get() >= 1024 * 1024);
+
+    display.assert_no_error(None);
+}
@@ -79,3 +79,87 @@ fn magnify_nearest_filtering() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn sampler_changed_on_same_texture() {
+    // ignoring test on travis
+    // TODO: find out why they are failing
+    if ::std::env::var("TRAVIS").is_ok() {
+        return;
+    }
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.51, 0.0));
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform lowp sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.51, 0.0));
+                }
+            ",
+        }).unwrap();
+
+    let texture_data = vec![vec![(0u8, 0, 0), (255, 255, 255)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+
+    // the texture stays bound to the same unit, so only the sampler object has to change
+    let nearest = uniform! {
+        texture: texture.sampled().magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest)
+    };
+
+    match output.as_surface().draw(&vb, &ib, &program, &nearest, &Default::default()) {
+        Ok(_) => (),
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 255, 255, 255));
+
+    let linear = uniform! {
+        texture: texture.sampled().magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear)
+    };
+
+    output.as_surface().draw(&vb, &ib, &program, &linear, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert!(data[0][0].0 > 0 && data[0][0].0 < 255);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,125 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::framebuffer::EmptyFrameBuffer;
+use glium::texture::{ImageMismatch, RawImage2d, ReadError};
+
+mod support;
+
+#[test]
+fn snapshot_texture() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let snapshot = texture.as_surface().snapshot(&display).unwrap();
+    assert_eq!((snapshot.width, snapshot.height), (1024, 1024));
+
+    let expected = RawImage2d::from_raw_rgba(vec![255u8, 0, 0, 255].into_iter().cycle()
+                                             .take(1024 * 1024 * 4).collect(), (1024, 1024));
+    snapshot.compare(&expected, 0).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn snapshot_empty_framebuffer() {
+    let display = support::build_display();
+    if !EmptyFrameBuffer::is_supported(&display) {
+        return;
+    }
+
+    let framebuffer = EmptyFrameBuffer::new(&display, 256, 256, None, None, true).unwrap();
+    match framebuffer.snapshot(&display) {
+        Err(ReadError::NoColorBuffer) => (),
+        _ => panic!()
+    }
+
+    match framebuffer.read_to_pixel_buffer_async(&display) {
+        Err(ReadError::NoColorBuffer) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_to_pixel_buffer_async() {
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let pending = texture.as_surface().read_to_pixel_buffer_async(&display).unwrap();
+    display.finish();
+    assert!(pending.is_ready());
+
+    let image: RawImage2d<u8> = pending.read().unwrap();
+    assert_eq!((image.width, image.height), (1024, 1024));
+    assert_eq!(&image.data[0 .. 4], &[0, 255, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compare_tolerance() {
+    let a = RawImage2d::from_raw_rgba(vec![10u8, 20, 30, 255, 0, 0, 0, 255], (2, 1));
+    let b = RawImage2d::from_raw_rgba(vec![12u8, 20, 28, 255, 0, 9, 0, 255], (2, 1));
+
+    assert!(a.compare(&a, 0).is_ok());
+    assert!(a.compare(&b, 9).is_ok());
+
+    match a.compare(&b, 2) {
+        Err(ImageMismatch::PixelsMismatch { mismatched_pixels, max_difference, first_mismatch }) => {
+            assert_eq!(mismatched_pixels, 1);
+            assert_eq!(max_difference, 9);
+            assert_eq!(first_mismatch, (1, 0));
+        },
+        _ => panic!()
+    }
+}
+
+#[test]
+fn compare_dimensions_mismatch() {
+    let a = RawImage2d::from_raw_rgba(vec![0u8; 8], (2, 1));
+    let b = RawImage2d::from_raw_rgba(vec![0u8; 8], (1, 2));
+
+    assert_eq!(a.compare(&b, 255),
+               Err(ImageMismatch::DimensionsMismatch { left: (2, 1), right: (1, 2) }));
+}
+
+#[test]
+fn video_capture() {
+    use glium::video_capture::{VideoCapture, CaptureFormat};
+
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    let mut capture = VideoCapture::new(&display, CaptureFormat::Rgba, 2);
+
+    for _ in 0 .. 3 {
+        texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+        capture.capture(&texture.as_surface());
+    }
+
+    assert!(capture.get_pending_frames() <= 2);
+
+    let mut frames = Vec::new();
+    while let Some(frame) = capture.poll() {
+        frames.push(frame);
+    }
+    frames.extend(capture.flush());
+
+    assert_eq!(frames.len(), 3);
+    for frame in &frames {
+        assert_eq!((frame.width, frame.height), (1024, 1024));
+        assert_eq!(frame.data.len(), 1024 * 1024 * 4);
+        assert_eq!(&frame.data[0 .. 4], &[255, 0, 0, 255]);
+    }
+
+    display.assert_no_error(None);
+}
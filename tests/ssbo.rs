@@ -134,3 +134,274 @@ fn custom_bind_point() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn std430_block() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 430
+
+            out vec4 f_color;
+
+            layout(std430) buffer MyBlock {
+                vec3 color;
+                float weights[8];
+            };
+
+            void main() {
+                color = vec3(1.0, 1.0, 0.5);
+                weights[5] = 2.0;
+                f_color = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: [f32; 3],
+        weights: [f32; 8],
+    }
+
+    implement_std430_block!(Data, color: [f32; 3], weights: [f32; 8]);
+
+    let data = Data { color: [0.0, 0.0, 0.0], weights: [0.0; 8] };
+    let buffer = match glium::uniforms::UniformBuffer::new(&display,
+                                                          glium::uniforms::Std430::new(&data))
+    {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let uniforms = uniform!{
+        MyBlock: &buffer
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data = buffer.read().unwrap().get();
+    assert_eq!(data.color, [1.0, 1.0, 0.5]);
+    assert_eq!(data.weights[5], 2.0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn std430_stride_mismatch() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 430
+
+            out vec4 f_color;
+
+            layout(std140) buffer MyBlock {
+                vec3 color;
+                float weights[8];
+            };
+
+            void main() {
+                color = vec3(1.0, 1.0, weights[5]);
+                f_color = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    assert_eq!(program.get_shader_storage_blocks()["MyBlock"].array_strides.get("weights"),
+               Some(&16));
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: [f32; 3],
+        weights: [f32; 8],
+    }
+
+    implement_std430_block!(Data, color: [f32; 3], weights: [f32; 8]);
+
+    let data = Data { color: [0.0, 0.0, 0.0], weights: [0.0; 8] };
+    let buffer = match glium::uniforms::UniformBuffer::new(&display,
+                                                          glium::uniforms::Std430::new(&data))
+    {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let uniforms = uniform!{
+        MyBlock: &buffer
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Err(glium::DrawError::UniformBlockLayoutMismatch {
+            err: glium::uniforms::LayoutMismatchError::MemberMismatch { ref member, .. }, ..
+        }) => {
+            assert_eq!(member, "weights");
+        },
+        res => panic!("{:?}", res),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compute_read_only_storage() {
+    let display = support::build_display();
+
+    let program = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        layout(std140) readonly buffer Input {
+            uvec4 input_values;
+        };
+
+        layout(std140) buffer Output {
+            uvec4 output_values;
+        };
+
+        void main() {
+            output_values = input_values * 2u;
+        }
+    ");
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        values: [u32; 4],
+    }
+
+    implement_uniform_block!(Data, values);
+
+    let input = match glium::uniforms::UniformBuffer::new(&display, Data { values: [1, 2, 3, 4] }) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let output = glium::uniforms::UniformBuffer::new(&display, Data { values: [0; 4] }).unwrap();
+
+    program.execute(uniform! {
+        Input: glium::uniforms::ShaderStorage::new(&input,
+                                                   glium::uniforms::ShaderAccess::ReadOnly),
+        Output: &output,
+    }, 1, 1, 1);
+
+    assert_eq!(output.read().unwrap().values, [2, 4, 6, 8]);
+    assert_eq!(input.read().unwrap().values, [1, 2, 3, 4]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compute_atomic_counter() {
+    let display = support::build_display();
+
+    let program = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 8, local_size_y = 1, local_size_z = 1) in;
+
+        layout(binding = 0, offset = 0) uniform atomic_uint counter;
+
+        void main() {
+            atomicCounterIncrement(counter);
+        }
+    ");
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let counter = match glium::uniforms::UniformBuffer::new(&display, 0u32) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    program.execute(uniform! { counter: &counter }, 4, 1, 1);
+    assert_eq!(counter.read().unwrap(), 32);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compute_image_write() {
+    let display = support::build_display();
+
+    let program = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        layout(rgba8) writeonly uniform image2D output_image;
+
+        void main() {
+            imageStore(output_image, ivec2(gl_GlobalInvocationID.xy), vec4(1.0, 0.0, 0.0, 1.0));
+        }
+    ");
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                            glium::texture::MipmapsOption::NoMipmap, 4, 4).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let image = glium::uniforms::ImageUnit::new(&texture, glium::uniforms::ImageUnitFormat::RGBA8)
+                    .access(glium::uniforms::ShaderAccess::WriteOnly);
+    program.execute(uniform! { output_image: image }, 4, 4, 1);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
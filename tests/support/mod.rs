@@ -20,7 +20,12 @@ pub fn build_display() -> glium::Display {
     let context = glutin::ContextBuilder::new()
         .with_gl_debug_flag(true)
         .with_gl(version);
-    glium::Display::new(window, context, &events_loop).unwrap()
+    let gl_window = glutin::GlWindow::new(window, context, &events_loop).unwrap();
+
+    // expects "GL_ARB_buffer_storage,GL_ARB_direct_state_access" for example
+    let disabled = env::var("GLIUM_DISABLED_EXTENSIONS").unwrap_or(String::new());
+    let disabled = disabled.split(',').collect::<Vec<_>>();
+    glium::Display::with_disabled_extensions(gl_window, &disabled).unwrap()
 }
 
 /// Builds a headless display for tests.
@@ -49,6 +54,27 @@ pub fn rebuild_display(display: &glium::Display) {
     display.rebuild(window, context, &events_loop).unwrap();
 }
 
+/// Builds a context that shares its objects with an existing display.
+pub fn build_shared_context(display: &glium::Display) -> glium::backend::glutin::SharedContext {
+    let version = parse_version();
+    let events_loop = glutin::EventsLoop::new();
+    let context = glutin::ContextBuilder::new()
+        .with_gl_debug_flag(true)
+        .with_gl(version);
+    display.new_shared_context(context, &events_loop).unwrap()
+}
+
+/// Opens another window that is drawn with the context of an existing display.
+pub fn build_window(display: &glium::Display) -> glium::backend::glutin::DisplayWindow {
+    let version = parse_version();
+    let events_loop = glutin::EventsLoop::new();
+    let window = glutin::WindowBuilder::new().with_visibility(false);
+    let context = glutin::ContextBuilder::new()
+        .with_gl_debug_flag(true)
+        .with_gl(version);
+    display.new_window(window, context, &events_loop).unwrap()
+}
+
 fn parse_version() -> glutin::GlRequest {
     match env::var("GLIUM_GL_VERSION") {
         Ok(version) => {
@@ -0,0 +1,84 @@
+extern crate glium;
+
+use glium::texture::container::{self, ContainerError, ContainerTexture};
+
+mod support;
+
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend_from_slice(&[value as u8, (value >> 8) as u8, (value >> 16) as u8,
+                             (value >> 24) as u8]);
+}
+
+// builds a KTX file with RGBA8 images filled with the color of each level and face
+fn build_ktx(size: u32, faces: u32, levels: u32, colors: &[[u8; 4]]) -> Vec<u8> {
+    let mut data = vec![0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+    for &field in &[0x04030201, 0x1401, 1, 0x1908, 0x8058, 0x1908, size, size, 0, 0, faces,
+                    levels, 0]
+    {
+        push_u32(&mut data, field);
+    }
+
+    for level in 0 .. levels {
+        let dimension = ::std::cmp::max(1, size >> level);
+        push_u32(&mut data, dimension * dimension * 4);
+
+        for face in 0 .. faces {
+            let color = colors[(level * faces + face) as usize];
+            for _ in 0 .. dimension * dimension {
+                data.extend_from_slice(&color);
+            }
+        }
+    }
+
+    data
+}
+
+#[test]
+fn ktx_texture2d_with_mipmaps() {
+    let display = support::build_display();
+    let data = build_ktx(4, 1, 3, &[[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]]);
+
+    let texture = match container::load(&display, &data).unwrap() {
+        ContainerTexture::Texture2d(t) => t,
+        _ => panic!(),
+    };
+
+    assert_eq!(texture.dimensions(), (4, 4));
+    assert_eq!(texture.get_mipmap_levels(), 3);
+
+    let pixels: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(pixels[3][3], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn ktx_cubemap() {
+    let display = support::build_display();
+    let colors: Vec<[u8; 4]> = (0 .. 12).map(|i| [i * 20, 0, 0, 255]).collect();
+    let data = build_ktx(2, 6, 2, &colors);
+
+    match container::load(&display, &data).unwrap() {
+        ContainerTexture::Cubemap(cubemap) => {
+            assert_eq!(cubemap.dimensions(), 2);
+            assert_eq!(cubemap.get_mipmap_levels(), 2);
+        },
+        _ => panic!(),
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn truncated_file() {
+    let display = support::build_display();
+    let mut data = build_ktx(4, 1, 1, &[[255, 0, 0, 255]]);
+    data.truncate(80);
+
+    match container::load(&display, &data) {
+        Err(ContainerError::Truncated) => (),
+        _ => panic!(),
+    };
+
+    display.assert_no_error(None);
+}
@@ -134,3 +134,116 @@ read_texture_test!(read_unsignedtexture2darray, UnsignedTexture2dArray, (u8, u8,
 read_texture_test!(read_unsignedtexture3d, UnsignedTexture3d, (u8, u8, u8, u8),
     vec![vec![vec![(0, 1, 2, 3), (4, 5, 6, 7)], vec![(8, 9, 10, 11), (12, 13, 14, 15)]]]);*/
 // TODO: srgb textures
+
+#[test]
+fn texture_2d_try_read() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8, 4u8), (8u8, 16u8, 32u8, 64u8)],
+    ]).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.try_read().unwrap();
+    assert_eq!(data, vec![vec![(0, 1, 2, 4), (8, 16, 32, 64)]]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_read_with_layout() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8, 4u8), (8u8, 16u8, 32u8, 64u8)],
+        vec![(128u8, 64u8, 32u8, 16u8), (8u8, 4u8, 2u8, 1u8)],
+    ]).unwrap();
+
+    // the texture is read in the middle of a 3x4 image
+    let mut image = vec![(255u8, 255u8, 255u8, 255u8); 12];
+    let layout = glium::texture::PixelLayout {
+        row_length: Some(3),
+        skip_pixels: 1,
+        skip_rows: 1,
+        .. Default::default()
+    };
+
+    texture.main_level().first_layer().into_image(None).unwrap().raw_read_with_layout(
+        &glium::Rect { bottom: 0, left: 0, width: 2, height: 2 }, &mut image, &layout);
+
+    assert_eq!(image[0], (255, 255, 255, 255));
+    assert_eq!(image[3], (255, 255, 255, 255));
+    assert_eq!(image[4], (0, 1, 2, 4));
+    assert_eq!(image[5], (8, 16, 32, 64));
+    assert_eq!(image[7], (128, 64, 32, 16));
+    assert_eq!(image[8], (8, 4, 2, 1));
+    assert_eq!(image[9], (255, 255, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_read_with_format() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(255u8, 0u8, 0u8, 255u8), (0u8, 255u8, 0u8, 255u8)],
+        vec![(0u8, 0u8, 255u8, 255u8), (255u8, 255u8, 255u8, 0u8)],
+    ]).unwrap();
+
+    let order = glium::texture::ChannelOrder::Bgra;
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = match texture.read_with_format(order) {
+        Ok(r) => r,
+        Err(glium::texture::ReadError::OutputFormatNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(read_back[0][0], (0, 0, 255, 255));
+    assert_eq!(read_back[0][1], (0, 255, 0, 255));
+    assert_eq!(read_back[1][0], (255, 0, 0, 255));
+    assert_eq!(read_back[1][1], (255, 255, 255, 0));
+
+    let order = glium::texture::ChannelOrder::Rgba;
+    let read_back: Vec<Vec<(f32, f32, f32, f32)>> = match texture.read_with_format(order) {
+        Ok(r) => r,
+        Err(glium::texture::ReadError::OutputFormatNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(read_back[0][0], (1.0, 0.0, 0.0, 1.0));
+    assert_eq!(read_back[1][1], (1.0, 1.0, 1.0, 0.0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_read_f16() {
+    use glium::vertex::F16;
+
+    let display = support::build_display();
+
+    let (one, half) = (F16::from_f32(1.0), F16::from_f32(0.5));
+    let zero = F16::default();
+
+    let texture = match glium::texture::Texture2d::with_format(&display, vec![
+        vec![(one, zero, zero, one), (zero, half, zero, one)],
+        vec![(zero, zero, one, half), (one, one, one, zero)],
+    ], glium::texture::UncompressedFloatFormat::F16F16F16F16,
+       glium::texture::MipmapsOption::NoMipmap)
+    {
+        Ok(t) => t,
+        Err(glium::texture::TextureCreationError::FormatNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let order = glium::texture::ChannelOrder::Rgba;
+    let read_back: Vec<Vec<(F16, F16, F16, F16)>> = match texture.read_with_format(order) {
+        Ok(r) => r,
+        Err(glium::texture::ReadError::OutputFormatNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(read_back[0][0], (one, zero, zero, one));
+    assert_eq!(read_back[1][1], (one, one, one, zero));
+
+    display.assert_no_error(None);
+}
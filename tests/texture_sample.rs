@@ -130,3 +130,170 @@ fn bindless_texture() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn bindless_texture_uniform() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255)],
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+
+    let texture = match texture.resident() {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 100
+
+            attribute lowp vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+            #extension GL_ARB_bindless_texture : require
+
+            layout(bindless_sampler) uniform sampler2D textures[2];
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = texture(textures[1], vec2(0.0, 0.0));
+            }
+        ",
+        None).unwrap();
+
+    let handles = vec![glium::texture::TextureHandle::new(&texture, &Default::default()); 2];
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ textures: &handles[..] },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sampler_array() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let red = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255)],
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+
+    let blue = glium::texture::Texture2d::new(&display, vec![
+        vec![(0, 0, 255, 255), (0, 0, 255, 255)],
+        vec![(0, 0, 255, 255), (0, 0, 255, 255u8)],
+    ]).unwrap();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D textures[2];
+
+            void main() {
+                gl_FragColor = texture2D(textures[0], vec2(0.5, 0.5)) +
+                               texture2D(textures[1], vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let textures = vec![red.sampled(), blue.sampled()];
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ textures: textures },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn more_textures_than_units_across_draws() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture1;
+            uniform sampler2D texture2;
+
+            void main() {
+                gl_FragColor = texture2D(texture1, vec2(0.5, 0.5)) +
+                               texture2D(texture2, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    // every texture unit ends up containing a texture, so some of them have to be replaced ;
+    // the number of textures is even so that each draw uses a red and a blue texture
+    let units = display.get_capabilities().max_combined_texture_image_units as usize;
+    let count = units + 4 + units % 2;
+    let textures = (0 .. count).map(|i| {
+        let color = if i % 2 == 0 { (255, 0, 0, 255) } else { (0, 0, 255, 255u8) };
+        glium::texture::Texture2d::new(&display, vec![vec![color]]).unwrap()
+    }).collect::<Vec<_>>();
+
+    let output = support::build_renderable_texture(&display);
+
+    for i in 0 .. count {
+        let uniforms = uniform! {
+            texture1: &textures[i],
+            texture2: &textures[(i + 1) % count],
+        };
+
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        assert_eq!(data[0][0], (255, 0, 255, 255));
+    }
+
+    display.assert_no_error(None);
+}
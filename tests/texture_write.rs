@@ -24,3 +24,167 @@ fn texture_2d_write() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_2d_write_with_layout() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 0u8, 0u8, 0u8), (0u8, 0u8, 0u8, 0u8)],
+        vec![(0u8, 0u8, 0u8, 0u8), (0u8, 0u8, 0u8, 0u8)],
+    ]).unwrap();
+
+    // a 4x3 image from which we upload the 2x2 rectangle starting at (1, 1)
+    let mut image = vec![(0u8, 0u8, 0u8, 0u8); 12];
+    image[5] = (255, 0, 0, 255);
+    image[6] = (0, 255, 0, 255);
+    image[9] = (0, 0, 255, 255);
+    image[10] = (255, 255, 255, 255);
+
+    let layout = glium::texture::PixelLayout {
+        row_length: Some(4),
+        skip_pixels: 1,
+        skip_rows: 1,
+        .. Default::default()
+    };
+
+    texture.main_level().raw_upload_with_layout(
+        &glium::Rect { bottom: 0, left: 0, width: 2, height: 2 }, &image, &layout);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back[0][1], (0, 255, 0, 255));
+    assert_eq!(read_back[1][0], (0, 0, 255, 255));
+    assert_eq!(read_back[1][1], (255, 255, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_3d_write_with_layout_leaves_state() {
+    let display = support::build_display();
+
+    let texture_3d = match glium::texture::Texture3d::empty(&display, 2, 2, 2) {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    let image = vec![(0u8, 0u8, 0u8, 0u8); 12];
+    let layout = glium::texture::PixelLayout {
+        row_length: Some(4),
+        skip_pixels: 1,
+        skip_rows: 1,
+        .. Default::default()
+    };
+
+    // the kind of texture must be rejected before any unpack parameter is changed
+    let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+        texture_3d.main_level().raw_upload_with_layout(
+            &glium::Rect { bottom: 0, left: 0, width: 2, height: 2 }, &image, &layout);
+    }));
+    assert!(result.is_err());
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8, 255u8), (4u8, 8u8, 16u8, 255u8)],
+        vec![(32u8, 64u8, 128u8, 255u8), (128u8, 64u8, 2u8, 255u8)],
+    ]).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (0, 1, 2, 255));
+    assert_eq!(read_back[1][1], (128, 64, 2, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn video_stream_texture() {
+    use glium::video_stream::VideoStreamTexture;
+
+    let display = support::build_display();
+
+    let mut stream: VideoStreamTexture<(u8, u8, u8)> =
+        VideoStreamTexture::new(&display, 3, 2, 2).unwrap();
+
+    for &value in &[10u8, 20, 30] {
+        stream.push_frame(&vec![value; 3 * 2 * 3]);
+    }
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = stream.get_texture().read();
+    assert_eq!(read_back.len(), 2);
+    assert_eq!(read_back[0][0], (30, 30, 30, 255));
+    assert_eq!(read_back[1][2], (30, 30, 30, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_clear() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.clear(0, [1.0f32, 0.0, 1.0, 1.0]).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (255, 0, 255, 255));
+    assert_eq!(read_back[1023][1023], (255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn texture_2d_clear_wrong_kind() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    texture.clear(0, [1i32, 0, 1, 1]).unwrap();
+}
+
+#[test]
+fn texture_3d_clear_mipmaps() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture3d::empty_with_mipmaps(&display,
+                                            glium::texture::MipmapsOption::EmptyMipmaps, 16, 16, 16)
+    {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    // the depth of each level is smaller than the depth of the first level
+    for level in 0 .. texture.get_mipmap_levels() {
+        texture.clear(level, [1.0f32, 0.0, 1.0, 1.0]).unwrap();
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn glyph_atlas_insert() {
+    let display = support::build_display();
+
+    let mut atlas = glium::texture::atlas::GlyphAtlas::new(&display,
+                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                    16, 16, 1).unwrap();
+
+    let red = vec![vec![(255u8, 0u8, 0u8, 255u8); 4]; 2];
+    let entry = atlas.insert('a', red).unwrap();
+    assert_eq!(entry.rect, glium::Rect { left: 1, bottom: 1, width: 4, height: 2 });
+    assert_eq!(atlas.get(&'a'), Some(&entry));
+
+    let space = atlas.get_or_insert_with(' ', || Vec::<Vec<(u8, u8, u8, u8)>>::new()).unwrap();
+    assert_eq!(space.rect.width, 0);
+    assert_eq!(atlas.len(), 2);
+
+    let too_large = vec![vec![(0u8, 0u8, 0u8, 0u8); 16]; 16];
+    assert!(atlas.insert('b', too_large).is_err());
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = atlas.texture().read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+    assert_eq!(data[2][4], (255, 0, 0, 255));
+    assert_eq!(data[3][1], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
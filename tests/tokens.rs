@@ -0,0 +1,83 @@
+#![cfg(feature = "tokens")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::tokens::{ComputeSupport, InstancingSupport, SyncSupport};
+
+mod support;
+
+#[test]
+fn sync_token() {
+    let display = support::build_display();
+
+    if let Some(sync) = SyncSupport::new(&display) {
+        sync.fence().wait();
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compute_token() {
+    let display = support::build_display();
+
+    let compute = match ComputeSupport::new(&display) {
+        Some(c) => c,
+        None => return,
+    };
+
+    compute.compute_shader("
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        void main() {
+        }
+    ").unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn instancing_token() {
+    let display = support::build_display();
+
+    let instancing = match InstancingSupport::new(&display) {
+        Some(i) => i,
+        None => return,
+    };
+
+    #[derive(Copy, Clone)]
+    struct Instance {
+        offset: [f32; 2],
+    }
+
+    implement_vertex!(Instance, offset);
+
+    let buffer = glium::VertexBuffer::new(&display, &[Instance { offset: [0.0, 0.0] }]).unwrap();
+    let _ = instancing.per_instance(&buffer);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn token_of_other_context() {
+    let display1 = support::build_display();
+    let display2 = support::build_display();
+
+    let instancing = match InstancingSupport::new(&display1) {
+        Some(i) => i,
+        None => panic!(),
+    };
+
+    #[derive(Copy, Clone)]
+    struct Instance {
+        offset: [f32; 2],
+    }
+
+    implement_vertex!(Instance, offset);
+
+    let buffer = glium::VertexBuffer::new(&display2, &[Instance { offset: [0.0, 0.0] }]).unwrap();
+    instancing.per_instance(&buffer);
+}
@@ -158,6 +158,78 @@ fn block() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn std140_block() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(std140) uniform MyBlock {
+                vec3 color;
+                float intensity;
+                vec3 tint;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color * tint * intensity, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: [f32; 3],
+        intensity: f32,
+        tint: [f32; 3],
+    }
+
+    implement_std140_block!(Data, color: [f32; 3], intensity: f32, tint: [f32; 3]);
+
+    let data = Data { color: [1.0, 1.0, 0.0], intensity: 1.0, tint: [1.0, 0.0, 1.0] };
+    let buffer = match glium::uniforms::UniformBuffer::new(&display,
+                                                          glium::uniforms::Std140::new(&data))
+    {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let uniforms = uniform!{
+        MyBlock: &buffer
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn block_wrong_type() {
     let display = support::build_display();
@@ -338,3 +410,167 @@ fn empty_uniform_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn fixed_block_binding() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec3 color;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: (f32, f32, f32),
+    }
+
+    implement_uniform_block!(Data, color);
+
+    let buffer = match glium::uniforms::UniformBuffer::new(&display, Data { color: (1.0f32, 1.0f32, 0.0f32) }) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    match program.set_uniform_block_binding("Missing", 1) {
+        Err(glium::program::BindingError::NotFound { .. }) => (),
+        _ => panic!()
+    };
+
+    program.set_uniform_block_binding("MyBlock", 1).unwrap();
+
+    let bindings = program.get_bindings();
+    assert_eq!(bindings.uniform_blocks.len(), 1);
+    assert_eq!(bindings.uniform_blocks[0].0, "MyBlock");
+    assert_eq!(bindings.uniform_blocks[0].1.point, Some(1));
+    assert!(bindings.uniform_blocks[0].1.fixed);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ MyBlock: &buffer },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn push_constants() {
+    use glium::uniforms::{PushConstants, PushConstantsPath};
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    #[derive(Copy, Clone)]
+    struct Constants {
+        color: [f32; 4],
+    }
+
+    implement_uniform_struct!(Constants, color);
+    implement_uniform_block!(Constants, color);
+
+    for &path in &[PushConstantsPath::Uniforms, PushConstantsPath::UniformBuffer] {
+        let mut constants = match PushConstants::with_path(&display,
+                                                           Constants { color: [1.0; 4] }, path)
+        {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+
+        let declaration = match constants.path() {
+            PushConstantsPath::Uniforms => "
+                struct Constants { vec4 color; };
+                uniform Constants constants;
+                #define COLOR constants.color
+            ",
+            PushConstantsPath::UniformBuffer => "
+                uniform constants { vec4 color; };
+                #define COLOR color
+            ",
+        };
+
+        let program = glium::Program::from_source(&display,
+            "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            &format!("
+                #version 140
+
+                {}
+
+                out vec4 f_color;
+
+                void main() {{
+                    f_color = COLOR;
+                }}
+            ", declaration),
+            None);
+
+        // ignoring test in case of compilation error (version may not be supported)
+        let program = match program {
+            Ok(p) => p,
+            Err(_) => continue
+        };
+
+        let texture = support::build_renderable_texture(&display);
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+        constants.set(Constants { color: [1.0, 0.0, 0.0, 1.0] });
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{ constants: &constants },
+                                  &Default::default()).unwrap();
+
+        // the previous draw command must not be affected
+        constants.set(Constants { color: [0.0, 1.0, 0.0, 1.0] });
+        assert_eq!(constants.get().color, [0.0, 1.0, 0.0, 1.0]);
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], (255, 0, 0, 255));
+
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{ constants: &constants },
+                                  &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], (0, 255, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
@@ -172,6 +172,201 @@ fn uniform_wrong_type() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn uniform_array() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform float weights[3];
+
+            void main() {
+                gl_FragColor = vec4(weights[0], weights[1], weights[2], 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let weights = [1.0, 0.0, 1.0f32];
+    let uniforms = uniform! { weights: &weights[..] };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_struct_array() {
+    #[derive(Copy, Clone)]
+    struct Light {
+        color: [f32; 3],
+        intensity: f32,
+    }
+
+    implement_uniform_struct!(Light, color, intensity);
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            struct Light {
+                vec3 color;
+                float intensity;
+            };
+
+            uniform Light lights[2];
+
+            void main() {
+                vec3 color = lights[0].color * lights[0].intensity +
+                             lights[1].color * lights[1].intensity;
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let lights = [
+        Light { color: [1.0, 0.0, 0.0], intensity: 1.0 },
+        Light { color: [0.0, 0.0, 1.0], intensity: 1.0 },
+    ];
+    let uniforms = uniform! { lights: &lights[..] };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dynamic_uniforms() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color1;
+            uniform vec4 color2;
+            uniform sampler2D tex;
+
+            void main() {
+                gl_FragColor = color1 + color2 + texture2D(tex, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let texture_data = vec![vec![(0u8, 0u8, 0u8, 0u8)]];
+    let black = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let mut uniforms = glium::uniforms::DynamicUniforms::new();
+    let colors = [("color1", [0.7, 0.0, 0.0, 0.1f32]), ("color2", [0.3, 0.0, 0.0, 0.4])];
+    for &(name, value) in colors.iter() {
+        uniforms.add(name.to_owned(), value);
+    }
+    uniforms.add("tex", glium::uniforms::Sampler::new(&black));
+    uniforms.add("unused", 1.0f32);
+    assert!(uniforms.remove("unused"));
+    assert_eq!(uniforms.len(), 3);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 128));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 128));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn chained_uniforms() {
+    use glium::uniforms::Uniforms;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color1;
+            uniform vec4 color2;
+
+            void main() {
+                gl_FragColor = color1 + color2;
+            }
+        ",
+        None).unwrap();
+
+    let globals = uniform! { color1: [0.7, 0.0, 0.0, 0.1f32], color2: [1.0, 1.0, 1.0, 1.0f32] };
+
+    // `color2` of the second object overrides the one of `globals`
+    let uniforms = (&globals).chain(uniform! { color2: [0.3, 0.0, 0.0, 0.4f32] });
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 128));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 128));
+
+    display.assert_no_error(None);
+}
+
 macro_rules! uniform_test(
     ($name:ident, $glsl_ty:expr, $value:expr) => (
         #[test]
@@ -292,6 +487,28 @@ uniform_test!(uniform_type_f32arr_floatmat4, "mat4", [[ 1.0f32,     2.4, -100000
                                                       [-2.0f32, -7.8867,     6.6666, -0.0],
                                                       [ 1.1f32,     7.7,       -6.1,  0.0],
                                                       [12.0f32, 12345.0,    0.11111,  0.0]]);
+uniform_test!(uniform_type_f32arr_floatmat2x3, "mat2x3", [[1.0f32, 2.4, 0.5], [-2.0, 7.8, 6.6]]);
+uniform_test!(uniform_type_f32arr_floatmat2x4, "mat2x4", [[1.0f32, 2.4, 0.5, 0.0],
+                                                          [-2.0, 7.8, 6.6, 1.0]]);
+uniform_test!(uniform_type_f32arr_floatmat3x2, "mat3x2", [[1.0f32, 2.4], [0.5, -2.0], [7.8, 6.6]]);
+uniform_test!(uniform_type_f32arr_floatmat3x4, "mat3x4", [[1.0f32, 2.4, 0.5, 0.0],
+                                                          [-2.0, 7.8, 6.6, 1.0],
+                                                          [1.1, 7.7, -6.1, 0.0]]);
+uniform_test!(uniform_type_f32arr_floatmat4x2, "mat4x2", [[1.0f32, 2.4], [0.5, -2.0],
+                                                          [7.8, 6.6], [1.1, 7.7]]);
+uniform_test!(uniform_type_f32arr_floatmat4x3, "mat4x3", [[1.0f32, 2.4, 0.5], [-2.0, 7.8, 6.6],
+                                                          [1.1, 7.7, -6.1], [12.0, 0.1, 0.0]]);
+uniform_test!(uniform_type_f16_float, "float", glium::vertex::F16::from_f32(12.5));
+uniform_test!(uniform_type_f16arr_floatvec2, "vec2", glium::vertex::F16::array([1.0, 2.5]));
+uniform_test!(uniform_type_f16arr_floatvec4, "vec4",
+              glium::vertex::F16::array([1.0, 2.5, 0.25, -3.0]));
+uniform_test!(uniform_type_f32transposed_floatmat3x2, "mat3x2",
+              glium::uniforms::Transposed([[1.0f32, 2.4, 0.5], [-2.0, 7.8, 6.6]]));
+uniform_test!(uniform_type_f32transposed_floatmat4, "mat4",
+              glium::uniforms::Transposed([[1.0f32, 0.0, 0.0, 5.0],
+                                           [0.0, 1.0, 0.0, 2.0],
+                                           [0.0, 0.0, 1.0, 0.0],
+                                           [0.0, 0.0, 0.0, 1.0]]));
 
 // Doubles
 uniform_test!(uniform_type_f64_double, "double", 12.567f64);
@@ -309,6 +526,11 @@ uniform_test!(uniform_type_f64arr_doublemat4, "dmat4", [[ 1.0f64,     2.4, -1000
                                                         [-2.0f64, -7.8867,     6.6666, -0.0],
                                                         [ 1.1f64,     7.7,       -6.1,  0.0],
                                                         [12.0f64, 12345.0,    0.11111,  0.0]]);
+uniform_test!(uniform_type_f64arr_doublemat2x3, "dmat2x3", [[1.0f64, 2.4, 0.5], [-2.0, 7.8, 6.6]]);
+uniform_test!(uniform_type_f64arr_doublemat4x2, "dmat4x2", [[1.0f64, 2.4], [0.5, -2.0],
+                                                            [7.8, 6.6], [1.1, 7.7]]);
+uniform_test!(uniform_type_f64transposed_doublemat2x3, "dmat2x3",
+              glium::uniforms::Transposed([[1.0f64, 2.4], [0.5, -2.0], [7.8, 6.6]]));
 
 // Integer
 uniform_test!(uniform_type_i8_int, "int", 5i8);
@@ -358,3 +580,161 @@ uniform_test!(uniform_type_boolarr_boolvec3, "bvec3", [true, true, true]);
 uniform_test!(uniform_type_booltup_boolvec3, "bvec3", (false, false, false));
 uniform_test!(uniform_type_boolarr_boolvec4, "bvec4", [true, false, false, true]);
 uniform_test!(uniform_type_booltup_boolvec4, "bvec4", (false, true, true, false));
+
+#[test]
+fn missing_uniforms_policy() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the default policy ignores the missing uniforms
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    program.set_missing_uniforms(glium::uniforms::MissingUniforms::Error);
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &Default::default())
+    {
+        Err(glium::DrawError::UniformMissing { ref name }) if name == "color" => (),
+        _ => panic!()
+    };
+
+    // the draw parameters override the policy of the program
+    let params = glium::DrawParameters {
+        missing_uniforms: Some(glium::uniforms::MissingUniforms::Ignore),
+        .. Default::default()
+    };
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &params).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn default_uniform() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    program.set_missing_uniforms(glium::uniforms::MissingUniforms::Error);
+    program.set_default_uniform("color", [1.0f32, 0.0, 1.0, 1.0]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+
+    // the value given when drawing has the priority
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ color: [0.0f32, 1.0, 0.0, 1.0] },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    assert!(program.remove_default_uniform("color"));
+    assert!(texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                      &Default::default()).is_err());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_values_after_raw_gl() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    let uniforms = uniform!{ color: [1.0f32, 0.0, 0.0, 1.0] };
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    // the value of the uniform is modified behind the back of glium
+    let location = program.get_uniform("color").unwrap().location;
+    let id = match program.get_id() {
+        glium::Handle::Id(id) => id,
+        glium::Handle::Handle(_) => return,
+    };
+
+    unsafe {
+        display.exec_with_raw_gl(|gl| {
+            gl.UseProgram(id);
+            gl.Uniform4f(location, 0.0, 0.0, 1.0, 1.0);
+        });
+    }
+
+    // glium must not assume that the uniform still has the value of the previous draw
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
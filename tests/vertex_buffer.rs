@@ -88,3 +88,171 @@ fn transform_feedback() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn transform_feedback_ping_pong() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Particle {
+        position: [f32; 2],
+        velocity: [f32; 2],
+    }
+
+    implement_vertex!(Particle, position, velocity);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 150
+
+            in vec2 position;
+            in vec2 velocity;
+
+            out Particle {
+                vec2 position;
+                vec2 velocity;
+            } particle;
+
+            void main() {
+                particle.position = position + velocity;
+                particle.velocity = velocity * 2.0;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 150
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["Particle.position".to_string(), "Particle.velocity".to_string()],
+            glium::program::TransformFeedbackMode::Interleaved
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let mut buffers = [
+        glium::VertexBuffer::new(&display, &[
+            Particle { position: [0.0, 0.0], velocity: [1.0, 0.5] },
+            Particle { position: [2.0, 1.0], velocity: [-1.0, 0.0] },
+        ]).unwrap(),
+        glium::VertexBuffer::empty(&display, 2).unwrap(),
+    ];
+
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::Points);
+
+    for _ in 0 .. 3 {
+        {
+            let (source, destination) = buffers.split_at_mut(1);
+            let session = glium::vertex::TransformFeedbackSession::new(&display, &program,
+                                                                       &mut destination[0])
+                                                                       .unwrap();
+
+            let params = glium::DrawParameters {
+                transform_feedback: Some(&session),
+                draw_primitives: false,
+                .. Default::default()
+            };
+
+            let texture = support::build_renderable_texture(&display);
+            texture.as_surface().draw(&source[0], &indices, &program, &uniform!{},
+                                      &params).unwrap();
+        }
+
+        buffers.swap(0, 1);
+    }
+
+    let result = match buffers[0].read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(result[0], Particle { position: [7.0, 3.5], velocity: [8.0, 4.0] });
+    assert_eq!(result[1], Particle { position: [-5.0, 1.0], velocity: [-8.0, 0.0] });
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_wrong_order() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        output_a: [f32; 2],
+        output_b: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, output_a, output_b);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_a;
+            varying vec2 output_b;
+
+            void main() {
+                output_a = position;
+                output_b = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_b".to_string(), "output_a".to_string()],
+            glium::program::TransformFeedbackMode::Interleaved
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> =
+        glium::VertexBuffer::empty(&display, 6).unwrap();
+
+    match glium::vertex::TransformFeedbackSession::new(&display, &program, &mut out_buffer) {
+        Err(glium::vertex::TransformFeedbackSessionCreationError::AttributeOffsetMismatch {
+            ref name, program: 0, vertex: 8
+        }) if name == "output_b" => (),
+        Err(glium::vertex::TransformFeedbackSessionCreationError::NotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+        Ok(_) => panic!(),
+    };
+
+    display.assert_no_error(None);
+}
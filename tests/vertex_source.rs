@@ -394,6 +394,50 @@ fn attributes_marker() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn no_vertices_fullscreen_triangle() {
+    let display = support::build_display();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 140
+
+            void main() {
+                vec2 position = vec2(float((gl_VertexID & 1) << 2), float((gl_VertexID & 2) << 1));
+                gl_Position = vec4(position - 1.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let fullscreen = glium::vertex::NoVertices(glium::index::PrimitiveType::TrianglesList, 3);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(fullscreen, fullscreen, &program, &uniform!{},
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn attributes_marker_indices() {
     let display = support::build_display();
@@ -762,3 +806,598 @@ fn normalized_vertex() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn runtime_vertex_format() {
+    let display = support::build_display();
+
+    let format = glium::vertex::VertexFormatBuilder::new()
+        .attribute("position", 0, glium::vertex::AttributeType::F32F32)
+        .normalized_attribute("color", 8, glium::vertex::AttributeType::U8U8U8U8)
+        .build();
+
+    // the bytes of four vertices, as a loader would read them from a file
+    let mut data = Vec::new();
+    for &(x, y) in &[(-1.0f32, 1.0f32), (1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)] {
+        let position: [u8; 8] = unsafe { std::mem::transmute([x, y]) };
+        data.extend_from_slice(&position);
+        data.extend_from_slice(&[255, 0, 0, 255]);
+    }
+
+    let vertex_buffer = glium::vertex::VertexBufferAny::new_raw(&display, &data, format, 12)
+                                                                .unwrap();
+    assert_eq!(vertex_buffer.len(), 4);
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                               &[0u16, 1, 2, 3]).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec4 color;
+
+                varying vec4 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+                varying vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+                attribute lowp vec4 color;
+
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 100
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            ",
+        }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer, &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn runtime_vertex_format_attribute_out_of_vertex() {
+    let display = support::build_display();
+
+    let format = glium::vertex::VertexFormatBuilder::new()
+        .attribute("position", 4, glium::vertex::AttributeType::F32F32)
+        .build();
+
+    match glium::vertex::VertexBufferAny::new_raw(&display, &[0; 32], format, 8) {
+        Err(glium::vertex::BufferCreationError::AttributeOutOfVertex) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn instancing_divisor() {
+    let display = support::build_display();
+
+    let buffer1 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { position: [-1.0,  1.0] },
+                Vertex { position: [ 1.0,  1.0] },
+                Vertex { position: [-1.0, -1.0] },
+                Vertex { position: [ 1.0, -1.0] },
+            ]
+        ).unwrap()
+    };
+
+    let buffer2 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            color: [f32; 3],
+        }
+
+        implement_vertex!(Vertex, color);
+
+        glium::vertex::VertexBuffer::new(&display,
+            &[
+                Vertex { color: [0.0, 0.0, 1.0] },
+                Vertex { color: [1.0, 0.0, 0.0] },
+            ]
+        ).unwrap()
+    };
+
+    // each color is used by two instances, so four instances are drawn
+    let buffer2 = match buffer2.per_instance() {
+        Ok(b) => b.divisor(2),
+        Err(_) => return
+    };
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                               &[0u16, 1, 2, 3]).unwrap();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 330
+
+            in vec2 position;
+            in vec3 color;
+
+            out vec3 v_color;
+            flat out int instance;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                v_color = color;
+                instance = gl_InstanceID;
+            }
+        ",
+        "
+            #version 330
+            in vec3 v_color;
+            flat in int instance;
+
+            void main() {
+                if (instance != 3) {
+                    discard;
+                }
+
+                gl_FragColor = vec4(v_color, 1.0);
+            }
+        ",
+        None) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw((&buffer1, buffer2), &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn instancing_divisor_changes_between_draws() {
+    let display = support::build_display();
+
+    let buffer1 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { position: [-1.0,  1.0] },
+                Vertex { position: [ 1.0,  1.0] },
+                Vertex { position: [-1.0, -1.0] },
+                Vertex { position: [ 1.0, -1.0] },
+            ]
+        ).unwrap()
+    };
+
+    let buffer2 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            color: [f32; 3],
+        }
+
+        implement_vertex!(Vertex, color);
+
+        glium::vertex::VertexBuffer::new(&display,
+            &[
+                Vertex { color: [0.0, 0.0, 1.0] },
+                Vertex { color: [1.0, 0.0, 0.0] },
+                Vertex { color: [0.0, 1.0, 0.0] },
+                Vertex { color: [1.0, 1.0, 1.0] },
+            ]
+        ).unwrap()
+    };
+
+    if buffer2.per_instance().is_err() {
+        return;
+    }
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                               &[0u16, 1, 2, 3]).unwrap();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 330
+
+            in vec2 position;
+            in vec3 color;
+
+            out vec3 v_color;
+            flat out int instance;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                v_color = color;
+                instance = gl_InstanceID;
+            }
+        ",
+        "
+            #version 330
+            in vec3 v_color;
+            flat in int instance;
+
+            void main() {
+                if (instance != 3) {
+                    discard;
+                }
+
+                gl_FragColor = vec4(v_color, 1.0);
+            }
+        ",
+        None) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+
+    // with a divisor of 1, the fourth instance uses the fourth color
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw((&buffer1, buffer2.per_instance().unwrap()), &index_buffer,
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 255, 255));
+
+    // the same buffer with a divisor of 2 must not reuse the previous bindings
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw((&buffer1, buffer2.per_instance().unwrap().divisor(2)),
+                              &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_vertices_from_arena() {
+    let display = support::build_display();
+
+    // 4 positions, then 2 unused floats, then 4 colors separated by one unused float
+    let arena = glium::buffer::Buffer::new(&display,
+        &[
+            -1.0f32, 1.0,  1.0, 1.0,  -1.0, -1.0,  1.0, -1.0,
+            0.0, 0.0,
+            1.0, 0.0, 0.0, 0.0,  1.0, 0.0, 0.0, 0.0,  1.0, 0.0, 0.0, 0.0,  1.0, 0.0, 0.0, 0.0,
+        ][..], glium::buffer::BufferType::ArrayBuffer,
+        glium::buffer::BufferMode::Default).unwrap();
+
+    let positions_format = glium::vertex::VertexFormatBuilder::new()
+        .attribute("position", 0, glium::vertex::AttributeType::F32F32)
+        .build();
+    let colors_format = glium::vertex::VertexFormatBuilder::new()
+        .attribute("color", 0, glium::vertex::AttributeType::F32F32F32)
+        .build();
+
+    let positions = glium::vertex::BufferVertices::new(arena.slice(0 .. 8).unwrap()
+                                                            .as_slice_any(),
+                                                       &positions_format, 8).unwrap();
+    let colors = glium::vertex::BufferVertices::new(arena.slice(10 .. 26).unwrap()
+                                                         .as_slice_any(),
+                                                    &colors_format, 16).unwrap();
+    assert_eq!(positions.len(), 4);
+    assert_eq!(colors.len(), 4);
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+                in vec3 color;
+                out vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 140
+
+                in vec3 v_color;
+                out vec4 f_color;
+
+                void main() {
+                    f_color = vec4(v_color, 1.0);
+                }
+            "
+        },
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec3 color;
+                varying vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            "
+        }
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw((positions, colors),
+                              &glium::index::NoIndices(PrimitiveType::TriangleStrip), &program,
+                              &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_vertices_stride_too_small() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::new(&display, &[0.0f32; 16][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            glium::buffer::BufferMode::Default).unwrap();
+
+    let format = glium::vertex::VertexFormatBuilder::new()
+        .attribute("position", 4, glium::vertex::AttributeType::F32F32)
+        .build();
+
+    match glium::vertex::BufferVertices::new(buffer.as_slice_any(), &format, 8) {
+        Err(glium::vertex::BufferCreationError::AttributeOutOfVertex) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn switching_buffers_with_same_format() {
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let display = support::build_display();
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        }).unwrap();
+
+    // the left half and the right half of the screen
+    let left = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [0.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [0.0, -1.0] },
+    ]).unwrap();
+    let right = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [0.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [0.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+
+    let texture = support::build_renderable_texture(&display);
+
+    for &(buffer, left_color, right_color) in &[(&left, (255, 0, 0, 255), (0, 0, 0, 0)),
+                                                (&right, (0, 0, 0, 0), (255, 0, 0, 255)),
+                                                (&left, (255, 0, 0, 255), (0, 0, 0, 0))]
+    {
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(buffer, &indices, &program, &glium::uniforms::EmptyUniforms,
+                                  &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], left_color);
+        assert_eq!(*data[0].last().unwrap(), right_color);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multiple_per_instance_slices() {
+    let display = support::build_display();
+
+    let (vertices, indices) = support::build_rectangle_vb_ib(&display);
+
+    let offsets = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            offset: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, offset);
+
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { offset: [10.0, 10.0] },
+                Vertex { offset: [10.0, 10.0] },
+                Vertex { offset: [10.0, 10.0] },
+                Vertex { offset: [ 0.0,  0.0] },
+                Vertex { offset: [10.0, 10.0] },
+            ]
+        ).unwrap()
+    };
+
+    let colors: glium::vertex::VertexBufferAny = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            color: [f32; 3],
+        }
+
+        implement_vertex!(Vertex, color);
+
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { color: [0.0, 0.0, 1.0] },
+                Vertex { color: [0.0, 0.0, 1.0] },
+                Vertex { color: [1.0, 0.0, 0.0] },
+                Vertex { color: [0.0, 1.0, 0.0] },
+            ]
+        ).unwrap().into()
+    };
+
+    // three instances, the colors are used for two instances each and the last one is only
+    // partially used
+    let offsets = match offsets.slice(1 .. 4).unwrap().per_instance() {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    let colors = match colors.slice(1 .. 3).unwrap().per_instance() {
+        Ok(b) => b.divisor(2),
+        Err(_) => return
+    };
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+            in vec2 offset;
+            in vec3 color;
+
+            out vec3 v_color;
+
+            void main() {
+                gl_Position = vec4(position + offset, 0.0, 1.0);
+                v_color = color;
+            }
+        ",
+        "
+            #version 140
+
+            in vec3 v_color;
+            out vec4 f_color;
+
+            void main() {
+                f_color = vec4(v_color, 1.0);
+            }
+        ",
+        None) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw((&vertices, offsets, colors), &indices, &program, &uniform!{},
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}